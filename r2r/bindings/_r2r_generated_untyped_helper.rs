@@ -1,4 +1,164 @@
 impl WrappedNativeMsgUntyped {
+    #[doc = r" The names of all message types that can be created with `new_from`."]
+    pub fn type_names() -> &'static [&'static str] {
+        &[
+            "action_msgs/msg/GoalInfo",
+            "action_msgs/msg/GoalStatus",
+            "action_msgs/msg/GoalStatusArray",
+            "builtin_interfaces/msg/Duration",
+            "builtin_interfaces/msg/Time",
+            "diagnostic_msgs/msg/DiagnosticArray",
+            "diagnostic_msgs/msg/DiagnosticStatus",
+            "diagnostic_msgs/msg/KeyValue",
+            "geometry_msgs/msg/Accel",
+            "geometry_msgs/msg/AccelStamped",
+            "geometry_msgs/msg/AccelWithCovariance",
+            "geometry_msgs/msg/AccelWithCovarianceStamped",
+            "geometry_msgs/msg/Inertia",
+            "geometry_msgs/msg/InertiaStamped",
+            "geometry_msgs/msg/Point",
+            "geometry_msgs/msg/Point32",
+            "geometry_msgs/msg/PointStamped",
+            "geometry_msgs/msg/Polygon",
+            "geometry_msgs/msg/PolygonStamped",
+            "geometry_msgs/msg/Pose",
+            "geometry_msgs/msg/Pose2D",
+            "geometry_msgs/msg/PoseArray",
+            "geometry_msgs/msg/PoseStamped",
+            "geometry_msgs/msg/PoseWithCovariance",
+            "geometry_msgs/msg/PoseWithCovarianceStamped",
+            "geometry_msgs/msg/Quaternion",
+            "geometry_msgs/msg/QuaternionStamped",
+            "geometry_msgs/msg/Transform",
+            "geometry_msgs/msg/TransformStamped",
+            "geometry_msgs/msg/Twist",
+            "geometry_msgs/msg/TwistStamped",
+            "geometry_msgs/msg/TwistWithCovariance",
+            "geometry_msgs/msg/TwistWithCovarianceStamped",
+            "geometry_msgs/msg/Vector3",
+            "geometry_msgs/msg/Vector3Stamped",
+            "geometry_msgs/msg/Wrench",
+            "geometry_msgs/msg/WrenchStamped",
+            "lifecycle_msgs/msg/State",
+            "lifecycle_msgs/msg/Transition",
+            "lifecycle_msgs/msg/TransitionDescription",
+            "lifecycle_msgs/msg/TransitionEvent",
+            "nav_msgs/msg/GridCells",
+            "nav_msgs/msg/MapMetaData",
+            "nav_msgs/msg/OccupancyGrid",
+            "nav_msgs/msg/Odometry",
+            "nav_msgs/msg/Path",
+            "rcl_interfaces/msg/FloatingPointRange",
+            "rcl_interfaces/msg/IntegerRange",
+            "rcl_interfaces/msg/ListParametersResult",
+            "rcl_interfaces/msg/Log",
+            "rcl_interfaces/msg/Parameter",
+            "rcl_interfaces/msg/ParameterDescriptor",
+            "rcl_interfaces/msg/ParameterEvent",
+            "rcl_interfaces/msg/ParameterEventDescriptors",
+            "rcl_interfaces/msg/ParameterType",
+            "rcl_interfaces/msg/ParameterValue",
+            "rcl_interfaces/msg/SetParametersResult",
+            "rosgraph_msgs/msg/Clock",
+            "sensor_msgs/msg/BatteryState",
+            "sensor_msgs/msg/CameraInfo",
+            "sensor_msgs/msg/ChannelFloat32",
+            "sensor_msgs/msg/CompressedImage",
+            "sensor_msgs/msg/FluidPressure",
+            "sensor_msgs/msg/Illuminance",
+            "sensor_msgs/msg/Image",
+            "sensor_msgs/msg/Imu",
+            "sensor_msgs/msg/JointState",
+            "sensor_msgs/msg/Joy",
+            "sensor_msgs/msg/JoyFeedback",
+            "sensor_msgs/msg/JoyFeedbackArray",
+            "sensor_msgs/msg/LaserEcho",
+            "sensor_msgs/msg/LaserScan",
+            "sensor_msgs/msg/MagneticField",
+            "sensor_msgs/msg/MultiDOFJointState",
+            "sensor_msgs/msg/MultiEchoLaserScan",
+            "sensor_msgs/msg/NavSatFix",
+            "sensor_msgs/msg/NavSatStatus",
+            "sensor_msgs/msg/PointCloud",
+            "sensor_msgs/msg/PointCloud2",
+            "sensor_msgs/msg/PointField",
+            "sensor_msgs/msg/Range",
+            "sensor_msgs/msg/RegionOfInterest",
+            "sensor_msgs/msg/RelativeHumidity",
+            "sensor_msgs/msg/Temperature",
+            "sensor_msgs/msg/TimeReference",
+            "shape_msgs/msg/Mesh",
+            "shape_msgs/msg/MeshTriangle",
+            "shape_msgs/msg/Plane",
+            "shape_msgs/msg/SolidPrimitive",
+            "statistics_msgs/msg/MetricsMessage",
+            "statistics_msgs/msg/StatisticDataPoint",
+            "statistics_msgs/msg/StatisticDataType",
+            "std_msgs/msg/Bool",
+            "std_msgs/msg/Byte",
+            "std_msgs/msg/ByteMultiArray",
+            "std_msgs/msg/Char",
+            "std_msgs/msg/ColorRGBA",
+            "std_msgs/msg/Empty",
+            "std_msgs/msg/Float32",
+            "std_msgs/msg/Float32MultiArray",
+            "std_msgs/msg/Float64",
+            "std_msgs/msg/Float64MultiArray",
+            "std_msgs/msg/Header",
+            "std_msgs/msg/Int16",
+            "std_msgs/msg/Int16MultiArray",
+            "std_msgs/msg/Int32",
+            "std_msgs/msg/Int32MultiArray",
+            "std_msgs/msg/Int64",
+            "std_msgs/msg/Int64MultiArray",
+            "std_msgs/msg/Int8",
+            "std_msgs/msg/Int8MultiArray",
+            "std_msgs/msg/MultiArrayDimension",
+            "std_msgs/msg/MultiArrayLayout",
+            "std_msgs/msg/String",
+            "std_msgs/msg/UInt16",
+            "std_msgs/msg/UInt16MultiArray",
+            "std_msgs/msg/UInt32",
+            "std_msgs/msg/UInt32MultiArray",
+            "std_msgs/msg/UInt64",
+            "std_msgs/msg/UInt64MultiArray",
+            "std_msgs/msg/UInt8",
+            "std_msgs/msg/UInt8MultiArray",
+            "stereo_msgs/msg/DisparityImage",
+            "test_msgs/msg/Arrays",
+            "test_msgs/msg/BasicTypes",
+            "test_msgs/msg/BoundedPlainSequences",
+            "test_msgs/msg/BoundedSequences",
+            "test_msgs/msg/Builtins",
+            "test_msgs/msg/Constants",
+            "test_msgs/msg/Defaults",
+            "test_msgs/msg/Empty",
+            "test_msgs/msg/MultiNested",
+            "test_msgs/msg/Nested",
+            "test_msgs/msg/Strings",
+            "test_msgs/msg/UnboundedSequences",
+            "test_msgs/msg/WStrings",
+            "tf2_msgs/msg/TF2Error",
+            "tf2_msgs/msg/TFMessage",
+            "trajectory_msgs/msg/JointTrajectory",
+            "trajectory_msgs/msg/JointTrajectoryPoint",
+            "trajectory_msgs/msg/MultiDOFJointTrajectory",
+            "trajectory_msgs/msg/MultiDOFJointTrajectoryPoint",
+            "unique_identifier_msgs/msg/UUID",
+            "visualization_msgs/msg/ImageMarker",
+            "visualization_msgs/msg/InteractiveMarker",
+            "visualization_msgs/msg/InteractiveMarkerControl",
+            "visualization_msgs/msg/InteractiveMarkerFeedback",
+            "visualization_msgs/msg/InteractiveMarkerInit",
+            "visualization_msgs/msg/InteractiveMarkerPose",
+            "visualization_msgs/msg/InteractiveMarkerUpdate",
+            "visualization_msgs/msg/Marker",
+            "visualization_msgs/msg/MarkerArray",
+            "visualization_msgs/msg/MenuEntry",
+            "visualization_msgs/msg/MeshFile",
+            "visualization_msgs/msg/UVCoordinate",
+        ]
+    }
     pub fn new_from(typename: &str) -> Result<Self> {
         #[allow(non_snake_case)]
         fn new_wrapped_native_msg_untyped_action_msgs_msg_GoalInfo() -> WrappedNativeMsgUntyped {
@@ -47,6 +47,11 @@ fn main() {
         "r2r__example_interfaces__srv__AddTwoInts",
         "r2r__std_srvs__srv__Empty",
         "r2r__example_interfaces__action__Fibonacci",
+        "r2r__std_msgs__msg__Header",
+        "r2r__composition_interfaces__srv__LoadNode",
+        "r2r__lifecycle_msgs__srv__ChangeState",
+        "r2r__statistics_msgs__msg__MetricsMessage",
+        "r2r__tf2_msgs__msg__TFMessage",
     ]);
     r2r_common::print_cargo_ros_distro();
 
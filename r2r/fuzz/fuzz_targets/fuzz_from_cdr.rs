@@ -0,0 +1,16 @@
+#![no_main]
+
+//! Feeds arbitrary bytes to the CDR deserialization of a few message types.
+//!
+//! Invalid input must be reported as an error, never panic or corrupt memory.
+//! Run with `cargo fuzz run fuzz_from_cdr` from the `r2r` directory.
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = r2r::from_cdr::<r2r::std_msgs::msg::String>(data);
+    let _ = r2r::from_cdr::<r2r::std_msgs::msg::Int32MultiArray>(data);
+    let _ = r2r::from_cdr::<r2r::sensor_msgs::msg::PointCloud2>(data);
+    let _ = r2r::from_cdr::<r2r::trajectory_msgs::msg::JointTrajectory>(data);
+    let _ = r2r::from_cdr::<r2r::visualization_msgs::msg::MarkerArray>(data);
+});
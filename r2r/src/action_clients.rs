@@ -1,7 +1,8 @@
 use futures::{
     channel::{mpsc, oneshot},
-    future::{FutureExt, TryFutureExt},
-    stream::Stream,
+    future::{self, BoxFuture, FutureExt, TryFutureExt},
+    select, select_biased,
+    stream::{BoxStream, FuturesUnordered, Stream, StreamExt},
 };
 use std::{
     collections::HashMap,
@@ -9,6 +10,7 @@ use std::{
     future::Future,
     mem::MaybeUninit,
     sync::{Mutex, Weak},
+    time::{Duration, Instant},
 };
 
 use crate::{
@@ -18,6 +20,7 @@ use crate::{
         generated_msgs::{action_msgs, builtin_interfaces, unique_identifier_msgs},
         *,
     },
+    rate::sleep_until,
 };
 use r2r_actions::*;
 use r2r_rcl::*;
@@ -54,6 +57,9 @@ where
     T: WrappedActionTypeSupport,
 {
     /// Get the current status of this goal.
+    ///
+    /// Once the result has been received the goal is forgotten and its
+    /// status is `GoalStatus::Unknown`, the final status comes with the result.
     pub fn get_status(&self) -> Result<GoalStatus> {
         let client = self
             .client
@@ -81,6 +87,13 @@ where
 
         client.send_cancel_request(&self.uuid)
     }
+
+    /// Returns true while the goal is accepted, executing or canceling.
+    ///
+    /// The status is updated when the node is spun.
+    pub fn is_active(&self) -> bool {
+        self.get_status().is_ok_and(|s| s.is_active())
+    }
 }
 
 impl<T: 'static> ActionClient<T>
@@ -152,6 +165,7 @@ where
                                     .upgrade()
                                     .ok_or(Error::RCL_RET_ACTION_CLIENT_INVALID)?;
                                 let mut c = c.lock().unwrap();
+                                c.goal_status.entry(uuid).or_insert(GoalStatus::Accepted);
                                 c.send_result_request(uuid);
                             }
 
@@ -175,6 +189,121 @@ where
             Err(Error::from_rcl_error(result))
         }
     }
+
+    /// Send a goal and wait until it is done.
+    ///
+    /// `feedback_cb` is called for each feedback message of the goal. The
+    /// future resolves to the final status (succeeded, aborted or canceled)
+    /// and the result of the goal, or to [`Error::RCL_RET_ACTION_GOAL_REJECTED`]
+    /// if the server rejected it. If `timeout` passes first, a cancel request
+    /// is sent for the goal and the future resolves to [`Error::Timeout`].
+    /// Like all futures of the node, it only makes progress while the node is spun.
+    pub fn send_goal_and_wait(
+        &self, goal: T::Goal, feedback_cb: impl Fn(T::Feedback) + 'static,
+        timeout: Option<Duration>,
+    ) -> impl Future<Output = Result<(GoalStatus, T::Result)>> {
+        let goal_request = self.send_goal_request(goal);
+        let deadline = timeout.map(|timeout| sleep_until(Instant::now() + timeout));
+        async move {
+            let mut timeout = Box::pin(async move {
+                match deadline {
+                    Some(deadline) => {
+                        let _ = deadline.await;
+                    }
+                    None => future::pending::<()>().await,
+                }
+            })
+            .fuse();
+            let mut goal_response = Box::pin(goal_request?).fuse();
+            let (goal, result, feedback) = select! {
+                response = goal_response => response?,
+                _ = timeout => return Err(Error::Timeout),
+            };
+
+            let mut result = Box::pin(result).fuse();
+            let mut feedback = feedback.fuse();
+            loop {
+                // feedback first, so all of it is seen before the result.
+                select_biased! {
+                    msg = feedback.next() => {
+                        if let Some(msg) = msg {
+                            feedback_cb(msg);
+                        }
+                    }
+                    result = result => return result,
+                    _ = timeout => {
+                        // don't leave the goal running on the server.
+                        if let Err(e) = goal.cancel() {
+                            log::debug!("could not cancel goal after timeout: {}", e);
+                        }
+                        return Err(Error::Timeout);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Make several goal requests at once.
+    ///
+    /// All goals are sent immediately, without waiting for the server to
+    /// accept the previous ones. The stream yields one item per goal in the
+    /// order the replies arrive, the same triple as [`ActionClient::send_goal_request`],
+    /// or the error of a goal that could not be sent or was rejected.
+    pub fn send_goals(
+        &self, goals: Vec<T::Goal>,
+    ) -> impl Stream<Item = Result<GoalReply<T>>> + Unpin
+    where
+        T::Result: Send,
+        T::Feedback: Send,
+    {
+        goals
+            .into_iter()
+            .map(|goal| match self.send_goal_request(goal) {
+                Ok(reply) => {
+                    future::Either::Left(reply.map_ok(|(goal, result, feedback)| {
+                        (goal, result.boxed(), feedback.boxed())
+                    }))
+                }
+                Err(e) => future::Either::Right(future::ready(Err(e))),
+            })
+            .collect::<FuturesUnordered<_>>()
+    }
+
+    /// Get handles for all goals of this client that are not yet done.
+    ///
+    /// This includes goals whose handles were dropped, so they can
+    /// still be canceled.
+    pub fn get_active_goals(&self) -> Vec<ActionClientGoal<T>> {
+        let Some(client) = self.client.upgrade() else {
+            return vec![];
+        };
+        let client = client.lock().unwrap();
+        client
+            .goal_status
+            .iter()
+            .filter(|(_, status)| status.is_active())
+            .map(|(uuid, _)| ActionClientGoal {
+                client: Weak::clone(&self.client),
+                uuid: *uuid,
+            })
+            .collect()
+    }
+
+    /// Send a request to cancel all goals on the server.
+    ///
+    /// This cancels the goals of all clients of the action, not only of this one.
+    /// If the server accepts the request, the future resolves to the ids of the goals
+    /// that are being canceled. Otherwise the same errors as for
+    /// [`ActionClientGoal::cancel`] can be returned.
+    pub fn cancel_all_goals(&self) -> Result<impl Future<Output = Result<Vec<uuid::Uuid>>>> {
+        let client = self
+            .client
+            .upgrade()
+            .ok_or(Error::RCL_RET_ACTION_CLIENT_INVALID)?;
+        let mut client = client.lock().unwrap();
+
+        client.send_cancel_all_request()
+    }
 }
 
 pub fn make_action_client<T>(client: Weak<Mutex<WrappedActionClient<T>>>) -> ActionClient<T>
@@ -184,6 +313,14 @@ where
     ActionClient { client }
 }
 
+/// The reply to a goal of [`ActionClient::send_goals`]: the goal handle, the
+/// future for the eventual result and the stream of feedback messages.
+pub type GoalReply<T> = (
+    ActionClientGoal<T>,
+    BoxFuture<'static, Result<(GoalStatus, <T as WrappedActionTypeSupport>::Result)>>,
+    BoxStream<'static, <T as WrappedActionTypeSupport>::Feedback>,
+);
+
 pub type ResultSender<R> = (uuid::Uuid, oneshot::Sender<(GoalStatus, R)>);
 pub struct WrappedActionClient<T>
 where
@@ -231,14 +368,43 @@ where
     where
         T: WrappedActionTypeSupport,
     {
-        let msg = action_msgs::srv::CancelGoal::Request {
-            goal_info: action_msgs::msg::GoalInfo {
-                goal_id: unique_identifier_msgs::msg::UUID {
-                    uuid: goal.as_bytes().to_vec(),
-                },
-                ..action_msgs::msg::GoalInfo::default()
+        let goal_info = action_msgs::msg::GoalInfo {
+            goal_id: unique_identifier_msgs::msg::UUID {
+                uuid: goal.as_bytes().to_vec(),
             },
+            ..action_msgs::msg::GoalInfo::default()
         };
+        let future = self
+            .send_cancel(goal_info)?
+            .map(|r| r.and_then(|r| cancel_response_result(&r)));
+        Ok(future)
+    }
+
+    pub fn send_cancel_all_request(
+        &mut self,
+    ) -> Result<impl Future<Output = Result<Vec<uuid::Uuid>>>> {
+        // a zero goal id and stamp means all goals.
+        let goal_info = action_msgs::msg::GoalInfo {
+            goal_id: unique_identifier_msgs::msg::UUID {
+                uuid: uuid::Uuid::nil().as_bytes().to_vec(),
+            },
+            ..action_msgs::msg::GoalInfo::default()
+        };
+        let future = self.send_cancel(goal_info)?.map(|r| {
+            let r = r?;
+            cancel_response_result(&r)?;
+            Ok(r.goals_canceling
+                .iter()
+                .map(|g| uuid_msg_to_uuid(&g.goal_id))
+                .collect())
+        });
+        Ok(future)
+    }
+
+    fn send_cancel(
+        &mut self, goal_info: action_msgs::msg::GoalInfo,
+    ) -> Result<impl Future<Output = Result<action_msgs::srv::CancelGoal::Response>>> {
+        let msg = action_msgs::srv::CancelGoal::Request { goal_info };
         let native_msg = WrappedNativeMsg::<action_msgs::srv::CancelGoal::Request>::from(&msg);
         let mut seq_no = 0i64;
         let result = unsafe {
@@ -252,33 +418,7 @@ where
             self.cancel_response_channels
                 .push((seq_no, cancel_req_sender));
             // instead of "canceled" we return invalid client.
-            let future = cancel_req_receiver
-                .map_err(|_| Error::RCL_RET_CLIENT_INVALID)
-                .map(|r| match r {
-                    Ok(r) => match r.return_code {
-                        e if e == action_msgs::srv::CancelGoal::Response::ERROR_NONE as i8 => {
-                            Ok(())
-                        }
-                        e if e == action_msgs::srv::CancelGoal::Response::ERROR_REJECTED as i8 => {
-                            Err(Error::GoalCancelRejected)
-                        }
-                        e if e
-                            == action_msgs::srv::CancelGoal::Response::ERROR_UNKNOWN_GOAL_ID
-                                as i8 =>
-                        {
-                            Err(Error::GoalCancelUnknownGoalID)
-                        }
-                        e if e
-                            == action_msgs::srv::CancelGoal::Response::ERROR_GOAL_TERMINATED
-                                as i8 =>
-                        {
-                            Err(Error::GoalCancelAlreadyTerminated)
-                        }
-                        x => panic!("unknown error code return from action server: {}", x),
-                    },
-                    Err(e) => Err(e),
-                });
-            Ok(future)
+            Ok(cancel_req_receiver.map_err(|_| Error::RCL_RET_CLIENT_INVALID))
         } else {
             log::error!("could not send goal request {}", result);
             Err(Error::from_rcl_error(result))
@@ -286,6 +426,22 @@ where
     }
 }
 
+fn cancel_response_result(r: &action_msgs::srv::CancelGoal::Response) -> Result<()> {
+    match r.return_code {
+        e if e == action_msgs::srv::CancelGoal::Response::ERROR_NONE as i8 => Ok(()),
+        e if e == action_msgs::srv::CancelGoal::Response::ERROR_REJECTED as i8 => {
+            Err(Error::GoalCancelRejected)
+        }
+        e if e == action_msgs::srv::CancelGoal::Response::ERROR_UNKNOWN_GOAL_ID as i8 => {
+            Err(Error::GoalCancelUnknownGoalID)
+        }
+        e if e == action_msgs::srv::CancelGoal::Response::ERROR_GOAL_TERMINATED as i8 => {
+            Err(Error::GoalCancelAlreadyTerminated)
+        }
+        x => panic!("unknown error code return from action server: {}", x),
+    }
+}
+
 impl<T: 'static> ActionClient_ for WrappedActionClient<T>
 where
     T: WrappedActionTypeSupport,
@@ -445,6 +601,8 @@ where
                     let response = <<T as WrappedActionTypeSupport>::GetResult as WrappedServiceTypeSupport>::Response::from_native(&response_msg);
                     let (status, result) = T::destructure_result_response_msg(response);
                     let status = GoalStatus::from_rcl(status);
+                    // the result carries the final status, the goal is done.
+                    self.goal_status.remove(&uuid);
                     match sender.send((status, result)) {
                         Ok(()) => {}
                         Err(e) => {
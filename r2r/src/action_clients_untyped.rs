@@ -49,6 +49,9 @@ pub struct ActionClientGoalUntyped {
 
 impl ActionClientGoalUntyped {
     /// Get the current status of this goal.
+    ///
+    /// Once the result has been received the goal is forgotten and its
+    /// status is `GoalStatus::Unknown`, the final status comes with the result.
     pub fn get_status(&self) -> Result<GoalStatus> {
         let client = self
             .client
@@ -402,6 +405,8 @@ impl ActionClient_ for WrappedActionClientUntyped {
                     let (status, result) =
                         (self.action_type_support.destructure_result_response_msg)(response_msg);
                     let status = GoalStatus::from_rcl(status);
+                    // the result carries the final status, the goal is done.
+                    self.goal_status.remove(&uuid);
                     match sender.send((status, result)) {
                         Ok(()) => {}
                         Err(e) => {
@@ -11,7 +11,6 @@ pub enum GoalStatus {
 }
 
 impl GoalStatus {
-    #[allow(dead_code)]
     pub fn to_rcl(&self) -> i8 {
         match self {
             GoalStatus::Unknown => crate::action_msgs::msg::GoalStatus::STATUS_UNKNOWN as i8,
@@ -24,46 +23,93 @@ impl GoalStatus {
         }
     }
 
+    /// Returns true if the goal is not yet done, i.e. accepted, executing or canceling.
+    pub fn is_active(&self) -> bool {
+        matches!(self, GoalStatus::Accepted | GoalStatus::Executing | GoalStatus::Canceling)
+    }
+
+    /// Returns true if the goal is done, i.e. succeeded, canceled or aborted.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, GoalStatus::Succeeded | GoalStatus::Canceled | GoalStatus::Aborted)
+    }
+
     pub fn from_rcl(s: i8) -> Self {
-        match s {
-            s if s == crate::action_msgs::msg::GoalStatus::STATUS_UNKNOWN as i8 => {
-                GoalStatus::Unknown
-            }
-            s if s == crate::action_msgs::msg::GoalStatus::STATUS_ACCEPTED as i8 => {
-                GoalStatus::Accepted
-            }
-            s if s == crate::action_msgs::msg::GoalStatus::STATUS_EXECUTING as i8 => {
-                GoalStatus::Executing
-            }
-            s if s == crate::action_msgs::msg::GoalStatus::STATUS_CANCELING as i8 => {
-                GoalStatus::Canceling
-            }
-            s if s == crate::action_msgs::msg::GoalStatus::STATUS_SUCCEEDED as i8 => {
-                GoalStatus::Succeeded
-            }
-            s if s == crate::action_msgs::msg::GoalStatus::STATUS_CANCELED as i8 => {
-                GoalStatus::Canceled
-            }
-            s if s == crate::action_msgs::msg::GoalStatus::STATUS_ABORTED as i8 => {
-                GoalStatus::Aborted
-            }
-            _ => panic!("unknown action status: {}", s),
-        }
+        GoalStatus::try_from(s).unwrap_or_else(|_| panic!("unknown action status: {}", s))
+    }
+}
+
+impl TryFrom<i8> for GoalStatus {
+    type Error = crate::Error;
+
+    /// Convert a status code of `action_msgs/msg/GoalStatus`.
+    fn try_from(s: i8) -> Result<Self, Self::Error> {
+        use crate::action_msgs::msg::GoalStatus as Msg;
+        let status = match s {
+            s if s == Msg::STATUS_UNKNOWN as i8 => GoalStatus::Unknown,
+            s if s == Msg::STATUS_ACCEPTED as i8 => GoalStatus::Accepted,
+            s if s == Msg::STATUS_EXECUTING as i8 => GoalStatus::Executing,
+            s if s == Msg::STATUS_CANCELING as i8 => GoalStatus::Canceling,
+            s if s == Msg::STATUS_SUCCEEDED as i8 => GoalStatus::Succeeded,
+            s if s == Msg::STATUS_CANCELED as i8 => GoalStatus::Canceled,
+            s if s == Msg::STATUS_ABORTED as i8 => GoalStatus::Aborted,
+            _ => return Err(crate::Error::InvalidGoalStatus { status: s }),
+        };
+        Ok(status)
+    }
+}
+
+impl From<GoalStatus> for i8 {
+    fn from(s: GoalStatus) -> i8 {
+        s.to_rcl()
     }
 }
 
 impl std::fmt::Display for GoalStatus {
     fn fmt(&self, fmtr: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let s = match self {
-            GoalStatus::Unknown => "unknown",
-            GoalStatus::Accepted => "accepted",
-            GoalStatus::Executing => "executing",
-            GoalStatus::Canceling => "canceling",
-            GoalStatus::Succeeded => "succeeded",
-            GoalStatus::Canceled => "canceled",
-            GoalStatus::Aborted => "aborted",
+            GoalStatus::Unknown => "UNKNOWN",
+            GoalStatus::Accepted => "ACCEPTED",
+            GoalStatus::Executing => "EXECUTING",
+            GoalStatus::Canceling => "CANCELING",
+            GoalStatus::Succeeded => "SUCCEEDED",
+            GoalStatus::Canceled => "CANCELED",
+            GoalStatus::Aborted => "ABORTED",
         };
 
         write!(fmtr, "{}", s)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [(GoalStatus, i8, &str); 7] = [
+        (GoalStatus::Unknown, 0, "UNKNOWN"),
+        (GoalStatus::Accepted, 1, "ACCEPTED"),
+        (GoalStatus::Executing, 2, "EXECUTING"),
+        (GoalStatus::Canceling, 3, "CANCELING"),
+        (GoalStatus::Succeeded, 4, "SUCCEEDED"),
+        (GoalStatus::Canceled, 5, "CANCELED"),
+        (GoalStatus::Aborted, 6, "ABORTED"),
+    ];
+
+    #[test]
+    fn test_conversions() {
+        for (status, code, name) in ALL {
+            assert_eq!(GoalStatus::try_from(code).unwrap(), status);
+            assert_eq!(GoalStatus::from_rcl(code), status);
+            assert_eq!(i8::from(status), code);
+            assert_eq!(status.to_string(), name);
+        }
+        assert!(GoalStatus::try_from(7).is_err());
+        assert!(GoalStatus::try_from(-1).is_err());
+    }
+
+    #[test]
+    fn test_is_terminal() {
+        for (status, _, _) in ALL {
+            assert_eq!(status.is_terminal(), !status.is_active() && status != GoalStatus::Unknown);
+        }
+    }
+}
@@ -1,21 +1,31 @@
 use futures::{
     channel::{mpsc, oneshot},
-    future::{join_all, FutureExt, JoinAll},
-    stream::Stream,
+    future::{join_all, poll_fn, FutureExt, JoinAll},
+    select,
+    stream::{SelectAll, Stream, StreamExt},
+    Future,
 };
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet, VecDeque},
     ffi::CString,
     mem::MaybeUninit,
-    sync::{Arc, Mutex, Weak},
+    panic::AssertUnwindSafe,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, Weak,
+    },
+    task::{Poll, Waker},
+    time::{Duration, Instant},
 };
 
 use crate::{
+    action_common::GoalStatus,
     error::*,
     msg_types::{
         generated_msgs::{action_msgs, builtin_interfaces, unique_identifier_msgs},
         *,
     },
+    qos::QosProfile,
 };
 use r2r_actions::*;
 use r2r_rcl::*;
@@ -35,10 +45,52 @@ pub trait ActionServer_ {
     fn add_result(&mut self, uuid: uuid::Uuid, msg: Box<dyn VoidPtr>);
     fn cancel_goal(&mut self, uuid: &uuid::Uuid);
     fn is_cancelling(&self, uuid: &uuid::Uuid) -> Result<bool>;
+    fn is_preempted(&self, uuid: &uuid::Uuid) -> bool;
+    fn goal_done(&mut self, uuid: &uuid::Uuid);
     fn add_goal_handle(&mut self, uuid: uuid::Uuid, goal_handle: *mut rcl_action_goal_handle_t);
     fn destroy(&mut self, node: &mut rcl_node_t);
 }
 
+/// How an action server handles goals arriving while it is busy with another goal.
+///
+/// The server is busy from the time a goal request is handed out on the
+/// request stream until the request is rejected or the goal is done
+/// (succeeded, aborted or canceled). Requests must therefore always be
+/// answered, otherwise the server stays busy.
+///
+/// See [`Node::create_action_server_with_policy`](crate::Node::create_action_server_with_policy).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoalHandlingPolicy {
+    /// Reject new goals while busy.
+    RejectWhileBusy,
+    /// Preempt the active goal and run the newest goal once the active goal is done.
+    ///
+    /// The active goal is moved to the canceling state and
+    /// [`ActionServerGoal::is_preempted`] returns true. A goal which was
+    /// waiting for the active goal to finish is rejected.
+    PreemptExisting,
+    /// Buffer up to this many goals, which are handed out in order when
+    /// the server is no longer busy. Goals beyond that are rejected.
+    Queue(usize),
+}
+
+/// Options used when creating an action server.
+///
+/// See [`Node::create_action_server_with_options`](crate::Node::create_action_server_with_options).
+#[derive(Debug, Clone)]
+pub struct ActionServerOptions {
+    /// The QoS profile of the feedback topic.
+    pub feedback_qos: QosProfile,
+}
+
+impl Default for ActionServerOptions {
+    fn default() -> Self {
+        ActionServerOptions {
+            feedback_qos: QosProfile::default(),
+        }
+    }
+}
+
 /// Request to cancel an active goal.
 pub struct ActionServerCancelRequest {
     pub uuid: uuid::Uuid,
@@ -68,6 +120,7 @@ where
     pub uuid: uuid::Uuid,
     pub goal: T::Goal,
     cancel_requests: mpsc::Receiver<ActionServerCancelRequest>,
+    cancel_signal: Arc<CancelSignal>,
     server: Weak<Mutex<dyn ActionServer_>>,
     request_id: rmw_request_id_t,
 }
@@ -81,8 +134,16 @@ where
     /// Accept the goal request and become a ServerGoal.
     /// Returns a handle to the goal and a stream on which cancel requests can be received.
     pub fn accept(
-        mut self,
+        self,
     ) -> Result<(ActionServerGoal<T>, impl Stream<Item = ActionServerCancelRequest> + Unpin)> {
+        self.accept_deferred()?.activate()
+    }
+
+    /// Accept the goal request without starting to execute it.
+    ///
+    /// The goal stays in the accepted state until [`DeferredGoal::activate`]
+    /// is called, e.g. when the server has a free slot.
+    pub fn accept_deferred(mut self) -> Result<DeferredGoal<T>> {
         let uuid_msg = unique_identifier_msgs::msg::UUID {
             uuid: self.uuid.as_bytes().to_vec(),
         };
@@ -93,7 +154,10 @@ where
         };
         let native_goal_info = WrappedNativeMsg::<action_msgs::msg::GoalInfo>::from(&goal_info);
 
-        let server = self.server.upgrade().unwrap(); // todo fixme
+        let server = self
+            .server
+            .upgrade()
+            .ok_or(Error::RCL_RET_ACTION_SERVER_INVALID)?;
         let mut server = server.lock().unwrap();
 
         let goal_handle: *mut rcl_action_goal_handle_t =
@@ -116,49 +180,326 @@ where
             return Err(Error::from_rcl_error(ret));
         }
 
-        unsafe {
-            rcl_action_update_goal_state(goal_handle, rcl_action_goal_event_t::GOAL_EVENT_EXECUTE);
-        }
-
+        server.add_goal_handle(self.uuid, goal_handle);
         server.publish_status();
 
-        let g = ActionServerGoal {
+        Ok(DeferredGoal {
             uuid: self.uuid,
             goal: self.goal,
+            cancel_requests: self.cancel_requests,
+            cancel_signal: self.cancel_signal,
+            abort_guard: abort_on_drop::<T>(self.uuid, self.server.clone(), true),
             server: self.server,
-        };
+            goal_handle,
+        })
+    }
 
-        // server.goals.insert(g.uuid.clone(), goal_handle);
-        server.add_goal_handle(g.uuid, goal_handle);
+    /// Accept the goal request, tracking the state of the goal in its type.
+    ///
+    /// Unlike [`ActionServerGoal`], the returned goal can only be finished in
+    /// ways which are valid in its current state, e.g. a goal can not succeed
+    /// once it is canceling. See [`AcceptedGoal`].
+    pub fn accept_typed(
+        self,
+    ) -> Result<(AcceptedGoal<T>, impl Stream<Item = ActionServerCancelRequest> + Unpin)> {
+        let deferred = self.accept_deferred()?;
+        let goal = AcceptedGoal {
+            uuid: deferred.uuid,
+            goal: deferred.goal,
+            cancel_signal: deferred.cancel_signal,
+            server: deferred.server,
+            goal_handle: deferred.goal_handle,
+            abort_guard: deferred.abort_guard,
+        };
+        Ok((goal, deferred.cancel_requests))
+    }
 
-        Ok((g, self.cancel_requests))
+    /// Reject the goal request, logging why.
+    ///
+    /// The ROS action protocol has no field for the reason, so the client only
+    /// sees that the goal was rejected.
+    pub fn reject_with_reason(self, reason: &str) -> Result<()> {
+        log::info!("action server: rejecting goal {}: {}", self.uuid, reason);
+        self.reject()
     }
 
     /// reject the goal request and be consumed in the process
     pub fn reject(mut self) -> Result<()> {
-        let time = builtin_interfaces::msg::Time::default();
         let server = self.server.upgrade().unwrap(); // todo fixme
         let mut server = server.lock().unwrap();
 
-        let response_msg = T::make_goal_response_msg(false, time);
-        let mut response_msg = WrappedNativeMsg::<
-            <<T as WrappedActionTypeSupport>::SendGoal as WrappedServiceTypeSupport>::Response,
-        >::from(&response_msg);
+        let result = send_goal_rejection::<T>(server.handle_mut(), &mut self.request_id);
+        server.goal_done(&self.uuid);
+        result
+    }
+}
+
+/// A goal which was accepted with [`ActionServerGoalRequest::accept_deferred`]
+/// but is not executing yet.
+///
+/// Dropping it without activating it aborts the goal.
+pub struct DeferredGoal<T>
+where
+    T: WrappedActionTypeSupport,
+{
+    pub uuid: uuid::Uuid,
+    pub goal: T::Goal,
+    cancel_requests: mpsc::Receiver<ActionServerCancelRequest>,
+    cancel_signal: Arc<CancelSignal>,
+    server: Weak<Mutex<dyn ActionServer_>>,
+    goal_handle: *mut rcl_action_goal_handle_t,
+    abort_guard: AbortOnDrop,
+}
 
+unsafe impl<T> Send for DeferredGoal<T> where T: WrappedActionTypeSupport {}
+
+impl<T: 'static> DeferredGoal<T>
+where
+    T: WrappedActionTypeSupport,
+{
+    /// Start executing the goal.
+    ///
+    /// Returns a handle to the goal and a stream on which cancel requests can
+    /// be received, including those that arrived while the goal was waiting.
+    pub fn activate(
+        self,
+    ) -> Result<(ActionServerGoal<T>, impl Stream<Item = ActionServerCancelRequest> + Unpin)> {
+        start_executing(&self.server, &self.uuid, self.goal_handle)?;
+        self.abort_guard.armed.store(false, Ordering::SeqCst);
+        let g = ActionServerGoal::new(self.uuid, self.goal, self.server, self.cancel_signal);
+        Ok((g, self.cancel_requests))
+    }
+}
+
+fn start_executing(
+    server: &Weak<Mutex<dyn ActionServer_>>, uuid: &uuid::Uuid,
+    goal_handle: *mut rcl_action_goal_handle_t,
+) -> Result<()> {
+    let server = server
+        .upgrade()
+        .ok_or(Error::RCL_RET_ACTION_SERVER_INVALID)?;
+    let server = server.lock().unwrap();
+
+    // a preempted goal is already canceling and cannot execute.
+    if !server.is_cancelling(uuid)? {
         let ret = unsafe {
-            rcl_action_send_goal_response(
-                server.handle_mut(),
-                &mut self.request_id,
-                response_msg.void_ptr_mut(),
-            )
+            rcl_action_update_goal_state(goal_handle, rcl_action_goal_event_t::GOAL_EVENT_EXECUTE)
         };
         if ret != RCL_RET_OK as i32 {
             return Err(Error::from_rcl_error(ret));
         }
+        server.publish_status();
+    }
+    Ok(())
+}
 
-        Ok(())
+/// A goal which was accepted with [`ActionServerGoalRequest::accept_typed`]
+/// but is not executing yet.
+///
+/// Like [`DeferredGoal`], dropping it without starting it aborts the goal.
+///
+/// The goal types [`AcceptedGoal`], [`ExecutingGoal`] and [`CancelingGoal`]
+/// follow the states of the action goal state machine, and each state
+/// transition consumes the goal. Finishing a goal in a way that is invalid in
+/// its state is therefore a compile time error:
+///
+/// ```compile_fail
+/// # use r2r::{CancelingGoal, test_msgs::action::Fibonacci};
+/// fn finish(goal: CancelingGoal<Fibonacci::Action>) {
+///     // a canceling goal can not succeed.
+///     goal.succeed(Fibonacci::Result::default());
+/// }
+/// ```
+pub struct AcceptedGoal<T>
+where
+    T: WrappedActionTypeSupport,
+{
+    pub uuid: uuid::Uuid,
+    pub goal: T::Goal,
+    cancel_signal: Arc<CancelSignal>,
+    server: Weak<Mutex<dyn ActionServer_>>,
+    goal_handle: *mut rcl_action_goal_handle_t,
+    abort_guard: AbortOnDrop,
+}
+
+unsafe impl<T> Send for AcceptedGoal<T> where T: WrappedActionTypeSupport {}
+
+impl<T: 'static> AcceptedGoal<T>
+where
+    T: WrappedActionTypeSupport,
+{
+    /// Start executing the goal.
+    ///
+    /// If the goal was canceled or preempted while waiting, it stays
+    /// canceling, so check [`ExecutingGoal::is_cancelling`] before working on it.
+    pub fn start_executing(self) -> Result<ExecutingGoal<T>> {
+        start_executing(&self.server, &self.uuid, self.goal_handle)?;
+        self.abort_guard.armed.store(false, Ordering::SeqCst);
+        Ok(ExecutingGoal {
+            inner: ActionServerGoal::new(self.uuid, self.goal, self.server, self.cancel_signal),
+        })
+    }
+}
+
+/// An executing goal, see [`AcceptedGoal`].
+///
+/// Only a canceling goal can be canceled:
+///
+/// ```compile_fail
+/// # use r2r::{ExecutingGoal, test_msgs::action::Fibonacci};
+/// fn finish(goal: ExecutingGoal<Fibonacci::Action>) {
+///     goal.canceled(Fibonacci::Result::default());
+/// }
+/// ```
+pub struct ExecutingGoal<T>
+where
+    T: WrappedActionTypeSupport,
+{
+    inner: ActionServerGoal<T>,
+}
+
+impl<T: 'static> ExecutingGoal<T>
+where
+    T: WrappedActionTypeSupport,
+{
+    pub fn uuid(&self) -> &uuid::Uuid {
+        &self.inner.uuid
+    }
+
+    pub fn goal(&self) -> &T::Goal {
+        &self.inner.goal
+    }
+
+    /// Returns true if a cancel request was accepted or the goal was preempted.
+    ///
+    /// The goal should then move to [`CancelingGoal`] with `start_canceling`.
+    pub fn is_cancelling(&self) -> Result<bool> {
+        self.inner.is_cancelling()
+    }
+
+    /// See [`ActionServerGoal::is_cancel_requested`].
+    pub fn is_cancel_requested(&self) -> bool {
+        self.inner.is_cancel_requested()
+    }
+
+    /// See [`ActionServerGoal::check_preempted`].
+    pub fn check_preempted(&self) -> Result<()> {
+        self.inner.check_preempted()
+    }
+
+    /// See [`ActionServerGoal::on_cancel`].
+    pub fn on_cancel(&self) -> impl Future<Output = ()> + Send + 'static {
+        self.inner.on_cancel()
+    }
+
+    pub fn publish_feedback(&self, feedback: T::Feedback) -> Result<()> {
+        self.inner.publish_feedback(feedback)
+    }
+
+    /// See [`ActionServerGoal::publish_feedback_throttled`].
+    pub fn publish_feedback_throttled(
+        &self, feedback: T::Feedback, min_interval: Duration,
+    ) -> Result<bool> {
+        self.inner
+            .publish_feedback_throttled(feedback, min_interval)
+    }
+
+    pub fn succeed(self, result: T::Result) -> Result<()> {
+        let mut inner = self.inner;
+        inner.succeed(result)
+    }
+
+    pub fn abort(self, result: T::Result) -> Result<()> {
+        let mut inner = self.inner;
+        inner.abort(result)
+    }
+
+    /// Move the goal to the canceling state.
+    ///
+    /// When a cancel request was accepted the goal is already canceling, then
+    /// only its type changes.
+    pub fn start_canceling(self) -> Result<CancelingGoal<T>> {
+        if !self.inner.is_cancelling()? {
+            let server = self
+                .inner
+                .server
+                .upgrade()
+                .ok_or(Error::RCL_RET_ACTION_SERVER_INVALID)?;
+            let mut server = server.lock().unwrap();
+            server.set_goal_state(
+                &self.inner.uuid,
+                rcl_action_goal_event_t::GOAL_EVENT_CANCEL_GOAL,
+            )?;
+        }
+        Ok(CancelingGoal { inner: self.inner })
     }
 }
+
+/// A goal which is being canceled, see [`AcceptedGoal`].
+pub struct CancelingGoal<T>
+where
+    T: WrappedActionTypeSupport,
+{
+    inner: ActionServerGoal<T>,
+}
+
+impl<T: 'static> CancelingGoal<T>
+where
+    T: WrappedActionTypeSupport,
+{
+    pub fn uuid(&self) -> &uuid::Uuid {
+        &self.inner.uuid
+    }
+
+    pub fn goal(&self) -> &T::Goal {
+        &self.inner.goal
+    }
+
+    pub fn publish_feedback(&self, feedback: T::Feedback) -> Result<()> {
+        self.inner.publish_feedback(feedback)
+    }
+
+    /// See [`ActionServerGoal::publish_feedback_throttled`].
+    pub fn publish_feedback_throttled(
+        &self, feedback: T::Feedback, min_interval: Duration,
+    ) -> Result<bool> {
+        self.inner
+            .publish_feedback_throttled(feedback, min_interval)
+    }
+
+    /// Finish canceling the goal.
+    pub fn canceled(self, result: T::Result) -> Result<()> {
+        let mut inner = self.inner;
+        inner.cancel(result)
+    }
+
+    /// Give up on the goal, e.g. if it could not be canceled cleanly.
+    pub fn abort(self, result: T::Result) -> Result<()> {
+        let mut inner = self.inner;
+        inner.abort(result)
+    }
+}
+
+fn send_goal_rejection<T>(
+    handle: &mut rcl_action_server_t, request_id: &mut rmw_request_id_t,
+) -> Result<()>
+where
+    T: WrappedActionTypeSupport + 'static,
+{
+    let time = builtin_interfaces::msg::Time::default();
+    let response_msg = T::make_goal_response_msg(false, time);
+    let mut response_msg = WrappedNativeMsg::<
+        <<T as WrappedActionTypeSupport>::SendGoal as WrappedServiceTypeSupport>::Response,
+    >::from(&response_msg);
+
+    let ret =
+        unsafe { rcl_action_send_goal_response(handle, request_id, response_msg.void_ptr_mut()) };
+    if ret != RCL_RET_OK as i32 {
+        return Err(Error::from_rcl_error(ret));
+    }
+
+    Ok(())
+}
 pub type ActiveCancelRequest = (
     rmw_request_id_t,
     action_msgs::srv::CancelGoal::Response,
@@ -173,10 +514,60 @@ where
     pub clock_handle: Box<rcl_clock_t>,
     pub goal_request_sender: mpsc::Sender<ActionServerGoalRequest<T>>,
     pub cancel_senders: HashMap<uuid::Uuid, mpsc::Sender<ActionServerCancelRequest>>,
+    pub cancel_signals: HashMap<uuid::Uuid, Arc<CancelSignal>>,
     pub active_cancel_requests: Vec<ActiveCancelRequest>,
     pub goals: HashMap<uuid::Uuid, *mut rcl_action_goal_handle_t>,
     pub result_msgs: HashMap<uuid::Uuid, Box<dyn VoidPtr>>,
     pub result_requests: HashMap<uuid::Uuid, Vec<rmw_request_id_t>>,
+    pub policy: Option<GoalHandlingPolicy>,
+    pub active_goal: Option<uuid::Uuid>,
+    pub pending_goals: VecDeque<ActionServerGoalRequest<T>>,
+    pub preempted_goals: HashSet<uuid::Uuid>,
+}
+
+impl<T: 'static> WrappedActionServer<T>
+where
+    T: WrappedActionTypeSupport,
+{
+    fn send_goal_request(&mut self, gr: ActionServerGoalRequest<T>) {
+        if self.policy.is_some() {
+            self.active_goal = Some(gr.uuid);
+        }
+        if let Err(e) = self.goal_request_sender.try_send(gr) {
+            log::error!("warning: could not send service request ({})", e)
+        }
+    }
+
+    fn reject_goal_request(&mut self, mut gr: ActionServerGoalRequest<T>) {
+        self.cancel_senders.remove(&gr.uuid);
+        self.cancel_signals.remove(&gr.uuid);
+        if let Err(e) = send_goal_rejection::<T>(&mut self.rcl_handle, &mut gr.request_id) {
+            log::debug!("action server: could not reject goal: {}", e);
+        }
+    }
+
+    fn preempt_goal(&mut self, uuid: uuid::Uuid) {
+        self.preempted_goals.insert(uuid);
+        if let Some(signal) = self.cancel_signals.get(&uuid) {
+            signal.set();
+        }
+        // the goal may not be accepted yet, then there is no state to update.
+        if let Some(handle) = self.goals.get(&uuid) {
+            let ret = unsafe {
+                rcl_action_update_goal_state(
+                    *handle,
+                    rcl_action_goal_event_t::GOAL_EVENT_CANCEL_GOAL,
+                )
+            };
+            if ret != RCL_RET_OK as i32 {
+                log::debug!(
+                    "action server: could not preempt goal: {}",
+                    Error::from_rcl_error(ret)
+                );
+            }
+            self.publish_status();
+        }
+    }
 }
 
 impl<T: 'static> ActionServer_ for WrappedActionServer<T>
@@ -205,6 +596,22 @@ where
         Err(Error::RCL_RET_ACTION_GOAL_HANDLE_INVALID)
     }
 
+    fn is_preempted(&self, uuid: &uuid::Uuid) -> bool {
+        self.preempted_goals.contains(uuid)
+    }
+
+    fn goal_done(&mut self, uuid: &uuid::Uuid) {
+        self.preempted_goals.remove(uuid);
+        self.cancel_signals.remove(uuid);
+        if self.active_goal != Some(*uuid) {
+            return;
+        }
+        self.active_goal = None;
+        if let Some(gr) = self.pending_goals.pop_front() {
+            self.send_goal_request(gr);
+        }
+    }
+
     fn cancel_goal(&mut self, uuid: &uuid::Uuid) {
         if let Some(handle) = self.goals.remove(uuid) {
             let ret = unsafe {
@@ -241,9 +648,10 @@ where
         }
 
         if let Some(handle) = self.goals.get(uuid) {
-            // todo: error handling
-            unsafe {
-                rcl_action_update_goal_state(*handle, new_state);
+            // e.g. a goal which already has a result.
+            let ret = unsafe { rcl_action_update_goal_state(*handle, new_state) };
+            if ret != RCL_RET_OK as i32 {
+                return Err(Error::from_rcl_error(ret));
             }
 
             // todo: error handling
@@ -277,9 +685,11 @@ where
                                     canceled.push(uuid);
                                 }
 
+                                // only remove the goal handled here, a request
+                                // can cancel several goals.
                                 response_msg.goals_canceling.retain(|goal_info| {
                                     let msg_uuid = uuid_msg_to_uuid(&goal_info.goal_id);
-                                    do_cancel && msg_uuid == uuid
+                                    do_cancel || msg_uuid != uuid
                                 });
                             }
                             Err(oneshot::Canceled) => {
@@ -303,7 +713,12 @@ where
                 }
             });
 
-        canceled.iter().for_each(|uuid| self.cancel_goal(uuid));
+        for uuid in &canceled {
+            if let Some(signal) = self.cancel_signals.get(uuid) {
+                signal.set();
+            }
+            self.cancel_goal(uuid);
+        }
         if !canceled.is_empty() {
             // at least one goal state changed, publish a new status message
             self.publish_status();
@@ -351,18 +766,42 @@ where
 
         let (cancel_sender, cancel_receiver) = mpsc::channel::<ActionServerCancelRequest>(10);
         self.cancel_senders.insert(uuid, cancel_sender);
+        let cancel_signal = Arc::new(CancelSignal::default());
+        self.cancel_signals.insert(uuid, cancel_signal.clone());
 
         let gr: ActionServerGoalRequest<T> = ActionServerGoalRequest {
             uuid,
             goal,
             cancel_requests: cancel_receiver,
+            cancel_signal,
             server: Arc::downgrade(&server),
             request_id: unsafe { request_id.assume_init() },
         };
 
-        // send out request.
-        if let Err(e) = self.goal_request_sender.try_send(gr) {
-            log::error!("warning: could not send service request ({})", e)
+        let Some(active_goal) = self.active_goal else {
+            self.send_goal_request(gr);
+            return;
+        };
+        match self.policy {
+            None => self.send_goal_request(gr),
+            Some(GoalHandlingPolicy::RejectWhileBusy) => self.reject_goal_request(gr),
+            Some(GoalHandlingPolicy::PreemptExisting) => {
+                // the newest goal wins, also over the one waiting to run.
+                if let Some(waiting) = self.pending_goals.pop_front() {
+                    self.reject_goal_request(waiting);
+                }
+                if !self.preempted_goals.contains(&active_goal) {
+                    self.preempt_goal(active_goal);
+                }
+                self.pending_goals.push_back(gr);
+            }
+            Some(GoalHandlingPolicy::Queue(depth)) => {
+                if self.pending_goals.len() < depth {
+                    self.pending_goals.push_back(gr);
+                } else {
+                    self.reject_goal_request(gr);
+                }
+            }
         }
     }
 
@@ -531,10 +970,7 @@ where
 
         let response_msg = if !goal_exists {
             // Goal does not exists
-            let msg = T::make_result_response_msg(
-                action_msgs::msg::GoalStatus::STATUS_UNKNOWN as i8,
-                T::Result::default(),
-            );
+            let msg = T::make_result_response_msg(GoalStatus::Unknown.into(), T::Result::default());
             let mut response_msg = WrappedNativeMsg::<
                 <<T as WrappedActionTypeSupport>::GetResult as WrappedServiceTypeSupport>::Response,
             >::from(&msg);
@@ -576,6 +1012,11 @@ where
 }
 
 /// A handle to an active `Goal`
+///
+/// When the last clone of the handle is dropped before the goal got a
+/// result, e.g. because the task executing it panicked, the goal is aborted
+/// with a default result so the client does not wait forever. See
+/// [`ActionServerGoal::disable_abort_on_drop`].
 #[derive(Clone)]
 pub struct ActionServerGoal<T>
 where
@@ -584,14 +1025,177 @@ where
     pub uuid: uuid::Uuid,
     pub goal: T::Goal,
     server: Weak<Mutex<dyn ActionServer_>>,
+    // shared by the clones of the handle
+    last_feedback: Arc<Mutex<Option<Instant>>>,
+    abort_guard: Arc<AbortOnDrop>,
+    cancel_signal: Arc<CancelSignal>,
 }
 
 unsafe impl<T> Send for ActionServerGoal<T> where T: WrappedActionTypeSupport {}
 
+// set when a cancel request for the goal is accepted or the goal is preempted, see
+// ActionServerGoal::is_cancel_requested.
+#[derive(Default)]
+pub struct CancelSignal {
+    requested: AtomicBool,
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl CancelSignal {
+    fn set(&self) {
+        self.requested.store(true, Ordering::SeqCst);
+        for waker in self.wakers.lock().unwrap().drain(..) {
+            waker.wake();
+        }
+    }
+
+    fn is_set(&self) -> bool {
+        self.requested.load(Ordering::SeqCst)
+    }
+}
+
+// aborts the goal when the last handle to it is dropped, unless disarmed.
+struct AbortOnDrop {
+    armed: AtomicBool,
+    abort: Option<Box<dyn FnOnce()>>,
+}
+
+// like the goal handles, the server is only used with its mutex locked, and
+// `abort` only by the last handle.
+unsafe impl Send for AbortOnDrop {}
+unsafe impl Sync for AbortOnDrop {}
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        if let Some(abort) = self.abort.take() {
+            if self.armed.load(Ordering::SeqCst) {
+                abort();
+            }
+        }
+    }
+}
+
+// aborts the goal on drop. an accepted goal must execute before it can abort,
+// unless it was canceled or preempted while waiting.
+fn abort_on_drop<T>(
+    uuid: uuid::Uuid, server: Weak<Mutex<dyn ActionServer_>>, accepted: bool,
+) -> AbortOnDrop
+where
+    T: WrappedActionTypeSupport + 'static,
+{
+    let abort = move || {
+        // not unwrapped, the handle may be dropped while panicking.
+        let Some(server) = server.upgrade() else {
+            return;
+        };
+        let Ok(mut server) = server.lock() else {
+            return;
+        };
+        log::warn!("action server: goal {} dropped without a result, aborting it", uuid);
+        if accepted && !server.is_cancelling(&uuid).unwrap_or(true) {
+            if let Err(e) =
+                server.set_goal_state(&uuid, rcl_action_goal_event_t::GOAL_EVENT_EXECUTE)
+            {
+                log::debug!("action server: could not abort goal {}: {}", uuid, e);
+                return;
+            }
+        }
+        if let Err(e) = finish_goal::<T>(
+            &mut *server,
+            &uuid,
+            rcl_action_goal_event_t::GOAL_EVENT_ABORT,
+            GoalStatus::Aborted,
+            T::Result::default(),
+        ) {
+            log::debug!("action server: could not abort goal {}: {}", uuid, e);
+        }
+    };
+    AbortOnDrop {
+        armed: AtomicBool::new(true),
+        abort: Some(Box::new(abort)),
+    }
+}
+
+// move the goal to a terminal state and store its result.
+fn finish_goal<T>(
+    server: &mut dyn ActionServer_, uuid: &uuid::Uuid, event: rcl_action_goal_event_t,
+    status: GoalStatus, result: T::Result,
+) -> Result<()>
+where
+    T: WrappedActionTypeSupport + 'static,
+{
+    server.set_goal_state(uuid, event)?;
+
+    // create result message
+    let result_msg = T::make_result_response_msg(status.into(), result);
+    let native_msg = WrappedNativeMsg::<
+        <<T as WrappedActionTypeSupport>::GetResult as WrappedServiceTypeSupport>::Response,
+    >::from(&result_msg);
+    server.add_result(*uuid, Box::new(native_msg));
+    server.goal_done(uuid);
+    Ok(())
+}
+
+/// A goal handle which is not aborted when dropped.
+///
+/// See [`ActionServerGoal::disable_abort_on_drop`]. Dereferences to the
+/// [`ActionServerGoal`].
+pub struct ManualGoalHandle<T>
+where
+    T: WrappedActionTypeSupport,
+{
+    inner: ActionServerGoal<T>,
+}
+
+impl<T> std::ops::Deref for ManualGoalHandle<T>
+where
+    T: WrappedActionTypeSupport,
+{
+    type Target = ActionServerGoal<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> std::ops::DerefMut for ManualGoalHandle<T>
+where
+    T: WrappedActionTypeSupport,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
 impl<T: 'static> ActionServerGoal<T>
 where
     T: WrappedActionTypeSupport,
 {
+    fn new(
+        uuid: uuid::Uuid, goal: T::Goal, server: Weak<Mutex<dyn ActionServer_>>,
+        cancel_signal: Arc<CancelSignal>,
+    ) -> Self {
+        let abort_guard = Arc::new(abort_on_drop::<T>(uuid, server.clone(), false));
+        ActionServerGoal {
+            uuid,
+            goal,
+            server,
+            last_feedback: Arc::new(Mutex::new(None)),
+            abort_guard,
+            cancel_signal,
+        }
+    }
+
+    /// Keep the goal alive when its handles are dropped.
+    ///
+    /// For goals which are intentionally finished elsewhere, e.g. by the
+    /// server after the task that accepted them is done. This applies to all
+    /// clones of the handle.
+    pub fn disable_abort_on_drop(self) -> ManualGoalHandle<T> {
+        self.abort_guard.armed.store(false, Ordering::SeqCst);
+        ManualGoalHandle { inner: self }
+    }
+
     pub fn is_cancelling(&self) -> Result<bool> {
         let action_server = self
             .server
@@ -602,6 +1206,69 @@ where
         action_server.is_cancelling(&self.uuid)
     }
 
+    /// Returns true if a newer goal preempted this goal.
+    ///
+    /// Only happens with [`GoalHandlingPolicy::PreemptExisting`]. The newer goal
+    /// is handed out once this goal is done, so it should finish as soon as possible.
+    pub fn is_preempted(&self) -> Result<bool> {
+        let action_server = self
+            .server
+            .upgrade()
+            .ok_or(Error::RCL_RET_ACTION_SERVER_INVALID)?;
+
+        let action_server = action_server.lock().unwrap();
+        Ok(action_server.is_preempted(&self.uuid))
+    }
+
+    /// Returns true once a cancel request for the goal was accepted, or the
+    /// goal was preempted.
+    ///
+    /// Unlike [`ActionServerGoal::is_cancelling`], this does not lock the
+    /// server, so it is cheap to poll while working on the goal. A rejected
+    /// cancel request does not set the flag.
+    pub fn is_cancel_requested(&self) -> bool {
+        self.cancel_signal.is_set()
+    }
+
+    /// Returns [`Error::ActionPreempted`] once [`ActionServerGoal::is_cancel_requested`]
+    /// is true, to stop working on the goal with `?`.
+    pub fn check_preempted(&self) -> Result<()> {
+        if self.is_cancel_requested() {
+            Err(Error::ActionPreempted)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Completes once [`ActionServerGoal::is_cancel_requested`] is true.
+    ///
+    /// ``` rust,ignore
+    /// futures::select! {
+    ///     result = work(&goal).fuse() => goal.succeed(result)?,
+    ///     _ = goal.on_cancel().fuse() => goal.cancel(Default::default())?,
+    /// }
+    /// ```
+    pub fn on_cancel(&self) -> impl Future<Output = ()> + Send + 'static {
+        let signal = self.cancel_signal.clone();
+        poll_fn(move |cx| {
+            if signal.is_set() {
+                return Poll::Ready(());
+            }
+            {
+                let mut wakers = signal.wakers.lock().unwrap();
+                if !wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                    wakers.push(cx.waker().clone());
+                }
+            }
+            // set meanwhile, before the waker was registered.
+            if signal.is_set() {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+    }
+
     pub fn publish_feedback(&self, msg: T::Feedback) -> Result<()>
     where
         T: WrappedActionTypeSupport,
@@ -627,9 +1294,30 @@ where
         if ret != RCL_RET_OK as i32 {
             log::error!("could not publish {}", Error::from_rcl_error(ret));
         }
+        *self.last_feedback.lock().unwrap() = Some(Instant::now());
         Ok(()) // todo: error codes
     }
 
+    /// Publish feedback unless feedback was published less than `min_interval` ago.
+    ///
+    /// Returns true if the feedback was published, and false if it was
+    /// dropped. Useful when feedback is produced at a higher rate than the
+    /// clients need it.
+    pub fn publish_feedback_throttled(
+        &self, msg: T::Feedback, min_interval: Duration,
+    ) -> Result<bool> {
+        let throttled = self
+            .last_feedback
+            .lock()
+            .unwrap()
+            .is_some_and(|last| last.elapsed() < min_interval);
+        if throttled {
+            return Ok(false);
+        }
+        self.publish_feedback(msg)?;
+        Ok(true)
+    }
+
     pub fn cancel(&mut self, msg: T::Result) -> Result<()> {
         // upgrade to actual ref. if still alive
         let action_server = self
@@ -665,12 +1353,13 @@ where
         action_server.publish_status();
 
         // create result message
-        let result_msg =
-            T::make_result_response_msg(action_msgs::msg::GoalStatus::STATUS_CANCELED as i8, msg);
+        let result_msg = T::make_result_response_msg(GoalStatus::Canceled.into(), msg);
         let native_msg = WrappedNativeMsg::<
             <<T as WrappedActionTypeSupport>::GetResult as WrappedServiceTypeSupport>::Response,
         >::from(&result_msg);
         action_server.add_result(self.uuid, Box::new(native_msg));
+        action_server.goal_done(&self.uuid);
+        self.abort_guard.armed.store(false, Ordering::SeqCst);
 
         Ok(())
     }
@@ -683,16 +1372,14 @@ where
             .ok_or(Error::RCL_RET_ACTION_SERVER_INVALID)?;
         let mut action_server = action_server.lock().unwrap();
 
-        action_server.set_goal_state(&self.uuid, rcl_action_goal_event_t::GOAL_EVENT_ABORT)?;
-
-        // create result message
-        let result_msg =
-            T::make_result_response_msg(action_msgs::msg::GoalStatus::STATUS_ABORTED as i8, msg);
-        let native_msg = WrappedNativeMsg::<
-            <<T as WrappedActionTypeSupport>::GetResult as WrappedServiceTypeSupport>::Response,
-        >::from(&result_msg);
-        action_server.add_result(self.uuid, Box::new(native_msg));
-
+        finish_goal::<T>(
+            &mut *action_server,
+            &self.uuid,
+            rcl_action_goal_event_t::GOAL_EVENT_ABORT,
+            GoalStatus::Aborted,
+            msg,
+        )?;
+        self.abort_guard.armed.store(false, Ordering::SeqCst);
         Ok(())
     }
 
@@ -707,30 +1394,29 @@ where
             .ok_or(Error::RCL_RET_ACTION_SERVER_INVALID)?;
         let mut action_server = action_server.lock().unwrap();
 
-        action_server.set_goal_state(&self.uuid, rcl_action_goal_event_t::GOAL_EVENT_SUCCEED)?;
-
-        // create result message
-        let result_msg =
-            T::make_result_response_msg(action_msgs::msg::GoalStatus::STATUS_SUCCEEDED as i8, msg);
-        let native_msg = WrappedNativeMsg::<
-            <<T as WrappedActionTypeSupport>::GetResult as WrappedServiceTypeSupport>::Response,
-        >::from(&result_msg);
-        action_server.add_result(self.uuid, Box::new(native_msg));
-
+        finish_goal::<T>(
+            &mut *action_server,
+            &self.uuid,
+            rcl_action_goal_event_t::GOAL_EVENT_SUCCEED,
+            GoalStatus::Succeeded,
+            msg,
+        )?;
+        self.abort_guard.armed.store(false, Ordering::SeqCst);
         Ok(())
     }
 }
 
 pub fn create_action_server_helper(
     node: &mut rcl_node_t, action_name: &str, clock_handle: *mut rcl_clock_t,
-    action_ts: *const rosidl_action_type_support_t,
+    action_ts: *const rosidl_action_type_support_t, options: &ActionServerOptions,
 ) -> Result<rcl_action_server_t> {
     let mut server_handle = unsafe { rcl_action_get_zero_initialized_server() };
     let action_name_c_string =
         CString::new(action_name).map_err(|_| Error::RCL_RET_INVALID_ARGUMENT)?;
 
     let result = unsafe {
-        let server_options = rcl_action_server_get_default_options();
+        let mut server_options = rcl_action_server_get_default_options();
+        server_options.feedback_topic_qos = options.feedback_qos.clone().into();
 
         rcl_action_server_init(
             &mut server_handle,
@@ -768,3 +1454,82 @@ pub fn action_server_get_num_waits(
         }
     }
 }
+
+type CancelRequests = std::pin::Pin<Box<dyn Stream<Item = ActionServerCancelRequest> + Send>>;
+
+/// Runs the goals of `requests` on `concurrency` worker threads.
+///
+/// Goals are accepted right away, and started once a worker is free. The
+/// cancel requests of running goals are accepted, so the handlers see them
+/// with [`ActionServerGoal::is_cancel_requested`]. A panicking handler aborts
+/// its goal without stopping the worker.
+pub(crate) fn run_threaded<T, F>(
+    requests: impl Stream<Item = ActionServerGoalRequest<T>> + Unpin + Send + 'static,
+    concurrency: usize, handler: F,
+) where
+    T: WrappedActionTypeSupport + 'static,
+    F: Fn(ActionServerGoal<T>) + Send + Sync + 'static,
+{
+    let (job_sender, job_receiver) = std::sync::mpsc::channel::<DeferredGoal<T>>();
+    let job_receiver = Arc::new(Mutex::new(job_receiver));
+    let (cancels_sender, mut cancels_receiver) = mpsc::unbounded::<CancelRequests>();
+    let handler = Arc::new(handler);
+
+    for _ in 0..concurrency {
+        let job_receiver = job_receiver.clone();
+        let cancels_sender = cancels_sender.clone();
+        let handler = handler.clone();
+        std::thread::spawn(move || loop {
+            // the sender is dropped once the server is gone.
+            let Ok(goal) = job_receiver.lock().unwrap().recv() else {
+                break;
+            };
+            match goal.activate() {
+                Ok((goal, cancel_requests)) => {
+                    let _ = cancels_sender.unbounded_send(Box::pin(cancel_requests));
+                    // the goal is dropped while unwinding, which aborts it, and
+                    // the worker goes on with the next goal.
+                    let handled = std::panic::catch_unwind(AssertUnwindSafe(|| handler(goal)));
+                    if handled.is_err() {
+                        log::error!("action server: goal handler panicked, the goal is aborted");
+                    }
+                }
+                Err(e) => log::error!("could not start executing goal: {}", e),
+            }
+        });
+    }
+
+    std::thread::spawn(move || {
+        futures::executor::block_on(async move {
+            let mut requests = requests.fuse();
+            let mut cancel_requests = SelectAll::new();
+            loop {
+                select! {
+                    request = requests.next() => {
+                        let Some(request) = request else {
+                            break;
+                        };
+                        match request.accept_deferred() {
+                            Ok(goal) => {
+                                if job_sender.send(goal).is_err() {
+                                    break;
+                                }
+                            }
+                            Err(e) => log::error!("could not accept goal: {}", e),
+                        }
+                    }
+                    stream = cancels_receiver.next() => {
+                        if let Some(stream) = stream {
+                            cancel_requests.push(stream);
+                        }
+                    }
+                    cancel = cancel_requests.next() => {
+                        if let Some(cancel) = cancel {
+                            cancel.accept();
+                        }
+                    }
+                }
+            }
+        })
+    });
+}
@@ -0,0 +1,171 @@
+use futures::{
+    future::FutureExt,
+    stream::{SelectAll, Stream, StreamExt},
+};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+use crate::{
+    action_common::GoalStatus,
+    error::*,
+    msg_types::{generated_msgs::action_msgs, uuid_msg_to_uuid},
+    nodes::Node,
+    qos::QosProfile,
+};
+
+// action servers publish their goal statuses on `<action>/_action/status`.
+const STATUS_SUFFIX: &str = "/_action/status";
+const STATUS_TYPE: &str = "action_msgs/msg/GoalStatusArray";
+
+type StatusStream = Pin<Box<dyn Stream<Item = (String, action_msgs::msg::GoalStatusArray)> + Send>>;
+
+/// A change of the status of a goal, see [`ActionStatusMonitor`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActionStatusChange {
+    /// The name of the action, e.g. `"/fibonacci"`.
+    pub action: String,
+    pub goal_id: uuid::Uuid,
+    /// The status before the change, `None` for a new goal.
+    pub previous: Option<GoalStatus>,
+    pub status: GoalStatus,
+}
+
+/// The statuses of all goals known to an [`ActionStatusMonitor`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActionStatusSnapshot {
+    /// The goal statuses by action name and goal id.
+    pub actions: HashMap<String, HashMap<uuid::Uuid, GoalStatus>>,
+}
+
+impl ActionStatusSnapshot {
+    /// The status of a goal, if the server of the action still knows it.
+    pub fn status(&self, action: &str, goal_id: &uuid::Uuid) -> Option<GoalStatus> {
+        self.actions.get(action)?.get(goal_id).copied()
+    }
+}
+
+/// Tracks the goals of all action servers in the graph.
+///
+/// The monitor subscribes to the status topics of the actions, and is a
+/// `Stream` of the changes of the goal statuses. Servers started after the
+/// monitor was created are picked up by [`ActionStatusMonitor::discover`].
+/// Goals are removed again when their server stops reporting them, i.e.
+/// when their result has expired.
+///
+/// ``` rust,ignore
+/// let mut monitor = r2r::action::ActionStatusMonitor::new(&mut node)?;
+/// while let Some(change) = monitor.next().await {
+///     println!("{} {}: {}", change.action, change.goal_id, change.status);
+/// }
+/// ```
+pub struct ActionStatusMonitor {
+    subscriptions: SelectAll<StatusStream>,
+    subscribed: HashSet<String>,
+    statuses: HashMap<String, HashMap<uuid::Uuid, GoalStatus>>,
+    // changes not yet returned by the stream.
+    pending: VecDeque<ActionStatusChange>,
+    // the task waiting while there is nothing to subscribe to.
+    waker: Option<Waker>,
+}
+
+impl ActionStatusMonitor {
+    /// Create a monitor subscribing to the status topics currently in the graph.
+    pub fn new(node: &mut Node) -> Result<ActionStatusMonitor> {
+        let mut monitor = ActionStatusMonitor {
+            subscriptions: SelectAll::new(),
+            subscribed: HashSet::new(),
+            statuses: HashMap::new(),
+            pending: VecDeque::new(),
+            waker: None,
+        };
+        monitor.discover(node)?;
+        Ok(monitor)
+    }
+
+    /// Subscribe to the status topics of actions added to the graph since the
+    /// last call, using the naming convention `<action>/_action/status`.
+    pub fn discover(&mut self, node: &mut Node) -> Result<()> {
+        // the status of servers already running is latched.
+        let qos = QosProfile::default().transient_local();
+        for (topic, types) in node.get_topic_names_and_types()? {
+            let Some(action) = topic.strip_suffix(STATUS_SUFFIX) else {
+                continue;
+            };
+            if !types.iter().any(|t| t == STATUS_TYPE) || self.subscribed.contains(action) {
+                continue;
+            }
+            let action = action.to_owned();
+            let stream =
+                node.subscribe::<action_msgs::msg::GoalStatusArray>(&topic, qos.clone())?;
+            let name = action.clone();
+            self.subscriptions
+                .push(Box::pin(stream.map(move |msg| (name.clone(), msg))));
+            self.subscribed.insert(action);
+        }
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+        Ok(())
+    }
+
+    /// The current statuses of all known goals.
+    ///
+    /// The status messages received so far are processed first, and their
+    /// changes are still returned by the stream.
+    pub fn snapshot(&mut self) -> ActionStatusSnapshot {
+        while let Some(Some((action, msg))) = self.subscriptions.next().now_or_never() {
+            self.update(action, msg);
+        }
+        ActionStatusSnapshot {
+            actions: self.statuses.clone(),
+        }
+    }
+
+    fn update(&mut self, action: String, msg: action_msgs::msg::GoalStatusArray) {
+        let mut goals = HashMap::new();
+        for s in msg.status_list {
+            let Ok(status) = GoalStatus::try_from(s.status) else {
+                log::debug!("unknown goal status {} on {}", s.status, action);
+                continue;
+            };
+            goals.insert(uuid_msg_to_uuid(&s.goal_info.goal_id), status);
+        }
+        let previous = self.statuses.get(&action);
+        for (goal_id, status) in &goals {
+            let previous = previous.and_then(|p| p.get(goal_id)).copied();
+            if previous != Some(*status) {
+                self.pending.push_back(ActionStatusChange {
+                    action: action.clone(),
+                    goal_id: *goal_id,
+                    previous,
+                    status: *status,
+                });
+            }
+        }
+        self.statuses.insert(action, goals);
+    }
+}
+
+impl Stream for ActionStatusMonitor {
+    type Item = ActionStatusChange;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(change) = self.pending.pop_front() {
+                return Poll::Ready(Some(change));
+            }
+            match self.subscriptions.poll_next_unpin(cx) {
+                Poll::Ready(Some((action, msg))) => self.update(action, msg),
+                // no subscriptions (left), wait for discover.
+                Poll::Ready(None) => {
+                    self.waker = Some(cx.waker().clone());
+                    return Poll::Pending;
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
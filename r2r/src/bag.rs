@@ -0,0 +1,327 @@
+//! Recording topics to bag files readable by `ros2 bag play`.
+//!
+//! A [`McapWriter`] writes messages to an [MCAP](https://mcap.dev) file in
+//! the `ros2` profile used by the `rosbag2_storage_mcap` plugin, so that
+//! recording can be started and stopped by the application.
+//!
+//! The module needs the `bag` feature, which adds the `mcap` dependency.
+
+use futures::{future::FutureExt, stream::Stream, StreamExt};
+use indexmap::IndexMap;
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    fs::File,
+    path::{Path, PathBuf},
+    pin::Pin,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    error::*,
+    msg_types::{message_type_name, WrappedTypesupport},
+    nodes::Node,
+    qos::QosProfile,
+};
+
+// the separator between the definitions of a message and its fields, as
+// written by rosbag2.
+const DEFINITION_SEPARATOR: &str =
+    "================================================================================";
+
+const BUILTIN_TYPES: [&str; 15] = [
+    "bool", "byte", "char", "float32", "float64", "int8", "uint8", "int16", "uint16", "int32",
+    "uint32", "int64", "uint64", "string", "wstring",
+];
+
+type RawStream = Pin<Box<dyn Stream<Item = Vec<u8>> + Send>>;
+
+struct Channel {
+    id: u16,
+    message_count: u64,
+}
+
+/// Writes messages to an MCAP file.
+///
+/// The messages are stored as CDR, one channel per topic, in uncompressed
+/// chunks. The file is finished with the indexes and a summary of the
+/// schemas, channels and message counts.
+///
+/// ``` rust,ignore
+/// let mut bag = r2r::bag::McapWriter::from_subscriptions(path, &mut node, &["/chatter"])?;
+/// loop {
+///     node.spin_once(std::time::Duration::from_millis(100));
+///     bag.record()?;
+/// }
+/// ```
+pub struct McapWriter {
+    path: PathBuf,
+    // chunks are buffered by the writer, the file is written unbuffered.
+    writer: mcap::Writer<File>,
+    // schema ids by message type.
+    schemas: HashMap<String, u16>,
+    channels: HashMap<String, Channel>,
+    subscriptions: Vec<(String, String, RawStream)>,
+}
+
+impl McapWriter {
+    /// Create the file at `path` and write the MCAP header.
+    pub fn open(path: &Path) -> Result<McapWriter> {
+        let file = File::create(path).map_err(|e| bag_error(path, e))?;
+        let writer = mcap::WriteOptions::new()
+            .profile("ros2")
+            .library("r2r")
+            .create(file)
+            .map_err(|e| bag_error(path, e))?;
+        Ok(McapWriter {
+            path: path.to_owned(),
+            writer,
+            schemas: HashMap::new(),
+            channels: HashMap::new(),
+            subscriptions: Vec::new(),
+        })
+    }
+
+    /// Create the file at `path` and subscribe to `topics`, which are
+    /// recorded by [`McapWriter::record`].
+    ///
+    /// The topics must be in the graph, as their types are looked up there.
+    pub fn from_subscriptions(path: &Path, node: &mut Node, topics: &[&str]) -> Result<McapWriter> {
+        let mut writer = McapWriter::open(path)?;
+        let names_and_types = node.get_topic_names_and_types()?;
+        for topic in topics {
+            let topic = node.resolve_topic_name(topic)?;
+            let topic_type = names_and_types
+                .get(&topic)
+                .and_then(|types| types.first())
+                .ok_or_else(|| bag_error(path, format!("topic {} not found", topic)))?
+                .clone();
+            let stream = node.subscribe_raw(&topic, &topic_type, QosProfile::default())?;
+            writer
+                .subscriptions
+                .push((topic, topic_type, Box::pin(stream)));
+        }
+        Ok(writer)
+    }
+
+    /// Write `msg` on `topic`, with `log_time` in nanoseconds since the epoch.
+    pub fn write_message<T>(&mut self, topic: &str, msg: &T, log_time: u64) -> Result<()>
+    where
+        T: WrappedTypesupport,
+    {
+        let topic_type = message_type_name(T::get_ts()).ok_or(Error::InvalidMessageType {
+            msgtype: std::any::type_name::<T>().to_owned(),
+        })?;
+        let data = msg.to_serialized_bytes()?;
+        self.write_serialized(topic, &topic_type, &data, log_time)
+    }
+
+    /// Write a message already serialized to CDR, e.g. by
+    /// [`Node::subscribe_raw`], with `topic_type` as `"std_msgs/msg/String"`.
+    pub fn write_serialized(
+        &mut self, topic: &str, topic_type: &str, data: &[u8], log_time: u64,
+    ) -> Result<()> {
+        self.add_channel(topic, topic_type)?;
+        let channel = self
+            .channels
+            .get_mut(topic)
+            .expect("channel was just added");
+        let header = mcap::records::MessageHeader {
+            channel_id: channel.id,
+            // the sequence of a record has 32 bits, and wraps around.
+            sequence: channel.message_count as u32,
+            log_time,
+            // the publish time is not known, rosbag2 uses the log time too.
+            publish_time: log_time,
+        };
+        channel.message_count += 1;
+        self.writer
+            .write_to_known_channel(&header, data)
+            .map_err(|e| bag_error(&self.path, e))
+    }
+
+    /// Write the messages received by the subscriptions of
+    /// [`McapWriter::from_subscriptions`] since the last call, logged at the
+    /// current time. Returns the number of messages written.
+    ///
+    /// The messages are received when the node is spun.
+    pub fn record(&mut self) -> Result<usize> {
+        let log_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos() as u64;
+        let mut received = vec![];
+        for (topic, topic_type, stream) in &mut self.subscriptions {
+            while let Some(Some(data)) = stream.next().now_or_never() {
+                received.push((topic.clone(), topic_type.clone(), data));
+            }
+        }
+        let count = received.len();
+        for (topic, topic_type, data) in received {
+            self.write_serialized(&topic, &topic_type, &data, log_time)?;
+        }
+        Ok(count)
+    }
+
+    /// Write the summary and close the file.
+    ///
+    /// Dropping the writer also finishes the file, but only logs errors.
+    pub fn close(mut self) -> Result<()> {
+        self.finish()
+    }
+
+    fn add_channel(&mut self, topic: &str, topic_type: &str) -> Result<()> {
+        if self.channels.contains_key(topic) {
+            return Ok(());
+        }
+        let schema_id = match self.schemas.get(topic_type) {
+            Some(id) => *id,
+            None => {
+                let definition = message_definition(topic_type);
+                let id = self
+                    .writer
+                    .add_schema(topic_type, "ros2msg", definition.as_bytes())
+                    .map_err(|e| bag_error(&self.path, e))?;
+                self.schemas.insert(topic_type.to_owned(), id);
+                id
+            }
+        };
+        let id = self
+            .writer
+            .add_channel(schema_id, topic, "cdr", &BTreeMap::new())
+            .map_err(|e| bag_error(&self.path, e))?;
+        self.channels.insert(
+            topic.to_owned(),
+            Channel {
+                id,
+                message_count: 0,
+            },
+        );
+        Ok(())
+    }
+
+    // finishing again after the first time does nothing.
+    fn finish(&mut self) -> Result<()> {
+        self.writer
+            .finish()
+            .map(|_| ())
+            .map_err(|e| bag_error(&self.path, e))
+    }
+}
+
+impl Drop for McapWriter {
+    fn drop(&mut self) {
+        if let Err(e) = self.finish() {
+            log::error!("could not finish bag file: {}", e);
+        }
+    }
+}
+
+fn bag_error(path: &Path, msg: impl ToString) -> Error {
+    Error::BagFile {
+        path: path.display().to_string(),
+        msg: msg.to_string(),
+    }
+}
+
+// the definition of `topic_type` and the types of its fields, read from the
+// `.msg` files of the installed packages. the schema is left empty when the
+// files are not found, which decoding readers need but `ros2 bag play` does not.
+fn message_definition(topic_type: &str) -> String {
+    let mut definitions = IndexMap::new();
+    let mut queue = VecDeque::from([topic_type.to_owned()]);
+    let mut cache = HashMap::new();
+    while let Some(ty) = queue.pop_front() {
+        if definitions.contains_key(&ty) {
+            continue;
+        }
+        let Some((package, name)) = split_type(&ty) else {
+            continue;
+        };
+        let Some(text) = cache
+            .entry(ty.clone())
+            .or_insert_with(|| read_msg_file(package, name))
+            .clone()
+        else {
+            log::debug!("no message definition found for {}", ty);
+            if ty == topic_type {
+                return String::new();
+            }
+            continue;
+        };
+        for field_type in text.lines().filter_map(field_type) {
+            queue.push_back(qualify_type(field_type, package));
+        }
+        definitions.insert(ty, text);
+    }
+
+    let mut definition = String::new();
+    for (i, (ty, text)) in definitions.iter().enumerate() {
+        if i > 0 {
+            let (package, name) = split_type(ty).unwrap_or_default();
+            definition
+                .push_str(&format!("\n{}\nMSG: {}/{}\n", DEFINITION_SEPARATOR, package, name));
+        }
+        definition.push_str(text.trim_end());
+    }
+    definition
+}
+
+// `pkg/msg/Name` or `pkg/Name` as (pkg, Name).
+fn split_type(ty: &str) -> Option<(&str, &str)> {
+    let mut parts = ty.split('/');
+    let package = parts.next()?;
+    let name = parts.next_back()?;
+    Some((package, name))
+}
+
+fn read_msg_file(package: &str, name: &str) -> Option<String> {
+    let prefixes = std::env::var("AMENT_PREFIX_PATH").ok()?;
+    std::env::split_paths(&prefixes).find_map(|prefix| {
+        let path = prefix
+            .join("share")
+            .join(package)
+            .join("msg")
+            .join(format!("{}.msg", name));
+        std::fs::read_to_string(path).ok()
+    })
+}
+
+// the type of a field of a `.msg` line, if it is not a builtin type.
+fn field_type(line: &str) -> Option<&str> {
+    let line = line.split('#').next()?.trim();
+    let ty = line.split_whitespace().next()?;
+    // arrays `T[]`, `T[3]`, `T[<=3]` and bounded strings `string<=5`.
+    let ty = ty.split(['[', '<']).next()?;
+    (!BUILTIN_TYPES.contains(&ty)).then_some(ty)
+}
+
+// the full name `pkg/msg/Name` of a field type.
+fn qualify_type(ty: &str, package: &str) -> String {
+    match ty.split_once('/') {
+        Some((package, name)) => format!("{}/msg/{}", package, name),
+        None if ty == "Header" => "std_msgs/msg/Header".to_owned(),
+        None => format!("{}/msg/{}", package, ty),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_types() {
+        assert_eq!(field_type("int32 data"), None);
+        assert_eq!(field_type("# a comment"), None);
+        assert_eq!(field_type(""), None);
+        assert_eq!(field_type("string<=5 name"), None);
+        assert_eq!(field_type("uint8 OK=0"), None);
+        assert_eq!(field_type("Header header # stamp"), Some("Header"));
+        assert_eq!(field_type("geometry_msgs/Point[<=3] points"), Some("geometry_msgs/Point"));
+        assert_eq!(qualify_type("Header", "sensor_msgs"), "std_msgs/msg/Header");
+        assert_eq!(qualify_type("Point", "geometry_msgs"), "geometry_msgs/msg/Point");
+        assert_eq!(
+            qualify_type("builtin_interfaces/Time", "std_msgs"),
+            "builtin_interfaces/msg/Time"
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use futures::{
+    channel::mpsc, executor::LocalPool, future::Future, stream::StreamExt, task::LocalSpawnExt,
+};
+use std::pin::Pin;
+
+type Task = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// How the callbacks of a [`CallbackGroup`] may run, like the callback group
+/// types of rclcpp.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CallbackGroupKind {
+    /// The callbacks of the group run one at a time, on one thread.
+    MutuallyExclusive,
+    /// The callbacks of the group run concurrently, each subscription, timer
+    /// and service on a thread of its own. The messages of one subscription
+    /// are still handled in order.
+    Reentrant,
+}
+
+/// Callbacks run on threads of their own instead of the one spinning the node.
+///
+/// `spin_once` hands the messages, timer ticks and requests of the callbacks
+/// in a group over to the threads of the group, so a slow callback never
+/// blocks `spin_once` or the callbacks of other groups. Callbacks in the same
+/// [`CallbackGroupKind::MutuallyExclusive`] group wait for each other.
+///
+/// Created by [`Node::create_callback_group`](crate::Node::create_callback_group),
+/// and used with e.g. [`Node::subscribe_in_group`](crate::Node::subscribe_in_group).
+/// The callbacks keep running until their node is dropped, also when the
+/// group itself is dropped before.
+///
+/// ``` rust,ignore
+/// let images = node.create_callback_group(CallbackGroupKind::MutuallyExclusive);
+/// let imu = node.create_callback_group(CallbackGroupKind::MutuallyExclusive);
+/// node.subscribe_in_group("/image", QosProfile::default(), &images, |msg: Image| process(msg))?;
+/// node.subscribe_in_group("/imu", QosProfile::default(), &imu, |msg: Imu| integrate(msg))?;
+/// loop {
+///     node.spin_once(std::time::Duration::from_millis(100));
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct CallbackGroup {
+    kind: CallbackGroupKind,
+    // the thread of a mutually exclusive group
+    worker: Option<mpsc::UnboundedSender<Task>>,
+}
+
+impl CallbackGroup {
+    pub(crate) fn new(kind: CallbackGroupKind) -> Self {
+        let worker = (kind == CallbackGroupKind::MutuallyExclusive).then(spawn_worker);
+        CallbackGroup { kind, worker }
+    }
+
+    /// How the callbacks of the group may run.
+    pub fn kind(&self) -> CallbackGroupKind {
+        self.kind
+    }
+
+    /// Run `task` on the thread of the group, or on a new thread for a
+    /// reentrant group.
+    pub(crate) fn spawn(&self, task: impl Future<Output = ()> + Send + 'static) {
+        let worker = match &self.worker {
+            Some(worker) => worker.clone(),
+            // the thread ends with the task, as the sender is dropped.
+            None => spawn_worker(),
+        };
+        // the thread only stops taking tasks once all senders are dropped.
+        let _ = worker.unbounded_send(Box::pin(task));
+    }
+}
+
+fn spawn_worker() -> mpsc::UnboundedSender<Task> {
+    let (sender, mut tasks) = mpsc::unbounded::<Task>();
+    std::thread::spawn(move || {
+        let mut pool = LocalPool::new();
+        let spawner = pool.spawner();
+        pool.run_until(async move {
+            while let Some(task) = tasks.next().await {
+                // the pool lives as long as the spawner, spawning cannot fail.
+                let _ = spawner.spawn_local(task);
+            }
+        });
+        // the tasks end when their streams do, with the node.
+        pool.run();
+    });
+    sender
+}
@@ -1,12 +1,20 @@
-use futures::{channel::oneshot, TryFutureExt};
+use futures::{
+    channel::oneshot, executor::LocalPool, future::poll_fn, stream::FuturesUnordered, FutureExt,
+    Stream, TryFutureExt,
+};
 use std::{
     ffi::CString,
     future::Future,
     mem::MaybeUninit,
-    sync::{Mutex, Weak},
+    sync::{Arc, Mutex, Weak},
+    task::{Poll, Waker},
+    time::{Duration, Instant},
 };
 
-use crate::{error::*, msg_types::*, QosProfile};
+use crate::{
+    error::*, msg_types::*, nodes::Node, services::ServiceIntrospectionState,
+    spin::spin_until_future_complete, QosProfile,
+};
 use r2r_rcl::*;
 
 /// ROS service client.
@@ -34,7 +42,225 @@ where
         // upgrade to actual ref. if still alive
         let client = self.client.upgrade().ok_or(Error::RCL_RET_CLIENT_INVALID)?;
         let mut client = client.lock().unwrap();
-        client.request(msg)
+        client.request(msg, None)
+    }
+
+    /// Make a service request that gives up after `timeout`.
+    ///
+    /// The request is sent right away, and the timeout starts then. The
+    /// returned `Future` resolves to [`ServiceError::Timeout`] if no response
+    /// has been received within `timeout`, or to
+    /// [`ServiceError::ServerUnavailable`] if the server is gone, either
+    /// before the timeout or when it expires. Both are checked in
+    /// `spin_once`, so their resolution depends on how often the node is spun.
+    pub fn call_with_timeout(
+        &self, req: T::Request, timeout: Duration,
+    ) -> impl Future<Output = std::result::Result<T::Response, ServiceError>> {
+        let receiver = match self.client.upgrade() {
+            Some(client) => client.lock().unwrap().send(&req, Some(timeout)),
+            None => Err(RCL_RET_CLIENT_INVALID as i32),
+        };
+        async move {
+            match receiver {
+                // instead of "canceled" we return invalid client.
+                Ok(receiver) => receiver
+                    .await
+                    .unwrap_or(Err(ServiceError::RclError(RCL_RET_CLIENT_INVALID as i32))),
+                Err(code) => Err(ServiceError::RclError(code)),
+            }
+        }
+    }
+
+    /// Returns a `Future` that completes when the service server is available.
+    ///
+    /// With a `timeout`, the `Future` resolves to `Error::RCL_RET_TIMEOUT` if
+    /// the server has not become available in time. Availability is checked
+    /// in `spin_once`, which also wakes up on changes of the ROS graph while
+    /// someone is waiting.
+    pub fn wait_for_service(&self, timeout: Option<Duration>) -> impl Future<Output = Result<()>> {
+        let (sender, receiver) = oneshot::channel();
+        // if the client is gone the sender is dropped here.
+        if let Some(client) = self.client.upgrade() {
+            let deadline = timeout.map(|timeout| Instant::now() + timeout);
+            client
+                .lock()
+                .unwrap()
+                .service_waiters
+                .push((sender, deadline));
+        }
+        receiver.map(|r| r.unwrap_or(Err(Error::RCL_RET_CLIENT_INVALID)))
+    }
+
+    /// Make a service request and wait for the response, from synchronous code.
+    ///
+    /// `node` is the node of the client, which is spun until the response
+    /// arrives, like `rclcpp::spin_until_future_complete`. Returns
+    /// `Error::RCL_RET_TIMEOUT` if no response is received within `timeout`,
+    /// see [`Client::call_with_timeout`].
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let ctx = r2r::Context::create()?;
+    /// # let mut node = r2r::Node::create(ctx, "node", "")?;
+    /// let client = node.create_client::<r2r::test_msgs::srv::Empty::Service>(
+    ///     "/service",
+    ///     r2r::QosProfile::default(),
+    /// )?;
+    /// let request = r2r::test_msgs::srv::Empty::Request::default();
+    /// let response = client.call_sync(&request, &mut node, std::time::Duration::from_secs(1))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn call_sync(
+        &self, msg: &T::Request, node: &mut Node, timeout: Duration,
+    ) -> Result<T::Response> {
+        let client = self.client.upgrade().ok_or(Error::RCL_RET_CLIENT_INVALID)?;
+        let response = client.lock().unwrap().request(msg, Some(timeout))?;
+        // the timeout of the request resolves the future, none needed here.
+        spin_until_future_complete(node, &mut LocalPool::new(), response, None)?
+    }
+
+    /// Make several service requests without waiting for the responses in between.
+    ///
+    /// All requests are sent right away, and the returned `Stream` yields
+    /// the responses as they arrive, which may not be the order of the
+    /// requests.
+    pub fn call_pipelined(
+        &self, requests: Vec<T::Request>,
+    ) -> Result<impl Stream<Item = Result<T::Response>>>
+    where
+        T: WrappedServiceTypeSupport,
+    {
+        let client = self.client.upgrade().ok_or(Error::RCL_RET_CLIENT_INVALID)?;
+        let mut client = client.lock().unwrap();
+        requests
+            .iter()
+            .map(|msg| client.request(msg, None))
+            .collect::<Result<FuturesUnordered<_>>>()
+    }
+}
+
+/// A fixed number of clients of one service, shared by several tasks.
+///
+/// Each client is a middleware endpoint, so tasks calling the same service
+/// can take turns on a few clients instead of creating their own. See
+/// [`Node::create_client_pool`].
+///
+/// ``` rust,ignore
+/// let pool = node.create_client_pool::<AddTwoInts::Service>("/add", QosProfile::default(), 3)?;
+/// let client = pool.checkout().await;
+/// let response = client.request(&request)?.await?;
+/// ```
+pub struct ClientPool<T>
+where
+    T: WrappedServiceTypeSupport,
+{
+    inner: Arc<Mutex<ClientPoolInner<T>>>,
+    size: usize,
+}
+
+struct ClientPoolInner<T>
+where
+    T: WrappedServiceTypeSupport,
+{
+    free: Vec<Client<T>>,
+    // checkouts waiting for a client to be returned.
+    waiters: Vec<Waker>,
+}
+
+impl<T> Clone for ClientPool<T>
+where
+    T: WrappedServiceTypeSupport,
+{
+    fn clone(&self) -> Self {
+        ClientPool {
+            inner: self.inner.clone(),
+            size: self.size,
+        }
+    }
+}
+
+impl<T> ClientPool<T>
+where
+    T: WrappedServiceTypeSupport,
+{
+    pub(crate) fn new(clients: Vec<Client<T>>) -> Self {
+        ClientPool {
+            size: clients.len(),
+            inner: Arc::new(Mutex::new(ClientPoolInner {
+                free: clients,
+                waiters: Vec::new(),
+            })),
+        }
+    }
+
+    /// The number of clients in the pool.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// The number of clients not checked out.
+    pub fn available(&self) -> usize {
+        self.inner.lock().unwrap().free.len()
+    }
+
+    /// Take a client from the pool, waiting until one is returned if all
+    /// of them are in use.
+    ///
+    /// The client goes back to the pool when the [`PooledClient`] is dropped.
+    pub fn checkout(&self) -> impl Future<Output = PooledClient<T>> {
+        let inner = self.inner.clone();
+        poll_fn(move |cx| {
+            let mut pool = inner.lock().unwrap();
+            match pool.free.pop() {
+                Some(client) => Poll::Ready(PooledClient {
+                    client: Some(client),
+                    pool: inner.clone(),
+                }),
+                None => {
+                    if !pool.waiters.iter().any(|w| w.will_wake(cx.waker())) {
+                        pool.waiters.push(cx.waker().clone());
+                    }
+                    Poll::Pending
+                }
+            }
+        })
+    }
+}
+
+/// A client checked out of a [`ClientPool`], dereferencing to the [`Client`].
+pub struct PooledClient<T>
+where
+    T: WrappedServiceTypeSupport,
+{
+    client: Option<Client<T>>,
+    pool: Arc<Mutex<ClientPoolInner<T>>>,
+}
+
+impl<T> std::ops::Deref for PooledClient<T>
+where
+    T: WrappedServiceTypeSupport,
+{
+    type Target = Client<T>;
+
+    fn deref(&self) -> &Self::Target {
+        self.client.as_ref().expect("client is only taken on drop")
+    }
+}
+
+impl<T> Drop for PooledClient<T>
+where
+    T: WrappedServiceTypeSupport,
+{
+    fn drop(&mut self) {
+        if let Some(client) = self.client.take() {
+            let mut pool = self.pool.lock().unwrap();
+            pool.free.push(client);
+            // all are woken, as a waiting checkout may have been dropped.
+            for waker in pool.waiters.drain(..) {
+                waker.wake();
+            }
+        }
     }
 }
 
@@ -62,7 +288,20 @@ impl ClientUntyped {
         // upgrade to actual ref. if still alive
         let client = self.client.upgrade().ok_or(Error::RCL_RET_CLIENT_INVALID)?;
         let mut client = client.lock().unwrap();
-        client.request(msg)
+        client.request(msg, None)
+    }
+
+    /// Make an "untyped" service request that gives up after `timeout`.
+    ///
+    /// The `Future` resolves to `Error::RCL_RET_TIMEOUT` if no response has
+    /// been received within `timeout` of sending the request.
+    pub fn request_with_timeout(
+        &self, msg: serde_json::Value, timeout: Duration,
+    ) -> Result<impl Future<Output = Result<Result<serde_json::Value>>>> {
+        // upgrade to actual ref. if still alive
+        let client = self.client.upgrade().ok_or(Error::RCL_RET_CLIENT_INVALID)?;
+        let mut client = client.lock().unwrap();
+        client.request(msg, Some(timeout))
     }
 }
 
@@ -83,24 +322,40 @@ impl<T: 'static> TypedClient<T>
 where
     T: WrappedServiceTypeSupport,
 {
-    pub fn request(&mut self, msg: &T::Request) -> Result<impl Future<Output = Result<T::Response>>>
+    pub fn request(
+        &mut self, msg: &T::Request, timeout: Option<Duration>,
+    ) -> Result<impl Future<Output = Result<T::Response>>>
     where
         T: WrappedServiceTypeSupport,
     {
+        let receiver = self.send(msg, timeout).map_err(Error::from_rcl_error)?;
+        // instead of "canceled" we return invalid client.
+        Ok(receiver.map(|r| match r {
+            Ok(response) => response.map_err(Error::from),
+            Err(_) => Err(Error::RCL_RET_CLIENT_INVALID),
+        }))
+    }
+
+    /// Send a request, returning the rcl return code on failure.
+    fn send(
+        &mut self, msg: &T::Request, timeout: Option<Duration>,
+    ) -> std::result::Result<ResponseReceiver<T::Response>, i32> {
         let native_msg: WrappedNativeMsg<T::Request> = WrappedNativeMsg::<T::Request>::from(msg);
         let mut seq_no = 0i64;
         let result =
             unsafe { rcl_send_request(&self.rcl_handle, native_msg.void_ptr(), &mut seq_no) };
 
-        let (sender, receiver) = oneshot::channel::<T::Response>();
-
         if result == RCL_RET_OK as i32 {
+            let (sender, receiver) = oneshot::channel();
             self.response_channels.push((seq_no, sender));
-            // instead of "canceled" we return invalid client.
-            Ok(receiver.map_err(|_| Error::RCL_RET_CLIENT_INVALID))
+            if let Some(timeout) = timeout {
+                self.request_deadlines
+                    .push((seq_no, Instant::now() + timeout));
+            }
+            Ok(receiver)
         } else {
             log::error!("could not send request {}", result);
-            Err(Error::from_rcl_error(result))
+            Err(result)
         }
     }
 }
@@ -109,7 +364,7 @@ unsafe impl Send for UntypedClient_ {}
 
 impl UntypedClient_ {
     pub fn request(
-        &mut self, msg: serde_json::Value,
+        &mut self, msg: serde_json::Value, timeout: Option<Duration>,
     ) -> Result<impl Future<Output = Result<Result<serde_json::Value>>>> {
         let native_msg = (self.service_type.make_request_msg)();
         native_msg.from_json(msg)?;
@@ -122,6 +377,10 @@ impl UntypedClient_ {
 
         if result == RCL_RET_OK as i32 {
             self.response_channels.push((seq_no, sender));
+            if let Some(timeout) = timeout {
+                self.request_deadlines
+                    .push((seq_no, Instant::now() + timeout));
+            }
             // instead of "canceled" we return invalid client.
             Ok(receiver.map_err(|_| Error::RCL_RET_CLIENT_INVALID))
         } else {
@@ -136,6 +395,9 @@ pub trait Client_ {
     fn handle_response(&mut self);
     fn register_poll_available(&mut self, s: oneshot::Sender<()>);
     fn poll_available(&mut self, node: &mut rcl_node_t);
+    /// True if `poll_available` should run again when the ROS graph changes.
+    fn waits_for_graph(&self) -> bool;
+    fn handle_timeouts(&mut self, node: &mut rcl_node_t);
     fn destroy(&mut self, node: &mut rcl_node_t);
 }
 
@@ -144,10 +406,18 @@ where
     T: WrappedServiceTypeSupport,
 {
     pub rcl_handle: rcl_client_t,
-    pub response_channels: Vec<(i64, oneshot::Sender<T::Response>)>,
+    pub response_channels: Vec<(i64, ResponseSender<T::Response>)>,
+    pub request_deadlines: Vec<(i64, Instant)>,
     pub poll_available_channels: Vec<oneshot::Sender<()>>,
+    // futures of wait_for_service, with their deadlines.
+    pub service_waiters: Vec<(oneshot::Sender<Result<()>>, Option<Instant>)>,
+    // if the server was available the last time it was polled.
+    pub server_available: bool,
 }
 
+type ResponseSender<R> = oneshot::Sender<std::result::Result<R, ServiceError>>;
+type ResponseReceiver<R> = oneshot::Receiver<std::result::Result<R, ServiceError>>;
+
 impl<T: 'static> Client_ for TypedClient<T>
 where
     T: WrappedServiceTypeSupport,
@@ -175,8 +445,10 @@ where
                 .position(|(id, _)| id == &request_id.sequence_number)
             {
                 let (_, sender) = self.response_channels.swap_remove(idx);
+                self.request_deadlines
+                    .retain(|(id, _)| id != &request_id.sequence_number);
                 let response = T::Response::from_native(&response_msg);
-                match sender.send(response) {
+                match sender.send(Ok(response)) {
                     Ok(()) => {}
                     Err(e) => {
                         log::debug!("error sending to client: {:?}", e);
@@ -203,7 +475,10 @@ where
     }
 
     fn poll_available(&mut self, node: &mut rcl_node_t) {
-        if self.poll_available_channels.is_empty() {
+        if self.poll_available_channels.is_empty()
+            && self.service_waiters.is_empty()
+            && self.request_deadlines.is_empty()
+        {
             return;
         }
         let available = service_available_helper(node, self.handle());
@@ -213,17 +488,77 @@ where
                 while let Some(sender) = self.poll_available_channels.pop() {
                     let _res = sender.send(()); // we ignore if receiver dropped.
                 }
+                for (sender, _) in self.service_waiters.drain(..) {
+                    let _res = sender.send(Ok(()));
+                }
+                self.server_available = true;
             }
             Ok(false) => {
-                // not available...
+                // requests sent while the server was there will not be answered.
+                if self.server_available {
+                    let response_channels = &mut self.response_channels;
+                    for (seq_no, _) in self.request_deadlines.drain(..) {
+                        if let Some(idx) =
+                            response_channels.iter().position(|(id, _)| *id == seq_no)
+                        {
+                            let (_, sender) = response_channels.swap_remove(idx);
+                            let _res = sender.send(Err(ServiceError::ServerUnavailable));
+                        }
+                    }
+                }
+                self.server_available = false;
             }
-            Err(_) => {
+            Err(e) => {
                 // error, close all channels
                 self.poll_available_channels.clear();
+                for (sender, _) in self.service_waiters.drain(..) {
+                    let _res = sender.send(Err(e.clone()));
+                }
             }
         }
     }
 
+    fn waits_for_graph(&self) -> bool {
+        !self.poll_available_channels.is_empty()
+            || !self.service_waiters.is_empty()
+            || !self.request_deadlines.is_empty()
+    }
+
+    fn handle_timeouts(&mut self, node: &mut rcl_node_t) {
+        let now = Instant::now();
+        let (expired, waiting) = std::mem::take(&mut self.service_waiters)
+            .into_iter()
+            .partition(|(_, deadline)| matches!(deadline, Some(deadline) if *deadline <= now));
+        self.service_waiters = waiting;
+        for (sender, _) in expired {
+            let _res = sender.send(Err(Error::RCL_RET_TIMEOUT));
+        }
+
+        if self
+            .request_deadlines
+            .iter()
+            .all(|(_, deadline)| *deadline > now)
+        {
+            return;
+        }
+        let error = match service_available_helper(node, &self.rcl_handle) {
+            Ok(false) => ServiceError::ServerUnavailable,
+            _ => ServiceError::Timeout,
+        };
+        let response_channels = &mut self.response_channels;
+        self.request_deadlines.retain(|(seq_no, deadline)| {
+            if *deadline > now {
+                return true;
+            }
+            if let Some(idx) = response_channels.iter().position(|(id, _)| id == seq_no) {
+                let (_, sender) = response_channels.swap_remove(idx);
+                // we ignore if receiver dropped.
+                let _res = sender.send(Err(error));
+            }
+            false
+        });
+    }
+
     fn destroy(&mut self, node: &mut rcl_node_t) {
         unsafe {
             rcl_client_fini(&mut self.rcl_handle, node);
@@ -235,6 +570,7 @@ pub struct UntypedClient_ {
     pub service_type: UntypedServiceSupport,
     pub rcl_handle: rcl_client_t,
     pub response_channels: Vec<(i64, oneshot::Sender<Result<serde_json::Value>>)>,
+    pub request_deadlines: Vec<(i64, Instant)>,
     pub poll_available_channels: Vec<oneshot::Sender<()>>,
 }
 
@@ -262,6 +598,8 @@ impl Client_ for UntypedClient_ {
                 .position(|(id, _)| id == &request_id.sequence_number)
             {
                 let (_, sender) = self.response_channels.swap_remove(idx);
+                self.request_deadlines
+                    .retain(|(id, _)| id != &request_id.sequence_number);
                 let response = response_msg.to_json();
                 match sender.send(response) {
                     Ok(()) => {}
@@ -311,6 +649,26 @@ impl Client_ for UntypedClient_ {
         }
     }
 
+    fn waits_for_graph(&self) -> bool {
+        !self.poll_available_channels.is_empty()
+    }
+
+    fn handle_timeouts(&mut self, _node: &mut rcl_node_t) {
+        let now = Instant::now();
+        let response_channels = &mut self.response_channels;
+        self.request_deadlines.retain(|(seq_no, deadline)| {
+            if *deadline > now {
+                return true;
+            }
+            if let Some(idx) = response_channels.iter().position(|(id, _)| id == seq_no) {
+                let (_, sender) = response_channels.swap_remove(idx);
+                // we ignore if receiver dropped.
+                let _res = sender.send(Err(Error::RCL_RET_TIMEOUT));
+            }
+            false
+        });
+    }
+
     fn destroy(&mut self, node: &mut rcl_node_t) {
         unsafe {
             rcl_client_fini(&mut self.rcl_handle, node);
@@ -339,11 +697,52 @@ pub fn create_client_helper(
     };
     if result == RCL_RET_OK as i32 {
         Ok(client_handle)
+    } else {
+        Err(Error::from_rcl_call("rcl_client_init", result))
+    }
+}
+
+/// Publish service events of `client` on `<service>/_service_event`.
+#[cfg(any(
+    r2r__ros__distro__iron,
+    r2r__ros__distro__jazzy,
+    r2r__ros__distro__rolling
+))]
+pub fn configure_client_introspection(
+    client: &mut rcl_client_t, node: &mut rcl_node_t, clock: &mut rcl_clock_t,
+    service_ts: *const rosidl_service_type_support_t, state: ServiceIntrospectionState,
+) -> Result<()> {
+    let result = unsafe {
+        let mut publisher_options = rcl_publisher_get_default_options();
+        publisher_options.qos = QosProfile::system_default().into();
+        rcl_client_configure_service_introspection(
+            client,
+            node,
+            clock,
+            service_ts,
+            publisher_options,
+            state.into(),
+        )
+    };
+    if result == RCL_RET_OK as i32 {
+        Ok(())
     } else {
         Err(Error::from_rcl_error(result))
     }
 }
 
+#[cfg(not(any(
+    r2r__ros__distro__iron,
+    r2r__ros__distro__jazzy,
+    r2r__ros__distro__rolling
+)))]
+pub fn configure_client_introspection(
+    _client: &mut rcl_client_t, _node: &mut rcl_node_t, _clock: &mut rcl_clock_t,
+    _service_ts: *const rosidl_service_type_support_t, _state: ServiceIntrospectionState,
+) -> Result<()> {
+    Err(Error::UnsupportedFeature("service_introspection"))
+}
+
 pub fn service_available_helper(node: &mut rcl_node_t, client: &rcl_client_t) -> Result<bool> {
     let mut avail = false;
     let result = unsafe { rcl_service_server_is_available(node, client, &mut avail) };
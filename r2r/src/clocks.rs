@@ -1,6 +1,15 @@
-use std::{fmt::Debug, mem::MaybeUninit, time::Duration};
+use std::{
+    cmp::Ordering,
+    fmt::Debug,
+    mem::MaybeUninit,
+    ops::{Add, Sub},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
-use crate::{error::*, msg_types::generated_msgs::builtin_interfaces};
+#[cfg(r2r__std_msgs__msg__Header)]
+use crate::msg_types::generated_msgs::std_msgs;
+use crate::{error::*, msg_types::generated_msgs::builtin_interfaces, nodes::Node};
 use r2r_rcl::*;
 
 /// Different ROS clock types.
@@ -82,7 +91,6 @@ impl Clock {
     /// The clock must be [`ClockType::RosTime`].
     ///
     /// Wrapper for `rcl_enable_ros_time_override`
-    #[cfg(r2r__rosgraph_msgs__msg__Clock)]
     pub(crate) fn enable_ros_time_override(
         &mut self, initial_time: rcl_time_point_value_t,
     ) -> Result<()> {
@@ -130,7 +138,6 @@ impl Clock {
     /// The clock must be [`ClockType::RosTime`].
     ///
     /// Wrapper for `rcl_set_ros_time_override`
-    #[cfg(r2r__rosgraph_msgs__msg__Clock)]
     pub(crate) fn set_ros_time_override(&mut self, time: rcl_time_point_value_t) -> Result<()> {
         let valid = unsafe { rcl_clock_valid(&mut *self.clock_handle) };
         if !valid {
@@ -147,6 +154,46 @@ impl Clock {
     }
 }
 
+/// A ROS clock where time only passes when it is advanced.
+///
+/// Used for deterministic testing of timer dependent code, see
+/// [`Node::create_with_clock`](crate::Node::create_with_clock). The time
+/// starts at zero.
+#[derive(Clone)]
+pub struct MockClock {
+    clock: Arc<Mutex<Clock>>,
+}
+
+impl MockClock {
+    /// Create a new mock clock.
+    pub fn new() -> Result<Self> {
+        let mut clock = Clock::create(ClockType::RosTime)?;
+        clock.enable_ros_time_override(0)?;
+        Ok(MockClock {
+            clock: Arc::new(Mutex::new(clock)),
+        })
+    }
+
+    /// The current time of the clock.
+    pub fn now(&self) -> Result<Duration> {
+        self.clock.lock().unwrap().get_now()
+    }
+
+    /// Move the time of the clock forward by `duration`.
+    ///
+    /// Timers of the node using the clock that are due become ready,
+    /// and fire on the next `spin_once`.
+    pub fn advance(&self, duration: Duration) -> Result<()> {
+        let mut clock = self.clock.lock().unwrap();
+        let now = clock.get_now()?;
+        clock.set_ros_time_override((now + duration).as_nanos() as rcl_time_point_value_t)
+    }
+
+    pub(crate) fn ros_clock(&self) -> Arc<Mutex<Clock>> {
+        self.clock.clone()
+    }
+}
+
 impl From<builtin_interfaces::msg::Time> for rcutils_time_point_value_t {
     fn from(msg: builtin_interfaces::msg::Time) -> Self {
         (msg.sec as rcl_time_point_value_t) * 1_000_000_000
@@ -154,6 +201,190 @@ impl From<builtin_interfaces::msg::Time> for rcutils_time_point_value_t {
     }
 }
 
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+
+fn nanos_to_sec_nanosec(nanos: i128) -> Result<(i32, u32)> {
+    let sec = i32::try_from(nanos.div_euclid(NANOS_PER_SEC)).map_err(|_| Error::TimeOutOfRange)?;
+    Ok((sec, nanos.rem_euclid(NANOS_PER_SEC) as u32))
+}
+
+/// Times before the epoch have a negative `sec` and a positive `nanosec`.
+/// A `nanosec` above one second is carried over into the seconds.
+impl From<builtin_interfaces::msg::Time> for SystemTime {
+    fn from(msg: builtin_interfaces::msg::Time) -> Self {
+        let nanos = msg.sec as i128 * NANOS_PER_SEC + msg.nanosec as i128;
+        let since_epoch = Duration::from_nanos(nanos.unsigned_abs() as u64);
+        if nanos >= 0 {
+            UNIX_EPOCH + since_epoch
+        } else {
+            UNIX_EPOCH - since_epoch
+        }
+    }
+}
+
+/// Fails with [`Error::TimeOutOfRange`] for times the 32 bit `sec` field can not
+/// hold, i.e. after January 2038 or before December 1901.
+impl TryFrom<SystemTime> for builtin_interfaces::msg::Time {
+    type Error = Error;
+
+    fn try_from(time: SystemTime) -> Result<Self> {
+        let nanos = match time.duration_since(UNIX_EPOCH) {
+            Ok(d) => d.as_nanos() as i128,
+            Err(e) => -(e.duration().as_nanos() as i128),
+        };
+        let (sec, nanosec) = nanos_to_sec_nanosec(nanos)?;
+        Ok(builtin_interfaces::msg::Time { sec, nanosec })
+    }
+}
+
+/// Negative durations are clamped to zero. A `nanosec` above one second is
+/// carried over into the seconds.
+impl From<builtin_interfaces::msg::Duration> for Duration {
+    fn from(msg: builtin_interfaces::msg::Duration) -> Self {
+        let nanos = msg.sec as i128 * NANOS_PER_SEC + msg.nanosec as i128;
+        Duration::from_nanos(nanos.max(0) as u64)
+    }
+}
+
+/// Fails with [`Error::TimeOutOfRange`] for durations longer than `i32::MAX` seconds.
+impl TryFrom<Duration> for builtin_interfaces::msg::Duration {
+    type Error = Error;
+
+    fn try_from(duration: Duration) -> Result<Self> {
+        let (sec, nanosec) = nanos_to_sec_nanosec(duration.as_nanos() as i128)?;
+        Ok(builtin_interfaces::msg::Duration { sec, nanosec })
+    }
+}
+
+fn msg_nanos(sec: i32, nanosec: u32) -> i128 {
+    sec as i128 * NANOS_PER_SEC + nanosec as i128
+}
+
+fn seconds_to_nanos(secs: f64) -> i128 {
+    assert!(secs.is_finite(), "cannot convert {} seconds to a time", secs);
+    (secs * NANOS_PER_SEC as f64).round() as i128
+}
+
+impl builtin_interfaces::msg::Time {
+    /// The time `secs` seconds after the epoch, rounded to nanoseconds.
+    ///
+    /// # Panics
+    ///
+    /// If `secs` is not finite or out of the range of the 32 bit `sec` field.
+    pub fn from_seconds(secs: f64) -> Self {
+        let (sec, nanosec) =
+            nanos_to_sec_nanosec(seconds_to_nanos(secs)).expect("time out of range");
+        builtin_interfaces::msg::Time { sec, nanosec }
+    }
+
+    /// The current time of the ROS clock of `node`, see [`Node::now`].
+    ///
+    /// # Panics
+    ///
+    /// If the clock of the node cannot be read.
+    pub fn from_node_clock(node: &Node) -> Self {
+        node.now().expect("cannot read the clock of the node")
+    }
+}
+
+impl builtin_interfaces::msg::Duration {
+    /// A duration of `secs` seconds, rounded to nanoseconds.
+    ///
+    /// Negative durations have a negative `sec` and a positive `nanosec`.
+    ///
+    /// # Panics
+    ///
+    /// If `secs` is not finite or out of the range of the 32 bit `sec` field.
+    pub fn from_seconds(secs: f64) -> Self {
+        let (sec, nanosec) =
+            nanos_to_sec_nanosec(seconds_to_nanos(secs)).expect("duration out of range");
+        builtin_interfaces::msg::Duration { sec, nanosec }
+    }
+
+    /// A duration of `ns` nanoseconds.
+    ///
+    /// Negative durations have a negative `sec` and a positive `nanosec`.
+    ///
+    /// # Panics
+    ///
+    /// If `ns` is out of the range of the 32 bit `sec` field.
+    pub fn from_nanos(ns: i64) -> Self {
+        let (sec, nanosec) = nanos_to_sec_nanosec(ns as i128).expect("duration out of range");
+        builtin_interfaces::msg::Duration { sec, nanosec }
+    }
+}
+
+/// Set the stamp of `header` to the current time of the ROS clock of `node`.
+///
+/// The `frame_id` is left unchanged.
+///
+/// # Panics
+///
+/// If the clock of the node cannot be read.
+#[cfg(r2r__std_msgs__msg__Header)]
+pub fn stamp_header(node: &Node, header: &mut std_msgs::msg::Header) {
+    header.stamp = builtin_interfaces::msg::Time::from_node_clock(node);
+}
+
+/// # Panics
+///
+/// If the resulting time is out of the range of the 32 bit `sec` field.
+impl Add<builtin_interfaces::msg::Duration> for builtin_interfaces::msg::Time {
+    type Output = builtin_interfaces::msg::Time;
+
+    fn add(self, rhs: builtin_interfaces::msg::Duration) -> Self::Output {
+        let nanos = msg_nanos(self.sec, self.nanosec) + msg_nanos(rhs.sec, rhs.nanosec);
+        let (sec, nanosec) =
+            nanos_to_sec_nanosec(nanos).expect("overflow when adding duration to time");
+        builtin_interfaces::msg::Time { sec, nanosec }
+    }
+}
+
+/// The duration from `rhs` to `self`, negative if `rhs` is later.
+///
+/// # Panics
+///
+/// If the duration is out of the range of the 32 bit `sec` field.
+impl Sub<builtin_interfaces::msg::Time> for builtin_interfaces::msg::Time {
+    type Output = builtin_interfaces::msg::Duration;
+
+    fn sub(self, rhs: builtin_interfaces::msg::Time) -> Self::Output {
+        let nanos = msg_nanos(self.sec, self.nanosec) - msg_nanos(rhs.sec, rhs.nanosec);
+        let (sec, nanosec) = nanos_to_sec_nanosec(nanos).expect("overflow when subtracting times");
+        builtin_interfaces::msg::Duration { sec, nanosec }
+    }
+}
+
+// the order is by `sec` and then `nanosec`, consistent with the derived
+// `PartialEq`, which is chronological as long as `nanosec` is below a second.
+impl Eq for builtin_interfaces::msg::Time {}
+
+impl PartialOrd for builtin_interfaces::msg::Time {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for builtin_interfaces::msg::Time {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.sec, self.nanosec).cmp(&(other.sec, other.nanosec))
+    }
+}
+
+impl Eq for builtin_interfaces::msg::Duration {}
+
+impl PartialOrd for builtin_interfaces::msg::Duration {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for builtin_interfaces::msg::Duration {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.sec, self.nanosec).cmp(&(other.sec, other.nanosec))
+    }
+}
+
 impl Drop for Clock {
     fn drop(&mut self) {
         unsafe {
@@ -161,3 +392,167 @@ impl Drop for Clock {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use builtin_interfaces::msg::{Duration as DurationMsg, Time};
+
+    #[test]
+    fn test_time_round_trip() {
+        let times = [
+            Time { sec: 0, nanosec: 0 },
+            Time { sec: 0, nanosec: 1 },
+            Time {
+                sec: 1_700_000_000,
+                nanosec: 123_456_789,
+            },
+            Time {
+                sec: i32::MAX,
+                nanosec: 999_999_999,
+            },
+            // pre-epoch times, 1.5 and 0.000000001 seconds before.
+            Time {
+                sec: -2,
+                nanosec: 500_000_000,
+            },
+            Time {
+                sec: -1,
+                nanosec: 999_999_999,
+            },
+            Time {
+                sec: i32::MIN,
+                nanosec: 0,
+            },
+        ];
+        for time in times {
+            let system_time = SystemTime::from(time.clone());
+            assert_eq!(Time::try_from(system_time).unwrap(), time);
+        }
+
+        let system_time = SystemTime::from(Time {
+            sec: -2,
+            nanosec: 500_000_000,
+        });
+        assert_eq!(UNIX_EPOCH.duration_since(system_time).unwrap(), Duration::from_millis(1500));
+
+        let system_time = UNIX_EPOCH + Duration::new(1_700_000_000, 1);
+        let time = Time::try_from(system_time).unwrap();
+        assert_eq!((time.sec, time.nanosec), (1_700_000_000, 1));
+    }
+
+    #[test]
+    fn test_time_out_of_range() {
+        // one nanosecond after the last time a 32 bit second count can hold.
+        let after_2038 = UNIX_EPOCH + Duration::new(i32::MAX as u64 + 1, 0);
+        assert!(matches!(Time::try_from(after_2038), Err(Error::TimeOutOfRange)));
+        let before_1901 = UNIX_EPOCH - Duration::new(i32::MIN.unsigned_abs() as u64, 1);
+        assert!(matches!(Time::try_from(before_1901), Err(Error::TimeOutOfRange)));
+
+        // nanoseconds above one second are carried over.
+        let time = Time {
+            sec: 1,
+            nanosec: 1_500_000_000,
+        };
+        assert_eq!(
+            SystemTime::from(time).duration_since(UNIX_EPOCH).unwrap(),
+            Duration::from_millis(2500)
+        );
+    }
+
+    #[test]
+    fn test_duration_round_trip() {
+        for duration in [
+            Duration::ZERO,
+            Duration::from_nanos(1),
+            Duration::new(3, 141_592_653),
+            Duration::new(i32::MAX as u64, 999_999_999),
+        ] {
+            let msg = DurationMsg::try_from(duration).unwrap();
+            assert_eq!(Duration::from(msg), duration);
+        }
+        assert!(matches!(
+            DurationMsg::try_from(Duration::from_secs(i32::MAX as u64 + 1)),
+            Err(Error::TimeOutOfRange)
+        ));
+
+        // negative durations are clamped.
+        let negative = DurationMsg {
+            sec: -1,
+            nanosec: 500_000_000,
+        };
+        assert_eq!(Duration::from(negative), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_duration_from_nanos() {
+        assert_eq!(
+            DurationMsg::from_nanos(1_500_000_001),
+            DurationMsg {
+                sec: 1,
+                nanosec: 500_000_001
+            }
+        );
+        assert_eq!(
+            DurationMsg::from_nanos(-1),
+            DurationMsg {
+                sec: -1,
+                nanosec: 999_999_999
+            }
+        );
+    }
+
+    #[test]
+    fn test_time_arithmetic() {
+        // the nanoseconds carry over into the seconds.
+        let time = Time {
+            sec: 0,
+            nanosec: 999_999_999,
+        } + DurationMsg { sec: 0, nanosec: 1 };
+        assert_eq!(time, Time { sec: 1, nanosec: 0 });
+
+        let earlier = Time::from_seconds(10.25);
+        let later = Time::from_seconds(12.0);
+        assert_eq!(
+            later.clone() - earlier.clone(),
+            DurationMsg {
+                sec: 1,
+                nanosec: 750_000_000
+            }
+        );
+        // -1.75 seconds.
+        assert_eq!(
+            earlier.clone() - later.clone(),
+            DurationMsg {
+                sec: -2,
+                nanosec: 250_000_000
+            }
+        );
+        assert_eq!(earlier.clone() + (later.clone() - earlier.clone()), later);
+        assert_eq!(DurationMsg::from_seconds(-1.75), earlier.clone() - later.clone());
+        assert!(DurationMsg::from_seconds(-1.75) < DurationMsg::from_seconds(0.5));
+
+        let mut times = vec![
+            later.clone(),
+            Time::from_seconds(-0.5),
+            earlier.clone(),
+            Time::from_seconds(10.000_000_001),
+        ];
+        times.sort();
+        assert_eq!(
+            times,
+            vec![
+                Time {
+                    sec: -1,
+                    nanosec: 500_000_000
+                },
+                Time {
+                    sec: 10,
+                    nanosec: 1
+                },
+                earlier,
+                later
+            ]
+        );
+    }
+}
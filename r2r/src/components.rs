@@ -0,0 +1,274 @@
+#![cfg(r2r__composition_interfaces__srv__LoadNode)]
+
+//! Loading nodes into a shared process at runtime.
+//!
+//! A [`ComponentContainer`] serves the same `_container` services as the
+//! rclcpp component containers, so components registered in the
+//! [`ComponentRegistry`] can be loaded with e.g. `ros2 component load`.
+
+use futures::{future::FutureExt, stream::StreamExt};
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
+use crate::{
+    context::Context,
+    error::*,
+    msg_types::generated_msgs::composition_interfaces,
+    nodes::{Node, NodeOptions},
+    parameters::ParameterValue,
+    qos::QosProfile,
+    services::ServiceRequest,
+    wait_set::spin_many,
+};
+
+/// A node implementation which can be loaded into a [`ComponentContainer`].
+pub trait Component: Send + 'static {
+    /// Create the component.
+    ///
+    /// The name, namespace, remappings and parameters given when loading
+    /// the component are passed as arguments in `options`, so the node
+    /// should be created with [`Node::create_with_options`].
+    fn create(ctx: Context, options: NodeOptions) -> Result<Box<dyn Component>>
+    where
+        Self: Sized;
+
+    /// The node of the component.
+    fn node(&mut self) -> &mut Node;
+
+    /// Called by the container after the nodes were spun, for work of the
+    /// component besides handling its subscriptions, services and timers.
+    fn after_spin(&mut self) {}
+
+    /// Spin the node of the component and call [`Component::after_spin`],
+    /// for running the component on its own. The container does not call
+    /// this, as it spins the nodes itself.
+    fn spin_once(&mut self, timeout: Duration) {
+        self.node().spin_once(timeout);
+        self.after_spin();
+    }
+}
+
+type ComponentFactory = fn(Context, NodeOptions) -> Result<Box<dyn Component>>;
+
+fn registry() -> &'static Mutex<HashMap<String, ComponentFactory>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<String, ComponentFactory>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// The components of the process, by the plugin name used to load them.
+pub struct ComponentRegistry;
+
+impl ComponentRegistry {
+    /// Register a component under `type_name`, e.g. `"my_package::Talker"`.
+    ///
+    /// A component registered earlier under the same name is replaced.
+    pub fn register<C: Component>(type_name: &str) {
+        registry()
+            .lock()
+            .unwrap()
+            .insert(type_name.to_owned(), C::create);
+    }
+
+    /// The names of the registered components.
+    pub fn type_names() -> Vec<String> {
+        let mut names = registry()
+            .lock()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    fn get(type_name: &str) -> Option<ComponentFactory> {
+        registry().lock().unwrap().get(type_name).copied()
+    }
+}
+
+type Requests<T> = Pin<Box<dyn futures::Stream<Item = ServiceRequest<T>> + Send>>;
+
+/// Runs the components loaded with the `_container` services of its node.
+///
+/// ``` rust,ignore
+/// ComponentRegistry::register::<Talker>("my_package::Talker");
+/// let mut container = ComponentContainer::new(ctx)?;
+/// loop {
+///     container.spin_once(std::time::Duration::from_millis(100));
+/// }
+/// ```
+pub struct ComponentContainer {
+    context: Context,
+    node: Node,
+    components: Vec<(u64, String, Box<dyn Component>)>,
+    next_id: u64,
+    load_requests: Requests<composition_interfaces::srv::LoadNode::Service>,
+    unload_requests: Requests<composition_interfaces::srv::UnloadNode::Service>,
+    list_requests: Requests<composition_interfaces::srv::ListNodes::Service>,
+}
+
+impl ComponentContainer {
+    /// Create a container with a node named `ComponentManager`, like rclcpp.
+    pub fn new(ctx: Context) -> Result<ComponentContainer> {
+        let node = Node::create(ctx, "ComponentManager", "")?;
+        Self::from_node(node)
+    }
+
+    /// Create a container serving the `_container` services on `node`.
+    pub fn from_node(mut node: Node) -> Result<ComponentContainer> {
+        let load_requests = node.create_service::<composition_interfaces::srv::LoadNode::Service>(
+            "~/_container/load_node",
+            QosProfile::services_default(),
+        )?;
+        let unload_requests = node
+            .create_service::<composition_interfaces::srv::UnloadNode::Service>(
+                "~/_container/unload_node",
+                QosProfile::services_default(),
+            )?;
+        let list_requests = node
+            .create_service::<composition_interfaces::srv::ListNodes::Service>(
+                "~/_container/list_nodes",
+                QosProfile::services_default(),
+            )?;
+        Ok(ComponentContainer {
+            context: node.context.clone(),
+            node,
+            components: Vec::new(),
+            next_id: 1,
+            load_requests: Box::pin(load_requests),
+            unload_requests: Box::pin(unload_requests),
+            list_requests: Box::pin(list_requests),
+        })
+    }
+
+    /// The node of the container.
+    pub fn node(&mut self) -> &mut Node {
+        &mut self.node
+    }
+
+    /// The unique ids and fully qualified node names of the loaded components.
+    pub fn components(&self) -> Vec<(u64, String)> {
+        self.components
+            .iter()
+            .map(|(id, name, _)| (*id, name.clone()))
+            .collect()
+    }
+
+    /// Spin the container and all loaded components.
+    ///
+    /// The nodes are waited for in a single `rcl_wait`, see [`spin_many`].
+    /// Then load and unload requests are handled, and each component gets
+    /// [`Component::after_spin`] called.
+    pub fn spin_once(&mut self, timeout: Duration) {
+        let mut nodes = vec![&mut self.node];
+        nodes.extend(self.components.iter_mut().map(|(_, _, c)| c.node()));
+        if let Err(e) = spin_many(nodes, timeout) {
+            log::error!("could not spin the component container: {}", e);
+        }
+
+        while let Some(Some(req)) = self.load_requests.next().now_or_never() {
+            let response = self.load(&req.message);
+            if let Err(e) = req.respond(response) {
+                log::debug!("could not send load_node response: {}", e);
+            }
+        }
+        while let Some(Some(req)) = self.unload_requests.next().now_or_never() {
+            let response = self.unload(req.message.unique_id);
+            if let Err(e) = req.respond(response) {
+                log::debug!("could not send unload_node response: {}", e);
+            }
+        }
+        while let Some(Some(req)) = self.list_requests.next().now_or_never() {
+            let (unique_ids, full_node_names) = self.components().into_iter().unzip();
+            let response = composition_interfaces::srv::ListNodes::Response {
+                unique_ids,
+                full_node_names,
+            };
+            if let Err(e) = req.respond(response) {
+                log::debug!("could not send list_nodes response: {}", e);
+            }
+        }
+
+        for (_, _, component) in &mut self.components {
+            component.after_spin();
+        }
+    }
+
+    fn load(
+        &mut self, req: &composition_interfaces::srv::LoadNode::Request,
+    ) -> composition_interfaces::srv::LoadNode::Response {
+        let mut response = composition_interfaces::srv::LoadNode::Response::default();
+        let Some(factory) = ComponentRegistry::get(&req.plugin_name) else {
+            response.error_message = format!("Component {} is not registered", req.plugin_name);
+            return response;
+        };
+
+        let created = load_options(req).and_then(|options| {
+            let mut component = factory(self.context.clone(), options)?;
+            let name = component.node().fully_qualified_name()?;
+            Ok((component, name))
+        });
+        match created {
+            Ok((component, name)) => {
+                let id = self.next_id;
+                self.next_id += 1;
+                self.components.push((id, name.clone(), component));
+                response.success = true;
+                response.full_node_name = name;
+                response.unique_id = id;
+            }
+            Err(e) => {
+                response.error_message = format!("Could not create {}: {}", req.plugin_name, e);
+            }
+        }
+        response
+    }
+
+    fn unload(&mut self, unique_id: u64) -> composition_interfaces::srv::UnloadNode::Response {
+        let mut response = composition_interfaces::srv::UnloadNode::Response::default();
+        match self
+            .components
+            .iter()
+            .position(|(id, _, _)| *id == unique_id)
+        {
+            Some(i) => {
+                self.components.remove(i);
+                response.success = true;
+            }
+            None => {
+                response.error_message = format!("No node found with unique_id: {}", unique_id);
+            }
+        }
+        response
+    }
+}
+
+// the options of a component node, with the name, namespace, remappings
+// and parameters of the request as node specific arguments.
+fn load_options(req: &composition_interfaces::srv::LoadNode::Request) -> Result<NodeOptions> {
+    let mut arguments = vec!["--ros-args".to_owned()];
+    if !req.node_name.is_empty() {
+        arguments.extend(["-r".to_owned(), format!("__node:={}", req.node_name)]);
+    }
+    if !req.node_namespace.is_empty() {
+        arguments.extend(["-r".to_owned(), format!("__ns:={}", req.node_namespace)]);
+    }
+    for rule in &req.remap_rules {
+        arguments.extend(["-r".to_owned(), rule.clone()]);
+    }
+    for p in &req.parameters {
+        let value = ParameterValue::from_parameter_value_msg(p.value.clone());
+        arguments.extend(["-p".to_owned(), format!("{}:={}", p.name, value.to_yaml())]);
+    }
+
+    // the extra arguments, like use_intra_process_comms, have no r2r equivalent.
+    Ok(NodeOptions {
+        use_global_arguments: false,
+        arguments,
+        ..NodeOptions::default()
+    })
+}
@@ -1,8 +1,12 @@
 use std::{
+    collections::HashSet,
     ffi::{CStr, CString},
     fmt::Debug,
     ops::{Deref, DerefMut},
-    sync::{Arc, Mutex, OnceLock},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, OnceLock,
+    },
 };
 
 use crate::{error::*, log_guard};
@@ -12,6 +16,27 @@ use r2r_rcl::*;
 #[derive(Debug, Clone)]
 pub struct Context {
     pub(crate) context_handle: Arc<Mutex<ContextHandle>>,
+    shutdown_callbacks: Arc<Mutex<ShutdownCallbacks>>,
+    // fully qualified names of the nodes created with the context
+    node_names: Arc<Mutex<HashSet<String>>>,
+}
+
+/// Identifies a callback registered with [`Context::on_shutdown`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ShutdownToken(u64);
+
+#[derive(Default)]
+struct ShutdownCallbacks {
+    next_id: u64,
+    callbacks: Vec<(u64, Box<dyn Fn() + Send>)>,
+}
+
+impl Debug for ShutdownCallbacks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ShutdownCallbacks")
+            .field("len", &self.callbacks.len())
+            .finish()
+    }
 }
 
 macro_rules! check_rcl_ret {
@@ -41,55 +66,82 @@ unsafe impl Sync for Context {}
 
 static CONTEXT: OnceLock<Result<Context>> = OnceLock::new();
 
+// Logging is process wide, so it is configured by the first context only, like in rclcpp.
+static LOGGING_CONFIGURED: OnceLock<bool> = OnceLock::new();
+
+// set once rcl_init succeeded for any context.
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+
 impl Context {
     /// Create a ROS context.
+    ///
+    /// The context is initialized with the arguments of the process and shared
+    /// by all callers.
     pub fn create() -> Result<Context> {
-        CONTEXT
-            .get_or_init(|| {
-                let mut ctx: Box<rcl_context_t> =
-                    unsafe { Box::new(rcl_get_zero_initialized_context()) };
-                // argc/v
-                let args = std::env::args()
-                    .map(|arg| CString::new(arg).unwrap())
-                    .collect::<Vec<CString>>();
-                let mut c_args = args
-                    .iter()
-                    .map(|arg| arg.as_ptr())
-                    .collect::<Vec<*const ::std::os::raw::c_char>>();
-                c_args.push(std::ptr::null());
-
-                let is_valid = unsafe {
-                    let allocator = rcutils_get_default_allocator();
-                    let mut init_options = rcl_get_zero_initialized_init_options();
-                    check_rcl_ret!(rcl_init_options_init(&mut init_options, allocator));
-                    check_rcl_ret!(rcl_init(
-                        (c_args.len() - 1) as ::std::os::raw::c_int,
-                        c_args.as_ptr(),
-                        &init_options,
-                        ctx.as_mut(),
-                    ));
-                    check_rcl_ret!(rcl_init_options_fini(&mut init_options as *mut _));
-                    rcl_context_is_valid(ctx.as_mut())
-                };
-
-                let logging_ok = unsafe {
-                    let _guard = log_guard();
-                    let ret = rcl_logging_configure(
-                        &ctx.as_ref().global_arguments,
-                        &rcutils_get_default_allocator(),
-                    );
-                    ret == RCL_RET_OK as i32
-                };
-
-                if is_valid && logging_ok {
-                    Ok(Context {
-                        context_handle: Arc::new(Mutex::new(ContextHandle(ctx))),
-                    })
-                } else {
-                    Err(Error::RCL_RET_ERROR) // TODO
-                }
+        CONTEXT.get_or_init(|| Self::init(std::env::args())).clone()
+    }
+
+    /// Create a new ROS context initialized with `args` instead of the arguments
+    /// of the process.
+    ///
+    /// Useful for testing nodes in-process with specific parameters and
+    /// remappings, e.g. `vec!["--ros-args".into(), "-p".into(), "key:=42".into()]`.
+    /// Unlike [`Context::create`], each call creates a separate context. Since
+    /// logging is process wide, logging arguments only take effect for the first
+    /// context created.
+    pub fn create_with_args(args: Vec<String>) -> Result<Context> {
+        Self::init(args)
+    }
+
+    fn init(args: impl IntoIterator<Item = String>) -> Result<Context> {
+        let mut ctx: Box<rcl_context_t> = unsafe { Box::new(rcl_get_zero_initialized_context()) };
+        // argc/v
+        let args = args
+            .into_iter()
+            .map(|arg| CString::new(arg).map_err(|_| Error::RCL_RET_INVALID_ARGUMENT))
+            .collect::<Result<Vec<CString>>>()?;
+        let mut c_args = args
+            .iter()
+            .map(|arg| arg.as_ptr())
+            .collect::<Vec<*const ::std::os::raw::c_char>>();
+        c_args.push(std::ptr::null());
+
+        let is_valid = unsafe {
+            let allocator = rcutils_get_default_allocator();
+            let mut init_options = rcl_get_zero_initialized_init_options();
+            check_rcl_ret!(rcl_init_options_init(&mut init_options, allocator));
+            check_rcl_ret!(rcl_init(
+                (c_args.len() - 1) as ::std::os::raw::c_int,
+                c_args.as_ptr(),
+                &init_options,
+                ctx.as_mut(),
+            ));
+            check_rcl_ret!(rcl_init_options_fini(&mut init_options as *mut _));
+            rcl_context_is_valid(ctx.as_mut())
+        };
+
+        let logging_ok = *LOGGING_CONFIGURED.get_or_init(|| unsafe {
+            let _guard = log_guard();
+            let ret = rcl_logging_configure(
+                &ctx.as_ref().global_arguments,
+                &rcutils_get_default_allocator(),
+            );
+            ret == RCL_RET_OK as i32
+        });
+
+        if is_valid && logging_ok {
+            INITIALIZED.store(true, Ordering::Relaxed);
+            Ok(Context {
+                context_handle: Arc::new(Mutex::new(ContextHandle {
+                    handle: ctx,
+                    shut_down: false,
+                })),
+                shutdown_callbacks: Arc::new(Mutex::new(ShutdownCallbacks::default())),
+                node_names: Arc::new(Mutex::new(HashSet::new())),
             })
-            .clone()
+        } else {
+            Err(Error::RCL_RET_ERROR) // TODO
+        }
     }
 
     /// Check if the ROS context is valid.
@@ -99,22 +151,115 @@ impl Context {
         let mut ctx = self.context_handle.lock().unwrap();
         unsafe { rcl_context_is_valid(ctx.as_mut()) }
     }
+
+    /// Reserve the fully qualified name of a new node, returns false if a node
+    /// of the context already has the name.
+    pub(crate) fn add_node_name(&self, name: &str) -> bool {
+        self.node_names.lock().unwrap().insert(name.to_owned())
+    }
+
+    pub(crate) fn remove_node_name(&self, name: &str) {
+        self.node_names.lock().unwrap().remove(name);
+    }
+
+    /// Register a callback to call when the context is shut down.
+    ///
+    /// The callbacks are called by [`Context::shutdown`], in the order they
+    /// were registered. Callbacks registered after the shutdown are never called.
+    pub fn on_shutdown(&self, f: impl Fn() + Send + 'static) -> ShutdownToken {
+        let mut shutdown_callbacks = self.shutdown_callbacks.lock().unwrap();
+        let id = shutdown_callbacks.next_id;
+        shutdown_callbacks.next_id += 1;
+        shutdown_callbacks.callbacks.push((id, Box::new(f)));
+        ShutdownToken(id)
+    }
+
+    /// Remove a callback registered with [`Context::on_shutdown`].
+    ///
+    /// Returns false if the callback was already removed or called.
+    pub fn remove_on_shutdown(&self, token: ShutdownToken) -> bool {
+        let mut shutdown_callbacks = self.shutdown_callbacks.lock().unwrap();
+        let len = shutdown_callbacks.callbacks.len();
+        shutdown_callbacks
+            .callbacks
+            .retain(|(id, _)| *id != token.0);
+        shutdown_callbacks.callbacks.len() != len
+    }
+
+    /// Shut down the context.
+    ///
+    /// Afterwards the context and all nodes created with it are no longer valid, see
+    /// [`Context::is_valid`]. The shutdown callbacks are called before this returns.
+    /// The shutdown is only attempted once: if rcl fails to shut down the error is
+    /// returned, and shutting down again, or dropping the context, does nothing.
+    pub fn shutdown(&self) -> Result<()> {
+        let ret = {
+            let mut ctx = self.context_handle.lock().unwrap();
+            if ctx.shut_down || !unsafe { rcl_context_is_valid(ctx.as_mut()) } {
+                return Ok(());
+            }
+            ctx.shut_down = true;
+            unsafe { rcl_shutdown(ctx.as_mut()) }
+        };
+
+        // take the callbacks, so that they can use the context themselves.
+        let callbacks = std::mem::take(&mut self.shutdown_callbacks.lock().unwrap().callbacks);
+        for (_, cb) in callbacks {
+            cb();
+        }
+        if ret == RCL_RET_OK as i32 {
+            Ok(())
+        } else {
+            Err(Error::from_rcl_error(ret))
+        }
+    }
+}
+
+/// Shut down the ROS context, if one was created.
+///
+/// See [`Context::shutdown`].
+pub fn try_shutdown() -> Result<()> {
+    match CONTEXT.get() {
+        Some(Ok(ctx)) => ctx.shutdown(),
+        _ => Ok(()),
+    }
+}
+
+/// Create the shared ROS context only if `enable` is true.
+///
+/// For applications where ROS is optional: with `enable` false, `None` is
+/// returned without initializing rcl or the middleware. Otherwise this is
+/// [`Context::create`].
+pub fn init_maybe(enable: bool) -> Result<Option<Context>> {
+    if !enable {
+        return Ok(None);
+    }
+    Context::create().map(Some)
+}
+
+/// Whether a ROS context was created in this process, i.e. rcl is initialized.
+pub fn is_initialized() -> bool {
+    INITIALIZED.load(Ordering::Relaxed)
 }
 
 #[derive(Debug)]
-pub struct ContextHandle(Box<rcl_context_t>);
+pub struct ContextHandle {
+    handle: Box<rcl_context_t>,
+    // set once rcl_shutdown was called, successfully or not.
+    shut_down: bool,
+}
 
 impl Deref for ContextHandle {
     type Target = Box<rcl_context_t>;
 
     fn deref(&self) -> &Box<rcl_context_t> {
-        &self.0
+        &self.handle
     }
 }
 
 impl DerefMut for ContextHandle {
     fn deref_mut(&mut self) -> &mut Box<rcl_context_t> {
-        &mut self.0
+        &mut self.handle
     }
 }
 
@@ -122,8 +267,10 @@ impl Drop for ContextHandle {
     fn drop(&mut self) {
         // TODO: error handling? atleast probably need rcl_reset_error
         unsafe {
-            rcl_shutdown(self.0.as_mut());
-            rcl_context_fini(self.0.as_mut());
+            if !self.shut_down {
+                rcl_shutdown(self.handle.as_mut());
+            }
+            rcl_context_fini(self.handle.as_mut());
         }
     }
 }
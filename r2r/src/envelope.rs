@@ -0,0 +1,118 @@
+#![cfg(r2r__std_msgs__msg__Header)]
+
+use std::{marker::PhantomData, sync::OnceLock};
+
+use crate::{
+    builtin_interfaces::msg::Time, error::*, introspection::FieldType, msg_types::*,
+    std_msgs::msg::Header,
+};
+use r2r_rcl::rosidl_message_type_support_t;
+
+/// A received message that is not deserialized yet.
+///
+/// See [`Node::create_envelope_subscription`](crate::Node::create_envelope_subscription).
+/// The header can be read without deserializing the whole message, e.g. to
+/// only deserialize messages within some time range.
+pub struct MessageEnvelope<T> {
+    data: Vec<u8>,
+    has_header: bool,
+    header: OnceLock<Option<Header>>,
+    type_: PhantomData<T>,
+}
+
+impl<T> MessageEnvelope<T>
+where
+    T: WrappedTypesupport,
+{
+    pub(crate) fn new(data: Vec<u8>, has_header: bool) -> Self {
+        MessageEnvelope {
+            data,
+            has_header,
+            header: OnceLock::new(),
+            type_: PhantomData,
+        }
+    }
+
+    /// The serialized message.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// The header of the message.
+    ///
+    /// Returns `None` if the first field of the message type is not a
+    /// `std_msgs/msg/Header`. Only the bytes of the header are read, the
+    /// first time this is called.
+    pub fn header(&self) -> Option<&Header> {
+        if !self.has_header {
+            return None;
+        }
+        self.header
+            .get_or_init(|| parse_cdr_header(&self.data))
+            .as_ref()
+    }
+
+    /// Deserialize the whole message.
+    pub fn deserialize(self) -> Result<T> {
+        T::from_serialized_bytes(&self.data)
+    }
+}
+
+/// Returns true if the first field of the message type is a header.
+pub(crate) fn starts_with_header(ts: &rosidl_message_type_support_t) -> bool {
+    let Ok(descriptor) = crate::introspection::MessageDescriptor::for_typesupport(ts) else {
+        return false;
+    };
+    descriptor.fields.first().is_some_and(|f| {
+        f.array.is_none()
+            && matches!(&f.field_type, FieldType::Message(m) if m.type_name == "std_msgs/msg/Header")
+    })
+}
+
+// A header is at the start of the message, as `int32 sec`, `uint32 nanosec`
+// and `string frame_id`, after the 4 byte encapsulation header of the CDR data.
+fn parse_cdr_header(data: &[u8]) -> Option<Header> {
+    let little_endian = match data.get(..4)? {
+        [0, 0, _, _] => false,
+        [0, 1, _, _] => true,
+        _ => return None,
+    };
+    let body = &data[4..];
+    let u32_at = |offset: usize| {
+        let bytes: [u8; 4] = body.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    };
+    let sec = u32_at(0)? as i32;
+    let nanosec = u32_at(4)?;
+    // the length of the string includes the terminating null.
+    let len = u32_at(8)? as usize;
+    let frame_id = body.get(12..12 + len)?;
+    let frame_id = frame_id.strip_suffix(&[0]).unwrap_or(frame_id);
+    Some(Header {
+        stamp: Time { sec, nanosec },
+        frame_id: String::from_utf8_lossy(frame_id).into_owned(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cdr_header() {
+        let header = Header {
+            stamp: Time {
+                sec: 12,
+                nanosec: 345,
+            },
+            frame_id: "map".into(),
+        };
+        let data = header.to_serialized_bytes().unwrap();
+        assert_eq!(parse_cdr_header(&data), Some(header));
+        assert_eq!(parse_cdr_header(&data[..10]), None);
+    }
+}
@@ -1,6 +1,7 @@
 #![allow(non_camel_case_types)]
 use r2r_actions::*;
 use r2r_rcl::*;
+use std::ffi::CStr;
 use thiserror::Error;
 
 /// r2r Result type.
@@ -45,6 +46,8 @@ pub enum Error {
     RCL_RET_NODE_INVALID_NAME,
     #[error("RCL_RET_NODE_INVALID_NAMESPACE")]
     RCL_RET_NODE_INVALID_NAMESPACE,
+    #[error("RCL_RET_NODE_NAME_NON_EXISTENT")]
+    RCL_RET_NODE_NAME_NON_EXISTENT,
     #[error("RCL_RET_PUBLISHER_INVALID")]
     RCL_RET_PUBLISHER_INVALID,
     #[error("RCL_RET_SUBSCRIPTION_INVALID")]
@@ -73,6 +76,8 @@ pub enum Error {
     RCL_RET_INVALID_REMAP_RULE,
     #[error("RCL_RET_WRONG_LEXEME")]
     RCL_RET_WRONG_LEXEME,
+    #[error("RCL_RET_INVALID_ROS_ARGS")]
+    RCL_RET_INVALID_ROS_ARGS,
     #[error("RCL_RET_INVALID_PARAM_RULE")]
     RCL_RET_INVALID_PARAM_RULE,
     #[error("RCL_RET_INVALID_LOG_LEVEL_RULE")]
@@ -119,6 +124,9 @@ pub enum Error {
     #[error("Goal already in a terminal state.")]
     GoalCancelAlreadyTerminated,
 
+    #[error("Goal canceled or preempted.")]
+    ActionPreempted,
+
     #[error("Invalid parameter name: {name}")]
     InvalidParameterName { name: String },
 
@@ -136,6 +144,105 @@ pub enum Error {
         expected_type: &'static str,
         actual_type: &'static str,
     },
+
+    #[error("Parameter {name} rejected: {reason}")]
+    ParameterRejected { name: String, reason: String },
+
+    #[error("Parameter file {path}: {msg}")]
+    ParameterFile { path: String, msg: String },
+
+    #[error("Bag file {path}: {msg}")]
+    BagFile { path: String, msg: String },
+
+    #[error("Invalid topic name {name}: {reason}")]
+    InvalidTopicName { name: String, reason: String },
+
+    #[error("Invalid service name {name}: {reason}")]
+    InvalidServiceName { name: String, reason: String },
+
+    #[error("Invalid node name {name}: {reason}")]
+    InvalidNodeName { name: String, reason: String },
+
+    #[error("Invalid namespace {namespace}: {reason}")]
+    InvalidNamespace { namespace: String, reason: String },
+
+    /// A node of the same context already has the name, see [`crate::Node::create`].
+    #[error("A node named {name} already exists in namespace {namespace}")]
+    NodeNameCollision { name: String, namespace: String },
+
+    #[error("Could not transform from {source_frame} to {target_frame}: {reason}")]
+    TransformLookup {
+        target_frame: String,
+        source_frame: String,
+        reason: String,
+    },
+
+    #[error("Lifecycle transition {transition} is not available from state {state}")]
+    InvalidLifecycleTransition { transition: u8, state: String },
+
+    #[error("Lifecycle transition {transition} failed, the node is {state}")]
+    LifecycleTransitionFailed { transition: String, state: String },
+
+    #[error("Topic {topic} has type {existing}, not {requested}")]
+    TypeMismatch {
+        topic: String,
+        existing: String,
+        requested: String,
+    },
+
+    #[error("Feature not supported by the middleware: {0}")]
+    UnsupportedFeature(&'static str),
+
+    #[error("Timed out")]
+    Timeout,
+
+    /// The service server is gone, see [`ServiceError::ServerUnavailable`].
+    #[error("Service server unavailable")]
+    ServiceUnavailable,
+
+    #[error("Time out of range of builtin_interfaces")]
+    TimeOutOfRange,
+
+    #[error("Invalid field path '{}': {}", path, reason)]
+    InvalidFieldPath { path: String, reason: String },
+
+    #[error("Invalid goal status: {}", status)]
+    InvalidGoalStatus { status: i8 },
+
+    /// A failed call to an rcl function, see [`Error::from_rcl_call`].
+    #[error("{function} failed with code {code}: {message}")]
+    RclCall {
+        function: &'static str,
+        code: i32,
+        message: String,
+        /// The error of the return code, e.g. [`Error::RCL_RET_INVALID_ROS_ARGS`].
+        #[source]
+        source: Box<Error>,
+    },
+}
+
+/// Error of a service call made with [`crate::Client::call_with_timeout`].
+#[derive(Error, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ServiceError {
+    /// No response was received within the timeout.
+    #[error("service call timed out")]
+    Timeout,
+    /// The service server is gone, so no response will be received.
+    #[error("service server unavailable")]
+    ServerUnavailable,
+    /// An rcl call failed with this return code.
+    #[error("service call failed with code {0}")]
+    RclError(i32),
+}
+
+impl From<ServiceError> for Error {
+    fn from(e: ServiceError) -> Self {
+        match e {
+            ServiceError::Timeout => Error::RCL_RET_TIMEOUT,
+            ServiceError::ServerUnavailable => Error::ServiceUnavailable,
+            ServiceError::RclError(code) => Error::from_rcl_error(code),
+        }
+    }
 }
 
 impl Error {
@@ -158,6 +265,7 @@ impl Error {
             _ if e == RCL_RET_NODE_INVALID => Error::RCL_RET_NODE_INVALID,
             _ if e == RCL_RET_NODE_INVALID_NAME => Error::RCL_RET_NODE_INVALID_NAME,
             _ if e == RCL_RET_NODE_INVALID_NAMESPACE => Error::RCL_RET_NODE_INVALID_NAMESPACE,
+            _ if e == RCL_RET_NODE_NAME_NON_EXISTENT => Error::RCL_RET_NODE_NAME_NON_EXISTENT,
             _ if e == RCL_RET_PUBLISHER_INVALID => Error::RCL_RET_PUBLISHER_INVALID,
             _ if e == RCL_RET_SUBSCRIPTION_INVALID => Error::RCL_RET_SUBSCRIPTION_INVALID,
             _ if e == RCL_RET_SUBSCRIPTION_TAKE_FAILED => Error::RCL_RET_SUBSCRIPTION_TAKE_FAILED,
@@ -172,6 +280,7 @@ impl Error {
             _ if e == RCL_RET_WAIT_SET_FULL => Error::RCL_RET_WAIT_SET_FULL,
             _ if e == RCL_RET_INVALID_REMAP_RULE => Error::RCL_RET_INVALID_REMAP_RULE,
             _ if e == RCL_RET_WRONG_LEXEME => Error::RCL_RET_WRONG_LEXEME,
+            _ if e == RCL_RET_INVALID_ROS_ARGS => Error::RCL_RET_INVALID_ROS_ARGS,
             _ if e == RCL_RET_INVALID_PARAM_RULE => Error::RCL_RET_INVALID_PARAM_RULE,
             _ if e == RCL_RET_INVALID_LOG_LEVEL_RULE => Error::RCL_RET_INVALID_LOG_LEVEL_RULE,
             _ if e == RCL_RET_EVENT_INVALID => Error::RCL_RET_EVENT_INVALID,
@@ -191,6 +300,41 @@ impl Error {
         }
     }
 
+    /// Create an error for a failed call of the rcl function `function`.
+    ///
+    /// Unlike [`Error::from_rcl_error`], the error message set by rcl is
+    /// included, and then reset.
+    pub fn from_rcl_call(function: &'static str, e: i32) -> Self {
+        let message = unsafe {
+            let message = if rcutils_error_is_set() {
+                let error = rcutils_get_error_string();
+                CStr::from_ptr(error.str_.as_ptr())
+                    .to_string_lossy()
+                    .into_owned()
+            } else {
+                String::new()
+            };
+            rcutils_reset_error();
+            message
+        };
+        Error::RclCall {
+            function,
+            code: e,
+            message,
+            source: Box::new(Error::from_rcl_error(e)),
+        }
+    }
+
+    /// The error of the rcl return code, which for [`Error::RclCall`] is its source.
+    ///
+    /// Useful to match on the return code regardless of how the error was created.
+    pub fn rcl_error(&self) -> &Error {
+        match self {
+            Error::RclCall { source, .. } => source,
+            e => e,
+        }
+    }
+
     /// Internal function used by code derived for the RosParams trait.
     pub fn update_param_name(self, param_name: &str) -> Error {
         match self {
@@ -0,0 +1,156 @@
+use futures::{
+    executor::{LocalPool, LocalSpawner},
+    future::Future,
+    task::LocalSpawnExt,
+};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Mutex,
+};
+
+use crate::{error::*, nodes::Node, spin::SPIN_PERIOD};
+
+// set by the SIGINT handler, the handler itself must not touch rcl.
+static SIGINT_RECEIVED: AtomicBool = AtomicBool::new(false);
+
+// the running event loops, the handler is installed while there are any.
+static ACTIVE_LOOPS: Mutex<ActiveLoops> = Mutex::new(ActiveLoops {
+    count: 0,
+    #[cfg(unix)]
+    previous: None,
+});
+
+struct ActiveLoops {
+    count: usize,
+    // the SIGINT action before the first loop started
+    #[cfg(unix)]
+    previous: Option<libc::sigaction>,
+}
+
+#[cfg(unix)]
+extern "C" fn on_sigint(_: libc::c_int) {
+    SIGINT_RECEIVED.store(true, Ordering::SeqCst);
+}
+
+// Counts a running event loop while alive. The first one installs the SIGINT
+// handler and the last one restores the previous action on drop.
+struct SigintGuard;
+
+impl SigintGuard {
+    fn install() -> Self {
+        let mut active = ACTIVE_LOOPS.lock().unwrap_or_else(|e| e.into_inner());
+        if active.count == 0 {
+            SIGINT_RECEIVED.store(false, Ordering::SeqCst);
+            #[cfg(unix)]
+            unsafe {
+                let mut action: libc::sigaction = std::mem::zeroed();
+                action.sa_sigaction = on_sigint as extern "C" fn(libc::c_int) as libc::sighandler_t;
+                libc::sigemptyset(&mut action.sa_mask);
+                let mut previous: libc::sigaction = std::mem::zeroed();
+                if libc::sigaction(libc::SIGINT, &action, &mut previous) == 0 {
+                    active.previous = Some(previous);
+                }
+            }
+        }
+        active.count += 1;
+        SigintGuard
+    }
+}
+
+impl Drop for SigintGuard {
+    fn drop(&mut self) {
+        let mut active = ACTIVE_LOOPS.lock().unwrap_or_else(|e| e.into_inner());
+        active.count -= 1;
+        #[cfg(unix)]
+        if active.count == 0 {
+            if let Some(previous) = active.previous.take() {
+                unsafe {
+                    libc::sigaction(libc::SIGINT, &previous, std::ptr::null_mut());
+                }
+            }
+        }
+    }
+}
+
+/// Runs a node together with the futures using it.
+///
+/// This replaces the manual `loop { node.spin_once(..); pool.run_until_stalled(); }`
+/// of most applications. Futures are added with [`EventLoop::spawn`] and
+/// driven on the thread calling [`EventLoop::run_until_shutdown`], so they do
+/// not need to be `Send`.
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let ctx = r2r::Context::create()?;
+/// let node = r2r::Node::create(ctx, "node", "")?;
+/// let mut event_loop = r2r::EventLoop::new(node);
+/// let publisher = event_loop
+///     .node_mut()
+///     .create_publisher::<r2r::std_msgs::msg::String>("/topic", r2r::QosProfile::default())?;
+/// event_loop.spawn(async move {
+///     let msg = r2r::std_msgs::msg::String { data: "hello".into() };
+///     publisher.publish(&msg).unwrap();
+/// });
+/// event_loop.run_until_shutdown()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct EventLoop {
+    node: Node,
+    pool: LocalPool,
+    spawner: LocalSpawner,
+}
+
+impl EventLoop {
+    /// Create an event loop running `node`.
+    pub fn new(node: Node) -> EventLoop {
+        let pool = LocalPool::new();
+        let spawner = pool.spawner();
+        EventLoop {
+            node,
+            pool,
+            spawner,
+        }
+    }
+
+    /// The node run by the event loop.
+    pub fn node(&self) -> &Node {
+        &self.node
+    }
+
+    /// The node run by the event loop, e.g. for creating publishers and
+    /// subscriptions before spawning the futures using them.
+    pub fn node_mut(&mut self) -> &mut Node {
+        &mut self.node
+    }
+
+    /// Add a future to be driven by [`EventLoop::run_until_shutdown`].
+    pub fn spawn(&self, fut: impl Future<Output = ()> + 'static) {
+        // the pool lives as long as the spawner, spawning cannot fail.
+        self.spawner
+            .spawn_local(fut)
+            .expect("event loop pool is alive");
+    }
+
+    /// Spin the node and run the spawned futures until the context is shut down.
+    ///
+    /// While running, SIGINT shuts down the context of the node, after which
+    /// this returns `Ok(())`. The SIGINT handler is installed while any event
+    /// loop runs, so it also stops loops running on other threads, and the
+    /// previous handler is restored when the last one returns. Futures that
+    /// did not complete stay in the event loop.
+    pub fn run_until_shutdown(&mut self) -> Result<()> {
+        let _sigint = SigintGuard::install();
+        // run what was spawned before the first wait.
+        self.pool.run_until_stalled();
+        while self.node.context.is_valid() {
+            if SIGINT_RECEIVED.load(Ordering::SeqCst) {
+                self.node.context.shutdown()?;
+                break;
+            }
+            self.node.spin_once(SPIN_PERIOD);
+            self.pool.run_until_stalled();
+        }
+        Ok(())
+    }
+}
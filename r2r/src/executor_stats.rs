@@ -0,0 +1,107 @@
+use std::{collections::HashMap, ffi::CStr, time::Duration};
+
+use r2r_rcl::*;
+
+/// Latency statistics of the callbacks run by `spin_once`.
+///
+/// See [`Node::enable_statistics`](crate::Node::enable_statistics).
+#[derive(Clone, Debug, Default)]
+pub struct ExecutorStats {
+    pub entries: Vec<CallbackStats>,
+    // position in `entries` of each instrumented entity
+    index: HashMap<usize, usize>,
+}
+
+/// Latency statistics of one callback.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CallbackStats {
+    /// The kind of callback and its topic or service, e.g. `"subscription /chatter"`.
+    pub name: String,
+    pub invocation_count: u64,
+    pub mean_latency_us: f64,
+    pub max_latency_us: f64,
+    pub total_latency_us: f64,
+}
+
+impl ExecutorStats {
+    /// Get the statistics of a callback by its name.
+    pub fn get(&self, name: &str) -> Option<&CallbackStats> {
+        self.entries.iter().find(|e| e.name == name)
+    }
+
+    /// Add a measurement for the entity identified by `key`.
+    ///
+    /// `name` is only called the first time the entity is seen.
+    pub(crate) fn record(&mut self, key: usize, latency: Duration, name: impl FnOnce() -> String) {
+        let i = *self.index.entry(key).or_insert_with(|| {
+            self.entries.push(CallbackStats {
+                name: name(),
+                invocation_count: 0,
+                mean_latency_us: 0.0,
+                max_latency_us: 0.0,
+                total_latency_us: 0.0,
+            });
+            self.entries.len() - 1
+        });
+        let entry = &mut self.entries[i];
+        let latency_us = latency.as_secs_f64() * 1e6;
+        entry.invocation_count += 1;
+        entry.total_latency_us += latency_us;
+        entry.max_latency_us = entry.max_latency_us.max(latency_us);
+        entry.mean_latency_us = entry.total_latency_us / entry.invocation_count as f64;
+    }
+
+    /// Stop tracking a removed entity, its statistics are kept.
+    pub(crate) fn forget(&mut self, key: usize) {
+        self.index.remove(&key);
+    }
+}
+
+pub(crate) fn subscription_name(handle: &rcl_subscription_t) -> String {
+    let topic = unsafe { rcl_subscription_get_topic_name(handle) };
+    format!("subscription {}", c_str_or_empty(topic))
+}
+
+pub(crate) fn service_name(handle: &rcl_service_t) -> String {
+    let service = unsafe { rcl_service_get_service_name(handle) };
+    format!("service {}", c_str_or_empty(service))
+}
+
+pub(crate) fn timer_name(handle: *const rcl_timer_t) -> String {
+    let mut period = 0;
+    unsafe { rcl_timer_get_period(handle, &mut period) };
+    format!("timer {:?}", Duration::from_nanos(period.max(0) as u64))
+}
+
+fn c_str_or_empty(s: *const std::os::raw::c_char) -> String {
+    if s.is_null() {
+        return String::new();
+    }
+    unsafe { CStr::from_ptr(s) }.to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let mut stats = ExecutorStats::default();
+        stats.record(1, Duration::from_micros(100), || "a".into());
+        stats.record(1, Duration::from_micros(300), || unreachable!());
+        stats.record(2, Duration::from_micros(50), || "b".into());
+
+        let a = stats.get("a").unwrap();
+        assert_eq!(a.invocation_count, 2);
+        assert!((a.mean_latency_us - 200.0).abs() < 1e-6);
+        assert!((a.max_latency_us - 300.0).abs() < 1e-6);
+        assert!((a.total_latency_us - 400.0).abs() < 1e-6);
+        assert_eq!(stats.get("b").unwrap().invocation_count, 1);
+
+        // a new entity with a reused key gets its own entry.
+        stats.forget(1);
+        stats.record(1, Duration::from_micros(10), || "c".into());
+        assert_eq!(stats.entries.len(), 3);
+        assert_eq!(stats.get("a").unwrap().invocation_count, 2);
+    }
+}
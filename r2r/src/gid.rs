@@ -0,0 +1,36 @@
+//! Globally unique identifiers (GIDs) of publishers and subscriptions.
+//!
+//! The middleware assigns each endpoint a GID, which is reported in
+//! [`TopicEndpointInfo`](crate::TopicEndpointInfo) and
+//! [`MessageInfo`](crate::MessageInfo), so endpoints seen in the graph can be
+//! matched with the messages they send.
+
+use std::fmt::Write;
+
+use r2r_rcl::RMW_GID_STORAGE_SIZE;
+
+/// The GID of an endpoint, as stored by rmw.
+pub type Gid = [u8; RMW_GID_STORAGE_SIZE as usize];
+
+/// Format a GID as lowercase hex, e.g. for logging.
+pub fn format(gid: &Gid) -> String {
+    gid.iter().fold(String::new(), |mut s, b| {
+        let _ = write!(s, "{:02x}", b);
+        s
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        let mut gid = [0; RMW_GID_STORAGE_SIZE as usize];
+        gid[0] = 0x01;
+        gid[1] = 0xab;
+        let s = format(&gid);
+        assert_eq!(s.len(), 2 * gid.len());
+        assert!(s.starts_with("01ab00"));
+    }
+}
@@ -0,0 +1,165 @@
+use futures::channel::{mpsc, oneshot};
+use std::{collections::BTreeSet, time::Instant};
+
+use crate::error::*;
+
+/// A change in the ROS graph.
+///
+/// See [`Node::subscribe_to_graph_events()`](crate::Node::subscribe_to_graph_events()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraphEvent {
+    PublisherAdded {
+        node_name: String,
+        namespace: String,
+        topic: String,
+        type_name: String,
+    },
+    PublisherRemoved {
+        node_name: String,
+        namespace: String,
+        topic: String,
+        type_name: String,
+    },
+    SubscriberAdded {
+        node_name: String,
+        namespace: String,
+        topic: String,
+        type_name: String,
+    },
+    SubscriberRemoved {
+        node_name: String,
+        namespace: String,
+        topic: String,
+        type_name: String,
+    },
+    ServiceAdded {
+        node_name: String,
+        namespace: String,
+        service: String,
+        type_name: String,
+    },
+    ServiceRemoved {
+        node_name: String,
+        namespace: String,
+        service: String,
+        type_name: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum EndpointKind {
+    Publisher,
+    Subscriber,
+    Service,
+}
+
+/// One publisher, subscriber or service of a node in the graph.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Endpoint {
+    pub kind: EndpointKind,
+    pub node_name: String,
+    pub namespace: String,
+    pub name: String,
+    pub type_name: String,
+}
+
+impl Endpoint {
+    fn into_event(self, added: bool) -> GraphEvent {
+        let Endpoint {
+            kind,
+            node_name,
+            namespace,
+            name,
+            type_name,
+        } = self;
+        match (kind, added) {
+            (EndpointKind::Publisher, true) => GraphEvent::PublisherAdded {
+                node_name,
+                namespace,
+                topic: name,
+                type_name,
+            },
+            (EndpointKind::Publisher, false) => GraphEvent::PublisherRemoved {
+                node_name,
+                namespace,
+                topic: name,
+                type_name,
+            },
+            (EndpointKind::Subscriber, true) => GraphEvent::SubscriberAdded {
+                node_name,
+                namespace,
+                topic: name,
+                type_name,
+            },
+            (EndpointKind::Subscriber, false) => GraphEvent::SubscriberRemoved {
+                node_name,
+                namespace,
+                topic: name,
+                type_name,
+            },
+            (EndpointKind::Service, true) => GraphEvent::ServiceAdded {
+                node_name,
+                namespace,
+                service: name,
+                type_name,
+            },
+            (EndpointKind::Service, false) => GraphEvent::ServiceRemoved {
+                node_name,
+                namespace,
+                service: name,
+                type_name,
+            },
+        }
+    }
+}
+
+pub(crate) type GraphSnapshot = BTreeSet<Endpoint>;
+
+pub(crate) struct GraphEventSubscriber {
+    snapshot: GraphSnapshot,
+    // unbounded, as the events are diffs and none of them may be dropped.
+    sender: mpsc::UnboundedSender<GraphEvent>,
+}
+
+impl GraphEventSubscriber {
+    pub(crate) fn new(snapshot: GraphSnapshot, sender: mpsc::UnboundedSender<GraphEvent>) -> Self {
+        GraphEventSubscriber { snapshot, sender }
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+
+    /// Send the differences to the new snapshot of the graph.
+    pub(crate) fn update(&mut self, snapshot: &GraphSnapshot) {
+        let removed = self
+            .snapshot
+            .difference(snapshot)
+            .map(|e| e.clone().into_event(false));
+        let added = snapshot
+            .difference(&self.snapshot)
+            .map(|e| e.clone().into_event(true));
+        for event in removed.chain(added) {
+            // ignore result, as user may have dropped the receiver.
+            let _ = self.sender.unbounded_send(event);
+        }
+        self.snapshot = snapshot.clone();
+    }
+}
+
+/// What a [`GraphWaiter`] waits for.
+pub(crate) enum GraphCondition {
+    /// The next change of the graph.
+    Change,
+    /// At least `min_count` publishers on the fully qualified `topic`.
+    Publishers { topic: String, min_count: usize },
+    /// At least `min_count` subscribers on the fully qualified `topic`.
+    Subscribers { topic: String, min_count: usize },
+}
+
+/// A future waiting for a condition on the graph, checked every time the node is spun.
+pub(crate) struct GraphWaiter {
+    pub condition: GraphCondition,
+    pub deadline: Option<Instant>,
+    pub sender: oneshot::Sender<Result<()>>,
+}
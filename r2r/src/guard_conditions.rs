@@ -0,0 +1,124 @@
+use futures::{channel::oneshot, task::ArcWake, Future, TryFutureExt};
+use std::{
+    cell::UnsafeCell,
+    sync::{Arc, Mutex, Weak},
+};
+
+use crate::error::*;
+use r2r_rcl::*;
+
+// Triggering a guard condition is thread safe, it is the intended way
+// of waking up a wait set from another thread. Everything else
+// (init, fini and adding to the wait set) is done from the thread
+// owning the Node.
+unsafe impl Send for GuardCondition_ {}
+unsafe impl Sync for GuardCondition_ {}
+
+pub(crate) struct GuardCondition_ {
+    handle: UnsafeCell<rcl_guard_condition_t>,
+    waiters: Mutex<Vec<oneshot::Sender<()>>>,
+}
+
+impl GuardCondition_ {
+    pub(crate) fn new(context: &mut rcl_context_t) -> Result<Self> {
+        let mut handle = unsafe { rcl_get_zero_initialized_guard_condition() };
+        let result = unsafe {
+            rcl_guard_condition_init(
+                &mut handle,
+                context,
+                rcl_guard_condition_get_default_options(),
+            )
+        };
+        if result == RCL_RET_OK as i32 {
+            Ok(GuardCondition_ {
+                handle: UnsafeCell::new(handle),
+                waiters: Mutex::new(Vec::new()),
+            })
+        } else {
+            Err(Error::from_rcl_error(result))
+        }
+    }
+
+    pub(crate) fn handle(&self) -> *const rcl_guard_condition_t {
+        self.handle.get()
+    }
+
+    pub(crate) fn trigger(&self) -> Result<()> {
+        let result = unsafe { rcl_trigger_guard_condition(self.handle.get()) };
+        if result == RCL_RET_OK as i32 {
+            Ok(())
+        } else {
+            Err(Error::from_rcl_error(result))
+        }
+    }
+
+    /// Called from spin when the guard condition was triggered.
+    pub(crate) fn wake_waiters(&self) {
+        let mut waiters = self.waiters.lock().unwrap();
+        for w in waiters.drain(..) {
+            let _ = w.send(()); // ignore result, as user may have dropped the receiver.
+        }
+    }
+
+    /// Drop all waiters, making their futures return an error.
+    pub(crate) fn clear_waiters(&self) {
+        self.waiters.lock().unwrap().clear();
+    }
+}
+
+impl Drop for GuardCondition_ {
+    fn drop(&mut self) {
+        unsafe {
+            rcl_guard_condition_fini(self.handle.get_mut());
+        }
+    }
+}
+
+/// Waker triggering a guard condition, used to wake up `spin_once`
+/// when a future driven by the node can make progress.
+pub(crate) struct GuardConditionWaker(pub(crate) Weak<GuardCondition_>);
+
+impl ArcWake for GuardConditionWaker {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        // if the guard condition is gone, so is the node, nothing to wake.
+        if let Some(g) = arc_self.0.upgrade() {
+            let _ = g.trigger();
+        }
+    }
+}
+
+/// A ROS guard condition.
+///
+/// Guard conditions are part of the wait set of the node that created them.
+/// They can be triggered from any thread to wake up `spin_once` of that
+/// node, e.g. from a hardware callback.
+#[derive(Clone)]
+pub struct GuardCondition {
+    pub(crate) handle: Weak<GuardCondition_>,
+}
+
+impl GuardCondition {
+    /// Trigger the guard condition, waking up the node's `spin_once`.
+    pub fn trigger(&self) -> Result<()> {
+        self.handle
+            .upgrade()
+            .ok_or(Error::RCL_RET_INVALID_ARGUMENT)?
+            .trigger()
+    }
+
+    /// Wait for the guard condition to be triggered.
+    ///
+    /// The returned future completes when the node is spun after
+    /// the next call to [`GuardCondition::trigger`].
+    pub fn triggered(&self) -> Result<impl Future<Output = Result<()>>> {
+        let (sender, receiver) = oneshot::channel();
+        self.handle
+            .upgrade()
+            .ok_or(Error::RCL_RET_INVALID_ARGUMENT)?
+            .waiters
+            .lock()
+            .unwrap()
+            .push(sender);
+        Ok(receiver.map_err(|_| Error::RCL_RET_INVALID_ARGUMENT))
+    }
+}
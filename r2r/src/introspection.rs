@@ -0,0 +1,485 @@
+//! Runtime access to message schemas and fields, using the ROS introspection type support.
+//!
+//! This is useful when the message type is only known at runtime, e.g. when
+//! working with recorded or untyped data.
+//!
+//! ```no_run
+//! # fn main() -> r2r::Result<()> {
+//! let mut pose = r2r::geometry_msgs::msg::Pose::default();
+//! pose.position.x = 1.5;
+//! let bytes = r2r::to_cdr(&pose)?;
+//! let x = r2r::introspection::get_field("geometry_msgs/msg/Pose", &bytes, "position.x")?;
+//! assert_eq!(x, r2r::introspection::FieldValue::F64(1.5));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{ffi::CStr, os::raw::c_void};
+
+use r2r_rcl::*;
+
+use crate::{
+    error::*,
+    msg_types::{message_type_name, VoidPtr, WrappedNativeMsgUntyped},
+};
+
+// the type ids of rosidl_typesupport_introspection_c/field_types.h
+const ROS_TYPE_FLOAT: u8 = 1;
+const ROS_TYPE_DOUBLE: u8 = 2;
+const ROS_TYPE_LONG_DOUBLE: u8 = 3;
+const ROS_TYPE_CHAR: u8 = 4;
+const ROS_TYPE_WCHAR: u8 = 5;
+const ROS_TYPE_BOOLEAN: u8 = 6;
+const ROS_TYPE_OCTET: u8 = 7;
+const ROS_TYPE_UINT8: u8 = 8;
+const ROS_TYPE_INT8: u8 = 9;
+const ROS_TYPE_UINT16: u8 = 10;
+const ROS_TYPE_INT16: u8 = 11;
+const ROS_TYPE_UINT32: u8 = 12;
+const ROS_TYPE_INT32: u8 = 13;
+const ROS_TYPE_UINT64: u8 = 14;
+const ROS_TYPE_INT64: u8 = 15;
+const ROS_TYPE_STRING: u8 = 16;
+const ROS_TYPE_WSTRING: u8 = 17;
+const ROS_TYPE_MESSAGE: u8 = 18;
+
+/// The value of a scalar message field.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldValue {
+    Bool(bool),
+    Byte(u8),
+    Char(u8),
+    WChar(u16),
+    I8(i8),
+    U8(u8),
+    I16(i16),
+    U16(u16),
+    I32(i32),
+    U32(u32),
+    I64(i64),
+    U64(u64),
+    F32(f32),
+    F64(f64),
+    String(String),
+    WString(String),
+}
+
+/// The type of a message field.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldType {
+    Bool,
+    Byte,
+    Char,
+    WChar,
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    I64,
+    U64,
+    F32,
+    F64,
+    LongDouble,
+    String { upper_bound: Option<usize> },
+    WString { upper_bound: Option<usize> },
+    Message(MessageDescriptor),
+}
+
+/// The size of an array field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArraySize {
+    /// A fixed size array, e.g. `float64[9]`.
+    Fixed(usize),
+    /// A sequence with an upper bound, e.g. `float64[<=9]`.
+    Bounded(usize),
+    /// A sequence, e.g. `float64[]`.
+    Unbounded,
+}
+
+/// A field of a message.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldDescriptor {
+    pub name: String,
+    pub field_type: FieldType,
+    /// `Some` if the field is an array of `field_type`.
+    pub array: Option<ArraySize>,
+}
+
+impl FieldDescriptor {
+    /// Returns true if the field is a sequence, bounded or not.
+    pub fn is_sequence(&self) -> bool {
+        matches!(self.array, Some(ArraySize::Bounded(_) | ArraySize::Unbounded))
+    }
+
+    /// Returns true if the field is a sequence with an upper bound.
+    pub fn is_bounded(&self) -> bool {
+        matches!(self.array, Some(ArraySize::Bounded(_)))
+    }
+
+    /// The size of a fixed size array, or the upper bound of a bounded sequence.
+    pub fn array_size(&self) -> Option<usize> {
+        match self.array {
+            Some(ArraySize::Fixed(n) | ArraySize::Bounded(n)) => Some(n),
+            _ => None,
+        }
+    }
+
+    /// The schema of the field, if it is a message.
+    pub fn nested_type(&self) -> Option<&MessageDescriptor> {
+        match &self.field_type {
+            FieldType::Message(desc) => Some(desc),
+            _ => None,
+        }
+    }
+}
+
+/// The schema of a message, including the schemas of all nested messages.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MessageDescriptor {
+    /// The message type, e.g. `"geometry_msgs/msg/Pose"`.
+    pub type_name: String,
+    pub fields: Vec<FieldDescriptor>,
+}
+
+impl MessageDescriptor {
+    /// Get the schema of a message type, e.g. `"geometry_msgs/msg/Pose"`.
+    pub fn for_type(type_name: &str) -> Result<MessageDescriptor> {
+        let msg = WrappedNativeMsgUntyped::new_from(type_name)?;
+        let members = introspection_members(msg.ts, type_name)?;
+        unsafe { describe(members) }
+    }
+
+    /// The fields of the message, in the order of the message definition.
+    pub fn fields(&self) -> &[FieldDescriptor] {
+        &self.fields
+    }
+
+    /// Get the schema of the message type of a type support.
+    #[cfg(r2r__std_msgs__msg__Header)]
+    pub(crate) fn for_typesupport(ts: &rosidl_message_type_support_t) -> Result<MessageDescriptor> {
+        let type_name = message_type_name(ts).unwrap_or_default();
+        let members = introspection_members(ts, &type_name)?;
+        unsafe { describe(members) }
+    }
+}
+
+/// Get the value of a scalar field of a serialized message.
+///
+/// The field is given as a dotted path, e.g. `"pose.position.x"`. Elements
+/// of array fields are selected with an index, e.g. `"poses[2].position.x"`.
+pub fn get_field(type_name: &str, cdr_bytes: &[u8], path: &str) -> Result<FieldValue> {
+    let mut msg = WrappedNativeMsgUntyped::new_from(type_name)?;
+    msg.from_serialized_bytes(cdr_bytes)?;
+    let members = introspection_members(msg.ts, type_name)?;
+    let segments = parse_path(path)?;
+
+    let invalid = |reason: String| Error::InvalidFieldPath {
+        path: path.to_string(),
+        reason,
+    };
+
+    let mut members = members;
+    let mut ptr = msg.void_ptr() as *const u8;
+    for (i, (name, index)) in segments.iter().enumerate() {
+        let member = unsafe { member_slice(members) }
+            .iter()
+            .find(|m| unsafe { CStr::from_ptr(m.name_) }.to_bytes() == name.as_bytes())
+            .ok_or_else(|| invalid(format!("no field named '{}'", name)))?;
+        let mut field = unsafe { ptr.add(member.offset_ as usize) };
+
+        match (member.is_array_, index) {
+            (true, Some(index)) => {
+                let (Some(size), Some(get)) = (member.size_function, member.get_const_function)
+                else {
+                    return Err(invalid(format!("'{}' can not be indexed", name)));
+                };
+                let len = unsafe { size(field as *const c_void) };
+                if *index >= len {
+                    return Err(invalid(format!(
+                        "index {} is out of bounds for '{}' of length {}",
+                        index, name, len
+                    )));
+                }
+                field = unsafe { get(field as *const c_void, *index) } as *const u8;
+            }
+            (true, None) => return Err(invalid(format!("'{}' is an array", name))),
+            (false, Some(_)) => return Err(invalid(format!("'{}' is not an array", name))),
+            (false, None) => {}
+        }
+
+        let last = i + 1 == segments.len();
+        if member.type_id_ == ROS_TYPE_MESSAGE {
+            if last {
+                return Err(invalid(format!("'{}' is a message", name)));
+            }
+            members = unsafe {
+                &*((*member.members_).data
+                    as *const rosidl_typesupport_introspection_c__MessageMembers)
+            };
+            ptr = field;
+        } else if !last {
+            return Err(invalid(format!("'{}' has no fields", name)));
+        } else {
+            return unsafe { read_scalar(member.type_id_, field) }
+                .ok_or_else(|| invalid(format!("'{}' has an unsupported type", name)));
+        }
+    }
+
+    Err(invalid("the path is empty".to_string()))
+}
+
+// the fields in which two native messages of the type support differ, see
+// message_diff.
+pub(crate) fn diff_native(
+    ts: &rosidl_message_type_support_t, a: *const c_void, b: *const c_void,
+) -> Result<Vec<String>> {
+    let type_name = message_type_name(ts).unwrap_or_default();
+    let members = introspection_members(ts, &type_name)?;
+    let mut diff = vec![];
+    unsafe { diff_members(members, a as *const u8, b as *const u8, "", &mut diff) };
+    Ok(diff)
+}
+
+unsafe fn diff_members(
+    members: &rosidl_typesupport_introspection_c__MessageMembers, a: *const u8, b: *const u8,
+    path: &str, diff: &mut Vec<String>,
+) {
+    for member in member_slice(members) {
+        let name = CStr::from_ptr(member.name_).to_string_lossy();
+        let path = if path.is_empty() {
+            name.into_owned()
+        } else {
+            format!("{}.{}", path, name)
+        };
+        let (a, b) = (a.add(member.offset_ as usize), b.add(member.offset_ as usize));
+        if !member.is_array_ {
+            diff_field(member, a, b, &path, diff);
+            continue;
+        }
+        let (Some(size), Some(get)) = (member.size_function, member.get_const_function) else {
+            continue;
+        };
+        let (len_a, len_b) = (size(a as *const c_void), size(b as *const c_void));
+        if len_a != len_b {
+            diff.push(format!("{}: {} elements != {} elements", path, len_a, len_b));
+            continue;
+        }
+        for i in 0..len_a {
+            let (a, b) = (get(a as *const c_void, i), get(b as *const c_void, i));
+            diff_field(member, a as *const u8, b as *const u8, &format!("{}[{}]", path, i), diff);
+        }
+    }
+}
+
+unsafe fn diff_field(
+    member: &rosidl_typesupport_introspection_c__MessageMember, a: *const u8, b: *const u8,
+    path: &str, diff: &mut Vec<String>,
+) {
+    if member.type_id_ == ROS_TYPE_MESSAGE {
+        let members = &*((*member.members_).data
+            as *const rosidl_typesupport_introspection_c__MessageMembers);
+        return diff_members(members, a, b, path, diff);
+    }
+    // long doubles are not read, and so never differ.
+    let a = read_scalar(member.type_id_, a);
+    let b = read_scalar(member.type_id_, b);
+    if let (Some(a), Some(b)) = (a, b) {
+        if a != b {
+            diff.push(format!("{}: {} != {}", path, format_value(&a), format_value(&b)));
+        }
+    }
+}
+
+fn format_value(value: &FieldValue) -> String {
+    match value {
+        FieldValue::Bool(v) => format!("{:?}", v),
+        FieldValue::Byte(v) | FieldValue::Char(v) | FieldValue::U8(v) => format!("{:?}", v),
+        FieldValue::WChar(v) | FieldValue::U16(v) => format!("{:?}", v),
+        FieldValue::I8(v) => format!("{:?}", v),
+        FieldValue::I16(v) => format!("{:?}", v),
+        FieldValue::I32(v) => format!("{:?}", v),
+        FieldValue::U32(v) => format!("{:?}", v),
+        FieldValue::I64(v) => format!("{:?}", v),
+        FieldValue::U64(v) => format!("{:?}", v),
+        FieldValue::F32(v) => format!("{:?}", v),
+        FieldValue::F64(v) => format!("{:?}", v),
+        FieldValue::String(v) | FieldValue::WString(v) => format!("{:?}", v),
+    }
+}
+
+// splits e.g. "poses[2].position.x" into [("poses", Some(2)), ("position", None), ("x", None)]
+fn parse_path(path: &str) -> Result<Vec<(&str, Option<usize>)>> {
+    let invalid = || Error::InvalidFieldPath {
+        path: path.to_string(),
+        reason: "malformed path".to_string(),
+    };
+    path.split('.')
+        .map(|segment| {
+            let (name, index) = match segment.split_once('[') {
+                Some((name, rest)) => {
+                    let index = rest.strip_suffix(']').ok_or_else(invalid)?;
+                    (name, Some(index.parse().map_err(|_| invalid())?))
+                }
+                None => (segment, None),
+            };
+            if name.is_empty() {
+                return Err(invalid());
+            }
+            Ok((name, index))
+        })
+        .collect()
+}
+
+fn introspection_members(
+    ts: &rosidl_message_type_support_t, type_name: &str,
+) -> Result<&'static rosidl_typesupport_introspection_c__MessageMembers> {
+    let identifier = b"rosidl_typesupport_introspection_c\0";
+    unsafe {
+        let introspection = get_message_typesupport_handle(ts, identifier.as_ptr() as *const _);
+        if introspection.is_null() {
+            rcutils_reset_error();
+            return Err(Error::InvalidMessageType {
+                msgtype: type_name.to_string(),
+            });
+        }
+        let members =
+            (*introspection).data as *const rosidl_typesupport_introspection_c__MessageMembers;
+        members.as_ref().ok_or_else(|| Error::InvalidMessageType {
+            msgtype: type_name.to_string(),
+        })
+    }
+}
+
+unsafe fn member_slice(
+    members: &rosidl_typesupport_introspection_c__MessageMembers,
+) -> &[rosidl_typesupport_introspection_c__MessageMember] {
+    if members.member_count_ == 0 || members.members_.is_null() {
+        return &[];
+    }
+    std::slice::from_raw_parts(members.members_, members.member_count_ as usize)
+}
+
+unsafe fn describe(
+    members: &rosidl_typesupport_introspection_c__MessageMembers,
+) -> Result<MessageDescriptor> {
+    let namespace = CStr::from_ptr(members.message_namespace_).to_string_lossy();
+    let name = CStr::from_ptr(members.message_name_).to_string_lossy();
+    // the namespace is e.g. "geometry_msgs__msg"
+    let type_name = format!("{}/{}", namespace.replace("__", "/"), name);
+
+    let fields = member_slice(members)
+        .iter()
+        .map(|m| {
+            let string_bound = (m.string_upper_bound_ > 0).then_some(m.string_upper_bound_);
+            let field_type = match m.type_id_ {
+                ROS_TYPE_FLOAT => FieldType::F32,
+                ROS_TYPE_DOUBLE => FieldType::F64,
+                ROS_TYPE_LONG_DOUBLE => FieldType::LongDouble,
+                ROS_TYPE_CHAR => FieldType::Char,
+                ROS_TYPE_WCHAR => FieldType::WChar,
+                ROS_TYPE_BOOLEAN => FieldType::Bool,
+                ROS_TYPE_OCTET => FieldType::Byte,
+                ROS_TYPE_UINT8 => FieldType::U8,
+                ROS_TYPE_INT8 => FieldType::I8,
+                ROS_TYPE_UINT16 => FieldType::U16,
+                ROS_TYPE_INT16 => FieldType::I16,
+                ROS_TYPE_UINT32 => FieldType::U32,
+                ROS_TYPE_INT32 => FieldType::I32,
+                ROS_TYPE_UINT64 => FieldType::U64,
+                ROS_TYPE_INT64 => FieldType::I64,
+                ROS_TYPE_STRING => FieldType::String {
+                    upper_bound: string_bound,
+                },
+                ROS_TYPE_WSTRING => FieldType::WString {
+                    upper_bound: string_bound,
+                },
+                ROS_TYPE_MESSAGE => FieldType::Message(describe(
+                    &*((*m.members_).data
+                        as *const rosidl_typesupport_introspection_c__MessageMembers),
+                )?),
+                // e.g. a type added to rosidl after this was written.
+                _ => {
+                    return Err(Error::InvalidMessageType {
+                        msgtype: type_name.clone(),
+                    })
+                }
+            };
+            let array = if !m.is_array_ {
+                None
+            } else if m.is_upper_bound_ {
+                Some(ArraySize::Bounded(m.array_size_))
+            } else if m.array_size_ > 0 {
+                Some(ArraySize::Fixed(m.array_size_))
+            } else {
+                Some(ArraySize::Unbounded)
+            };
+            Ok(FieldDescriptor {
+                name: CStr::from_ptr(m.name_).to_string_lossy().into_owned(),
+                field_type,
+                array,
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(MessageDescriptor { type_name, fields })
+}
+
+unsafe fn read_scalar(type_id: u8, ptr: *const u8) -> Option<FieldValue> {
+    use std::ptr::read_unaligned as read;
+    let value = match type_id {
+        ROS_TYPE_FLOAT => FieldValue::F32(read(ptr as *const f32)),
+        ROS_TYPE_DOUBLE => FieldValue::F64(read(ptr as *const f64)),
+        ROS_TYPE_CHAR => FieldValue::Char(read(ptr)),
+        ROS_TYPE_WCHAR => FieldValue::WChar(read(ptr as *const u16)),
+        ROS_TYPE_BOOLEAN => FieldValue::Bool(read(ptr) != 0),
+        ROS_TYPE_OCTET => FieldValue::Byte(read(ptr)),
+        ROS_TYPE_UINT8 => FieldValue::U8(read(ptr)),
+        ROS_TYPE_INT8 => FieldValue::I8(read(ptr as *const i8)),
+        ROS_TYPE_UINT16 => FieldValue::U16(read(ptr as *const u16)),
+        ROS_TYPE_INT16 => FieldValue::I16(read(ptr as *const i16)),
+        ROS_TYPE_UINT32 => FieldValue::U32(read(ptr as *const u32)),
+        ROS_TYPE_INT32 => FieldValue::I32(read(ptr as *const i32)),
+        ROS_TYPE_UINT64 => FieldValue::U64(read(ptr as *const u64)),
+        ROS_TYPE_INT64 => FieldValue::I64(read(ptr as *const i64)),
+        ROS_TYPE_STRING => {
+            let s = &*(ptr as *const rosidl_runtime_c__String);
+            let bytes = if s.data.is_null() {
+                &[]
+            } else {
+                std::slice::from_raw_parts(s.data as *const u8, s.size)
+            };
+            FieldValue::String(String::from_utf8_lossy(bytes).into_owned())
+        }
+        ROS_TYPE_WSTRING => {
+            let s = &*(ptr as *const rosidl_runtime_c__U16String);
+            let chars = if s.data.is_null() {
+                &[]
+            } else {
+                std::slice::from_raw_parts(s.data as *const u16, s.size)
+            };
+            FieldValue::WString(String::from_utf16_lossy(chars))
+        }
+        // long double has no rust equivalent.
+        _ => return None,
+    };
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            parse_path("poses[2].position.x").unwrap(),
+            vec![("poses", Some(2)), ("position", None), ("x", None)]
+        );
+        assert!(parse_path("").is_err());
+        assert!(parse_path("a..b").is_err());
+        assert!(parse_path("a[x]").is_err());
+        assert!(parse_path("a[1").is_err());
+    }
+}
@@ -70,63 +70,200 @@
 //!     }
 //! }
 //! ```
+//!
+//! Concurrency
+//!---
+//! `spin_once` only hands incoming messages, requests and timer ticks
+//! over to the streams and futures returned when creating them, so a
+//! slow consumer never blocks `spin_once` or any other consumer. How
+//! they run concurrently is decided by how they are spawned: streams
+//! consumed in separate tasks on a multi-threaded executor run in
+//! parallel, while streams consumed in the same task, e.g. using
+//! `select`, are processed one at a time.
+//!
+//! For callbacks instead of streams, [`CallbackGroup`]s work like the
+//! callback groups of rclcpp: the callbacks of a mutually exclusive group
+//! run one at a time on a thread of the group, those of a reentrant group
+//! run concurrently. See [`Node::create_callback_group`].
 
 // otherwise crates using r2r needs to specify the same version of indexmap and uuid as
 // this crate depend on, which seem like bad user experience.
 pub extern crate indexmap;
 pub extern crate uuid;
+// used by code derived for `r2r_macros::Parameters`.
+#[doc(hidden)]
+pub extern crate futures;
 
 mod error;
-pub use error::{Error, Result};
+pub use error::{Error, Result, ServiceError};
 
 mod msg_types;
 pub use msg_types::{
-    generated_msgs::*, WrappedActionTypeSupport, WrappedNativeMsg as NativeMsg,
+    from_cdr, generated_msgs::*, to_cdr, WrappedActionTypeSupport, WrappedNativeMsg as NativeMsg,
     WrappedNativeMsgUntyped, WrappedServiceTypeSupport, WrappedTypesupport,
 };
 
 mod utils;
 pub use utils::*;
 
+mod message_diff;
+pub use message_diff::message_diff;
+
 mod subscribers;
+pub use subscribers::{
+    AnySubscription, BoundedSubscription, ContentFilter, DeserError, DeserErrorHandler,
+    DropCounter, MessageInfo, OverflowPolicy, Subscription, SubscriptionOptions, SubscriptionStats,
+};
+
+mod envelope;
+#[cfg(r2r__std_msgs__msg__Header)]
+pub use envelope::MessageEnvelope;
+
+mod operators;
+pub use operators::{DebouncedStream, FilteredStream, MappedStream, ThrottledStream};
 
 mod publishers;
-pub use publishers::{Publisher, PublisherUntyped};
+pub use publishers::{
+    AnyPublisher, ConditionalPublisher, IntraPublisher, PublishResult, Publisher, PublisherOptions,
+    PublisherStats, PublisherUntyped,
+};
+
+mod network_flow;
+pub use network_flow::{InternetProtocol, NetworkFlowEndpoint, TransportProtocol};
+
+mod qos_events;
+pub use qos_events::{
+    DeadlineMissedStatus, IncompatibleQosStatus, LivelinessChangedStatus, LivelinessLostStatus,
+    MessageLostStatus, QosPolicyKind,
+};
 
 mod services;
-pub use services::ServiceRequest;
+pub use services::{ServiceIntrospectionState, ServiceOptions, ServiceRequest};
 
 mod clients;
-pub use clients::{Client, ClientUntyped};
+pub use clients::{Client, ClientPool, ClientUntyped, PooledClient};
+
+mod service_spy;
+pub use service_spy::ServiceInteraction;
+
+mod guard_conditions;
+pub use guard_conditions::GuardCondition;
+
+mod graph_events;
+pub use graph_events::GraphEvent;
 
 mod action_common;
 pub use action_common::GoalStatus;
 
+mod action_status_monitor;
+
+/// Types shared by action clients and servers.
+pub mod action {
+    pub use crate::action_common::GoalStatus;
+    pub use crate::action_status_monitor::{
+        ActionStatusChange, ActionStatusMonitor, ActionStatusSnapshot,
+    };
+}
+
 mod action_clients;
-pub use action_clients::{ActionClient, ActionClientGoal};
+pub use action_clients::{ActionClient, ActionClientGoal, GoalReply};
 
 mod action_clients_untyped;
 pub use action_clients_untyped::{ActionClientGoalUntyped, ActionClientUntyped};
 
 mod action_servers;
-pub use action_servers::{ActionServerCancelRequest, ActionServerGoal, ActionServerGoalRequest};
+pub use action_servers::{
+    AcceptedGoal, ActionServerCancelRequest, ActionServerGoal, ActionServerGoalRequest,
+    ActionServerOptions, CancelingGoal, DeferredGoal, ExecutingGoal, GoalHandlingPolicy,
+    ManualGoalHandle,
+};
 
 mod context;
-pub use context::Context;
+pub use context::{init_maybe, is_initialized, try_shutdown, Context, ShutdownToken};
 
 mod parameters;
-pub use parameters::{Parameter, ParameterValue, RosParams, WrongParameterType};
+#[doc(hidden)]
+pub use parameters::{parameter_group_name, parameter_group_value};
+pub use parameters::{
+    BatchParameterEvent, FloatingPointRange, IntegerRange, Parameter, ParameterChange,
+    ParameterDescriptor, ParameterEventExt, ParameterGroup, ParameterHandle, ParameterValue,
+    RosParams, SetParameterResult, WrongParameterType,
+};
+
+mod parameter_client;
+pub use parameter_client::ParameterClient;
 
-pub use r2r_macros::RosParams;
+pub use r2r_macros::{Parameters, RosParams};
 
 mod clocks;
-pub use clocks::{Clock, ClockType};
+pub use clocks::{Clock, ClockType, MockClock};
+
+#[cfg(r2r__std_msgs__msg__Header)]
+pub use clocks::stamp_header;
+
+mod rate;
+pub use rate::{Rate, SleepResult};
 
 mod nodes;
-pub use nodes::{Node, Timer};
+pub use nodes::{EndpointType, Node, NodeBuilder, NodeOptions, Timer, TopicEndpointInfo};
+
+mod shared_node;
+pub use shared_node::SharedNode;
+
+mod wait_set;
+pub use wait_set::{spin_many, WaitSet, WaitSetReady, WaitSetToken};
+
+mod spin;
+pub use spin::{spin_some, spin_until_future_complete};
+
+mod event_loop;
+pub use event_loop::EventLoop;
+
+mod callback_groups;
+pub use callback_groups::{CallbackGroup, CallbackGroupKind};
+
+mod executor_stats;
+pub use executor_stats::{CallbackStats, ExecutorStats};
+
+#[cfg(feature = "bag")]
+pub mod bag;
+
+pub mod gid;
 
 pub mod qos;
 
+pub mod rmw;
+
+pub mod sync;
+
+pub mod tf2;
+
+pub mod introspection;
+
+mod names;
+pub use names::{
+    expand_service_name, expand_topic_name, validate_namespace, validate_node_name,
+    validate_service_name, validate_topic_name,
+};
+
+#[cfg(r2r__composition_interfaces__srv__LoadNode)]
+mod components;
+#[cfg(r2r__composition_interfaces__srv__LoadNode)]
+pub use components::{Component, ComponentContainer, ComponentRegistry};
+
+#[cfg(r2r__lifecycle_msgs__srv__ChangeState)]
+mod lifecycle;
+#[cfg(r2r__lifecycle_msgs__srv__ChangeState)]
+pub use lifecycle::{
+    CallbackReturn, LifecycleNode, LifecyclePublisher, LifecycleState, LifecycleTransition,
+};
+
+#[cfg(feature = "tokio")]
+pub mod tokio;
+
+#[cfg(r2r__statistics_msgs__msg__MetricsMessage)]
+mod topic_statistics;
+
 #[cfg(r2r__rosgraph_msgs__msg__Clock)]
 mod time_source;
 #[cfg(r2r__rosgraph_msgs__msg__Clock)]
@@ -155,7 +292,6 @@ pub const ROS_DISTRO: &str = "rolling";
     r2r__ros__distro__humble,
     r2r__ros__distro__iron,
     r2r__ros__distro__jazzy,
-
     r2r__ros__distro__rolling
 )))]
 pub const ROS_DISTRO: &str = "unknown";
@@ -0,0 +1,915 @@
+#![cfg(r2r__lifecycle_msgs__srv__ChangeState)]
+
+//! Managed (lifecycle) nodes.
+//!
+//! A [`LifecycleNode`] wraps a [`Node`] and the lifecycle state machine of
+//! `rcl_lifecycle`, the same one used by rclcpp lifecycle nodes. rcl creates
+//! the `~/change_state`, `~/get_state`, `~/get_available_states`,
+//! `~/get_available_transitions` and `~/get_transition_graph` services and
+//! publishes on `~/transition_event`, so the node can be controlled using
+//! e.g. `ros2 lifecycle set`.
+
+use futures::{
+    channel::mpsc,
+    executor::block_on,
+    future::{self, join_all, FutureExt},
+    stream::{Stream, StreamExt},
+};
+use std::{
+    ffi::{CStr, CString},
+    future::Future,
+    ops::{Deref, DerefMut},
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    context::Context,
+    error::*,
+    msg_types::{
+        generated_msgs::lifecycle_msgs, VoidPtr, WrappedServiceTypeSupport, WrappedTypesupport,
+    },
+    nodes::Node,
+    publishers::Publisher,
+    qos::QosProfile,
+    services::{send_response, take_request, ServiceRequest, Service_},
+};
+use r2r_rcl::*;
+
+/// The states of a lifecycle node.
+///
+/// The first four are primary states, the rest are the intermediate
+/// states a node is in while a transition callback is running.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LifecycleState {
+    Unconfigured,
+    Inactive,
+    Active,
+    Finalized,
+    Configuring,
+    CleaningUp,
+    ShuttingDown,
+    Activating,
+    Deactivating,
+    ErrorProcessing,
+}
+
+impl LifecycleState {
+    const ALL: [LifecycleState; 10] = [
+        LifecycleState::Unconfigured,
+        LifecycleState::Inactive,
+        LifecycleState::Active,
+        LifecycleState::Finalized,
+        LifecycleState::Configuring,
+        LifecycleState::CleaningUp,
+        LifecycleState::ShuttingDown,
+        LifecycleState::Activating,
+        LifecycleState::Deactivating,
+        LifecycleState::ErrorProcessing,
+    ];
+
+    pub fn to_rcl(&self) -> u8 {
+        use lifecycle_msgs::msg::State;
+        match self {
+            LifecycleState::Unconfigured => State::PRIMARY_STATE_UNCONFIGURED as u8,
+            LifecycleState::Inactive => State::PRIMARY_STATE_INACTIVE as u8,
+            LifecycleState::Active => State::PRIMARY_STATE_ACTIVE as u8,
+            LifecycleState::Finalized => State::PRIMARY_STATE_FINALIZED as u8,
+            LifecycleState::Configuring => State::TRANSITION_STATE_CONFIGURING as u8,
+            LifecycleState::CleaningUp => State::TRANSITION_STATE_CLEANINGUP as u8,
+            LifecycleState::ShuttingDown => State::TRANSITION_STATE_SHUTTINGDOWN as u8,
+            LifecycleState::Activating => State::TRANSITION_STATE_ACTIVATING as u8,
+            LifecycleState::Deactivating => State::TRANSITION_STATE_DEACTIVATING as u8,
+            LifecycleState::ErrorProcessing => State::TRANSITION_STATE_ERRORPROCESSING as u8,
+        }
+    }
+
+    pub fn from_rcl(s: u8) -> Option<Self> {
+        Self::ALL.iter().find(|state| state.to_rcl() == s).copied()
+    }
+
+    /// Returns true for the four primary states.
+    pub fn is_primary(&self) -> bool {
+        matches!(
+            self,
+            LifecycleState::Unconfigured
+                | LifecycleState::Inactive
+                | LifecycleState::Active
+                | LifecycleState::Finalized
+        )
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            LifecycleState::Unconfigured => "unconfigured",
+            LifecycleState::Inactive => "inactive",
+            LifecycleState::Active => "active",
+            LifecycleState::Finalized => "finalized",
+            LifecycleState::Configuring => "configuring",
+            LifecycleState::CleaningUp => "cleaningup",
+            LifecycleState::ShuttingDown => "shuttingdown",
+            LifecycleState::Activating => "activating",
+            LifecycleState::Deactivating => "deactivating",
+            LifecycleState::ErrorProcessing => "errorprocessing",
+        }
+    }
+}
+
+impl std::fmt::Display for LifecycleState {
+    fn fmt(&self, fmtr: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(fmtr, "{}", self.label())
+    }
+}
+
+/// The result of a lifecycle transition callback.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CallbackReturn {
+    /// The transition succeeded, move on to the goal state.
+    Success,
+    /// The transition failed, go back to the start state.
+    Failure,
+    /// An error occured, go to `ErrorProcessing` and from there to
+    /// `Unconfigured`.
+    Error,
+}
+
+impl CallbackReturn {
+    // the label of the transition out of the intermediate state, see rcl_lifecycle.
+    fn transition_label(&self) -> &'static str {
+        match self {
+            CallbackReturn::Success => "transition_success",
+            CallbackReturn::Failure => "transition_failure",
+            CallbackReturn::Error => "transition_error",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum CallbackKind {
+    Configure,
+    Cleanup,
+    Activate,
+    Deactivate,
+    Shutdown,
+}
+
+impl CallbackKind {
+    // the callback run in an intermediate state, like in rclcpp.
+    fn of(state: LifecycleState) -> Option<Self> {
+        match state {
+            LifecycleState::Configuring => Some(CallbackKind::Configure),
+            LifecycleState::CleaningUp => Some(CallbackKind::Cleanup),
+            LifecycleState::Activating => Some(CallbackKind::Activate),
+            LifecycleState::Deactivating => Some(CallbackKind::Deactivate),
+            LifecycleState::ShuttingDown => Some(CallbackKind::Shutdown),
+            _ => None,
+        }
+    }
+}
+
+/// A transition of the lifecycle state machine.
+///
+/// See [`LifecycleNode::available_transitions`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LifecycleTransition {
+    /// The id of the transition, one of the `TRANSITION_*` constants of
+    /// `lifecycle_msgs/msg/Transition`.
+    pub id: u8,
+    pub label: String,
+    pub start: LifecycleState,
+    pub goal: LifecycleState,
+}
+
+type TransitionCallback =
+    Box<dyn FnMut(LifecycleState) -> Pin<Box<dyn Future<Output = CallbackReturn> + Send>> + Send>;
+
+#[derive(Default)]
+struct Callbacks {
+    configure: Option<TransitionCallback>,
+    cleanup: Option<TransitionCallback>,
+    activate: Option<TransitionCallback>,
+    deactivate: Option<TransitionCallback>,
+    shutdown: Option<TransitionCallback>,
+}
+
+impl Callbacks {
+    fn get(&mut self, kind: CallbackKind) -> &mut Option<TransitionCallback> {
+        match kind {
+            CallbackKind::Configure => &mut self.configure,
+            CallbackKind::Cleanup => &mut self.cleanup,
+            CallbackKind::Activate => &mut self.activate,
+            CallbackKind::Deactivate => &mut self.deactivate,
+            CallbackKind::Shutdown => &mut self.shutdown,
+        }
+    }
+}
+
+fn label_of(label: *const std::os::raw::c_char) -> String {
+    if label.is_null() {
+        return String::new();
+    }
+    unsafe { CStr::from_ptr(label) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn state_msg(state: &rcl_lifecycle_state_t) -> lifecycle_msgs::msg::State {
+    lifecycle_msgs::msg::State {
+        id: state.id,
+        label: label_of(state.label),
+    }
+}
+
+fn transition_description(
+    transition: &rcl_lifecycle_transition_t,
+) -> lifecycle_msgs::msg::TransitionDescription {
+    let (start, goal) = unsafe { (&*transition.start, &*transition.goal) };
+    lifecycle_msgs::msg::TransitionDescription {
+        transition: lifecycle_msgs::msg::Transition {
+            id: transition.id as u8,
+            label: label_of(transition.label),
+        },
+        start_state: state_msg(start),
+        goal_state: state_msg(goal),
+    }
+}
+
+/// The `rcl_lifecycle` state machine of a node.
+struct StateMachine {
+    handle: rcl_lifecycle_state_machine_t,
+    // the state after the last transition, also kept once finalized.
+    state: LifecycleState,
+    finalized: bool,
+    // the state stream, once the lifecycle handler exists.
+    event_tx: Option<mpsc::Sender<LifecycleState>>,
+}
+
+// the rcl handles are only used with the mutex of the state machine held.
+unsafe impl Send for StateMachine {}
+
+type SharedStateMachine = Arc<Mutex<StateMachine>>;
+
+impl StateMachine {
+    fn new(node: &mut rcl_node_t) -> Result<StateMachine> {
+        use lifecycle_msgs::{
+            msg::TransitionEvent,
+            srv::{ChangeState, GetAvailableStates, GetAvailableTransitions, GetState},
+        };
+
+        let mut handle = unsafe { rcl_lifecycle_get_zero_initialized_state_machine() };
+        let ts_pub_notify = TransitionEvent::get_ts();
+        let ts_change_state = ChangeState::Service::get_ts();
+        let ts_get_state = GetState::Service::get_ts();
+        let ts_get_available_states = GetAvailableStates::Service::get_ts();
+        let ts_get_available_transitions = GetAvailableTransitions::Service::get_ts();
+
+        #[cfg(r2r__ros__distro__foxy)]
+        let ret = unsafe {
+            let allocator = rcutils_get_default_allocator();
+            rcl_lifecycle_state_machine_init(
+                &mut handle,
+                node,
+                ts_pub_notify,
+                ts_change_state,
+                ts_get_state,
+                ts_get_available_states,
+                ts_get_available_transitions,
+                // the transition graph uses the same service type.
+                ts_get_available_transitions,
+                true,
+                &allocator,
+            )
+        };
+        #[cfg(not(r2r__ros__distro__foxy))]
+        let ret = unsafe {
+            let mut options = rcl_lifecycle_get_default_state_machine_options();
+            options.initialize_default_states = true;
+            options.enable_com_interface = true;
+            rcl_lifecycle_state_machine_init(
+                &mut handle,
+                node,
+                ts_pub_notify,
+                ts_change_state,
+                ts_get_state,
+                ts_get_available_states,
+                ts_get_available_transitions,
+                // the transition graph uses the same service type.
+                ts_get_available_transitions,
+                &options,
+            )
+        };
+        if ret != RCL_RET_OK as i32 {
+            unsafe { rcutils_reset_error() };
+            return Err(Error::from_rcl_error(ret));
+        }
+
+        let mut state_machine = StateMachine {
+            handle,
+            state: LifecycleState::Unconfigured,
+            finalized: false,
+            event_tx: None,
+        };
+        state_machine.state = state_machine.current_state();
+        Ok(state_machine)
+    }
+
+    fn current_state(&self) -> LifecycleState {
+        let id = unsafe { (*self.handle.current_state).id };
+        LifecycleState::from_rcl(id).expect("state of the default state machine")
+    }
+
+    fn valid_transitions(&self) -> &[rcl_lifecycle_transition_t] {
+        if self.finalized {
+            return &[];
+        }
+        unsafe {
+            let state = &*self.handle.current_state;
+            if state.valid_transitions.is_null() {
+                return &[];
+            }
+            std::slice::from_raw_parts(
+                state.valid_transitions,
+                state.valid_transition_size as usize,
+            )
+        }
+    }
+
+    fn states(&self) -> &[rcl_lifecycle_state_t] {
+        let map = &self.handle.transition_map;
+        if self.finalized || map.states.is_null() {
+            return &[];
+        }
+        unsafe { std::slice::from_raw_parts(map.states, map.states_size as usize) }
+    }
+
+    fn transitions(&self) -> &[rcl_lifecycle_transition_t] {
+        let map = &self.handle.transition_map;
+        if self.finalized || map.transitions.is_null() {
+            return &[];
+        }
+        unsafe { std::slice::from_raw_parts(map.transitions, map.transitions_size as usize) }
+    }
+
+    fn available_transitions(&self) -> Vec<LifecycleTransition> {
+        self.valid_transitions()
+            .iter()
+            .filter_map(|t| {
+                let (start, goal) = unsafe { ((*t.start).id, (*t.goal).id) };
+                Some(LifecycleTransition {
+                    id: t.id as u8,
+                    label: label_of(t.label),
+                    start: LifecycleState::from_rcl(start)?,
+                    goal: LifecycleState::from_rcl(goal)?,
+                })
+            })
+            .collect()
+    }
+
+    // the transition id to use for a change_state request, which may give a label instead.
+    fn resolve(&self, transition: &lifecycle_msgs::msg::Transition) -> u8 {
+        if transition.id != 0 || transition.label.is_empty() {
+            return transition.id;
+        }
+        self.available_transitions()
+            .into_iter()
+            .find(|t| t.label == transition.label)
+            .map(|t| t.id)
+            .unwrap_or(0)
+    }
+
+    fn trigger_by_id(&mut self, id: u8) -> Result<()> {
+        let ret = if self.finalized {
+            RCL_RET_ERROR as i32
+        } else {
+            unsafe { rcl_lifecycle_trigger_transition_by_id(&mut self.handle, id, true) }
+        };
+        if ret != RCL_RET_OK as i32 {
+            unsafe { rcutils_reset_error() };
+            return Err(Error::InvalidLifecycleTransition {
+                transition: id,
+                state: self.state.to_string(),
+            });
+        }
+        self.state_changed();
+        Ok(())
+    }
+
+    fn trigger_by_label(&mut self, label: &str) -> Result<()> {
+        let label_c_string = CString::new(label).map_err(|_| Error::RCL_RET_INVALID_ARGUMENT)?;
+        let ret = if self.finalized {
+            RCL_RET_ERROR as i32
+        } else {
+            unsafe {
+                rcl_lifecycle_trigger_transition_by_label(
+                    &mut self.handle,
+                    label_c_string.as_ptr(),
+                    true,
+                )
+            }
+        };
+        if ret != RCL_RET_OK as i32 {
+            unsafe { rcutils_reset_error() };
+            return Err(Error::LifecycleTransitionFailed {
+                transition: label.to_string(),
+                state: self.state.to_string(),
+            });
+        }
+        self.state_changed();
+        Ok(())
+    }
+
+    fn state_changed(&mut self) {
+        self.state = self.current_state();
+        if let Some(event_tx) = &mut self.event_tx {
+            if let Err(e) = event_tx.try_send(self.state) {
+                log::debug!("Warning: could not send lifecycle event ({}).", e);
+            }
+        }
+    }
+
+    fn fini(&mut self, node: &mut rcl_node_t) {
+        if self.finalized {
+            return;
+        }
+        self.finalized = true;
+        #[cfg(r2r__ros__distro__foxy)]
+        unsafe {
+            let allocator = rcutils_get_default_allocator();
+            rcl_lifecycle_state_machine_fini(&mut self.handle, node, &allocator);
+        }
+        #[cfg(not(r2r__ros__distro__foxy))]
+        unsafe {
+            rcl_lifecycle_state_machine_fini(&mut self.handle, node);
+        }
+    }
+}
+
+/// Runs a transition and its callback, moving the node through the
+/// intermediate state to the goal state, or back on failure.
+async fn run_transition(
+    state_machine: &SharedStateMachine, callbacks: &Mutex<Callbacks>, transition_id: u8,
+) -> Result<(CallbackReturn, String)> {
+    let (start, intermediate, label) = {
+        let mut state_machine = state_machine.lock().unwrap();
+        let start = state_machine.state;
+        let label = state_machine
+            .available_transitions()
+            .into_iter()
+            .find(|t| t.id == transition_id)
+            .map(|t| t.label)
+            .unwrap_or_default();
+        state_machine.trigger_by_id(transition_id)?;
+        (start, state_machine.state, label)
+    };
+
+    let cb_future = CallbackKind::of(intermediate).and_then(|kind| {
+        callbacks
+            .lock()
+            .unwrap()
+            .get(kind)
+            .as_mut()
+            .map(|cb| cb(start))
+    });
+    let ret = match cb_future {
+        Some(f) => f.await,
+        None => CallbackReturn::Success,
+    };
+
+    let mut state_machine = state_machine.lock().unwrap();
+    state_machine.trigger_by_label(ret.transition_label())?;
+    if state_machine.state == LifecycleState::ErrorProcessing {
+        // there is no error callback, error processing always succeeds.
+        state_machine.trigger_by_label(CallbackReturn::Success.transition_label())?;
+    }
+    Ok((ret, label))
+}
+
+/// A service created by `rcl_lifecycle`, served by the node like its own.
+struct LifecycleService<T>
+where
+    T: WrappedServiceTypeSupport,
+{
+    state_machine: SharedStateMachine,
+    // points into the state machine, which is kept alive by the Arc above.
+    rcl_handle: *const rcl_service_t,
+    sender: mpsc::Sender<ServiceRequest<T>>,
+}
+
+impl<T> Service_ for LifecycleService<T>
+where
+    T: WrappedServiceTypeSupport + 'static,
+{
+    fn handle(&self) -> &rcl_service_t {
+        unsafe { &*self.rcl_handle }
+    }
+
+    fn send_response(&mut self, request_id: rmw_request_id_t, msg: Box<dyn VoidPtr>) -> Result<()> {
+        if self.state_machine.lock().unwrap().finalized {
+            return Err(Error::RCL_RET_SERVICE_INVALID);
+        }
+        send_response(self.handle(), request_id, msg)
+    }
+
+    fn handle_request(&mut self, service: Arc<Mutex<dyn Service_>>) -> bool {
+        if let Some(request) = take_request::<T>(self.handle(), &service) {
+            if let Err(e) = self.sender.try_send(request) {
+                log::error!("warning: could not send lifecycle service request ({})", e)
+            }
+        }
+        // the service belongs to the state machine, it is never removed on its own.
+        false
+    }
+
+    fn destroy(&mut self, node: &mut rcl_node_t) {
+        // the first of the services finalizes the state machine, including all services.
+        self.state_machine.lock().unwrap().fini(node);
+    }
+}
+
+type Requests<T> = mpsc::Receiver<ServiceRequest<T>>;
+
+/// The requests to the lifecycle services, until the handler takes them.
+struct LifecycleRequests {
+    change_state: Requests<lifecycle_msgs::srv::ChangeState::Service>,
+    get_state: Requests<lifecycle_msgs::srv::GetState::Service>,
+    get_available_states: Requests<lifecycle_msgs::srv::GetAvailableStates::Service>,
+    get_available_transitions: Requests<lifecycle_msgs::srv::GetAvailableTransitions::Service>,
+    get_transition_graph: Requests<lifecycle_msgs::srv::GetAvailableTransitions::Service>,
+}
+
+fn add_service<T>(
+    node: &mut Node, state_machine: &SharedStateMachine,
+    rcl_handle: impl FnOnce(&StateMachine) -> *const rcl_service_t,
+) -> Requests<T>
+where
+    T: WrappedServiceTypeSupport + 'static,
+{
+    let (sender, receiver) = mpsc::channel::<ServiceRequest<T>>(10);
+    let rcl_handle = rcl_handle(&state_machine.lock().unwrap());
+    node.services
+        .push(Arc::new(Mutex::new(LifecycleService::<T> {
+            state_machine: state_machine.clone(),
+            rcl_handle,
+            sender,
+        })));
+    receiver
+}
+
+/// A ROS node with a managed lifecycle.
+///
+/// Dereferences to the wrapped [`Node`], so it is used (and spun) like
+/// any other node. Transition callbacks are registered with the `on_*`
+/// methods and called once
+/// [`make_lifecycle_handler`](LifecycleNode::make_lifecycle_handler)
+/// is running. Transitions without a registered callback always
+/// succeed.
+pub struct LifecycleNode {
+    node: Node,
+    state_machine: SharedStateMachine,
+    callbacks: Arc<Mutex<Callbacks>>,
+    requests: Option<LifecycleRequests>,
+}
+
+impl LifecycleNode {
+    /// Creates a ROS lifecycle node, starting in the `Unconfigured` state.
+    ///
+    /// The lifecycle services and the transition event topic are created
+    /// right away, requests are answered once the lifecycle handler runs.
+    pub fn create(ctx: Context, name: &str, namespace: &str) -> Result<LifecycleNode> {
+        use lifecycle_msgs::srv::{
+            ChangeState, GetAvailableStates, GetAvailableTransitions, GetState,
+        };
+
+        let mut node = Node::create(ctx, name, namespace)?;
+        let state_machine = Arc::new(Mutex::new(StateMachine::new(node.node_handle.as_mut())?));
+
+        let requests = LifecycleRequests {
+            change_state: add_service::<ChangeState::Service>(&mut node, &state_machine, |sm| {
+                &sm.handle.com_interface.srv_change_state
+            }),
+            get_state: add_service::<GetState::Service>(&mut node, &state_machine, |sm| {
+                &sm.handle.com_interface.srv_get_state
+            }),
+            get_available_states: add_service::<GetAvailableStates::Service>(
+                &mut node,
+                &state_machine,
+                |sm| &sm.handle.com_interface.srv_get_available_states,
+            ),
+            get_available_transitions: add_service::<GetAvailableTransitions::Service>(
+                &mut node,
+                &state_machine,
+                |sm| &sm.handle.com_interface.srv_get_available_transitions,
+            ),
+            get_transition_graph: add_service::<GetAvailableTransitions::Service>(
+                &mut node,
+                &state_machine,
+                |sm| &sm.handle.com_interface.srv_get_transition_graph,
+            ),
+        };
+
+        Ok(LifecycleNode {
+            node,
+            state_machine,
+            callbacks: Arc::new(Mutex::new(Callbacks::default())),
+            requests: Some(requests),
+        })
+    }
+
+    /// The current lifecycle state of the node.
+    pub fn get_state(&self) -> LifecycleState {
+        self.state_machine.lock().unwrap().state
+    }
+
+    /// The current lifecycle state of the node, same as [`LifecycleNode::get_state`].
+    pub fn current_state(&self) -> LifecycleState {
+        self.get_state()
+    }
+
+    /// The transitions available from the current state.
+    pub fn available_transitions(&self) -> Vec<LifecycleTransition> {
+        self.state_machine.lock().unwrap().available_transitions()
+    }
+
+    /// Trigger a transition without going through the `change_state` service.
+    ///
+    /// The transition callback is run to completion on the calling thread, so
+    /// it must not wait for anything that needs this node to be spun. The
+    /// state changes are published on the transition event topic like for
+    /// requested transitions.
+    ///
+    /// Returns an error if the transition is not available from the current
+    /// state, or if its callback did not succeed.
+    pub fn trigger_transition(&mut self, transition_id: u8) -> Result<()> {
+        match block_on(run_transition(&self.state_machine, &self.callbacks, transition_id))? {
+            (CallbackReturn::Success, _) => Ok(()),
+            (_, label) => Err(Error::LifecycleTransitionFailed {
+                transition: label,
+                state: self.get_state().to_string(),
+            }),
+        }
+    }
+
+    /// Register the callback for the configure transition.
+    ///
+    /// The callback gets the state the transition started from.
+    pub fn on_configure<F, Fut>(&mut self, cb: F)
+    where
+        F: FnMut(LifecycleState) -> Fut + Send + 'static,
+        Fut: Future<Output = CallbackReturn> + Send + 'static,
+    {
+        self.set_callback(CallbackKind::Configure, cb);
+    }
+
+    /// Register the callback for the activate transition.
+    pub fn on_activate<F, Fut>(&mut self, cb: F)
+    where
+        F: FnMut(LifecycleState) -> Fut + Send + 'static,
+        Fut: Future<Output = CallbackReturn> + Send + 'static,
+    {
+        self.set_callback(CallbackKind::Activate, cb);
+    }
+
+    /// Register the callback for the deactivate transition.
+    pub fn on_deactivate<F, Fut>(&mut self, cb: F)
+    where
+        F: FnMut(LifecycleState) -> Fut + Send + 'static,
+        Fut: Future<Output = CallbackReturn> + Send + 'static,
+    {
+        self.set_callback(CallbackKind::Deactivate, cb);
+    }
+
+    /// Register the callback for the cleanup transition.
+    pub fn on_cleanup<F, Fut>(&mut self, cb: F)
+    where
+        F: FnMut(LifecycleState) -> Fut + Send + 'static,
+        Fut: Future<Output = CallbackReturn> + Send + 'static,
+    {
+        self.set_callback(CallbackKind::Cleanup, cb);
+    }
+
+    /// Register the callback for the shutdown transitions.
+    ///
+    /// Called when shutting down from any of the primary states.
+    pub fn on_shutdown<F, Fut>(&mut self, cb: F)
+    where
+        F: FnMut(LifecycleState) -> Fut + Send + 'static,
+        Fut: Future<Output = CallbackReturn> + Send + 'static,
+    {
+        self.set_callback(CallbackKind::Shutdown, cb);
+    }
+
+    fn set_callback<F, Fut>(&mut self, kind: CallbackKind, mut cb: F)
+    where
+        F: FnMut(LifecycleState) -> Fut + Send + 'static,
+        Fut: Future<Output = CallbackReturn> + Send + 'static,
+    {
+        let cb: TransitionCallback = Box::new(move |s| Box::pin(cb(s)));
+        *self.callbacks.lock().unwrap().get(kind) = Some(cb);
+    }
+
+    /// Create a publisher that only publishes while the node is `Active`.
+    pub fn create_lifecycle_publisher<T>(
+        &mut self, topic: &str, qos_profile: QosProfile,
+    ) -> Result<LifecyclePublisher<T>>
+    where
+        T: WrappedTypesupport,
+    {
+        let publisher = self.node.create_publisher::<T>(topic, qos_profile)?;
+        Ok(LifecyclePublisher {
+            publisher,
+            state_machine: self.state_machine.clone(),
+        })
+    }
+
+    /// Creates the lifecycle service handlers for the node.
+    ///
+    /// This function returns a tuple (`Future`, `Stream`), where the
+    /// future should be spawned on onto the executor of choice. The
+    /// future answers the requests to the lifecycle services and runs
+    /// the transition callbacks. The `Stream` produces the new state
+    /// whenever the node changes state.
+    ///
+    /// The handler can only be created once, afterwards
+    /// `Error::RCL_RET_ALREADY_INIT` is returned.
+    pub fn make_lifecycle_handler(
+        &mut self,
+    ) -> Result<(impl Future<Output = ()> + Send, impl Stream<Item = LifecycleState>)> {
+        use lifecycle_msgs::srv::{
+            ChangeState, GetAvailableStates, GetAvailableTransitions, GetState,
+        };
+
+        let requests = self.requests.take().ok_or(Error::RCL_RET_ALREADY_INIT)?;
+        let mut handlers: Vec<Pin<Box<dyn Future<Output = ()> + Send>>> = Vec::new();
+        let (event_tx, event_rx) = mpsc::channel::<LifecycleState>(10);
+        self.state_machine.lock().unwrap().event_tx = Some(event_tx);
+
+        // lifecycle_msgs/srv/ChangeState
+        let state_machine = self.state_machine.clone();
+        let callbacks = self.callbacks.clone();
+        let change_state_future =
+            requests
+                .change_state
+                .for_each(move |req: ServiceRequest<ChangeState::Service>| {
+                    let state_machine = state_machine.clone();
+                    let callbacks = callbacks.clone();
+                    async move {
+                        let requested = &req.message.transition;
+                        let transition_id = state_machine.lock().unwrap().resolve(requested);
+                        let success =
+                            match run_transition(&state_machine, &callbacks, transition_id).await {
+                                Ok((ret, _)) => ret == CallbackReturn::Success,
+                                Err(e) => {
+                                    log::error!(
+                                        "unable to start transition {} ({}): {}",
+                                        requested.id,
+                                        requested.label,
+                                        e
+                                    );
+                                    false
+                                }
+                            };
+                        req.respond(ChangeState::Response { success })
+                            .expect("could not send reply to change state request");
+                    }
+                });
+        handlers.push(Box::pin(change_state_future));
+
+        // lifecycle_msgs/srv/GetState
+        let state_machine = self.state_machine.clone();
+        let get_state_future =
+            requests
+                .get_state
+                .for_each(move |req: ServiceRequest<GetState::Service>| {
+                    let state = state_machine.lock().unwrap().state;
+                    let current_state = lifecycle_msgs::msg::State {
+                        id: state.to_rcl(),
+                        label: state.label().to_string(),
+                    };
+                    req.respond(GetState::Response { current_state })
+                        .expect("could not send reply to get state request");
+                    future::ready(())
+                });
+        handlers.push(Box::pin(get_state_future));
+
+        // lifecycle_msgs/srv/GetAvailableStates
+        let state_machine = self.state_machine.clone();
+        let get_available_states_future = requests.get_available_states.for_each(
+            move |req: ServiceRequest<GetAvailableStates::Service>| {
+                let available_states = state_machine
+                    .lock()
+                    .unwrap()
+                    .states()
+                    .iter()
+                    .map(state_msg)
+                    .collect();
+                req.respond(GetAvailableStates::Response { available_states })
+                    .expect("could not send reply to get available states request");
+                future::ready(())
+            },
+        );
+        handlers.push(Box::pin(get_available_states_future));
+
+        // lifecycle_msgs/srv/GetAvailableTransitions
+        let state_machine = self.state_machine.clone();
+        let get_available_transitions_future = requests.get_available_transitions.for_each(
+            move |req: ServiceRequest<GetAvailableTransitions::Service>| {
+                let available_transitions = state_machine
+                    .lock()
+                    .unwrap()
+                    .valid_transitions()
+                    .iter()
+                    .map(transition_description)
+                    .collect();
+                req.respond(GetAvailableTransitions::Response {
+                    available_transitions,
+                })
+                .expect("could not send reply to get available transitions request");
+                future::ready(())
+            },
+        );
+        handlers.push(Box::pin(get_available_transitions_future));
+
+        // lifecycle_msgs/srv/GetAvailableTransitions, for all transitions
+        let state_machine = self.state_machine.clone();
+        let get_transition_graph_future = requests.get_transition_graph.for_each(
+            move |req: ServiceRequest<GetAvailableTransitions::Service>| {
+                let available_transitions = state_machine
+                    .lock()
+                    .unwrap()
+                    .transitions()
+                    .iter()
+                    .map(transition_description)
+                    .collect();
+                req.respond(GetAvailableTransitions::Response {
+                    available_transitions,
+                })
+                .expect("could not send reply to get transition graph request");
+                future::ready(())
+            },
+        );
+        handlers.push(Box::pin(get_transition_graph_future));
+
+        // we don't care about the result, the futures will not complete anyway.
+        Ok((join_all(handlers).map(|_| ()), event_rx))
+    }
+}
+
+impl Deref for LifecycleNode {
+    type Target = Node;
+
+    fn deref(&self) -> &Node {
+        &self.node
+    }
+}
+
+impl DerefMut for LifecycleNode {
+    fn deref_mut(&mut self) -> &mut Node {
+        &mut self.node
+    }
+}
+
+/// A ROS publisher belonging to a [`LifecycleNode`].
+///
+/// Messages are only published while the node is in the `Active`
+/// state, otherwise they are silently dropped.
+#[derive(Clone)]
+pub struct LifecyclePublisher<T>
+where
+    T: WrappedTypesupport,
+{
+    publisher: Publisher<T>,
+    state_machine: SharedStateMachine,
+}
+
+impl<T: 'static> LifecyclePublisher<T>
+where
+    T: WrappedTypesupport,
+{
+    /// Publish a ROS message if the node is active.
+    pub fn publish(&self, msg: &T) -> Result<()> {
+        if !self.is_activated() {
+            return Ok(());
+        }
+        self.publisher.publish(msg)
+    }
+
+    /// Returns true if published messages are currently sent out.
+    pub fn is_activated(&self) -> bool {
+        self.state_machine.lock().unwrap().state == LifecycleState::Active
+    }
+}
+
+impl<T> std::fmt::Debug for LifecyclePublisher<T>
+where
+    T: WrappedTypesupport,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LifecyclePublisher")
+            .field("publisher", &self.publisher)
+            .field(
+                "activated",
+                &(self.state_machine.lock().unwrap().state == LifecycleState::Active),
+            )
+            .finish()
+    }
+}
@@ -0,0 +1,63 @@
+use crate::{
+    error::Result,
+    introspection::diff_native,
+    msg_types::{VoidPtr, WrappedNativeMsg, WrappedTypesupport},
+};
+
+/// The fields in which two messages differ, one line per field.
+///
+/// The fields are walked with the introspection type support of the message,
+/// in the order of the message definition. Each line has the path of the
+/// field and both values, e.g. `position.x: 1.0 != 2.0`. Fields of nested
+/// messages are joined with `.`, and elements of arrays are given by index.
+/// Used by [`assert_message_eq!`](crate::assert_message_eq).
+pub fn message_diff<T: WrappedTypesupport + 'static>(a: &T, b: &T) -> Result<Vec<String>> {
+    let a = WrappedNativeMsg::<T>::from(a);
+    let b = WrappedNativeMsg::<T>::from(b);
+    diff_native(T::get_ts(), a.void_ptr(), b.void_ptr())
+}
+
+/// Asserts that two messages are equal, like `assert_eq!`.
+///
+/// On failure the differing fields are listed, see [`message_diff`](crate::message_diff).
+///
+/// ``` rust,ignore
+/// r2r::assert_message_eq!(received, expected);
+/// ```
+#[macro_export]
+macro_rules! assert_message_eq {
+    ($left:expr, $right:expr $(,)?) => {{
+        match (&$left, &$right) {
+            (left, right) => {
+                if left != right {
+                    panic!(
+                        "assertion `left == right` failed, the messages differ in:\n  {}",
+                        $crate::message_diff(left, right)
+                            .map(|diff| diff.join("\n  "))
+                            .unwrap_or_else(|err| format!("(no field diff: {})", err))
+                    );
+                }
+            }
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry_msgs::msg::Pose;
+
+    #[test]
+    fn test_message_diff() {
+        let a = Pose::default();
+        let mut b = Pose::default();
+        assert!(message_diff(&a, &b).unwrap().is_empty());
+        b.position.y = 2.5;
+        // the default orientation is the identity, w = 1.0.
+        b.orientation.w = 0.5;
+        assert_eq!(
+            message_diff(&a, &b).unwrap(),
+            vec!["position.y: 0.0 != 2.5", "orientation.w: 1.0 != 0.5"]
+        );
+    }
+}
@@ -149,6 +149,18 @@ pub trait WrappedTypesupport:
     }
 }
 
+/// Serialize a message to CDR bytes, the same encoding as used on the wire.
+///
+/// Useful for e.g. bridging to other transports or writing bag files.
+pub fn to_cdr<T: WrappedTypesupport>(msg: &T) -> Result<Vec<u8>> {
+    msg.to_serialized_bytes()
+}
+
+/// Deserialize a message from CDR bytes, e.g. created by [`to_cdr`].
+pub fn from_cdr<T: WrappedTypesupport>(bytes: &[u8]) -> Result<T> {
+    T::from_serialized_bytes(bytes)
+}
+
 pub trait WrappedServiceTypeSupport: Debug + Clone {
     type Request: WrappedTypesupport;
     type Response: WrappedTypesupport;
@@ -217,6 +229,36 @@ pub trait VoidPtr {
     fn void_ptr_mut(&mut self) -> *mut std::os::raw::c_void;
 }
 
+/// Get the type name, e.g. `"std_msgs/msg/String"`, of a message type support.
+///
+/// The name is looked up using the introspection type support, `None` is
+/// returned if that is not available.
+pub(crate) fn message_type_name(ts: &rosidl_message_type_support_t) -> Option<String> {
+    use r2r_rcl::*;
+
+    let identifier = b"rosidl_typesupport_introspection_c\0";
+    unsafe {
+        let introspection = get_message_typesupport_handle(ts, identifier.as_ptr() as *const _);
+        if introspection.is_null() {
+            rcutils_reset_error();
+            return None;
+        }
+        let members =
+            (*introspection).data as *const rosidl_typesupport_introspection_c__MessageMembers;
+        if members.is_null() {
+            return None;
+        }
+        let namespace = std::ffi::CStr::from_ptr((*members).message_namespace_)
+            .to_str()
+            .ok()?;
+        let name = std::ffi::CStr::from_ptr((*members).message_name_)
+            .to_str()
+            .ok()?;
+        // the namespace is e.g. "std_msgs__msg"
+        Some(format!("{}/{}", namespace.replace("__", "/"), name))
+    }
+}
+
 #[derive(Debug)]
 pub struct WrappedNativeMsgUntyped {
     pub ts: &'static rosidl_message_type_support_t,
@@ -412,6 +454,32 @@ impl WrappedNativeMsgUntyped {
         }
     }
 
+    /// This serializes the message using ROS2 methods.
+    pub fn to_serialized_bytes(&self) -> Result<Vec<u8>> {
+        use r2r_rcl::*;
+
+        SERIALIZED_MESSAGE_CACHE.with(|msg_buf| {
+            let msg_buf: &mut rcl_serialized_message_t = &mut msg_buf
+                .as_ref()
+                .map_err(|err| Error::from_rcl_error(*err))?
+                .borrow_mut();
+
+            let result = unsafe {
+                rmw_serialize(self.msg, self.ts, msg_buf as *mut rcl_serialized_message_t)
+            };
+            if result != RCL_RET_OK as i32 {
+                return Err(Error::from_rcl_error(result));
+            }
+
+            if msg_buf.buffer.is_null() {
+                Ok(Vec::new())
+            } else {
+                Ok(unsafe { std::slice::from_raw_parts(msg_buf.buffer, msg_buf.buffer_length) }
+                    .to_vec())
+            }
+        })
+    }
+
     pub fn from_serialized_bytes(&mut self, data: &[u8]) -> Result<()> {
         // TODO: Copy paste from above, should refactor later.
         use r2r_rcl::*;
@@ -773,6 +841,121 @@ mod tests {
         assert_eq!(bytes, bytes_3);
     }
 
+    #[cfg(r2r__test_msgs__msg__Arrays)]
+    fn assert_cdr_round_trip<T: WrappedTypesupport + PartialEq>(msg: T) {
+        let bytes = to_cdr(&msg).unwrap();
+        assert_eq!(bytes, to_cdr(&msg).unwrap());
+        let msg_2 = from_cdr::<T>(&bytes).unwrap();
+        assert_eq!(msg, msg_2);
+    }
+
+    #[cfg(r2r__test_msgs__msg__Arrays)]
+    #[test]
+    fn test_cdr_round_trip() {
+        assert_cdr_round_trip(test_msgs::msg::BasicTypes {
+            bool_value: true,
+            byte_value: 0xab,
+            char_value: b'x',
+            float32_value: 1.5,
+            float64_value: -2.25,
+            int8_value: -8,
+            uint8_value: 8,
+            int16_value: -16,
+            uint16_value: 16,
+            int32_value: -32,
+            uint32_value: 32,
+            int64_value: i64::MIN,
+            uint64_value: u64::MAX,
+        });
+        assert_cdr_round_trip(test_msgs::msg::Strings {
+            string_value: "hello wörld".to_string(),
+            ..Default::default()
+        });
+
+        assert_cdr_round_trip(test_msgs::msg::Arrays {
+            int32_values: vec![1, -2, 3],
+            float64_values: vec![0.5, 1.5, 2.5],
+            string_values: vec!["a".into(), "".into(), "c".into()],
+            ..Default::default()
+        });
+        assert_cdr_round_trip(test_msgs::msg::BoundedSequences {
+            bool_values: vec![true, false],
+            int16_values: vec![-1, 1, 300],
+            string_values: vec!["bounded".into()],
+            ..Default::default()
+        });
+        assert_cdr_round_trip(test_msgs::msg::UnboundedSequences {
+            byte_values: (0..=255).collect(),
+            uint64_values: vec![u64::MAX; 100],
+            string_values: vec!["x".repeat(1000)],
+            ..Default::default()
+        });
+    }
+
+    #[cfg(r2r__test_msgs__msg__Arrays)]
+    #[test]
+    fn test_cdr_round_trip_extremes() {
+        assert_cdr_round_trip(test_msgs::msg::BasicTypes::default());
+        assert_cdr_round_trip(test_msgs::msg::BasicTypes {
+            bool_value: true,
+            byte_value: u8::MAX,
+            char_value: u8::MAX,
+            float32_value: f32::MAX,
+            float64_value: f64::MAX,
+            int8_value: i8::MAX,
+            uint8_value: u8::MAX,
+            int16_value: i16::MAX,
+            uint16_value: u16::MAX,
+            int32_value: i32::MAX,
+            uint32_value: u32::MAX,
+            int64_value: i64::MAX,
+            uint64_value: u64::MAX,
+        });
+        assert_cdr_round_trip(test_msgs::msg::BasicTypes {
+            float32_value: f32::MIN,
+            float64_value: f64::MIN,
+            int8_value: i8::MIN,
+            int16_value: i16::MIN,
+            int32_value: i32::MIN,
+            int64_value: i64::MIN,
+            ..Default::default()
+        });
+
+        // odd lengths, so that the fields after them are misaligned.
+        assert_cdr_round_trip(test_msgs::msg::Nested {
+            basic_types_value: test_msgs::msg::BasicTypes {
+                bool_value: true,
+                uint64_value: u64::MAX,
+                ..Default::default()
+            },
+        });
+        assert_cdr_round_trip(test_msgs::msg::UnboundedSequences {
+            bool_values: vec![true; 3],
+            string_values: vec!["a".into(), "bcd".into(), "".into()],
+            float64_values: vec![f64::MAX, f64::MIN],
+            basic_types_values: vec![test_msgs::msg::BasicTypes::default(); 3],
+            ..Default::default()
+        });
+        assert_cdr_round_trip(test_msgs::msg::Strings {
+            string_value: "x".repeat(1 << 16),
+            bounded_string_value: "y".repeat(22),
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn test_cdr_round_trip_all_types() {
+        // every message type, with default values.
+        for type_name in WrappedNativeMsgUntyped::type_names() {
+            let msg = WrappedNativeMsgUntyped::new_from(type_name).unwrap();
+            let bytes = msg.to_serialized_bytes().unwrap();
+            let mut msg_2 = WrappedNativeMsgUntyped::new_from(type_name).unwrap();
+            msg_2.from_serialized_bytes(&bytes).unwrap();
+            assert_eq!(msg.to_json().unwrap(), msg_2.to_json().unwrap(), "{type_name}");
+            assert_eq!(bytes, msg_2.to_serialized_bytes().unwrap(), "{type_name}");
+        }
+    }
+
     #[cfg(r2r__test_msgs__msg__Defaults)]
     #[test]
     fn test_untyped_json_default() {
@@ -0,0 +1,280 @@
+//! Validation and expansion of topic, service and node names.
+//!
+//! See the [ROS 2 design article](https://design.ros2.org/articles/topic_and_service_names.html)
+//! on names. [`Node::resolve_topic_name`](crate::Node::resolve_topic_name) also applies
+//! the remapping rules of a node.
+
+use std::ffi::{CStr, CString};
+#[cfg(not(feature = "doc-only"))]
+use std::os::raw::{c_char, c_int, c_void};
+
+use crate::error::*;
+#[cfg(not(feature = "doc-only"))]
+use r2r_rcl::*;
+
+/// Check that `name` is a valid topic name.
+///
+/// Relative names (`foo`) and home-relative names (`~/foo`) are accepted, but
+/// as their length depends on the node they are expanded for, only fully
+/// qualified names (`/foo`) are checked against the maximum length.
+pub fn validate_topic_name(name: &str) -> Result<()> {
+    validate_name(name).map_err(|reason| Error::InvalidTopicName {
+        name: name.to_owned(),
+        reason,
+    })
+}
+
+/// Check that `name` is a valid service name.
+///
+/// Service names follow the same rules as topic names, see [`validate_topic_name`].
+pub fn validate_service_name(name: &str) -> Result<()> {
+    validate_name(name).map_err(|reason| Error::InvalidServiceName {
+        name: name.to_owned(),
+        reason,
+    })
+}
+
+/// Check that `name` is a valid node name, e.g. `"my_node"`.
+pub fn validate_node_name(name: &str) -> Result<()> {
+    let invalid = |reason| Error::InvalidNodeName {
+        name: name.to_owned(),
+        reason,
+    };
+    let c_name = CString::new(name).map_err(|_| invalid("contains a null character".into()))?;
+    match check_node_name(&c_name)? {
+        Some(reason) => Err(invalid(reason)),
+        None => Ok(()),
+    }
+}
+
+/// Check that `namespace` is a valid, fully qualified namespace, e.g. `"/robot"`.
+pub fn validate_namespace(namespace: &str) -> Result<()> {
+    let invalid = |reason| Error::InvalidNamespace {
+        namespace: namespace.to_owned(),
+        reason,
+    };
+    let c_namespace =
+        CString::new(namespace).map_err(|_| invalid("contains a null character".into()))?;
+    match check_namespace(&c_namespace)? {
+        Some(reason) => Err(invalid(reason)),
+        None => Ok(()),
+    }
+}
+
+/// Expand `name` to a fully qualified topic name for the node `node_name`
+/// in `namespace`, e.g. `"~/foo"` becomes `"/namespace/node_name/foo"`.
+///
+/// Unlike [`Node::resolve_topic_name`](crate::Node::resolve_topic_name), no
+/// remapping rules are applied.
+pub fn expand_topic_name(name: &str, node_name: &str, namespace: &str) -> Result<String> {
+    validate_topic_name(name)?;
+    let expanded = expand_name(name, node_name, namespace)?;
+    validate_topic_name(&expanded)?;
+    Ok(expanded)
+}
+
+/// Expand `name` to a fully qualified service name, see [`expand_topic_name`].
+pub fn expand_service_name(name: &str, node_name: &str, namespace: &str) -> Result<String> {
+    validate_service_name(name)?;
+    let expanded = expand_name(name, node_name, namespace)?;
+    validate_service_name(&expanded)?;
+    Ok(expanded)
+}
+
+// The name functions of rcl and rmw. The saved bindings of doc-only builds
+// do not include their headers, so there they are stubs.
+
+/// Returns the reason if the node name is invalid.
+#[cfg(not(feature = "doc-only"))]
+fn check_node_name(c_name: &CStr) -> Result<Option<String>> {
+    let mut result: c_int = 0;
+    let mut invalid_index = 0;
+    let ret = unsafe { rmw_validate_node_name(c_name.as_ptr(), &mut result, &mut invalid_index) };
+    if ret != RMW_RET_OK as i32 {
+        return Err(Error::from_rcl_error(ret));
+    }
+    if result != RMW_NODE_NAME_VALID as c_int {
+        let reason = unsafe { rmw_node_name_validation_result_string(result) };
+        return Ok(Some(validation_reason(reason, invalid_index)));
+    }
+    Ok(None)
+}
+
+/// Returns the reason if the namespace is invalid.
+#[cfg(not(feature = "doc-only"))]
+fn check_namespace(c_namespace: &CStr) -> Result<Option<String>> {
+    let mut result: c_int = 0;
+    let mut invalid_index = 0;
+    let ret =
+        unsafe { rmw_validate_namespace(c_namespace.as_ptr(), &mut result, &mut invalid_index) };
+    if ret != RMW_RET_OK as i32 {
+        return Err(Error::from_rcl_error(ret));
+    }
+    if result != RMW_NAMESPACE_VALID as c_int {
+        let reason = unsafe { rmw_namespace_validation_result_string(result) };
+        return Ok(Some(validation_reason(reason, invalid_index)));
+    }
+    Ok(None)
+}
+
+#[cfg(not(feature = "doc-only"))]
+fn validation_reason(reason: *const c_char, invalid_index: usize) -> String {
+    let reason = if reason.is_null() {
+        "invalid name".into()
+    } else {
+        unsafe { CStr::from_ptr(reason) }.to_string_lossy()
+    };
+    format!("{} (at index {})", reason, invalid_index)
+}
+
+/// Returns the reason if the name is invalid.
+#[cfg(not(feature = "doc-only"))]
+fn validate_name(name: &str) -> std::result::Result<(), String> {
+    let c_name = CString::new(name).map_err(|_| "contains a null character".to_owned())?;
+    let mut result: c_int = 0;
+    let mut invalid_index = 0;
+
+    let ret = unsafe { rcl_validate_topic_name(c_name.as_ptr(), &mut result, &mut invalid_index) };
+    if ret != RCL_RET_OK as i32 {
+        return Err(Error::from_rcl_error(ret).to_string());
+    }
+    if result != RCL_TOPIC_NAME_VALID as c_int {
+        let reason = unsafe { rcl_topic_name_validation_result_string(result) };
+        return Err(validation_reason(reason, invalid_index));
+    }
+
+    // the remaining rules, such as the maximum length, only apply to fully qualified names.
+    if name.starts_with('/') {
+        let ret = unsafe {
+            rmw_validate_full_topic_name(c_name.as_ptr(), &mut result, &mut invalid_index)
+        };
+        if ret != RMW_RET_OK as i32 {
+            return Err(Error::from_rcl_error(ret).to_string());
+        }
+        if result != RMW_TOPIC_VALID as c_int {
+            let reason = unsafe { rmw_full_topic_name_validation_result_string(result) };
+            return Err(validation_reason(reason, invalid_index));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "doc-only"))]
+fn expand_name(name: &str, node_name: &str, namespace: &str) -> Result<String> {
+    let c_name = CString::new(name).map_err(|_| Error::RCL_RET_INVALID_ARGUMENT)?;
+    let c_node_name = CString::new(node_name).map_err(|_| Error::RCL_RET_NODE_INVALID_NAME)?;
+    let c_namespace = CString::new(namespace).map_err(|_| Error::RCL_RET_NODE_INVALID_NAMESPACE)?;
+    let allocator = unsafe { rcutils_get_default_allocator() };
+
+    let mut substitutions = unsafe { rcutils_get_zero_initialized_string_map() };
+    let ret = unsafe { rcutils_string_map_init(&mut substitutions, 0, allocator) };
+    if ret != RCL_RET_OK as i32 {
+        return Err(Error::from_rcl_error(ret));
+    }
+
+    let mut output: *mut c_char = std::ptr::null_mut();
+    let ret = unsafe {
+        let ret = rcl_get_default_topic_name_substitutions(&mut substitutions);
+        if ret == RCL_RET_OK as i32 {
+            rcl_expand_topic_name(
+                c_name.as_ptr(),
+                c_node_name.as_ptr(),
+                c_namespace.as_ptr(),
+                &substitutions,
+                allocator,
+                &mut output,
+            )
+        } else {
+            ret
+        }
+    };
+    unsafe {
+        rcutils_string_map_fini(&mut substitutions);
+    }
+    if ret != RCL_RET_OK as i32 {
+        return Err(Error::from_rcl_error(ret));
+    }
+
+    let expanded = unsafe { CStr::from_ptr(output) }
+        .to_str()
+        .unwrap_or("")
+        .to_owned();
+    if let Some(deallocate) = allocator.deallocate {
+        unsafe { deallocate(output as *mut c_void, allocator.state) };
+    }
+    Ok(expanded)
+}
+
+#[cfg(feature = "doc-only")]
+fn check_node_name(_c_name: &CStr) -> Result<Option<String>> {
+    Err(Error::UnsupportedFeature("name validation"))
+}
+
+#[cfg(feature = "doc-only")]
+fn check_namespace(_c_namespace: &CStr) -> Result<Option<String>> {
+    Err(Error::UnsupportedFeature("name validation"))
+}
+
+#[cfg(feature = "doc-only")]
+fn validate_name(_name: &str) -> std::result::Result<(), String> {
+    Err(Error::UnsupportedFeature("name validation").to_string())
+}
+
+#[cfg(feature = "doc-only")]
+fn expand_name(_name: &str, _node_name: &str, _namespace: &str) -> Result<String> {
+    Err(Error::UnsupportedFeature("name expansion"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_topic_name() {
+        assert!(validate_topic_name("/foo").is_ok());
+        assert!(validate_topic_name("foo").is_ok());
+        assert!(validate_topic_name("~/foo").is_ok());
+        assert!(validate_topic_name("foo/bar_2").is_ok());
+
+        assert!(matches!(validate_topic_name(""), Err(Error::InvalidTopicName { .. })));
+        assert!(matches!(validate_topic_name("foo bar"), Err(Error::InvalidTopicName { .. })));
+        assert!(matches!(validate_topic_name("/foo/"), Err(Error::InvalidTopicName { .. })));
+        assert!(matches!(validate_topic_name("/2foo"), Err(Error::InvalidTopicName { .. })));
+        assert!(matches!(validate_topic_name("foo~"), Err(Error::InvalidTopicName { .. })));
+
+        let too_long = format!("/{}", "a".repeat(300));
+        assert!(matches!(validate_topic_name(&too_long), Err(Error::InvalidTopicName { .. })));
+        assert!(matches!(
+            validate_service_name(&too_long),
+            Err(Error::InvalidServiceName { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_node_name() {
+        assert!(validate_node_name("node").is_ok());
+        assert!(validate_node_name("node_2").is_ok());
+        assert!(matches!(validate_node_name(""), Err(Error::InvalidNodeName { .. })));
+        assert!(matches!(validate_node_name("2node"), Err(Error::InvalidNodeName { .. })));
+        assert!(matches!(validate_node_name("my/node"), Err(Error::InvalidNodeName { .. })));
+
+        assert!(validate_namespace("/").is_ok());
+        assert!(validate_namespace("/robot/arm").is_ok());
+        assert!(matches!(validate_namespace("robot"), Err(Error::InvalidNamespace { .. })));
+        assert!(matches!(validate_namespace("/robot/"), Err(Error::InvalidNamespace { .. })));
+    }
+
+    #[test]
+    fn test_expand_topic_name() {
+        assert_eq!(expand_topic_name("/foo", "node", "/ns").unwrap(), "/foo");
+        assert_eq!(expand_topic_name("foo", "node", "/ns").unwrap(), "/ns/foo");
+        assert_eq!(expand_topic_name("~/foo", "node", "/ns").unwrap(), "/ns/node/foo");
+        assert_eq!(expand_topic_name("{node}/foo", "node", "/").unwrap(), "/node/foo");
+        assert_eq!(expand_service_name("~/get", "node", "/").unwrap(), "/node/get");
+
+        assert!(expand_topic_name("foo bar", "node", "/ns").is_err());
+        assert!(expand_topic_name("foo", "node", "ns").is_err());
+        let long_namespace = format!("/{}", "a".repeat(300));
+        assert!(expand_topic_name("foo", "node", &long_namespace).is_err());
+    }
+}
@@ -0,0 +1,144 @@
+use crate::error::*;
+use r2r_rcl::*;
+
+/// The transport protocol of a [`NetworkFlowEndpoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransportProtocol {
+    Unknown,
+    Udp,
+    Tcp,
+}
+
+/// The internet protocol of a [`NetworkFlowEndpoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InternetProtocol {
+    Unknown,
+    Ipv4,
+    Ipv6,
+}
+
+/// A network flow endpoint of a publisher or subscription.
+///
+/// Describes how the data of the endpoint travels on the network, e.g. to
+/// configure time-sensitive networking. See
+/// [`Publisher::get_network_flow_endpoints`](crate::Publisher::get_network_flow_endpoints) and
+/// [`Node::get_subscription_network_flow_endpoints`](crate::Node::get_subscription_network_flow_endpoints).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NetworkFlowEndpoint {
+    pub transport_protocol: TransportProtocol,
+    pub internet_protocol: InternetProtocol,
+    pub transport_port: u16,
+    /// The IPv6 flow label, zero if not used.
+    pub flow_label: u32,
+    /// The differentiated services code point, zero if not used.
+    pub dscp: u8,
+    pub internet_address: String,
+}
+
+// the endpoints can not be queried on foxy, and the saved bindings of doc-only
+// builds do not include rcl/network_flow_endpoints.h.
+#[cfg(not(any(r2r__ros__distro__foxy, feature = "doc-only")))]
+impl From<&rcl_network_flow_endpoint_t> for NetworkFlowEndpoint {
+    fn from(endpoint: &rcl_network_flow_endpoint_t) -> Self {
+        let transport_protocol = match endpoint.transport_protocol {
+            rmw_transport_protocol_t::RMW_TRANSPORT_PROTOCOL_UDP => TransportProtocol::Udp,
+            rmw_transport_protocol_t::RMW_TRANSPORT_PROTOCOL_TCP => TransportProtocol::Tcp,
+            _ => TransportProtocol::Unknown,
+        };
+        let internet_protocol = match endpoint.internet_protocol {
+            rmw_internet_protocol_t::RMW_INTERNET_PROTOCOL_IPV4 => InternetProtocol::Ipv4,
+            rmw_internet_protocol_t::RMW_INTERNET_PROTOCOL_IPV6 => InternetProtocol::Ipv6,
+            _ => InternetProtocol::Unknown,
+        };
+        let address = &endpoint.internet_address;
+        // the address is nul terminated, unless it fills the whole buffer.
+        let len = address
+            .iter()
+            .position(|c| *c == 0)
+            .unwrap_or(address.len());
+        let bytes = unsafe { std::slice::from_raw_parts(address.as_ptr() as *const u8, len) };
+        NetworkFlowEndpoint {
+            transport_protocol,
+            internet_protocol,
+            transport_port: endpoint.transport_port,
+            flow_label: endpoint.flow_label,
+            dscp: endpoint.dscp,
+            internet_address: String::from_utf8_lossy(bytes).into_owned(),
+        }
+    }
+}
+
+// converts the endpoints filled in by `get`.
+#[cfg(not(any(r2r__ros__distro__foxy, feature = "doc-only")))]
+fn collect_endpoints(
+    function: &'static str,
+    get: impl FnOnce(*mut rcutils_allocator_t, *mut rcl_network_flow_endpoint_array_t) -> rcl_ret_t,
+) -> Result<Vec<NetworkFlowEndpoint>> {
+    let mut allocator = unsafe { rcutils_get_default_allocator() };
+    let mut array = unsafe { rmw_get_zero_initialized_network_flow_endpoint_array() };
+    let ret = get(&mut allocator, &mut array);
+    if ret == RCL_RET_UNSUPPORTED as i32 {
+        unsafe { rcutils_reset_error() };
+        return Err(Error::UnsupportedFeature("network_flow_endpoints"));
+    }
+    if ret != RCL_RET_OK as i32 {
+        return Err(Error::from_rcl_call(function, ret));
+    }
+    let endpoints = if array.network_flow_endpoint.is_null() {
+        vec![]
+    } else {
+        unsafe { std::slice::from_raw_parts(array.network_flow_endpoint, array.size) }
+            .iter()
+            .map(NetworkFlowEndpoint::from)
+            .collect()
+    };
+    unsafe {
+        rmw_network_flow_endpoint_array_fini(&mut array);
+    }
+    Ok(endpoints)
+}
+
+#[cfg(not(any(r2r__ros__distro__foxy, feature = "doc-only")))]
+pub(crate) fn publisher_network_flow_endpoints(
+    publisher: &rcl_publisher_t,
+) -> Result<Vec<NetworkFlowEndpoint>> {
+    collect_endpoints("rcl_publisher_get_network_flow_endpoints", |allocator, array| unsafe {
+        rcl_publisher_get_network_flow_endpoints(publisher, allocator, array)
+    })
+}
+
+#[cfg(not(any(r2r__ros__distro__foxy, feature = "doc-only")))]
+pub(crate) fn subscription_network_flow_endpoints(
+    subscription: &rcl_subscription_t,
+) -> Result<Vec<NetworkFlowEndpoint>> {
+    collect_endpoints("rcl_subscription_get_network_flow_endpoints", |allocator, array| unsafe {
+        rcl_subscription_get_network_flow_endpoints(subscription, allocator, array)
+    })
+}
+
+#[cfg(any(r2r__ros__distro__foxy, feature = "doc-only"))]
+pub(crate) fn publisher_network_flow_endpoints(
+    _publisher: &rcl_publisher_t,
+) -> Result<Vec<NetworkFlowEndpoint>> {
+    Err(Error::UnsupportedFeature("network_flow_endpoints"))
+}
+
+#[cfg(any(r2r__ros__distro__foxy, feature = "doc-only"))]
+pub(crate) fn subscription_network_flow_endpoints(
+    _subscription: &rcl_subscription_t,
+) -> Result<Vec<NetworkFlowEndpoint>> {
+    Err(Error::UnsupportedFeature("network_flow_endpoints"))
+}
+
+// the rmw requirement for the `unique_network_flow_endpoint` options. unique
+// endpoints are only optionally required, as not all middlewares support them.
+#[cfg(not(r2r__ros__distro__foxy))]
+pub(crate) fn unique_network_flow_endpoints_requirement(
+    unique: bool,
+) -> rmw_unique_network_flow_endpoints_requirement_t {
+    if unique {
+        rmw_unique_network_flow_endpoints_requirement_t::RMW_UNIQUE_NETWORK_FLOW_ENDPOINTS_OPTIONALLY_REQUIRED
+    } else {
+        rmw_unique_network_flow_endpoints_requirement_t::RMW_UNIQUE_NETWORK_FLOW_ENDPOINTS_NOT_REQUIRED
+    }
+}
@@ -1,39 +1,57 @@
 use futures::{
     channel::{mpsc, oneshot},
     future::{self, join_all, FutureExt, TryFutureExt},
-    stream::{Stream, StreamExt},
+    stream::{FuturesUnordered, Stream, StreamExt},
 };
 use indexmap::IndexMap;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet, VecDeque},
     ffi::{CStr, CString},
     future::Future,
     marker::PhantomPinned,
     mem::MaybeUninit,
-    pin::Pin,
-    sync::{Arc, Mutex},
-    time::Duration,
+    path::Path,
+    pin::{pin, Pin},
+    sync::{Arc, Mutex, OnceLock, RwLock, Weak},
+    time::{Duration, Instant},
 };
 
 use r2r_actions::*;
 use r2r_rcl::*;
 
+#[cfg(r2r__std_msgs__msg__Header)]
+use crate::envelope::{starts_with_header, MessageEnvelope};
 #[cfg(r2r__rosgraph_msgs__msg__Clock)]
 use crate::time_source::TimeSource;
+#[cfg(r2r__statistics_msgs__msg__MetricsMessage)]
+use crate::topic_statistics::{TopicStatistics, TypedSubscriberWithStatistics};
 use crate::{
     action_clients::*,
     action_clients_untyped::*,
     action_servers::*,
+    callback_groups::{CallbackGroup, CallbackGroupKind},
     clients::*,
     clocks::*,
     context::*,
     error::*,
-    msg_types::{generated_msgs::rcl_interfaces, *},
+    executor_stats::{self, ExecutorStats},
+    gid::Gid,
+    graph_events::*,
+    guard_conditions::*,
+    msg_types::{
+        generated_msgs::{builtin_interfaces, rcl_interfaces},
+        *,
+    },
+    names::{validate_namespace, validate_node_name},
+    network_flow::{subscription_network_flow_endpoints, NetworkFlowEndpoint},
     parameters::*,
     publishers::*,
     qos::QosProfile,
+    qos_events::*,
+    service_spy::{ServiceInteraction, ServiceSpySubscriber},
     services::*,
     subscribers::*,
+    wait_set::WaitSetCounts,
 };
 
 /// A ROS Node.
@@ -42,14 +60,28 @@ use crate::{
 /// from the ROS network into your ros application, `spin_once` should
 /// be called continously.
 pub struct Node {
-    context: Context,
+    pub(crate) context: Context,
     /// ROS parameters.
     pub params: Arc<Mutex<IndexMap<String, Parameter>>>,
+    // callbacks validating parameter changes from external sources
+    parameter_callbacks: Arc<Mutex<Vec<ParameterCallback>>>,
+    // callbacks validating all parameters changed by one request together
+    parameters_callbacks: Arc<Mutex<Vec<ParametersCallback>>>,
+    // the setter of the parameter services, see set_parameters_atomically.
+    // shared with the parameter groups of the node.
+    parameter_setter: Arc<Mutex<Option<ParameterSetter>>>,
+    // descriptors of parameters declared with declare_parameter
+    parameter_descriptors: Arc<Mutex<HashMap<String, ParameterDescriptor>>>,
+    // the parameter handles fed by one /parameter_events subscription, see
+    // declare_parameter_live
+    parameter_event_senders: Option<ParameterEventSenders>,
+    // cleared before the node is finalized, see NodeGraphHandle
+    graph_alive: Arc<Mutex<bool>>,
     pub(crate) node_handle: Box<rcl_node_t>,
     // the node owns the subscribers
     pub(crate) subscribers: Vec<Box<dyn Subscriber_>>,
     // services,
-    services: Vec<Arc<Mutex<dyn Service_>>>,
+    pub(crate) services: Vec<Arc<Mutex<dyn Service_>>>,
     // service clients
     clients: Vec<Arc<Mutex<dyn Client_>>>,
     // action clients
@@ -60,6 +92,27 @@ pub struct Node {
     timers: Vec<Timer_>,
     // and the publishers, whom we allow to be shared.. hmm.
     pubs: Vec<Arc<Publisher_>>,
+    // guard conditions that can be triggered from other threads
+    guard_conditions: Vec<Arc<GuardCondition_>>,
+    // qos events of the publishers and subscribers
+    qos_events: Vec<QosEvent_>,
+    // streams of graph events, see subscribe_to_graph_events
+    graph_event_subscribers: Vec<GraphEventSubscriber>,
+    // futures waiting for graph changes, see wait_for_graph_change
+    graph_waiters: Vec<GraphWaiter>,
+    // futures driven by spin_once, e.g. async service handlers
+    async_tasks: FuturesUnordered<Pin<Box<dyn Future<Output = ()> + Send>>>,
+    // guard condition waking up spin_once when an async task can make progress
+    async_task_waker: Option<Arc<GuardCondition_>>,
+    // create parameter services in make_parameter_handler
+    start_parameter_services: bool,
+    // see NodeOptions::check_type_on_connect
+    check_type_on_connect: bool,
+    // callback latencies measured by spin_once, see enable_statistics
+    statistics: Arc<OnceLock<Arc<RwLock<ExecutorStats>>>>,
+    // statistics of subscriptions with topic statistics enabled
+    #[cfg(r2r__statistics_msgs__msg__MetricsMessage)]
+    topic_statistics: Vec<Arc<Mutex<TopicStatistics>>>,
     // RosTime clock used by all timers created by create_timer()
     ros_clock: Arc<Mutex<Clock>>,
     // time source that provides simulated time
@@ -69,6 +122,358 @@ pub struct Node {
 
 unsafe impl Send for Node {}
 
+/// Options used when creating a [`Node`].
+#[derive(Debug, Clone)]
+pub struct NodeOptions {
+    /// Use the arguments given when creating the [`Context`], e.g. remappings.
+    pub use_global_arguments: bool,
+    /// Publish log messages of the node on `/rosout`.
+    pub enable_rosout: bool,
+    /// Create the parameter services in [`Node::make_parameter_handler`].
+    pub start_parameter_services: bool,
+    /// Add all parameters given on the command line (or in parameter files
+    /// passed as arguments) to the node's parameters.
+    ///
+    /// Unlike rclcpp this defaults to true, as that is what r2r has always done.
+    pub automatically_declare_initial_parameters: bool,
+    /// Command line style arguments for this node only, e.g.
+    /// `["--ros-args", "-r", "/chatter:=/talk"]`. They take precedence
+    /// over the global arguments.
+    pub arguments: Vec<String>,
+    /// Refuse to create publishers and subscriptions with another message
+    /// type than the endpoints already on the topic, returning
+    /// [`Error::TypeMismatch`]. Only endpoints discovered so far are checked.
+    pub check_type_on_connect: bool,
+}
+
+impl Default for NodeOptions {
+    fn default() -> Self {
+        NodeOptions {
+            use_global_arguments: true,
+            enable_rosout: true,
+            start_parameter_services: true,
+            automatically_declare_initial_parameters: true,
+            arguments: Vec::new(),
+            check_type_on_connect: false,
+        }
+    }
+}
+
+/// Builds a [`Node`] with [`NodeOptions`] set one at a time.
+///
+/// ``` rust,ignore
+/// let node = r2r::NodeBuilder::new(ctx, "talker")
+///     .namespace("/demo")
+///     .enable_rosout(false)
+///     .build()?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct NodeBuilder {
+    ctx: Context,
+    name: String,
+    namespace: String,
+    options: NodeOptions,
+}
+
+impl NodeBuilder {
+    /// Start building a node in the root namespace with the default options.
+    pub fn new(ctx: Context, name: &str) -> NodeBuilder {
+        NodeBuilder {
+            ctx,
+            name: name.to_owned(),
+            namespace: String::new(),
+            options: NodeOptions::default(),
+        }
+    }
+
+    /// The namespace of the node.
+    pub fn namespace(mut self, namespace: &str) -> Self {
+        self.namespace = namespace.to_owned();
+        self
+    }
+
+    /// See [`NodeOptions::enable_rosout`].
+    pub fn enable_rosout(mut self, enable: bool) -> Self {
+        self.options.enable_rosout = enable;
+        self
+    }
+
+    /// See [`NodeOptions::start_parameter_services`].
+    pub fn start_parameter_services(mut self, start: bool) -> Self {
+        self.options.start_parameter_services = start;
+        self
+    }
+
+    /// See [`NodeOptions::use_global_arguments`].
+    pub fn use_global_arguments(mut self, use_global: bool) -> Self {
+        self.options.use_global_arguments = use_global;
+        self
+    }
+
+    /// See [`NodeOptions::arguments`].
+    pub fn arguments(mut self, args: Vec<String>) -> Self {
+        self.options.arguments = args;
+        self
+    }
+
+    /// Create the node.
+    ///
+    /// The name and namespace are validated first, see [`Node::create_with_options`].
+    pub fn build(self) -> Result<Node> {
+        Node::create_with_options(self.ctx, &self.name, &self.namespace, self.options)
+    }
+}
+
+type ParameterCallback =
+    Box<dyn Fn(&str, &ParameterValue) -> std::result::Result<(), String> + Send>;
+type ParametersCallback =
+    Box<dyn Fn(&[(String, ParameterValue)]) -> std::result::Result<(), String> + Send>;
+type ParameterEventSenders = Arc<Mutex<Vec<mpsc::Sender<rcl_interfaces::msg::ParameterEvent>>>>;
+
+// Validates and applies the changes requested on the set_parameters
+// services, announcing them on /parameter_events. Without the services
+// there is nothing to announce the changes on.
+#[derive(Clone)]
+pub(crate) struct ParameterSetter {
+    params: Arc<Mutex<IndexMap<String, Parameter>>>,
+    params_struct: Option<Arc<Mutex<dyn RosParams + Send>>>,
+    callbacks: Arc<Mutex<Vec<ParameterCallback>>>,
+    batch_callbacks: Arc<Mutex<Vec<ParametersCallback>>>,
+    descriptors: Arc<Mutex<HashMap<String, ParameterDescriptor>>>,
+    event_tx: Option<mpsc::Sender<(String, ParameterValue)>>,
+    batch_event_tx: Option<mpsc::Sender<BatchParameterEvent>>,
+    events_publisher: Option<Publisher<rcl_interfaces::msg::ParameterEvent>>,
+    node_name: String,
+    ros_clock: Arc<Mutex<Clock>>,
+}
+
+// the changes of one request, announced together once it is done.
+struct PendingParameterEvent {
+    msg: rcl_interfaces::msg::ParameterEvent,
+    changes: Vec<(String, ParameterValue)>,
+}
+
+impl ParameterSetter {
+    /// The reason to reject the new value, if any.
+    fn check(&self, name: &str, val: &ParameterValue) -> Option<String> {
+        let current = self
+            .params
+            .lock()
+            .unwrap()
+            .get(name)
+            .map_or(ParameterValue::NotSet, |p| p.value.clone());
+        self.descriptors
+            .lock()
+            .unwrap()
+            .get(name)
+            .and_then(|d| d.check_set(name, &current, val).err())
+            .or_else(|| {
+                self.callbacks
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .find_map(|cb| cb(name, val).err())
+            })
+    }
+
+    /// The reason to reject the new values set by one request, if any.
+    fn check_batch(&self, values: &[(String, ParameterValue)]) -> Option<String> {
+        self.batch_callbacks
+            .lock()
+            .unwrap()
+            .iter()
+            .find_map(|cb| cb(values).err())
+    }
+
+    /// Like `check`, but also checks that a parameter struct can take the value.
+    fn check_all(&self, name: &str, val: &ParameterValue) -> Option<String> {
+        if let Some(ps) = &self.params_struct {
+            match ps.lock().unwrap().get_parameter(name) {
+                Ok(v) if v.into_parameter_type() == val.into_parameter_type() => (),
+                Ok(_) => return Some(format!("wrong type for parameter {name}")),
+                Err(e) => return Some(e.to_string()),
+            }
+        }
+        self.check(name, val)
+    }
+
+    fn apply(
+        &mut self, name: &str, val: ParameterValue, parameter_event: &mut PendingParameterEvent,
+    ) -> rcl_interfaces::msg::SetParametersResult {
+        let existing = self
+            .params
+            .lock()
+            .unwrap()
+            .get(name)
+            .map(|v| v.value.clone());
+        // changed=true if new
+        let changed = existing.as_ref().map(|v| *v != val).unwrap_or(true);
+        let r = if let Some(ps) = &self.params_struct {
+            // Update parameter structure
+            let result = ps.lock().unwrap().set_parameter(name, &val);
+            if result.is_ok() {
+                // Also update Node::params
+                self.params
+                    .lock()
+                    .unwrap()
+                    .entry(name.to_owned())
+                    .and_modify(|p| p.value = val.clone());
+            }
+            rcl_interfaces::msg::SetParametersResult {
+                successful: result.is_ok(),
+                reason: result.err().map_or("".into(), |e| e.to_string()),
+            }
+        } else {
+            // No parameter structure - update only Node::params
+            self.params
+                .lock()
+                .unwrap()
+                .entry(name.to_owned())
+                .and_modify(|p| p.value = val.clone())
+                .or_insert(Parameter::new(val.clone()));
+            rcl_interfaces::msg::SetParametersResult {
+                successful: true,
+                reason: "".into(),
+            }
+        };
+        // if the value changed, send out new value on parameter event stream
+        if changed && r.successful {
+            let msg = rcl_interfaces::msg::Parameter {
+                name: name.to_owned(),
+                value: val.clone().into_parameter_value_msg(),
+            };
+            if existing.is_some() {
+                parameter_event.msg.changed_parameters.push(msg);
+            } else {
+                parameter_event.msg.new_parameters.push(msg);
+            }
+            if let Some(event_tx) = &mut self.event_tx {
+                if let Err(e) = event_tx.try_send((name.to_owned(), val.clone())) {
+                    log::debug!("Warning: could not send parameter event ({}).", e);
+                }
+            }
+            parameter_event.changes.push((name.to_owned(), val));
+        }
+        r
+    }
+
+    fn new_event(&self) -> PendingParameterEvent {
+        PendingParameterEvent {
+            msg: rcl_interfaces::msg::ParameterEvent {
+                node: self.node_name.clone(),
+                ..Default::default()
+            },
+            changes: Vec::new(),
+        }
+    }
+
+    fn publish_event(&mut self, parameter_event: PendingParameterEvent) {
+        let PendingParameterEvent {
+            msg: mut parameter_event,
+            changes,
+        } = parameter_event;
+        if changes.is_empty() {
+            return;
+        }
+        if let Some(batch_event_tx) = &mut self.batch_event_tx {
+            let batch = BatchParameterEvent {
+                node_name: self.node_name.clone(),
+                changes,
+            };
+            if let Err(e) = batch_event_tx.try_send(batch) {
+                log::debug!("Warning: could not send parameter event ({}).", e);
+            }
+        }
+        let Some(events_publisher) = &self.events_publisher else {
+            return;
+        };
+        if let Ok(now) = self.ros_clock.lock().unwrap().get_now() {
+            parameter_event.stamp = Clock::to_builtin_time(&now);
+        }
+        if let Err(e) = events_publisher.publish(&parameter_event) {
+            log::debug!("Warning: could not publish parameter event ({}).", e);
+        }
+    }
+
+    /// Set the parameters one by one, each of them may be rejected.
+    fn set(
+        &mut self, parameters: &[rcl_interfaces::msg::Parameter],
+    ) -> Vec<rcl_interfaces::msg::SetParametersResult> {
+        let mut parameter_event = self.new_event();
+        let results = parameters
+            .iter()
+            .map(|p| {
+                let val = ParameterValue::from_parameter_value_msg(p.value.clone());
+                let batch = [(p.name.clone(), val)];
+                if let Some(reason) = self
+                    .check(&p.name, &batch[0].1)
+                    .or_else(|| self.check_batch(&batch))
+                {
+                    return rcl_interfaces::msg::SetParametersResult {
+                        successful: false,
+                        reason,
+                    };
+                }
+                let [(_, val)] = batch;
+                self.apply(&p.name, val, &mut parameter_event)
+            })
+            .collect();
+        self.publish_event(parameter_event);
+        results
+    }
+
+    /// Set all parameters, or none of them if one is rejected.
+    fn set_atomically(
+        &mut self, parameters: &[rcl_interfaces::msg::Parameter],
+    ) -> rcl_interfaces::msg::SetParametersResult {
+        let values = parameters
+            .iter()
+            .map(|p| (p.name.clone(), ParameterValue::from_parameter_value_msg(p.value.clone())))
+            .collect();
+        match self.try_set_atomically(values) {
+            Ok(()) => rcl_interfaces::msg::SetParametersResult {
+                successful: true,
+                reason: "".into(),
+            },
+            Err(Error::ParameterRejected { reason, .. }) => {
+                rcl_interfaces::msg::SetParametersResult {
+                    successful: false,
+                    reason,
+                }
+            }
+            Err(e) => rcl_interfaces::msg::SetParametersResult {
+                successful: false,
+                reason: e.to_string(),
+            },
+        }
+    }
+
+    /// Set all values, or return the reason to reject them.
+    pub(crate) fn try_set_atomically(
+        &mut self, values: Vec<(String, ParameterValue)>,
+    ) -> Result<()> {
+        // everything is checked first, nothing can fail when applying.
+        let rejected = values
+            .iter()
+            .find_map(|(name, val)| self.check_all(name, val).map(|r| (name.clone(), r)))
+            .or_else(|| {
+                self.check_batch(&values).map(|r| {
+                    let names = values.iter().map(|(n, _)| n.as_str());
+                    (names.collect::<Vec<_>>().join(", "), r)
+                })
+            });
+        if let Some((name, reason)) = rejected {
+            return Err(Error::ParameterRejected { name, reason });
+        }
+        let mut parameter_event = self.new_event();
+        for (name, val) in values {
+            self.apply(&name, val, &mut parameter_event);
+        }
+        self.publish_event(parameter_event);
+        Ok(())
+    }
+}
+
 impl Node {
     /// Returns the name of the node.
     pub fn name(&self) -> Result<String> {
@@ -90,6 +495,13 @@ impl Node {
         Ok(s.to_str().unwrap_or("").to_owned())
     }
 
+    /// Check if the node is valid.
+    ///
+    /// A node becomes invalid when its context is shut down.
+    pub fn is_valid(&self) -> bool {
+        unsafe { rcl_node_is_valid(self.node_handle.as_ref()) }
+    }
+
     /// Returns the namespace of the node.
     pub fn namespace(&self) -> Result<String> {
         let cstr = unsafe { rcl_node_get_namespace(self.node_handle.as_ref()) };
@@ -101,42 +513,67 @@ impl Node {
     }
 
     fn load_params(&mut self) -> Result<()> {
-        let ctx = self.context.context_handle.lock().unwrap();
-        let mut params: Box<*mut rcl_params_t> = Box::new(std::ptr::null_mut());
+        let params = {
+            let ctx = self.context.context_handle.lock().unwrap();
+            Self::get_param_overrides(&ctx.global_arguments)?
+        };
+        self.load_param_overrides(params)?;
+
+        // arguments given in NodeOptions, applied last to take precedence.
+        let options = unsafe { rcl_node_get_options(self.node_handle.as_ref()) };
+        if !options.is_null() {
+            let params = Self::get_param_overrides(unsafe { &(*options).arguments })?;
+            self.load_param_overrides(params)?;
+        }
+        Ok(())
+    }
 
-        let ret =
-            unsafe { rcl_arguments_get_param_overrides(&ctx.global_arguments, params.as_mut()) };
+    fn get_param_overrides(arguments: &rcl_arguments_t) -> Result<*mut rcl_params_t> {
+        let mut params: *mut rcl_params_t = std::ptr::null_mut();
+        // without any arguments there is nothing to override.
+        if arguments.impl_.is_null() {
+            return Ok(params);
+        }
+
+        let ret = unsafe { rcl_arguments_get_param_overrides(arguments, &mut params) };
         if ret != RCL_RET_OK as i32 {
             log::error!("could not read parameters: {}", ret);
             return Err(Error::from_rcl_error(ret));
         }
+        Ok(params)
+    }
 
+    fn load_param_overrides(&mut self, params: *mut rcl_params_t) -> Result<()> {
+        let overrides = self.param_overrides_of_node(params)?;
+        let mut params = self.params.lock().unwrap();
+        for (key, val) in overrides {
+            params.insert(key, Parameter::new(val));
+        }
+        Ok(())
+    }
+
+    // the values of `params` for this node, in their order. `params` is
+    // finalized.
+    fn param_overrides_of_node(
+        &self, params: *mut rcl_params_t,
+    ) -> Result<Vec<(String, ParameterValue)>> {
+        let mut overrides = Vec::new();
         if params.is_null() {
-            return Ok(());
+            return Ok(overrides);
         }
 
         unsafe {
-            if (*(*params.as_ref())).node_names == std::ptr::null_mut()
-                || (*(*params.as_ref())).params == std::ptr::null_mut()
-            {
-                rcl_yaml_node_struct_fini(*params);
-                return Ok(());
+            if (*params).node_names.is_null() || (*params).params.is_null() {
+                rcl_yaml_node_struct_fini(params);
+                return Ok(overrides);
             }
         }
 
-        let node_names = unsafe {
-            std::slice::from_raw_parts(
-                (*(*params.as_ref())).node_names,
-                (*(*params.as_ref())).num_nodes,
-            )
-        };
+        let node_names =
+            unsafe { std::slice::from_raw_parts((*params).node_names, (*params).num_nodes) };
 
-        let node_params = unsafe {
-            std::slice::from_raw_parts(
-                (*(*params.as_ref())).params,
-                (*(*params.as_ref())).num_nodes,
-            )
-        };
+        let node_params =
+            unsafe { std::slice::from_raw_parts((*params).params, (*params).num_nodes) };
 
         let qualified_name = self.fully_qualified_name()?;
         let name = self.name()?;
@@ -157,7 +594,6 @@ impl Node {
             }
 
             // make key value pairs.
-            let mut params = self.params.lock().unwrap();
             if np.parameter_names != std::ptr::null_mut()
                 && np.parameter_values != std::ptr::null_mut()
             {
@@ -171,26 +607,88 @@ impl Node {
                     let s = unsafe { CStr::from_ptr(*s) };
                     let key = s.to_str().unwrap_or("");
                     let val = ParameterValue::from_rcl(v);
-                    params.insert(key.to_owned(), Parameter::new(val));
+                    overrides.push((key.to_owned(), val));
                 }
             }
         }
 
-        unsafe { rcl_yaml_node_struct_fini(*params) };
-        Ok(())
+        unsafe { rcl_yaml_node_struct_fini(params) };
+        Ok(overrides)
     }
 
     /// Creates a ROS node.
+    ///
+    /// Fails with [`Error::NodeNameCollision`] if a node with the same fully
+    /// qualified name already exists in `ctx`. Only the nodes of the same
+    /// context are checked: nodes of other contexts, or of other processes,
+    /// may still have the name.
     pub fn create(ctx: Context, name: &str, namespace: &str) -> Result<Node> {
-        let (res, node_handle) = {
+        Self::create_with_options(ctx, name, namespace, NodeOptions::default())
+    }
+
+    /// Creates a ROS node with the given [`NodeOptions`].
+    pub fn create_with_options(
+        ctx: Context, name: &str, namespace: &str, options: NodeOptions,
+    ) -> Result<Node> {
+        Self::create_internal(ctx, name, namespace, options, None)
+    }
+
+    /// Creates a ROS node using a [`MockClock`] as its ROS clock.
+    ///
+    /// Timers created with [`Node::create_timer`] and [`Node::now`] use
+    /// the mock clock, so time only passes when the clock is advanced.
+    /// The clock is not affected by the `use_sim_time` parameter.
+    pub fn create_with_clock(
+        ctx: Context, name: &str, namespace: &str, clock: &MockClock,
+    ) -> Result<Node> {
+        Self::create_internal(ctx, name, namespace, NodeOptions::default(), Some(clock.ros_clock()))
+    }
+
+    fn create_internal(
+        ctx: Context, name: &str, namespace: &str, options: NodeOptions,
+        ros_clock: Option<Arc<Mutex<Clock>>>,
+    ) -> Result<Node> {
+        validate_node_name(name)?;
+        // like rcl, relative namespaces are taken relative to the root.
+        if !namespace.is_empty() {
+            if namespace.starts_with('/') {
+                validate_namespace(namespace)?;
+            } else {
+                validate_namespace(&format!("/{namespace}"))?;
+            }
+        }
+
+        let (res, mut node_handle) = {
             let mut ctx_handle = ctx.context_handle.lock().unwrap();
 
             let c_node_name = CString::new(name).unwrap();
             let c_node_ns = CString::new(namespace).unwrap();
             let mut node_handle: Box<rcl_node_t> =
                 unsafe { Box::new(rcl_get_zero_initialized_node()) };
+            let mut node_options = unsafe { rcl_node_get_default_options() };
+            node_options.use_global_arguments = options.use_global_arguments;
+            node_options.enable_rosout = options.enable_rosout;
+            if !options.arguments.is_empty() {
+                let args = options
+                    .arguments
+                    .iter()
+                    .map(|arg| CString::new(arg.as_str()))
+                    .collect::<std::result::Result<Vec<_>, _>>()
+                    .map_err(|_| Error::RCL_RET_INVALID_ARGUMENT)?;
+                let c_args = args.iter().map(|arg| arg.as_ptr()).collect::<Vec<_>>();
+                let ret = unsafe {
+                    rcl_parse_arguments(
+                        c_args.len() as ::std::os::raw::c_int,
+                        c_args.as_ptr(),
+                        node_options.allocator,
+                        &mut node_options.arguments,
+                    )
+                };
+                if ret != RCL_RET_OK as i32 {
+                    return Err(Error::from_rcl_call("rcl_parse_arguments", ret));
+                }
+            }
             let res = unsafe {
-                let node_options = rcl_node_get_default_options();
                 rcl_node_init(
                     node_handle.as_mut(),
                     c_node_name.as_ptr(),
@@ -199,20 +697,53 @@ impl Node {
                     &node_options as *const _,
                 )
             };
+            // the node keeps a copy of the options.
+            unsafe { rcl_node_options_fini(&mut node_options) };
             (res, node_handle)
         };
 
         if res == RCL_RET_OK as i32 {
-            let ros_clock = Arc::new(Mutex::new(Clock::create(ClockType::RosTime)?));
+            #[cfg(r2r__rosgraph_msgs__msg__Clock)]
+            let mocked = ros_clock.is_some();
+            let ros_clock = match ros_clock {
+                Some(ros_clock) => ros_clock,
+                None => Arc::new(Mutex::new(Clock::create(ClockType::RosTime)?)),
+            };
             #[cfg(r2r__rosgraph_msgs__msg__Clock)]
             let time_source = {
                 let time_source = TimeSource::new();
-                time_source.attach_ros_clock(Arc::downgrade(&ros_clock))?;
+                if !mocked {
+                    time_source.attach_ros_clock(Arc::downgrade(&ros_clock))?;
+                }
                 time_source
             };
 
+            // checked after rcl_node_init, as remapping may change the name.
+            let (name, namespace, qualified_name) = unsafe {
+                let node_handle = node_handle.as_ref();
+                let to_string = |s: *const std::os::raw::c_char| {
+                    CStr::from_ptr(s).to_string_lossy().into_owned()
+                };
+                (
+                    to_string(rcl_node_get_name(node_handle)),
+                    to_string(rcl_node_get_namespace(node_handle)),
+                    to_string(rcl_node_get_fully_qualified_name(node_handle)),
+                )
+            };
+            if !ctx.add_node_name(&qualified_name) {
+                let _ctx_handle = ctx.context_handle.lock().unwrap();
+                unsafe { rcl_node_fini(node_handle.as_mut()) };
+                return Err(Error::NodeNameCollision { name, namespace });
+            }
+
             let mut node = Node {
                 params: Arc::new(Mutex::new(IndexMap::new())),
+                parameter_callbacks: Arc::new(Mutex::new(Vec::new())),
+                parameters_callbacks: Arc::new(Mutex::new(Vec::new())),
+                parameter_setter: Arc::new(Mutex::new(None)),
+                parameter_descriptors: Arc::new(Mutex::new(HashMap::new())),
+                parameter_event_senders: None,
+                graph_alive: Arc::new(Mutex::new(true)),
                 context: ctx,
                 node_handle,
                 subscribers: Vec::new(),
@@ -222,16 +753,131 @@ impl Node {
                 action_servers: Vec::new(),
                 timers: Vec::new(),
                 pubs: Vec::new(),
+                guard_conditions: Vec::new(),
+                qos_events: Vec::new(),
+                graph_event_subscribers: Vec::new(),
+                graph_waiters: Vec::new(),
+                async_tasks: FuturesUnordered::new(),
+                async_task_waker: None,
+                start_parameter_services: options.start_parameter_services,
+                check_type_on_connect: options.check_type_on_connect,
+                statistics: Arc::new(OnceLock::new()),
+                #[cfg(r2r__statistics_msgs__msg__MetricsMessage)]
+                topic_statistics: Vec::new(),
                 ros_clock,
                 #[cfg(r2r__rosgraph_msgs__msg__Clock)]
                 time_source,
             };
-            node.load_params()?;
+            if options.automatically_declare_initial_parameters {
+                node.load_params()?;
+            }
             Ok(node)
         } else {
-            log::error!("could not create node{}", res);
-            Err(Error::from_rcl_error(res))
+            let e = Error::from_rcl_call("rcl_node_init", res);
+            log::error!("could not create node: {}", e);
+            Err(e)
+        }
+    }
+
+    /// Load parameters from a ROS2 parameter YAML file.
+    ///
+    /// This is the programmatic equivalent of `--params-file`, the file is
+    /// read by the rcl parameter parser. Values in the `ros__parameters`
+    /// sections matching this node (by name, fully qualified name or the
+    /// `/**` wildcard) are set one by one, and validated like those of a
+    /// `set_parameters` request. The type of an existing parameter is kept
+    /// when e.g. an integer is given for a double or a byte array. The
+    /// results are in the order of the file.
+    pub fn load_params_from_file(&mut self, path: &Path) -> Result<Vec<SetParameterResult>> {
+        let file_error = |msg: String| Error::ParameterFile {
+            path: path.display().to_string(),
+            msg,
+        };
+        let c_path = CString::new(path.to_string_lossy().as_bytes())
+            .map_err(|_| file_error("the path contains a nul byte".into()))?;
+        let params = unsafe { rcl_yaml_node_struct_init(rcutils_get_default_allocator()) };
+        if params.is_null() {
+            return Err(file_error("could not allocate the parameters".into()));
         }
+        if !unsafe { rcl_parse_yaml_file(c_path.as_ptr(), params) } {
+            let msg = unsafe {
+                let msg = if rcutils_error_is_set() {
+                    let error = rcutils_get_error_string();
+                    CStr::from_ptr(error.str_.as_ptr())
+                        .to_string_lossy()
+                        .into_owned()
+                } else {
+                    "could not parse the file".to_owned()
+                };
+                rcutils_reset_error();
+                rcl_yaml_node_struct_fini(params);
+                msg
+            };
+            return Err(file_error(msg));
+        }
+
+        let parameters = self
+            .param_overrides_of_node(params)?
+            .into_iter()
+            .map(|(name, value)| {
+                let existing = self
+                    .params
+                    .lock()
+                    .unwrap()
+                    .get(&name)
+                    .map(|p| p.value.clone());
+                rcl_interfaces::msg::Parameter {
+                    value: value
+                        .with_type_of(existing.as_ref())
+                        .into_parameter_value_msg(),
+                    name,
+                }
+            })
+            .collect::<Vec<_>>();
+        let results = self.parameter_setter()?.set(&parameters);
+        Ok(parameters
+            .into_iter()
+            .zip(results)
+            .map(|(p, r)| SetParameterResult {
+                name: p.name,
+                successful: r.successful,
+                reason: r.reason,
+            })
+            .collect())
+    }
+
+    /// Save the current parameters to a ROS2 parameter YAML file.
+    ///
+    /// The file can be loaded again with `load_params_from_file` or
+    /// passed to a node using `--params-file`. Byte arrays are written as
+    /// integer lists, which are only read back as byte arrays into byte
+    /// array parameters. Fails if names collide once nested by their dots,
+    /// like `a` and `a.b`.
+    pub fn save_params_to_file(&self, path: &Path) -> Result<()> {
+        let file_error = |msg: String| Error::ParameterFile {
+            path: path.display().to_string(),
+            msg,
+        };
+
+        let mut ros_parameters = IndexMap::new();
+        for (name, param) in self.params.lock().unwrap().iter() {
+            if param.value == ParameterValue::NotSet {
+                continue;
+            }
+            if !yaml_insert_param(&mut ros_parameters, name, param.value.to_yaml()) {
+                return Err(file_error(format!(
+                    "parameter {name} collides with another parameter when nested by its dots"
+                )));
+            }
+        }
+        let mut node = IndexMap::new();
+        node.insert("ros__parameters".to_owned(), YamlNode::Mapping(ros_parameters));
+        let mut yaml = IndexMap::new();
+        yaml.insert(self.fully_qualified_name()?, YamlNode::Mapping(node));
+
+        let mut contents = String::new();
+        yaml_write(&mut contents, &yaml, 0);
+        std::fs::write(path, contents).map_err(|e| file_error(e.to_string()))
     }
 
     /// Creates parameter service handlers for the Node.
@@ -242,11 +888,33 @@ impl Node {
     /// external sources. The event elements of the event stream
     /// include the name of the parameter which was updated as well as
     /// its new value.
+    ///
+    /// If the node was created with `start_parameter_services` disabled in
+    /// its [`NodeOptions`], no services are created and the event stream
+    /// ends immediately.
     pub fn make_parameter_handler(
         &mut self,
     ) -> Result<(impl Future<Output = ()> + Send, impl Stream<Item = (String, ParameterValue)>)>
     {
-        self.make_parameter_handler_internal(None)
+        let (event_tx, event_rx) = mpsc::channel::<(String, ParameterValue)>(10);
+        let handler = self.make_parameter_handler_internal(None, Some(event_tx), None)?;
+        Ok((handler, event_rx))
+    }
+
+    /// Creates parameter service handlers for the Node, with the changes
+    /// of each request announced together.
+    ///
+    /// Like [`Node::make_parameter_handler`], but the `Stream` produces one
+    /// [`BatchParameterEvent`] for all parameters changed by a `set_parameters`
+    /// or `set_parameters_atomically` request, or a call to
+    /// [`Node::set_parameters_atomically`]. Observers never see the
+    /// intermediate states of an atomic change.
+    pub fn make_parameter_handler_batched(
+        &mut self,
+    ) -> Result<(impl Future<Output = ()> + Send, impl Stream<Item = BatchParameterEvent>)> {
+        let (batch_event_tx, batch_event_rx) = mpsc::channel::<BatchParameterEvent>(10);
+        let handler = self.make_parameter_handler_internal(None, None, Some(batch_event_tx))?;
+        Ok((handler, batch_event_rx))
     }
 
     /// Creates parameter service handlers for the Node based on the
@@ -269,13 +937,239 @@ impl Node {
         &mut self, params_struct: Arc<Mutex<dyn RosParams + Send>>,
     ) -> Result<(impl Future<Output = ()> + Send, impl Stream<Item = (String, ParameterValue)>)>
     {
-        self.make_parameter_handler_internal(Some(params_struct))
+        let (event_tx, event_rx) = mpsc::channel::<(String, ParameterValue)>(10);
+        let handler =
+            self.make_parameter_handler_internal(Some(params_struct), Some(event_tx), None)?;
+        Ok((handler, event_rx))
+    }
+
+    /// Register a callback validating parameter changes.
+    ///
+    /// The callback is called with the parameter name and the new
+    /// value whenever a parameter is set from an external source.
+    /// Returning `Err(reason)` rejects the change, leaving the old
+    /// value in place and reporting `reason` back to the caller.
+    /// Callbacks are called in the order they were added, and the first
+    /// rejection stops the remaining ones from being called.
+    pub fn add_parameter_callback<F>(&mut self, f: F)
+    where
+        F: Fn(&str, &ParameterValue) -> std::result::Result<(), String> + Send + 'static,
+    {
+        self.parameter_callbacks.lock().unwrap().push(Box::new(f));
+    }
+
+    /// Register a callback validating the parameters changed together.
+    ///
+    /// Unlike [`Node::add_parameter_callback`], the callback gets all new
+    /// values of a [`Node::set_parameters_atomically`] call or
+    /// `set_parameters_atomically` request at once, so that it can check
+    /// parameters depending on each other. For other changes, it is called with
+    /// the single new value. Returning `Err(reason)` rejects all of the
+    /// changes.
+    pub fn add_parameters_callback<F>(&mut self, f: F)
+    where
+        F: Fn(&[(String, ParameterValue)]) -> std::result::Result<(), String> + Send + 'static,
+    {
+        self.parameters_callbacks.lock().unwrap().push(Box::new(f));
+    }
+
+    /// Set several parameters of this node at once, all of them or none.
+    ///
+    /// The values are validated like those of a `set_parameters_atomically`
+    /// request, and if one is rejected, none of the parameters are changed
+    /// and [`Error::ParameterRejected`] is returned. Once the parameter
+    /// handler is created, the changes are announced like external ones.
+    pub fn set_parameters_atomically(&mut self, params: &[(&str, ParameterValue)]) -> Result<()> {
+        let values = params
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect();
+        self.parameter_setter()?.try_set_atomically(values)
+    }
+
+    /// The current parameters of this node as a JSON object, e.g. for a web interface.
+    ///
+    /// Parameters nested with dots in their name are flat keys of the object,
+    /// unset parameters are `null`.
+    pub fn parameters_as_json(&self) -> Result<serde_json::Value> {
+        let params = self.params.lock().unwrap();
+        let object = params
+            .iter()
+            .map(|(name, param)| (name.clone(), param.value.to_json()))
+            .collect::<serde_json::Map<_, _>>();
+        Ok(serde_json::Value::Object(object))
+    }
+
+    /// Set parameters of this node from a JSON object of names and values.
+    ///
+    /// This is the counterpart of [`Node::parameters_as_json`]. The parameters
+    /// are set one by one, and validated like those of a `set_parameters`
+    /// request. Values are converted like those of a parameter file, so the type of
+    /// an existing parameter is kept when e.g. an integer is given for a double.
+    /// Values which can not be converted, like JSON objects, are rejected.
+    /// The results are in the order of the object.
+    pub fn set_parameters_from_json(
+        &mut self, json: &serde_json::Value,
+    ) -> Result<Vec<SetParameterResult>> {
+        let object = json.as_object().ok_or_else(|| Error::SerdeError {
+            err: "expected a JSON object of parameters".into(),
+        })?;
+        // unconvertible values are rejected, the others are set like the
+        // parameters of a single set_parameters request.
+        let mut parameters = Vec::new();
+        let mut rejected = Vec::new();
+        for (name, value) in object {
+            let existing = self
+                .params
+                .lock()
+                .unwrap()
+                .get(name)
+                .map(|p| p.value.clone());
+            match ParameterValue::from_json(value, existing.as_ref()) {
+                Some(value) => parameters.push(rcl_interfaces::msg::Parameter {
+                    name: name.clone(),
+                    value: value.into_parameter_value_msg(),
+                }),
+                None => rejected.push(SetParameterResult {
+                    name: name.clone(),
+                    successful: false,
+                    reason: format!("unsupported value for parameter {name}: {value}"),
+                }),
+            }
+        }
+        let results = self.parameter_setter()?.set(&parameters);
+        let mut results = parameters
+            .into_iter()
+            .zip(results)
+            .map(|(p, r)| SetParameterResult {
+                name: p.name,
+                successful: r.successful,
+                reason: r.reason,
+            })
+            .chain(rejected)
+            .collect::<Vec<_>>();
+        // in the order of the object.
+        results.sort_by_key(|r| object.keys().position(|name| *name == r.name));
+        Ok(results)
+    }
+
+    // the setter of the parameter handler, so changes are announced, or
+    // one validating and applying changes directly.
+    fn parameter_setter(&self) -> Result<ParameterSetter> {
+        let (handler_setter, direct_setter) = self.parameter_setters()?;
+        let setter = handler_setter.lock().unwrap().clone();
+        Ok(setter.unwrap_or(direct_setter))
+    }
+
+    // the setter of the parameter handler, once it is created, and the one
+    // to use until then.
+    pub(crate) fn parameter_setters(
+        &self,
+    ) -> Result<(Arc<Mutex<Option<ParameterSetter>>>, ParameterSetter)> {
+        let direct_setter = ParameterSetter {
+            params: self.params.clone(),
+            params_struct: None,
+            callbacks: self.parameter_callbacks.clone(),
+            batch_callbacks: self.parameters_callbacks.clone(),
+            descriptors: self.parameter_descriptors.clone(),
+            event_tx: None,
+            batch_event_tx: None,
+            events_publisher: None,
+            node_name: self.fully_qualified_name()?,
+            ros_clock: self.ros_clock.clone(),
+        };
+        Ok((self.parameter_setter.clone(), direct_setter))
+    }
+
+    /// Declare a parameter with a descriptor.
+    ///
+    /// If the parameter was already given, e.g. on the command line, that
+    /// value is kept, otherwise it is set to `default`. The descriptor is
+    /// enforced by the parameter handler, rejecting changes to read only
+    /// parameters and values out of range, and is returned by the
+    /// `describe_parameters` service.
+    pub fn declare_parameter(
+        &mut self, name: &str, default: ParameterValue, descriptor: ParameterDescriptor,
+    ) -> Result<()> {
+        let mut params = self.params.lock().unwrap();
+        let value = params
+            .get(name)
+            .map(|p| p.value.clone())
+            .filter(|v| *v != ParameterValue::NotSet)
+            .unwrap_or(default);
+        descriptor
+            .check_range(name, &value)
+            .map_err(|reason| Error::ParameterRejected {
+                name: name.to_owned(),
+                reason,
+            })?;
+        params.insert(name.to_owned(), Parameter::new(value));
+        self.parameter_descriptors
+            .lock()
+            .unwrap()
+            .insert(name.to_owned(), descriptor);
+        Ok(())
+    }
+
+    /// Declare a parameter and get a [`ParameterHandle`] following its value.
+    ///
+    /// The parameter is declared like with [`Node::declare_parameter`]. A
+    /// descriptor the parameter was declared with before is kept, otherwise
+    /// the default descriptor is used. The handles of a node share one
+    /// subscription to `/parameter_events`.
+    pub fn declare_parameter_live<T>(
+        &mut self, name: &str, default: T,
+    ) -> Result<ParameterHandle<T>>
+    where
+        T: Clone + Send + Into<ParameterValue> + 'static,
+        ParameterValue: TryInto<T, Error = WrongParameterType>,
+    {
+        let descriptor = self
+            .parameter_descriptors
+            .lock()
+            .unwrap()
+            .get(name)
+            .cloned()
+            .unwrap_or_default();
+        self.declare_parameter(name, default.clone().into(), descriptor)?;
+        let events = self.shared_parameter_events()?;
+        ParameterHandle::new(self, name, default, events)
+    }
+
+    // a stream of the events on /parameter_events, forwarded from one
+    // subscription created on first use. like the messages of a subscription,
+    // events are dropped while the stream is full.
+    fn shared_parameter_events(
+        &mut self,
+    ) -> Result<mpsc::Receiver<rcl_interfaces::msg::ParameterEvent>> {
+        let senders = match &self.parameter_event_senders {
+            Some(senders) => senders.clone(),
+            None => {
+                let senders = ParameterEventSenders::default();
+                let mut events = self.subscribe_to_parameter_events()?;
+                let task_senders = senders.clone();
+                self.spawn_async_task(Box::pin(async move {
+                    while let Some(event) = events.next().await {
+                        // forget the handles which were dropped.
+                        task_senders.lock().unwrap().retain_mut(|sender| {
+                            !matches!(sender.try_send(event.clone()), Err(e) if e.is_disconnected())
+                        });
+                    }
+                }))?;
+                self.parameter_event_senders = Some(senders.clone());
+                senders
+            }
+        };
+        let (sender, receiver) = mpsc::channel(10);
+        senders.lock().unwrap().push(sender);
+        Ok(receiver)
     }
 
     fn make_parameter_handler_internal(
         &mut self, params_struct: Option<Arc<Mutex<dyn RosParams + Send>>>,
-    ) -> Result<(impl Future<Output = ()> + Send, impl Stream<Item = (String, ParameterValue)>)>
-    {
+        event_tx: Option<mpsc::Sender<(String, ParameterValue)>>,
+        batch_event_tx: Option<mpsc::Sender<BatchParameterEvent>>,
+    ) -> Result<impl Future<Output = ()> + Send> {
         if let Some(ps) = &params_struct {
             // register all parameters
             ps.lock()
@@ -283,8 +1177,44 @@ impl Node {
                 .register_parameters("", None, &mut self.params.lock().unwrap())?;
         }
         let mut handlers: Vec<std::pin::Pin<Box<dyn Future<Output = ()> + Send>>> = Vec::new();
-        let (mut event_tx, event_rx) = mpsc::channel::<(String, ParameterValue)>(10);
 
+        if self.start_parameter_services {
+            self.make_parameter_services(params_struct, event_tx, batch_event_tx, &mut handlers)?;
+        }
+
+        #[cfg(r2r__rosgraph_msgs__msg__Clock)]
+        {
+            // create TimeSource based on value of use_sim_time parameter
+            let use_sim_time = {
+                let params = self.params.lock().unwrap();
+                params
+                    .get("use_sim_time")
+                    .and_then(|param| {
+                        if let ParameterValue::Bool(val) = param.value {
+                            Some(val)
+                        } else {
+                            log::error!("Parameter use_sim_time is not bool. Assuming false");
+                            None
+                        }
+                    })
+                    .unwrap_or(false)
+            };
+            if use_sim_time {
+                let ts = self.time_source.clone();
+                ts.enable_sim_time(self)?;
+            }
+        }
+
+        // we don't care about the result, the futures will not complete anyway.
+        Ok(join_all(handlers).map(|_| ()))
+    }
+
+    fn make_parameter_services(
+        &mut self, params_struct: Option<Arc<Mutex<dyn RosParams + Send>>>,
+        event_tx: Option<mpsc::Sender<(String, ParameterValue)>>,
+        batch_event_tx: Option<mpsc::Sender<BatchParameterEvent>>,
+        handlers: &mut Vec<std::pin::Pin<Box<dyn Future<Output = ()> + Send>>>,
+    ) -> Result<()> {
         let node_name = self.name()?;
         let set_params_request_stream = self
             .create_service::<rcl_interfaces::srv::SetParameters::Service>(
@@ -292,55 +1222,31 @@ impl Node {
                 QosProfile::default(),
             )?;
 
-        let params = self.params.clone();
-        let params_struct_clone = params_struct.clone();
+        // changes are also announced to other nodes on /parameter_events
+        let parameter_events_publisher = self
+            .create_publisher::<rcl_interfaces::msg::ParameterEvent>(
+                "/parameter_events",
+                QosProfile::parameter_events(),
+            )?;
+        let setter = ParameterSetter {
+            params: self.params.clone(),
+            params_struct: params_struct.clone(),
+            callbacks: self.parameter_callbacks.clone(),
+            batch_callbacks: self.parameters_callbacks.clone(),
+            descriptors: self.parameter_descriptors.clone(),
+            event_tx,
+            batch_event_tx,
+            events_publisher: Some(parameter_events_publisher),
+            node_name: self.fully_qualified_name()?,
+            ros_clock: self.ros_clock.clone(),
+        };
+        *self.parameter_setter.lock().unwrap() = Some(setter.clone());
+
+        let mut set_params_setter = setter.clone();
         let set_params_future = set_params_request_stream.for_each(
             move |req: ServiceRequest<rcl_interfaces::srv::SetParameters::Service>| {
-                let mut result = rcl_interfaces::srv::SetParameters::Response::default();
-                for p in &req.message.parameters {
-                    let val = ParameterValue::from_parameter_value_msg(p.value.clone());
-                    let changed = params
-                        .lock()
-                        .unwrap()
-                        .get(&p.name)
-                        .map(|v| v.value != val)
-                        .unwrap_or(true); // changed=true if new
-                    let r = if let Some(ps) = &params_struct_clone {
-                        // Update parameter structure
-                        let result = ps.lock().unwrap().set_parameter(&p.name, &val);
-                        if result.is_ok() {
-                            // Also update Node::params
-                            params
-                                .lock()
-                                .unwrap()
-                                .entry(p.name.clone())
-                                .and_modify(|p| p.value = val.clone());
-                        }
-                        rcl_interfaces::msg::SetParametersResult {
-                            successful: result.is_ok(),
-                            reason: result.err().map_or("".into(), |e| e.to_string()),
-                        }
-                    } else {
-                        // No parameter structure - update only Node::params
-                        params
-                            .lock()
-                            .unwrap()
-                            .entry(p.name.clone())
-                            .and_modify(|p| p.value = val.clone())
-                            .or_insert(Parameter::new(val.clone()));
-                        rcl_interfaces::msg::SetParametersResult {
-                            successful: true,
-                            reason: "".into(),
-                        }
-                    };
-                    // if the value changed, send out new value on parameter event stream
-                    if changed && r.successful {
-                        if let Err(e) = event_tx.try_send((p.name.clone(), val)) {
-                            log::debug!("Warning: could not send parameter event ({}).", e);
-                        }
-                    }
-                    result.results.push(r);
-                }
+                let results = set_params_setter.set(&req.message.parameters);
+                let result = rcl_interfaces::srv::SetParameters::Response { results };
                 req.respond(result)
                     .expect("could not send reply to set parameter request");
                 future::ready(())
@@ -348,6 +1254,25 @@ impl Node {
         );
         handlers.push(Box::pin(set_params_future));
 
+        // rcl_interfaces/srv/SetParametersAtomically
+        use rcl_interfaces::srv::SetParametersAtomically;
+        let set_params_atomically_request_stream = self
+            .create_service::<SetParametersAtomically::Service>(
+                &format!("{node_name}/set_parameters_atomically"),
+                QosProfile::default(),
+            )?;
+
+        let mut set_params_atomically_setter = setter;
+        let set_params_atomically_future = set_params_atomically_request_stream.for_each(
+            move |req: ServiceRequest<SetParametersAtomically::Service>| {
+                let result = set_params_atomically_setter.set_atomically(&req.message.parameters);
+                req.respond(SetParametersAtomically::Response { result })
+                    .expect("could not send reply to set parameters atomically request");
+                future::ready(())
+            },
+        );
+        handlers.push(Box::pin(set_params_atomically_future));
+
         // rcl_interfaces/srv/GetParameters
         let get_params_request_stream = self
             .create_service::<rcl_interfaces::srv::GetParameters::Service>(
@@ -413,9 +1338,10 @@ impl Node {
         )?;
 
         let params = self.params.clone();
+        let parameter_descriptors = self.parameter_descriptors.clone();
         let desc_params_future = desc_params_request_stream.for_each(
             move |req: ServiceRequest<DescribeParameters::Service>| {
-                Self::handle_desc_parameters(req, &params)
+                Self::handle_desc_parameters(req, &params, &parameter_descriptors)
             },
         );
 
@@ -449,31 +1375,7 @@ impl Node {
 
         handlers.push(Box::pin(get_param_types_future));
 
-        #[cfg(r2r__rosgraph_msgs__msg__Clock)]
-        {
-            // create TimeSource based on value of use_sim_time parameter
-            let use_sim_time = {
-                let params = self.params.lock().unwrap();
-                params
-                    .get("use_sim_time")
-                    .and_then(|param| {
-                        if let ParameterValue::Bool(val) = param.value {
-                            Some(val)
-                        } else {
-                            log::error!("Parameter use_sim_time is not bool. Assuming false");
-                            None
-                        }
-                    })
-                    .unwrap_or(false)
-            };
-            if use_sim_time {
-                let ts = self.time_source.clone();
-                ts.enable_sim_time(self)?;
-            }
-        }
-
-        // we don't care about the result, the futures will not complete anyway.
-        Ok((join_all(handlers).map(|_| ()), event_rx))
+        Ok(())
     }
 
     fn handle_list_parameters(
@@ -522,13 +1424,19 @@ impl Node {
     fn handle_desc_parameters(
         req: ServiceRequest<rcl_interfaces::srv::DescribeParameters::Service>,
         params: &Arc<Mutex<IndexMap<String, Parameter>>>,
+        parameter_descriptors: &Arc<Mutex<HashMap<String, ParameterDescriptor>>>,
     ) -> future::Ready<()> {
         use rcl_interfaces::{msg::ParameterDescriptor, srv::DescribeParameters};
         let mut descriptors = Vec::<ParameterDescriptor>::new();
         let params = params.lock().unwrap();
+        let parameter_descriptors = parameter_descriptors.lock().unwrap();
         for name in &req.message.names {
             let default = Parameter::empty();
             let param = params.get(name).unwrap_or(&default);
+            if let Some(descriptor) = parameter_descriptors.get(name) {
+                descriptors.push(descriptor.to_msg(name, &param.value));
+                continue;
+            }
             descriptors.push(ParameterDescriptor {
                 name: name.clone(),
                 type_: param.value.into_parameter_type(),
@@ -546,45 +1454,415 @@ impl Node {
     where
         ParameterValue: TryInto<T, Error = WrongParameterType>,
     {
-        let params = self.params.lock().unwrap();
-        let value = params
-            .get(name)
-            .map(|parameter| parameter.value.clone())
-            .unwrap_or(ParameterValue::NotSet);
+        get_parameter(&self.params.lock().unwrap(), name)
+    }
 
-        let value: T =
-            value
-                .try_into()
-                .map_err(|error: WrongParameterType| Error::ParameterWrongType {
-                    name: name.to_string(),
-                    expected_type: error.expected_type_name,
-                    actual_type: error.actual_type_name,
-                })?;
+    /// Internal function used by code derived for `r2r_macros::Parameters`.
+    #[doc(hidden)]
+    pub fn parameter_values(&self, names: &[String]) -> IndexMap<String, ParameterValue> {
+        let params = self.params.lock().unwrap();
+        names
+            .iter()
+            .map(|name| {
+                let value = params
+                    .get(name)
+                    .map(|p| p.value.clone())
+                    .unwrap_or(ParameterValue::NotSet);
+                (name.clone(), value)
+            })
+            .collect()
+    }
 
-        Ok(value)
+    /// Internal function used by code derived for `r2r_macros::Parameters`.
+    ///
+    /// Yields the values of all `names` whenever one of them is changed
+    /// through the parameter services of this node.
+    #[doc(hidden)]
+    pub fn parameter_group_events(
+        &mut self, names: Vec<String>,
+    ) -> Result<impl Stream<Item = IndexMap<String, ParameterValue>> + Unpin> {
+        let node_name = self.fully_qualified_name()?;
+        let mut values = self.parameter_values(&names);
+        let events = self.subscribe_to_parameter_events()?;
+        Ok(events.filter_map(move |event| {
+            let mut changed = false;
+            if event.node == node_name {
+                for p in event
+                    .new_parameters
+                    .into_iter()
+                    .chain(event.changed_parameters)
+                {
+                    if let Some(value) = values.get_mut(&p.name) {
+                        *value = ParameterValue::from_parameter_value_msg(p.value);
+                        changed = true;
+                    }
+                }
+            }
+            future::ready(changed.then(|| values.clone()))
+        }))
     }
 
     /// Subscribe to a ROS topic.
     ///
-    /// This function returns a `Stream` of ros messages.
+    /// This function returns a [`Subscription`], a `Stream` of ros messages.
     pub fn subscribe<T: 'static>(
         &mut self, topic: &str, qos_profile: QosProfile,
-    ) -> Result<impl Stream<Item = T> + Unpin>
+    ) -> Result<Subscription<T>>
     where
         T: WrappedTypesupport,
     {
+        self.check_topic_type(topic, T::get_ts())?;
         let subscription_handle =
             create_subscription_helper(self.node_handle.as_mut(), topic, T::get_ts(), qos_profile)?;
         let (sender, receiver) = mpsc::channel::<T>(10);
+        let stats = Arc::new(SubscriptionStats::default());
 
         let ws = TypedSubscriber {
             rcl_handle: subscription_handle,
             sender,
+            stats: Some(stats.clone()),
+        };
+        self.subscribers.push(Box::new(ws));
+        let graph = self.subscription_graph(topic)?;
+        Ok(Subscription::new(receiver, stats).with_graph(graph))
+    }
+
+    /// Subscribe to a ROS topic, receiving the messages in batches.
+    ///
+    /// Each spin takes up to `max_batch` messages from the subscription queue
+    /// and sends them as one item, instead of waking the stream up for each
+    /// message. Spins without new messages produce no item. Useful for high
+    /// rate topics, where the queue depth of the QoS profile should be at
+    /// least `max_batch`.
+    pub fn create_batched_subscription<T: 'static>(
+        &mut self, topic: &str, qos_profile: QosProfile, max_batch: usize,
+    ) -> Result<impl Stream<Item = Vec<T>> + Unpin>
+    where
+        T: WrappedTypesupport,
+    {
+        if max_batch == 0 {
+            return Err(Error::RCL_RET_INVALID_ARGUMENT);
+        }
+        self.check_topic_type(topic, T::get_ts())?;
+        let subscription_handle =
+            create_subscription_helper(self.node_handle.as_mut(), topic, T::get_ts(), qos_profile)?;
+        let (sender, receiver) = mpsc::channel::<Vec<T>>(10);
+
+        let ws = BatchedSubscriber {
+            rcl_handle: subscription_handle,
+            max_batch,
+            sender,
+        };
+        self.subscribers.push(Box::new(ws));
+        Ok(receiver)
+    }
+
+    /// Subscribe to a ROS topic, with a bounded channel to the stream.
+    ///
+    /// The subscription keeps the last `qos_depth` messages in the middleware,
+    /// and at most `channel_bound` messages wait in the stream. When the
+    /// consumer does not keep up, new messages are still taken from the
+    /// middleware but dropped, and counted by the returned [`DropCounter`].
+    pub fn create_bounded_subscription<T>(
+        &mut self, topic: &str, qos_depth: usize, channel_bound: usize,
+    ) -> Result<(BoundedSubscription<T>, DropCounter)>
+    where
+        T: WrappedTypesupport + 'static,
+    {
+        if channel_bound == 0 {
+            return Err(Error::RCL_RET_INVALID_ARGUMENT);
+        }
+        let qos_profile = QosProfile::default().keep_last(qos_depth);
+        self.check_topic_type(topic, T::get_ts())?;
+        let subscription_handle =
+            create_subscription_helper(self.node_handle.as_mut(), topic, T::get_ts(), qos_profile)?;
+        // the channel has room for one message per sender on top of the buffer.
+        let (sender, receiver) = mpsc::channel::<T>(channel_bound - 1);
+        let drops = DropCounter::default();
+
+        let ws = BoundedSubscriber {
+            rcl_handle: subscription_handle,
+            sender,
+            drops: drops.clone(),
+        };
+        self.subscribers.push(Box::new(ws));
+        Ok((BoundedSubscription::new(receiver), drops))
+    }
+
+    /// Subscribe to a ROS topic, receiving metadata alongside each message.
+    ///
+    /// This function returns a `Stream` of ros messages paired with a [`MessageInfo`]
+    /// containing e.g. the source timestamp and the gid of the publisher.
+    pub fn subscribe_with_info<T: 'static>(
+        &mut self, topic: &str, qos_profile: QosProfile,
+    ) -> Result<impl Stream<Item = (T, MessageInfo)> + Unpin>
+    where
+        T: WrappedTypesupport,
+    {
+        self.check_topic_type(topic, T::get_ts())?;
+        let subscription_handle =
+            create_subscription_helper(self.node_handle.as_mut(), topic, T::get_ts(), qos_profile)?;
+        let (sender, receiver) = mpsc::channel::<(T, MessageInfo)>(10);
+
+        let ws = TypedSubscriberWithInfo {
+            rcl_handle: subscription_handle,
+            sender,
+        };
+        self.subscribers.push(Box::new(ws));
+        Ok(receiver)
+    }
+
+    /// Subscribe to a ROS topic with additional subscription options.
+    ///
+    /// This function returns a `Stream` of ros messages. If a content filter is
+    /// given but the middleware does not support content filtered topics,
+    /// [`Error::UnsupportedFeature`] is returned.
+    ///
+    /// With topic statistics enabled, the period and age of received messages are
+    /// published as `statistics_msgs/msg/MetricsMessage` on `<node>/<topic>/statistics`
+    /// every `statistics_publish_period` while the node is spinning.
+    ///
+    /// Messages which cannot be deserialized are dropped, and passed to
+    /// `on_deserialization_error` of the options if set. At most `queue_depth`
+    /// messages wait in the stream, see [`Node::create_subscription_with_options`]
+    /// for how `overflow_policy` applies.
+    pub fn subscribe_with_options<T: 'static>(
+        &mut self, topic: &str, qos_profile: QosProfile, options: SubscriptionOptions,
+    ) -> Result<impl Stream<Item = T> + Unpin>
+    where
+        T: WrappedTypesupport,
+    {
+        if options.queue_depth == 0 {
+            return Err(Error::RCL_RET_INVALID_ARGUMENT);
+        }
+        self.check_topic_type(topic, T::get_ts())?;
+        let subscription_handle = create_subscription_helper_with_options(
+            self.node_handle.as_mut(),
+            topic,
+            T::get_ts(),
+            qos_profile,
+            &options,
+        )?;
+        match create_subscription_events(&subscription_handle, &options) {
+            Ok(events) => self.qos_events.extend(events),
+            Err(e) => {
+                let mut subscription_handle = subscription_handle;
+                unsafe {
+                    rcl_subscription_fini(&mut subscription_handle, self.node_handle.as_mut());
+                }
+                return Err(e);
+            }
+        }
+        let queue = Arc::new(MessageQueue::new(options.queue_depth, options.overflow_policy));
+        let stats = Arc::new(SubscriptionStats::default());
+        let receiver = Subscription::from_queue(queue.clone(), stats.clone());
+
+        if options.enable_topic_statistics {
+            #[cfg(r2r__statistics_msgs__msg__MetricsMessage)]
+            {
+                let statistics = match self.create_topic_statistics(topic, &options) {
+                    Ok(statistics) => statistics,
+                    Err(e) => {
+                        destroy_subscription_events(&mut self.qos_events, &subscription_handle);
+                        let mut subscription_handle = subscription_handle;
+                        unsafe {
+                            rcl_subscription_fini(
+                                &mut subscription_handle,
+                                self.node_handle.as_mut(),
+                            );
+                        }
+                        return Err(e);
+                    }
+                };
+                let ws = TypedSubscriberWithStatistics {
+                    rcl_handle: subscription_handle,
+                    queue,
+                    statistics,
+                };
+                self.subscribers.push(Box::new(ws));
+                return Ok(receiver);
+            }
+            #[cfg(not(r2r__statistics_msgs__msg__MetricsMessage))]
+            {
+                destroy_subscription_events(&mut self.qos_events, &subscription_handle);
+                let mut subscription_handle = subscription_handle;
+                unsafe {
+                    rcl_subscription_fini(&mut subscription_handle, self.node_handle.as_mut());
+                }
+                return Err(Error::UnsupportedFeature("topic_statistics"));
+            }
+        }
+
+        let Some(on_error) = options.on_deserialization_error else {
+            let ws = QueuedSubscriber {
+                rcl_handle: subscription_handle,
+                queue,
+                stats,
+            };
+            self.subscribers.push(Box::new(ws));
+            return Ok(receiver);
+        };
+        let msg_buf = match create_serialized_buffer() {
+            Ok(msg_buf) => msg_buf,
+            Err(e) => {
+                destroy_subscription_events(&mut self.qos_events, &subscription_handle);
+                let mut subscription_handle = subscription_handle;
+                unsafe {
+                    rcl_subscription_fini(&mut subscription_handle, self.node_handle.as_mut());
+                }
+                return Err(e);
+            }
+        };
+        let ws = TypedSubscriberWithErrorHandler {
+            rcl_handle: subscription_handle,
+            msg_buf,
+            queue,
+            on_error,
         };
         self.subscribers.push(Box::new(ws));
         Ok(receiver)
     }
 
+    /// Subscribe to a ROS topic, with an explicit queue between the middleware and the stream.
+    ///
+    /// At most `options.queue_depth` messages wait in the returned [`Subscription`].
+    /// When the consumer does not keep up, `options.overflow_policy` decides
+    /// which messages are dropped, or whether `spin_once` waits for room, and
+    /// [`Subscription::total_dropped_count`] counts the dropped messages.
+    ///
+    /// The content filter and QoS event callbacks of the options are used as in
+    /// [`Node::subscribe_with_options`]. Topic statistics are not supported here,
+    /// and give [`Error::UnsupportedFeature`].
+    pub fn create_subscription_with_options<T>(
+        &mut self, topic: &str, qos_profile: QosProfile, options: SubscriptionOptions,
+    ) -> Result<Subscription<T>>
+    where
+        T: WrappedTypesupport + 'static,
+    {
+        if options.queue_depth == 0 {
+            return Err(Error::RCL_RET_INVALID_ARGUMENT);
+        }
+        if options.enable_topic_statistics {
+            return Err(Error::UnsupportedFeature("topic_statistics"));
+        }
+        self.check_topic_type(topic, T::get_ts())?;
+        let subscription_handle = create_subscription_helper_with_options(
+            self.node_handle.as_mut(),
+            topic,
+            T::get_ts(),
+            qos_profile,
+            &options,
+        )?;
+        match create_subscription_events(&subscription_handle, &options) {
+            Ok(events) => self.qos_events.extend(events),
+            Err(e) => {
+                let mut subscription_handle = subscription_handle;
+                unsafe {
+                    rcl_subscription_fini(&mut subscription_handle, self.node_handle.as_mut());
+                }
+                return Err(e);
+            }
+        }
+        let queue = Arc::new(MessageQueue::new(options.queue_depth, options.overflow_policy));
+        let stats = Arc::new(SubscriptionStats::default());
+
+        let ws = QueuedSubscriber {
+            rcl_handle: subscription_handle,
+            queue: queue.clone(),
+            stats: stats.clone(),
+        };
+        self.subscribers.push(Box::new(ws));
+        let graph = self.subscription_graph(topic)?;
+        Ok(Subscription::from_queue(queue, stats).with_graph(graph))
+    }
+
+    // what a subscription on `topic` needs to query the graph.
+    fn subscription_graph(&self, topic: &str) -> Result<(String, NodeGraphHandle)> {
+        let topic = self.resolve_topic_name(topic)?;
+        let handle = NodeGraphHandle {
+            node: self.node_handle.as_ref(),
+            alive: Arc::downgrade(&self.graph_alive),
+        };
+        Ok((topic, handle))
+    }
+
+    #[cfg(r2r__statistics_msgs__msg__MetricsMessage)]
+    fn create_topic_statistics(
+        &mut self, topic: &str, options: &SubscriptionOptions,
+    ) -> Result<Arc<Mutex<TopicStatistics>>> {
+        let node_name = self.fully_qualified_name()?;
+        let statistics_topic = format!(
+            "{}/{}/statistics",
+            node_name.trim_end_matches('/'),
+            topic.trim_start_matches('/')
+        );
+        let publisher = self.create_publisher(&statistics_topic, QosProfile::default())?;
+        let statistics = Arc::new(Mutex::new(TopicStatistics::new(
+            node_name,
+            options.statistics_publish_period,
+            publisher,
+        )));
+        self.topic_statistics.push(statistics.clone());
+        Ok(statistics)
+    }
+
+    /// Subscribe to a latched ROS topic.
+    ///
+    /// This is a shorthand for [`Node::subscribe`] with a reliable, transient local
+    /// QoS profile keeping the last `depth` messages. Messages published before the
+    /// subscription was created are received as long as the publisher is also
+    /// transient local, e.g. created with [`Node::create_publisher_latched`].
+    ///
+    /// Note that when the durability of the publisher and subscriber does not match
+    /// (a volatile publisher and a transient local subscriber), they will not be connected
+    /// and no messages are received, without any error being reported.
+    pub fn subscribe_latched<T: 'static>(
+        &mut self, topic: &str, depth: usize,
+    ) -> Result<impl Stream<Item = T> + Unpin>
+    where
+        T: WrappedTypesupport,
+    {
+        self.subscribe(topic, latched_qos(depth))
+    }
+
+    /// Subscribe to the global `/parameter_events` topic.
+    ///
+    /// The stream yields the parameter changes of all nodes in the system.
+    pub fn subscribe_to_parameter_events(
+        &mut self,
+    ) -> Result<impl Stream<Item = rcl_interfaces::msg::ParameterEvent> + Unpin> {
+        self.subscribe("/parameter_events", QosProfile::parameter_events())
+    }
+
+    /// Subscribe to the parameter changes of another node.
+    ///
+    /// Events on `/parameter_events` from other nodes are filtered out and the
+    /// stream yields `(parameter_name, new_value)` for each new or changed parameter.
+    /// Use the fully qualified name (e.g. `"/ns/node"`) for nodes in other namespaces.
+    pub fn subscribe_to_parameter_events_for_node(
+        &mut self, remote_node_name: &str,
+    ) -> Result<impl Stream<Item = (String, ParameterValue)> + Unpin> {
+        let remote_node_name = if remote_node_name.starts_with('/') {
+            remote_node_name.to_owned()
+        } else {
+            format!("/{remote_node_name}")
+        };
+        let events = self.subscribe_to_parameter_events()?;
+        Ok(events.flat_map(move |event| {
+            let changes = if event.node == remote_node_name {
+                event
+                    .new_parameters
+                    .into_iter()
+                    .chain(event.changed_parameters)
+                    .map(|p| (p.name, ParameterValue::from_parameter_value_msg(p.value)))
+                    .collect()
+            } else {
+                vec![]
+            };
+            futures::stream::iter(changes)
+        }))
+    }
+
     /// Subscribe to a ROS topic.
     ///
     /// This function returns a `Receiver` of ros messages.
@@ -594,6 +1872,7 @@ impl Node {
     where
         T: WrappedTypesupport,
     {
+        self.check_topic_type(topic, T::get_ts())?;
         let subscription_handle =
             create_subscription_helper(self.node_handle.as_mut(), topic, T::get_ts(), qos_profile)?;
         let (sender, receiver) = mpsc::channel::<T>(10);
@@ -601,12 +1880,12 @@ impl Node {
         let ws = TypedSubscriber {
             rcl_handle: subscription_handle,
             sender,
+            stats: None,
         };
         self.subscribers.push(Box::new(ws));
         Ok(receiver)
     }
 
-
     /// Subscribe to a ROS topic.
     ///
     /// This function returns a `Stream` of ros messages without the rust convenience types.
@@ -616,6 +1895,7 @@ impl Node {
     where
         T: WrappedTypesupport,
     {
+        self.check_topic_type(topic, T::get_ts())?;
         let subscription_handle =
             create_subscription_helper(self.node_handle.as_mut(), topic, T::get_ts(), qos_profile)?;
         let (sender, receiver) = mpsc::channel::<WrappedNativeMsg<T>>(10);
@@ -636,6 +1916,7 @@ impl Node {
         &mut self, topic: &str, topic_type: &str, qos_profile: QosProfile,
     ) -> Result<impl Stream<Item = Result<serde_json::Value>> + Unpin> {
         let msg = WrappedNativeMsgUntyped::new_from(topic_type)?;
+        self.check_topic_type(topic, msg.ts)?;
         let subscription_handle =
             create_subscription_helper(self.node_handle.as_mut(), topic, msg.ts, qos_profile)?;
         let (sender, receiver) = mpsc::channel::<Result<serde_json::Value>>(10);
@@ -649,10 +1930,24 @@ impl Node {
         Ok(receiver)
     }
 
+    /// Create a type erased ROS subscription.
+    ///
+    /// `topic_type` is given in the form `"std_msgs/msg/String"`. The messages
+    /// are received as serialized bytes, see [`Node::subscribe_raw`].
+    pub fn create_any_subscription(
+        &mut self, topic: &str, topic_type: &str, qos_profile: QosProfile,
+    ) -> Result<AnySubscription> {
+        let stream = self.subscribe_raw(topic, topic_type, qos_profile)?;
+        Ok(AnySubscription::new(topic_type.to_owned(), Box::new(stream)))
+    }
+
     /// Subscribe to a ROS topic.
     ///
     /// This function returns a `Stream` of ros messages as non-deserialized `Vec<u8>`:s.
     /// Useful if you just want to pass the data along to another part of the system.
+    ///
+    /// `topic_type` is given in the form `"std_msgs/msg/String"`. It is only
+    /// used to look up the type support, the messages are never deserialized.
     pub fn subscribe_raw(
         &mut self, topic: &str, topic_type: &str, qos_profile: QosProfile,
     ) -> Result<impl Stream<Item = Vec<u8>> + Unpin> {
@@ -664,25 +1959,36 @@ impl Node {
         // because it means we always have to build the message types even if we
         // are just after the raw bytes.
         let msg = WrappedNativeMsgUntyped::new_from(topic_type)?;
+        self.subscribe_raw_helper(topic, msg.ts, qos_profile)
+    }
+
+    /// Subscribe to a ROS topic, deserializing the messages only on demand.
+    ///
+    /// The header of a [`MessageEnvelope`] can be read without deserializing the
+    /// rest of the message, which is useful to filter messages e.g. by their
+    /// timestamp before paying for the full deserialization.
+    #[cfg(r2r__std_msgs__msg__Header)]
+    pub fn create_envelope_subscription<T>(
+        &mut self, topic: &str, qos_profile: QosProfile,
+    ) -> Result<impl Stream<Item = MessageEnvelope<T>> + Unpin>
+    where
+        T: WrappedTypesupport + 'static,
+    {
+        let has_header = starts_with_header(T::get_ts());
+        let receiver = self.subscribe_raw_helper(topic, T::get_ts(), qos_profile)?;
+        Ok(receiver.map(move |data| MessageEnvelope::new(data, has_header)))
+    }
 
+    fn subscribe_raw_helper(
+        &mut self, topic: &str, ts: *const rosidl_message_type_support_t, qos_profile: QosProfile,
+    ) -> Result<mpsc::Receiver<Vec<u8>>> {
         // Keep a buffer to reduce number of allocations. The rmw will
         // resize it if the message size exceeds the buffer size.
-        let mut msg_buf: rcl_serialized_message_t =
-            unsafe { rcutils_get_zero_initialized_uint8_array() };
-        let ret = unsafe {
-            rcutils_uint8_array_init(
-                &mut msg_buf as *mut rcl_serialized_message_t,
-                0,
-                &rcutils_get_default_allocator(),
-            )
-        };
-
-        if ret != RCL_RET_OK as i32 {
-            return Err(Error::from_rcl_error(ret));
-        }
+        let msg_buf = create_serialized_buffer()?;
 
+        self.check_topic_type(topic, ts)?;
         let subscription_handle =
-            create_subscription_helper(self.node_handle.as_mut(), topic, msg.ts, qos_profile)?;
+            create_subscription_helper(self.node_handle.as_mut(), topic, ts, qos_profile)?;
         let (sender, receiver) = mpsc::channel::<Vec<u8>>(10);
 
         let ws = RawSubscriber {
@@ -694,6 +2000,127 @@ impl Node {
         Ok(receiver)
     }
 
+    /// Resolves `topic` to the fully qualified name used by publishers and
+    /// subscriptions created with it, after expansion and remapping.
+    ///
+    /// E.g. `"~/status"` becomes `"/ns/node/status"`. As subscriptions are streams
+    /// without a handle of their own, this is also how to get their topic name.
+    pub fn resolve_topic_name(&self, topic: &str) -> Result<String> {
+        self.resolve_name(topic, false)
+    }
+
+    /// Resolves `service` to the fully qualified name used by services and
+    /// clients created with it, after expansion and remapping.
+    pub fn resolve_service_name(&self, service: &str) -> Result<String> {
+        self.resolve_name(service, true)
+    }
+
+    fn resolve_name(&self, name: &str, is_service: bool) -> Result<String> {
+        let c_name = CString::new(name).map_err(|_| Error::RCL_RET_INVALID_ARGUMENT)?;
+        let allocator = unsafe { rcutils_get_default_allocator() };
+        let mut output: *mut std::os::raw::c_char = std::ptr::null_mut();
+        let ret = unsafe {
+            rcl_node_resolve_name(
+                self.node_handle.as_ref(),
+                c_name.as_ptr(),
+                allocator,
+                is_service,
+                false,
+                &mut output,
+            )
+        };
+        if ret != RCL_RET_OK as i32 {
+            return Err(Error::from_rcl_error(ret));
+        }
+
+        let name = unsafe { CStr::from_ptr(output) }
+            .to_str()
+            .unwrap_or("")
+            .to_owned();
+        if let Some(deallocate) = allocator.deallocate {
+            unsafe { deallocate(output as *mut std::os::raw::c_void, allocator.state) };
+        }
+        Ok(name)
+    }
+
+    /// Gets the number of publishers matched by this node's subscription on `topic`.
+    ///
+    /// Subscriptions are streams without a handle of their own, so the subscription
+    /// is looked up by topic, resolved with [`Node::resolve_topic_name`]. Returns an
+    /// error if the node has no subscription on the topic.
+    pub fn get_publisher_count(&self, topic: &str) -> Result<usize> {
+        let (_, subscription) = self.find_subscription(topic)?;
+        let mut count = 0;
+        let result = unsafe { rcl_subscription_get_publisher_count(subscription, &mut count) };
+        if result == RCL_RET_OK as i32 {
+            Ok(count)
+        } else {
+            Err(Error::from_rcl_error(result))
+        }
+    }
+
+    /// Gets the GIDs of the publishers matched by this node's subscription on `topic`.
+    ///
+    /// As for [`Node::get_publisher_count`], the subscription is looked up by
+    /// topic. The publishers are taken from the graph, leaving out those with
+    /// a QoS incompatible with the subscription. Compare with
+    /// [`Publisher::get_gid`](crate::Publisher::get_gid).
+    pub fn get_publisher_gids(&self, topic: &str) -> Result<Vec<Gid>> {
+        let (topic, subscription) = self.find_subscription(topic)?;
+        let qos = unsafe { rcl_subscription_get_actual_qos(subscription) };
+        if qos.is_null() {
+            return Err(Error::RCL_RET_SUBSCRIPTION_INVALID);
+        }
+        let qos = unsafe { *qos };
+
+        let gids = self
+            .get_publishers_info_by_topic(&topic, false)?
+            .into_iter()
+            .filter(|info| {
+                let mut compatibility = rmw_qos_compatibility_type_t::RMW_QOS_COMPATIBILITY_OK;
+                let ret = unsafe {
+                    rmw_qos_profile_check_compatible(
+                        info.qos_profile.clone().into(),
+                        qos,
+                        &mut compatibility,
+                        std::ptr::null_mut(),
+                        0,
+                    )
+                };
+                ret != RMW_RET_OK as i32
+                    || compatibility != rmw_qos_compatibility_type_t::RMW_QOS_COMPATIBILITY_ERROR
+            })
+            .map(|info| info.endpoint_gid)
+            .collect();
+        Ok(gids)
+    }
+
+    /// The network flow endpoints of the subscription of this node on `topic`.
+    ///
+    /// Returns [`Error::UnsupportedFeature`] if the middleware cannot report them.
+    /// See also [`SubscriptionOptions::unique_network_flow_endpoint`].
+    pub fn get_subscription_network_flow_endpoints(
+        &self, topic: &str,
+    ) -> Result<Vec<NetworkFlowEndpoint>> {
+        let (_, subscription) = self.find_subscription(topic)?;
+        subscription_network_flow_endpoints(subscription)
+    }
+
+    // the subscription of this node on `topic`, and the resolved topic name.
+    fn find_subscription(&self, topic: &str) -> Result<(String, &rcl_subscription_t)> {
+        let topic = self.resolve_topic_name(topic)?;
+        let subscription = self
+            .subscribers
+            .iter()
+            .map(|s| s.handle())
+            .find(|h| {
+                let name = unsafe { rcl_subscription_get_topic_name(*h) };
+                !name.is_null() && unsafe { CStr::from_ptr(name) }.to_str() == Ok(topic.as_str())
+            })
+            .ok_or(Error::RCL_RET_SUBSCRIPTION_INVALID)?;
+        Ok((topic, subscription))
+    }
+
     /// Create a ROS service.
     ///
     /// This function returns a `Stream` of `ServiceRequest`:s. Call
@@ -704,21 +2131,241 @@ impl Node {
     where
         T: WrappedServiceTypeSupport,
     {
-        let service_handle = create_service_helper(
+        self.create_service_with_options(service_name, qos_profile, ServiceOptions::default())
+    }
+
+    /// Create a ROS service with the given [`ServiceOptions`].
+    ///
+    /// Enabling service introspection requires ROS 2 Iron or later, on
+    /// earlier distributions [`Error::UnsupportedFeature`] is returned.
+    pub fn create_service_with_options<T: 'static>(
+        &mut self, service_name: &str, qos_profile: QosProfile, options: ServiceOptions,
+    ) -> Result<impl Stream<Item = ServiceRequest<T>> + Unpin>
+    where
+        T: WrappedServiceTypeSupport,
+    {
+        let mut service_handle = create_service_helper(
             self.node_handle.as_mut(),
             service_name,
             T::get_ts(),
             qos_profile,
         )?;
+        if options.introspection != ServiceIntrospectionState::Disabled {
+            let mut clock = self.ros_clock.lock().unwrap();
+            if let Err(e) = configure_service_introspection(
+                &mut service_handle,
+                self.node_handle.as_mut(),
+                clock.clock_handle.as_mut(),
+                T::get_ts(),
+                options.introspection,
+            ) {
+                unsafe {
+                    rcl_service_fini(&mut service_handle, self.node_handle.as_mut());
+                }
+                return Err(e);
+            }
+        }
         let (sender, receiver) = mpsc::channel::<ServiceRequest<T>>(10);
 
-        let ws = TypedService::<T> {
-            rcl_handle: service_handle,
-            sender,
+        let ws = TypedService::<T> {
+            rcl_handle: service_handle,
+            sender,
+        };
+
+        self.services.push(Arc::new(Mutex::new(ws)));
+        Ok(receiver)
+    }
+
+    /// Observe the requests and responses of a service, like `ros2 service echo`.
+    ///
+    /// The spy subscribes to the service event topic `<service>/_service_event`,
+    /// so introspection with [`ServiceIntrospectionState::Contents`] must be
+    /// enabled on the server or the client. A call is yielded once its
+    /// response is seen. Requires ROS 2 Iron or later, on earlier
+    /// distributions [`Error::UnsupportedFeature`] is returned.
+    pub fn create_service_spy<T>(
+        &mut self, service_name: &str,
+    ) -> Result<impl Stream<Item = ServiceInteraction<T>> + Unpin>
+    where
+        T: WrappedServiceTypeSupport + 'static,
+    {
+        let (sender, receiver) = mpsc::channel::<ServiceInteraction<T>>(10);
+        let spy = ServiceSpySubscriber::<T>::new(self.node_handle.as_mut(), service_name, sender)?;
+        self.subscribers.push(Box::new(spy));
+        Ok(receiver)
+    }
+
+    /// Create a ROS service with an async handler.
+    ///
+    /// For each request, `handler` is called and the returned future is driven
+    /// by `spin_once`. When it completes, its output is sent as the response.
+    /// Requests are handled concurrently, so the handler can e.g. await other
+    /// service calls of the same node without blocking it.
+    pub fn create_async_service<T: 'static, F, Fut>(
+        &mut self, service_name: &str, qos_profile: QosProfile, handler: F,
+    ) -> Result<()>
+    where
+        T: WrappedServiceTypeSupport,
+        F: Fn(T::Request) -> Fut + Send + 'static,
+        Fut: Future<Output = T::Response> + Send + 'static,
+    {
+        let requests = self.create_service::<T>(service_name, qos_profile)?;
+        let name: Arc<str> = match self.services.last() {
+            Some(s) => {
+                format!("{} handler", executor_stats::service_name(s.lock().unwrap().handle()))
+            }
+            None => format!("service {} handler", service_name),
+        }
+        .into();
+        let statistics = self.statistics.clone();
+        let task = requests.for_each_concurrent(None, move |req| {
+            // the time spent in the handler, summed over all polls of its future.
+            let start = Instant::now();
+            let response = handler(req.message.clone());
+            let mut elapsed = start.elapsed();
+            let name = name.clone();
+            let statistics = statistics.clone();
+            async move {
+                let mut response = pin!(response);
+                let response = future::poll_fn(|cx| {
+                    let start = Instant::now();
+                    let poll = response.as_mut().poll(cx);
+                    elapsed += start.elapsed();
+                    poll
+                })
+                .await;
+                if let Some(stats) = statistics.get() {
+                    let key = Arc::as_ptr(&name) as *const u8 as usize;
+                    stats
+                        .write()
+                        .unwrap()
+                        .record(key, elapsed, || name.to_string());
+                }
+                if let Err(e) = req.respond(response) {
+                    log::debug!("could not send service response: {}", e);
+                }
+            }
+        });
+        self.spawn_async_task(Box::pin(task))
+    }
+
+    /// Create a ROS service where `handler` is given the requests.
+    ///
+    /// Unlike [`Node::create_async_service`], the handler gets the whole
+    /// [`ServiceRequest`], and the future it returns is driven by `spin_once`.
+    /// The request can be kept and responded to later, from any task or
+    /// thread, so responses may be sent in a different order than the
+    /// requests arrived.
+    pub fn create_stateful_service<T, F, Fut>(
+        &mut self, service_name: &str, qos_profile: QosProfile, handler: F,
+    ) -> Result<()>
+    where
+        T: WrappedServiceTypeSupport + 'static,
+        F: Fn(ServiceRequest<T>) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let requests = self.create_service::<T>(service_name, qos_profile)?;
+        let task = requests.for_each_concurrent(None, handler);
+        self.spawn_async_task(Box::pin(task))
+    }
+
+    /// Create a ROS service forwarding its requests to another service.
+    ///
+    /// Each request to `inbound_name` is passed through `transform` and sent
+    /// to `outbound_name`, and the response is sent back to the caller. The
+    /// forwarding is driven by `spin_once`. When the outbound call fails, e.g.
+    /// because the service is not available, the inbound request is dropped
+    /// without a response.
+    pub fn create_service_forwarder<T, F>(
+        &mut self, inbound_name: &str, outbound_name: &str, transform: F,
+    ) -> Result<()>
+    where
+        T: WrappedServiceTypeSupport + 'static,
+        T::Request: Send,
+        T::Response: Send,
+        F: Fn(T::Request) -> T::Request + Send + Sync + 'static,
+    {
+        self.create_service_forwarder_with_response::<T, _, _>(
+            inbound_name,
+            outbound_name,
+            transform,
+            |response| response,
+        )
+    }
+
+    /// Like [`Node::create_service_forwarder`], with the responses of the
+    /// outbound service passed through `transform_response`.
+    pub fn create_service_forwarder_with_response<T, F, G>(
+        &mut self, inbound_name: &str, outbound_name: &str, transform_request: F,
+        transform_response: G,
+    ) -> Result<()>
+    where
+        T: WrappedServiceTypeSupport + 'static,
+        T::Request: Send,
+        T::Response: Send,
+        F: Fn(T::Request) -> T::Request + Send + Sync + 'static,
+        G: Fn(T::Response) -> T::Response + Send + Sync + 'static,
+    {
+        let client =
+            Arc::new(self.create_client::<T>(outbound_name, QosProfile::services_default())?);
+        // requests are only sent once the outbound service is available.
+        let available = Self::is_available(&*client)?.shared();
+        let transform_request = Arc::new(transform_request);
+        let transform_response = Arc::new(transform_response);
+        let outbound_name = outbound_name.to_owned();
+        self.create_stateful_service::<T, _, _>(
+            inbound_name,
+            QosProfile::services_default(),
+            move |req: ServiceRequest<T>| {
+                let request = transform_request(req.message.clone());
+                let client = client.clone();
+                let available = available.clone();
+                let transform_response = transform_response.clone();
+                let outbound_name = outbound_name.clone();
+                async move {
+                    let response = match available.await.and_then(|()| client.request(&request)) {
+                        Ok(response) => response.await,
+                        Err(e) => Err(e),
+                    };
+                    match response {
+                        Ok(response) => {
+                            if let Err(e) = req.respond(transform_response(response)) {
+                                log::debug!("could not send service response: {}", e);
+                            }
+                        }
+                        Err(e) => log::warn!("could not forward request to {outbound_name}: {e}"),
+                    }
+                }
+            },
+        )
+    }
+
+    /// Add a future to be driven by spin_once.
+    fn spawn_async_task(&mut self, task: Pin<Box<dyn Future<Output = ()> + Send>>) -> Result<()> {
+        if self.async_task_waker.is_none() {
+            let guard_condition = {
+                let mut ctx = self.context.context_handle.lock().unwrap();
+                Arc::new(GuardCondition_::new(ctx.as_mut())?)
+            };
+            self.guard_conditions.push(guard_condition.clone());
+            self.async_task_waker = Some(guard_condition);
+        }
+        self.async_tasks.push(task);
+        // make sure the new task is polled on the next spin.
+        if let Some(g) = &self.async_task_waker {
+            g.trigger()?;
+        }
+        Ok(())
+    }
+
+    /// Poll the async tasks until none of them can make progress.
+    pub(crate) fn poll_async_tasks(&mut self) {
+        let Some(g) = &self.async_task_waker else {
+            return;
         };
-
-        self.services.push(Arc::new(Mutex::new(ws)));
-        Ok(receiver)
+        let waker = futures::task::waker(Arc::new(GuardConditionWaker(Arc::downgrade(g))));
+        let mut cx = std::task::Context::from_waker(&waker);
+        while let std::task::Poll::Ready(Some(())) = self.async_tasks.poll_next_unpin(&mut cx) {}
     }
 
     /// Create a ROS service client.
@@ -730,16 +2377,65 @@ impl Node {
     where
         T: WrappedServiceTypeSupport,
     {
-        let client_handle = create_client_helper(
+        self.create_client_with_options(service_name, qos_profile, ServiceOptions::default())
+    }
+
+    /// Create a pool of `size` clients of a ROS service.
+    ///
+    /// Tasks check out a client for their requests, see [`ClientPool`].
+    pub fn create_client_pool<T>(
+        &mut self, service_name: &str, qos_profile: QosProfile, size: usize,
+    ) -> Result<ClientPool<T>>
+    where
+        T: WrappedServiceTypeSupport + 'static,
+    {
+        if size == 0 {
+            return Err(Error::RCL_RET_INVALID_ARGUMENT);
+        }
+        let clients = (0..size)
+            .map(|_| self.create_client::<T>(service_name, qos_profile.clone()))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(ClientPool::new(clients))
+    }
+
+    /// Create a ROS service client with the given [`ServiceOptions`].
+    ///
+    /// Enabling service introspection requires ROS 2 Iron or later, on
+    /// earlier distributions [`Error::UnsupportedFeature`] is returned.
+    pub fn create_client_with_options<T: 'static>(
+        &mut self, service_name: &str, qos_profile: QosProfile, options: ServiceOptions,
+    ) -> Result<Client<T>>
+    where
+        T: WrappedServiceTypeSupport,
+    {
+        let mut client_handle = create_client_helper(
             self.node_handle.as_mut(),
             service_name,
             T::get_ts(),
             qos_profile,
         )?;
+        if options.introspection != ServiceIntrospectionState::Disabled {
+            let mut clock = self.ros_clock.lock().unwrap();
+            if let Err(e) = configure_client_introspection(
+                &mut client_handle,
+                self.node_handle.as_mut(),
+                clock.clock_handle.as_mut(),
+                T::get_ts(),
+                options.introspection,
+            ) {
+                unsafe {
+                    rcl_client_fini(&mut client_handle, self.node_handle.as_mut());
+                }
+                return Err(e);
+            }
+        }
         let ws = TypedClient::<T> {
             rcl_handle: client_handle,
             response_channels: Vec::new(),
+            request_deadlines: Vec::new(),
             poll_available_channels: Vec::new(),
+            service_waiters: Vec::new(),
+            server_available: false,
         };
 
         let client_arc = Arc::new(Mutex::new(ws));
@@ -768,6 +2464,7 @@ impl Node {
             service_type,
             rcl_handle: client_handle,
             response_channels: Vec::new(),
+            request_deadlines: Vec::new(),
             poll_available_channels: Vec::new(),
         };
 
@@ -858,6 +2555,44 @@ impl Node {
     pub fn create_action_server<T: 'static>(
         &mut self, action_name: &str,
     ) -> Result<impl Stream<Item = ActionServerGoalRequest<T>> + Unpin>
+    where
+        T: WrappedActionTypeSupport,
+    {
+        self.create_action_server_internal(action_name, None, ActionServerOptions::default())
+    }
+
+    /// Create a ROS action server with the given [`ActionServerOptions`].
+    pub fn create_action_server_with_options<T>(
+        &mut self, action_name: &str, options: ActionServerOptions,
+    ) -> Result<impl Stream<Item = ActionServerGoalRequest<T>> + Unpin>
+    where
+        T: WrappedActionTypeSupport + 'static,
+    {
+        self.create_action_server_internal(action_name, None, options)
+    }
+
+    /// Create a ROS action server handling one goal at a time.
+    ///
+    /// Like [`Node::create_action_server`], but a new goal request is only
+    /// handed out on the stream when the previous one is done. Goals arriving
+    /// in the meantime are handled according to `policy`.
+    pub fn create_action_server_with_policy<T: 'static>(
+        &mut self, action_name: &str, policy: GoalHandlingPolicy,
+    ) -> Result<impl Stream<Item = ActionServerGoalRequest<T>> + Unpin>
+    where
+        T: WrappedActionTypeSupport,
+    {
+        self.create_action_server_internal(
+            action_name,
+            Some(policy),
+            ActionServerOptions::default(),
+        )
+    }
+
+    fn create_action_server_internal<T: 'static>(
+        &mut self, action_name: &str, policy: Option<GoalHandlingPolicy>,
+        options: ActionServerOptions,
+    ) -> Result<mpsc::Receiver<ActionServerGoalRequest<T>>>
     where
         T: WrappedActionTypeSupport,
     {
@@ -880,6 +2615,7 @@ impl Node {
             action_name,
             clock_handle.as_mut(),
             T::get_ts(),
+            &options,
         )?;
         let server = WrappedActionServer::<T> {
             rcl_handle: server_handle,
@@ -887,9 +2623,14 @@ impl Node {
             goal_request_sender,
             active_cancel_requests: Vec::new(),
             cancel_senders: HashMap::new(),
+            cancel_signals: HashMap::new(),
             goals: HashMap::new(),
             result_msgs: HashMap::new(),
             result_requests: HashMap::new(),
+            policy,
+            active_goal: None,
+            pending_goals: VecDeque::new(),
+            preempted_goals: HashSet::new(),
         };
 
         let server_arc = Arc::new(Mutex::new(server));
@@ -897,6 +2638,29 @@ impl Node {
         Ok(goal_request_receiver)
     }
 
+    /// Create a ROS action server running its goals on worker threads.
+    ///
+    /// Every goal is accepted and handed to `handler` on one of `concurrency`
+    /// threads, so long running handlers do not block the thread spinning the
+    /// node. Goals arriving while all workers are busy wait in the accepted
+    /// state. Cancel requests are accepted, and the handler should check
+    /// [`ActionServerGoal::is_cancel_requested`] and cancel the goal. If the
+    /// handler panics, the goal is aborted and the thread takes the next goal.
+    pub fn create_action_server_threaded<T, F>(
+        &mut self, action_name: &str, concurrency: usize, handler: F,
+    ) -> Result<()>
+    where
+        T: WrappedActionTypeSupport + 'static,
+        F: Fn(ActionServerGoal<T>) + Send + Sync + 'static,
+    {
+        if concurrency == 0 {
+            return Err(Error::RCL_RET_INVALID_ARGUMENT);
+        }
+        let requests = self.create_action_server::<T>(action_name)?;
+        run_threaded(requests, concurrency, handler);
+        Ok(())
+    }
+
     /// Create a ROS publisher.
     pub fn create_publisher<T>(
         &mut self, topic: &str, qos_profile: QosProfile,
@@ -904,6 +2668,7 @@ impl Node {
     where
         T: WrappedTypesupport,
     {
+        self.check_topic_type(topic, T::get_ts())?;
         let publisher_handle =
             create_publisher_helper(self.node_handle.as_mut(), topic, T::get_ts(), qos_profile)?;
         let arc = Arc::new(publisher_handle);
@@ -912,13 +2677,77 @@ impl Node {
         Ok(p)
     }
 
+    /// Create a publisher and a stream receiving its messages within this node.
+    ///
+    /// Messages published with the [`IntraPublisher`] are moved to the stream
+    /// without going through the middleware, so no serialization is involved.
+    /// Subscriptions of other nodes still receive them on `topic`.
+    pub fn create_intranode_channel<T>(
+        &mut self, topic: &str, qos_profile: QosProfile,
+    ) -> Result<(IntraPublisher<T>, impl Stream<Item = T> + Unpin)>
+    where
+        T: WrappedTypesupport + 'static,
+    {
+        let publisher = self.create_publisher::<T>(topic, qos_profile)?;
+        let (sender, receiver) = mpsc::channel::<T>(10);
+        Ok((IntraPublisher::new(publisher, sender), receiver))
+    }
+
+    /// Create a ROS publisher with additional publisher options.
+    ///
+    /// The QoS event callbacks of `options` are called from `spin_once`.
+    pub fn create_publisher_with_options<T>(
+        &mut self, topic: &str, qos_profile: QosProfile, options: PublisherOptions,
+    ) -> Result<Publisher<T>>
+    where
+        T: WrappedTypesupport,
+    {
+        self.check_topic_type(topic, T::get_ts())?;
+        let publisher_handle = create_publisher_helper_with_options(
+            self.node_handle.as_mut(),
+            topic,
+            T::get_ts(),
+            qos_profile,
+            &options,
+        )?;
+        match create_publisher_events(publisher_handle.handle(), &options) {
+            Ok(events) => self.qos_events.extend(events),
+            Err(e) => {
+                publisher_handle.destroy(self.node_handle.as_mut());
+                return Err(e);
+            }
+        }
+        let arc = Arc::new(publisher_handle);
+        let p = make_publisher(Arc::downgrade(&arc));
+        self.pubs.push(arc);
+        Ok(p)
+    }
+
+    /// Create a latched ROS publisher.
+    ///
+    /// This is a shorthand for [`Node::create_publisher`] with a reliable, transient
+    /// local QoS profile keeping the last `depth` messages, so that subscribers joining
+    /// later still receive them.
+    ///
+    /// Note that late joining subscribers must also be transient local, e.g. created with
+    /// [`Node::subscribe_latched`]. A volatile subscriber will only receive new messages.
+    pub fn create_publisher_latched<T>(&mut self, topic: &str, depth: usize) -> Result<Publisher<T>>
+    where
+        T: WrappedTypesupport,
+    {
+        self.create_publisher(topic, latched_qos(depth))
+    }
+
     /// Create a ROS publisher with a type given at runtime, where the data may either be
     /// supplied as JSON (using the `publish` method) or a pre-serialized ROS message
     /// (i.e. &[u8], using the `publish_raw` method).
+    ///
+    /// `topic_type` is given in the form `"std_msgs/msg/String"`.
     pub fn create_publisher_untyped(
         &mut self, topic: &str, topic_type: &str, qos_profile: QosProfile,
     ) -> Result<PublisherUntyped> {
         let dummy = WrappedNativeMsgUntyped::new_from(topic_type)?;
+        self.check_topic_type(topic, dummy.ts)?;
         let publisher_handle =
             create_publisher_helper(self.node_handle.as_mut(), topic, dummy.ts, qos_profile)?;
         let arc = Arc::new(publisher_handle);
@@ -927,6 +2756,18 @@ impl Node {
         Ok(p)
     }
 
+    /// Create a type erased ROS publisher.
+    ///
+    /// `topic_type` is given in the form `"std_msgs/msg/String"`. As
+    /// the type is only known at runtime, messages are published with
+    /// [`AnyPublisher::publish_raw`].
+    pub fn create_any_publisher(
+        &mut self, topic: &str, topic_type: &str, qos_profile: QosProfile,
+    ) -> Result<AnyPublisher> {
+        self.create_publisher_untyped(topic, topic_type, qos_profile)
+            .map(AnyPublisher::from_untyped)
+    }
+
     /// Destroy a ROS publisher.
     pub fn destroy_publisher<T: WrappedTypesupport>(&mut self, p: Publisher<T>) {
         if let Some(handle) = p.handle.upgrade() {
@@ -935,6 +2776,7 @@ impl Node {
                 .map(|i| self.pubs.swap_remove(i));
 
             let handle = wait_until_unwrapped(handle);
+            destroy_publisher_events(&mut self.qos_events, handle.handle());
             handle.destroy(self.node_handle.as_mut());
         }
     }
@@ -947,10 +2789,83 @@ impl Node {
                 .map(|i| self.pubs.swap_remove(i));
 
             let handle = wait_until_unwrapped(handle);
+            destroy_publisher_events(&mut self.qos_events, handle.handle());
             handle.destroy(self.node_handle.as_mut());
         }
     }
 
+    /// Destroy the node and all its entities, keeping the context alive.
+    ///
+    /// This is what dropping the node does, but reports if the node could
+    /// not be finalized. Like [`Node::destroy_publisher`], it blocks while a
+    /// publisher is in use by another thread. Afterwards, publishers, clients
+    /// and timers of the node return errors, and its subscription and
+    /// service streams end.
+    pub fn shutdown(mut self) -> Result<()> {
+        self.destroy_entities()
+    }
+
+    // finalize the entities and then the node, which leaves nothing to do
+    // when the node is dropped after a shutdown.
+    fn destroy_entities(&mut self) -> Result<()> {
+        if self.node_handle.impl_.is_null() {
+            return Ok(());
+        }
+        // waits for graph queries of the subscriptions in progress.
+        *self.graph_alive.lock().unwrap() = false;
+        // fini functions are not thread safe so lock the context.
+        let context = self.context.clone();
+        let _ctx_handle = context.context_handle.lock().unwrap();
+
+        // events first, as they refer to their publisher or subscription.
+        for mut e in self.qos_events.drain(..) {
+            e.destroy();
+        }
+        for mut s in self.subscribers.drain(..) {
+            s.destroy(&mut self.node_handle);
+        }
+        for c in self.clients.drain(..) {
+            c.lock().unwrap().destroy(&mut self.node_handle);
+        }
+        for s in self.services.drain(..) {
+            s.lock().unwrap().destroy(&mut self.node_handle);
+        }
+        for c in self.action_clients.drain(..) {
+            c.lock().unwrap().destroy(&mut self.node_handle);
+        }
+        for s in self.action_servers.drain(..) {
+            s.lock().unwrap().destroy(&mut self.node_handle);
+        }
+        while let Some(p) = self.pubs.pop() {
+            let p = wait_until_unwrapped(p);
+
+            p.destroy(self.node_handle.as_mut());
+        }
+        self.timers.clear();
+        for g in &self.guard_conditions {
+            g.clear_waiters();
+        }
+        if let Ok(name) = self.fully_qualified_name() {
+            self.context.remove_node_name(&name);
+        }
+        let ret = unsafe { rcl_node_fini(self.node_handle.as_mut()) };
+        if ret == RCL_RET_OK as i32 {
+            Ok(())
+        } else {
+            Err(Error::from_rcl_error(ret))
+        }
+    }
+
+    /// Measure the latency of the callbacks run by `spin_once`.
+    ///
+    /// From now on, handling the messages of each subscription, the ticks of
+    /// each timer and the requests of each service is timed, as are the
+    /// handlers of async services. The returned statistics are updated by
+    /// each spin. Calling this again returns the same statistics.
+    pub fn enable_statistics(&mut self) -> Arc<RwLock<ExecutorStats>> {
+        self.statistics.get_or_init(Default::default).clone()
+    }
+
     /// Spin the ROS node.
     ///
     /// This handles wakeups of all subscribes, services, etc on the
@@ -961,14 +2876,61 @@ impl Node {
     /// `timeout` is a duration specifying how long the spin should
     /// block for if there are no pending events.
     pub fn spin_once(&mut self, timeout: Duration) {
+        let wait_for_graph = self.prepare_wait();
+        let (counts, action_counts) = self.wait_set_counts(wait_for_graph);
+        let counts = counts.add(action_counts);
+
+        let timeout = timeout.as_nanos() as i64;
+        let mut ws = unsafe { rcl_get_zero_initialized_wait_set() };
+
+        // #[doc = "* This function is thread-safe for unique wait sets with unique contents."]
+        // #[doc = "* This function cannot operate on the same wait set in multiple threads, and"]
+        // #[doc = "* the wait sets may not share content."]
+        // #[doc = "* For example, calling rcl_wait() in two threads on two different wait sets"]
+        // #[doc = "* that both contain a single, shared guard condition is undefined behavior."]
+        {
+            let mut ctx = self.context.context_handle.lock().unwrap();
+            unsafe {
+                counts.init_wait_set(&mut ws, ctx.as_mut());
+            }
+        }
+
+        self.add_to_wait_set(&mut ws, wait_for_graph);
+        self.add_actions_to_wait_set(&mut ws);
+
+        let ret = unsafe { rcl_wait(&mut ws, timeout) };
+
+        if ret == RCL_RET_TIMEOUT as i32 {
+            unsafe {
+                rcl_wait_set_fini(&mut ws);
+            }
+            return;
+        }
+
+        self.dispatch_wait_set(&ws, WaitSetCounts::default(), wait_for_graph);
+
+        unsafe {
+            rcl_wait_set_fini(&mut ws);
+        }
+
+        // last, as the handling above may have woken up some of the tasks.
+        self.poll_async_tasks();
+    }
+
+    /// The work done by a spin before waiting. Returns true if the graph
+    /// guard condition should be waited for.
+    pub(crate) fn prepare_wait(&mut self) -> bool {
         // first handle any completed action cancellation responses
         for a in &mut self.action_servers {
             a.lock().unwrap().send_completed_cancel_requests();
         }
 
         // as well as polling any services/action servers for availability
+        // and timing out service requests
         for c in &mut self.clients {
-            c.lock().unwrap().poll_available(self.node_handle.as_mut());
+            let mut c = c.lock().unwrap();
+            c.poll_available(self.node_handle.as_mut());
+            c.handle_timeouts(self.node_handle.as_mut());
         }
 
         for c in &mut self.action_clients {
@@ -977,17 +2939,32 @@ impl Node {
 
         for p in &self.pubs {
             p.poll_has_inter_process_subscribers();
+            p.poll_subscriber_waiters();
+            p.poll_publish_retries();
         }
 
-        let timeout = timeout.as_nanos() as i64;
-        let mut ws = unsafe { rcl_get_zero_initialized_wait_set() };
-
-        // #[doc = "* This function is thread-safe for unique wait sets with unique contents."]
-        // #[doc = "* This function cannot operate on the same wait set in multiple threads, and"]
-        // #[doc = "* the wait sets may not share content."]
-        // #[doc = "* For example, calling rcl_wait() in two threads on two different wait sets"]
-        // #[doc = "* that both contain a single, shared guard condition is undefined behavior."]
+        #[cfg(r2r__rosgraph_msgs__msg__Clock)]
+        self.time_source.check_clock_timeout(self.logger());
+
+        // only wait for graph changes if someone is listening.
+        self.graph_event_subscribers.retain(|s| !s.is_closed());
+        self.poll_graph_waiters(false);
+        let wait_for_graph = !self.graph_event_subscribers.is_empty()
+            || !self.graph_waiters.is_empty()
+            || self
+                .clients
+                .iter()
+                .any(|c| c.lock().unwrap().waits_for_graph());
+
+        #[cfg(r2r__statistics_msgs__msg__MetricsMessage)]
+        self.publish_topic_statistics();
+
+        wait_for_graph
+    }
 
+    /// The number of entities of this node in a wait set, the plain
+    /// entities and those of actions, which are added after all plain ones.
+    pub(crate) fn wait_set_counts(&self, wait_for_graph: bool) -> (WaitSetCounts, WaitSetCounts) {
         // count action client wait set needs
         let mut total_action_subs = 0;
         let mut total_action_clients = 0;
@@ -1048,51 +3025,75 @@ impl Node {
             total_action_services += num_services;
         }
 
-        {
-            let mut ctx = self.context.context_handle.lock().unwrap();
+        let counts = WaitSetCounts {
+            subscriptions: self.subscribers.len(),
+            guard_conditions: self.guard_conditions.len() + wait_for_graph as usize,
+            timers: self.timers.len(),
+            clients: self.clients.len(),
+            services: self.services.len(),
+            events: self.qos_events.len(),
+        };
+        let action_counts = WaitSetCounts {
+            subscriptions: total_action_subs,
+            timers: total_action_timers,
+            clients: total_action_clients,
+            services: total_action_services,
+            ..WaitSetCounts::default()
+        };
+        (counts, action_counts)
+    }
 
+    /// Add the entities, except those of actions, to a wait set.
+    pub(crate) fn add_to_wait_set(&self, ws: &mut rcl_wait_set_t, wait_for_graph: bool) {
+        for s in &self.subscribers {
             unsafe {
-                rcl_wait_set_init(
-                    &mut ws,
-                    self.subscribers.len() + total_action_subs,
-                    0,
-                    self.timers.len() + total_action_timers,
-                    self.clients.len() + total_action_clients,
-                    self.services.len() + total_action_services,
-                    0,
-                    ctx.as_mut(),
-                    rcutils_get_default_allocator(),
-                );
+                rcl_wait_set_add_subscription(ws, s.handle(), std::ptr::null_mut());
             }
         }
-        unsafe {
-            rcl_wait_set_clear(&mut ws);
+
+        for s in &self.guard_conditions {
+            unsafe {
+                rcl_wait_set_add_guard_condition(ws, s.handle(), std::ptr::null_mut());
+            }
         }
 
-        for s in &self.subscribers {
+        if wait_for_graph {
             unsafe {
-                rcl_wait_set_add_subscription(&mut ws, s.handle(), std::ptr::null_mut());
+                rcl_wait_set_add_guard_condition(
+                    ws,
+                    rcl_node_get_graph_guard_condition(self.node_handle.as_ref()),
+                    std::ptr::null_mut(),
+                );
             }
         }
 
         for s in &self.timers {
             unsafe {
-                rcl_wait_set_add_timer(&mut ws, s.get_handle(), std::ptr::null_mut());
+                rcl_wait_set_add_timer(ws, s.get_handle(), std::ptr::null_mut());
             }
         }
 
         for s in &self.clients {
             unsafe {
-                rcl_wait_set_add_client(&mut ws, s.lock().unwrap().handle(), std::ptr::null_mut());
+                rcl_wait_set_add_client(ws, s.lock().unwrap().handle(), std::ptr::null_mut());
             }
         }
 
         for s in &self.services {
             unsafe {
-                rcl_wait_set_add_service(&mut ws, s.lock().unwrap().handle(), std::ptr::null_mut());
+                rcl_wait_set_add_service(ws, s.lock().unwrap().handle(), std::ptr::null_mut());
+            }
+        }
+
+        for e in &self.qos_events {
+            unsafe {
+                rcl_wait_set_add_event(ws, e.handle(), std::ptr::null_mut());
             }
         }
+    }
 
+    /// Add the entities of the actions to a wait set.
+    pub(crate) fn add_actions_to_wait_set(&self, ws: &mut rcl_wait_set_t) {
         // code (further) below assumes that actions are added last... perhaps a
         // bad assumption.  e.g. we add subscriptions and timers of
         // the node before ones created automatically by actions. we
@@ -1101,7 +3102,7 @@ impl Node {
         for ac in &self.action_clients {
             unsafe {
                 rcl_action_wait_set_add_action_client(
-                    &mut ws,
+                    ws,
                     ac.lock().unwrap().handle(),
                     std::ptr::null_mut(),
                     std::ptr::null_mut(),
@@ -1111,30 +3112,58 @@ impl Node {
         for acs in &self.action_servers {
             unsafe {
                 rcl_action_wait_set_add_action_server(
-                    &mut ws,
+                    ws,
                     acs.lock().unwrap().handle(),
                     std::ptr::null_mut(),
                 );
             }
         }
+    }
 
-        let ret = unsafe { rcl_wait(&mut ws, timeout) };
-
-        if ret == RCL_RET_TIMEOUT as i32 {
-            unsafe {
-                rcl_wait_set_fini(&mut ws);
+    /// Handle the ready entities of this node in a wait set, which start at
+    /// `offsets` in the arrays of the wait set.
+    pub(crate) fn dispatch_wait_set(
+        &mut self, ws: &rcl_wait_set_t, offsets: WaitSetCounts, wait_for_graph: bool,
+    ) {
+        // before the subscriptions, as removing one also removes its events.
+        if !ws.events.is_null() {
+            let ws_events = unsafe {
+                std::slice::from_raw_parts(ws.events.add(offsets.events), self.qos_events.len())
+            };
+            for (e, ws_e) in self.qos_events.iter().zip(ws_events) {
+                if !ws_e.is_null() {
+                    e.handle_event();
+                }
             }
-            return;
         }
 
+        let statistics = self.statistics.get().cloned();
+        let statistics = statistics.as_ref();
+
         let mut subs_to_remove = vec![];
         if ws.subscriptions != std::ptr::null_mut() {
-            let ws_subs =
-                unsafe { std::slice::from_raw_parts(ws.subscriptions, self.subscribers.len()) };
+            let ws_subs = unsafe {
+                std::slice::from_raw_parts(
+                    ws.subscriptions.add(offsets.subscriptions),
+                    self.subscribers.len(),
+                )
+            };
             for (s, ws_s) in self.subscribers.iter_mut().zip(ws_subs) {
                 if ws_s != &std::ptr::null() {
+                    let start = statistics.map(|_| Instant::now());
                     let dropped = s.handle_incoming();
+                    if let (Some(stats), Some(start)) = (statistics, start) {
+                        let key = s.handle() as *const _ as usize;
+                        let mut stats = stats.write().unwrap();
+                        stats.record(key, start.elapsed(), || {
+                            executor_stats::subscription_name(s.handle())
+                        });
+                        if dropped {
+                            stats.forget(key);
+                        }
+                    }
                     if dropped {
+                        destroy_subscription_events(&mut self.qos_events, s.handle());
                         s.destroy(&mut self.node_handle);
                         subs_to_remove.push(*s.handle());
                     }
@@ -1144,25 +3173,75 @@ impl Node {
         self.subscribers
             .retain(|s| !subs_to_remove.contains(s.handle()));
 
+        if !ws.guard_conditions.is_null() {
+            let ws_guard_conditions = unsafe {
+                std::slice::from_raw_parts(
+                    ws.guard_conditions.add(offsets.guard_conditions),
+                    self.guard_conditions.len(),
+                )
+            };
+            for (s, ws_s) in self.guard_conditions.iter().zip(ws_guard_conditions) {
+                if !ws_s.is_null() {
+                    s.wake_waiters();
+                }
+            }
+
+            // the graph guard condition is added last.
+            let graph_changed = wait_for_graph
+                && !unsafe {
+                    *ws.guard_conditions
+                        .add(offsets.guard_conditions + self.guard_conditions.len())
+                }
+                .is_null();
+            if graph_changed && !self.graph_event_subscribers.is_empty() {
+                match self.graph_snapshot() {
+                    Ok(snapshot) => {
+                        for s in &mut self.graph_event_subscribers {
+                            s.update(&snapshot);
+                        }
+                    }
+                    Err(e) => log::debug!("could not get the ROS graph: {}", e),
+                }
+            }
+            if graph_changed {
+                self.poll_graph_waiters(true);
+            }
+        }
+
         let mut timers_to_remove = vec![];
         if ws.timers != std::ptr::null_mut() {
-            let ws_timers = unsafe { std::slice::from_raw_parts(ws.timers, self.timers.len()) };
+            let ws_timers = unsafe {
+                std::slice::from_raw_parts(ws.timers.add(offsets.timers), self.timers.len())
+            };
             for (s, ws_s) in self.timers.iter_mut().zip(ws_timers) {
                 if ws_s != &std::ptr::null() {
+                    let start = statistics.map(|_| Instant::now());
                     // TODO: move this to impl Timer
                     let dropped = s.handle_incoming();
+                    if let (Some(stats), Some(start)) = (statistics, start) {
+                        let key = s.get_handle() as usize;
+                        let mut stats = stats.write().unwrap();
+                        stats.record(key, start.elapsed(), || {
+                            executor_stats::timer_name(s.get_handle())
+                        });
+                        if dropped {
+                            stats.forget(key);
+                        }
+                    }
                     if dropped {
-                        timers_to_remove.push((*s.timer_handle).to_owned());
+                        timers_to_remove.push(s.get_handle());
                     }
                 }
             }
         }
         // drop timers scheduled for deletion
         self.timers
-            .retain(|t| !timers_to_remove.contains(&*t.timer_handle));
+            .retain(|t| !timers_to_remove.contains(&t.get_handle()));
 
         if ws.clients != std::ptr::null_mut() {
-            let ws_clients = unsafe { std::slice::from_raw_parts(ws.clients, self.clients.len()) };
+            let ws_clients = unsafe {
+                std::slice::from_raw_parts(ws.clients.add(offsets.clients), self.clients.len())
+            };
             for (s, ws_s) in self.clients.iter_mut().zip(ws_clients) {
                 if ws_s != &std::ptr::null() {
                     let mut s = s.lock().unwrap();
@@ -1173,12 +3252,24 @@ impl Node {
 
         let mut services_to_remove = vec![];
         if ws.services != std::ptr::null_mut() {
-            let ws_services =
-                unsafe { std::slice::from_raw_parts(ws.services, self.services.len()) };
+            let ws_services = unsafe {
+                std::slice::from_raw_parts(ws.services.add(offsets.services), self.services.len())
+            };
             for (s, ws_s) in self.services.iter_mut().zip(ws_services) {
                 if ws_s != &std::ptr::null() {
                     let mut service = s.lock().unwrap();
+                    let start = statistics.map(|_| Instant::now());
                     let dropped = service.handle_request(s.clone());
+                    if let (Some(stats), Some(start)) = (statistics, start) {
+                        let key = service.handle() as *const _ as usize;
+                        let mut stats = stats.write().unwrap();
+                        stats.record(key, start.elapsed(), || {
+                            executor_stats::service_name(service.handle())
+                        });
+                        if dropped {
+                            stats.forget(key);
+                        }
+                    }
                     if dropped {
                         service.destroy(&mut self.node_handle);
                         services_to_remove.push(*service.handle());
@@ -1198,7 +3289,7 @@ impl Node {
 
             let ret = unsafe {
                 rcl_action_client_wait_set_get_entities_ready(
-                    &ws,
+                    ws,
                     ac.lock().unwrap().handle(),
                     &mut is_feedback_ready,
                     &mut is_status_ready,
@@ -1246,7 +3337,7 @@ impl Node {
 
             let ret = unsafe {
                 rcl_action_server_wait_set_get_entities_ready(
-                    &ws,
+                    ws,
                     s.lock().unwrap().handle(),
                     &mut is_goal_request_ready,
                     &mut is_cancel_request_ready,
@@ -1255,34 +3346,210 @@ impl Node {
                 )
             };
 
-            if ret != RCL_RET_OK as i32 {
-                continue;
-            }
+            if ret != RCL_RET_OK as i32 {
+                continue;
+            }
+
+            if is_goal_request_ready {
+                let mut acs = s.lock().unwrap();
+                acs.handle_goal_request(s.clone());
+            }
+
+            if is_cancel_request_ready {
+                let mut acs = s.lock().unwrap();
+                acs.handle_cancel_request();
+            }
+
+            if is_result_request_ready {
+                let mut acs = s.lock().unwrap();
+                acs.handle_result_request();
+            }
+
+            if is_goal_expired {
+                let mut acs = s.lock().unwrap();
+                acs.handle_goal_expired();
+            }
+        }
+    }
+
+    /// Returns the names and namespaces of the nodes visible to this
+    /// node, as (name, namespace) pairs.
+    pub fn get_node_names(&self) -> Result<Vec<(String, String)>> {
+        let mut names = unsafe { rcutils_get_zero_initialized_string_array() };
+        let mut namespaces = unsafe { rcutils_get_zero_initialized_string_array() };
+        let ret = unsafe {
+            rcl_get_node_names(
+                self.node_handle.as_ref(),
+                rcutils_get_default_allocator(),
+                &mut names,
+                &mut namespaces,
+            )
+        };
+        if ret != RCL_RET_OK as i32 {
+            log::error!("could not get node names {}", ret);
+            unsafe {
+                rcutils_string_array_fini(&mut names);
+                rcutils_string_array_fini(&mut namespaces);
+            }
+            return Err(Error::from_rcl_error(ret));
+        }
+
+        let res = string_array_to_vec(&names)
+            .into_iter()
+            .zip(string_array_to_vec(&namespaces))
+            .collect();
+        unsafe {
+            rcutils_string_array_fini(&mut names);
+            rcutils_string_array_fini(&mut namespaces);
+        } // TODO: check return value
+        Ok(res)
+    }
+
+    /// Subscribe to changes in the ROS graph.
+    ///
+    /// The stream yields an event when a publisher, subscriber or service
+    /// appears or disappears anywhere in the graph, including the ones of
+    /// this node. Changes are only detected while the node is spinning.
+    pub fn subscribe_to_graph_events(&mut self) -> Result<impl Stream<Item = GraphEvent> + Unpin> {
+        let snapshot = self.graph_snapshot()?;
+        let (sender, receiver) = mpsc::unbounded();
+        self.graph_event_subscribers
+            .push(GraphEventSubscriber::new(snapshot, sender));
+        Ok(receiver)
+    }
 
-            if is_goal_request_ready {
-                let mut acs = s.lock().unwrap();
-                acs.handle_goal_request(s.clone());
-            }
+    /// Wait for the next change in the ROS graph.
+    ///
+    /// The future completes when a publisher, subscriber or service appears or
+    /// disappears anywhere in the graph. With a timeout, the future returns
+    /// [`Error::Timeout`] if nothing changed in time. Changes are only detected
+    /// while the node is spinning.
+    pub fn wait_for_graph_change(
+        &mut self, timeout: Option<Duration>,
+    ) -> impl Future<Output = Result<()>> {
+        self.add_graph_waiter(GraphCondition::Change, timeout)
+    }
 
-            if is_cancel_request_ready {
-                let mut acs = s.lock().unwrap();
-                acs.handle_cancel_request();
-            }
+    /// Wait until there are at least `min_count` publishers on `topic`.
+    ///
+    /// The topic is resolved with [`Node::resolve_topic_name`] and the publishers
+    /// of this node are counted too. With a timeout, the future returns
+    /// [`Error::Timeout`] if the count was not reached in time.
+    pub fn wait_for_publisher(
+        &mut self, topic: &str, min_count: usize, timeout: Option<Duration>,
+    ) -> Result<impl Future<Output = Result<()>>> {
+        let topic = self.resolve_topic_name(topic)?;
+        Ok(self.add_graph_waiter(GraphCondition::Publishers { topic, min_count }, timeout))
+    }
 
-            if is_result_request_ready {
-                let mut acs = s.lock().unwrap();
-                acs.handle_result_request();
-            }
+    /// Wait until there are at least `min_count` subscribers on `topic`.
+    ///
+    /// Like [`Node::wait_for_publisher`], but for subscribers.
+    pub fn wait_for_subscriber(
+        &mut self, topic: &str, min_count: usize, timeout: Option<Duration>,
+    ) -> Result<impl Future<Output = Result<()>>> {
+        let topic = self.resolve_topic_name(topic)?;
+        Ok(self.add_graph_waiter(GraphCondition::Subscribers { topic, min_count }, timeout))
+    }
 
-            if is_goal_expired {
-                let mut acs = s.lock().unwrap();
-                acs.handle_goal_expired();
+    fn add_graph_waiter(
+        &mut self, condition: GraphCondition, timeout: Option<Duration>,
+    ) -> impl Future<Output = Result<()>> {
+        let (sender, receiver) = oneshot::channel();
+        self.graph_waiters.push(GraphWaiter {
+            condition,
+            deadline: timeout.map(|t| Instant::now() + t),
+            sender,
+        });
+        // the sender is dropped together with the node.
+        receiver.map(|r| r.unwrap_or(Err(Error::RCL_RET_NODE_INVALID)))
+    }
+
+    /// Complete the graph waiters whose condition holds or whose deadline passed.
+    fn poll_graph_waiters(&mut self, graph_changed: bool) {
+        if self.graph_waiters.is_empty() {
+            return;
+        }
+        let node = self.node_handle.as_ref();
+        let now = Instant::now();
+        let mut waiters = std::mem::take(&mut self.graph_waiters);
+        waiters.retain(|w| !w.sender.is_canceled());
+        let mut i = 0;
+        while i < waiters.len() {
+            let w = &waiters[i];
+            let count = match &w.condition {
+                GraphCondition::Change => Ok(graph_changed),
+                GraphCondition::Publishers { topic, min_count } => {
+                    count_endpoints(node, topic, rcl_count_publishers).map(|c| c >= *min_count)
+                }
+                GraphCondition::Subscribers { topic, min_count } => {
+                    count_endpoints(node, topic, rcl_count_subscribers).map(|c| c >= *min_count)
+                }
+            };
+            let result = match count {
+                Ok(true) => Some(Ok(())),
+                Ok(false) if w.deadline.is_some_and(|d| now >= d) => Some(Err(Error::Timeout)),
+                Ok(false) => None,
+                Err(e) => Some(Err(e)),
+            };
+            match result {
+                Some(result) => {
+                    // ignore result, as user may have dropped the receiver.
+                    let _ = waiters.swap_remove(i).sender.send(result);
+                }
+                None => i += 1,
             }
         }
+        self.graph_waiters = waiters;
+    }
 
-        unsafe {
-            rcl_wait_set_fini(&mut ws);
+    /// Get all publishers, subscribers and services of all nodes in the graph.
+    fn graph_snapshot(&self) -> Result<GraphSnapshot> {
+        let node = self.node_handle.as_ref();
+        let mut snapshot = GraphSnapshot::new();
+        for (node_name, namespace) in self.get_node_names()? {
+            let c_node_name =
+                CString::new(node_name.as_str()).map_err(|_| Error::RCL_RET_INVALID_ARGUMENT)?;
+            let c_namespace =
+                CString::new(namespace.as_str()).map_err(|_| Error::RCL_RET_INVALID_ARGUMENT)?;
+            for kind in [EndpointKind::Publisher, EndpointKind::Subscriber, EndpointKind::Service] {
+                let mut nat = unsafe { rmw_get_zero_initialized_names_and_types() };
+                let mut allocator = unsafe { rcutils_get_default_allocator() };
+                let (name, ns) = (c_node_name.as_ptr(), c_namespace.as_ptr());
+                let ret = unsafe {
+                    match kind {
+                        EndpointKind::Publisher => rcl_get_publisher_names_and_types_by_node(
+                            node, &mut allocator, false, name, ns, &mut nat,
+                        ),
+                        EndpointKind::Subscriber => rcl_get_subscriber_names_and_types_by_node(
+                            node, &mut allocator, false, name, ns, &mut nat,
+                        ),
+                        EndpointKind::Service => rcl_get_service_names_and_types_by_node(
+                            node, &mut allocator, name, ns, &mut nat,
+                        ),
+                    }
+                };
+                if ret != RCL_RET_OK as i32 {
+                    // the node may have left the graph since listing the nodes.
+                    continue;
+                }
+                for (topic, types) in names_and_types_to_map(&nat) {
+                    for type_name in types {
+                        snapshot.insert(Endpoint {
+                            kind,
+                            node_name: node_name.clone(),
+                            namespace: namespace.clone(),
+                            name: topic.clone(),
+                            type_name,
+                        });
+                    }
+                }
+                unsafe {
+                    rmw_names_and_types_fini(&mut nat);
+                } // TODO: check return value
+            }
         }
+        Ok(snapshot)
     }
 
     /// Returns a map of topic names and type names of the publishers
@@ -1302,36 +3569,151 @@ impl Node {
             return Err(Error::from_rcl_error(ret));
         }
 
-        let mut res = HashMap::new();
-        if tnat.names.data != std::ptr::null_mut() && tnat.types != std::ptr::null_mut() {
-            let names = unsafe { std::slice::from_raw_parts(tnat.names.data, tnat.names.size) };
-            let types = unsafe { std::slice::from_raw_parts(tnat.types, tnat.names.size) };
-
-            for (n, t) in names.iter().zip(types) {
-                assert!(!(*n).is_null());
-                let topic_name = unsafe { CStr::from_ptr(*n).to_str().unwrap().to_owned() };
-                assert!(!t.data.is_null());
-                let topic_types = unsafe { std::slice::from_raw_parts(t, t.size) };
-                let topic_types: Vec<String> = unsafe {
-                    topic_types
-                        .iter()
-                        .map(|t| {
-                            assert!(*(t.data) != std::ptr::null_mut());
-                            CStr::from_ptr(*(t.data)).to_str().unwrap().to_owned()
-                        })
-                        .collect()
-                };
-                res.insert(topic_name, topic_types);
+        let res = names_and_types_to_map(&tnat);
+        unsafe {
+            rmw_names_and_types_fini(&mut tnat);
+        } // TODO: check return value
+        Ok(res)
+    }
+
+    /// Get all service names in the ROS graph along with their types.
+    pub fn get_service_names_and_types(&self) -> Result<HashMap<String, Vec<String>>> {
+        let mut snat = unsafe { rmw_get_zero_initialized_names_and_types() };
+        let ret = unsafe {
+            rcl_get_service_names_and_types(
+                self.node_handle.as_ref(),
+                &mut rcutils_get_default_allocator(),
+                &mut snat,
+            )
+        };
+        if ret != RCL_RET_OK as i32 {
+            log::error!("could not get service names and types {}", ret);
+            return Err(Error::from_rcl_error(ret));
+        }
+
+        let res = names_and_types_to_map(&snat);
+        unsafe {
+            rmw_names_and_types_fini(&mut snat);
+        } // TODO: check return value
+        Ok(res)
+    }
+
+    /// Get the service servers of a node along with their types.
+    pub fn get_service_server_names_and_types_by_node(
+        &self, node_name: &str, namespace: &str,
+    ) -> Result<HashMap<String, Vec<String>>> {
+        names_and_types_by_node(
+            self.node_handle.as_ref(),
+            node_name,
+            namespace,
+            rcl_get_service_names_and_types_by_node,
+        )
+    }
+
+    /// Get the service clients of a node along with their types.
+    pub fn get_client_names_and_types_by_node(
+        &self, node_name: &str, namespace: &str,
+    ) -> Result<HashMap<String, Vec<String>>> {
+        names_and_types_by_node(
+            self.node_handle.as_ref(),
+            node_name,
+            namespace,
+            rcl_get_client_names_and_types_by_node,
+        )
+    }
+
+    /// Gets the number of clients of a service in the ROS graph.
+    ///
+    /// Before Jazzy, rcl has no per service count. The clients of every node
+    /// in the graph are listed instead, which makes this rather expensive, and
+    /// what is counted are the nodes with a client of the service: several
+    /// clients in one node count once.
+    pub fn get_client_count(&self, service_name: &str) -> Result<usize> {
+        let service_name = self.resolve_service_name(service_name)?;
+        self.count_clients(&service_name)
+    }
+
+    #[cfg(any(r2r__ros__distro__jazzy, r2r__ros__distro__rolling))]
+    fn count_clients(&self, service_name: &str) -> Result<usize> {
+        count_endpoints(self.node_handle.as_ref(), service_name, rcl_count_clients)
+    }
+
+    #[cfg(not(any(r2r__ros__distro__jazzy, r2r__ros__distro__rolling)))]
+    fn count_clients(&self, service_name: &str) -> Result<usize> {
+        let mut count = 0;
+        for (node_name, namespace) in self.get_node_names()? {
+            match self.get_client_names_and_types_by_node(&node_name, &namespace) {
+                Ok(clients) => count += usize::from(clients.contains_key(service_name)),
+                // the node may have left the graph since listing the nodes.
+                Err(Error::RCL_RET_NODE_NAME_NON_EXISTENT) => continue,
+                Err(e) => return Err(e),
             }
         }
+        Ok(count)
+    }
+
+    /// Get all action servers in the ROS graph along with their types.
+    ///
+    /// Actions are found by the topics of the action naming convention, as
+    /// an action server `/name` publishes on `/name/_action/feedback`.
+    pub fn get_action_server_names_and_types(&self) -> Result<Vec<(String, Vec<String>)>> {
+        let topics = self.get_topic_names_and_types()?;
+        let node = self.node_handle.as_ref();
+        let mut actions = action_names_and_types(&topics);
+        // clients only subscribe to the feedback topic.
+        actions.retain(|(name, _)| {
+            count_endpoints(node, &format!("{name}/_action/feedback"), rcl_count_publishers)
+                .is_ok_and(|count| count > 0)
+        });
+        Ok(actions)
+    }
+
+    /// Get the action clients of a node along with their types.
+    ///
+    /// Actions are found by the topics of the action naming convention, as
+    /// an action client of `/name` subscribes to `/name/_action/feedback`.
+    pub fn get_action_client_names_and_types_by_node(
+        &self, node_name: &str, namespace: &str,
+    ) -> Result<Vec<(String, Vec<String>)>> {
+        let c_node_name = CString::new(node_name).map_err(|_| Error::RCL_RET_INVALID_ARGUMENT)?;
+        let c_namespace = CString::new(namespace).map_err(|_| Error::RCL_RET_INVALID_ARGUMENT)?;
+        let mut nat = unsafe { rmw_get_zero_initialized_names_and_types() };
+        let ret = unsafe {
+            rcl_get_subscriber_names_and_types_by_node(
+                self.node_handle.as_ref(),
+                &mut rcutils_get_default_allocator(),
+                false,
+                c_node_name.as_ptr(),
+                c_namespace.as_ptr(),
+                &mut nat,
+            )
+        };
+        if ret != RCL_RET_OK as i32 {
+            log::error!("could not get subscriber names and types of {} {}", node_name, ret);
+            return Err(Error::from_rcl_error(ret));
+        }
+
+        let res = action_names_and_types(&names_and_types_to_map(&nat));
         unsafe {
-            rmw_names_and_types_fini(&mut tnat);
+            rmw_names_and_types_fini(&mut nat);
         } // TODO: check return value
         Ok(res)
     }
 
+    /// Get information about all publishers on a topic.
+    ///
+    /// If `no_mangle` is true, `topic_name` is passed to the middleware as is.
     pub fn get_publishers_info_by_topic(
         &self, topic_name: &str, no_mangle: bool,
+    ) -> Result<Vec<TopicEndpointInfo>> {
+        publishers_info_by_topic(self.node_handle.as_ref(), topic_name, no_mangle)
+    }
+
+    /// Get information about all subscriptions on a topic.
+    ///
+    /// If `no_mangle` is true, `topic_name` is passed to the middleware as is.
+    pub fn get_subscriptions_info_by_topic(
+        &self, topic_name: &str, no_mangle: bool,
     ) -> Result<Vec<TopicEndpointInfo>> {
         let node = self.node_handle.as_ref();
 
@@ -1344,7 +3726,7 @@ impl Node {
             unsafe { rmw_get_zero_initialized_topic_endpoint_info_array() };
 
         let result = unsafe {
-            rcl_get_publishers_info_by_topic(
+            rcl_get_subscriptions_info_by_topic(
                 node,
                 &mut allocator,
                 topic_c_string.as_ptr(),
@@ -1370,29 +3752,199 @@ impl Node {
         Ok(topic_info_list)
     }
 
+    // with `check_type_on_connect`, refuse to add an endpoint of another
+    // type than the existing publishers and subscriptions of the topic.
+    fn check_topic_type(
+        &self, topic: &str, ts: *const rosidl_message_type_support_t,
+    ) -> Result<()> {
+        if !self.check_type_on_connect {
+            return Ok(());
+        }
+        let Some(requested) = (unsafe { ts.as_ref() }).and_then(message_type_name) else {
+            return Ok(());
+        };
+        let mut endpoints = self.get_publishers_info_by_topic(topic, false)?;
+        endpoints.extend(self.get_subscriptions_info_by_topic(topic, false)?);
+        match endpoints.into_iter().find(|e| e.topic_type != requested) {
+            Some(existing) => Err(Error::TypeMismatch {
+                topic: topic.to_owned(),
+                existing: existing.topic_type,
+                requested,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Create a callback group, see [`CallbackGroup`].
+    pub fn create_callback_group(&mut self, kind: CallbackGroupKind) -> CallbackGroup {
+        CallbackGroup::new(kind)
+    }
+
+    /// Subscribe to a ROS topic, calling `callback` with each message on the
+    /// threads of `group`.
+    ///
+    /// Like [`Node::subscribe`], the messages are taken by `spin_once`.
+    pub fn subscribe_in_group<T>(
+        &mut self, topic: &str, qos_profile: QosProfile, group: &CallbackGroup,
+        mut callback: impl FnMut(T) + Send + 'static,
+    ) -> Result<()>
+    where
+        T: WrappedTypesupport + Send + 'static,
+    {
+        let mut subscription = self.subscribe::<T>(topic, qos_profile)?;
+        group.spawn(async move {
+            while let Some(msg) = subscription.next().await {
+                callback(msg);
+            }
+        });
+        Ok(())
+    }
+
+    /// Create a ROS wall timer, calling `callback` with the time since the
+    /// last call on the threads of `group`. See [`Node::create_wall_timer`].
+    pub fn create_wall_timer_in_group(
+        &mut self, period: Duration, group: &CallbackGroup,
+        mut callback: impl FnMut(Duration) + Send + 'static,
+    ) -> Result<()> {
+        let mut timer = self.create_wall_timer(period)?;
+        group.spawn(async move {
+            while let Ok(elapsed) = timer.tick().await {
+                callback(elapsed);
+            }
+        });
+        Ok(())
+    }
+
+    /// Create a ROS service, answering each request with the response of
+    /// `handler` on the threads of `group`. See [`Node::create_service`].
+    pub fn create_service_in_group<T>(
+        &mut self, service_name: &str, qos_profile: QosProfile, group: &CallbackGroup,
+        mut handler: impl FnMut(&T::Request) -> T::Response + Send + 'static,
+    ) -> Result<()>
+    where
+        T: WrappedServiceTypeSupport + 'static,
+    {
+        let mut requests = self.create_service::<T>(service_name, qos_profile)?;
+        group.spawn(async move {
+            while let Some(request) = requests.next().await {
+                let response = handler(&request.message);
+                if let Err(e) = request.respond(response) {
+                    log::debug!("could not send service response ({}).", e);
+                }
+            }
+        });
+        Ok(())
+    }
+
     /// Create a ROS wall timer.
     ///
     /// Create a ROS timer that is woken up by spin every `period`.
     ///
     /// This timer uses [`ClockType::SteadyTime`] clock.
     pub fn create_wall_timer(&mut self, period: Duration) -> Result<Timer> {
-        let mut clock = Clock::create(ClockType::SteadyTime)?;
+        self.create_timer_with_owned_clock(period, ClockType::SteadyTime)
+    }
+
+    /// Create a ROS timer using a clock of type `clock_type`.
+    ///
+    /// With [`ClockType::RosTime`] this is the same as [`Node::create_timer`], so the
+    /// timer follows simulated time from `/clock` when it is enabled, and is held while
+    /// no `/clock` messages arrive (see [`TimeSource::set_clock_timeout`]).
+    /// [`ClockType::SteadyTime`] is the same as [`Node::create_wall_timer`].
+    pub fn create_timer_with_clock(
+        &mut self, period: Duration, clock_type: ClockType,
+    ) -> Result<Timer> {
+        match clock_type {
+            ClockType::RosTime => self.create_timer(period),
+            _ => self.create_timer_with_owned_clock(period, clock_type),
+        }
+    }
+
+    fn create_timer_with_owned_clock(
+        &mut self, period: Duration, clock_type: ClockType,
+    ) -> Result<Timer> {
+        let mut clock = Clock::create(clock_type)?;
         let timer_handle = self.create_timer_helper(&mut clock, period)?;
 
         let (tx, rx) = mpsc::channel::<Duration>(1);
 
+        let timer_handle = Arc::new(Mutex::new(timer_handle));
+        let out_timer = Timer {
+            handle: Arc::downgrade(&timer_handle),
+            receiver: rx,
+        };
+
         let timer = Timer_ {
             timer_handle,
             _clock: Some(clock), // The timer owns the clock.
             sender: tx,
+            one_shot: false,
         };
         self.timers.push(timer);
 
-        let out_timer = Timer { receiver: rx };
-
         Ok(out_timer)
     }
 
+    #[cfg(r2r__statistics_msgs__msg__MetricsMessage)]
+    fn publish_topic_statistics(&mut self) {
+        let mut i = 0;
+        while i < self.topic_statistics.len() {
+            if Arc::strong_count(&self.topic_statistics[i]) == 1 {
+                // the subscription is gone, remove its statistics publisher.
+                let statistics = self.topic_statistics.swap_remove(i);
+                if let Ok(statistics) = Arc::try_unwrap(statistics) {
+                    let publisher = statistics.into_inner().unwrap().into_publisher();
+                    self.destroy_publisher(publisher);
+                }
+            } else {
+                self.topic_statistics[i].lock().unwrap().publish_if_due();
+                i += 1;
+            }
+        }
+    }
+
+    /// Create a guard condition.
+    ///
+    /// The guard condition is added to the wait set of this node, so that
+    /// triggering it from any thread wakes up `spin_once`. It is valid for as
+    /// long as the node is alive.
+    pub fn create_guard_condition(&mut self) -> Result<GuardCondition> {
+        let guard_condition = {
+            let mut ctx = self.context.context_handle.lock().unwrap();
+            GuardCondition_::new(ctx.as_mut())?
+        };
+        let arc = Arc::new(guard_condition);
+        let g = GuardCondition {
+            handle: Arc::downgrade(&arc),
+        };
+        self.guard_conditions.push(arc);
+        Ok(g)
+    }
+
+    /// Create a one-shot wall timer.
+    ///
+    /// The returned future completes once, on the first spin after `duration` has
+    /// passed (on the next spin if `duration` is zero), with the time elapsed since
+    /// the timer was created. The timer is cancelled and removed after firing.
+    pub fn create_one_shot_timer(
+        &mut self, duration: Duration,
+    ) -> Result<impl Future<Output = Result<Duration>>> {
+        let mut clock = Clock::create(ClockType::SteadyTime)?;
+        let timer_handle = self.create_timer_helper(&mut clock, duration)?;
+
+        let (tx, mut rx) = mpsc::channel::<Duration>(1);
+
+        let timer = Timer_ {
+            timer_handle: Arc::new(Mutex::new(timer_handle)),
+            _clock: Some(clock), // The timer owns the clock.
+            sender: tx,
+            one_shot: true,
+        };
+        self.timers.push(timer);
+
+        Ok(async move { rx.next().await.ok_or(Error::RCL_RET_TIMER_INVALID) })
+    }
+
     /// Create a ROS timer
     ///
     /// Create a ROS timer that is woken up by spin every `period`.
@@ -1404,15 +3956,20 @@ impl Node {
 
         let (tx, rx) = mpsc::channel::<Duration>(1);
 
+        let timer_handle = Arc::new(Mutex::new(timer_handle));
+        let out_timer = Timer {
+            handle: Arc::downgrade(&timer_handle),
+            receiver: rx,
+        };
+
         let timer = Timer_ {
             timer_handle,
             _clock: None, // The timer does not own the clock (the node owns it).
             sender: tx,
+            one_shot: false,
         };
         self.timers.push(timer);
 
-        let out_timer = Timer { receiver: rx };
-
         Ok(out_timer)
     }
 
@@ -1452,6 +4009,13 @@ impl Node {
         s.to_str().unwrap_or("")
     }
 
+    /// Set the log level of the node's logger.
+    ///
+    /// Messages below `severity` are neither printed nor published on `/rosout`.
+    pub fn set_log_level(&self, severity: crate::LogSeverity) -> Result<()> {
+        crate::utils::set_log_level(self.logger(), severity)
+    }
+
     /// Get TimeSource of the node
     ///
     /// See: [`TimeSource`]
@@ -1460,6 +4024,14 @@ impl Node {
         self.time_source.clone()
     }
 
+    /// Get the current time of the node's ROS clock.
+    ///
+    /// When simulated time is enabled this is the latest time received on `/clock`.
+    pub fn now(&self) -> Result<builtin_interfaces::msg::Time> {
+        let now = self.ros_clock.lock().unwrap().get_now()?;
+        Ok(Clock::to_builtin_time(&now))
+    }
+
     /// Get ROS clock of the node
     ///
     /// This is the same clock that is used by ROS timers created in [`Node::create_timer`].
@@ -1474,6 +4046,13 @@ struct RclTimer {
     _pin: PhantomPinned, // To prevent Unpin implementation
 }
 
+// The rcl timer functions used from the user's `Timer` (reset, cancel and
+// the time queries) are thread safe. Finalizing the timer is guarded by
+// the mutex around it, see `Timer_::drop`.
+unsafe impl Send for RclTimer {}
+
+type SharedRclTimer = Arc<Mutex<Pin<Box<RclTimer>>>>;
+
 impl RclTimer {
     unsafe fn new() -> Self {
         Self {
@@ -1484,14 +4063,16 @@ impl RclTimer {
 }
 
 struct Timer_ {
-    timer_handle: Pin<Box<RclTimer>>,
+    timer_handle: SharedRclTimer,
     _clock: Option<Clock>, // Some(clock) if the timer owns the clock, just here to be dropped properly later.
     sender: mpsc::Sender<Duration>,
+    one_shot: bool,
 }
 
 impl Timer_ {
     fn get_handle(&self) -> *const rcl_timer_t {
-        &self.timer_handle.handle
+        // the timer is pinned, so the pointer stays valid after unlocking.
+        &self.timer_handle.lock().unwrap().handle
     }
 
     /// Get mutable pointer to handle
@@ -1501,6 +4082,8 @@ impl Timer_ {
     ///     Must not invalidate or replace the timer unless in Drop.
     unsafe fn get_handle_mut(&mut self) -> *mut rcl_timer_t {
         self.timer_handle
+            .lock()
+            .unwrap()
             .as_mut()
             .map_unchecked_mut(|s| &mut s.handle)
             .get_unchecked_mut()
@@ -1527,6 +4110,11 @@ impl Timer_ {
                             );
                         }
                     }
+                    if self.one_shot {
+                        // fired once, cancel and drop our timer. the tick stays in the channel.
+                        unsafe { rcl_timer_cancel(self.get_handle_mut()) };
+                        return true;
+                    }
                 }
             }
         }
@@ -1536,16 +4124,79 @@ impl Timer_ {
 
 impl Drop for Timer_ {
     fn drop(&mut self) {
-        let _ret = unsafe { rcl_timer_fini(self.get_handle_mut()) };
+        // hold the lock, so that the user's `Timer` can't use the handle meanwhile.
+        let mut timer = self.timer_handle.lock().unwrap();
+        unsafe {
+            let handle = &mut timer.as_mut().get_unchecked_mut().handle;
+            let _ret = rcl_timer_fini(handle);
+            // makes later calls from `Timer` return an error.
+            *handle = rcl_get_zero_initialized_timer();
+        }
     }
 }
 
 /// A ROS timer.
 pub struct Timer {
+    handle: Weak<Mutex<Pin<Box<RclTimer>>>>,
     receiver: mpsc::Receiver<Duration>,
 }
 
 impl Timer {
+    fn with_handle<F>(&self, f: F) -> Result<()>
+    where
+        F: FnOnce(*mut rcl_timer_t) -> rcl_ret_t,
+    {
+        let timer = self.handle.upgrade().ok_or(Error::RCL_RET_TIMER_INVALID)?;
+        let mut timer = timer.lock().unwrap();
+        let handle = unsafe { &mut timer.as_mut().get_unchecked_mut().handle };
+        let ret = f(handle);
+        if ret == RCL_RET_OK as i32 {
+            Ok(())
+        } else {
+            Err(Error::from_rcl_error(ret))
+        }
+    }
+
+    /// Restart the countdown of the timer, e.g. to debounce events.
+    ///
+    /// This also undoes [`Timer::cancel`].
+    pub fn reset(&self) -> Result<()> {
+        self.with_handle(|handle| unsafe { rcl_timer_reset(handle) })
+    }
+
+    /// Stop the timer from ticking until it is [`reset`](Timer::reset).
+    pub fn cancel(&self) -> Result<()> {
+        self.with_handle(|handle| unsafe { rcl_timer_cancel(handle) })
+    }
+
+    /// Returns true if the timer is canceled, or no longer valid.
+    pub fn is_canceled(&self) -> bool {
+        let mut canceled = true;
+        let result =
+            self.with_handle(|handle| unsafe { rcl_timer_is_canceled(handle, &mut canceled) });
+        result.is_err() || canceled
+    }
+
+    /// The time left until the timer ticks next.
+    ///
+    /// Zero if the tick is overdue.
+    pub fn time_until_next_call(&self) -> Result<Duration> {
+        let mut nanos = 0i64;
+        self.with_handle(|handle| unsafe {
+            rcl_timer_get_time_until_next_call(handle, &mut nanos)
+        })?;
+        Ok(Duration::from_nanos(nanos.max(0) as u64))
+    }
+
+    /// The time since the timer last ticked, or since it was created.
+    pub fn time_since_last_call(&self) -> Result<Duration> {
+        let mut nanos = 0i64;
+        self.with_handle(|handle| unsafe {
+            rcl_timer_get_time_since_last_call(handle, &mut nanos)
+        })?;
+        Ok(Duration::from_nanos(nanos.max(0) as u64))
+    }
+
     /// Completes when the next instant in the interval has been reached.
     ///
     /// Returns the time passed since the timer was last woken up.
@@ -1559,6 +4210,13 @@ impl Timer {
     }
 }
 
+fn latched_qos(depth: usize) -> QosProfile {
+    QosProfile::default()
+        .reliable()
+        .transient_local()
+        .keep_last(depth)
+}
+
 // Since publishers are temporarily upgraded to owners during the
 // actual publish but are not the ones that handle cleanup, we simply
 // wait until there are no other owners in the cleanup procedure. The
@@ -1575,32 +4233,32 @@ fn wait_until_unwrapped<T>(mut a: Arc<T>) -> T {
 
 impl Drop for Node {
     fn drop(&mut self) {
-        // fini functions are not thread safe so lock the context.
-        let _ctx_handle = self.context.context_handle.lock().unwrap();
-
-        for s in &mut self.subscribers {
-            s.destroy(&mut self.node_handle);
-        }
-        for c in &mut self.clients {
-            c.lock().unwrap().destroy(&mut self.node_handle);
-        }
-        for s in &mut self.services {
-            s.lock().unwrap().destroy(&mut self.node_handle);
-        }
-        for c in &mut self.action_clients {
-            c.lock().unwrap().destroy(&mut self.node_handle);
+        if let Err(e) = self.destroy_entities() {
+            log::error!("could not finalize node: {}", e);
         }
-        for s in &mut self.action_servers {
-            s.lock().unwrap().destroy(&mut self.node_handle);
-        }
-        while let Some(p) = self.pubs.pop() {
-            let p = wait_until_unwrapped(p);
+    }
+}
 
-            p.destroy(self.node_handle.as_mut());
-        }
-        unsafe {
-            rcl_node_fini(self.node_handle.as_mut());
+// lets a subscription query the graph through its node, while the node is
+// alive. the flag is cleared under the lock before the node is finalized.
+#[derive(Clone)]
+pub(crate) struct NodeGraphHandle {
+    node: *const rcl_node_t,
+    alive: Weak<Mutex<bool>>,
+}
+
+// the node is only used while the flag is locked and set.
+unsafe impl Send for NodeGraphHandle {}
+unsafe impl Sync for NodeGraphHandle {}
+
+impl NodeGraphHandle {
+    pub(crate) fn publishers_info_by_topic(&self, topic: &str) -> Result<Vec<TopicEndpointInfo>> {
+        let alive = self.alive.upgrade().ok_or(Error::RCL_RET_NODE_INVALID)?;
+        let alive = alive.lock().unwrap();
+        if !*alive {
+            return Err(Error::RCL_RET_NODE_INVALID);
         }
+        publishers_info_by_topic(unsafe { &*self.node }, topic, false)
     }
 }
 
@@ -1608,11 +4266,32 @@ pub trait IsAvailablePollable {
     fn register_poll_available(&self, sender: oneshot::Sender<()>) -> Result<()>;
 }
 
+/// Whether a [`TopicEndpointInfo`] is about a publisher or a subscription.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndpointType {
+    Invalid,
+    Publisher,
+    Subscription,
+}
+
+impl From<rmw_endpoint_type_t> for EndpointType {
+    fn from(endpoint_type: rmw_endpoint_type_t) -> Self {
+        match endpoint_type {
+            rmw_endpoint_type_t::RMW_ENDPOINT_PUBLISHER => EndpointType::Publisher,
+            rmw_endpoint_type_t::RMW_ENDPOINT_SUBSCRIPTION => EndpointType::Subscription,
+            rmw_endpoint_type_t::RMW_ENDPOINT_INVALID => EndpointType::Invalid,
+        }
+    }
+}
+
+/// Information about a publisher or subscription on a topic.
+#[derive(Debug, Clone)]
 pub struct TopicEndpointInfo {
     pub node_name: String,
     pub node_namespace: String,
     pub topic_type: String,
-    pub endpoint_gid: [u8; RMW_GID_STORAGE_SIZE as usize],
+    pub endpoint_type: EndpointType,
+    pub endpoint_gid: Gid,
     pub qos_profile: QosProfile,
 }
 
@@ -1630,7 +4309,7 @@ impl From<rmw_topic_endpoint_info_t> for TopicEndpointInfo {
             .into_owned();
 
         // Copy the endpoint_gid array
-        let endpoint_gid: [u8; RMW_GID_STORAGE_SIZE as usize] = info.endpoint_gid;
+        let endpoint_gid: Gid = info.endpoint_gid;
 
         // Convert qos_profile
         let qos_profile = QosProfile::from(info.qos_profile); // Adjust this line based on how QosProfile is defined
@@ -1639,12 +4318,80 @@ impl From<rmw_topic_endpoint_info_t> for TopicEndpointInfo {
             node_name,
             node_namespace,
             topic_type,
+            endpoint_type: info.endpoint_type.into(),
             endpoint_gid,
             qos_profile,
         }
     }
 }
 
+fn string_array_to_vec(array: &rcutils_string_array_t) -> Vec<String> {
+    if array.data.is_null() {
+        return Vec::new();
+    }
+    let strings = unsafe { std::slice::from_raw_parts(array.data, array.size) };
+    strings
+        .iter()
+        .map(|s| {
+            if s.is_null() {
+                String::new()
+            } else {
+                unsafe { CStr::from_ptr(*s) }.to_string_lossy().into_owned()
+            }
+        })
+        .collect()
+}
+
+fn names_and_types_to_map(nat: &rmw_names_and_types_t) -> HashMap<String, Vec<String>> {
+    let mut res = HashMap::new();
+    if nat.names.data.is_null() || nat.types.is_null() {
+        return res;
+    }
+    let names = string_array_to_vec(&nat.names);
+    let types = unsafe { std::slice::from_raw_parts(nat.types, nat.names.size) };
+    for (name, types) in names.into_iter().zip(types) {
+        res.insert(name, string_array_to_vec(types));
+    }
+    res
+}
+
+pub(crate) fn publishers_info_by_topic(
+    node: &rcl_node_t, topic_name: &str, no_mangle: bool,
+) -> Result<Vec<TopicEndpointInfo>> {
+    let topic_c_string = CString::new(topic_name).map_err(|_| Error::RCL_RET_INVALID_ARGUMENT)?;
+
+    let mut allocator = unsafe { rcutils_get_default_allocator() };
+
+    let mut info_array: rcl_topic_endpoint_info_array_t =
+        unsafe { rmw_get_zero_initialized_topic_endpoint_info_array() };
+
+    let result = unsafe {
+        rcl_get_publishers_info_by_topic(
+            node,
+            &mut allocator,
+            topic_c_string.as_ptr(),
+            no_mangle,
+            &mut info_array,
+        )
+    };
+
+    if result != RCL_RET_OK as i32 {
+        unsafe { rmw_topic_endpoint_info_array_fini(&mut info_array, &mut allocator) };
+        return Err(Error::from_rcl_error(result));
+    }
+
+    // Convert info_array to Vec<TopicEndpointInfo>
+    let topic_info_list = convert_info_array_to_vec(&info_array);
+
+    let result = unsafe { rmw_topic_endpoint_info_array_fini(&mut info_array, &mut allocator) };
+
+    if result != RCL_RET_OK as i32 {
+        return Err(Error::from_rcl_error(result));
+    }
+
+    Ok(topic_info_list)
+}
+
 fn convert_info_array_to_vec(
     info_array: &rcl_topic_endpoint_info_array_t,
 ) -> Vec<TopicEndpointInfo> {
@@ -1662,3 +4409,72 @@ fn convert_info_array_to_vec(
 
     topic_info_list
 }
+
+/// Actions and their types from the feedback topics among `topics`, sorted by name.
+fn action_names_and_types(topics: &HashMap<String, Vec<String>>) -> Vec<(String, Vec<String>)> {
+    let mut actions = topics
+        .iter()
+        .filter_map(|(topic, types)| {
+            let name = topic.strip_suffix("/_action/feedback")?;
+            let types = types
+                .iter()
+                .filter_map(|t| t.strip_suffix("_FeedbackMessage"))
+                .map(|t| t.to_owned())
+                .collect::<Vec<_>>();
+            (!types.is_empty()).then(|| (name.to_owned(), types))
+        })
+        .collect::<Vec<_>>();
+    actions.sort();
+    actions
+}
+
+fn names_and_types_by_node(
+    node: &rcl_node_t, node_name: &str, namespace: &str,
+    get_fn: unsafe extern "C" fn(
+        *const rcl_node_t,
+        *mut rcl_allocator_t,
+        *const std::os::raw::c_char,
+        *const std::os::raw::c_char,
+        *mut rcl_names_and_types_t,
+    ) -> rcl_ret_t,
+) -> Result<HashMap<String, Vec<String>>> {
+    let c_node_name = CString::new(node_name).map_err(|_| Error::RCL_RET_INVALID_ARGUMENT)?;
+    let c_namespace = CString::new(namespace).map_err(|_| Error::RCL_RET_INVALID_ARGUMENT)?;
+    let mut nat = unsafe { rmw_get_zero_initialized_names_and_types() };
+    let ret = unsafe {
+        get_fn(
+            node,
+            &mut rcutils_get_default_allocator(),
+            c_node_name.as_ptr(),
+            c_namespace.as_ptr(),
+            &mut nat,
+        )
+    };
+    if ret != RCL_RET_OK as i32 {
+        return Err(Error::from_rcl_error(ret));
+    }
+
+    let res = names_and_types_to_map(&nat);
+    unsafe {
+        rmw_names_and_types_fini(&mut nat);
+    } // TODO: check return value
+    Ok(res)
+}
+
+fn count_endpoints(
+    node: &rcl_node_t, topic: &str,
+    count_fn: unsafe extern "C" fn(
+        *const rcl_node_t,
+        *const std::os::raw::c_char,
+        *mut usize,
+    ) -> rcl_ret_t,
+) -> Result<usize> {
+    let c_topic = CString::new(topic).map_err(|_| Error::RCL_RET_INVALID_ARGUMENT)?;
+    let mut count = 0;
+    let ret = unsafe { count_fn(node, c_topic.as_ptr(), &mut count) };
+    if ret == RCL_RET_OK as i32 {
+        Ok(count)
+    } else {
+        Err(Error::from_rcl_error(ret))
+    }
+}
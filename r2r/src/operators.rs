@@ -0,0 +1,270 @@
+//! Operators for processing the messages of a subscription, see [`Subscription`].
+
+use futures::{
+    channel::oneshot,
+    future,
+    stream::{BoxStream, Stream, StreamExt},
+};
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    rate::sleep_until,
+    subscribers::{BoundedSubscription, Subscription},
+};
+
+/// Messages for which the predicate returned true, see `filter_msgs`.
+pub struct FilteredStream<T> {
+    inner: BoxStream<'static, T>,
+}
+
+/// Messages transformed by a function, see `map_msgs`.
+pub struct MappedStream<U> {
+    inner: BoxStream<'static, U>,
+}
+
+/// At most one message per interval, see `throttle`.
+pub struct ThrottledStream<T> {
+    inner: BoxStream<'static, T>,
+    min_interval: Duration,
+    last: Option<Instant>,
+}
+
+/// Messages that were not followed by another one within a delay, see `debounce`.
+pub struct DebouncedStream<T> {
+    inner: Option<BoxStream<'static, T>>,
+    delay: Duration,
+    pending: Option<T>,
+    timer: Option<oneshot::Receiver<()>>,
+}
+
+macro_rules! impl_operators {
+    ($stream:ident) => {
+        impl<T: Send + 'static> $stream<T> {
+            /// Only pass on the messages for which `pred` returns true.
+            ///
+            /// Named apart from `StreamExt::filter`, which takes a closure
+            /// returning a future.
+            pub fn filter_msgs(
+                self, mut pred: impl FnMut(&T) -> bool + Send + 'static,
+            ) -> FilteredStream<T> {
+                FilteredStream {
+                    inner: StreamExt::filter(self, move |msg| future::ready(pred(msg))).boxed(),
+                }
+            }
+
+            /// Transform each message with `f`, like `StreamExt::map`.
+            pub fn map_msgs<U>(self, f: impl FnMut(T) -> U + Send + 'static) -> MappedStream<U> {
+                MappedStream {
+                    inner: StreamExt::map(self, f).boxed(),
+                }
+            }
+
+            /// Pass on at most one message per `min_interval`, dropping the others.
+            pub fn throttle(self, min_interval: Duration) -> ThrottledStream<T> {
+                ThrottledStream {
+                    inner: self.boxed(),
+                    min_interval,
+                    last: None,
+                }
+            }
+
+            /// Only pass on a message if no newer message arrives within `delay`.
+            ///
+            /// The message is delayed by `delay`. When the input ends, the
+            /// last message is passed on immediately.
+            pub fn debounce(self, delay: Duration) -> DebouncedStream<T> {
+                DebouncedStream {
+                    inner: Some(self.boxed()),
+                    delay,
+                    pending: None,
+                    timer: None,
+                }
+            }
+        }
+    };
+}
+
+impl_operators!(Subscription);
+impl_operators!(BoundedSubscription);
+impl_operators!(FilteredStream);
+impl_operators!(MappedStream);
+impl_operators!(ThrottledStream);
+impl_operators!(DebouncedStream);
+
+// the pending message is never pinned.
+impl<T> Unpin for DebouncedStream<T> {}
+
+impl<T> Stream for FilteredStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.inner.poll_next_unpin(cx)
+    }
+}
+
+impl<U> Stream for MappedStream<U> {
+    type Item = U;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<U>> {
+        self.inner.poll_next_unpin(cx)
+    }
+}
+
+impl<T> Stream for ThrottledStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        loop {
+            match self.inner.poll_next_unpin(cx) {
+                Poll::Ready(Some(msg)) => {
+                    let now = Instant::now();
+                    if self
+                        .last
+                        .is_some_and(|last| now.duration_since(last) < self.min_interval)
+                    {
+                        continue;
+                    }
+                    self.last = Some(now);
+                    return Poll::Ready(Some(msg));
+                }
+                other => return other,
+            }
+        }
+    }
+}
+
+impl<T> Stream for DebouncedStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let this = &mut *self;
+        // keep only the newest message, restarting the delay for each one.
+        while let Some(inner) = &mut this.inner {
+            match inner.poll_next_unpin(cx) {
+                Poll::Ready(Some(msg)) => {
+                    this.pending = Some(msg);
+                    this.timer = Some(sleep_until(Instant::now() + this.delay));
+                }
+                Poll::Ready(None) => {
+                    this.inner = None;
+                    this.timer = None;
+                    return Poll::Ready(this.pending.take());
+                }
+                Poll::Pending => break,
+            }
+        }
+
+        let Some(timer) = &mut this.timer else {
+            return if this.inner.is_some() {
+                Poll::Pending
+            } else {
+                Poll::Ready(None)
+            };
+        };
+        match Pin::new(timer).poll(cx) {
+            Poll::Ready(_) => {
+                this.timer = None;
+                Poll::Ready(this.pending.take())
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{channel::mpsc, executor::block_on, SinkExt};
+
+    fn subscription<T>() -> (mpsc::Sender<T>, Subscription<T>) {
+        let (sender, receiver) = mpsc::channel(10);
+        (sender, Subscription::new(receiver, Default::default()))
+    }
+
+    #[test]
+    fn test_filter_map() {
+        let (mut sender, sub) = subscription::<i32>();
+        block_on(async {
+            for i in -2..=2 {
+                sender.send(i).await.unwrap();
+            }
+        });
+        drop(sender);
+        let filtered = block_on(sub.filter_msgs(|m| *m > 0).collect::<Vec<_>>());
+        assert_eq!(filtered, vec![1, 2]);
+
+        let (mut sender, sub) = subscription::<i32>();
+        block_on(sender.send(3)).unwrap();
+        drop(sender);
+        let mapped = block_on(sub.map_msgs(|m| m.to_string()).collect::<Vec<_>>());
+        assert_eq!(mapped, vec!["3".to_string()]);
+
+        // the combinators of StreamExt are not shadowed.
+        let (mut sender, sub) = subscription::<i32>();
+        block_on(sender.send(4)).unwrap();
+        drop(sender);
+        let filtered = block_on(sub.filter(|m| future::ready(*m > 0)).collect::<Vec<_>>());
+        assert_eq!(filtered, vec![4]);
+    }
+
+    #[test]
+    fn test_throttle() {
+        let (mut sender, sub) = subscription::<i32>();
+        block_on(async {
+            for i in 0..5 {
+                sender.send(i).await.unwrap();
+            }
+        });
+        drop(sender);
+        // all messages are already queued, so only the first one passes.
+        let throttled = block_on(sub.throttle(Duration::from_secs(10)).collect::<Vec<_>>());
+        assert_eq!(throttled, vec![0]);
+    }
+
+    #[test]
+    fn test_debounce() {
+        let (mut sender, sub) = subscription::<i32>();
+        let mut debounced = sub.debounce(Duration::from_millis(50));
+        block_on(async {
+            // a burst of messages only passes on the last one.
+            for i in 0..3 {
+                sender.send(i).await.unwrap();
+            }
+            assert_eq!(debounced.next().await, Some(2));
+
+            sender.send(3).await.unwrap();
+            let start = Instant::now();
+            assert_eq!(debounced.next().await, Some(3));
+            assert!(start.elapsed() >= Duration::from_millis(50));
+
+            // the last message is flushed when the input ends.
+            sender.send(4).await.unwrap();
+            drop(sender);
+            assert_eq!(debounced.next().await, Some(4));
+            assert_eq!(debounced.next().await, None);
+        });
+    }
+
+    #[test]
+    fn test_composition() {
+        let (mut sender, sub) = subscription::<f64>();
+        block_on(async {
+            for v in [-1.0, 2.0, 3.0, -4.0, 5.0] {
+                sender.send(v).await.unwrap();
+            }
+        });
+        drop(sender);
+        let values = sub
+            .filter_msgs(|m| *m > 0.0)
+            .map_msgs(|m| m * 2.0)
+            .throttle(Duration::ZERO)
+            .debounce(Duration::from_millis(10));
+        // the input ends before the delay, so the last value is flushed.
+        assert_eq!(block_on(values.collect::<Vec<_>>()), vec![10.0]);
+    }
+}
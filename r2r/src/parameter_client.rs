@@ -0,0 +1,190 @@
+use futures::future::{try_join_all, Future, TryFutureExt};
+
+use crate::{
+    clients::Client, error::*, msg_types::generated_msgs::rcl_interfaces, nodes::Node,
+    parameters::ParameterValue, qos::QosProfile,
+};
+
+use rcl_interfaces::{
+    msg::{ParameterDescriptor, SetParametersResult},
+    srv::{
+        DescribeParameters, GetParameters, ListParameters, SetParameters, SetParametersAtomically,
+    },
+};
+
+/// A client for the parameter services of another node.
+///
+/// The requests are only handled while the node used to create
+/// the client is spinning.
+pub struct ParameterClient {
+    get_parameters: Client<GetParameters::Service>,
+    set_parameters: Client<SetParameters::Service>,
+    set_parameters_atomically: Client<SetParametersAtomically::Service>,
+    list_parameters: Client<ListParameters::Service>,
+    describe_parameters: Client<DescribeParameters::Service>,
+}
+
+impl ParameterClient {
+    /// Create clients for the parameter services of `remote_node_name`.
+    ///
+    /// The name is resolved like any other service name, so use the fully
+    /// qualified name (e.g. `"/ns/node"`) for nodes in other namespaces.
+    pub fn new(node: &mut Node, remote_node_name: &str) -> Result<Self> {
+        let remote_node_name = remote_node_name.trim_end_matches('/');
+        let service = |name: &str| format!("{remote_node_name}/{name}");
+        Ok(ParameterClient {
+            get_parameters: node
+                .create_client(&service("get_parameters"), QosProfile::default())?,
+            set_parameters: node
+                .create_client(&service("set_parameters"), QosProfile::default())?,
+            set_parameters_atomically: node
+                .create_client(&service("set_parameters_atomically"), QosProfile::default())?,
+            list_parameters: node
+                .create_client(&service("list_parameters"), QosProfile::default())?,
+            describe_parameters: node
+                .create_client(&service("describe_parameters"), QosProfile::default())?,
+        })
+    }
+
+    /// Wait until all parameter services of the remote node are available.
+    pub fn wait_for_services(&self) -> Result<impl Future<Output = Result<()>>> {
+        let available = vec![
+            Node::is_available(&self.get_parameters)?,
+            Node::is_available(&self.set_parameters)?,
+            Node::is_available(&self.set_parameters_atomically)?,
+            Node::is_available(&self.list_parameters)?,
+            Node::is_available(&self.describe_parameters)?,
+        ];
+        Ok(try_join_all(available).map_ok(|_| ()))
+    }
+
+    /// Get the value of a parameter.
+    ///
+    /// Returns [`ParameterValue::NotSet`] if the parameter does not exist.
+    pub async fn get(&self, name: &str) -> Result<ParameterValue> {
+        let req = GetParameters::Request {
+            names: vec![name.to_owned()],
+        };
+        let mut response = self.get_parameters.request(&req)?.await?;
+        let value = response
+            .values
+            .pop()
+            .ok_or_else(|| Error::InvalidParameterName {
+                name: name.to_owned(),
+            })?;
+        Ok(ParameterValue::from_parameter_value_msg(value))
+    }
+
+    /// Get the values of several parameters in one request.
+    ///
+    /// The values are in the same order as `names`.
+    pub async fn get_many(&self, names: &[&str]) -> Result<Vec<ParameterValue>> {
+        let req = GetParameters::Request {
+            names: names.iter().map(|n| n.to_string()).collect(),
+        };
+        let response = self.get_parameters.request(&req)?.await?;
+        Ok(response
+            .values
+            .into_iter()
+            .map(ParameterValue::from_parameter_value_msg)
+            .collect())
+    }
+
+    /// Set the value of a parameter.
+    ///
+    /// The result tells if the remote node accepted the new value,
+    /// and if not, the reason why.
+    pub async fn set(&self, name: &str, value: ParameterValue) -> Result<SetParametersResult> {
+        let req = SetParameters::Request {
+            parameters: vec![rcl_interfaces::msg::Parameter {
+                name: name.to_owned(),
+                value: value.into_parameter_value_msg(),
+            }],
+        };
+        let mut response = self.set_parameters.request(&req)?.await?;
+        response
+            .results
+            .pop()
+            .ok_or_else(|| Error::InvalidParameterName {
+                name: name.to_owned(),
+            })
+    }
+
+    /// Set several parameters in one request.
+    ///
+    /// Each parameter is accepted or rejected on its own, the results
+    /// are in the same order as `params`.
+    pub async fn set_many(
+        &self, params: &[(&str, ParameterValue)],
+    ) -> Result<Vec<SetParametersResult>> {
+        let req = SetParameters::Request {
+            parameters: parameter_msgs(params),
+        };
+        let response = self.set_parameters.request(&req)?.await?;
+        Ok(response.results)
+    }
+
+    /// Set several parameters at once, all of them or none.
+    ///
+    /// If one of the parameters is rejected, none of them are changed.
+    pub async fn set_atomically(
+        &self, params: &[(&str, ParameterValue)],
+    ) -> Result<SetParametersResult> {
+        let req = SetParametersAtomically::Request {
+            parameters: parameter_msgs(params),
+        };
+        let response = self.set_parameters_atomically.request(&req)?.await?;
+        Ok(response.result)
+    }
+
+    /// List the names of all parameters under `prefix`.
+    ///
+    /// E.g. `"limits"` lists `limits.max` and `limits.min`. An empty
+    /// prefix lists all parameters.
+    pub async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let prefixes = if prefix.is_empty() {
+            vec![]
+        } else {
+            vec![prefix.to_owned()]
+        };
+        let req = ListParameters::Request {
+            prefixes,
+            depth: ListParameters::Request::DEPTH_RECURSIVE as u64,
+        };
+        let response = self.list_parameters.request(&req)?.await?;
+        Ok(response.result.names)
+    }
+
+    /// Get the descriptor of a parameter.
+    pub async fn describe(&self, name: &str) -> Result<ParameterDescriptor> {
+        let req = DescribeParameters::Request {
+            names: vec![name.to_owned()],
+        };
+        let mut response = self.describe_parameters.request(&req)?.await?;
+        response
+            .descriptors
+            .pop()
+            .ok_or_else(|| Error::InvalidParameterName {
+                name: name.to_owned(),
+            })
+    }
+
+    /// Get the descriptors of several parameters in one request.
+    pub async fn describe_many(&self, names: &[&str]) -> Result<Vec<ParameterDescriptor>> {
+        let req = DescribeParameters::Request {
+            names: names.iter().map(|n| n.to_string()).collect(),
+        };
+        let response = self.describe_parameters.request(&req)?.await?;
+        Ok(response.descriptors)
+    }
+}
+
+fn parameter_msgs(params: &[(&str, ParameterValue)]) -> Vec<rcl_interfaces::msg::Parameter> {
+    params
+        .iter()
+        .map(|(name, value)| rcl_interfaces::msg::Parameter {
+            name: name.to_string(),
+            value: value.clone().into_parameter_value_msg(),
+        })
+        .collect()
+}
@@ -1,5 +1,13 @@
-use crate::{Error, Result};
-use std::ffi::CStr;
+use crate::{
+    nodes::{Node, ParameterSetter},
+    Error, Result,
+};
+use futures::stream::{self, Stream, StreamExt};
+use std::{
+    ffi::CStr,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
 
 use crate::msg_types::generated_msgs::rcl_interfaces;
 use indexmap::IndexMap;
@@ -92,6 +100,26 @@ try_into_option_template!(Vec<i64>, "integer array", ParameterValue::IntegerArra
 try_into_option_template!(Vec<f64>, "double array", ParameterValue::DoubleArray(value) => value);
 try_into_option_template!(Vec<String>, "string array", ParameterValue::StringArray(value) => value);
 
+macro_rules! from_template {
+    ($ty:ty, $variant:ident) => {
+        impl From<$ty> for ParameterValue {
+            fn from(value: $ty) -> Self {
+                ParameterValue::$variant(value)
+            }
+        }
+    };
+}
+
+from_template!(bool, Bool);
+from_template!(i64, Integer);
+from_template!(f64, Double);
+from_template!(String, String);
+from_template!(Vec<bool>, BoolArray);
+from_template!(Vec<u8>, ByteArray);
+from_template!(Vec<i64>, IntegerArray);
+from_template!(Vec<f64>, DoubleArray);
+from_template!(Vec<String>, StringArray);
+
 impl ParameterValue {
     pub fn type_name(&self) -> &'static str {
         match self {
@@ -272,6 +300,202 @@ impl ParameterValue {
             ParameterValue::StringArray(_) => 9,  // int PARAMETER_STRING_ARRAY=9
         }
     }
+
+    /// Convert a value from JSON, e.g. from a web interface.
+    ///
+    /// `existing` is the current value of the parameter, if any. It is used
+    /// to tell integers from doubles and byte arrays from integer arrays,
+    /// which look the same in JSON.
+    pub(crate) fn from_json(
+        value: &serde_json::Value, existing: Option<&ParameterValue>,
+    ) -> Option<Self> {
+        use serde_json::Value;
+
+        let value = match value {
+            Value::Null => ParameterValue::NotSet,
+            Value::Bool(b) => ParameterValue::Bool(*b),
+            Value::Number(n) => match n.as_i64() {
+                Some(i) => ParameterValue::Integer(i),
+                None => ParameterValue::Double(n.as_f64()?),
+            },
+            Value::String(s) => ParameterValue::String(s.clone()),
+            Value::Array(seq) => {
+                if let Some(v) = seq.iter().map(|v| v.as_bool()).collect::<Option<Vec<_>>>() {
+                    ParameterValue::BoolArray(v)
+                } else if let Some(v) = seq.iter().map(|v| v.as_i64()).collect::<Option<Vec<_>>>() {
+                    ParameterValue::IntegerArray(v)
+                } else if let Some(v) = seq.iter().map(|v| v.as_f64()).collect::<Option<Vec<_>>>() {
+                    ParameterValue::DoubleArray(v)
+                } else if let Some(v) = seq
+                    .iter()
+                    .map(|v| v.as_str().map(|s| s.to_owned()))
+                    .collect::<Option<Vec<_>>>()
+                {
+                    ParameterValue::StringArray(v)
+                } else {
+                    return None;
+                }
+            }
+            Value::Object(_) => return None,
+        };
+        Some(value.with_type_of(existing))
+    }
+
+    // Use the type of the existing value when the yaml or json is ambiguous.
+    pub(crate) fn with_type_of(self, existing: Option<&ParameterValue>) -> Self {
+        match (self, existing) {
+            (ParameterValue::Integer(i), Some(ParameterValue::Double(_))) => {
+                ParameterValue::Double(i as f64)
+            }
+            (ParameterValue::IntegerArray(v), Some(ParameterValue::DoubleArray(_))) => {
+                ParameterValue::DoubleArray(v.into_iter().map(|i| i as f64).collect())
+            }
+            (ParameterValue::IntegerArray(v), Some(ParameterValue::ByteArray(_))) => {
+                match v.iter().map(|i| u8::try_from(*i).ok()).collect() {
+                    Some(bytes) => ParameterValue::ByteArray(bytes),
+                    None => ParameterValue::IntegerArray(v),
+                }
+            }
+            // an empty list carries no type information at all.
+            (ParameterValue::BoolArray(v), Some(existing))
+                if v.is_empty() && existing.type_name().ends_with("array") =>
+            {
+                match existing {
+                    ParameterValue::ByteArray(_) => ParameterValue::ByteArray(vec![]),
+                    ParameterValue::IntegerArray(_) => ParameterValue::IntegerArray(vec![]),
+                    ParameterValue::DoubleArray(_) => ParameterValue::DoubleArray(vec![]),
+                    ParameterValue::StringArray(_) => ParameterValue::StringArray(vec![]),
+                    _ => ParameterValue::BoolArray(vec![]),
+                }
+            }
+            (value, _) => value,
+        }
+    }
+
+    /// The value in YAML flow style, as read by the rcl parameter parser.
+    ///
+    /// Byte arrays are written as integer lists, which the parser reads as
+    /// integer arrays.
+    pub(crate) fn to_yaml(&self) -> String {
+        fn double(d: f64) -> String {
+            match d {
+                d if d.is_nan() => ".nan".into(),
+                d if d == f64::INFINITY => ".inf".into(),
+                d if d == f64::NEG_INFINITY => "-.inf".into(),
+                // the debug format always has a '.' or an exponent.
+                d => format!("{:?}", d),
+            }
+        }
+        // a JSON string is a double quoted YAML string, and so never
+        // read as a number or a boolean.
+        fn string(s: &str) -> String {
+            serde_json::Value::from(s).to_string()
+        }
+        fn list<T>(v: &[T], f: impl Fn(&T) -> String) -> String {
+            format!("[{}]", v.iter().map(f).collect::<Vec<_>>().join(", "))
+        }
+
+        match self {
+            ParameterValue::NotSet => "null".into(),
+            ParameterValue::Bool(b) => b.to_string(),
+            ParameterValue::Integer(i) => i.to_string(),
+            ParameterValue::Double(d) => double(*d),
+            ParameterValue::String(s) => string(s),
+            ParameterValue::BoolArray(v) => list(v, |b| b.to_string()),
+            ParameterValue::ByteArray(v) => list(v, |b| b.to_string()),
+            ParameterValue::IntegerArray(v) => list(v, |i| i.to_string()),
+            ParameterValue::DoubleArray(v) => list(v, |d| double(*d)),
+            ParameterValue::StringArray(v) => list(v, |s| string(s)),
+        }
+    }
+
+    /// Convert the value to JSON.
+    ///
+    /// Doubles which are not finite have no JSON representation and become `null`.
+    pub(crate) fn to_json(&self) -> serde_json::Value {
+        use serde_json::Value;
+
+        match self {
+            ParameterValue::NotSet => Value::Null,
+            ParameterValue::Bool(b) => Value::from(*b),
+            ParameterValue::Integer(i) => Value::from(*i),
+            ParameterValue::Double(d) => Value::from(*d),
+            ParameterValue::String(s) => Value::from(s.as_str()),
+            ParameterValue::BoolArray(v) => Value::from(v.clone()),
+            ParameterValue::ByteArray(v) => Value::from(v.clone()),
+            ParameterValue::IntegerArray(v) => Value::from(v.clone()),
+            ParameterValue::DoubleArray(v) => Value::from(v.clone()),
+            ParameterValue::StringArray(v) => Value::from(v.clone()),
+        }
+    }
+}
+
+/// The result of setting one parameter, see
+/// [`Node::set_parameters_from_json`](crate::Node::set_parameters_from_json).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetParameterResult {
+    pub name: String,
+    pub successful: bool,
+    /// Why the change was rejected, empty if it was successful.
+    pub reason: String,
+}
+
+/// The parameters changed together by one request, see
+/// [`Node::make_parameter_handler_batched`](crate::Node::make_parameter_handler_batched).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchParameterEvent {
+    /// The fully qualified name of the node.
+    pub node_name: String,
+    /// The names and new values, in the order they were set.
+    pub changes: Vec<(String, ParameterValue)>,
+}
+
+/// A value or a nested mapping of a ROS2 parameter YAML file.
+pub(crate) enum YamlNode {
+    /// A value in flow style, see [`ParameterValue::to_yaml`].
+    Value(String),
+    Mapping(IndexMap<String, YamlNode>),
+}
+
+/// Insert a parameter value into nested mappings, splitting the name on dots.
+///
+/// Returns false, leaving `mapping` as it was, if the name collides with
+/// a parameter inserted before, e.g. `a` and `a.b`.
+pub(crate) fn yaml_insert_param(
+    mapping: &mut IndexMap<String, YamlNode>, name: &str, value: String,
+) -> bool {
+    match name.split_once('.') {
+        Some((head, rest)) => {
+            let entry = mapping
+                .entry(head.to_owned())
+                .or_insert_with(|| YamlNode::Mapping(IndexMap::new()));
+            match entry {
+                YamlNode::Mapping(m) => yaml_insert_param(m, rest, value),
+                YamlNode::Value(_) => false,
+            }
+        }
+        None if mapping.contains_key(name) => false,
+        None => {
+            mapping.insert(name.to_owned(), YamlNode::Value(value));
+            true
+        }
+    }
+}
+
+/// Write nested mappings as YAML, indented by two spaces per level.
+pub(crate) fn yaml_write(out: &mut String, mapping: &IndexMap<String, YamlNode>, indent: usize) {
+    for (key, node) in mapping {
+        match node {
+            YamlNode::Value(value) => out.push_str(&format!("{:indent$}{key}: {value}\n", "")),
+            YamlNode::Mapping(m) if m.is_empty() => {
+                out.push_str(&format!("{:indent$}{key}: {{}}\n", ""))
+            }
+            YamlNode::Mapping(m) => {
+                out.push_str(&format!("{:indent$}{key}:\n", ""));
+                yaml_write(out, m, indent + 2);
+            }
+        }
+    }
 }
 
 /// ROS parameter.
@@ -297,6 +521,146 @@ impl Parameter {
     }
 }
 
+/// Range constraint of a floating point parameter.
+///
+/// The range is inclusive. If `step` is non-zero, valid values are
+/// `min + n * step` and `max`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FloatingPointRange {
+    pub min: f64,
+    pub max: f64,
+    pub step: f64,
+}
+
+impl FloatingPointRange {
+    // same tolerance as rclcpp.
+    const TOLERANCE: f64 = 1e-9;
+
+    fn contains(&self, value: f64) -> bool {
+        let close = |a: f64, b: f64| (a - b).abs() <= Self::TOLERANCE;
+        if close(value, self.min) || close(value, self.max) {
+            return true;
+        }
+        if value < self.min || value > self.max {
+            return false;
+        }
+        self.step == 0.0 || {
+            let steps = ((value - self.min) / self.step).round();
+            close(self.min + steps * self.step, value)
+        }
+    }
+}
+
+/// Range constraint of an integer parameter.
+///
+/// The range is inclusive. If `step` is non-zero, valid values are
+/// `min + n * step` and `max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntegerRange {
+    pub min: i64,
+    pub max: i64,
+    pub step: u64,
+}
+
+impl IntegerRange {
+    fn contains(&self, value: i64) -> bool {
+        if value == self.max {
+            return true;
+        }
+        self.min <= value
+            && value <= self.max
+            && (self.step == 0 || value.abs_diff(self.min).is_multiple_of(self.step))
+    }
+}
+
+/// Metadata and constraints of a declared parameter.
+///
+/// See [`Node::declare_parameter()`](crate::Node::declare_parameter()).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParameterDescriptor {
+    pub description: String,
+    /// Plain English description of constraints not covered by the ranges.
+    pub additional_constraints: String,
+    /// Read only parameters can not be changed using the parameter services.
+    pub read_only: bool,
+    /// Allow the parameter services to change the type of the parameter.
+    pub dynamic_typing: bool,
+    /// Only used for [`ParameterValue::Double`] parameters.
+    pub floating_point_range: Option<FloatingPointRange>,
+    /// Only used for [`ParameterValue::Integer`] parameters.
+    pub integer_range: Option<IntegerRange>,
+}
+
+impl ParameterDescriptor {
+    /// Checks that `value` is within the ranges of the descriptor.
+    pub(crate) fn check_range(
+        &self, name: &str, value: &ParameterValue,
+    ) -> std::result::Result<(), String> {
+        let in_range = match (value, &self.floating_point_range, &self.integer_range) {
+            (ParameterValue::Double(v), Some(range), _) => range.contains(*v),
+            (ParameterValue::Integer(v), _, Some(range)) => range.contains(*v),
+            _ => true,
+        };
+        if in_range {
+            Ok(())
+        } else {
+            Err(format!("Parameter {name} out of range"))
+        }
+    }
+
+    /// Checks that the parameter can be changed from `current` to `value`
+    /// by an external source.
+    pub(crate) fn check_set(
+        &self, name: &str, current: &ParameterValue, value: &ParameterValue,
+    ) -> std::result::Result<(), String> {
+        if self.read_only {
+            return Err(format!("Trying to set a read-only parameter: {name}"));
+        }
+        if !self.dynamic_typing
+            && *current != ParameterValue::NotSet
+            && current.into_parameter_type() != value.into_parameter_type()
+        {
+            return Err(format!(
+                "Wrong parameter type, parameter {name} is of type {}, setting it to {} is not allowed",
+                current.type_name(),
+                value.type_name()
+            ));
+        }
+        self.check_range(name, value)
+    }
+
+    pub(crate) fn to_msg(
+        &self, name: &str, value: &ParameterValue,
+    ) -> rcl_interfaces::msg::ParameterDescriptor {
+        rcl_interfaces::msg::ParameterDescriptor {
+            name: name.to_owned(),
+            type_: value.into_parameter_type(),
+            description: self.description.clone(),
+            additional_constraints: self.additional_constraints.clone(),
+            read_only: self.read_only,
+            dynamic_typing: self.dynamic_typing,
+            floating_point_range: self
+                .floating_point_range
+                .iter()
+                .map(|r| rcl_interfaces::msg::FloatingPointRange {
+                    from_value: r.min,
+                    to_value: r.max,
+                    step: r.step,
+                })
+                .collect(),
+            integer_range: self
+                .integer_range
+                .iter()
+                .map(|r| rcl_interfaces::msg::IntegerRange {
+                    from_value: r.min,
+                    to_value: r.max,
+                    step: r.step,
+                })
+                .collect(),
+        }
+    }
+}
+
 /// Trait for use it with
 /// [`Node::make_derived_parameter_handler()`](crate::Node::make_derived_parameter_handler()).
 ///
@@ -404,3 +768,410 @@ where
 fn to_string(x: &str) -> Result<String> {
     Ok(x.to_string())
 }
+
+/// Internal function used by code derived for `r2r_macros::Parameters`.
+#[doc(hidden)]
+pub fn parameter_group_name(prefix: &str, field: &str) -> String {
+    if prefix.is_empty() {
+        field.to_owned()
+    } else {
+        format!("{prefix}.{field}")
+    }
+}
+
+/// Internal function used by code derived for `r2r_macros::Parameters`.
+#[doc(hidden)]
+pub fn parameter_group_value<T: RosParams + Default>(
+    values: &IndexMap<String, ParameterValue>, prefix: &str, field: &str,
+) -> Result<T> {
+    let name = parameter_group_name(prefix, field);
+    let value = values.get(&name).unwrap_or(&ParameterValue::NotSet);
+    let mut result = T::default();
+    result
+        .set_parameter("", value)
+        .map_err(|e| e.update_param_name(&name))?;
+    Ok(result)
+}
+
+pub(crate) fn get_parameter<T>(params: &IndexMap<String, Parameter>, name: &str) -> Result<T>
+where
+    ParameterValue: TryInto<T, Error = WrongParameterType>,
+{
+    let value = params
+        .get(name)
+        .map(|parameter| parameter.value.clone())
+        .unwrap_or(ParameterValue::NotSet);
+
+    let value: T =
+        value
+            .try_into()
+            .map_err(|error: WrongParameterType| Error::ParameterWrongType {
+                name: name.to_string(),
+                expected_type: error.expected_type_name,
+                actual_type: error.actual_type_name,
+            })?;
+
+    Ok(value)
+}
+
+/// The parameters of a node under a common prefix, e.g. `camera` for
+/// `camera.width` and `camera.height`.
+///
+/// Names are given relative to the prefix. Values are set like with
+/// [`Node::set_parameters_atomically`], so they are validated and, once the
+/// parameter handler is created, announced on `/parameter_events`.
+///
+/// ``` rust,ignore
+/// let mut camera = ParameterGroup::new(&mut node, "camera")?;
+/// let width: i64 = camera.get("width")?;
+/// camera.set("height", ParameterValue::Integer(480))?;
+/// ```
+pub struct ParameterGroup {
+    prefix: String,
+    node_name: String,
+    params: Arc<Mutex<IndexMap<String, Parameter>>>,
+    handler_setter: Arc<Mutex<Option<ParameterSetter>>>,
+    direct_setter: ParameterSetter,
+    events: Option<ParameterEvents>,
+}
+
+type ParameterEvents = Pin<Box<dyn Stream<Item = rcl_interfaces::msg::ParameterEvent> + Send>>;
+
+impl ParameterGroup {
+    /// Create the group of the parameters of `node` under `prefix`.
+    ///
+    /// This subscribes to `/parameter_events` for the
+    /// [`ParameterGroup::event_stream`] of the group.
+    pub fn new(node: &mut Node, prefix: &str) -> Result<ParameterGroup> {
+        let (handler_setter, direct_setter) = node.parameter_setters()?;
+        let events = node.subscribe_to_parameter_events()?;
+        Ok(ParameterGroup {
+            prefix: prefix.to_owned(),
+            node_name: node.fully_qualified_name()?,
+            params: node.params.clone(),
+            handler_setter,
+            direct_setter,
+            events: Some(Box::pin(events)),
+        })
+    }
+
+    /// The prefix of the group.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Fetch the parameter `name` of the group.
+    pub fn get<T>(&self, name: &str) -> Result<T>
+    where
+        ParameterValue: TryInto<T, Error = WrongParameterType>,
+    {
+        let name = parameter_group_name(&self.prefix, name);
+        get_parameter(&self.params.lock().unwrap(), &name)
+    }
+
+    /// Set the parameter `name` of the group.
+    ///
+    /// Returns [`Error::ParameterRejected`] if the value is not valid.
+    pub fn set(&self, name: &str, value: ParameterValue) -> Result<()> {
+        let name = parameter_group_name(&self.prefix, name);
+        let mut setter = self
+            .handler_setter
+            .lock()
+            .unwrap()
+            .clone()
+            .unwrap_or_else(|| self.direct_setter.clone());
+        setter.try_set_atomically(vec![(name, value)])
+    }
+
+    /// The new and changed parameters of the group, with their names
+    /// relative to the prefix.
+    ///
+    /// The events are those announced on `/parameter_events`, so changes are
+    /// only seen once the parameter handler of the node is created. The
+    /// stream can only be taken once, later calls return
+    /// [`Error::RCL_RET_SUBSCRIPTION_INVALID`].
+    pub fn event_stream(
+        &mut self,
+    ) -> Result<impl Stream<Item = (String, ParameterValue)> + Unpin + Send> {
+        let events = self
+            .events
+            .take()
+            .ok_or(Error::RCL_RET_SUBSCRIPTION_INVALID)?;
+        let node_name = self.node_name.clone();
+        // like parameter_group_name, an empty prefix groups all parameters.
+        let prefix = match self.prefix.as_str() {
+            "" => String::new(),
+            prefix => format!("{prefix}."),
+        };
+        Ok(events.flat_map(move |event| {
+            let changes = if event.node == node_name {
+                event
+                    .new_parameters
+                    .into_iter()
+                    .chain(event.changed_parameters)
+                    .filter_map(|p| {
+                        let name = p.name.strip_prefix(&prefix)?.to_owned();
+                        Some((name, ParameterValue::from_parameter_value_msg(p.value)))
+                    })
+                    .collect()
+            } else {
+                vec![]
+            };
+            stream::iter(changes)
+        }))
+    }
+}
+
+/// A parameter of a node, declared with [`Node::declare_parameter_live`].
+///
+/// [`ParameterHandle::get`] reads the current value of the parameter from
+/// the node, so changes made through the parameter services are seen as
+/// soon as they are applied.
+///
+/// ``` rust,ignore
+/// let mut rate = node.declare_parameter_live("rate", 10.0)?;
+/// let mut changes = rate.changed()?;
+/// println!("rate is {}", rate.get());
+/// ```
+pub struct ParameterHandle<T> {
+    name: String,
+    node_name: String,
+    params: Arc<Mutex<IndexMap<String, Parameter>>>,
+    default: Arc<Mutex<T>>,
+    events: Option<ParameterEvents>,
+}
+
+impl<T> ParameterHandle<T>
+where
+    T: Clone + Send + 'static,
+    ParameterValue: TryInto<T, Error = WrongParameterType>,
+{
+    pub(crate) fn new(
+        node: &Node, name: &str, default: T,
+        events: impl Stream<Item = rcl_interfaces::msg::ParameterEvent> + Send + 'static,
+    ) -> Result<ParameterHandle<T>> {
+        Ok(ParameterHandle {
+            name: name.to_owned(),
+            node_name: node.fully_qualified_name()?,
+            params: node.params.clone(),
+            default: Arc::new(Mutex::new(default)),
+            events: Some(Box::pin(events)),
+        })
+    }
+
+    /// The name of the parameter.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The current value of the parameter.
+    ///
+    /// The default is returned if the parameter is not set or has another type.
+    pub fn get(&self) -> T {
+        get_parameter(&self.params.lock().unwrap(), &self.name)
+            .unwrap_or_else(|_| self.default.lock().unwrap().clone())
+    }
+
+    /// Change the value returned by [`ParameterHandle::get`] when the
+    /// parameter is not set or has another type.
+    pub fn set_default(&self, default: T) {
+        *self.default.lock().unwrap() = default;
+    }
+
+    /// The new values of the parameter.
+    ///
+    /// The values are those announced on `/parameter_events`, so changes are
+    /// only seen once the parameter handler of the node is created, and values
+    /// of another type are skipped. The stream can only be taken once, later
+    /// calls return [`Error::RCL_RET_SUBSCRIPTION_INVALID`].
+    pub fn changed(&mut self) -> Result<impl Stream<Item = T> + Unpin + Send> {
+        let events = self
+            .events
+            .take()
+            .ok_or(Error::RCL_RET_SUBSCRIPTION_INVALID)?;
+        let node_name = self.node_name.clone();
+        let name = self.name.clone();
+        Ok(events.flat_map(move |event| {
+            let values = if event.node == node_name {
+                event
+                    .new_parameters
+                    .into_iter()
+                    .chain(event.changed_parameters)
+                    .filter(|p| p.name == name)
+                    .filter_map(|p| {
+                        ParameterValue::from_parameter_value_msg(p.value)
+                            .try_into()
+                            .ok()
+                    })
+                    .collect()
+            } else {
+                vec![]
+            };
+            stream::iter(values)
+        }))
+    }
+}
+
+/// A change announced in a `rcl_interfaces/msg/ParameterEvent`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParameterChange {
+    New { name: String, value: ParameterValue },
+    Changed { name: String, value: ParameterValue },
+    Deleted { name: String },
+}
+
+/// Helpers for reading a `rcl_interfaces/msg/ParameterEvent`.
+///
+/// The values are converted from the message, so they are returned by value.
+pub trait ParameterEventExt {
+    /// The parameters declared by the event.
+    fn new_params(&self) -> impl Iterator<Item = (&str, ParameterValue)>;
+    /// The parameters given a new value by the event.
+    fn changed_params(&self) -> impl Iterator<Item = (&str, ParameterValue)>;
+    /// The names of the parameters deleted by the event.
+    fn deleted_params(&self) -> impl Iterator<Item = &str>;
+    /// All changes of the event, new parameters first, then changed and deleted ones.
+    fn changes(&self) -> impl Iterator<Item = ParameterChange>;
+}
+
+fn parameter_msg_values(
+    params: &[rcl_interfaces::msg::Parameter],
+) -> impl Iterator<Item = (&str, ParameterValue)> {
+    params
+        .iter()
+        .map(|p| (p.name.as_str(), ParameterValue::from_parameter_value_msg(p.value.clone())))
+}
+
+impl ParameterEventExt for rcl_interfaces::msg::ParameterEvent {
+    fn new_params(&self) -> impl Iterator<Item = (&str, ParameterValue)> {
+        parameter_msg_values(&self.new_parameters)
+    }
+
+    fn changed_params(&self) -> impl Iterator<Item = (&str, ParameterValue)> {
+        parameter_msg_values(&self.changed_parameters)
+    }
+
+    fn deleted_params(&self) -> impl Iterator<Item = &str> {
+        self.deleted_parameters.iter().map(|p| p.name.as_str())
+    }
+
+    fn changes(&self) -> impl Iterator<Item = ParameterChange> {
+        let new = self.new_params().map(|(name, value)| ParameterChange::New {
+            name: name.to_owned(),
+            value,
+        });
+        let changed = self
+            .changed_params()
+            .map(|(name, value)| ParameterChange::Changed {
+                name: name.to_owned(),
+                value,
+            });
+        let deleted = self.deleted_params().map(|name| ParameterChange::Deleted {
+            name: name.to_owned(),
+        });
+        new.chain(changed).chain(deleted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    // parse a parameter override like rcl does for `--ros-args -p name:=value`.
+    fn parse_param_override(arg: &str) -> ParameterValue {
+        let args = ["--ros-args", "-p", arg].map(|a| CString::new(a).unwrap());
+        let c_args = args.iter().map(|a| a.as_ptr()).collect::<Vec<_>>();
+        unsafe {
+            let mut arguments = rcl_get_zero_initialized_arguments();
+            let ret = rcl_parse_arguments(
+                c_args.len() as ::std::os::raw::c_int,
+                c_args.as_ptr(),
+                rcutils_get_default_allocator(),
+                &mut arguments,
+            );
+            assert_eq!(ret, RCL_RET_OK as i32);
+
+            let mut params: *mut rcl_params_t = std::ptr::null_mut();
+            let ret = rcl_arguments_get_param_overrides(&arguments, &mut params);
+            assert_eq!(ret, RCL_RET_OK as i32);
+            assert!(!params.is_null());
+
+            // one node ("/**") with one parameter.
+            assert_eq!((*params).num_nodes, 1);
+            let node_params = &*(*params).params;
+            assert_eq!(node_params.num_params, 1);
+            let value = ParameterValue::from_rcl(&*node_params.parameter_values);
+
+            rcl_yaml_node_struct_fini(params);
+            rcl_arguments_fini(&mut arguments);
+            value
+        }
+    }
+
+    #[test]
+    fn test_array_parameter_overrides() {
+        let gains = parse_param_override("gains:=[1.5, 2.5, 3.5]");
+        let gains: Vec<f64> = gains.try_into().unwrap();
+        assert_eq!(gains, vec![1.5, 2.5, 3.5]);
+
+        assert_eq!(
+            parse_param_override("flags:=[true, false]"),
+            ParameterValue::BoolArray(vec![true, false])
+        );
+        assert_eq!(
+            parse_param_override("ids:=[1, 2, 3]"),
+            ParameterValue::IntegerArray(vec![1, 2, 3])
+        );
+        assert_eq!(
+            parse_param_override("names:=[left, right]"),
+            ParameterValue::StringArray(vec!["left".into(), "right".into()])
+        );
+    }
+
+    #[test]
+    fn test_parameter_event_ext() {
+        let param = |name: &str, value: ParameterValue| rcl_interfaces::msg::Parameter {
+            name: name.into(),
+            value: value.into_parameter_value_msg(),
+        };
+        let event = rcl_interfaces::msg::ParameterEvent {
+            node: "/node".into(),
+            new_parameters: vec![param("a", ParameterValue::Integer(1))],
+            changed_parameters: vec![
+                param("b", ParameterValue::Bool(true)),
+                param("c", ParameterValue::String("x".into())),
+            ],
+            deleted_parameters: vec![param("d", ParameterValue::Double(0.5))],
+            ..Default::default()
+        };
+
+        assert_eq!(event.new_params().collect::<Vec<_>>(), vec![("a", ParameterValue::Integer(1))]);
+        assert_eq!(
+            event.changed_params().collect::<Vec<_>>(),
+            vec![
+                ("b", ParameterValue::Bool(true)),
+                ("c", ParameterValue::String("x".into()))
+            ]
+        );
+        assert_eq!(event.deleted_params().collect::<Vec<_>>(), vec!["d"]);
+        assert_eq!(
+            event.changes().collect::<Vec<_>>(),
+            vec![
+                ParameterChange::New {
+                    name: "a".into(),
+                    value: ParameterValue::Integer(1)
+                },
+                ParameterChange::Changed {
+                    name: "b".into(),
+                    value: ParameterValue::Bool(true)
+                },
+                ParameterChange::Changed {
+                    name: "c".into(),
+                    value: ParameterValue::String("x".into())
+                },
+                ParameterChange::Deleted { name: "d".into() },
+            ]
+        );
+    }
+}
@@ -1,12 +1,31 @@
-use futures::{channel::oneshot, Future, TryFutureExt};
+use futures::{
+    channel::{mpsc, oneshot},
+    Future, FutureExt, Sink, TryFutureExt,
+};
 use std::{
-    ffi::{c_void, CString},
+    any::Any,
+    collections::VecDeque,
+    ffi::{c_void, CStr, CString},
     fmt::Debug,
     marker::PhantomData,
-    sync::{Mutex, Once, Weak},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, Once, Weak,
+    },
+    task::{Context, Poll},
+    time::{Duration, Instant},
 };
 
-use crate::{error::*, msg_types::*, qos::QosProfile};
+use crate::{
+    error::*,
+    gid::Gid,
+    msg_types::*,
+    network_flow::*,
+    qos::QosProfile,
+    qos_events::{DeadlineMissedStatus, IncompatibleQosStatus, LivelinessLostStatus},
+    subscribers::{estimate_frequency_hz, ARRIVAL_HISTORY},
+};
 use r2r_rcl::*;
 
 // The publish function is thread safe. ROS2 docs state:
@@ -35,15 +54,99 @@ use r2r_rcl::*;
 // and send out publishers.
 
 unsafe impl<T> Send for Publisher<T> where T: WrappedTypesupport {}
+// Publishing only needs a shared reference, see above.
+unsafe impl<T> Sync for Publisher<T> where T: WrappedTypesupport {}
 
 pub(crate) struct Publisher_ {
     handle: rcl_publisher_t,
 
     // TODO use a mpsc to avoid the mutex?
     poll_inter_process_subscriber_channels: Mutex<Vec<oneshot::Sender<()>>>,
+    subscriber_waiters: Mutex<Vec<SubscriberWaiter>>,
+    // woken up on the next spin to retry publish_async.
+    publish_retry_channels: Mutex<Vec<oneshot::Sender<()>>>,
+    stats: Arc<PublisherStats>,
+}
+
+/// Statistics of the messages sent by a publisher.
+///
+/// The statistics are shared by all handles to the publisher, see
+/// [`Publisher::stats`].
+#[derive(Debug, Default)]
+pub struct PublisherStats {
+    /// The number of messages published so far.
+    pub message_count: AtomicU64,
+    /// The number of bytes published with `publish_raw`. The messages of
+    /// typed publishes are handed to the middleware unserialized, so their
+    /// size is not known and not counted.
+    pub byte_count: AtomicU64,
+    publish_times: Mutex<VecDeque<Instant>>,
+}
+
+impl PublisherStats {
+    /// The rate of published messages, as an exponential moving average with
+    /// the time constant `window`.
+    ///
+    /// It is computed like
+    /// [`SubscriptionStats::estimated_frequency_hz`](crate::SubscriptionStats::estimated_frequency_hz),
+    /// from the last 1024 messages.
+    pub fn estimated_hz(&self, window: Duration) -> f64 {
+        estimate_frequency_hz(&self.publish_times.lock().unwrap(), window, Instant::now())
+    }
+
+    /// When the last message was published.
+    pub fn last_publish_time(&self) -> Option<Instant> {
+        self.publish_times.lock().unwrap().back().copied()
+    }
+
+    // one lock per publish, the counts are atomics.
+    fn record(&self, bytes: usize) {
+        self.message_count.fetch_add(1, Ordering::Relaxed);
+        self.byte_count.fetch_add(bytes as u64, Ordering::Relaxed);
+        let mut times = self.publish_times.lock().unwrap();
+        if times.len() == ARRIVAL_HISTORY {
+            times.pop_front();
+        }
+        times.push_back(Instant::now());
+    }
+}
+
+/// See [`Publisher::wait_for_subscribers`].
+struct SubscriberWaiter {
+    min_count: usize,
+    deadline: Option<Instant>,
+    sender: oneshot::Sender<Result<()>>,
 }
 
 impl Publisher_ {
+    pub(crate) fn handle(&self) -> &rcl_publisher_t {
+        &self.handle
+    }
+
+    fn topic_name(&self) -> Result<String> {
+        let name = unsafe { rcl_publisher_get_topic_name(&self.handle) };
+        if name.is_null() {
+            return Err(Error::RCL_RET_PUBLISHER_INVALID);
+        }
+        let name = unsafe { CStr::from_ptr(name) };
+        Ok(name.to_str().unwrap_or("").to_owned())
+    }
+
+    fn gid(&self) -> Result<Gid> {
+        let mut gid = rmw_gid_t {
+            implementation_identifier: std::ptr::null(),
+            data: [0; RMW_GID_STORAGE_SIZE as usize],
+        };
+        let result = unsafe {
+            rmw_get_gid_for_publisher(rcl_publisher_get_rmw_handle(&self.handle), &mut gid)
+        };
+        if result == RMW_RET_OK as i32 {
+            Ok(gid.data)
+        } else {
+            Err(Error::from_rcl_error(result))
+        }
+    }
+
     fn get_inter_process_subscription_count(&self) -> Result<usize> {
         // See https://github.com/ros2/rclcpp/issues/623
 
@@ -88,6 +191,41 @@ impl Publisher_ {
         }
     }
 
+    /// Called from spin, completes the waiters that reached their
+    /// subscription count or timed out.
+    pub(crate) fn poll_subscriber_waiters(&self) {
+        let mut waiters = self.subscriber_waiters.lock().unwrap();
+        if waiters.is_empty() {
+            return;
+        }
+        let count = self.get_inter_process_subscription_count();
+        let now = Instant::now();
+        let mut i = 0;
+        while i < waiters.len() {
+            let w = &waiters[i];
+            let result = match &count {
+                Ok(count) if *count >= w.min_count => Some(Ok(())),
+                Ok(_) if w.deadline.is_some_and(|d| now >= d) => Some(Err(Error::Timeout)),
+                Ok(_) => None,
+                Err(e) => Some(Err(e.clone())),
+            };
+            match result {
+                Some(result) => {
+                    // ignore result, as user may have dropped the receiver.
+                    let _ = waiters.swap_remove(i).sender.send(result);
+                }
+                None => i += 1,
+            }
+        }
+    }
+
+    /// Called from spin, wakes up the pending `publish_async` calls.
+    pub(crate) fn poll_publish_retries(&self) {
+        for sender in self.publish_retry_channels.lock().unwrap().drain(..) {
+            let _ = sender.send(()); // the future may have been dropped.
+        }
+    }
+
     pub(crate) fn destroy(mut self, node: &mut rcl_node_t) {
         let _ret = unsafe { rcl_publisher_fini(&mut self.handle as *mut _, node) };
 
@@ -95,6 +233,45 @@ impl Publisher_ {
     }
 }
 
+/// The outcome of [`Publisher::try_publish`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublishResult {
+    Sent,
+    /// The message could not be sent without blocking, e.g. because the
+    /// queue of a reliable subscriber is full.
+    QueueFull,
+}
+
+/// Options used when creating a publisher.
+///
+/// The callbacks are called from `spin_once` when the corresponding QoS event
+/// occurs. See [`Node::create_publisher_with_options`](crate::Node::create_publisher_with_options).
+#[derive(Clone, Default)]
+pub struct PublisherOptions {
+    /// Called when the publisher did not publish within the deadline of its QoS profile.
+    pub on_deadline_missed: Option<Arc<dyn Fn(DeadlineMissedStatus) + Send + Sync>>,
+    /// Called when the publisher did not assert its liveliness within the lease duration.
+    pub on_liveliness_lost: Option<Arc<dyn Fn(LivelinessLostStatus) + Send + Sync>>,
+    /// Called when a subscription with an incompatible QoS profile was found.
+    pub on_incompatible_qos: Option<Arc<dyn Fn(IncompatibleQosStatus) + Send + Sync>>,
+    /// Ask the middleware for network flow endpoints not shared with other
+    /// publishers, e.g. so that their traffic can be prioritized in
+    /// time-sensitive networking. Middlewares without support ignore this.
+    /// See [`Publisher::get_network_flow_endpoints`].
+    pub unique_network_flow_endpoint: bool,
+}
+
+impl Debug for PublisherOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PublisherOptions")
+            .field("on_deadline_missed", &self.on_deadline_missed.is_some())
+            .field("on_liveliness_lost", &self.on_liveliness_lost.is_some())
+            .field("on_incompatible_qos", &self.on_incompatible_qos.is_some())
+            .field("unique_network_flow_endpoint", &self.unique_network_flow_endpoint)
+            .finish()
+    }
+}
+
 /// A ROS (typed) publisher.
 ///
 /// This contains a `Weak Arc` to a typed publisher. As such it is safe to
@@ -120,6 +297,17 @@ pub struct PublisherUntyped {
     type_: String,
 }
 
+/// A type erased ROS publisher.
+///
+/// Publishers of different message types can be stored together as
+/// `AnyPublisher`, e.g. in a topic bridge. Messages can be published as
+/// serialized bytes, or by downcasting to the typed publisher.
+#[derive(Debug, Clone)]
+pub struct AnyPublisher {
+    untyped: PublisherUntyped,
+    typed: Option<Arc<dyn Any + Send + Sync>>,
+}
+
 pub fn make_publisher<T>(handle: Weak<Publisher_>) -> Publisher<T>
 where
     T: WrappedTypesupport,
@@ -137,13 +325,38 @@ pub fn make_publisher_untyped(handle: Weak<Publisher_>, type_: String) -> Publis
 pub fn create_publisher_helper(
     node: &mut rcl_node_t, topic: &str, typesupport: *const rosidl_message_type_support_t,
     qos_profile: QosProfile,
+) -> Result<Publisher_> {
+    create_publisher_helper_with_options(
+        node,
+        topic,
+        typesupport,
+        qos_profile,
+        &PublisherOptions::default(),
+    )
+}
+
+pub fn create_publisher_helper_with_options(
+    node: &mut rcl_node_t, topic: &str, typesupport: *const rosidl_message_type_support_t,
+    qos_profile: QosProfile, options: &PublisherOptions,
 ) -> Result<Publisher_> {
     let mut publisher_handle = unsafe { rcl_get_zero_initialized_publisher() };
     let topic_c_string = CString::new(topic).map_err(|_| Error::RCL_RET_INVALID_ARGUMENT)?;
 
+    #[cfg(r2r__ros__distro__foxy)]
+    if options.unique_network_flow_endpoint {
+        return Err(Error::UnsupportedFeature("network_flow_endpoints"));
+    }
+
     let result = unsafe {
         let mut publisher_options = rcl_publisher_get_default_options();
         publisher_options.qos = qos_profile.into();
+        #[cfg(not(r2r__ros__distro__foxy))]
+        {
+            publisher_options
+                .rmw_publisher_options
+                .require_unique_network_flow_endpoints =
+                unique_network_flow_endpoints_requirement(options.unique_network_flow_endpoint);
+        }
         rcl_publisher_init(
             &mut publisher_handle,
             node,
@@ -156,9 +369,12 @@ pub fn create_publisher_helper(
         Ok(Publisher_ {
             handle: publisher_handle,
             poll_inter_process_subscriber_channels: Mutex::new(Vec::new()),
+            subscriber_waiters: Mutex::new(Vec::new()),
+            publish_retry_channels: Mutex::new(Vec::new()),
+            stats: Arc::new(PublisherStats::default()),
         })
     } else {
-        Err(Error::from_rcl_error(result))
+        Err(Error::from_rcl_call("rcl_publisher_init", result))
     }
 }
 
@@ -185,6 +401,7 @@ impl PublisherUntyped {
         };
 
         if result == RCL_RET_OK as i32 {
+            publisher.stats.record(0);
             Ok(())
         } else {
             log::error!("could not publish {}", result);
@@ -223,6 +440,7 @@ impl PublisherUntyped {
         };
 
         if result == RCL_RET_OK as i32 {
+            publisher.stats.record(data.len());
             Ok(())
         } else {
             log::error!("could not publish {}", result);
@@ -230,6 +448,47 @@ impl PublisherUntyped {
         }
     }
 
+    /// Gets the fully qualified name of the topic, after remapping.
+    pub fn topic_name(&self) -> Result<String> {
+        self.handle
+            .upgrade()
+            .ok_or(Error::RCL_RET_PUBLISHER_INVALID)?
+            .topic_name()
+    }
+
+    /// Gets the globally unique identifier of the publisher.
+    ///
+    /// Subscribers see it in [`MessageInfo::publisher_gid`](crate::MessageInfo::publisher_gid).
+    pub fn get_gid(&self) -> Result<Gid> {
+        self.handle
+            .upgrade()
+            .ok_or(Error::RCL_RET_PUBLISHER_INVALID)?
+            .gid()
+    }
+
+    /// The statistics of the messages published so far, see [`PublisherStats`].
+    pub fn stats(&self) -> Result<Arc<PublisherStats>> {
+        Ok(self
+            .handle
+            .upgrade()
+            .ok_or(Error::RCL_RET_PUBLISHER_INVALID)?
+            .stats
+            .clone())
+    }
+
+    /// The network flow endpoints of the publisher, e.g. the address and port
+    /// its data is sent from.
+    ///
+    /// Returns [`Error::UnsupportedFeature`] if the middleware cannot report them.
+    /// See also [`PublisherOptions::unique_network_flow_endpoint`].
+    pub fn get_network_flow_endpoints(&self) -> Result<Vec<NetworkFlowEndpoint>> {
+        let publisher = self
+            .handle
+            .upgrade()
+            .ok_or(Error::RCL_RET_PUBLISHER_INVALID)?;
+        publisher_network_flow_endpoints(&publisher.handle)
+    }
+
     /// Gets the number of external subscribers (i.e. it doesn't
     /// count subscribers from the same process).
     pub fn get_inter_process_subscription_count(&self) -> Result<usize> {
@@ -256,10 +515,50 @@ impl PublisherUntyped {
     }
 }
 
+impl AnyPublisher {
+    pub(crate) fn from_untyped(untyped: PublisherUntyped) -> Self {
+        AnyPublisher {
+            untyped,
+            typed: None,
+        }
+    }
+
+    /// The message type of the publisher, e.g. `"std_msgs/msg/String"`.
+    pub fn type_name(&self) -> &str {
+        &self.untyped.type_
+    }
+
+    /// Get the typed publisher, if this was created from a `Publisher<T>`.
+    pub fn downcast_ref<T>(&self) -> Option<&Publisher<T>>
+    where
+        T: WrappedTypesupport + 'static,
+    {
+        self.typed.as_ref()?.downcast_ref::<Publisher<T>>()
+    }
+
+    /// Publish a pre-serialized ROS message.
+    ///
+    /// See [`PublisherUntyped::publish_raw`].
+    pub fn publish_raw(&self, data: &[u8]) -> Result<()> {
+        self.untyped.publish_raw(data)
+    }
+}
+
 impl<T: 'static> Publisher<T>
 where
     T: WrappedTypesupport,
 {
+    /// Convert into a type erased [`AnyPublisher`].
+    ///
+    /// The typed publisher can be recovered with [`AnyPublisher::downcast_ref`].
+    pub fn into_any(self) -> AnyPublisher {
+        let type_name = message_type_name(T::get_ts()).unwrap_or_default();
+        AnyPublisher {
+            untyped: make_publisher_untyped(self.handle.clone(), type_name),
+            typed: Some(Arc::new(self)),
+        }
+    }
+
     /// Publish a ROS message.
     pub fn publish(&self, msg: &T) -> Result<()>
     where
@@ -280,6 +579,7 @@ where
         };
 
         if result == RCL_RET_OK as i32 {
+            publisher.stats.record(0);
             Ok(())
         } else {
             log::error!("could not publish {}", result);
@@ -287,6 +587,76 @@ where
         }
     }
 
+    /// Publish a ROS message, reporting a full queue instead of an error.
+    ///
+    /// Whether a full queue can be detected depends on the middleware: for a
+    /// reliable publisher it blocks for at most the max blocking time of the
+    /// QoS, and then reports a timeout, which is returned as
+    /// [`PublishResult::QueueFull`].
+    pub fn try_publish(&self, msg: &T) -> Result<PublishResult>
+    where
+        T: WrappedTypesupport,
+    {
+        let publisher = self
+            .handle
+            .upgrade()
+            .ok_or(Error::RCL_RET_PUBLISHER_INVALID)?;
+        let native_msg: WrappedNativeMsg<T> = WrappedNativeMsg::<T>::from(msg);
+        let result = unsafe {
+            rcl_publish(
+                &publisher.handle as *const rcl_publisher_t,
+                native_msg.void_ptr(),
+                std::ptr::null_mut(),
+            )
+        };
+
+        if result == RCL_RET_OK as i32 {
+            publisher.stats.record(0);
+            Ok(PublishResult::Sent)
+        } else if result == RCL_RET_TIMEOUT as i32 {
+            Ok(PublishResult::QueueFull)
+        } else {
+            Err(Error::from_rcl_error(result))
+        }
+    }
+
+    /// Publish a ROS message, retrying while the queue is full.
+    ///
+    /// Instead of dropping the message, the future waits for the next time the
+    /// node is spun and tries again, until the message is sent. See
+    /// [`Publisher::try_publish`].
+    pub fn publish_async(&self, msg: T) -> impl Future<Output = Result<()>>
+    where
+        T: WrappedTypesupport,
+    {
+        let publisher = self.clone();
+        async move {
+            loop {
+                if publisher.try_publish(&msg)? == PublishResult::Sent {
+                    return Ok(());
+                }
+                let (sender, receiver) = oneshot::channel();
+                publisher
+                    .handle
+                    .upgrade()
+                    .ok_or(Error::RCL_RET_PUBLISHER_INVALID)?
+                    .publish_retry_channels
+                    .lock()
+                    .unwrap()
+                    .push(sender);
+                receiver
+                    .await
+                    .map_err(|_| Error::RCL_RET_PUBLISHER_INVALID)?;
+            }
+        }
+    }
+
+    /// Borrow a message from the middleware for zero-copy publishing.
+    ///
+    /// The returned message should be filled in and then published with
+    /// [`Publisher::publish_native`]. If the middleware can't loan messages, a
+    /// regular heap allocated message is returned instead, so this can be used
+    /// regardless of which middleware is used.
     pub fn borrow_loaned_message(&self) -> Result<WrappedNativeMsg<T>>
     where
         T: WrappedTypesupport,
@@ -306,7 +676,10 @@ where
                     &mut loaned_msg,
                 )
             };
-            if ret != RCL_RET_OK as i32 {
+            if ret == RCL_RET_UNSUPPORTED as i32 {
+                log_loan_fallback();
+                return Ok(WrappedNativeMsg::<T>::new());
+            } else if ret != RCL_RET_OK as i32 {
                 log::error!("Failed getting loaned message");
                 return Err(Error::from_rcl_error(ret));
             }
@@ -332,13 +705,7 @@ where
             );
             Ok(msg)
         } else {
-            static LOG_LOANED_ERROR: Once = Once::new();
-            LOG_LOANED_ERROR.call_once(|| {
-                log::error!(
-                    "Currently used middleware can't loan messages. Local allocator will be used."
-                );
-            });
-
+            log_loan_fallback();
             Ok(WrappedNativeMsg::<T>::new())
         }
     }
@@ -346,7 +713,9 @@ where
     /// Publish a "native" ROS message.
     ///
     /// This function is useful if you want to bypass the generated
-    /// rust types as it lets you work with the raw C struct.
+    /// rust types as it lets you work with the raw C struct. Messages
+    /// from [`Publisher::borrow_loaned_message`] are handed back to the
+    /// middleware without copying.
     pub fn publish_native(&self, msg: &mut WrappedNativeMsg<T>) -> Result<()>
     where
         T: WrappedTypesupport,
@@ -380,6 +749,7 @@ where
         };
 
         if result == RCL_RET_OK as i32 {
+            publisher.stats.record(0);
             Ok(())
         } else {
             log::error!("could not publish native {}", result);
@@ -387,6 +757,47 @@ where
         }
     }
 
+    /// Gets the fully qualified name of the topic, after remapping.
+    pub fn topic_name(&self) -> Result<String> {
+        self.handle
+            .upgrade()
+            .ok_or(Error::RCL_RET_PUBLISHER_INVALID)?
+            .topic_name()
+    }
+
+    /// Gets the globally unique identifier of the publisher.
+    ///
+    /// Subscribers see it in [`MessageInfo::publisher_gid`](crate::MessageInfo::publisher_gid).
+    pub fn get_gid(&self) -> Result<Gid> {
+        self.handle
+            .upgrade()
+            .ok_or(Error::RCL_RET_PUBLISHER_INVALID)?
+            .gid()
+    }
+
+    /// The statistics of the messages published so far, see [`PublisherStats`].
+    pub fn stats(&self) -> Result<Arc<PublisherStats>> {
+        Ok(self
+            .handle
+            .upgrade()
+            .ok_or(Error::RCL_RET_PUBLISHER_INVALID)?
+            .stats
+            .clone())
+    }
+
+    /// The network flow endpoints of the publisher, e.g. the address and port
+    /// its data is sent from.
+    ///
+    /// Returns [`Error::UnsupportedFeature`] if the middleware cannot report them.
+    /// See also [`PublisherOptions::unique_network_flow_endpoint`].
+    pub fn get_network_flow_endpoints(&self) -> Result<Vec<NetworkFlowEndpoint>> {
+        let publisher = self
+            .handle
+            .upgrade()
+            .ok_or(Error::RCL_RET_PUBLISHER_INVALID)?;
+        publisher_network_flow_endpoints(&publisher.handle)
+    }
+
     /// Gets the number of external subscribers (i.e. it doesn't
     /// count subscribers from the same process).
     pub fn get_inter_process_subscription_count(&self) -> Result<usize> {
@@ -411,4 +822,189 @@ where
 
         Ok(receiver.map_err(|_| Error::RCL_RET_CLIENT_INVALID))
     }
+
+    /// Gets the number of subscriptions matched by this publisher.
+    ///
+    /// Useful to skip computing and publishing expensive messages
+    /// when no one is listening.
+    pub fn get_subscription_count(&self) -> Result<usize> {
+        self.handle
+            .upgrade()
+            .ok_or(Error::RCL_RET_PUBLISHER_INVALID)?
+            .get_inter_process_subscription_count()
+    }
+
+    /// Publish a ROS message if at least one subscription is matched.
+    ///
+    /// Returns false, without converting the message, if no one is listening.
+    pub fn publish_if_subscribed(&self, msg: &T) -> Result<bool> {
+        if self.get_subscription_count()? == 0 {
+            return Ok(false);
+        }
+        self.publish(msg)?;
+        Ok(true)
+    }
+
+    /// Wrap the publisher to only publish when at least `min` subscriptions are matched.
+    pub fn with_subscribers_required(self, min: usize) -> ConditionalPublisher<T> {
+        ConditionalPublisher {
+            publisher: self,
+            min_subscribers: min,
+        }
+    }
+
+    /// Waits until at least `min_count` subscriptions are matched.
+    ///
+    /// The count is checked every time the node is spun. With a timeout,
+    /// the future returns [`Error::Timeout`] if the count was not reached in time.
+    pub fn wait_for_subscribers(
+        &self, min_count: usize, timeout: Option<Duration>,
+    ) -> impl Future<Output = Result<()>> {
+        let (sender, receiver) = oneshot::channel();
+
+        // if the publisher is gone, the sender is dropped and the future returns an error.
+        if let Some(publisher) = self.handle.upgrade() {
+            publisher
+                .subscriber_waiters
+                .lock()
+                .unwrap()
+                .push(SubscriberWaiter {
+                    min_count,
+                    deadline: timeout.map(|t| Instant::now() + t),
+                    sender,
+                });
+        }
+
+        receiver.map(|r| r.unwrap_or(Err(Error::RCL_RET_PUBLISHER_INVALID)))
+    }
+
+    /// Use the publisher as a [`Sink`], e.g. to forward a stream of messages.
+    ///
+    /// ``` rust,ignore
+    /// messages.map(Ok).forward(publisher.into_sink()).await?;
+    /// ```
+    pub fn into_sink(self) -> impl Sink<T, Error = Error> {
+        self
+    }
+}
+
+/// Each message is published when it is sent, the sink is always ready and
+/// flushing does nothing.
+impl<T: 'static> Sink<T> for Publisher<T>
+where
+    T: WrappedTypesupport,
+{
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<()> {
+        self.publish(&item)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// A [`Publisher`] which skips publishing while too few subscriptions are matched.
+///
+/// See [`Publisher::with_subscribers_required`].
+#[derive(Debug, Clone)]
+pub struct ConditionalPublisher<T>
+where
+    T: WrappedTypesupport,
+{
+    publisher: Publisher<T>,
+    min_subscribers: usize,
+}
+
+impl<T: 'static> ConditionalPublisher<T>
+where
+    T: WrappedTypesupport,
+{
+    /// Publish a ROS message if enough subscriptions are matched.
+    ///
+    /// Returns true if the message was published.
+    pub fn publish(&self, msg: &T) -> Result<bool> {
+        if self.publisher.get_subscription_count()? < self.min_subscribers {
+            return Ok(false);
+        }
+        self.publisher.publish(msg)?;
+        Ok(true)
+    }
+
+    /// The wrapped publisher.
+    pub fn publisher(&self) -> &Publisher<T> {
+        &self.publisher
+    }
+
+    /// Unwrap the publisher.
+    pub fn into_inner(self) -> Publisher<T> {
+        self.publisher
+    }
+}
+
+/// A [`Publisher`] which also hands its messages directly to a stream in the same node.
+///
+/// The stream receives the messages without serialization, while other
+/// subscriptions get them through the middleware as usual. See
+/// [`Node::create_intranode_channel`](crate::Node::create_intranode_channel).
+#[derive(Debug, Clone)]
+pub struct IntraPublisher<T>
+where
+    T: WrappedTypesupport,
+{
+    publisher: Publisher<T>,
+    sender: Arc<Mutex<mpsc::Sender<T>>>,
+}
+
+impl<T: 'static> IntraPublisher<T>
+where
+    T: WrappedTypesupport,
+{
+    pub(crate) fn new(publisher: Publisher<T>, sender: mpsc::Sender<T>) -> Self {
+        IntraPublisher {
+            publisher,
+            sender: Arc::new(Mutex::new(sender)),
+        }
+    }
+
+    /// Publish a ROS message, and send it to the intra-node stream.
+    ///
+    /// If the buffer of the stream is full, the message is not published at
+    /// all and [`PublishResult::QueueFull`] is returned, so the stream and the
+    /// other subscriptions see the same messages. After the stream was
+    /// dropped, messages are only published.
+    pub fn publish(&self, msg: T) -> Result<PublishResult> {
+        let mut sender = self.sender.lock().unwrap();
+        let mut cx = Context::from_waker(futures::task::noop_waker_ref());
+        let Poll::Ready(ready) = sender.poll_ready(&mut cx) else {
+            return Ok(PublishResult::QueueFull);
+        };
+        self.publisher.publish(&msg)?;
+        if ready.is_ok() {
+            // cannot fail after poll_ready, the sender is locked.
+            let _ = sender.start_send(msg);
+        }
+        Ok(PublishResult::Sent)
+    }
+
+    /// The wrapped publisher.
+    pub fn publisher(&self) -> &Publisher<T> {
+        &self.publisher
+    }
+}
+
+fn log_loan_fallback() {
+    static LOG_LOANED_FALLBACK: Once = Once::new();
+    LOG_LOANED_FALLBACK.call_once(|| {
+        log::debug!("Currently used middleware can't loan messages. Local allocator will be used.");
+    });
 }
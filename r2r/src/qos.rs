@@ -208,8 +208,9 @@
 use std::time::Duration;
 
 use r2r_rcl::{
-    rmw_qos_durability_policy_t, rmw_qos_history_policy_t, rmw_qos_liveliness_policy_t,
-    rmw_qos_reliability_policy_t, rmw_time_t,
+    rmw_qos_compatibility_type_t, rmw_qos_durability_policy_t, rmw_qos_history_policy_t,
+    rmw_qos_liveliness_policy_t, rmw_qos_profile_check_compatible, rmw_qos_reliability_policy_t,
+    rmw_time_t, RMW_RET_OK,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -729,6 +730,9 @@ impl QosProfile {
 
     /// Set the deadline setting.
     ///
+    /// `Duration::ZERO` means no deadline, `Duration::MAX` is passed on as
+    /// `RMW_DURATION_INFINITE`.
+    ///
     /// # Examples
     ///
     /// ```
@@ -744,6 +748,8 @@ impl QosProfile {
 
     /// Set the lifespan setting.
     ///
+    /// `Duration::MAX` is passed on as `RMW_DURATION_INFINITE`.
+    ///
     /// # Examples
     ///
     /// ```
@@ -805,6 +811,55 @@ impl QosProfile {
             ..self
         }
     }
+
+    /// Check if a subscription with `sub_qos` receives the messages of a
+    /// publisher with this profile, using the compatibility rules of the rmw.
+    ///
+    /// Profiles which might not be compatible, e.g. because a policy is left
+    /// to the system default, are reported as compatible with a reason.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use r2r::qos::QosProfile;
+    /// #
+    /// let publisher = QosProfile::default().best_effort();
+    /// let result = publisher.is_compatible_with_subscription(&QosProfile::default().reliable());
+    /// assert!(!result.compatible);
+    /// ```
+    #[cfg(not(r2r__ros__distro__foxy))]
+    pub fn is_compatible_with_subscription(&self, sub_qos: &QosProfile) -> QosCompatibilityResult {
+        let mut compatibility = rmw_qos_compatibility_type_t::RMW_QOS_COMPATIBILITY_OK;
+        let mut reason = [0 as std::os::raw::c_char; 2048];
+        let ret = unsafe {
+            rmw_qos_profile_check_compatible(
+                self.clone().into(),
+                sub_qos.clone().into(),
+                &mut compatibility,
+                reason.as_mut_ptr(),
+                reason.len(),
+            )
+        };
+        if ret != RMW_RET_OK as i32 {
+            return QosCompatibilityResult {
+                compatible: false,
+                reason: format!("could not check the QoS compatibility ({ret})"),
+            };
+        }
+        let reason = unsafe { std::ffi::CStr::from_ptr(reason.as_ptr()) };
+        QosCompatibilityResult {
+            compatible: compatibility != rmw_qos_compatibility_type_t::RMW_QOS_COMPATIBILITY_ERROR,
+            reason: reason.to_string_lossy().into_owned(),
+        }
+    }
+}
+
+/// The result of [`QosProfile::is_compatible_with_subscription`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QosCompatibilityResult {
+    pub compatible: bool,
+    /// Why the profiles are not or might not be compatible, empty if they are.
+    pub reason: String,
 }
 
 impl From<QosProfile> for r2r_rcl::rmw_qos_profile_t {
@@ -841,6 +896,12 @@ impl From<r2r_rcl::rmw_qos_profile_t> for QosProfile {
     }
 }
 
+/// Value of `RMW_DURATION_INFINITE`, which `Duration::MAX` maps to.
+const RMW_DURATION_INFINITE: rmw_time_t = rmw_time_t {
+    sec: 9223372036,
+    nsec: 854775807,
+};
+
 pub(crate) trait RclDurationT {
     fn to_rmw_time_t(&self) -> rmw_time_t;
     fn from_rmw_time_t(rmw_time: &rmw_time_t) -> Self;
@@ -848,6 +909,9 @@ pub(crate) trait RclDurationT {
 
 impl RclDurationT for Duration {
     fn to_rmw_time_t(&self) -> rmw_time_t {
+        if *self == Duration::MAX {
+            return RMW_DURATION_INFINITE;
+        }
         rmw_time_t {
             sec: self.as_secs(),
             nsec: self.subsec_nanos().into(),
@@ -855,6 +919,11 @@ impl RclDurationT for Duration {
     }
 
     fn from_rmw_time_t(rmw_time: &rmw_time_t) -> Self {
+        if rmw_time.sec == RMW_DURATION_INFINITE.sec && rmw_time.nsec == RMW_DURATION_INFINITE.nsec
+        {
+            return Duration::MAX;
+        }
+
         #[cfg(not(r2r__ros__distro__foxy))]
         {
             assert!(
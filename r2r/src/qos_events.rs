@@ -0,0 +1,364 @@
+use std::{ffi::c_void, mem::MaybeUninit, sync::Arc};
+
+use crate::{error::*, publishers::PublisherOptions, subscribers::SubscriptionOptions};
+use r2r_rcl::*;
+
+/// The deadline of a publisher or subscription was missed.
+///
+/// See [`QosProfile::deadline`](crate::qos::QosProfile::deadline).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeadlineMissedStatus {
+    /// Total number of missed deadlines.
+    pub total_count: i32,
+    /// Number of missed deadlines since the last callback.
+    pub total_count_change: i32,
+}
+
+/// A publisher failed to assert its liveliness within the lease duration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LivelinessLostStatus {
+    /// Total number of times the liveliness was lost.
+    pub total_count: i32,
+    /// Number of times the liveliness was lost since the last callback.
+    pub total_count_change: i32,
+}
+
+/// The liveliness of a publisher matched by a subscription changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LivelinessChangedStatus {
+    /// Number of matched publishers that are alive.
+    pub alive_count: i32,
+    /// Number of matched publishers that are not alive.
+    pub not_alive_count: i32,
+    /// Change of `alive_count` since the last callback.
+    pub alive_count_change: i32,
+    /// Change of `not_alive_count` since the last callback.
+    pub not_alive_count_change: i32,
+}
+
+/// Messages sent to a subscription were lost before it could take them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageLostStatus {
+    /// Total number of lost messages.
+    pub total_count: u64,
+    /// Number of lost messages since the last callback.
+    pub total_count_change: u64,
+}
+
+/// A QoS policy, as reported by [`IncompatibleQosStatus`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QosPolicyKind {
+    Invalid,
+    Durability,
+    Deadline,
+    Liveliness,
+    Reliability,
+    History,
+    Lifespan,
+    Depth,
+    LivelinessLeaseDuration,
+    AvoidRosNamespaceConventions,
+}
+
+impl From<rmw_qos_policy_kind_t> for QosPolicyKind {
+    fn from(kind: rmw_qos_policy_kind_t) -> Self {
+        // compare the values, as the older distributions have fewer policy kinds.
+        match kind as u32 {
+            2 => QosPolicyKind::Durability,
+            4 => QosPolicyKind::Deadline,
+            8 => QosPolicyKind::Liveliness,
+            16 => QosPolicyKind::Reliability,
+            32 => QosPolicyKind::History,
+            64 => QosPolicyKind::Lifespan,
+            128 => QosPolicyKind::Depth,
+            256 => QosPolicyKind::LivelinessLeaseDuration,
+            512 => QosPolicyKind::AvoidRosNamespaceConventions,
+            _ => QosPolicyKind::Invalid,
+        }
+    }
+}
+
+/// A publisher and a subscription on the same topic could not be matched
+/// because their QoS profiles are incompatible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IncompatibleQosStatus {
+    /// Total number of incompatible publishers or subscriptions found.
+    pub total_count: i32,
+    /// Number of incompatible publishers or subscriptions since the last callback.
+    pub total_count_change: i32,
+    /// The policy of the last incompatibility found.
+    pub last_policy_kind: QosPolicyKind,
+}
+
+impl From<rmw_offered_deadline_missed_status_t> for DeadlineMissedStatus {
+    fn from(status: rmw_offered_deadline_missed_status_t) -> Self {
+        DeadlineMissedStatus {
+            total_count: status.total_count,
+            total_count_change: status.total_count_change,
+        }
+    }
+}
+
+impl From<rmw_requested_deadline_missed_status_t> for DeadlineMissedStatus {
+    fn from(status: rmw_requested_deadline_missed_status_t) -> Self {
+        DeadlineMissedStatus {
+            total_count: status.total_count,
+            total_count_change: status.total_count_change,
+        }
+    }
+}
+
+impl From<rmw_liveliness_lost_status_t> for LivelinessLostStatus {
+    fn from(status: rmw_liveliness_lost_status_t) -> Self {
+        LivelinessLostStatus {
+            total_count: status.total_count,
+            total_count_change: status.total_count_change,
+        }
+    }
+}
+
+impl From<rmw_liveliness_changed_status_t> for LivelinessChangedStatus {
+    fn from(status: rmw_liveliness_changed_status_t) -> Self {
+        LivelinessChangedStatus {
+            alive_count: status.alive_count,
+            not_alive_count: status.not_alive_count,
+            alive_count_change: status.alive_count_change,
+            not_alive_count_change: status.not_alive_count_change,
+        }
+    }
+}
+
+impl From<rmw_message_lost_status_t> for MessageLostStatus {
+    fn from(status: rmw_message_lost_status_t) -> Self {
+        MessageLostStatus {
+            total_count: status.total_count as u64,
+            total_count_change: status.total_count_change as u64,
+        }
+    }
+}
+
+impl From<rmw_qos_incompatible_event_status_t> for IncompatibleQosStatus {
+    fn from(status: rmw_qos_incompatible_event_status_t) -> Self {
+        IncompatibleQosStatus {
+            total_count: status.total_count,
+            total_count_change: status.total_count_change,
+            last_policy_kind: status.last_policy_kind.into(),
+        }
+    }
+}
+
+enum EventCallback {
+    OfferedDeadlineMissed(Arc<dyn Fn(DeadlineMissedStatus) + Send + Sync>),
+    RequestedDeadlineMissed(Arc<dyn Fn(DeadlineMissedStatus) + Send + Sync>),
+    LivelinessLost(Arc<dyn Fn(LivelinessLostStatus) + Send + Sync>),
+    LivelinessChanged(Arc<dyn Fn(LivelinessChangedStatus) + Send + Sync>),
+    IncompatibleQos(Arc<dyn Fn(IncompatibleQosStatus) + Send + Sync>),
+    MessageLost(Arc<dyn Fn(MessageLostStatus) + Send + Sync>),
+}
+
+enum EventOwner {
+    Publisher(rcl_publisher_t),
+    Subscription(rcl_subscription_t),
+}
+
+/// A QoS event of a publisher or subscription, part of the node's wait set.
+///
+/// The event must be destroyed before its publisher or subscription.
+pub(crate) struct QosEvent_ {
+    handle: rcl_event_t,
+    owner: EventOwner,
+    callback: EventCallback,
+}
+
+fn take_status<S>(event: &rcl_event_t) -> Option<S> {
+    // the status types are plain C structs, so zeroed memory is a valid value.
+    let mut status = MaybeUninit::<S>::zeroed();
+    let ret = unsafe { rcl_take_event(event, status.as_mut_ptr() as *mut c_void) };
+    (ret == RCL_RET_OK as i32).then(|| unsafe { status.assume_init() })
+}
+
+impl QosEvent_ {
+    pub(crate) fn handle(&self) -> &rcl_event_t {
+        &self.handle
+    }
+
+    /// Called from spin when the event is ready.
+    pub(crate) fn handle_event(&self) {
+        match &self.callback {
+            EventCallback::OfferedDeadlineMissed(cb) => {
+                if let Some(s) = take_status::<rmw_offered_deadline_missed_status_t>(&self.handle) {
+                    cb(s.into());
+                }
+            }
+            EventCallback::RequestedDeadlineMissed(cb) => {
+                if let Some(s) = take_status::<rmw_requested_deadline_missed_status_t>(&self.handle)
+                {
+                    cb(s.into());
+                }
+            }
+            EventCallback::LivelinessLost(cb) => {
+                if let Some(s) = take_status::<rmw_liveliness_lost_status_t>(&self.handle) {
+                    cb(s.into());
+                }
+            }
+            EventCallback::LivelinessChanged(cb) => {
+                if let Some(s) = take_status::<rmw_liveliness_changed_status_t>(&self.handle) {
+                    cb(s.into());
+                }
+            }
+            EventCallback::IncompatibleQos(cb) => {
+                if let Some(s) = take_status::<rmw_qos_incompatible_event_status_t>(&self.handle) {
+                    cb(s.into());
+                }
+            }
+            EventCallback::MessageLost(cb) => {
+                if let Some(s) = take_status::<rmw_message_lost_status_t>(&self.handle) {
+                    cb(s.into());
+                }
+            }
+        }
+    }
+
+    pub(crate) fn destroy(&mut self) {
+        unsafe {
+            rcl_event_fini(&mut self.handle);
+        }
+    }
+}
+
+fn publisher_event(
+    publisher: &rcl_publisher_t, event_type: rcl_publisher_event_type_t, callback: EventCallback,
+) -> Result<QosEvent_> {
+    let mut handle = unsafe { rcl_get_zero_initialized_event() };
+    let result = unsafe { rcl_publisher_event_init(&mut handle, publisher, event_type) };
+    if result == RCL_RET_OK as i32 {
+        Ok(QosEvent_ {
+            handle,
+            owner: EventOwner::Publisher(*publisher),
+            callback,
+        })
+    } else {
+        Err(Error::from_rcl_error(result))
+    }
+}
+
+fn subscription_event(
+    subscription: &rcl_subscription_t, event_type: rcl_subscription_event_type_t,
+    callback: EventCallback,
+) -> Result<QosEvent_> {
+    let mut handle = unsafe { rcl_get_zero_initialized_event() };
+    let result = unsafe { rcl_subscription_event_init(&mut handle, subscription, event_type) };
+    if result == RCL_RET_OK as i32 {
+        Ok(QosEvent_ {
+            handle,
+            owner: EventOwner::Subscription(*subscription),
+            callback,
+        })
+    } else {
+        Err(Error::from_rcl_error(result))
+    }
+}
+
+/// Create all events or none of them.
+fn collect_events(events: Vec<Result<QosEvent_>>) -> Result<Vec<QosEvent_>> {
+    let mut created = Vec::with_capacity(events.len());
+    for event in events {
+        match event {
+            Ok(event) => created.push(event),
+            Err(e) => {
+                created.iter_mut().for_each(QosEvent_::destroy);
+                return Err(e);
+            }
+        }
+    }
+    Ok(created)
+}
+
+pub(crate) fn create_publisher_events(
+    publisher: &rcl_publisher_t, options: &PublisherOptions,
+) -> Result<Vec<QosEvent_>> {
+    use rcl_publisher_event_type_t::*;
+    let mut events = vec![];
+    if let Some(cb) = &options.on_deadline_missed {
+        events.push(publisher_event(
+            publisher,
+            RCL_PUBLISHER_OFFERED_DEADLINE_MISSED,
+            EventCallback::OfferedDeadlineMissed(cb.clone()),
+        ));
+    }
+    if let Some(cb) = &options.on_liveliness_lost {
+        events.push(publisher_event(
+            publisher,
+            RCL_PUBLISHER_LIVELINESS_LOST,
+            EventCallback::LivelinessLost(cb.clone()),
+        ));
+    }
+    if let Some(cb) = &options.on_incompatible_qos {
+        events.push(publisher_event(
+            publisher,
+            RCL_PUBLISHER_OFFERED_INCOMPATIBLE_QOS,
+            EventCallback::IncompatibleQos(cb.clone()),
+        ));
+    }
+    collect_events(events)
+}
+
+pub(crate) fn create_subscription_events(
+    subscription: &rcl_subscription_t, options: &SubscriptionOptions,
+) -> Result<Vec<QosEvent_>> {
+    use rcl_subscription_event_type_t::*;
+    let mut events = vec![];
+    if let Some(cb) = &options.on_deadline_missed {
+        events.push(subscription_event(
+            subscription,
+            RCL_SUBSCRIPTION_REQUESTED_DEADLINE_MISSED,
+            EventCallback::RequestedDeadlineMissed(cb.clone()),
+        ));
+    }
+    if let Some(cb) = &options.on_liveliness_changed {
+        events.push(subscription_event(
+            subscription,
+            RCL_SUBSCRIPTION_LIVELINESS_CHANGED,
+            EventCallback::LivelinessChanged(cb.clone()),
+        ));
+    }
+    if let Some(cb) = &options.on_incompatible_qos {
+        events.push(subscription_event(
+            subscription,
+            RCL_SUBSCRIPTION_REQUESTED_INCOMPATIBLE_QOS,
+            EventCallback::IncompatibleQos(cb.clone()),
+        ));
+    }
+    if let Some(cb) = &options.on_message_lost {
+        events.push(subscription_event(
+            subscription,
+            RCL_SUBSCRIPTION_MESSAGE_LOST,
+            EventCallback::MessageLost(cb.clone()),
+        ));
+    }
+    collect_events(events)
+}
+
+/// Destroy and remove the events of a publisher, before it is destroyed itself.
+pub(crate) fn destroy_publisher_events(events: &mut Vec<QosEvent_>, publisher: &rcl_publisher_t) {
+    events.retain_mut(|e| match &e.owner {
+        EventOwner::Publisher(p) if p == publisher => {
+            e.destroy();
+            false
+        }
+        _ => true,
+    });
+}
+
+/// Destroy and remove the events of a subscription, before it is destroyed itself.
+pub(crate) fn destroy_subscription_events(
+    events: &mut Vec<QosEvent_>, subscription: &rcl_subscription_t,
+) {
+    events.retain_mut(|e| match &e.owner {
+        EventOwner::Subscription(s) if s == subscription => {
+            e.destroy();
+            false
+        }
+        _ => true,
+    });
+}
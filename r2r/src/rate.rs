@@ -0,0 +1,155 @@
+use futures::{channel::oneshot, future::Future};
+use std::{
+    sync::{Condvar, Mutex, Once},
+    time::{Duration, Instant},
+};
+
+// The deadlines of `sleep_until`, all served by a single thread.
+struct Sleepers {
+    sleepers: Mutex<Vec<(Instant, oneshot::Sender<()>)>>,
+    changed: Condvar,
+}
+
+static SLEEPERS: Sleepers = Sleepers {
+    sleepers: Mutex::new(Vec::new()),
+    changed: Condvar::new(),
+};
+
+static SLEEP_THREAD: Once = Once::new();
+
+impl Sleepers {
+    fn run(&self) {
+        let mut sleepers = self.sleepers.lock().unwrap();
+        loop {
+            let now = Instant::now();
+            let mut i = 0;
+            while i < sleepers.len() {
+                // also drop the sleeps no one waits for anymore.
+                if sleepers[i].0 <= now || sleepers[i].1.is_canceled() {
+                    let (_, sender) = sleepers.swap_remove(i);
+                    let _ = sender.send(());
+                } else {
+                    i += 1;
+                }
+            }
+            sleepers = match sleepers.iter().map(|(deadline, _)| *deadline).min() {
+                Some(next) => self.changed.wait_timeout(sleepers, next - now).unwrap().0,
+                None => self.changed.wait(sleepers).unwrap(),
+            };
+        }
+    }
+}
+
+/// Completes at `deadline`, without depending on any particular async runtime.
+///
+/// The sleeps of the whole process share one thread.
+pub(crate) fn sleep_until(deadline: Instant) -> oneshot::Receiver<()> {
+    SLEEP_THREAD.call_once(|| {
+        std::thread::Builder::new()
+            .name("r2r-sleep".into())
+            .spawn(|| SLEEPERS.run())
+            .expect("could not spawn the sleep thread");
+    });
+    let (sender, receiver) = oneshot::channel();
+    SLEEPERS.sleepers.lock().unwrap().push((deadline, sender));
+    SLEEPERS.changed.notify_one();
+    receiver
+}
+
+/// Timing information returned by [`Rate::sleep`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SleepResult {
+    /// The instant the sleep was scheduled to end.
+    pub scheduled: Instant,
+    /// The instant the sleep actually ended.
+    pub actual: Instant,
+    /// Number of deadlines missed since the rate was created or last reset.
+    pub missed_deadlines: u64,
+}
+
+/// Helper for running a loop at a fixed frequency.
+///
+/// Unlike timers created by the node, a `Rate` does not need to be spun
+/// and does not depend on any particular async runtime. The wall clock is
+/// always used, and the sleeps of all rates are served by one thread
+/// started on first use.
+///
+/// ```
+/// let mut rate = r2r::Rate::new(100.0);
+/// futures::executor::block_on(async {
+///     for _ in 0..10 {
+///         // do work...
+///         rate.sleep().await;
+///     }
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct Rate {
+    period: Duration,
+    next: Instant,
+    missed_deadlines: u64,
+}
+
+impl Rate {
+    /// Create a new rate running at `hz` iterations per second.
+    ///
+    /// Panics if `hz` is not positive.
+    pub fn new(hz: f64) -> Self {
+        assert!(hz > 0.0, "rate must be positive");
+        Self::from_period(Duration::from_secs_f64(1.0 / hz))
+    }
+
+    /// Create a new rate with the given period.
+    pub fn from_period(period: Duration) -> Self {
+        Rate {
+            period,
+            next: Instant::now() + period,
+            missed_deadlines: 0,
+        }
+    }
+
+    /// The period of the rate.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Sleep until the end of the current period.
+    ///
+    /// The time spent since the previous call is compensated for. If the
+    /// deadline has already passed the future completes immediately and the
+    /// missed deadline is counted. If more than one full period was missed,
+    /// the schedule is restarted from now rather than trying to catch up.
+    pub fn sleep(&mut self) -> impl Future<Output = SleepResult> {
+        let now = Instant::now();
+        let scheduled = self.next;
+
+        if now > scheduled {
+            self.missed_deadlines += 1;
+        }
+        self.next = if now > scheduled + self.period {
+            now + self.period
+        } else {
+            scheduled + self.period
+        };
+        let missed_deadlines = self.missed_deadlines;
+
+        let receiver = (now < scheduled).then(|| sleep_until(scheduled));
+
+        async move {
+            if let Some(receiver) = receiver {
+                let _ = receiver.await;
+            }
+            SleepResult {
+                scheduled,
+                actual: Instant::now(),
+                missed_deadlines,
+            }
+        }
+    }
+
+    /// Restart the schedule from now and clear the missed deadline count.
+    pub fn reset(&mut self) {
+        self.next = Instant::now() + self.period;
+        self.missed_deadlines = 0;
+    }
+}
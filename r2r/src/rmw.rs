@@ -0,0 +1,140 @@
+//! Information about the ROS middleware (RMW) implementation in use.
+
+use std::{ffi::CStr, fmt};
+
+use r2r_rcl::*;
+
+/// Optional middleware features, which not all RMW implementations support.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RmwFeature {
+    /// Subscriptions filtering messages on the publisher side (Humble and later).
+    ContentFilteredTopics,
+    /// Publishing and taking messages without copying them.
+    LoanedMessages,
+    /// Unique network flow endpoints for publishers and subscriptions.
+    UniqueNetworkFlowEndpoints,
+    /// The message lost event on subscriptions.
+    MessageLostStatus,
+    /// The matched event on publishers and subscriptions (Iron and later).
+    MatchedStatus,
+    /// The publication sequence number in the message info (Iron and later).
+    PublicationSequenceNumbers,
+    /// The reception sequence number in the message info (Iron and later).
+    ReceptionSequenceNumbers,
+}
+
+impl RmwFeature {
+    /// All features, e.g. for listing what the middleware supports.
+    pub const ALL: [RmwFeature; 7] = [
+        RmwFeature::ContentFilteredTopics,
+        RmwFeature::LoanedMessages,
+        RmwFeature::UniqueNetworkFlowEndpoints,
+        RmwFeature::MessageLostStatus,
+        RmwFeature::MatchedStatus,
+        RmwFeature::PublicationSequenceNumbers,
+        RmwFeature::ReceptionSequenceNumbers,
+    ];
+}
+
+impl fmt::Display for RmwFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            RmwFeature::ContentFilteredTopics => "content filtered topics",
+            RmwFeature::LoanedMessages => "loaned messages",
+            RmwFeature::UniqueNetworkFlowEndpoints => "unique network flow endpoints",
+            RmwFeature::MessageLostStatus => "message lost status",
+            RmwFeature::MatchedStatus => "matched status",
+            RmwFeature::PublicationSequenceNumbers => "publication sequence numbers",
+            RmwFeature::ReceptionSequenceNumbers => "reception sequence numbers",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The name of the RMW implementation, e.g. `"rmw_fastrtps_cpp"`.
+///
+/// This is the implementation r2r is running on, selected at
+/// runtime with the `RMW_IMPLEMENTATION` environment variable.
+pub fn get_implementation_identifier() -> &'static str {
+    let id = unsafe { rmw_get_implementation_identifier() };
+    if id.is_null() {
+        return "";
+    }
+    unsafe { CStr::from_ptr(id) }.to_str().unwrap_or("")
+}
+
+/// Check if the RMW implementation in use supports `feature`.
+///
+/// The sequence numbers are queried with `rmw_feature_supported`, which
+/// only exists since Iron. rmw has no query for the other features, so the
+/// answer is based on the known capabilities of the implementation and the
+/// ROS distribution, and unknown implementations are assumed to support
+/// none of them. Note that some features also depend on the configuration
+/// of the middleware, e.g. loaned messages in Cyclone DDS require shared
+/// memory to be enabled.
+pub fn is_feature_supported(feature: RmwFeature) -> bool {
+    #[cfg(any(
+        r2r__ros__distro__iron,
+        r2r__ros__distro__jazzy,
+        r2r__ros__distro__rolling
+    ))]
+    if let Some(feature) = rmw_feature(feature) {
+        return unsafe { rmw_feature_supported(feature) };
+    }
+    feature_supported_by(get_implementation_identifier(), feature)
+}
+
+// the features rmw can be asked about.
+#[cfg(any(
+    r2r__ros__distro__iron,
+    r2r__ros__distro__jazzy,
+    r2r__ros__distro__rolling
+))]
+fn rmw_feature(feature: RmwFeature) -> Option<rmw_feature_t> {
+    match feature {
+        RmwFeature::PublicationSequenceNumbers => {
+            Some(rmw_feature_t::RMW_FEATURE_MESSAGE_INFO_PUBLICATION_SEQUENCE_NUMBER)
+        }
+        RmwFeature::ReceptionSequenceNumbers => {
+            Some(rmw_feature_t::RMW_FEATURE_MESSAGE_INFO_RECEPTION_SEQUENCE_NUMBER)
+        }
+        _ => None,
+    }
+}
+
+fn feature_supported_by(identifier: &str, feature: RmwFeature) -> bool {
+    let fastrtps = identifier.starts_with("rmw_fastrtps");
+    let cyclonedds = identifier == "rmw_cyclonedds_cpp";
+    let connextdds = identifier.starts_with("rmw_connextdds");
+    match feature {
+        RmwFeature::ContentFilteredTopics => {
+            cfg!(not(any(r2r__ros__distro__foxy, r2r__ros__distro__galactic)))
+                && (fastrtps || connextdds)
+        }
+        RmwFeature::LoanedMessages => fastrtps || cyclonedds,
+        RmwFeature::UniqueNetworkFlowEndpoints => {
+            cfg!(not(r2r__ros__distro__foxy)) && (fastrtps || connextdds)
+        }
+        RmwFeature::MessageLostStatus => {
+            cfg!(not(r2r__ros__distro__foxy)) && (fastrtps || cyclonedds || connextdds)
+        }
+        RmwFeature::MatchedStatus => {
+            cfg!(any(r2r__ros__distro__iron, r2r__ros__distro__jazzy, r2r__ros__distro__rolling))
+                && (fastrtps || cyclonedds || connextdds)
+        }
+        // asked to rmw where it knows about them.
+        RmwFeature::PublicationSequenceNumbers | RmwFeature::ReceptionSequenceNumbers => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_implementation_supports_nothing() {
+        for feature in RmwFeature::ALL {
+            assert!(!feature_supported_by("rmw_unknown", feature), "{feature}");
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use futures::channel::mpsc;
+use indexmap::IndexMap;
+use std::{collections::VecDeque, time::Instant};
+
+use crate::{
+    error::*,
+    msg_types::*,
+    qos::QosProfile,
+    subscribers::{create_subscription_helper, Subscriber_},
+};
+use r2r_rcl::*;
+
+/// A request and its response, observed on the service event topic.
+///
+/// See [`Node::create_service_spy`](crate::Node::create_service_spy).
+#[derive(Debug, Clone)]
+pub struct ServiceInteraction<T>
+where
+    T: WrappedServiceTypeSupport,
+{
+    pub request: T::Request,
+    /// The response, `None` if the server was not seen responding.
+    pub response: Option<T::Response>,
+    /// When the spy received the request event.
+    pub request_time: Instant,
+    /// When the spy received the response event.
+    pub response_time: Option<Instant>,
+}
+
+// the event types of service_msgs/msg/ServiceEventInfo.
+const REQUEST_SENT: u8 = 0;
+const REQUEST_RECEIVED: u8 = 1;
+const RESPONSE_SENT: u8 = 2;
+const RESPONSE_RECEIVED: u8 = 3;
+
+// requests without a response are given up on after this many newer ones.
+const PENDING_LIMIT: usize = 64;
+
+// the C layout of the generated `<Service>_Event` messages, which r2r does
+// not generate rust types for.
+#[repr(C)]
+#[allow(dead_code)]
+struct EventTime {
+    sec: i32,
+    nanosec: u32,
+}
+
+#[repr(C)]
+#[allow(dead_code)]
+struct EventInfo {
+    event_type: u8,
+    stamp: EventTime,
+    client_gid: [u8; 16],
+    sequence_number: i64,
+}
+
+#[repr(C)]
+#[allow(dead_code)]
+struct EventSequence<C> {
+    data: *mut C,
+    size: usize,
+    capacity: usize,
+}
+
+impl<C> EventSequence<C> {
+    // the sequences hold at most one message.
+    unsafe fn first(&self) -> Option<&C> {
+        if self.data.is_null() || self.size == 0 {
+            None
+        } else {
+            Some(&*self.data)
+        }
+    }
+}
+
+#[repr(C)]
+struct EventMsg<Req, Resp> {
+    info: EventInfo,
+    request: EventSequence<Req>,
+    response: EventSequence<Resp>,
+}
+
+/// The type support of the event messages of a service.
+#[cfg(any(
+    r2r__ros__distro__iron,
+    r2r__ros__distro__jazzy,
+    r2r__ros__distro__rolling
+))]
+fn event_type_support(
+    ts: &rosidl_service_type_support_t,
+) -> Result<*const rosidl_message_type_support_t> {
+    if ts.event_typesupport.is_null() {
+        return Err(Error::UnsupportedFeature("service_introspection"));
+    }
+    Ok(ts.event_typesupport)
+}
+
+#[cfg(not(any(
+    r2r__ros__distro__iron,
+    r2r__ros__distro__jazzy,
+    r2r__ros__distro__rolling
+)))]
+fn event_type_support(
+    _ts: &rosidl_service_type_support_t,
+) -> Result<*const rosidl_message_type_support_t> {
+    Err(Error::UnsupportedFeature("service_introspection"))
+}
+
+#[cfg(any(
+    r2r__ros__distro__iron,
+    r2r__ros__distro__jazzy,
+    r2r__ros__distro__rolling
+))]
+fn create_event(ts: &rosidl_service_type_support_t) -> Result<*mut std::os::raw::c_void> {
+    let create = ts
+        .event_message_create_handle_function
+        .ok_or(Error::UnsupportedFeature("service_introspection"))?;
+    let msg = unsafe {
+        // without a request and response, only the info is copied.
+        let info: rosidl_service_introspection_info_t = std::mem::zeroed();
+        let mut allocator = rcutils_get_default_allocator();
+        create(&info, &mut allocator, std::ptr::null(), std::ptr::null())
+    };
+    if msg.is_null() {
+        return Err(Error::RCL_RET_BAD_ALLOC);
+    }
+    Ok(msg)
+}
+
+#[cfg(not(any(
+    r2r__ros__distro__iron,
+    r2r__ros__distro__jazzy,
+    r2r__ros__distro__rolling
+)))]
+fn create_event(_ts: &rosidl_service_type_support_t) -> Result<*mut std::os::raw::c_void> {
+    Err(Error::UnsupportedFeature("service_introspection"))
+}
+
+#[cfg(any(
+    r2r__ros__distro__iron,
+    r2r__ros__distro__jazzy,
+    r2r__ros__distro__rolling
+))]
+fn destroy_event(ts: &rosidl_service_type_support_t, msg: *mut std::os::raw::c_void) {
+    if let Some(destroy) = ts.event_message_destroy_handle_function {
+        unsafe {
+            let mut allocator = rcutils_get_default_allocator();
+            destroy(msg, &mut allocator);
+        }
+    }
+}
+
+#[cfg(not(any(
+    r2r__ros__distro__iron,
+    r2r__ros__distro__jazzy,
+    r2r__ros__distro__rolling
+)))]
+fn destroy_event(_ts: &rosidl_service_type_support_t, _msg: *mut std::os::raw::c_void) {}
+
+type RequestKey = ([u8; 16], i64);
+
+/// Pairs the request and response events of a service.
+///
+/// Both the client and the server may publish events for the same call, so
+/// a request or response is used once, whichever event arrives first.
+pub struct ServiceSpySubscriber<T>
+where
+    T: WrappedServiceTypeSupport,
+{
+    pub rcl_handle: rcl_subscription_t,
+    // the native event message, reused for every take.
+    event: *mut std::os::raw::c_void,
+    pending: IndexMap<RequestKey, (T::Request, Instant)>,
+    // recently answered requests, as the other side may still report them.
+    answered: VecDeque<RequestKey>,
+    sender: mpsc::Sender<ServiceInteraction<T>>,
+}
+
+impl<T> ServiceSpySubscriber<T>
+where
+    T: WrappedServiceTypeSupport,
+{
+    pub fn new(
+        node: &mut rcl_node_t, service_name: &str, sender: mpsc::Sender<ServiceInteraction<T>>,
+    ) -> Result<Self> {
+        let event_ts = event_type_support(T::get_ts())?;
+        let event = create_event(T::get_ts())?;
+        let topic = format!("{}/_service_event", service_name);
+        let rcl_handle =
+            match create_subscription_helper(node, &topic, event_ts, QosProfile::default()) {
+                Ok(handle) => handle,
+                Err(e) => {
+                    destroy_event(T::get_ts(), event);
+                    return Err(e);
+                }
+            };
+        Ok(ServiceSpySubscriber {
+            rcl_handle,
+            event,
+            pending: IndexMap::new(),
+            answered: VecDeque::new(),
+            sender,
+        })
+    }
+
+    fn send(&mut self, interaction: ServiceInteraction<T>) -> bool {
+        if let Err(e) = self.sender.try_send(interaction) {
+            if e.is_disconnected() {
+                return true;
+            }
+            log::debug!("error {:?}", e)
+        }
+        false
+    }
+}
+
+impl<T: 'static> Subscriber_ for ServiceSpySubscriber<T>
+where
+    T: WrappedServiceTypeSupport,
+{
+    fn handle(&self) -> &rcl_subscription_t {
+        &self.rcl_handle
+    }
+
+    fn handle_incoming(&mut self) -> bool {
+        let mut msg_info = rmw_message_info_t::default();
+        let ret =
+            unsafe { rcl_take(&self.rcl_handle, self.event, &mut msg_info, std::ptr::null_mut()) };
+        if ret != RCL_RET_OK as i32 {
+            return false;
+        }
+        let now = Instant::now();
+
+        let event = unsafe {
+            &*(self.event
+                as *const EventMsg<
+                    <T::Request as WrappedTypesupport>::CStruct,
+                    <T::Response as WrappedTypesupport>::CStruct,
+                >)
+        };
+        let key = (event.info.client_gid, event.info.sequence_number);
+        match event.info.event_type {
+            REQUEST_SENT | REQUEST_RECEIVED => {
+                let Some(request) = (unsafe { event.request.first() }) else {
+                    return false;
+                };
+                if self.pending.contains_key(&key) || self.answered.contains(&key) {
+                    return false;
+                }
+                let request = T::Request::from_native(request);
+                self.pending.insert(key, (request, now));
+                if self.pending.len() > PENDING_LIMIT {
+                    let (_, (request, request_time)) = self.pending.shift_remove_index(0).unwrap();
+                    return self.send(ServiceInteraction {
+                        request,
+                        response: None,
+                        request_time,
+                        response_time: None,
+                    });
+                }
+                false
+            }
+            RESPONSE_SENT | RESPONSE_RECEIVED => {
+                let Some(response) = (unsafe { event.response.first() }) else {
+                    return false;
+                };
+                let Some((request, request_time)) = self.pending.shift_remove(&key) else {
+                    return false;
+                };
+                self.answered.push_back(key);
+                if self.answered.len() > PENDING_LIMIT {
+                    self.answered.pop_front();
+                }
+                self.send(ServiceInteraction {
+                    request,
+                    response: Some(T::Response::from_native(response)),
+                    request_time,
+                    response_time: Some(now),
+                })
+            }
+            _ => false,
+        }
+    }
+
+    fn destroy(&mut self, node: &mut rcl_node_t) {
+        unsafe {
+            rcl_subscription_fini(&mut self.rcl_handle, node);
+        }
+        destroy_event(T::get_ts(), self.event);
+    }
+}
@@ -8,6 +8,50 @@ use std::{
 use crate::{error::*, msg_types::*, QosProfile};
 use r2r_rcl::*;
 
+/// What to publish on the service event topic `<service>/_service_event`.
+///
+/// Service introspection requires ROS 2 Iron or later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ServiceIntrospectionState {
+    /// Nothing is published.
+    #[default]
+    Disabled,
+    /// Only metadata, e.g. timestamps and sequence numbers, is published.
+    Metadata,
+    /// Metadata and the request and response messages are published.
+    Contents,
+}
+
+/// Options used when creating a service or a client.
+///
+/// See [`Node::create_service_with_options`](crate::Node::create_service_with_options)
+/// and [`Node::create_client_with_options`](crate::Node::create_client_with_options).
+#[derive(Debug, Clone, Default)]
+pub struct ServiceOptions {
+    pub introspection: ServiceIntrospectionState,
+}
+
+#[cfg(any(
+    r2r__ros__distro__iron,
+    r2r__ros__distro__jazzy,
+    r2r__ros__distro__rolling
+))]
+impl From<ServiceIntrospectionState> for rcl_service_introspection_state_t {
+    fn from(state: ServiceIntrospectionState) -> Self {
+        match state {
+            ServiceIntrospectionState::Disabled => {
+                rcl_service_introspection_state_t::RCL_SERVICE_INTROSPECTION_OFF
+            }
+            ServiceIntrospectionState::Metadata => {
+                rcl_service_introspection_state_t::RCL_SERVICE_INTROSPECTION_METADATA
+            }
+            ServiceIntrospectionState::Contents => {
+                rcl_service_introspection_state_t::RCL_SERVICE_INTROSPECTION_CONTENTS
+            }
+        }
+    }
+}
+
 /// Encapsulates a service request.
 ///
 /// In contrast to having a callback from Request -> Response
@@ -31,6 +75,19 @@ impl<T> ServiceRequest<T>
 where
     T: 'static + WrappedServiceTypeSupport,
 {
+    /// The sequence number the client assigned to the request.
+    pub fn sequence_number(&self) -> i64 {
+        self.request_id.sequence_number
+    }
+
+    /// Globally unique identifier of the client that sent the request.
+    ///
+    /// Together with the sequence number this identifies the request, e.g.
+    /// to detect duplicated requests.
+    pub fn client_gid(&self) -> [u8; 16] {
+        self.request_id.writer_guid.map(|b| b as u8)
+    }
+
     /// Complete the service request, consuming the request in the process.
     pub fn respond(self, msg: T::Response) -> Result<()> {
         let service = self
@@ -51,6 +108,43 @@ pub trait Service_ {
     fn destroy(&mut self, node: &mut rcl_node_t);
 }
 
+/// Send the response to a request taken from `handle`.
+pub(crate) fn send_response(
+    handle: &rcl_service_t, mut request_id: rmw_request_id_t, mut msg: Box<dyn VoidPtr>,
+) -> Result<()> {
+    let res = unsafe { rcl_send_response(handle, &mut request_id, msg.void_ptr_mut()) };
+    if res == RCL_RET_OK as i32 {
+        Ok(())
+    } else {
+        Err(Error::from_rcl_error(res))
+    }
+}
+
+/// Take a request from `handle`, to be answered through `service`.
+pub(crate) fn take_request<T>(
+    handle: &rcl_service_t, service: &Arc<Mutex<dyn Service_>>,
+) -> Option<ServiceRequest<T>>
+where
+    T: WrappedServiceTypeSupport + 'static,
+{
+    let mut request_id = MaybeUninit::<rmw_request_id_t>::uninit();
+    let mut request_msg = WrappedNativeMsg::<T::Request>::new();
+
+    let ret =
+        unsafe { rcl_take_request(handle, request_id.as_mut_ptr(), request_msg.void_ptr_mut()) };
+    if ret == RCL_RET_OK as i32 {
+        let request_id = unsafe { request_id.assume_init() };
+        Some(ServiceRequest::<T> {
+            message: T::Request::from_native(&request_msg),
+            request_id,
+            service: Arc::downgrade(service),
+        })
+    } else {
+        // TODO handle failure.
+        None
+    }
+}
+
 pub struct TypedService<T>
 where
     T: WrappedServiceTypeSupport,
@@ -67,40 +161,19 @@ where
         &self.rcl_handle
     }
 
-    fn send_response(
-        &mut self, mut request_id: rmw_request_id_t, mut msg: Box<dyn VoidPtr>,
-    ) -> Result<()> {
-        let res =
-            unsafe { rcl_send_response(&self.rcl_handle, &mut request_id, msg.void_ptr_mut()) };
-        if res == RCL_RET_OK as i32 {
-            Ok(())
-        } else {
-            Err(Error::from_rcl_error(res))
-        }
+    fn send_response(&mut self, request_id: rmw_request_id_t, msg: Box<dyn VoidPtr>) -> Result<()> {
+        send_response(&self.rcl_handle, request_id, msg)
     }
 
     fn handle_request(&mut self, service: Arc<Mutex<dyn Service_>>) -> bool {
-        let mut request_id = MaybeUninit::<rmw_request_id_t>::uninit();
-        let mut request_msg = WrappedNativeMsg::<T::Request>::new();
-
-        let ret = unsafe {
-            rcl_take_request(&self.rcl_handle, request_id.as_mut_ptr(), request_msg.void_ptr_mut())
-        };
-        if ret == RCL_RET_OK as i32 {
-            let request_id = unsafe { request_id.assume_init() };
-            let request_msg = T::Request::from_native(&request_msg);
-            let request = ServiceRequest::<T> {
-                message: request_msg,
-                request_id,
-                service: Arc::downgrade(&service),
-            };
+        if let Some(request) = take_request::<T>(&self.rcl_handle, &service) {
             if let Err(e) = self.sender.try_send(request) {
                 if e.is_disconnected() {
                     return true;
                 }
                 log::error!("warning: could not send service request ({})", e)
             }
-        } // TODO handle failure.
+        }
         false
     }
 
@@ -111,6 +184,47 @@ where
     }
 }
 
+/// Publish service events of `service` on `<service>/_service_event`.
+#[cfg(any(
+    r2r__ros__distro__iron,
+    r2r__ros__distro__jazzy,
+    r2r__ros__distro__rolling
+))]
+pub fn configure_service_introspection(
+    service: &mut rcl_service_t, node: &mut rcl_node_t, clock: &mut rcl_clock_t,
+    service_ts: *const rosidl_service_type_support_t, state: ServiceIntrospectionState,
+) -> Result<()> {
+    let result = unsafe {
+        let mut publisher_options = rcl_publisher_get_default_options();
+        publisher_options.qos = QosProfile::system_default().into();
+        rcl_service_configure_service_introspection(
+            service,
+            node,
+            clock,
+            service_ts,
+            publisher_options,
+            state.into(),
+        )
+    };
+    if result == RCL_RET_OK as i32 {
+        Ok(())
+    } else {
+        Err(Error::from_rcl_error(result))
+    }
+}
+
+#[cfg(not(any(
+    r2r__ros__distro__iron,
+    r2r__ros__distro__jazzy,
+    r2r__ros__distro__rolling
+)))]
+pub fn configure_service_introspection(
+    _service: &mut rcl_service_t, _node: &mut rcl_node_t, _clock: &mut rcl_clock_t,
+    _service_ts: *const rosidl_service_type_support_t, _state: ServiceIntrospectionState,
+) -> Result<()> {
+    Err(Error::UnsupportedFeature("service_introspection"))
+}
+
 pub fn create_service_helper(
     node: &mut rcl_node_t, service_name: &str, service_ts: *const rosidl_service_type_support_t,
     qos_profile: QosProfile,
@@ -133,6 +247,6 @@ pub fn create_service_helper(
     if result == RCL_RET_OK as i32 {
         Ok(service_handle)
     } else {
-        Err(Error::from_rcl_error(result))
+        Err(Error::from_rcl_call("rcl_service_init", result))
     }
 }
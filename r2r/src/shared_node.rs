@@ -0,0 +1,310 @@
+use futures::{future::Future, stream::Stream};
+use std::{
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use crate::{
+    action_clients::ActionClient,
+    action_clients_untyped::ActionClientUntyped,
+    action_servers::ActionServerGoalRequest,
+    clients::{Client, ClientUntyped},
+    error::*,
+    guard_conditions::GuardCondition,
+    msg_types::*,
+    nodes::{Node, Timer},
+    publishers::{Publisher, PublisherOptions, PublisherUntyped},
+    qos::QosProfile,
+    services::{ServiceOptions, ServiceRequest},
+    subscribers::{
+        BoundedSubscription, DropCounter, MessageInfo, Subscription, SubscriptionOptions,
+    },
+};
+
+/// A [`Node`] that can be shared between tasks.
+///
+/// Cloning a `SharedNode` gives another handle to the same node, and the
+/// methods lock the node for the duration of the call. This avoids passing
+/// `&mut Node` around when several tasks create e.g. publishers.
+///
+/// The methods creating publishers, subscriptions, services, clients, action
+/// servers and clients, timers and guard conditions are forwarded. Not
+/// forwarded are the less common variants of these, e.g. the service
+/// forwarders and spies, and the methods for parameters, the graph, logging
+/// and destroying entities. Use [`SharedNode::lock`] for them.
+///
+/// ``` rust,ignore
+/// let node = SharedNode::from(r2r::Node::create(ctx, "node", "")?);
+/// let task_node = node.clone();
+/// spawner.spawn_local(async move {
+///     let publisher = task_node.create_publisher::<std_msgs::msg::String>("/topic", qos)?;
+///     // ...
+/// })?;
+/// loop {
+///     node.spin_once(std::time::Duration::from_millis(100));
+/// }
+/// ```
+#[derive(Clone)]
+pub struct SharedNode(Arc<Mutex<Node>>);
+
+impl From<Node> for SharedNode {
+    fn from(node: Node) -> Self {
+        SharedNode(Arc::new(Mutex::new(node)))
+    }
+}
+
+impl From<Arc<Mutex<Node>>> for SharedNode {
+    fn from(node: Arc<Mutex<Node>>) -> Self {
+        SharedNode(node)
+    }
+}
+
+impl SharedNode {
+    /// Lock the node, e.g. to call methods not wrapped by `SharedNode`.
+    pub fn lock(&self) -> MutexGuard<'_, Node> {
+        self.0.lock().unwrap()
+    }
+
+    /// The shared node, e.g. for APIs taking `Arc<Mutex<Node>>`.
+    pub fn inner(&self) -> &Arc<Mutex<Node>> {
+        &self.0
+    }
+
+    /// See [`Node::spin_once`].
+    pub fn spin_once(&self, timeout: Duration) {
+        self.lock().spin_once(timeout)
+    }
+
+    /// See [`Node::create_publisher`].
+    pub fn create_publisher<T>(&self, topic: &str, qos_profile: QosProfile) -> Result<Publisher<T>>
+    where
+        T: WrappedTypesupport,
+    {
+        self.lock().create_publisher(topic, qos_profile)
+    }
+
+    /// See [`Node::create_publisher_with_options`].
+    pub fn create_publisher_with_options<T>(
+        &self, topic: &str, qos_profile: QosProfile, options: PublisherOptions,
+    ) -> Result<Publisher<T>>
+    where
+        T: WrappedTypesupport,
+    {
+        self.lock()
+            .create_publisher_with_options(topic, qos_profile, options)
+    }
+
+    /// See [`Node::create_publisher_latched`].
+    pub fn create_publisher_latched<T>(&self, topic: &str, depth: usize) -> Result<Publisher<T>>
+    where
+        T: WrappedTypesupport,
+    {
+        self.lock().create_publisher_latched(topic, depth)
+    }
+
+    /// See [`Node::create_publisher_untyped`].
+    pub fn create_publisher_untyped(
+        &self, topic: &str, topic_type: &str, qos_profile: QosProfile,
+    ) -> Result<PublisherUntyped> {
+        self.lock()
+            .create_publisher_untyped(topic, topic_type, qos_profile)
+    }
+
+    /// See [`Node::subscribe`].
+    pub fn subscribe<T>(&self, topic: &str, qos_profile: QosProfile) -> Result<Subscription<T>>
+    where
+        T: WrappedTypesupport + 'static,
+    {
+        self.lock().subscribe(topic, qos_profile)
+    }
+
+    /// See [`Node::create_subscription_with_options`].
+    pub fn create_subscription_with_options<T>(
+        &self, topic: &str, qos_profile: QosProfile, options: SubscriptionOptions,
+    ) -> Result<Subscription<T>>
+    where
+        T: WrappedTypesupport + 'static,
+    {
+        self.lock()
+            .create_subscription_with_options(topic, qos_profile, options)
+    }
+
+    /// See [`Node::create_bounded_subscription`].
+    pub fn create_bounded_subscription<T>(
+        &self, topic: &str, qos_depth: usize, channel_bound: usize,
+    ) -> Result<(BoundedSubscription<T>, DropCounter)>
+    where
+        T: WrappedTypesupport + 'static,
+    {
+        self.lock()
+            .create_bounded_subscription(topic, qos_depth, channel_bound)
+    }
+
+    /// See [`Node::create_batched_subscription`].
+    pub fn create_batched_subscription<T>(
+        &self, topic: &str, qos_profile: QosProfile, max_batch: usize,
+    ) -> Result<impl Stream<Item = Vec<T>> + Unpin>
+    where
+        T: WrappedTypesupport + 'static,
+    {
+        self.lock()
+            .create_batched_subscription(topic, qos_profile, max_batch)
+    }
+
+    /// See [`Node::subscribe_latched`].
+    pub fn subscribe_latched<T>(
+        &self, topic: &str, depth: usize,
+    ) -> Result<impl Stream<Item = T> + Unpin>
+    where
+        T: WrappedTypesupport + 'static,
+    {
+        self.lock().subscribe_latched(topic, depth)
+    }
+
+    /// See [`Node::subscribe_with_info`].
+    pub fn subscribe_with_info<T>(
+        &self, topic: &str, qos_profile: QosProfile,
+    ) -> Result<impl Stream<Item = (T, MessageInfo)> + Unpin>
+    where
+        T: WrappedTypesupport + 'static,
+    {
+        self.lock().subscribe_with_info(topic, qos_profile)
+    }
+
+    /// See [`Node::subscribe_untyped`].
+    pub fn subscribe_untyped(
+        &self, topic: &str, topic_type: &str, qos_profile: QosProfile,
+    ) -> Result<impl Stream<Item = Result<serde_json::Value>> + Unpin> {
+        self.lock()
+            .subscribe_untyped(topic, topic_type, qos_profile)
+    }
+
+    /// See [`Node::subscribe_raw`].
+    pub fn subscribe_raw(
+        &self, topic: &str, topic_type: &str, qos_profile: QosProfile,
+    ) -> Result<impl Stream<Item = Vec<u8>> + Unpin> {
+        self.lock().subscribe_raw(topic, topic_type, qos_profile)
+    }
+
+    /// See [`Node::create_service`].
+    pub fn create_service<T>(
+        &self, service_name: &str, qos_profile: QosProfile,
+    ) -> Result<impl Stream<Item = ServiceRequest<T>> + Unpin>
+    where
+        T: WrappedServiceTypeSupport + 'static,
+    {
+        self.lock().create_service(service_name, qos_profile)
+    }
+
+    /// See [`Node::create_service_with_options`].
+    pub fn create_service_with_options<T>(
+        &self, service_name: &str, qos_profile: QosProfile, options: ServiceOptions,
+    ) -> Result<impl Stream<Item = ServiceRequest<T>> + Unpin>
+    where
+        T: WrappedServiceTypeSupport + 'static,
+    {
+        self.lock()
+            .create_service_with_options(service_name, qos_profile, options)
+    }
+
+    /// See [`Node::create_async_service`].
+    pub fn create_async_service<T, F, Fut>(
+        &self, service_name: &str, qos_profile: QosProfile, handler: F,
+    ) -> Result<()>
+    where
+        T: WrappedServiceTypeSupport + 'static,
+        F: Fn(T::Request) -> Fut + Send + 'static,
+        Fut: Future<Output = T::Response> + Send + 'static,
+    {
+        self.lock()
+            .create_async_service::<T, F, Fut>(service_name, qos_profile, handler)
+    }
+
+    /// See [`Node::create_stateful_service`].
+    pub fn create_stateful_service<T, F, Fut>(
+        &self, service_name: &str, qos_profile: QosProfile, handler: F,
+    ) -> Result<()>
+    where
+        T: WrappedServiceTypeSupport + 'static,
+        F: Fn(ServiceRequest<T>) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.lock()
+            .create_stateful_service(service_name, qos_profile, handler)
+    }
+
+    /// See [`Node::create_client`].
+    pub fn create_client<T>(&self, service_name: &str, qos_profile: QosProfile) -> Result<Client<T>>
+    where
+        T: WrappedServiceTypeSupport + 'static,
+    {
+        self.lock().create_client(service_name, qos_profile)
+    }
+
+    /// See [`Node::create_client_with_options`].
+    pub fn create_client_with_options<T>(
+        &self, service_name: &str, qos_profile: QosProfile, options: ServiceOptions,
+    ) -> Result<Client<T>>
+    where
+        T: WrappedServiceTypeSupport + 'static,
+    {
+        self.lock()
+            .create_client_with_options(service_name, qos_profile, options)
+    }
+
+    /// See [`Node::create_client_untyped`].
+    pub fn create_client_untyped(
+        &self, service_name: &str, service_type: &str, qos_profile: QosProfile,
+    ) -> Result<ClientUntyped> {
+        self.lock()
+            .create_client_untyped(service_name, service_type, qos_profile)
+    }
+
+    /// See [`Node::create_action_client`].
+    pub fn create_action_client<T>(&self, action_name: &str) -> Result<ActionClient<T>>
+    where
+        T: WrappedActionTypeSupport + 'static,
+    {
+        self.lock().create_action_client(action_name)
+    }
+
+    /// See [`Node::create_action_client_untyped`].
+    pub fn create_action_client_untyped(
+        &self, action_name: &str, action_type: &str,
+    ) -> Result<ActionClientUntyped> {
+        self.lock()
+            .create_action_client_untyped(action_name, action_type)
+    }
+
+    /// See [`Node::create_action_server`].
+    pub fn create_action_server<T>(
+        &self, action_name: &str,
+    ) -> Result<impl Stream<Item = ActionServerGoalRequest<T>> + Unpin>
+    where
+        T: WrappedActionTypeSupport + 'static,
+    {
+        self.lock().create_action_server(action_name)
+    }
+
+    /// See [`Node::create_wall_timer`].
+    pub fn create_wall_timer(&self, period: Duration) -> Result<Timer> {
+        self.lock().create_wall_timer(period)
+    }
+
+    /// See [`Node::create_timer`].
+    pub fn create_timer(&self, period: Duration) -> Result<Timer> {
+        self.lock().create_timer(period)
+    }
+
+    /// See [`Node::create_one_shot_timer`].
+    pub fn create_one_shot_timer(
+        &self, duration: Duration,
+    ) -> Result<impl Future<Output = Result<Duration>>> {
+        self.lock().create_one_shot_timer(duration)
+    }
+
+    /// See [`Node::create_guard_condition`].
+    pub fn create_guard_condition(&self) -> Result<GuardCondition> {
+        self.lock().create_guard_condition()
+    }
+}
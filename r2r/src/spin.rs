@@ -0,0 +1,69 @@
+use futures::{executor::LocalPool, task::noop_waker_ref};
+use std::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use crate::{error::*, nodes::Node};
+
+// upper bound of each spin_once, so the future is polled regularly.
+pub(crate) const SPIN_PERIOD: Duration = Duration::from_millis(10);
+
+/// Spin `node` and run `pool` until `future` completes.
+///
+/// This replaces the common `loop { node.spin_once(..); pool.run_until_stalled(); }`
+/// when waiting for a single result, e.g. a service response. Returns
+/// [`Error::Timeout`] if `timeout` passes before the future completes. If the
+/// future panics, the panic is propagated to the caller.
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let ctx = r2r::Context::create()?;
+/// let mut node = r2r::Node::create(ctx, "node", "")?;
+/// let mut pool = futures::executor::LocalPool::new();
+/// let client = node.create_client::<r2r::test_msgs::srv::Empty::Service>(
+///     "/service",
+///     r2r::QosProfile::default(),
+/// )?;
+/// let available = r2r::Node::is_available(&client)?;
+/// r2r::spin_until_future_complete(&mut node, &mut pool, available, None)??;
+/// # Ok(())
+/// # }
+/// ```
+pub fn spin_until_future_complete<F: Future>(
+    node: &mut Node, pool: &mut LocalPool, future: F, timeout: Option<Duration>,
+) -> Result<F::Output> {
+    let deadline = timeout.map(|t| Instant::now() + t);
+    let mut future = pin!(future);
+    // the loop below polls the future after each spin, no need to be woken up.
+    let mut cx = Context::from_waker(noop_waker_ref());
+    loop {
+        // a panic of the future unwinds out of here, dropping the future.
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return Ok(output);
+        }
+
+        let spin_timeout = match deadline {
+            Some(deadline) => {
+                let now = Instant::now();
+                if now >= deadline {
+                    return Err(Error::Timeout);
+                }
+                SPIN_PERIOD.min(deadline - now)
+            }
+            None => SPIN_PERIOD,
+        };
+        node.spin_once(spin_timeout);
+        pool.run_until_stalled();
+    }
+}
+
+/// Process the work that is ready on `node`, waiting at most `max_duration` for it.
+///
+/// This is a single call to [`Node::spin_once`], e.g. for interleaving ROS
+/// processing with other work in a loop.
+pub fn spin_some(node: &mut Node, max_duration: Duration) {
+    node.spin_once(max_duration);
+}
@@ -1,9 +1,588 @@
-use futures::channel::mpsc;
+use futures::{channel::mpsc, task::AtomicWaker, Stream};
 use std::ffi::CString;
 
-use crate::{error::*, msg_types::*, qos::QosProfile};
+use crate::{
+    error::*,
+    gid::Gid,
+    msg_types::*,
+    network_flow::*,
+    nodes::{NodeGraphHandle, TopicEndpointInfo},
+    qos::QosProfile,
+    qos_events::{
+        DeadlineMissedStatus, IncompatibleQosStatus, LivelinessChangedStatus, MessageLostStatus,
+    },
+};
 use r2r_rcl::*;
-use std::ffi::{c_void, CStr};
+use std::{
+    collections::VecDeque,
+    ffi::{c_void, CStr},
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    task::Poll,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// A type erased ROS subscription.
+///
+/// The messages are received as serialized bytes, so subscriptions of
+/// different message types can be stored together, e.g. in a topic bridge.
+/// See [`Node::create_any_subscription`](crate::Node::create_any_subscription).
+pub struct AnySubscription {
+    type_name: String,
+    stream: Box<dyn Stream<Item = Vec<u8>> + Send + Unpin>,
+}
+
+impl AnySubscription {
+    pub(crate) fn new(
+        type_name: String, stream: Box<dyn Stream<Item = Vec<u8>> + Send + Unpin>,
+    ) -> Self {
+        AnySubscription { type_name, stream }
+    }
+
+    /// The message type of the subscription, e.g. `"std_msgs/msg/String"`.
+    pub fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    /// The stream of serialized messages.
+    pub fn into_stream_raw(self) -> impl Stream<Item = Vec<u8>> + Send + Unpin {
+        self.stream
+    }
+}
+
+/// The stream of messages of a ROS subscription.
+///
+/// Returned by [`Node::subscribe`](crate::Node::subscribe). Besides the
+/// combinators of `futures::StreamExt`, the messages can be processed with
+/// the operators `filter_msgs`, `map_msgs`, `throttle` and `debounce`. Unlike the
+/// combinators the closures are plain functions instead of returning
+/// futures, and the operators compose:
+///
+/// ``` rust,ignore
+/// let values = node
+///     .subscribe::<std_msgs::msg::Float64>("/sensor", QosProfile::default())?
+///     .filter_msgs(|m| m.data > 0.0)
+///     .map_msgs(|m| m.data)
+///     .throttle(Duration::from_millis(100));
+/// ```
+pub struct Subscription<T> {
+    source: SubscriptionSource<T>,
+    stats: Arc<SubscriptionStats>,
+    // the resolved topic and the node, for get_publisher_infos
+    graph: Option<(String, NodeGraphHandle)>,
+}
+
+enum SubscriptionSource<T> {
+    Channel(mpsc::Receiver<T>),
+    Queue(Arc<MessageQueue<T>>),
+}
+
+impl<T> Subscription<T> {
+    pub(crate) fn new(receiver: mpsc::Receiver<T>, stats: Arc<SubscriptionStats>) -> Self {
+        Subscription {
+            source: SubscriptionSource::Channel(receiver),
+            stats,
+            graph: None,
+        }
+    }
+
+    pub(crate) fn from_queue(queue: Arc<MessageQueue<T>>, stats: Arc<SubscriptionStats>) -> Self {
+        Subscription {
+            source: SubscriptionSource::Queue(queue),
+            stats,
+            graph: None,
+        }
+    }
+
+    pub(crate) fn with_graph(mut self, graph: (String, NodeGraphHandle)) -> Self {
+        self.graph = Some(graph);
+        self
+    }
+
+    /// Get information about the publishers on the topic of this subscription,
+    /// see [`Node::get_publishers_info_by_topic`](crate::Node::get_publishers_info_by_topic).
+    ///
+    /// Together with [`QosProfile::is_compatible_with_subscription`] this tells
+    /// which publishers the subscription can receive from. Returns
+    /// `RCL_RET_NODE_INVALID` after the node was dropped, or for subscriptions
+    /// not created directly by a node.
+    pub fn get_publisher_infos(&self) -> Result<Vec<TopicEndpointInfo>> {
+        let (topic, node) = self.graph.as_ref().ok_or(Error::RCL_RET_NODE_INVALID)?;
+        node.publishers_info_by_topic(topic)
+    }
+
+    /// The statistics of the received messages, see [`SubscriptionStats`].
+    ///
+    /// The statistics can be kept to check them after the stream was moved
+    /// into a task.
+    pub fn stats(&self) -> Arc<SubscriptionStats> {
+        self.stats.clone()
+    }
+
+    /// The number of messages received so far, see [`SubscriptionStats::message_count`].
+    pub fn message_count(&self) -> u64 {
+        self.stats.message_count()
+    }
+
+    /// The rate of received messages, see [`SubscriptionStats::estimated_frequency_hz`].
+    pub fn estimated_frequency_hz(&self, window: Duration) -> f64 {
+        self.stats.estimated_frequency_hz(window)
+    }
+
+    /// When the last message was received, see [`SubscriptionStats::last_received_time`].
+    pub fn last_received_time(&self) -> Option<Instant> {
+        self.stats.last_received_time()
+    }
+
+    /// The number of messages dropped because the queue of the subscription was full.
+    ///
+    /// Only subscriptions created with
+    /// [`Node::create_subscription_with_options`](crate::Node::create_subscription_with_options)
+    /// count their dropped messages, for other subscriptions this is always zero.
+    pub fn total_dropped_count(&self) -> u64 {
+        match &self.source {
+            SubscriptionSource::Channel(_) => 0,
+            SubscriptionSource::Queue(queue) => queue.dropped.count(),
+        }
+    }
+}
+
+impl<T> Stream for Subscription<T> {
+    type Item = T;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<T>> {
+        match &mut self.source {
+            SubscriptionSource::Channel(receiver) => std::pin::Pin::new(receiver).poll_next(cx),
+            SubscriptionSource::Queue(queue) => queue.poll_pop(cx),
+        }
+    }
+}
+
+impl<T> Drop for Subscription<T> {
+    fn drop(&mut self) {
+        if let SubscriptionSource::Queue(queue) = &self.source {
+            queue.close();
+        }
+    }
+}
+
+// the number of arrival times kept for estimating the frequency.
+pub(crate) const ARRIVAL_HISTORY: usize = 1024;
+
+/// Statistics of the messages received by a [`Subscription`].
+///
+/// The statistics are updated in `spin_once` when a message is taken from
+/// the middleware, whether or not it is consumed from the stream.
+#[derive(Debug, Default)]
+pub struct SubscriptionStats {
+    count: AtomicU64,
+    arrivals: Mutex<VecDeque<Instant>>,
+}
+
+impl SubscriptionStats {
+    /// The number of messages received so far.
+    pub fn message_count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// When the last message was received.
+    pub fn last_received_time(&self) -> Option<Instant> {
+        self.arrivals.lock().unwrap().back().copied()
+    }
+
+    /// The rate of received messages, as an exponential moving average with
+    /// the time constant `window`.
+    ///
+    /// Recent messages count the most, so the estimate follows changes of the
+    /// rate within about `window`, and decays towards zero when no messages
+    /// are received. It is based on the last 1024 messages, and is zero
+    /// until two messages were received.
+    pub fn estimated_frequency_hz(&self, window: Duration) -> f64 {
+        self.estimated_frequency_hz_at(window, Instant::now())
+    }
+
+    pub(crate) fn record(&self) {
+        self.record_at(Instant::now());
+    }
+
+    fn record_at(&self, time: Instant) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+        let mut arrivals = self.arrivals.lock().unwrap();
+        if arrivals.len() == ARRIVAL_HISTORY {
+            arrivals.pop_front();
+        }
+        arrivals.push_back(time);
+    }
+
+    fn estimated_frequency_hz_at(&self, window: Duration, now: Instant) -> f64 {
+        estimate_frequency_hz(&self.arrivals.lock().unwrap(), window, now)
+    }
+}
+
+// the exponential moving average of the rate of the events at `times`,
+// see SubscriptionStats::estimated_frequency_hz.
+pub(crate) fn estimate_frequency_hz(
+    times: &VecDeque<Instant>, window: Duration, now: Instant,
+) -> f64 {
+    let window = window.as_secs_f64();
+    let (Some(first), true) = (times.front(), times.len() >= 2 && window > 0.0) else {
+        return 0.0;
+    };
+    let age = |t: &Instant| now.saturating_duration_since(*t).as_secs_f64();
+    let weights: f64 = times.iter().map(|t| (-age(t) / window).exp()).sum();
+    // the weight of the time covered by the history, so a short history
+    // does not bias the estimate towards zero.
+    let covered = window * (1.0 - (-age(first) / window).exp());
+    if covered <= 0.0 {
+        return 0.0;
+    }
+    weights / covered
+}
+
+/// What a subscription does with a new message when its queue is full.
+///
+/// See [`SubscriptionOptions::overflow_policy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Drop the oldest message in the queue to make room for the new one.
+    DropOldest,
+    /// Drop the new message, like [`Node::subscribe`](crate::Node::subscribe) does.
+    #[default]
+    DropNewest,
+    /// Wait up to the given duration in `spin_once` for the consumer to make
+    /// room. If the queue is still full, the message is left in the middleware,
+    /// so a reliable publisher is slowed down once the history of the
+    /// subscription is full as well.
+    ///
+    /// This only makes sense when the stream is consumed on another thread
+    /// than the one spinning the node.
+    Block(Duration),
+}
+
+// the queue between the subscriber, filled in spin_once, and the stream.
+pub(crate) struct MessageQueue<T> {
+    state: Mutex<QueueState<T>>,
+    not_full: Condvar,
+    waker: AtomicWaker,
+    depth: usize,
+    policy: OverflowPolicy,
+    dropped: DropCounter,
+}
+
+struct QueueState<T> {
+    messages: VecDeque<T>,
+    // set when either the subscriber or the stream is dropped.
+    closed: bool,
+}
+
+impl<T> MessageQueue<T> {
+    pub(crate) fn new(depth: usize, policy: OverflowPolicy) -> Self {
+        MessageQueue {
+            state: Mutex::new(QueueState {
+                messages: VecDeque::with_capacity(depth),
+                closed: false,
+            }),
+            not_full: Condvar::new(),
+            waker: AtomicWaker::new(),
+            depth,
+            policy,
+            dropped: DropCounter::default(),
+        }
+    }
+
+    /// Returns false if the queue is full and the message should not be taken
+    /// yet. With [`OverflowPolicy::Block`] this waits for room first.
+    pub(crate) fn wait_for_room(&self) -> bool {
+        let OverflowPolicy::Block(timeout) = self.policy else {
+            return true;
+        };
+        let state = self.state.lock().unwrap();
+        let (state, _) = self
+            .not_full
+            .wait_timeout_while(state, timeout, |s| !s.closed && s.messages.len() >= self.depth)
+            .unwrap();
+        state.messages.len() < self.depth
+    }
+
+    pub(crate) fn push(&self, msg: T) {
+        let mut state = self.state.lock().unwrap();
+        if state.messages.len() >= self.depth {
+            self.dropped.dropped.fetch_add(1, Ordering::Relaxed);
+            match self.policy {
+                OverflowPolicy::DropNewest => return,
+                OverflowPolicy::DropOldest | OverflowPolicy::Block(_) => {
+                    state.messages.pop_front();
+                }
+            }
+        }
+        state.messages.push_back(msg);
+        drop(state);
+        self.waker.wake();
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.state.lock().unwrap().closed
+    }
+
+    pub(crate) fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.not_full.notify_all();
+        self.waker.wake();
+    }
+
+    fn poll_pop(&self, cx: &mut std::task::Context<'_>) -> Poll<Option<T>> {
+        // register first, so a message pushed after the check wakes the task.
+        self.waker.register(cx.waker());
+        let mut state = self.state.lock().unwrap();
+        match state.messages.pop_front() {
+            Some(msg) => {
+                drop(state);
+                self.not_full.notify_one();
+                Poll::Ready(Some(msg))
+            }
+            None if state.closed => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    }
+}
+
+/// The stream of messages of a subscription with a bounded channel.
+///
+/// Returned by [`Node::create_bounded_subscription`](crate::Node::create_bounded_subscription).
+/// When the consumer falls behind and the channel is full, new messages are
+/// dropped and counted by the accompanying [`DropCounter`].
+pub struct BoundedSubscription<T> {
+    receiver: mpsc::Receiver<T>,
+}
+
+impl<T> BoundedSubscription<T> {
+    pub(crate) fn new(receiver: mpsc::Receiver<T>) -> Self {
+        BoundedSubscription { receiver }
+    }
+}
+
+impl<T> Stream for BoundedSubscription<T> {
+    type Item = T;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<T>> {
+        std::pin::Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+/// Counts the messages of a [`BoundedSubscription`] dropped because its channel was full.
+#[derive(Clone, Debug, Default)]
+pub struct DropCounter {
+    dropped: Arc<AtomicU64>,
+}
+
+impl DropCounter {
+    /// The number of messages dropped so far.
+    pub fn count(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// A message which could not be deserialized, see [`DeserErrorHandler`].
+#[derive(Debug, Clone)]
+pub struct DeserError {
+    /// The serialized message as received.
+    pub data: Vec<u8>,
+    /// Why deserialization failed.
+    pub description: String,
+}
+
+/// What a subscription does with a message which cannot be deserialized.
+///
+/// The message is dropped in any case, so a publisher sending malformed
+/// data does not stop the subscription. See
+/// [`SubscriptionOptions::on_deserialization_error`].
+#[derive(Clone, Default)]
+pub enum DeserErrorHandler {
+    /// Silently drop the message.
+    Skip,
+    /// Log the error as a warning and drop the message.
+    #[default]
+    Log,
+    /// Call the function with the error and drop the message.
+    Custom(Arc<dyn Fn(DeserError) + Send + Sync>),
+}
+
+impl DeserErrorHandler {
+    fn handle(&self, topic: &str, error: DeserError) {
+        match self {
+            DeserErrorHandler::Skip => (),
+            DeserErrorHandler::Log => log::warn!(
+                "dropping message of {} bytes on {}: {}",
+                error.data.len(),
+                topic,
+                error.description
+            ),
+            DeserErrorHandler::Custom(f) => f(error),
+        }
+    }
+}
+
+impl fmt::Debug for DeserErrorHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeserErrorHandler::Skip => write!(f, "Skip"),
+            DeserErrorHandler::Log => write!(f, "Log"),
+            DeserErrorHandler::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+/// Options used when creating a subscription.
+///
+/// The QoS event callbacks are called from `spin_once` when the corresponding
+/// event occurs. See [`Node::subscribe_with_options`](crate::Node::subscribe_with_options).
+#[derive(Clone)]
+pub struct SubscriptionOptions {
+    /// Only deliver messages matching this filter.
+    pub content_filter: Option<ContentFilter>,
+    /// Publish statistics about received messages on `<node>/<topic>/statistics`.
+    pub enable_topic_statistics: bool,
+    /// How often topic statistics are published, defaults to one second.
+    pub statistics_publish_period: Duration,
+    /// Called when no message was received within the deadline of the QoS profile.
+    pub on_deadline_missed: Option<Arc<dyn Fn(DeadlineMissedStatus) + Send + Sync>>,
+    /// Called when the liveliness of a matched publisher changed.
+    pub on_liveliness_changed: Option<Arc<dyn Fn(LivelinessChangedStatus) + Send + Sync>>,
+    /// Called when a publisher with an incompatible QoS profile was found.
+    pub on_incompatible_qos: Option<Arc<dyn Fn(IncompatibleQosStatus) + Send + Sync>>,
+    /// Called when messages were lost, e.g. because the history of the QoS
+    /// profile overflowed before they were taken. Not all middlewares report this.
+    pub on_message_lost: Option<Arc<dyn Fn(MessageLostStatus) + Send + Sync>>,
+    /// The number of messages waiting in the stream, defaults to 10.
+    pub queue_depth: usize,
+    /// What to do with new messages when the stream is full.
+    pub overflow_policy: OverflowPolicy,
+    /// What to do with messages which cannot be deserialized, e.g. truncated
+    /// data from a faulty publisher. Only used by
+    /// [`Node::subscribe_with_options`](crate::Node::subscribe_with_options)
+    /// without topic statistics.
+    ///
+    /// With a handler, messages are taken serialized and deserialized by r2r,
+    /// so that the data of a failing message can be passed on. Without one,
+    /// such messages are dropped by the middleware.
+    pub on_deserialization_error: Option<DeserErrorHandler>,
+    /// Ask the middleware for network flow endpoints not shared with other
+    /// subscriptions, e.g. so that their traffic can be prioritized in
+    /// time-sensitive networking. Middlewares without support ignore this.
+    /// See [`Node::get_subscription_network_flow_endpoints`](crate::Node::get_subscription_network_flow_endpoints).
+    pub unique_network_flow_endpoint: bool,
+}
+
+impl Default for SubscriptionOptions {
+    fn default() -> Self {
+        SubscriptionOptions {
+            content_filter: None,
+            enable_topic_statistics: false,
+            statistics_publish_period: Duration::from_secs(1),
+            on_deadline_missed: None,
+            on_liveliness_changed: None,
+            on_incompatible_qos: None,
+            on_message_lost: None,
+            queue_depth: 10,
+            overflow_policy: OverflowPolicy::default(),
+            on_deserialization_error: None,
+            unique_network_flow_endpoint: false,
+        }
+    }
+}
+
+impl fmt::Debug for SubscriptionOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SubscriptionOptions")
+            .field("content_filter", &self.content_filter)
+            .field("enable_topic_statistics", &self.enable_topic_statistics)
+            .field("statistics_publish_period", &self.statistics_publish_period)
+            .field("on_deadline_missed", &self.on_deadline_missed.is_some())
+            .field("on_liveliness_changed", &self.on_liveliness_changed.is_some())
+            .field("on_incompatible_qos", &self.on_incompatible_qos.is_some())
+            .field("on_message_lost", &self.on_message_lost.is_some())
+            .field("queue_depth", &self.queue_depth)
+            .field("overflow_policy", &self.overflow_policy)
+            .field("on_deserialization_error", &self.on_deserialization_error)
+            .field("unique_network_flow_endpoint", &self.unique_network_flow_endpoint)
+            .finish()
+    }
+}
+
+/// A content filter evaluated by the middleware.
+///
+/// Messages that do not match the filter are dropped before they reach the
+/// subscriber. The expression uses the DDS SQL filter syntax, e.g. `"x > %0 AND y < %1"`,
+/// where `%0`, `%1`, ... are replaced by the corresponding entry in `parameters`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentFilter {
+    pub expression: String,
+    pub parameters: Vec<String>,
+}
+
+impl ContentFilter {
+    /// Create a filter from an expression without parameters.
+    pub fn new(expression: &str) -> Self {
+        ContentFilter {
+            expression: expression.to_owned(),
+            parameters: Vec::new(),
+        }
+    }
+
+    /// Create a filter from an expression and its parameters.
+    pub fn with_parameters(expression: &str, parameters: &[&str]) -> Self {
+        ContentFilter {
+            expression: expression.to_owned(),
+            parameters: parameters.iter().map(|p| (*p).to_owned()).collect(),
+        }
+    }
+}
+
+/// Metadata about a received message.
+///
+/// See [`Node::subscribe_with_info`](crate::Node::subscribe_with_info).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageInfo {
+    /// Time when the message was published, if provided by the middleware.
+    pub source_timestamp: Option<SystemTime>,
+    /// Time when the message was received.
+    pub received_timestamp: SystemTime,
+    /// Globally unique identifier of the publisher.
+    pub publisher_gid: Gid,
+    /// Sequence number assigned by the publisher, if provided by the middleware.
+    pub sequence_number: Option<u64>,
+    /// True if the message was delivered from a publisher in the same process.
+    pub from_intra_process: bool,
+}
+
+impl From<&rmw_message_info_t> for MessageInfo {
+    fn from(info: &rmw_message_info_t) -> Self {
+        // rmw leaves unsupported fields zeroed.
+        let to_system_time = |t: rmw_time_point_value_t| {
+            (t > 0).then(|| UNIX_EPOCH + Duration::from_nanos(t as u64))
+        };
+
+        #[cfg(not(any(r2r__ros__distro__foxy, r2r__ros__distro__galactic)))]
+        let sequence_number = Some(info.publication_sequence_number).filter(|n| *n != 0);
+        #[cfg(any(r2r__ros__distro__foxy, r2r__ros__distro__galactic))]
+        let sequence_number = None;
+
+        MessageInfo {
+            source_timestamp: to_system_time(info.source_timestamp),
+            received_timestamp: to_system_time(info.received_timestamp).unwrap_or(UNIX_EPOCH),
+            publisher_gid: info.publisher_gid.data,
+            sequence_number,
+            from_intra_process: info.from_intra_process,
+        }
+    }
+}
 
 pub trait Subscriber_ {
     fn handle(&self) -> &rcl_subscription_t;
@@ -18,6 +597,54 @@ where
 {
     pub rcl_handle: rcl_subscription_t,
     pub sender: mpsc::Sender<T>,
+    pub stats: Option<Arc<SubscriptionStats>>,
+}
+
+// takes the serialized messages and deserializes them itself, so the
+// data is still around when that fails.
+pub struct TypedSubscriberWithErrorHandler<T>
+where
+    T: WrappedTypesupport,
+{
+    pub rcl_handle: rcl_subscription_t,
+    pub msg_buf: rcl_serialized_message_t,
+    pub(crate) queue: Arc<MessageQueue<T>>,
+    pub on_error: DeserErrorHandler,
+}
+
+pub struct TypedSubscriberWithInfo<T>
+where
+    T: WrappedTypesupport,
+{
+    pub rcl_handle: rcl_subscription_t,
+    pub sender: mpsc::Sender<(T, MessageInfo)>,
+}
+
+pub struct BatchedSubscriber<T>
+where
+    T: WrappedTypesupport,
+{
+    pub rcl_handle: rcl_subscription_t,
+    pub max_batch: usize,
+    pub sender: mpsc::Sender<Vec<T>>,
+}
+
+pub struct BoundedSubscriber<T>
+where
+    T: WrappedTypesupport,
+{
+    pub rcl_handle: rcl_subscription_t,
+    pub sender: mpsc::Sender<T>,
+    pub drops: DropCounter,
+}
+
+pub struct QueuedSubscriber<T>
+where
+    T: WrappedTypesupport,
+{
+    pub rcl_handle: rcl_subscription_t,
+    pub(crate) queue: Arc<MessageQueue<T>>,
+    pub stats: Arc<SubscriptionStats>,
 }
 
 pub struct NativeSubscriber<T>
@@ -55,6 +682,9 @@ where
             rcl_take(&self.rcl_handle, msg.void_ptr_mut(), &mut msg_info, std::ptr::null_mut())
         };
         if ret == RCL_RET_OK as i32 {
+            if let Some(stats) = &self.stats {
+                stats.record();
+            }
             let msg = T::from_native(&msg);
             if let Err(e) = self.sender.try_send(msg) {
                 if e.is_disconnected() {
@@ -74,6 +704,236 @@ where
     }
 }
 
+impl<T: 'static> Subscriber_ for TypedSubscriberWithErrorHandler<T>
+where
+    T: WrappedTypesupport,
+{
+    fn handle(&self) -> &rcl_subscription_t {
+        &self.rcl_handle
+    }
+
+    fn handle_incoming(&mut self) -> bool {
+        if self.queue.is_closed() {
+            // user dropped the handle to the stream, signal removal.
+            return true;
+        }
+        if !self.queue.wait_for_room() {
+            // leave the message in the middleware until there is room.
+            return false;
+        }
+        let mut msg_info = rmw_message_info_t::default(); // we dont care for now
+        let ret = unsafe {
+            rcl_take_serialized_message(
+                &self.rcl_handle,
+                &mut self.msg_buf as *mut rcl_serialized_message_t,
+                &mut msg_info,
+                std::ptr::null_mut(),
+            )
+        };
+        if ret != RCL_RET_OK as i32 {
+            return false;
+        }
+
+        let mut msg = WrappedNativeMsg::<T>::new();
+        let ret = unsafe { rmw_deserialize(&self.msg_buf, T::get_ts(), msg.void_ptr_mut()) };
+        if ret != RCL_RET_OK as i32 {
+            let data = if self.msg_buf.buffer.is_null() {
+                Vec::new()
+            } else {
+                unsafe {
+                    std::slice::from_raw_parts(self.msg_buf.buffer, self.msg_buf.buffer_length)
+                        .to_vec()
+                }
+            };
+            let error = DeserError {
+                data,
+                description: Error::from_rcl_call("rmw_deserialize", ret).to_string(),
+            };
+            let topic =
+                unsafe { CStr::from_ptr(rcl_subscription_get_topic_name(&self.rcl_handle)) };
+            self.on_error.handle(&topic.to_string_lossy(), error);
+            return false;
+        }
+
+        self.queue.push(T::from_native(&msg));
+        false
+    }
+
+    fn destroy(&mut self, node: &mut rcl_node_t) {
+        unsafe {
+            rcl_subscription_fini(&mut self.rcl_handle, node);
+            rcutils_uint8_array_fini(&mut self.msg_buf as *mut rcl_serialized_message_t);
+        }
+    }
+}
+
+impl<T: 'static> Subscriber_ for TypedSubscriberWithInfo<T>
+where
+    T: WrappedTypesupport,
+{
+    fn handle(&self) -> &rcl_subscription_t {
+        &self.rcl_handle
+    }
+
+    fn handle_incoming(&mut self) -> bool {
+        let mut msg_info = rmw_message_info_t::default();
+        let mut msg = WrappedNativeMsg::<T>::new();
+        let ret = unsafe {
+            rcl_take(&self.rcl_handle, msg.void_ptr_mut(), &mut msg_info, std::ptr::null_mut())
+        };
+        if ret == RCL_RET_OK as i32 {
+            let msg = T::from_native(&msg);
+            if let Err(e) = self.sender.try_send((msg, MessageInfo::from(&msg_info))) {
+                if e.is_disconnected() {
+                    // user dropped the handle to the stream, signal removal.
+                    return true;
+                }
+                log::debug!("error {:?}", e)
+            }
+        }
+        false
+    }
+
+    fn destroy(&mut self, node: &mut rcl_node_t) {
+        unsafe {
+            rcl_subscription_fini(&mut self.rcl_handle, node);
+        }
+    }
+}
+
+impl<T: 'static> Subscriber_ for BatchedSubscriber<T>
+where
+    T: WrappedTypesupport,
+{
+    fn handle(&self) -> &rcl_subscription_t {
+        &self.rcl_handle
+    }
+
+    fn handle_incoming(&mut self) -> bool {
+        let mut msg_info = rmw_message_info_t::default(); // we dont care for now
+        let mut batch = Vec::new();
+        // drain the queue, until it is empty or the batch is full.
+        while batch.len() < self.max_batch {
+            let mut msg = WrappedNativeMsg::<T>::new();
+            let ret = unsafe {
+                rcl_take(&self.rcl_handle, msg.void_ptr_mut(), &mut msg_info, std::ptr::null_mut())
+            };
+            if ret != RCL_RET_OK as i32 {
+                break;
+            }
+            batch.push(T::from_native(&msg));
+        }
+        if batch.is_empty() {
+            return false;
+        }
+        if let Err(e) = self.sender.try_send(batch) {
+            if e.is_disconnected() {
+                // user dropped the handle to the stream, signal removal.
+                return true;
+            }
+            log::debug!("error {:?}", e)
+        }
+        false
+    }
+
+    fn destroy(&mut self, node: &mut rcl_node_t) {
+        unsafe {
+            rcl_subscription_fini(&mut self.rcl_handle, node);
+        }
+    }
+}
+
+impl<T: 'static> Subscriber_ for BoundedSubscriber<T>
+where
+    T: WrappedTypesupport,
+{
+    fn handle(&self) -> &rcl_subscription_t {
+        &self.rcl_handle
+    }
+
+    fn handle_incoming(&mut self) -> bool {
+        let mut msg_info = rmw_message_info_t::default(); // we dont care for now
+        let mut msg = WrappedNativeMsg::<T>::new();
+        let ret = unsafe {
+            rcl_take(&self.rcl_handle, msg.void_ptr_mut(), &mut msg_info, std::ptr::null_mut())
+        };
+        if ret == RCL_RET_OK as i32 {
+            // the message is taken even if the channel is full, so the
+            // middleware queue does not fill up behind a slow consumer.
+            if let Err(e) = self.sender.try_send(T::from_native(&msg)) {
+                if e.is_disconnected() {
+                    // user dropped the handle to the stream, signal removal.
+                    return true;
+                }
+                self.drops.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        false
+    }
+
+    fn destroy(&mut self, node: &mut rcl_node_t) {
+        unsafe {
+            rcl_subscription_fini(&mut self.rcl_handle, node);
+        }
+    }
+}
+
+impl<T: 'static> Subscriber_ for QueuedSubscriber<T>
+where
+    T: WrappedTypesupport,
+{
+    fn handle(&self) -> &rcl_subscription_t {
+        &self.rcl_handle
+    }
+
+    fn handle_incoming(&mut self) -> bool {
+        if self.queue.is_closed() {
+            // user dropped the handle to the stream, signal removal.
+            return true;
+        }
+        if !self.queue.wait_for_room() {
+            // leave the message in the middleware until there is room.
+            return false;
+        }
+        let mut msg_info = rmw_message_info_t::default(); // we dont care for now
+        let mut msg = WrappedNativeMsg::<T>::new();
+        let ret = unsafe {
+            rcl_take(&self.rcl_handle, msg.void_ptr_mut(), &mut msg_info, std::ptr::null_mut())
+        };
+        if ret == RCL_RET_OK as i32 {
+            self.stats.record();
+            self.queue.push(T::from_native(&msg));
+        }
+        false
+    }
+
+    fn destroy(&mut self, node: &mut rcl_node_t) {
+        unsafe {
+            rcl_subscription_fini(&mut self.rcl_handle, node);
+        }
+    }
+}
+
+impl<T> Drop for QueuedSubscriber<T>
+where
+    T: WrappedTypesupport,
+{
+    fn drop(&mut self) {
+        // end the stream.
+        self.queue.close();
+    }
+}
+
+impl<T> Drop for TypedSubscriberWithErrorHandler<T>
+where
+    T: WrappedTypesupport,
+{
+    fn drop(&mut self) {
+        // end the stream.
+        self.queue.close();
+    }
+}
+
 impl<T: 'static> Subscriber_ for NativeSubscriber<T>
 where
     T: WrappedTypesupport,
@@ -105,7 +965,10 @@ where
                         drop(Box::from_raw(handle_ptr));
                     } else {
                         let topic_str = rcl_subscription_get_topic_name(handle_ptr);
-                        let topic = CStr::from_ptr(topic_str).to_str().expect("to_str() call failed").to_owned();
+                        let topic = CStr::from_ptr(topic_str)
+                            .to_str()
+                            .expect("to_str() call failed")
+                            .to_owned();
                         drop(Box::from_raw(handle_ptr));
 
                         let err_str = rcutils_get_error_string();
@@ -234,16 +1097,89 @@ impl Subscriber_ for RawSubscriber {
     }
 }
 
+// a buffer for serialized messages, which the rmw resizes as needed.
+pub(crate) fn create_serialized_buffer() -> Result<rcl_serialized_message_t> {
+    let mut msg_buf: rcl_serialized_message_t =
+        unsafe { rcutils_get_zero_initialized_uint8_array() };
+    let ret = unsafe {
+        rcutils_uint8_array_init(
+            &mut msg_buf as *mut rcl_serialized_message_t,
+            0,
+            &rcutils_get_default_allocator(),
+        )
+    };
+    if ret != RCL_RET_OK as i32 {
+        return Err(Error::from_rcl_error(ret));
+    }
+    Ok(msg_buf)
+}
+
 pub fn create_subscription_helper(
     node: &mut rcl_node_t, topic: &str, ts: *const rosidl_message_type_support_t,
     qos_profile: QosProfile,
+) -> Result<rcl_subscription_t> {
+    create_subscription_helper_with_options(
+        node,
+        topic,
+        ts,
+        qos_profile,
+        &SubscriptionOptions::default(),
+    )
+}
+
+pub fn create_subscription_helper_with_options(
+    node: &mut rcl_node_t, topic: &str, ts: *const rosidl_message_type_support_t,
+    qos_profile: QosProfile, options: &SubscriptionOptions,
 ) -> Result<rcl_subscription_t> {
     let mut subscription_handle = unsafe { rcl_get_zero_initialized_subscription() };
     let topic_c_string = CString::new(topic).map_err(|_| Error::RCL_RET_INVALID_ARGUMENT)?;
 
+    let mut subscription_options = unsafe { rcl_subscription_get_default_options() };
+    subscription_options.qos = qos_profile.into();
+
+    #[cfg(r2r__ros__distro__foxy)]
+    if options.unique_network_flow_endpoint {
+        return Err(Error::UnsupportedFeature("network_flow_endpoints"));
+    }
+
+    #[cfg(not(r2r__ros__distro__foxy))]
+    {
+        subscription_options
+            .rmw_subscription_options
+            .require_unique_network_flow_endpoints =
+            unique_network_flow_endpoints_requirement(options.unique_network_flow_endpoint);
+    }
+
+    #[cfg(any(r2r__ros__distro__foxy, r2r__ros__distro__galactic))]
+    if options.content_filter.is_some() {
+        return Err(Error::UnsupportedFeature("content_filtered_topics"));
+    }
+
+    #[cfg(not(any(r2r__ros__distro__foxy, r2r__ros__distro__galactic)))]
+    if let Some(filter) = &options.content_filter {
+        let expression = CString::new(filter.expression.as_str())
+            .map_err(|_| Error::RCL_RET_INVALID_ARGUMENT)?;
+        let parameters = filter
+            .parameters
+            .iter()
+            .map(|p| CString::new(p.as_str()).map_err(|_| Error::RCL_RET_INVALID_ARGUMENT))
+            .collect::<Result<Vec<_>>>()?;
+        let mut parameter_ptrs: Vec<*const std::os::raw::c_char> =
+            parameters.iter().map(|p| p.as_ptr()).collect();
+        let result = unsafe {
+            rcl_subscription_options_set_content_filter_options(
+                expression.as_ptr(),
+                parameter_ptrs.len(),
+                parameter_ptrs.as_mut_ptr(),
+                &mut subscription_options,
+            )
+        };
+        if result != RCL_RET_OK as i32 {
+            return Err(Error::from_rcl_error(result));
+        }
+    }
+
     let result = unsafe {
-        let mut subscription_options = rcl_subscription_get_default_options();
-        subscription_options.qos = qos_profile.into();
         rcl_subscription_init(
             &mut subscription_handle,
             node,
@@ -252,9 +1188,55 @@ pub fn create_subscription_helper(
             &subscription_options,
         )
     };
+
+    #[cfg(not(any(r2r__ros__distro__foxy, r2r__ros__distro__galactic)))]
+    if options.content_filter.is_some() {
+        // the options own a copy of the filter, the subscription keeps its own.
+        unsafe {
+            rcl_subscription_options_fini(&mut subscription_options);
+        }
+        // rmw implementations without content filter support silently
+        // ignore the filter, so check that it was actually applied.
+        if result == RCL_RET_OK as i32
+            && !unsafe { rcl_subscription_is_cft_enabled(&subscription_handle) }
+        {
+            unsafe {
+                rcl_subscription_fini(&mut subscription_handle, node);
+            }
+            return Err(Error::UnsupportedFeature("content_filtered_topics"));
+        }
+    }
+
     if result == RCL_RET_OK as i32 {
         Ok(subscription_handle)
     } else {
-        Err(Error::from_rcl_error(result))
+        Err(Error::from_rcl_call("rcl_subscription_init", result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimated_frequency() {
+        let stats = SubscriptionStats::default();
+        let start = Instant::now();
+        let window = Duration::from_secs(1);
+        assert_eq!(stats.estimated_frequency_hz_at(window, start), 0.0);
+
+        // 50 Hz for two seconds.
+        for i in 0..100 {
+            stats.record_at(start + Duration::from_millis(20 * i));
+        }
+        assert_eq!(stats.message_count(), 100);
+        assert_eq!(stats.last_received_time(), Some(start + Duration::from_millis(1980)));
+        let end = start + Duration::from_secs(2);
+        let hz = stats.estimated_frequency_hz_at(window, end);
+        assert!((hz - 50.0).abs() < 1.0, "{hz}");
+
+        // without new messages the estimate decays.
+        let later = stats.estimated_frequency_hz_at(window, end + Duration::from_secs(2));
+        assert!(later < 10.0, "{later}");
     }
 }
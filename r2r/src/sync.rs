@@ -0,0 +1,248 @@
+//! Synchronization of messages from several topics by their timestamps.
+//!
+//! Similar to `message_filters` in ROS, e.g. to get matching frames from a
+//! camera and a lidar. The inputs are streams of stamped messages, where the
+//! messages are boxed so that topics of different types can be synchronized:
+//!
+//! ``` rust,ignore
+//! use futures::stream::StreamExt;
+//! use r2r::sync::ApproximateTimeSynchronizer;
+//!
+//! let images = node
+//!     .subscribe::<Image>("/camera/image", QosProfile::default())?
+//!     .map(|msg| (msg.header.stamp.clone(), Box::new(msg) as _))
+//!     .boxed();
+//! let clouds = node
+//!     .subscribe::<PointCloud2>("/lidar/points", QosProfile::default())?
+//!     .map(|msg| (msg.header.stamp.clone(), Box::new(msg) as _))
+//!     .boxed();
+//! let mut synced =
+//!     ApproximateTimeSynchronizer::new(vec![images, clouds], Duration::from_millis(10), 10);
+//! while let Some(inputs) = synced.next().await {
+//!     // one message per input, in the order of the inputs.
+//!     let image = inputs[0].1.downcast_ref::<Image>().unwrap();
+//!     let cloud = inputs[1].1.downcast_ref::<PointCloud2>().unwrap();
+//! }
+//! ```
+
+use futures::stream::{BoxStream, Stream};
+use std::{
+    any::Any,
+    collections::VecDeque,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use crate::msg_types::generated_msgs::builtin_interfaces;
+
+/// A message together with the timestamp it is synchronized by.
+pub type SyncInput = (builtin_interfaces::msg::Time, Box<dyn Any + Send>);
+
+fn to_nanos(stamp: &builtin_interfaces::msg::Time) -> i128 {
+    stamp.sec as i128 * 1_000_000_000 + stamp.nanosec as i128
+}
+
+/// Matches messages whose timestamps are at most `slop` apart.
+///
+/// The timestamps of each input must be increasing.
+struct Synchronizer {
+    inputs: Vec<BoxStream<'static, SyncInput>>,
+    queues: Vec<VecDeque<SyncInput>>,
+    finished: Vec<bool>,
+    slop: i128,
+    queue_size: usize,
+}
+
+impl Synchronizer {
+    fn new(inputs: Vec<BoxStream<'static, SyncInput>>, slop: Duration, queue_size: usize) -> Self {
+        let n = inputs.len();
+        Synchronizer {
+            inputs,
+            queues: (0..n).map(|_| VecDeque::new()).collect(),
+            finished: vec![false; n],
+            slop: slop.as_nanos() as i128,
+            queue_size: queue_size.max(1),
+        }
+    }
+
+    /// Returns a set of matching messages if there is one at the front of the queues.
+    fn try_match(&mut self) -> Option<Vec<SyncInput>> {
+        while self.queues.iter().all(|q| !q.is_empty()) {
+            let stamps = self
+                .queues
+                .iter()
+                .map(|q| to_nanos(&q[0].0))
+                .collect::<Vec<_>>();
+            let (oldest, min) = stamps
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, s)| **s)
+                .map(|(i, s)| (i, *s))?;
+            let max = stamps.iter().max().copied()?;
+            if max - min <= self.slop {
+                return Some(
+                    self.queues
+                        .iter_mut()
+                        .filter_map(|q| q.pop_front())
+                        .collect(),
+                );
+            }
+            // the newest message at the front is too far from the oldest one,
+            // and later messages of that input are even newer, so the oldest
+            // message cannot match anything anymore.
+            self.queues[oldest].pop_front();
+        }
+        None
+    }
+
+    fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<Vec<SyncInput>>> {
+        if self.inputs.is_empty() {
+            return Poll::Ready(None);
+        }
+        for (i, input) in self.inputs.iter_mut().enumerate() {
+            while !self.finished[i] {
+                match input.as_mut().poll_next(cx) {
+                    Poll::Ready(Some(msg)) => {
+                        let queue = &mut self.queues[i];
+                        if queue.len() == self.queue_size {
+                            queue.pop_front();
+                        }
+                        queue.push_back(msg);
+                    }
+                    Poll::Ready(None) => self.finished[i] = true,
+                    Poll::Pending => break,
+                }
+            }
+        }
+
+        if let Some(matched) = self.try_match() {
+            // more messages may be queued, so make sure we are polled again.
+            cx.waker().wake_by_ref();
+            return Poll::Ready(Some(matched));
+        }
+        let exhausted = self
+            .queues
+            .iter()
+            .zip(&self.finished)
+            .any(|(q, finished)| *finished && q.is_empty());
+        if exhausted {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Synchronizes several inputs by their timestamps, allowing them to differ.
+///
+/// Yields one message from each input, in the order of the inputs, whenever
+/// the timestamps of the oldest queued messages are all within `slop` of each
+/// other. Messages that cannot be matched anymore are dropped. The timestamps
+/// of each input must be increasing. At most `queue_size` messages are queued
+/// per input, after that the oldest message is dropped. The stream ends when
+/// one of the inputs ends.
+pub struct ApproximateTimeSynchronizer {
+    synchronizer: Synchronizer,
+}
+
+impl ApproximateTimeSynchronizer {
+    pub fn new(
+        inputs: Vec<BoxStream<'static, SyncInput>>, slop: Duration, queue_size: usize,
+    ) -> Self {
+        ApproximateTimeSynchronizer {
+            synchronizer: Synchronizer::new(inputs, slop, queue_size),
+        }
+    }
+}
+
+impl Stream for ApproximateTimeSynchronizer {
+    type Item = Vec<SyncInput>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().synchronizer.poll_next(cx)
+    }
+}
+
+/// Synchronizes several inputs by their timestamps, which must be equal.
+///
+/// Like [`ApproximateTimeSynchronizer`] with a slop of zero.
+pub struct ExactTimeSynchronizer {
+    synchronizer: Synchronizer,
+}
+
+impl ExactTimeSynchronizer {
+    pub fn new(inputs: Vec<BoxStream<'static, SyncInput>>, queue_size: usize) -> Self {
+        ExactTimeSynchronizer {
+            synchronizer: Synchronizer::new(inputs, Duration::ZERO, queue_size),
+        }
+    }
+}
+
+impl Stream for ExactTimeSynchronizer {
+    type Item = Vec<SyncInput>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().synchronizer.poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{executor::block_on, stream::StreamExt};
+
+    fn input(stamps_ms: &[i64]) -> BoxStream<'static, SyncInput> {
+        let msgs = stamps_ms
+            .iter()
+            .map(|ms| {
+                let stamp = builtin_interfaces::msg::Time {
+                    sec: (ms / 1000) as i32,
+                    nanosec: ((ms % 1000) * 1_000_000) as u32,
+                };
+                (stamp, Box::new(*ms) as Box<dyn Any + Send>)
+            })
+            .collect::<Vec<_>>();
+        futures::stream::iter(msgs).boxed()
+    }
+
+    fn values(sets: Vec<Vec<SyncInput>>) -> Vec<Vec<i64>> {
+        sets.into_iter()
+            .map(|set| {
+                set.into_iter()
+                    .map(|(_, msg)| *msg.downcast::<i64>().unwrap())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_approximate_sync() {
+        let camera = input(&[0, 100, 200, 300, 400]);
+        let lidar = input(&[3, 140, 198, 420]);
+        let synced =
+            ApproximateTimeSynchronizer::new(vec![camera, lidar], Duration::from_millis(5), 10);
+        let sets = values(block_on(synced.collect()));
+        // 100/140 and 400/420 are further apart than the slop.
+        assert_eq!(sets, vec![vec![0, 3], vec![200, 198]]);
+    }
+
+    #[test]
+    fn test_exact_sync() {
+        let a = input(&[0, 100, 200, 300]);
+        let b = input(&[100, 150, 300]);
+        let c = input(&[0, 100, 300, 400]);
+        let synced = ExactTimeSynchronizer::new(vec![a, b, c], 10);
+        let sets = values(block_on(synced.collect()));
+        assert_eq!(sets, vec![vec![100, 100, 100], vec![300, 300, 300]]);
+    }
+
+    #[test]
+    fn test_queue_size() {
+        // with a queue size of one, only the latest message of the first input is kept.
+        let a = input(&[0, 10, 20]);
+        let b = input(&[0]);
+        let synced = ExactTimeSynchronizer::new(vec![a, b], 1);
+        assert!(values(block_on(synced.collect())).is_empty());
+    }
+}
@@ -0,0 +1,592 @@
+#![cfg(r2r__tf2_msgs__msg__TFMessage)]
+
+//! Coordinate frame transforms, like `tf2_ros` in rclcpp.
+//!
+//! A [`Tf2Buffer`] listens to `/tf` and `/tf_static` and keeps a time indexed
+//! tree of the transforms between frames, which are published with a
+//! [`Tf2Broadcaster`].
+
+use crate::{
+    builtin_interfaces::msg::Time,
+    error::*,
+    geometry_msgs::msg::{Quaternion, Transform, TransformStamped, Vector3},
+    msg_types::{VoidPtr, WrappedNativeMsg},
+    publishers::Publisher,
+    subscribers::{create_subscription_helper, Subscriber_},
+    tf2_msgs::msg::TFMessage,
+    Node, QosProfile, WrappedTypesupport,
+};
+use r2r_rcl::{
+    rcl_node_t, rcl_subscription_fini, rcl_subscription_t, rcl_take, rmw_message_info_t, RCL_RET_OK,
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex, Weak},
+    time::Duration,
+};
+
+/// Same as the `tf2_ros` listener, so that no transforms are dropped between spins.
+const TF_QOS: QosProfile = QosProfile::default().keep_last(100);
+/// Static transforms are latched.
+const TF_STATIC_QOS: QosProfile = QosProfile::default().keep_last(1).transient_local();
+
+fn to_nanos(time: &Time) -> i64 {
+    time.sec as i64 * 1_000_000_000 + time.nanosec as i64
+}
+
+fn from_nanos(nanos: i64) -> Time {
+    Time {
+        sec: nanos.div_euclid(1_000_000_000) as i32,
+        nanosec: nanos.rem_euclid(1_000_000_000) as u32,
+    }
+}
+
+/// A rigid transform, rotation as a unit quaternion `[x, y, z, w]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Tf {
+    translation: [f64; 3],
+    rotation: [f64; 4],
+}
+
+impl Tf {
+    const IDENTITY: Tf = Tf {
+        translation: [0.0; 3],
+        rotation: [0.0, 0.0, 0.0, 1.0],
+    };
+
+    fn from_msg(t: &Transform) -> Self {
+        let q = &t.rotation;
+        Tf {
+            translation: [t.translation.x, t.translation.y, t.translation.z],
+            rotation: normalize([q.x, q.y, q.z, q.w]),
+        }
+    }
+
+    fn to_msg(self) -> Transform {
+        let [x, y, z] = self.translation;
+        let [qx, qy, qz, qw] = self.rotation;
+        Transform {
+            translation: Vector3 { x, y, z },
+            rotation: Quaternion {
+                x: qx,
+                y: qy,
+                z: qz,
+                w: qw,
+            },
+        }
+    }
+
+    /// `self * other`, i.e. applies `other` first.
+    fn compose(&self, other: &Tf) -> Tf {
+        let t = rotate(&self.rotation, &other.translation);
+        Tf {
+            translation: [
+                self.translation[0] + t[0],
+                self.translation[1] + t[1],
+                self.translation[2] + t[2],
+            ],
+            rotation: normalize(quat_mul(&self.rotation, &other.rotation)),
+        }
+    }
+
+    fn inverse(&self) -> Tf {
+        let [x, y, z, w] = self.rotation;
+        let rotation = [-x, -y, -z, w];
+        let t = rotate(&rotation, &self.translation);
+        Tf {
+            translation: [-t[0], -t[1], -t[2]],
+            rotation,
+        }
+    }
+
+    /// Linear interpolation of the translation and spherical of the rotation.
+    fn interpolate(&self, other: &Tf, ratio: f64) -> Tf {
+        let lerp = |a: f64, b: f64| a + (b - a) * ratio;
+        Tf {
+            translation: [
+                lerp(self.translation[0], other.translation[0]),
+                lerp(self.translation[1], other.translation[1]),
+                lerp(self.translation[2], other.translation[2]),
+            ],
+            rotation: slerp(&self.rotation, &other.rotation, ratio),
+        }
+    }
+}
+
+fn normalize(q: [f64; 4]) -> [f64; 4] {
+    let norm = q.iter().map(|v| v * v).sum::<f64>().sqrt();
+    if norm == 0.0 {
+        Tf::IDENTITY.rotation
+    } else {
+        q.map(|v| v / norm)
+    }
+}
+
+fn quat_mul(a: &[f64; 4], b: &[f64; 4]) -> [f64; 4] {
+    let [ax, ay, az, aw] = *a;
+    let [bx, by, bz, bw] = *b;
+    [
+        aw * bx + ax * bw + ay * bz - az * by,
+        aw * by - ax * bz + ay * bw + az * bx,
+        aw * bz + ax * by - ay * bx + az * bw,
+        aw * bw - ax * bx - ay * by - az * bz,
+    ]
+}
+
+fn rotate(q: &[f64; 4], v: &[f64; 3]) -> [f64; 3] {
+    let [x, y, z, w] = *q;
+    let r = quat_mul(&quat_mul(q, &[v[0], v[1], v[2], 0.0]), &[-x, -y, -z, w]);
+    [r[0], r[1], r[2]]
+}
+
+fn slerp(a: &[f64; 4], b: &[f64; 4], ratio: f64) -> [f64; 4] {
+    let mut dot = a.iter().zip(b).map(|(a, b)| a * b).sum::<f64>();
+    // take the shorter way around.
+    let b = if dot < 0.0 {
+        dot = -dot;
+        b.map(|v| -v)
+    } else {
+        *b
+    };
+    if dot > 0.9995 {
+        // close enough for linear interpolation, and avoids dividing by zero.
+        let mut q = [0.0; 4];
+        for i in 0..4 {
+            q[i] = a[i] + (b[i] - a[i]) * ratio;
+        }
+        return normalize(q);
+    }
+    let theta = dot.acos();
+    let sin_theta = theta.sin();
+    let wa = ((1.0 - ratio) * theta).sin() / sin_theta;
+    let wb = (ratio * theta).sin() / sin_theta;
+    let mut q = [0.0; 4];
+    for i in 0..4 {
+        q[i] = wa * a[i] + wb * b[i];
+    }
+    normalize(q)
+}
+
+/// The transforms from a frame to its parent.
+struct Frame {
+    parent: String,
+    is_static: bool,
+    /// Sorted by time, in nanoseconds.
+    samples: VecDeque<(i64, Tf)>,
+}
+
+impl Frame {
+    fn newest(&self) -> Option<i64> {
+        self.samples.back().map(|(t, _)| *t)
+    }
+
+    /// The transform at `time`, interpolated between the samples around it.
+    fn transform_at(&self, time: i64) -> std::result::Result<Tf, String> {
+        if self.is_static {
+            return Ok(self
+                .samples
+                .back()
+                .map(|(_, tf)| *tf)
+                .unwrap_or(Tf::IDENTITY));
+        }
+        let (Some((oldest, _)), Some((newest, _))) = (self.samples.front(), self.samples.back())
+        else {
+            return Err("no transforms received".into());
+        };
+        if time < *oldest || time > *newest {
+            return Err(format!(
+                "requested time {} is outside of the buffered range [{}, {}]",
+                time, oldest, newest
+            ));
+        }
+        let after = self.samples.partition_point(|(t, _)| *t < time);
+        let (t1, tf1) = self.samples[after];
+        if t1 == time || after == 0 {
+            return Ok(tf1);
+        }
+        let (t0, tf0) = self.samples[after - 1];
+        let ratio = (time - t0) as f64 / (t1 - t0) as f64;
+        Ok(tf0.interpolate(&tf1, ratio))
+    }
+}
+
+/// The transform tree, without any ROS communication.
+struct BufferCore {
+    frames: HashMap<String, Frame>,
+    cache_duration: i64,
+}
+
+impl BufferCore {
+    fn new(cache_duration: Duration) -> Self {
+        BufferCore {
+            frames: HashMap::new(),
+            cache_duration: cache_duration.as_nanos() as i64,
+        }
+    }
+
+    fn set_transform(&mut self, transform: &TransformStamped, is_static: bool) -> Result<()> {
+        let child = transform.child_frame_id.trim_start_matches('/');
+        let parent = transform.header.frame_id.trim_start_matches('/');
+        if child.is_empty() || parent.is_empty() || child == parent {
+            return Err(Error::TransformLookup {
+                target_frame: parent.to_owned(),
+                source_frame: child.to_owned(),
+                reason: "invalid frame ids in transform".into(),
+            });
+        }
+
+        let frame = self
+            .frames
+            .entry(child.to_owned())
+            .or_insert_with(|| Frame {
+                parent: parent.to_owned(),
+                is_static,
+                samples: VecDeque::new(),
+            });
+        // a frame can be moved to another parent, its history is then no longer valid.
+        if frame.parent != parent || frame.is_static != is_static || is_static {
+            frame.parent = parent.to_owned();
+            frame.is_static = is_static;
+            frame.samples.clear();
+        }
+
+        let time = to_nanos(&transform.header.stamp);
+        let tf = Tf::from_msg(&transform.transform);
+        let index = frame.samples.partition_point(|(t, _)| *t < time);
+        match frame.samples.get_mut(index) {
+            Some(sample) if sample.0 == time => sample.1 = tf,
+            _ => frame.samples.insert(index, (time, tf)),
+        }
+
+        // drop samples older than the cache duration.
+        if let Some(newest) = frame.newest() {
+            while frame
+                .samples
+                .front()
+                .is_some_and(|(t, _)| *t < newest - self.cache_duration)
+            {
+                frame.samples.pop_front();
+            }
+        }
+        Ok(())
+    }
+
+    /// The frames from `frame` up to the root of its tree, starting with `frame`.
+    fn path_to_root<'a>(&'a self, frame: &'a str) -> Vec<&'a str> {
+        let mut path = vec![frame];
+        let mut current = frame;
+        while let Some(f) = self.frames.get(current) {
+            // guard against loops in the tree.
+            if path.contains(&f.parent.as_str()) {
+                break;
+            }
+            path.push(&f.parent);
+            current = &f.parent;
+        }
+        path
+    }
+
+    fn lookup_transform(
+        &self, target: &str, source: &str, time: &Time,
+    ) -> Result<TransformStamped> {
+        let target = target.trim_start_matches('/');
+        let source = source.trim_start_matches('/');
+        let lookup_error = |reason: String| Error::TransformLookup {
+            target_frame: target.to_owned(),
+            source_frame: source.to_owned(),
+            reason,
+        };
+
+        let known = |frame: &str| {
+            self.frames.contains_key(frame) || self.frames.values().any(|f| f.parent == frame)
+        };
+        for frame in [target, source] {
+            if !known(frame) {
+                return Err(lookup_error(format!("frame {} does not exist", frame)));
+            }
+        }
+
+        let source_path = self.path_to_root(source);
+        let target_path = self.path_to_root(target);
+        let Some(common) = source_path
+            .iter()
+            .find(|f| target_path.contains(f))
+            .copied()
+        else {
+            return Err(lookup_error("the frames are not connected".into()));
+        };
+        let source_chain = &source_path[..source_path.iter().position(|f| *f == common).unwrap()];
+        let target_chain = &target_path[..target_path.iter().position(|f| *f == common).unwrap()];
+
+        // time zero means the latest time at which all transforms are available.
+        let mut time = to_nanos(time);
+        if time == 0 {
+            time = source_chain
+                .iter()
+                .chain(target_chain)
+                .map(|f| &self.frames[*f])
+                .filter(|f| !f.is_static)
+                .filter_map(|f| f.newest())
+                .min()
+                .unwrap_or(0);
+        }
+
+        // the transforms from the source and target frames to the common ancestor.
+        let to_common = |chain: &[&str]| {
+            chain.iter().try_fold(Tf::IDENTITY, |acc, frame| {
+                let tf = self.frames[*frame]
+                    .transform_at(time)
+                    .map_err(|reason| lookup_error(format!("frame {}: {}", frame, reason)))?;
+                Ok::<_, Error>(tf.compose(&acc))
+            })
+        };
+        let source_to_common = to_common(source_chain)?;
+        let target_to_common = to_common(target_chain)?;
+        let tf = target_to_common.inverse().compose(&source_to_common);
+
+        let mut transform = TransformStamped {
+            child_frame_id: source.to_owned(),
+            transform: tf.to_msg(),
+            ..Default::default()
+        };
+        transform.header.frame_id = target.to_owned();
+        transform.header.stamp = from_nanos(time);
+        Ok(transform)
+    }
+}
+
+struct Tf2Subscriber {
+    rcl_handle: rcl_subscription_t,
+    is_static: bool,
+    core: Weak<Mutex<BufferCore>>,
+}
+
+impl Subscriber_ for Tf2Subscriber {
+    fn handle(&self) -> &rcl_subscription_t {
+        &self.rcl_handle
+    }
+
+    fn handle_incoming(&mut self) -> bool {
+        let Some(core) = self.core.upgrade() else {
+            // the buffer is dropped, destroy the subscriber.
+            return true;
+        };
+        let mut core = core.lock().unwrap();
+        // take everything that is queued, as transforms are often published at high rates.
+        loop {
+            let mut msg_info = rmw_message_info_t::default(); // we dont care for now
+            let mut msg = WrappedNativeMsg::<TFMessage>::new();
+            let ret = unsafe {
+                rcl_take(&self.rcl_handle, msg.void_ptr_mut(), &mut msg_info, std::ptr::null_mut())
+            };
+            if ret != RCL_RET_OK as i32 {
+                break;
+            }
+            for transform in TFMessage::from_native(&msg).transforms {
+                if let Err(e) = core.set_transform(&transform, self.is_static) {
+                    log::debug!("ignoring transform: {}", e);
+                }
+            }
+        }
+        false
+    }
+
+    fn destroy(&mut self, node: &mut rcl_node_t) {
+        unsafe {
+            rcl_subscription_fini(&mut self.rcl_handle, node);
+        }
+    }
+}
+
+/// A time indexed tree of coordinate frame transforms.
+///
+/// The buffer subscribes to `/tf` and `/tf_static` and is updated when the node
+/// is spun. Transforms older than the cache duration are dropped, static
+/// transforms are kept until they are replaced. The subscriptions are removed
+/// when the buffer is dropped.
+#[derive(Clone)]
+pub struct Tf2Buffer {
+    core: Arc<Mutex<BufferCore>>,
+}
+
+impl Tf2Buffer {
+    /// Create a buffer keeping `cache_duration` of transforms, `tf2_ros` uses 10 seconds.
+    pub fn new(node: &mut Node, cache_duration: Duration) -> Result<Tf2Buffer> {
+        let core = Arc::new(Mutex::new(BufferCore::new(cache_duration)));
+        for (topic, qos, is_static) in [("/tf", TF_QOS, false), ("/tf_static", TF_STATIC_QOS, true)]
+        {
+            let rcl_handle =
+                create_subscription_helper(&mut node.node_handle, topic, TFMessage::get_ts(), qos)?;
+            node.subscribers.push(Box::new(Tf2Subscriber {
+                rcl_handle,
+                is_static,
+                core: Arc::downgrade(&core),
+            }));
+        }
+        Ok(Tf2Buffer { core })
+    }
+
+    /// Add a transform to the buffer, as if it was received on `/tf` or `/tf_static`.
+    pub fn set_transform(&self, transform: &TransformStamped, is_static: bool) -> Result<()> {
+        self.core
+            .lock()
+            .unwrap()
+            .set_transform(transform, is_static)
+    }
+
+    /// Get the transform from `source_frame` to `target_frame` at `time`.
+    ///
+    /// The returned transform maps coordinates in `source_frame` to `target_frame`,
+    /// so its `header.frame_id` is `target_frame` and its `child_frame_id` is
+    /// `source_frame`. Transforms between the received samples are interpolated.
+    /// A time of zero means the latest time at which all transforms along the path
+    /// are available. Returns [`Error::TransformLookup`] if a frame is unknown,
+    /// the frames are not connected, or `time` is outside of the buffered range.
+    pub fn lookup_transform(
+        &self, target_frame: &str, source_frame: &str, time: Time,
+    ) -> Result<TransformStamped> {
+        self.core
+            .lock()
+            .unwrap()
+            .lookup_transform(target_frame, source_frame, &time)
+    }
+
+    /// Returns true if [`Tf2Buffer::lookup_transform`] would succeed.
+    pub fn can_transform(&self, target_frame: &str, source_frame: &str, time: Time) -> bool {
+        self.lookup_transform(target_frame, source_frame, time)
+            .is_ok()
+    }
+}
+
+/// Publishes transforms on `/tf`.
+pub struct Tf2Broadcaster {
+    publisher: Publisher<TFMessage>,
+}
+
+impl Tf2Broadcaster {
+    pub fn new(node: &mut Node) -> Result<Tf2Broadcaster> {
+        let publisher = node.create_publisher::<TFMessage>("/tf", TF_QOS)?;
+        Ok(Tf2Broadcaster { publisher })
+    }
+
+    pub fn send_transform(&self, transform: TransformStamped) -> Result<()> {
+        self.send_transforms(vec![transform])
+    }
+
+    pub fn send_transforms(&self, transforms: Vec<TransformStamped>) -> Result<()> {
+        self.publisher.publish(&TFMessage { transforms })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transform(
+        parent: &str, child: &str, time: i64, xyz: [f64; 3], yaw: f64,
+    ) -> TransformStamped {
+        let mut t = TransformStamped {
+            child_frame_id: child.into(),
+            ..Default::default()
+        };
+        t.header.frame_id = parent.into();
+        t.header.stamp = from_nanos(time);
+        t.transform.translation = Vector3 {
+            x: xyz[0],
+            y: xyz[1],
+            z: xyz[2],
+        };
+        t.transform.rotation = Quaternion {
+            x: 0.0,
+            y: 0.0,
+            z: (yaw / 2.0).sin(),
+            w: (yaw / 2.0).cos(),
+        };
+        t
+    }
+
+    fn assert_translation(t: &TransformStamped, xyz: [f64; 3]) {
+        let v = &t.transform.translation;
+        let diff = (v.x - xyz[0]).abs() + (v.y - xyz[1]).abs() + (v.z - xyz[2]).abs();
+        assert!(diff < 1e-9, "{:?} != {:?}", v, xyz);
+    }
+
+    const SEC: i64 = 1_000_000_000;
+
+    #[test]
+    fn test_lookup_chain() {
+        let mut core = BufferCore::new(Duration::from_secs(10));
+        let quarter = std::f64::consts::FRAC_PI_2;
+        core.set_transform(&transform("map", "odom", 0, [1.0, 0.0, 0.0], 0.0), true)
+            .unwrap();
+        core.set_transform(&transform("odom", "base", SEC, [0.0, 1.0, 0.0], quarter), false)
+            .unwrap();
+        core.set_transform(&transform("base", "laser", SEC, [1.0, 0.0, 0.0], 0.0), false)
+            .unwrap();
+
+        let t = core
+            .lookup_transform("map", "laser", &from_nanos(SEC))
+            .unwrap();
+        assert_eq!(t.header.frame_id, "map");
+        assert_eq!(t.child_frame_id, "laser");
+        // the laser is one meter in front of the base, which is turned to the left.
+        assert_translation(&t, [1.0, 2.0, 0.0]);
+
+        let t = core
+            .lookup_transform("laser", "map", &from_nanos(0))
+            .unwrap();
+        assert_eq!(to_nanos(&t.header.stamp), SEC);
+        assert_translation(&t, [-2.0, 1.0, 0.0]);
+
+        let t = core
+            .lookup_transform("laser", "laser", &from_nanos(0))
+            .unwrap();
+        assert_translation(&t, [0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_interpolation() {
+        let mut core = BufferCore::new(Duration::from_secs(10));
+        core.set_transform(&transform("odom", "base", SEC, [0.0, 0.0, 0.0], 0.0), false)
+            .unwrap();
+        core.set_transform(&transform("odom", "base", 3 * SEC, [2.0, 4.0, 0.0], 1.0), false)
+            .unwrap();
+
+        let t = core
+            .lookup_transform("odom", "base", &from_nanos(2 * SEC))
+            .unwrap();
+        assert_translation(&t, [1.0, 2.0, 0.0]);
+        let yaw = 2.0 * t.transform.rotation.z.atan2(t.transform.rotation.w);
+        assert!((yaw - 0.5).abs() < 1e-9, "{}", yaw);
+
+        // no extrapolation.
+        assert!(core
+            .lookup_transform("odom", "base", &from_nanos(4 * SEC))
+            .is_err());
+        assert!(core
+            .lookup_transform("odom", "base", &from_nanos(SEC / 2))
+            .is_err());
+    }
+
+    #[test]
+    fn test_lookup_errors() {
+        let mut core = BufferCore::new(Duration::from_secs(1));
+        core.set_transform(&transform("a", "b", SEC, [0.0; 3], 0.0), false)
+            .unwrap();
+        core.set_transform(&transform("c", "d", SEC, [0.0; 3], 0.0), false)
+            .unwrap();
+        assert!(matches!(
+            core.lookup_transform("a", "unknown", &from_nanos(0)),
+            Err(Error::TransformLookup { .. })
+        ));
+        assert!(core.lookup_transform("a", "d", &from_nanos(0)).is_err());
+        assert!(core
+            .set_transform(&transform("a", "a", SEC, [0.0; 3], 0.0), false)
+            .is_err());
+
+        // old samples are dropped.
+        core.set_transform(&transform("a", "b", 3 * SEC, [0.0; 3], 0.0), false)
+            .unwrap();
+        assert!(core.lookup_transform("a", "b", &from_nanos(SEC)).is_err());
+    }
+}
@@ -12,7 +12,13 @@ use r2r_rcl::{
     rcl_node_t, rcl_subscription_fini, rcl_subscription_t, rcl_take, rcl_time_point_value_t,
     rmw_message_info_t, RCL_RET_OK,
 };
-use std::sync::{Arc, Mutex, Weak};
+use std::{
+    sync::{Arc, Mutex, Weak},
+    time::{Duration, Instant},
+};
+
+/// Default time to wait for `/clock` messages before warning.
+const DEFAULT_CLOCK_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Provides time from `/clock` topic to attached ROS clocks
 ///
@@ -33,6 +39,9 @@ pub(crate) struct TimeSource_ {
     subscriber_state: TimeSourceSubscriberState,
     simulated_time_enabled: bool,
     last_time_msg: rcl_time_point_value_t,
+    clock_timeout: Option<Duration>,
+    last_time_msg_received: Instant,
+    clock_timeout_warned: bool,
 }
 
 #[derive(Copy, Clone)]
@@ -98,6 +107,8 @@ impl TimeSource {
         }
 
         inner.simulated_time_enabled = true;
+        inner.last_time_msg_received = Instant::now();
+        inner.clock_timeout_warned = false;
 
         match inner.subscriber_state {
             TimeSourceSubscriberState::None => {
@@ -147,6 +158,35 @@ impl TimeSource {
             inner.subscriber_state = TimeSourceSubscriberState::ToBeDestroyed;
         }
     }
+
+    /// Sets how long to wait for messages on the `"/clock"` topic before warning
+    ///
+    /// While simulated time is enabled the ROS clocks (and any timers using them) are paused
+    /// until a new [`rosgraph_msgs::msg::Clock`] message arrives. If no message has been
+    /// received for `timeout`, a warning is logged. `None` disables the warning. Defaults to
+    /// 5 seconds.
+    pub fn set_clock_timeout(&self, timeout: Option<Duration>) {
+        self.inner.lock().unwrap().clock_timeout = timeout;
+    }
+
+    /// Warns (once) if simulated time is enabled but `"/clock"` has gone silent.
+    pub(crate) fn check_clock_timeout(&self, logger: &str) {
+        let mut inner = self.inner.lock().unwrap();
+        let Some(timeout) = inner.clock_timeout else {
+            return;
+        };
+        if inner.simulated_time_enabled
+            && !inner.clock_timeout_warned
+            && inner.last_time_msg_received.elapsed() > timeout
+        {
+            crate::log_warn!(
+                logger,
+                "no message received on /clock for {:?}, ROS time is paused",
+                timeout
+            );
+            inner.clock_timeout_warned = true;
+        }
+    }
 }
 
 impl TimeSource_ {
@@ -156,6 +196,9 @@ impl TimeSource_ {
             subscriber_state: TimeSourceSubscriberState::None,
             simulated_time_enabled: false,
             last_time_msg: 0,
+            clock_timeout: Some(DEFAULT_CLOCK_TIMEOUT),
+            last_time_msg_received: Instant::now(),
+            clock_timeout_warned: false,
         }
     }
 
@@ -181,6 +224,8 @@ impl TimeSource_ {
     fn set_clock_time(&mut self, time_msg: Time) {
         let time = time_msg.into();
         self.last_time_msg = time;
+        self.last_time_msg_received = Instant::now();
+        self.clock_timeout_warned = false;
         self.for_each_managed_clock(|clock| {
             // This should never panic:
             // This could only fail if the clock is invalid or not RosTime, but the clock is
@@ -0,0 +1,80 @@
+//! Integration with the tokio runtime.
+//!
+//! Enabled with the `tokio` feature. Instead of calling
+//! [`Node::spin_once`] in a loop next to a `LocalPool`, the node can
+//! be shared behind an `Arc<Mutex<_>>` and driven by a task on the
+//! tokio runtime. Streams and futures created by the node
+//! (subscriptions, service requests, timers etc) can then be awaited
+//! from any tokio task, on any worker thread.
+//!
+//! ``` rust
+//! use std::sync::{Arc, Mutex};
+//! use futures::stream::StreamExt;
+//! use r2r::QosProfile;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let ctx = r2r::Context::create()?;
+//!     let node = Arc::new(Mutex::new(r2r::Node::create(ctx, "node", "namespace")?));
+//!     let mut sub = node
+//!         .lock()
+//!         .unwrap()
+//!         .subscribe::<r2r::std_msgs::msg::String>("/topic", QosProfile::default())?;
+//!
+//!     tokio::spawn(r2r::tokio::spin(node.clone()));
+//!
+//!     while let Some(msg) = sub.next().await {
+//!         println!("got new msg: {}", msg.data);
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::nodes::Node;
+
+/// How long each call to `spin_once` may block waiting for events.
+///
+/// The node is locked during this time, so keep it short to let other
+/// tasks create publishers, subscribers etc on the same node.
+const SPIN_TIMEOUT: Duration = Duration::from_millis(10);
+
+/// Spin a shared node on the tokio runtime.
+///
+/// The returned future calls [`Node::spin_once`] repeatedly, each time
+/// on tokio's blocking thread pool (`tokio::task::spawn_blocking`) so
+/// that the wait on the rcl wait set never stalls the async worker
+/// threads. Wakeups of streams and futures are delivered to whatever
+/// tasks are awaiting them.
+///
+/// The future completes when the ROS context is shut down or if the
+/// node mutex is poisoned. It is meant to be passed to `tokio::spawn`.
+pub async fn spin(node: Arc<Mutex<Node>>) {
+    loop {
+        let n = node.clone();
+        let still_valid = ::tokio::task::spawn_blocking(move || {
+            let Ok(mut node) = n.lock() else {
+                return false;
+            };
+            if !node.context.is_valid() {
+                return false;
+            }
+            node.spin_once(SPIN_TIMEOUT);
+            true
+        })
+        .await;
+
+        match still_valid {
+            Ok(true) => ::tokio::task::yield_now().await,
+            Ok(false) => break,
+            Err(e) => {
+                log::error!("node spin task failed: {}", e);
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,206 @@
+#![cfg(r2r__statistics_msgs__msg__MetricsMessage)]
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    msg_types::{
+        generated_msgs::{builtin_interfaces, statistics_msgs},
+        *,
+    },
+    publishers::Publisher,
+    subscribers::{MessageInfo, MessageQueue, Subscriber_},
+};
+use r2r_rcl::*;
+
+use statistics_msgs::msg::{MetricsMessage, StatisticDataPoint, StatisticDataType};
+
+/// Running mean, variance, min and max of a metric (Welford's algorithm).
+#[derive(Default)]
+struct Collector {
+    count: u64,
+    mean: f64,
+    m2: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Collector {
+    fn add(&mut self, value: f64) {
+        if self.count == 0 {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    fn data_points(&self) -> Vec<StatisticDataPoint> {
+        // same as rclcpp, NaN when there are no samples.
+        let (mean, min, max, stddev) = if self.count == 0 {
+            (f64::NAN, f64::NAN, f64::NAN, f64::NAN)
+        } else {
+            (self.mean, self.min, self.max, (self.m2 / self.count as f64).sqrt())
+        };
+        let point = |data_type, data| StatisticDataPoint { data_type, data };
+        vec![
+            point(StatisticDataType::STATISTICS_DATA_TYPE_AVERAGE as u8, mean),
+            point(StatisticDataType::STATISTICS_DATA_TYPE_MINIMUM as u8, min),
+            point(StatisticDataType::STATISTICS_DATA_TYPE_MAXIMUM as u8, max),
+            point(StatisticDataType::STATISTICS_DATA_TYPE_STDDEV as u8, stddev),
+            point(StatisticDataType::STATISTICS_DATA_TYPE_SAMPLE_COUNT as u8, self.count as f64),
+        ]
+    }
+}
+
+fn to_builtin_time(t: SystemTime) -> builtin_interfaces::msg::Time {
+    let d = t.duration_since(UNIX_EPOCH).unwrap_or_default();
+    builtin_interfaces::msg::Time {
+        sec: d.as_secs() as i32,
+        nanosec: d.subsec_nanos(),
+    }
+}
+
+/// Statistics collected for one subscription, published every period.
+pub(crate) struct TopicStatistics {
+    node_name: String,
+    period: Duration,
+    publisher: Publisher<MetricsMessage>,
+    window_start: SystemTime,
+    last_received: Option<SystemTime>,
+    message_age: Collector,
+    message_period: Collector,
+}
+
+impl TopicStatistics {
+    pub(crate) fn new(
+        node_name: String, period: Duration, publisher: Publisher<MetricsMessage>,
+    ) -> Self {
+        TopicStatistics {
+            node_name,
+            period,
+            publisher,
+            window_start: SystemTime::now(),
+            last_received: None,
+            message_age: Collector::default(),
+            message_period: Collector::default(),
+        }
+    }
+
+    fn record(&mut self, info: &MessageInfo) {
+        let received = SystemTime::now();
+        if let Some(age) = info
+            .source_timestamp
+            .and_then(|source| received.duration_since(source).ok())
+        {
+            self.message_age.add(age.as_secs_f64() * 1000.0);
+        }
+        if let Some(period) = self
+            .last_received
+            .and_then(|last| received.duration_since(last).ok())
+        {
+            self.message_period.add(period.as_secs_f64() * 1000.0);
+        }
+        self.last_received = Some(received);
+    }
+
+    /// Publish the statistics of the current window if the period has passed.
+    pub(crate) fn publish_if_due(&mut self) {
+        let now = SystemTime::now();
+        if now.duration_since(self.window_start).unwrap_or_default() < self.period {
+            return;
+        }
+
+        let window_start = to_builtin_time(self.window_start);
+        let window_stop = to_builtin_time(now);
+        for (source, collector) in [
+            ("message_age", &self.message_age),
+            ("message_period", &self.message_period),
+        ] {
+            let msg = MetricsMessage {
+                measurement_source_name: self.node_name.clone(),
+                metrics_source: source.to_owned(),
+                unit: "ms".to_owned(),
+                window_start: window_start.clone(),
+                window_stop: window_stop.clone(),
+                statistics: collector.data_points(),
+            };
+            if let Err(e) = self.publisher.publish(&msg) {
+                log::debug!("could not publish topic statistics: {}", e);
+            }
+        }
+
+        self.window_start = now;
+        self.message_age = Collector::default();
+        self.message_period = Collector::default();
+    }
+
+    pub(crate) fn into_publisher(self) -> Publisher<MetricsMessage> {
+        self.publisher
+    }
+}
+
+pub struct TypedSubscriberWithStatistics<T>
+where
+    T: WrappedTypesupport,
+{
+    pub rcl_handle: rcl_subscription_t,
+    pub(crate) queue: Arc<MessageQueue<T>>,
+    pub(crate) statistics: Arc<Mutex<TopicStatistics>>,
+}
+
+impl<T: 'static> Subscriber_ for TypedSubscriberWithStatistics<T>
+where
+    T: WrappedTypesupport,
+{
+    fn handle(&self) -> &rcl_subscription_t {
+        &self.rcl_handle
+    }
+
+    fn handle_incoming(&mut self) -> bool {
+        if self.queue.is_closed() {
+            // user dropped the handle to the stream, signal removal.
+            return true;
+        }
+        if !self.queue.wait_for_room() {
+            // leave the message in the middleware until there is room.
+            return false;
+        }
+        let mut msg_info = rmw_message_info_t::default();
+        let mut msg = WrappedNativeMsg::<T>::new();
+        let ret = unsafe {
+            rcl_take(&self.rcl_handle, msg.void_ptr_mut(), &mut msg_info, std::ptr::null_mut())
+        };
+        if ret == RCL_RET_OK as i32 {
+            self.statistics
+                .lock()
+                .unwrap()
+                .record(&MessageInfo::from(&msg_info));
+            self.queue.push(T::from_native(&msg));
+        }
+        false
+    }
+
+    fn destroy(&mut self, node: &mut rcl_node_t) {
+        unsafe {
+            rcl_subscription_fini(&mut self.rcl_handle, node);
+        }
+    }
+}
+
+impl<T> Drop for TypedSubscriberWithStatistics<T>
+where
+    T: WrappedTypesupport,
+{
+    fn drop(&mut self) {
+        // end the stream.
+        self.queue.close();
+    }
+}
@@ -5,8 +5,10 @@ use std::{
         atomic::{AtomicBool, Ordering},
         Mutex, MutexGuard,
     },
+    time::{Duration, Instant},
 };
 
+use crate::{error::*, nodes::Node};
 use lazy_static::lazy_static;
 
 lazy_static! {
@@ -22,10 +24,8 @@ pub(crate) fn log_guard() -> MutexGuard<'static, ()> {
 // as a work around using onwned is_init
 static IS_INIT: AtomicBool = AtomicBool::new(false);
 
-/// Don't call this directly, use the logging macros instead.
-#[doc(hidden)]
-pub fn log(msg: &str, logger_name: &str, file: &str, line: u32, severity: LogSeverity) {
-    let _guard = log_guard();
+/// Initialize rcutils logging if it is not already, must hold the log guard.
+fn ensure_logging_initialized() -> Result<()> {
     let is_init = if cfg!(target_os = "windows") {
         IS_INIT.load(Ordering::Relaxed)
     } else {
@@ -38,9 +38,19 @@ pub fn log(msg: &str, logger_name: &str, file: &str, line: u32, severity: LogSev
         let ret = unsafe { rcutils_logging_initialize() };
         if ret != RCL_RET_OK as i32 {
             log::error!("could not create logging system (Err: {})", ret);
-            return;
+            return Err(Error::from_rcl_error(ret));
         }
     }
+    Ok(())
+}
+
+/// Don't call this directly, use the logging macros instead.
+#[doc(hidden)]
+pub fn log(msg: &str, logger_name: &str, file: &str, line: u32, severity: LogSeverity) {
+    let _guard = log_guard();
+    if ensure_logging_initialized().is_err() {
+        return;
+    }
     // currently not possible to get function name in rust.
     // see https://github.com/rust-lang/rfcs/pull/2818
     let function = CString::new("").unwrap();
@@ -65,7 +75,130 @@ pub fn log(msg: &str, logger_name: &str, file: &str, line: u32, severity: LogSev
     }
 }
 
+/// Set the log level of the logger `logger_name`.
+///
+/// Messages below `severity` are dropped. See also [`Node::set_log_level`].
+pub fn set_log_level(logger_name: &str, severity: LogSeverity) -> Result<()> {
+    let _guard = log_guard();
+    ensure_logging_initialized()?;
+    let logger_name = CString::new(logger_name).map_err(|_| Error::RCL_RET_INVALID_ARGUMENT)?;
+    let ret = unsafe {
+        rcutils_logging_set_logger_level(logger_name.as_ptr(), severity.to_native() as i32)
+    };
+    if ret == RCL_RET_OK as i32 {
+        Ok(())
+    } else {
+        Err(Error::from_rcl_error(ret))
+    }
+}
+
+/// Set the log level of all loggers without a level of their own.
+pub fn set_global_log_level(severity: LogSeverity) -> Result<()> {
+    let _guard = log_guard();
+    ensure_logging_initialized()?;
+    unsafe { rcutils_logging_set_default_logger_level(severity.to_native() as i32) };
+    Ok(())
+}
+
+/// Something to log with, the first argument of the logging macros.
+///
+/// This is the logger name itself, or a [`Node`], which logs with its own logger.
+pub trait LoggerName {
+    fn logger_name(&self) -> &str;
+}
+
+impl LoggerName for str {
+    fn logger_name(&self) -> &str {
+        self
+    }
+}
+
+impl LoggerName for String {
+    fn logger_name(&self) -> &str {
+        self
+    }
+}
+
+impl LoggerName for Node {
+    fn logger_name(&self) -> &str {
+        self.logger()
+    }
+}
+
+impl<T: LoggerName + ?Sized> LoggerName for &T {
+    fn logger_name(&self) -> &str {
+        (**self).logger_name()
+    }
+}
+
+/// Don't use this directly, use the throttled logging macros instead.
+#[doc(hidden)]
+pub struct LogThrottle {
+    last: Mutex<Option<Instant>>,
+}
+
+impl LogThrottle {
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        LogThrottle {
+            last: Mutex::new(None),
+        }
+    }
+
+    /// Returns true if nothing was logged within the last `period`.
+    pub fn allow(&self, period: impl LogPeriod) -> bool {
+        let period = period.to_duration();
+        let mut last = self.last.lock().unwrap();
+        let now = Instant::now();
+        match *last {
+            Some(t) if now.duration_since(t) < period => false,
+            _ => {
+                *last = Some(now);
+                true
+            }
+        }
+    }
+}
+
+/// Don't use this directly, use the log once macros instead.
+#[doc(hidden)]
+pub struct LogOnce {
+    logged: AtomicBool,
+}
+
+impl LogOnce {
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        LogOnce {
+            logged: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns true the first time it is called.
+    pub fn allow(&self) -> bool {
+        !self.logged.swap(true, Ordering::Relaxed)
+    }
+}
+
+/// The period of the throttled logging macros, a [`Duration`] or seconds as `f64`.
+pub trait LogPeriod {
+    fn to_duration(self) -> Duration;
+}
+
+impl LogPeriod for Duration {
+    fn to_duration(self) -> Duration {
+        self
+    }
+}
+
+impl LogPeriod for f64 {
+    fn to_duration(self) -> Duration {
+        Duration::try_from_secs_f64(self).unwrap_or(Duration::ZERO)
+    }
+}
+
 /// Logging severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LogSeverity {
     Unset,
     Debug,
@@ -76,7 +209,7 @@ pub enum LogSeverity {
 }
 
 impl LogSeverity {
-    fn to_native(&self) -> RCUTILS_LOG_SEVERITY {
+    fn to_native(self) -> RCUTILS_LOG_SEVERITY {
         use RCUTILS_LOG_SEVERITY::*;
         match self {
             LogSeverity::Unset => RCUTILS_LOG_SEVERITY_UNSET,
@@ -94,7 +227,37 @@ impl LogSeverity {
 #[macro_export]
 macro_rules! __impl_log {
     ($logger_name:expr, $msg:expr, $file:expr, $line:expr, $severity:expr) => {{
-        $crate::log(&std::fmt::format($msg), $logger_name, $file, $line, $severity);
+        $crate::log(
+            &std::fmt::format($msg),
+            $crate::LoggerName::logger_name(&$logger_name),
+            $file,
+            $line,
+            $severity,
+        );
+    }};
+}
+
+// A helper macro to log the message at most once per period.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_log_throttle {
+    ($logger_name:expr, $period:expr, $msg:expr, $file:expr, $line:expr, $severity:expr) => {{
+        static THROTTLE: $crate::LogThrottle = $crate::LogThrottle::new();
+        if THROTTLE.allow($period) {
+            $crate::__impl_log!($logger_name, $msg, $file, $line, $severity)
+        }
+    }};
+}
+
+// A helper macro to log the message only the first time.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_log_once {
+    ($logger_name:expr, $msg:expr, $file:expr, $line:expr, $severity:expr) => {{
+        static ONCE: $crate::LogOnce = $crate::LogOnce::new();
+        if ONCE.allow() {
+            $crate::__impl_log!($logger_name, $msg, $file, $line, $severity)
+        }
     }};
 }
 
@@ -107,6 +270,26 @@ macro_rules! log_debug {
     }}
 }
 
+/// Debug log message, logged at most once per `period`.
+///
+/// The period is a [`Duration`](std::time::Duration) or seconds as `f64`.
+#[macro_export]
+macro_rules! log_debug_throttle {
+    ($logger_name:expr, $period:expr, $($args:tt)*) => {{
+        $crate::__impl_log_throttle!($logger_name, $period, format_args!($($args)*),
+                                     file!(), line!(), $crate::LogSeverity::Debug)
+    }}
+}
+
+/// Debug log message, logged only the first time the call is reached.
+#[macro_export]
+macro_rules! log_debug_once {
+    ($logger_name:expr, $($args:tt)*) => {{
+        $crate::__impl_log_once!($logger_name, format_args!($($args)*),
+                                 file!(), line!(), $crate::LogSeverity::Debug)
+    }}
+}
+
 /// Info log message.
 #[macro_export]
 macro_rules! log_info {
@@ -116,6 +299,26 @@ macro_rules! log_info {
     }}
 }
 
+/// Info log message, logged at most once per `period`.
+///
+/// The period is a [`Duration`](std::time::Duration) or seconds as `f64`.
+#[macro_export]
+macro_rules! log_info_throttle {
+    ($logger_name:expr, $period:expr, $($args:tt)*) => {{
+        $crate::__impl_log_throttle!($logger_name, $period, format_args!($($args)*),
+                                     file!(), line!(), $crate::LogSeverity::Info)
+    }}
+}
+
+/// Info log message, logged only the first time the call is reached.
+#[macro_export]
+macro_rules! log_info_once {
+    ($logger_name:expr, $($args:tt)*) => {{
+        $crate::__impl_log_once!($logger_name, format_args!($($args)*),
+                                 file!(), line!(), $crate::LogSeverity::Info)
+    }}
+}
+
 /// Warning log message.
 #[macro_export]
 macro_rules! log_warn {
@@ -125,6 +328,26 @@ macro_rules! log_warn {
     }}
 }
 
+/// Warning log message, logged at most once per `period`.
+///
+/// The period is a [`Duration`](std::time::Duration) or seconds as `f64`.
+#[macro_export]
+macro_rules! log_warn_throttle {
+    ($logger_name:expr, $period:expr, $($args:tt)*) => {{
+        $crate::__impl_log_throttle!($logger_name, $period, format_args!($($args)*),
+                                     file!(), line!(), $crate::LogSeverity::Warn)
+    }}
+}
+
+/// Warning log message, logged only the first time the call is reached.
+#[macro_export]
+macro_rules! log_warn_once {
+    ($logger_name:expr, $($args:tt)*) => {{
+        $crate::__impl_log_once!($logger_name, format_args!($($args)*),
+                                 file!(), line!(), $crate::LogSeverity::Warn)
+    }}
+}
+
 /// Error log message.
 #[macro_export]
 macro_rules! log_error {
@@ -134,6 +357,26 @@ macro_rules! log_error {
     }}
 }
 
+/// Error log message, logged at most once per `period`.
+///
+/// The period is a [`Duration`](std::time::Duration) or seconds as `f64`.
+#[macro_export]
+macro_rules! log_error_throttle {
+    ($logger_name:expr, $period:expr, $($args:tt)*) => {{
+        $crate::__impl_log_throttle!($logger_name, $period, format_args!($($args)*),
+                                     file!(), line!(), $crate::LogSeverity::Error)
+    }}
+}
+
+/// Error log message, logged only the first time the call is reached.
+#[macro_export]
+macro_rules! log_error_once {
+    ($logger_name:expr, $($args:tt)*) => {{
+        $crate::__impl_log_once!($logger_name, format_args!($($args)*),
+                                 file!(), line!(), $crate::LogSeverity::Error)
+    }}
+}
+
 /// Fatal log message.
 #[macro_export]
 macro_rules! log_fatal {
@@ -143,6 +386,26 @@ macro_rules! log_fatal {
     }}
 }
 
+/// Fatal log message, logged at most once per `period`.
+///
+/// The period is a [`Duration`](std::time::Duration) or seconds as `f64`.
+#[macro_export]
+macro_rules! log_fatal_throttle {
+    ($logger_name:expr, $period:expr, $($args:tt)*) => {{
+        $crate::__impl_log_throttle!($logger_name, $period, format_args!($($args)*),
+                                     file!(), line!(), $crate::LogSeverity::Fatal)
+    }}
+}
+
+/// Fatal log message, logged only the first time the call is reached.
+#[macro_export]
+macro_rules! log_fatal_once {
+    ($logger_name:expr, $($args:tt)*) => {{
+        $crate::__impl_log_once!($logger_name, format_args!($($args)*),
+                                 file!(), line!(), $crate::LogSeverity::Fatal)
+    }}
+}
+
 /// Causes compile time error if `use_sim_time` is unsupported.
 #[cfg(r2r__rosgraph_msgs__msg__Clock)]
 #[macro_export]
@@ -166,4 +429,14 @@ fn test_log() {
     log_warn!("log_test", "warn msg");
     log_error!("log_test", "error msg");
     log_fatal!("log_test", "fatal msg");
+    log_info_throttle!("log_test", Duration::from_secs(1), "throttled msg");
+    log_warn_throttle!("log_test", 0.5, "throttled msg");
+    log_error_once!("log_test", "once msg");
+}
+
+#[test]
+fn test_log_once() {
+    let once = LogOnce::new();
+    assert!(once.allow());
+    assert!(!once.allow());
 }
@@ -0,0 +1,242 @@
+use std::{
+    sync::{Arc, Mutex, Weak},
+    time::Duration,
+};
+
+use crate::{context::Context, error::*, nodes::Node};
+use r2r_rcl::*;
+
+/// The number of entities of each kind in a wait set, or the position of
+/// the entities of one node in the arrays of a wait set.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct WaitSetCounts {
+    pub subscriptions: usize,
+    pub guard_conditions: usize,
+    pub timers: usize,
+    pub clients: usize,
+    pub services: usize,
+    pub events: usize,
+}
+
+impl WaitSetCounts {
+    pub fn add(self, other: WaitSetCounts) -> WaitSetCounts {
+        WaitSetCounts {
+            subscriptions: self.subscriptions + other.subscriptions,
+            guard_conditions: self.guard_conditions + other.guard_conditions,
+            timers: self.timers + other.timers,
+            clients: self.clients + other.clients,
+            services: self.services + other.services,
+            events: self.events + other.events,
+        }
+    }
+
+    /// Initialize a zero initialized wait set with room for the entities.
+    pub unsafe fn init_wait_set(&self, ws: &mut rcl_wait_set_t, context: &mut rcl_context_t) {
+        rcl_wait_set_init(
+            ws,
+            self.subscriptions,
+            self.guard_conditions,
+            self.timers,
+            self.clients,
+            self.services,
+            self.events,
+            context,
+            rcutils_get_default_allocator(),
+        );
+        rcl_wait_set_clear(ws);
+    }
+}
+
+type WaitSetNodes<'a> = Arc<Mutex<Vec<(u64, &'a mut Node)>>>;
+
+/// Waits for several nodes in a single `rcl_wait` call.
+///
+/// This is an alternative to calling [`Node::spin_once`] on each node in
+/// turn. `wait` blocks until an entity of any of the nodes is ready, and
+/// `dispatch` then handles the ready entities of all nodes, like `spin_once`
+/// does for a single node:
+///
+/// ``` rust,ignore
+/// let wait_set = WaitSet::new(&ctx)?;
+/// let _a = wait_set.add_node(&mut node_a)?;
+/// let _b = wait_set.add_node(&mut node_b)?;
+/// loop {
+///     let ready = wait_set.wait(Duration::from_millis(100))?;
+///     wait_set.dispatch(ready)?;
+/// }
+/// ```
+///
+/// A node is part of the wait set until its [`WaitSetToken`] is dropped.
+pub struct WaitSet<'a> {
+    context: Context,
+    nodes: WaitSetNodes<'a>,
+    next_id: u64,
+}
+
+/// Keeps a node in a [`WaitSet`], dropping the token removes the node.
+pub struct WaitSetToken<'a> {
+    id: u64,
+    nodes: Weak<Mutex<Vec<(u64, &'a mut Node)>>>,
+}
+
+impl Drop for WaitSetToken<'_> {
+    fn drop(&mut self) {
+        if let Some(nodes) = self.nodes.upgrade() {
+            nodes.lock().unwrap().retain(|(id, _)| *id != self.id);
+        }
+    }
+}
+
+/// The result of [`WaitSet::wait`], to be handled by [`WaitSet::dispatch`].
+pub struct WaitSetReady {
+    ws: rcl_wait_set_t,
+    timed_out: bool,
+    // the nodes waited for, with the position of their entities in the
+    // wait set and whether the graph guard condition was added.
+    layout: Vec<(u64, WaitSetCounts, bool)>,
+}
+
+unsafe impl Send for WaitSetReady {}
+
+impl WaitSetReady {
+    /// Returns true if no entity became ready before the timeout.
+    pub fn timed_out(&self) -> bool {
+        self.timed_out
+    }
+}
+
+impl Drop for WaitSetReady {
+    fn drop(&mut self) {
+        unsafe {
+            rcl_wait_set_fini(&mut self.ws);
+        }
+    }
+}
+
+impl<'a> WaitSet<'a> {
+    /// Create an empty wait set for nodes of the given context.
+    pub fn new(context: &Context) -> Result<WaitSet<'a>> {
+        if !context.is_valid() {
+            return Err(Error::RCL_RET_NOT_INIT);
+        }
+        Ok(WaitSet {
+            context: context.clone(),
+            nodes: Arc::new(Mutex::new(Vec::new())),
+            next_id: 0,
+        })
+    }
+
+    /// Add a node to the wait set.
+    ///
+    /// The node is borrowed until the returned token is dropped, which removes
+    /// the node from the wait set again. The node must belong to the context
+    /// of the wait set.
+    pub fn add_node(&mut self, node: &'a mut Node) -> Result<WaitSetToken<'a>> {
+        if !Arc::ptr_eq(&node.context.context_handle, &self.context.context_handle) {
+            return Err(Error::RCL_RET_INVALID_ARGUMENT);
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        self.nodes.lock().unwrap().push((id, node));
+        Ok(WaitSetToken {
+            id,
+            nodes: Arc::downgrade(&self.nodes),
+        })
+    }
+
+    /// Wait until an entity of any of the nodes is ready, or the timeout expires.
+    ///
+    /// Without any entities to wait for, this sleeps for the timeout and
+    /// reports a timeout.
+    pub fn wait(&self, timeout: Duration) -> Result<WaitSetReady> {
+        let mut nodes = self.nodes.lock().unwrap();
+
+        // add the plain entities of all nodes first, as the entities of
+        // actions are expected at the end of the arrays, see spin_once.
+        let mut layout = Vec::with_capacity(nodes.len());
+        let mut total = WaitSetCounts::default();
+        let mut action_total = WaitSetCounts::default();
+        for (id, node) in nodes.iter_mut() {
+            let wait_for_graph = node.prepare_wait();
+            let (counts, action_counts) = node.wait_set_counts(wait_for_graph);
+            layout.push((*id, total, wait_for_graph));
+            total = total.add(counts);
+            action_total = action_total.add(action_counts);
+        }
+
+        let mut ready = WaitSetReady {
+            ws: unsafe { rcl_get_zero_initialized_wait_set() },
+            timed_out: false,
+            layout,
+        };
+        {
+            let mut ctx = self.context.context_handle.lock().unwrap();
+            unsafe {
+                total
+                    .add(action_total)
+                    .init_wait_set(&mut ready.ws, ctx.as_mut());
+            }
+        }
+        for ((_, node), (_, _, wait_for_graph)) in nodes.iter().zip(&ready.layout) {
+            node.add_to_wait_set(&mut ready.ws, *wait_for_graph);
+        }
+        for (_, node) in nodes.iter() {
+            node.add_actions_to_wait_set(&mut ready.ws);
+        }
+
+        let ret = unsafe { rcl_wait(&mut ready.ws, timeout.as_nanos() as i64) };
+        if ret == RCL_RET_WAIT_SET_EMPTY as i32 {
+            // nothing to wait for, e.g. after all nodes were removed, which
+            // rcl reports without waiting.
+            drop(nodes);
+            std::thread::sleep(timeout);
+            ready.timed_out = true;
+        } else if ret == RCL_RET_TIMEOUT as i32 {
+            ready.timed_out = true;
+        } else if ret != RCL_RET_OK as i32 {
+            return Err(Error::from_rcl_error(ret));
+        }
+        Ok(ready)
+    }
+
+    /// Handle the ready entities of all nodes, and poll their async tasks.
+    ///
+    /// Nodes removed from the wait set since `wait` are skipped.
+    pub fn dispatch(&self, ready: WaitSetReady) -> Result<()> {
+        let mut nodes = self.nodes.lock().unwrap();
+        if !ready.timed_out {
+            for (id, offsets, wait_for_graph) in &ready.layout {
+                if let Some((_, node)) = nodes.iter_mut().find(|(n, _)| n == id) {
+                    node.dispatch_wait_set(&ready.ws, *offsets, *wait_for_graph);
+                }
+            }
+        }
+        drop(ready);
+
+        // last, as the handling above may have woken up some of the tasks.
+        for (_, node) in nodes.iter_mut() {
+            node.poll_async_tasks();
+        }
+        Ok(())
+    }
+}
+
+/// Wait for and handle the ready entities of several nodes, in a single `rcl_wait`.
+///
+/// The multi-node equivalent of [`Node::spin_once`], to be called in a loop.
+/// All nodes must belong to the same context. See [`WaitSet`] for waiting and
+/// dispatching in separate steps.
+pub fn spin_many(nodes: Vec<&mut Node>, timeout: Duration) -> Result<()> {
+    let Some(context) = nodes.first().map(|node| node.context.clone()) else {
+        // like a wait set without nodes.
+        std::thread::sleep(timeout);
+        return Ok(());
+    };
+    let mut wait_set = WaitSet::new(&context)?;
+    let _tokens = nodes
+        .into_iter()
+        .map(|node| wait_set.add_node(node))
+        .collect::<Result<Vec<_>>>()?;
+    let ready = wait_set.wait(timeout)?;
+    wait_set.dispatch(ready)
+}
@@ -0,0 +1,70 @@
+use futures::{executor::LocalPool, stream::StreamExt, task::LocalSpawnExt};
+use r2r::{test_msgs::action::Fibonacci, GoalStatus};
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+// accepts each goal and drops the handle, or keeps it with manual goals.
+fn run_goal(name: &str, manual: bool) -> Result<Option<GoalStatus>, r2r::Error> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, name, "")?;
+    let action_name = format!("/{name}_fibonacci");
+    let mut requests = node.create_action_server::<Fibonacci::Action>(&action_name)?;
+    let client = node.create_action_client::<Fibonacci::Action>(&action_name)?;
+    let server_available = r2r::Node::is_available(&client)?;
+
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let kept = Arc::new(Mutex::new(Vec::new()));
+    let task_kept = kept.clone();
+    spawner
+        .spawn_local(async move {
+            while let Some(req) = requests.next().await {
+                let (goal, _cancel) = req.accept().expect("could not accept goal");
+                if manual {
+                    task_kept.lock().unwrap().push(goal.disable_abort_on_drop());
+                }
+            }
+        })
+        .unwrap();
+
+    let outcome = Arc::new(Mutex::new(None));
+    let task_outcome = outcome.clone();
+    spawner
+        .spawn_local(async move {
+            server_available.await.unwrap();
+            let (_goal, result, _feedback) = client
+                .send_goal_request(Fibonacci::Goal { order: 5 })
+                .unwrap()
+                .await
+                .unwrap();
+            let (status, _) = result.await.unwrap();
+            *task_outcome.lock().unwrap() = Some(status);
+        })
+        .unwrap();
+
+    let start = Instant::now();
+    while outcome.lock().unwrap().is_none() && start.elapsed() < Duration::from_secs(2) {
+        node.spin_once(Duration::from_millis(10));
+        pool.run_until_stalled();
+    }
+    let status = outcome.lock().unwrap().take();
+    Ok(status)
+}
+
+#[test]
+fn dropped_goal_is_aborted() -> Result<(), Box<dyn std::error::Error>> {
+    let status = run_goal("testnode_goal_dropped", false)?;
+    assert_eq!(status, Some(GoalStatus::Aborted));
+    Ok(())
+}
+
+#[test]
+fn manual_goal_is_kept() -> Result<(), Box<dyn std::error::Error>> {
+    // without a result the client is still waiting.
+    let status = run_goal("testnode_goal_manual", true)?;
+    assert_eq!(status, None);
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use futures::{
+    executor::LocalPool,
+    future::join_all,
+    stream::{Stream, StreamExt},
+    task::{LocalSpawn, LocalSpawnExt},
+};
+use r2r::{test_msgs::action::Fibonacci, GoalStatus};
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+// accepts all goals and cancels them when asked to.
+async fn cancel_server(
+    spawner: impl LocalSpawn, node: Arc<Mutex<r2r::Node>>,
+    mut requests: impl Stream<Item = r2r::ActionServerGoalRequest<Fibonacci::Action>> + Unpin,
+) {
+    while let Some(req) = requests.next().await {
+        let (mut g, mut cancel) = req.accept().expect("could not accept goal");
+        let node = node.clone();
+        spawner
+            .spawn_local(async move {
+                if let Some(request) = cancel.next().await {
+                    request.accept();
+                    let mut timer = node
+                        .lock()
+                        .unwrap()
+                        .create_wall_timer(Duration::from_millis(10))
+                        .unwrap();
+                    while !g.is_cancelling().unwrap() {
+                        timer.tick().await.unwrap();
+                    }
+                    g.cancel(Fibonacci::Result { sequence: vec![] }).unwrap();
+                }
+            })
+            .unwrap();
+    }
+}
+
+#[test]
+fn cancel_all_goals() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let node = Arc::new(Mutex::new(r2r::Node::create(ctx, "testnode_action_cancel", "")?));
+    let requests = node
+        .lock()
+        .unwrap()
+        .create_action_server::<Fibonacci::Action>("/cancel_all_fibonacci")?;
+    let client = node
+        .lock()
+        .unwrap()
+        .create_action_client::<Fibonacci::Action>("/cancel_all_fibonacci")?;
+    let server_available = r2r::Node::is_available(&client)?;
+
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    spawner.spawn_local(cancel_server(spawner.clone(), node.clone(), requests))?;
+
+    let statuses = Arc::new(Mutex::new(None));
+    let task_statuses = statuses.clone();
+    spawner.spawn_local(async move {
+        server_available.await.unwrap();
+
+        let mut goals = vec![];
+        let mut results = vec![];
+        for order in 1..=3 {
+            let (goal, result, _feedback) = client
+                .send_goal_request(Fibonacci::Goal { order })
+                .unwrap()
+                .await
+                .unwrap();
+            assert!(goal.is_active());
+            goals.push(goal);
+            results.push(result);
+        }
+        assert_eq!(client.get_active_goals().len(), 3);
+
+        let mut canceling = client.cancel_all_goals().unwrap().await.unwrap();
+        let mut uuids = goals.iter().map(|g| g.uuid).collect::<Vec<_>>();
+        canceling.sort();
+        uuids.sort();
+        assert_eq!(canceling, uuids);
+
+        let results = join_all(results).await;
+        assert!(goals.iter().all(|g| !g.is_active()));
+        assert!(client.get_active_goals().is_empty());
+        *task_statuses.lock().unwrap() = Some(
+            results
+                .into_iter()
+                .map(|r| r.unwrap().0)
+                .collect::<Vec<_>>(),
+        );
+    })?;
+
+    let start = Instant::now();
+    while statuses.lock().unwrap().is_none() {
+        assert!(start.elapsed() < Duration::from_secs(10), "goals were not canceled");
+        node.lock().unwrap().spin_once(Duration::from_millis(10));
+        pool.run_until_stalled();
+    }
+
+    let statuses = statuses.lock().unwrap().take().unwrap();
+    assert_eq!(statuses, vec![GoalStatus::Canceled; 3]);
+
+    Ok(())
+}
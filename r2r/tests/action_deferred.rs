@@ -0,0 +1,129 @@
+use futures::{
+    executor::LocalPool,
+    stream::{Stream, StreamExt},
+    task::LocalSpawnExt,
+};
+use r2r::{test_msgs::action::Fibonacci, GoalStatus};
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+// rejects negative orders and holds the others back until the next goal arrives.
+async fn deferring_server(
+    mut requests: impl Stream<Item = r2r::ActionServerGoalRequest<Fibonacci::Action>> + Unpin,
+) {
+    let mut waiting: Option<r2r::DeferredGoal<Fibonacci::Action>> = None;
+    while let Some(req) = requests.next().await {
+        if req.goal.order < 0 {
+            req.reject_with_reason("order must not be negative")
+                .expect("could not reject goal");
+            continue;
+        }
+        let deferred = req.accept_deferred().expect("could not accept goal");
+        if let Some(previous) = waiting.replace(deferred) {
+            let (mut g, _cancel) = previous.activate().expect("could not activate goal");
+            let result = Fibonacci::Result {
+                sequence: vec![g.goal.order],
+            };
+            g.succeed(result).unwrap();
+        }
+    }
+}
+
+#[test]
+fn reject_and_defer_goals() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_action_deferred", "")?;
+    let requests = node.create_action_server::<Fibonacci::Action>("/deferred_fibonacci")?;
+    let client = node.create_action_client::<Fibonacci::Action>("/deferred_fibonacci")?;
+    let server_available = r2r::Node::is_available(&client)?;
+
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    spawner.spawn_local(deferring_server(requests))?;
+
+    let done = Arc::new(Mutex::new(None));
+    let task_done = done.clone();
+    spawner.spawn_local(async move {
+        server_available.await.unwrap();
+
+        let rejected = client
+            .send_goal_request(Fibonacci::Goal { order: -1 })
+            .unwrap()
+            .await;
+        assert!(matches!(rejected, Err(r2r::Error::RCL_RET_ACTION_GOAL_REJECTED)));
+
+        // the first goal only runs once the second one is accepted.
+        let (first, first_result, _feedback) = client
+            .send_goal_request(Fibonacci::Goal { order: 1 })
+            .unwrap()
+            .await
+            .unwrap();
+        assert!(first.is_active());
+        let (_second, _second_result, _feedback) = client
+            .send_goal_request(Fibonacci::Goal { order: 2 })
+            .unwrap()
+            .await
+            .unwrap();
+
+        *task_done.lock().unwrap() = Some(first_result.await.unwrap());
+    })?;
+
+    let start = Instant::now();
+    while done.lock().unwrap().is_none() {
+        assert!(start.elapsed() < Duration::from_secs(10), "deferred goal did not finish");
+        node.spin_once(Duration::from_millis(10));
+        pool.run_until_stalled();
+    }
+
+    let (status, result) = done.lock().unwrap().take().unwrap();
+    assert_eq!(status, GoalStatus::Succeeded);
+    assert_eq!(result.sequence, vec![1]);
+
+    Ok(())
+}
+
+#[test]
+fn dropped_deferred_goal_is_aborted() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_action_deferred_dropped", "")?;
+    let mut requests =
+        node.create_action_server::<Fibonacci::Action>("/dropped_deferred_fibonacci")?;
+    let client = node.create_action_client::<Fibonacci::Action>("/dropped_deferred_fibonacci")?;
+    let server_available = r2r::Node::is_available(&client)?;
+
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    spawner.spawn_local(async move {
+        while let Some(req) = requests.next().await {
+            let deferred = req.accept_deferred().expect("could not accept goal");
+            drop(deferred);
+        }
+    })?;
+
+    let done = Arc::new(Mutex::new(None));
+    let task_done = done.clone();
+    spawner.spawn_local(async move {
+        server_available.await.unwrap();
+        let (_goal, result, _feedback) = client
+            .send_goal_request(Fibonacci::Goal { order: 1 })
+            .unwrap()
+            .await
+            .unwrap();
+        *task_done.lock().unwrap() = Some(result.await.unwrap());
+    })?;
+
+    let start = Instant::now();
+    while done.lock().unwrap().is_none() {
+        assert!(start.elapsed() < Duration::from_secs(10), "dropped goal did not finish");
+        node.spin_once(Duration::from_millis(10));
+        pool.run_until_stalled();
+    }
+
+    let (status, _result) = done.lock().unwrap().take().unwrap();
+    assert_eq!(status, GoalStatus::Aborted);
+
+    Ok(())
+}
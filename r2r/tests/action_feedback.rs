@@ -0,0 +1,186 @@
+use futures::{
+    executor::LocalPool,
+    future::FutureExt,
+    select_biased,
+    stream::{Stream, StreamExt},
+    task::{LocalSpawn, LocalSpawnExt},
+};
+use r2r::{test_msgs::action::Fibonacci, GoalStatus};
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+// publishes the sequence one number at a time as feedback, then succeeds.
+async fn fibonacci_server(
+    spawner: impl LocalSpawn, node: Arc<Mutex<r2r::Node>>,
+    mut requests: impl Stream<Item = r2r::ActionServerGoalRequest<Fibonacci::Action>> + Unpin,
+) {
+    while let Some(req) = requests.next().await {
+        let (mut g, _cancel) = req.accept().expect("could not accept goal");
+        let node = node.clone();
+        spawner
+            .spawn_local(async move {
+                let mut timer = node
+                    .lock()
+                    .unwrap()
+                    .create_wall_timer(Duration::from_millis(20))
+                    .unwrap();
+                let mut sequence = vec![0, 1];
+                for _ in 0..g.goal.order {
+                    timer.tick().await.unwrap();
+                    let next = sequence[sequence.len() - 1] + sequence[sequence.len() - 2];
+                    sequence.push(next);
+                    let feedback = Fibonacci::Feedback {
+                        sequence: sequence.clone(),
+                    };
+                    g.publish_feedback(feedback).unwrap();
+                }
+                timer.tick().await.unwrap();
+                g.succeed(Fibonacci::Result { sequence }).unwrap();
+            })
+            .unwrap();
+    }
+}
+
+#[test]
+fn feedback_before_result() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let node = Arc::new(Mutex::new(r2r::Node::create(ctx, "testnode_action_feedback", "")?));
+    let requests = node
+        .lock()
+        .unwrap()
+        .create_action_server::<Fibonacci::Action>("/feedback_fibonacci")?;
+    let client = node
+        .lock()
+        .unwrap()
+        .create_action_client::<Fibonacci::Action>("/feedback_fibonacci")?;
+    let server_available = r2r::Node::is_available(&client)?;
+
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    spawner.spawn_local(fibonacci_server(spawner.clone(), node.clone(), requests))?;
+
+    let done = Arc::new(Mutex::new(None));
+    let task_done = done.clone();
+    spawner.spawn_local(async move {
+        server_available.await.unwrap();
+
+        let (_goal, result, feedback) = client
+            .send_goal_request(Fibonacci::Goal { order: 5 })
+            .unwrap()
+            .await
+            .unwrap();
+
+        // drive both in the same task, preferring feedback that is already there.
+        let mut feedback = feedback.fuse();
+        let mut result = Box::pin(result).fuse();
+        let mut received = vec![];
+        let result = loop {
+            select_biased! {
+                msg = feedback.next() => received.extend(msg.map(|m| m.sequence)),
+                result = result => break result.unwrap(),
+            }
+        };
+        *task_done.lock().unwrap() = Some((received, result));
+    })?;
+
+    let start = Instant::now();
+    while done.lock().unwrap().is_none() {
+        assert!(start.elapsed() < Duration::from_secs(10), "goal did not finish");
+        node.lock().unwrap().spin_once(Duration::from_millis(10));
+        pool.run_until_stalled();
+    }
+
+    let (received, (status, result)) = done.lock().unwrap().take().unwrap();
+    assert_eq!(status, GoalStatus::Succeeded);
+    assert_eq!(result.sequence, vec![0, 1, 1, 2, 3, 5, 8]);
+    let lengths = received.iter().map(|s| s.len()).collect::<Vec<_>>();
+    assert_eq!(lengths, vec![3, 4, 5, 6, 7]);
+    assert_eq!(received.last(), Some(&result.sequence));
+
+    Ok(())
+}
+
+#[test]
+fn throttled_feedback() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let node = Arc::new(Mutex::new(r2r::Node::create(ctx, "testnode_feedback_throttle", "")?));
+    let options = r2r::ActionServerOptions {
+        feedback_qos: r2r::QosProfile::default().keep_last(100),
+    };
+    let mut requests = node
+        .lock()
+        .unwrap()
+        .create_action_server_with_options::<Fibonacci::Action>("/throttled_fibonacci", options)?;
+    let client = node
+        .lock()
+        .unwrap()
+        .create_action_client::<Fibonacci::Action>("/throttled_fibonacci")?;
+    let server_available = r2r::Node::is_available(&client)?;
+
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+
+    // feedback is produced at about 10 kHz for a second from another thread.
+    spawner.spawn_local(async move {
+        let req = requests.next().await.unwrap();
+        let (mut g, _cancel) = req.accept().unwrap();
+        std::thread::spawn(move || {
+            let start = Instant::now();
+            let mut published = 0;
+            while start.elapsed() < Duration::from_secs(1) {
+                let feedback = Fibonacci::Feedback {
+                    sequence: vec![published],
+                };
+                if g.publish_feedback_throttled(feedback, Duration::from_millis(100))
+                    .unwrap()
+                {
+                    published += 1;
+                }
+                std::thread::sleep(Duration::from_micros(100));
+            }
+            g.succeed(Fibonacci::Result {
+                sequence: vec![published],
+            })
+            .unwrap();
+        });
+    })?;
+
+    let done = Arc::new(Mutex::new(None));
+    let task_done = done.clone();
+    spawner.spawn_local(async move {
+        server_available.await.unwrap();
+
+        let (_goal, result, feedback) = client
+            .send_goal_request(Fibonacci::Goal { order: 1 })
+            .unwrap()
+            .await
+            .unwrap();
+        let mut feedback = feedback.fuse();
+        let mut result = Box::pin(result).fuse();
+        let mut received = 0;
+        let result = loop {
+            select_biased! {
+                msg = feedback.next() => received += msg.is_some() as usize,
+                result = result => break result.unwrap(),
+            }
+        };
+        *task_done.lock().unwrap() = Some((received, result));
+    })?;
+
+    let start = Instant::now();
+    while done.lock().unwrap().is_none() {
+        assert!(start.elapsed() < Duration::from_secs(10), "goal did not finish");
+        node.lock().unwrap().spin_once(Duration::from_millis(10));
+        pool.run_until_stalled();
+    }
+
+    let (received, (status, result)) = done.lock().unwrap().take().unwrap();
+    assert_eq!(status, GoalStatus::Succeeded);
+    assert!((9..=11).contains(&result.sequence[0]), "published {:?}", result.sequence);
+    assert!((9..=11).contains(&received), "received {received}");
+
+    Ok(())
+}
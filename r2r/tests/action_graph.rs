@@ -0,0 +1,38 @@
+use r2r::test_msgs::action::Fibonacci;
+
+use std::time::{Duration, Instant};
+
+#[test]
+fn action_names_and_types() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut server_node = r2r::Node::create(ctx.clone(), "testnode_action_graph_server", "")?;
+    let mut client_node = r2r::Node::create(ctx, "testnode_action_graph_client", "/graph")?;
+
+    let _server = server_node.create_action_server::<Fibonacci::Action>("/graph_fibonacci")?;
+    let _client = client_node.create_action_client::<Fibonacci::Action>("/graph_fibonacci")?;
+    // an action without a server.
+    let _lonely_client =
+        client_node.create_action_client::<Fibonacci::Action>("/graph_fibonacci_no_server")?;
+
+    let expected = ("/graph_fibonacci".to_string(), vec!["test_msgs/action/Fibonacci".to_string()]);
+    let start = Instant::now();
+    let (servers, clients) = loop {
+        assert!(start.elapsed() < Duration::from_secs(10), "actions were not found");
+        server_node.spin_once(Duration::from_millis(10));
+        client_node.spin_once(Duration::from_millis(10));
+        let servers = client_node.get_action_server_names_and_types()?;
+        let clients = server_node
+            .get_action_client_names_and_types_by_node("testnode_action_graph_client", "/graph")?;
+        if servers.contains(&expected) && clients.contains(&expected) {
+            break (servers, clients);
+        }
+    };
+    assert!(!servers
+        .iter()
+        .any(|(name, _)| name == "/graph_fibonacci_no_server"));
+    assert!(clients
+        .iter()
+        .any(|(name, _)| name == "/graph_fibonacci_no_server"));
+
+    Ok(())
+}
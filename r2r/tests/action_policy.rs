@@ -0,0 +1,140 @@
+use futures::{
+    executor::LocalPool,
+    future::join_all,
+    stream::{Stream, StreamExt},
+    task::{LocalSpawn, LocalSpawnExt},
+};
+use r2r::{test_msgs::action::Fibonacci, GoalHandlingPolicy, GoalStatus};
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+// runs each goal for a while, aborting it when preempted.
+async fn slow_server(
+    spawner: impl LocalSpawn, node: Arc<Mutex<r2r::Node>>,
+    mut requests: impl Stream<Item = r2r::ActionServerGoalRequest<Fibonacci::Action>> + Unpin,
+) {
+    while let Some(req) = requests.next().await {
+        let (mut g, _cancel) = req.accept().expect("could not accept goal");
+        let node = node.clone();
+        spawner
+            .spawn_local(async move {
+                let mut timer = node
+                    .lock()
+                    .unwrap()
+                    .create_wall_timer(Duration::from_millis(20))
+                    .unwrap();
+                for _ in 0..10 {
+                    timer.tick().await.unwrap();
+                    if g.is_preempted().unwrap() {
+                        g.abort(Fibonacci::Result { sequence: vec![] }).unwrap();
+                        return;
+                    }
+                }
+                let result = Fibonacci::Result {
+                    sequence: vec![g.goal.order],
+                };
+                g.succeed(result).unwrap();
+            })
+            .unwrap();
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Outcome {
+    Rejected,
+    Done(GoalStatus),
+}
+
+// sends three goals at once and returns what happened to each of them.
+fn run_goals(name: &str, policy: GoalHandlingPolicy) -> Result<Vec<Outcome>, r2r::Error> {
+    let ctx = r2r::Context::create()?;
+    let node = Arc::new(Mutex::new(r2r::Node::create(ctx, name, "")?));
+    let action_name = format!("/{name}_fibonacci");
+    let requests = node
+        .lock()
+        .unwrap()
+        .create_action_server_with_policy::<Fibonacci::Action>(&action_name, policy)?;
+    let client = node
+        .lock()
+        .unwrap()
+        .create_action_client::<Fibonacci::Action>(&action_name)?;
+    let server_available = r2r::Node::is_available(&client)?;
+
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    spawner
+        .spawn_local(slow_server(spawner.clone(), node.clone(), requests))
+        .unwrap();
+
+    let outcomes = Arc::new(Mutex::new(None));
+    let task_outcomes = outcomes.clone();
+    spawner
+        .spawn_local(async move {
+            server_available.await.unwrap();
+
+            let sent = (1..=3)
+                .map(|order| client.send_goal_request(Fibonacci::Goal { order }).unwrap())
+                .collect::<Vec<_>>();
+            let goals = sent.into_iter().map(|goal| async move {
+                match goal.await {
+                    Ok((_goal, result, _feedback)) => Outcome::Done(result.await.unwrap().0),
+                    Err(r2r::Error::RCL_RET_ACTION_GOAL_REJECTED) => Outcome::Rejected,
+                    Err(e) => panic!("unexpected error: {e}"),
+                }
+            });
+            *task_outcomes.lock().unwrap() = Some(join_all(goals).await);
+        })
+        .unwrap();
+
+    let start = Instant::now();
+    while outcomes.lock().unwrap().is_none() {
+        assert!(start.elapsed() < Duration::from_secs(10), "goals did not finish");
+        node.lock().unwrap().spin_once(Duration::from_millis(10));
+        pool.run_until_stalled();
+    }
+
+    let outcomes = outcomes.lock().unwrap().take().unwrap();
+    Ok(outcomes)
+}
+
+#[test]
+fn reject_while_busy() -> Result<(), Box<dyn std::error::Error>> {
+    let outcomes = run_goals("testnode_policy_reject", GoalHandlingPolicy::RejectWhileBusy)?;
+    assert_eq!(
+        outcomes,
+        vec![
+            Outcome::Done(GoalStatus::Succeeded),
+            Outcome::Rejected,
+            Outcome::Rejected
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn preempt_existing() -> Result<(), Box<dyn std::error::Error>> {
+    let outcomes = run_goals("testnode_policy_preempt", GoalHandlingPolicy::PreemptExisting)?;
+    // the second goal is either preempted itself or replaced while waiting,
+    // depending on when the first goal notices the preemption.
+    assert_eq!(outcomes[0], Outcome::Done(GoalStatus::Aborted));
+    assert!(matches!(outcomes[1], Outcome::Rejected | Outcome::Done(GoalStatus::Aborted)));
+    assert_eq!(outcomes[2], Outcome::Done(GoalStatus::Succeeded));
+    Ok(())
+}
+
+#[test]
+fn queue() -> Result<(), Box<dyn std::error::Error>> {
+    let outcomes = run_goals("testnode_policy_queue", GoalHandlingPolicy::Queue(1))?;
+    assert_eq!(
+        outcomes,
+        vec![
+            Outcome::Done(GoalStatus::Succeeded),
+            Outcome::Done(GoalStatus::Succeeded),
+            Outcome::Rejected
+        ]
+    );
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use futures::{future::FutureExt, stream::StreamExt};
+use r2r::test_msgs::action::Fibonacci;
+
+use std::time::{Duration, Instant};
+
+// spin until `f` returns something, or panic after a while.
+fn spin_until<R>(node: &mut r2r::Node, what: &str, mut f: impl FnMut() -> Option<R>) -> R {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        assert!(Instant::now() < deadline, "timed out waiting for {what}");
+        node.spin_once(Duration::from_millis(10));
+        if let Some(r) = f() {
+            return r;
+        }
+    }
+}
+
+#[test]
+fn on_cancel_resolves() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_action_preemption", "")?;
+    let mut requests = node.create_action_server::<Fibonacci::Action>("/preempted_fibonacci")?;
+    let client = node.create_action_client::<Fibonacci::Action>("/preempted_fibonacci")?;
+
+    let mut available = r2r::Node::is_available(&client)?.boxed();
+    spin_until(&mut node, "the server", || (&mut available).now_or_never())?;
+
+    let mut sent = client
+        .send_goal_request(Fibonacci::Goal { order: 5 })?
+        .boxed();
+    let request =
+        spin_until(&mut node, "the goal request", || requests.next().now_or_never().flatten());
+    let (mut goal, mut cancel_requests) = request.accept()?;
+    let (client_goal, _result, _feedback) =
+        spin_until(&mut node, "the goal response", || (&mut sent).now_or_never())?;
+
+    let mut on_cancel = goal.on_cancel().boxed();
+    assert!((&mut on_cancel).now_or_never().is_none());
+    assert!(!goal.is_cancel_requested());
+    assert!(goal.check_preempted().is_ok());
+
+    let mut canceled = client_goal.cancel()?.boxed();
+    let cancel_request = spin_until(&mut node, "the cancel request", || {
+        cancel_requests.next().now_or_never().flatten()
+    });
+    // the flag is set once the handler accepts the request.
+    assert!(!goal.is_cancel_requested());
+    cancel_request.accept();
+    spin_until(&mut node, "on_cancel", || (&mut on_cancel).now_or_never());
+    assert!(goal.is_cancel_requested());
+    assert!(matches!(goal.check_preempted(), Err(r2r::Error::ActionPreempted)));
+
+    spin_until(&mut node, "canceling", || goal.is_cancelling().unwrap().then_some(()));
+    goal.cancel(Fibonacci::Result { sequence: vec![] })?;
+    spin_until(&mut node, "the cancel response", || (&mut canceled).now_or_never())?;
+    Ok(())
+}
+
+#[test]
+fn rejected_cancel_is_not_requested() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_action_rejected_cancel", "")?;
+    let mut requests = node.create_action_server::<Fibonacci::Action>("/rejected_cancel")?;
+    let client = node.create_action_client::<Fibonacci::Action>("/rejected_cancel")?;
+
+    let mut available = r2r::Node::is_available(&client)?.boxed();
+    spin_until(&mut node, "the server", || (&mut available).now_or_never())?;
+
+    let mut sent = client
+        .send_goal_request(Fibonacci::Goal { order: 5 })?
+        .boxed();
+    let request =
+        spin_until(&mut node, "the goal request", || requests.next().now_or_never().flatten());
+    let (mut goal, mut cancel_requests) = request.accept()?;
+    let (client_goal, _result, _feedback) =
+        spin_until(&mut node, "the goal response", || (&mut sent).now_or_never())?;
+
+    let mut on_cancel = goal.on_cancel().boxed();
+    let mut canceled = client_goal.cancel()?.boxed();
+    let cancel_request = spin_until(&mut node, "the cancel request", || {
+        cancel_requests.next().now_or_never().flatten()
+    });
+    cancel_request.reject();
+    let response = spin_until(&mut node, "the cancel response", || (&mut canceled).now_or_never());
+    assert!(matches!(response, Err(r2r::Error::GoalCancelRejected)));
+
+    assert!(!goal.is_cancel_requested());
+    assert!(goal.check_preempted().is_ok());
+    assert!((&mut on_cancel).now_or_never().is_none());
+    goal.succeed(Fibonacci::Result { sequence: vec![0] })?;
+    Ok(())
+}
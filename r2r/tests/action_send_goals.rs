@@ -0,0 +1,65 @@
+use futures::{executor::LocalPool, future::join_all, stream::TryStreamExt, task::LocalSpawnExt};
+use r2r::{test_msgs::action::Fibonacci, GoalStatus};
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+#[test]
+fn send_goals_pipelines_requests() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_action_send_goals", "")?;
+    node.create_action_server_threaded::<Fibonacci::Action, _>(
+        "/send_goals_fibonacci",
+        2,
+        |mut goal| {
+            let result = Fibonacci::Result {
+                sequence: vec![goal.goal.order],
+            };
+            goal.succeed(result).expect("could not send result");
+        },
+    )?;
+    let client = node.create_action_client::<Fibonacci::Action>("/send_goals_fibonacci")?;
+    let server_available = r2r::Node::is_available(&client)?;
+
+    let mut pool = LocalPool::new();
+    let done = Arc::new(Mutex::new(None));
+    let task_done = done.clone();
+    pool.spawner().spawn_local(async move {
+        server_available.await.unwrap();
+        let goals = (0..5).map(|order| Fibonacci::Goal { order }).collect();
+        let accepted: Vec<_> = client
+            .send_goals(goals)
+            .try_collect()
+            .await
+            .expect("goal not accepted");
+        let accepted_count = accepted.len();
+        let results = join_all(
+            accepted
+                .into_iter()
+                .map(|(_goal, result, _feedback)| result),
+        )
+        .await;
+        *task_done.lock().unwrap() = Some((accepted_count, results));
+    })?;
+
+    let start = Instant::now();
+    while done.lock().unwrap().is_none() {
+        assert!(start.elapsed() < Duration::from_secs(10), "goals did not finish");
+        node.spin_once(Duration::from_millis(5));
+        pool.run_until_stalled();
+    }
+
+    let (accepted_count, results) = done.lock().unwrap().take().unwrap();
+    assert_eq!(accepted_count, 5);
+    let mut orders = vec![];
+    for result in results {
+        let (status, result) = result?;
+        assert_eq!(status, GoalStatus::Succeeded);
+        orders.push(result.sequence[0]);
+    }
+    orders.sort();
+    assert_eq!(orders, vec![0, 1, 2, 3, 4]);
+    Ok(())
+}
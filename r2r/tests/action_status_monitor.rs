@@ -0,0 +1,88 @@
+use futures::{future::FutureExt, stream::StreamExt};
+use r2r::{
+    action::{ActionStatusChange, ActionStatusMonitor},
+    test_msgs::action::Fibonacci,
+    GoalStatus,
+};
+
+use std::time::{Duration, Instant};
+
+// spin until the monitor reports a change to `status`, discovering the
+// status topic on the way.
+fn wait_for_status(
+    node: &mut r2r::Node, monitor: &mut ActionStatusMonitor, status: GoalStatus,
+    changes: &mut Vec<ActionStatusChange>,
+) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while Instant::now() < deadline {
+        node.spin_once(Duration::from_millis(10));
+        monitor.discover(node).unwrap();
+        while let Some(Some(change)) = monitor.next().now_or_never() {
+            changes.push(change.clone());
+            if change.status == status {
+                return;
+            }
+        }
+    }
+    panic!("no change to {status} in {changes:?}");
+}
+
+#[test]
+fn monitor_goal_statuses() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_action_status_monitor", "")?;
+    let mut requests = node.create_action_server::<Fibonacci::Action>("/monitored_fibonacci")?;
+    let client = node.create_action_client::<Fibonacci::Action>("/monitored_fibonacci")?;
+    let mut monitor = ActionStatusMonitor::new(&mut node)?;
+
+    let mut available = Box::pin(r2r::Node::is_available(&client)?);
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while (&mut available).now_or_never().is_none() {
+        assert!(Instant::now() < deadline, "action server not available");
+        node.spin_once(Duration::from_millis(10));
+    }
+
+    let _goal = client.send_goal_request(Fibonacci::Goal { order: 3 })?;
+    let request = loop {
+        assert!(Instant::now() < deadline, "no goal request");
+        node.spin_once(Duration::from_millis(10));
+        if let Some(Some(request)) = requests.next().now_or_never() {
+            break request;
+        }
+    };
+    let goal_id = request.uuid;
+
+    // each state is waited for before the next transition, so no status
+    // message is replaced before the monitor has seen it.
+    let mut changes = vec![];
+    let (goal, _cancel) = request.accept_typed()?;
+    wait_for_status(&mut node, &mut monitor, GoalStatus::Accepted, &mut changes);
+    assert_eq!(
+        monitor.snapshot().status("/monitored_fibonacci", &goal_id),
+        Some(GoalStatus::Accepted)
+    );
+
+    let goal = goal.start_executing()?;
+    wait_for_status(&mut node, &mut monitor, GoalStatus::Executing, &mut changes);
+    goal.succeed(Fibonacci::Result {
+        sequence: vec![0, 1, 1, 2],
+    })?;
+    wait_for_status(&mut node, &mut monitor, GoalStatus::Succeeded, &mut changes);
+
+    let expected = [
+        (None, GoalStatus::Accepted),
+        (Some(GoalStatus::Accepted), GoalStatus::Executing),
+        (Some(GoalStatus::Executing), GoalStatus::Succeeded),
+    ];
+    let observed = changes
+        .iter()
+        .filter(|c| c.action == "/monitored_fibonacci" && c.goal_id == goal_id)
+        .map(|c| (c.previous, c.status))
+        .collect::<Vec<_>>();
+    assert_eq!(observed, expected);
+    assert_eq!(
+        monitor.snapshot().status("/monitored_fibonacci", &goal_id),
+        Some(GoalStatus::Succeeded)
+    );
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use futures::{executor::LocalPool, future::join_all, task::LocalSpawnExt};
+use r2r::{test_msgs::action::Fibonacci, GoalStatus};
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+#[test]
+fn threaded_goals_run_concurrently() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_action_threaded", "")?;
+    node.create_action_server_threaded::<Fibonacci::Action, _>(
+        "/threaded_fibonacci",
+        4,
+        |mut goal| {
+            std::thread::sleep(Duration::from_millis(200));
+            let result = Fibonacci::Result {
+                sequence: vec![goal.goal.order],
+            };
+            goal.succeed(result).expect("could not send result");
+        },
+    )?;
+    let client = node.create_action_client::<Fibonacci::Action>("/threaded_fibonacci")?;
+    let server_available = r2r::Node::is_available(&client)?;
+
+    let mut pool = LocalPool::new();
+    let done = Arc::new(Mutex::new(None));
+    let task_done = done.clone();
+    pool.spawner().spawn_local(async move {
+        server_available.await.unwrap();
+        let start = Instant::now();
+        let goals = (0..4).map(|order| {
+            let request = client.send_goal_request(Fibonacci::Goal { order }).unwrap();
+            async move {
+                let (_goal, result, _feedback) = request.await.unwrap();
+                result.await.unwrap()
+            }
+        });
+        let results = join_all(goals).await;
+        *task_done.lock().unwrap() = Some((results, start.elapsed()));
+    })?;
+
+    let start = Instant::now();
+    while done.lock().unwrap().is_none() {
+        assert!(start.elapsed() < Duration::from_secs(10), "goals did not finish");
+        node.spin_once(Duration::from_millis(5));
+        pool.run_until_stalled();
+    }
+
+    let (results, elapsed) = done.lock().unwrap().take().unwrap();
+    let orders: Vec<_> = results.iter().map(|(_, r)| r.sequence[0]).collect();
+    assert_eq!(orders, vec![0, 1, 2, 3]);
+    // sequentially the goals would take 800ms.
+    assert!(elapsed < Duration::from_millis(600), "goals took {elapsed:?}");
+    Ok(())
+}
+
+#[test]
+fn panicking_handler_aborts_goal() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_action_threaded_panic", "")?;
+    // one worker, so the second goal runs on the thread the first one panicked on.
+    node.create_action_server_threaded::<Fibonacci::Action, _>(
+        "/panicking_fibonacci",
+        1,
+        |mut goal| {
+            if goal.goal.order == 0 {
+                panic!("handler failed");
+            }
+            let result = Fibonacci::Result {
+                sequence: vec![goal.goal.order],
+            };
+            goal.succeed(result).expect("could not send result");
+        },
+    )?;
+    let client = node.create_action_client::<Fibonacci::Action>("/panicking_fibonacci")?;
+    let server_available = r2r::Node::is_available(&client)?;
+
+    let mut pool = LocalPool::new();
+    let done = Arc::new(Mutex::new(None));
+    let task_done = done.clone();
+    pool.spawner().spawn_local(async move {
+        server_available.await.unwrap();
+        let mut statuses = vec![];
+        for order in 0..2 {
+            let request = client.send_goal_request(Fibonacci::Goal { order }).unwrap();
+            let (_goal, result, _feedback) = request.await.unwrap();
+            statuses.push(result.await.unwrap().0);
+        }
+        *task_done.lock().unwrap() = Some(statuses);
+    })?;
+
+    let start = Instant::now();
+    while done.lock().unwrap().is_none() {
+        assert!(start.elapsed() < Duration::from_secs(10), "goals did not finish");
+        node.spin_once(Duration::from_millis(5));
+        pool.run_until_stalled();
+    }
+
+    let statuses = done.lock().unwrap().take().unwrap();
+    assert_eq!(statuses, vec![GoalStatus::Aborted, GoalStatus::Succeeded]);
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use futures::{
+    executor::LocalPool,
+    stream::{Stream, StreamExt},
+    task::LocalSpawnExt,
+};
+use r2r::{test_msgs::action::Fibonacci, GoalStatus};
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+// the server cancels goals with a negative order by itself.
+async fn fibonacci_server(
+    mut requests: impl Stream<Item = r2r::ActionServerGoalRequest<Fibonacci::Action>> + Unpin,
+) {
+    while let Some(req) = requests.next().await {
+        let (goal, _cancel) = req.accept_typed().expect("could not accept goal");
+        let goal = goal.start_executing().expect("could not execute goal");
+        let order = goal.goal().order;
+        if order < 0 {
+            let goal = goal.start_canceling().expect("could not cancel goal");
+            goal.canceled(Fibonacci::Result { sequence: vec![] })
+                .unwrap();
+            continue;
+        }
+        let mut sequence = vec![0, 1];
+        for i in 1..order as usize {
+            sequence.push(sequence[i] + sequence[i - 1]);
+            goal.publish_feedback(Fibonacci::Feedback {
+                sequence: sequence.clone(),
+            })
+            .unwrap();
+        }
+        goal.succeed(Fibonacci::Result { sequence }).unwrap();
+    }
+}
+
+fn run_goal(name: &str, order: i32) -> Result<(GoalStatus, Fibonacci::Result), r2r::Error> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, name, "")?;
+    let action_name = format!("/{name}_fibonacci");
+    let requests = node.create_action_server::<Fibonacci::Action>(&action_name)?;
+    let client = node.create_action_client::<Fibonacci::Action>(&action_name)?;
+    let server_available = r2r::Node::is_available(&client)?;
+
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    spawner.spawn_local(fibonacci_server(requests)).unwrap();
+
+    let outcome = Arc::new(Mutex::new(None));
+    let task_outcome = outcome.clone();
+    spawner
+        .spawn_local(async move {
+            server_available.await.unwrap();
+            let result = client
+                .send_goal_and_wait(Fibonacci::Goal { order }, |_| {}, None)
+                .await;
+            *task_outcome.lock().unwrap() = Some(result);
+        })
+        .unwrap();
+
+    let start = Instant::now();
+    while outcome.lock().unwrap().is_none() {
+        assert!(start.elapsed() < Duration::from_secs(10), "goal did not finish");
+        node.spin_once(Duration::from_millis(10));
+        pool.run_until_stalled();
+    }
+
+    let result = outcome.lock().unwrap().take().unwrap();
+    result
+}
+
+#[test]
+fn typed_goal_succeeds() -> Result<(), Box<dyn std::error::Error>> {
+    let (status, result) = run_goal("testnode_typed_goal_succeed", 5)?;
+    assert_eq!(status, GoalStatus::Succeeded);
+    assert_eq!(result.sequence, vec![0, 1, 1, 2, 3, 5]);
+    Ok(())
+}
+
+#[test]
+fn typed_goal_canceled_by_server() -> Result<(), Box<dyn std::error::Error>> {
+    let (status, _) = run_goal("testnode_typed_goal_cancel", -1)?;
+    assert_eq!(status, GoalStatus::Canceled);
+    Ok(())
+}
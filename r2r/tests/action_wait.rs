@@ -0,0 +1,170 @@
+use futures::{
+    executor::LocalPool,
+    stream::{Stream, StreamExt},
+    task::{LocalSpawn, LocalSpawnExt},
+};
+use r2r::{test_msgs::action::Fibonacci, GoalStatus};
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+// negative orders make the server abort the goal, or run until canceled.
+const ABORT: i32 = -1;
+const RUN_FOREVER: i32 = -2;
+
+async fn fibonacci_server(
+    spawner: impl LocalSpawn, node: Arc<Mutex<r2r::Node>>,
+    mut requests: impl Stream<Item = r2r::ActionServerGoalRequest<Fibonacci::Action>> + Unpin,
+) {
+    while let Some(req) = requests.next().await {
+        let (mut g, mut cancel) = req.accept().expect("could not accept goal");
+        let node = node.clone();
+        spawner
+            .spawn_local(async move {
+                let mut timer = node
+                    .lock()
+                    .unwrap()
+                    .create_wall_timer(Duration::from_millis(10))
+                    .unwrap();
+                match g.goal.order {
+                    ABORT => g.abort(Fibonacci::Result { sequence: vec![] }).unwrap(),
+                    RUN_FOREVER => {
+                        if let Some(request) = cancel.next().await {
+                            request.accept();
+                            while !g.is_cancelling().unwrap() {
+                                timer.tick().await.unwrap();
+                            }
+                            g.cancel(Fibonacci::Result { sequence: vec![] }).unwrap();
+                        }
+                    }
+                    order => {
+                        let mut sequence = vec![0, 1];
+                        for i in 1..order as usize {
+                            sequence.push(sequence[i] + sequence[i - 1]);
+                            g.publish_feedback(Fibonacci::Feedback {
+                                sequence: sequence.clone(),
+                            })
+                            .unwrap();
+                            timer.tick().await.unwrap();
+                        }
+                        g.succeed(Fibonacci::Result { sequence }).unwrap();
+                    }
+                }
+            })
+            .unwrap();
+    }
+}
+
+struct Outcome {
+    result: r2r::Result<(GoalStatus, Fibonacci::Result)>,
+    feedback_lengths: Vec<usize>,
+}
+
+// sends one goal with send_goal_and_wait, optionally canceling it from the client.
+fn run_goal(
+    name: &str, order: i32, timeout: Option<Duration>, cancel_after: Option<Duration>,
+) -> Result<Outcome, r2r::Error> {
+    let ctx = r2r::Context::create()?;
+    let node = Arc::new(Mutex::new(r2r::Node::create(ctx, name, "")?));
+    let action_name = format!("/{name}_fibonacci");
+    let requests = node
+        .lock()
+        .unwrap()
+        .create_action_server::<Fibonacci::Action>(&action_name)?;
+    let client = node
+        .lock()
+        .unwrap()
+        .create_action_client::<Fibonacci::Action>(&action_name)?;
+    let server_available = r2r::Node::is_available(&client)?;
+
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    spawner
+        .spawn_local(fibonacci_server(spawner.clone(), node.clone(), requests))
+        .unwrap();
+
+    let outcome = Arc::new(Mutex::new(None));
+    let task_outcome = outcome.clone();
+    let task_node = node.clone();
+    spawner
+        .spawn_local(async move {
+            server_available.await.unwrap();
+
+            let feedback_lengths = Arc::new(Mutex::new(vec![]));
+            let cb_lengths = feedback_lengths.clone();
+            let wait = client.send_goal_and_wait(
+                Fibonacci::Goal { order },
+                move |feedback| cb_lengths.lock().unwrap().push(feedback.sequence.len()),
+                timeout,
+            );
+            let result = match cancel_after {
+                Some(delay) => {
+                    let mut timer = task_node.lock().unwrap().create_wall_timer(delay).unwrap();
+                    let cancel = async {
+                        timer.tick().await.unwrap();
+                        let goals = client.get_active_goals();
+                        assert_eq!(goals.len(), 1);
+                        goals[0].cancel().unwrap().await.unwrap();
+                    };
+                    futures::join!(wait, cancel).0
+                }
+                None => wait.await,
+            };
+            let feedback_lengths = feedback_lengths.lock().unwrap().clone();
+            *task_outcome.lock().unwrap() = Some(Outcome {
+                result,
+                feedback_lengths,
+            });
+        })
+        .unwrap();
+
+    let start = Instant::now();
+    while outcome.lock().unwrap().is_none() {
+        assert!(start.elapsed() < Duration::from_secs(10), "goal did not finish");
+        node.lock().unwrap().spin_once(Duration::from_millis(10));
+        pool.run_until_stalled();
+    }
+
+    let outcome = outcome.lock().unwrap().take().unwrap();
+    Ok(outcome)
+}
+
+#[test]
+fn wait_for_success() -> Result<(), Box<dyn std::error::Error>> {
+    let outcome = run_goal("testnode_wait_success", 5, Some(Duration::from_secs(5)), None)?;
+    let (status, result) = outcome.result?;
+    assert_eq!(status, GoalStatus::Succeeded);
+    assert_eq!(result.sequence, vec![0, 1, 1, 2, 3, 5]);
+    // the callback saw all feedback before the result.
+    assert_eq!(outcome.feedback_lengths, vec![3, 4, 5, 6]);
+    Ok(())
+}
+
+#[test]
+fn wait_for_abort() -> Result<(), Box<dyn std::error::Error>> {
+    let outcome = run_goal("testnode_wait_abort", ABORT, None, None)?;
+    let (status, _) = outcome.result?;
+    assert_eq!(status, GoalStatus::Aborted);
+    Ok(())
+}
+
+#[test]
+fn wait_for_cancel() -> Result<(), Box<dyn std::error::Error>> {
+    let outcome =
+        run_goal("testnode_wait_cancel", RUN_FOREVER, None, Some(Duration::from_millis(100)))?;
+    let (status, _) = outcome.result?;
+    assert_eq!(status, GoalStatus::Canceled);
+    Ok(())
+}
+
+#[test]
+fn wait_timeout() -> Result<(), Box<dyn std::error::Error>> {
+    let start = Instant::now();
+    let outcome =
+        run_goal("testnode_wait_timeout", RUN_FOREVER, Some(Duration::from_millis(200)), None)?;
+    assert!(matches!(outcome.result, Err(r2r::Error::Timeout)));
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    Ok(())
+}
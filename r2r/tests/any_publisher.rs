@@ -0,0 +1,82 @@
+use futures::{future::FutureExt, stream::StreamExt};
+use r2r::{
+    std_msgs::msg::{Float64, Int32, String as StringMsg},
+    AnyPublisher, QosProfile, WrappedTypesupport,
+};
+
+use std::time::Duration;
+
+#[test]
+fn any_publishers() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_any_publisher", "")?;
+
+    let mut string_sub = node.subscribe::<StringMsg>("/any_string", QosProfile::default())?;
+    let any_sub =
+        node.create_any_subscription("/any_float", "std_msgs/msg/Float64", QosProfile::default())?;
+    assert_eq!(any_sub.type_name(), "std_msgs/msg/Float64");
+    let mut float_sub = any_sub.into_stream_raw();
+
+    let publishers: Vec<AnyPublisher> = vec![
+        node.create_publisher::<StringMsg>("/any_string", QosProfile::default())?
+            .into_any(),
+        node.create_publisher::<Int32>("/any_int", QosProfile::default())?
+            .into_any(),
+        node.create_any_publisher("/any_float", "std_msgs/msg/Float64", QosProfile::default())?,
+    ];
+    let type_names: Vec<_> = publishers.iter().map(|p| p.type_name()).collect();
+    assert_eq!(
+        type_names,
+        vec![
+            "std_msgs/msg/String",
+            "std_msgs/msg/Int32",
+            "std_msgs/msg/Float64"
+        ]
+    );
+
+    // only publishers created from a typed publisher can be downcast.
+    assert!(publishers[0].downcast_ref::<StringMsg>().is_some());
+    assert!(publishers[0].downcast_ref::<Int32>().is_none());
+    assert!(publishers[1].downcast_ref::<Int32>().is_some());
+    assert!(publishers[2].downcast_ref::<Float64>().is_none());
+
+    // wait for the publishers and subscribers to discover each other.
+    for _ in 0..100 {
+        node.spin_once(Duration::from_millis(10));
+        if node
+            .get_subscriptions_info_by_topic("/any_float", false)?
+            .len()
+            == 1
+        {
+            break;
+        }
+    }
+
+    let msg = StringMsg {
+        data: "hello".into(),
+    };
+    publishers[0]
+        .downcast_ref::<StringMsg>()
+        .unwrap()
+        .publish(&msg)?;
+    publishers[2].publish_raw(&Float64 { data: 1.5 }.to_serialized_bytes()?)?;
+
+    let mut received_string = None;
+    let mut received_float = None;
+    for _ in 0..100 {
+        node.spin_once(Duration::from_millis(10));
+        if let Some(Some(msg)) = string_sub.next().now_or_never() {
+            received_string = Some(msg);
+        }
+        if let Some(Some(bytes)) = float_sub.next().now_or_never() {
+            received_float = Some(Float64::from_serialized_bytes(&bytes)?);
+        }
+        if received_string.is_some() && received_float.is_some() {
+            break;
+        }
+    }
+    assert_eq!(received_string, Some(msg));
+    assert_eq!(received_float, Some(Float64 { data: 1.5 }));
+
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use r2r::{test_msgs::srv::BasicTypes, QosProfile};
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+fn request(value: i64) -> BasicTypes::Request {
+    BasicTypes::Request {
+        int64_value: value,
+        ..Default::default()
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn chained_async_service() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx.clone(), "testnode_async_service", "")?;
+    let mut client_node = r2r::Node::create(ctx, "testnode_async_service_client", "")?;
+
+    node.create_async_service::<BasicTypes::Service, _, _>(
+        "/async_double",
+        QosProfile::default(),
+        |req| async move {
+            BasicTypes::Response {
+                int64_value: req.int64_value * 2,
+                ..Default::default()
+            }
+        },
+    )?;
+
+    // the outer service awaits two calls to the inner service of the same node,
+    // which only works if the node keeps spinning while the handler waits.
+    let inner = Arc::new(
+        node.create_client::<BasicTypes::Service>("/async_double", QosProfile::default())?,
+    );
+    let inner_available = r2r::Node::is_available(&*inner)?;
+    node.create_async_service::<BasicTypes::Service, _, _>(
+        "/async_quadruple",
+        QosProfile::default(),
+        move |req| {
+            let inner = inner.clone();
+            async move {
+                let doubled = inner
+                    .request(&request(req.int64_value))
+                    .expect("could not call inner service")
+                    .await
+                    .expect("inner service failed");
+                inner
+                    .request(&request(doubled.int64_value))
+                    .expect("could not call inner service")
+                    .await
+                    .expect("inner service failed")
+            }
+        },
+    )?;
+
+    let client = client_node
+        .create_client::<BasicTypes::Service>("/async_quadruple", QosProfile::default())?;
+    let outer_available = r2r::Node::is_available(&client)?;
+
+    let node = Arc::new(Mutex::new(node));
+    let client_node = Arc::new(Mutex::new(client_node));
+    let done = Arc::new(AtomicBool::new(false));
+    let spin_done = done.clone();
+    let spin_task = tokio::task::spawn_blocking(move || {
+        while !spin_done.load(Ordering::SeqCst) {
+            node.lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+            client_node
+                .lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+        }
+    });
+
+    inner_available.await?;
+    outer_available.await?;
+
+    // concurrent requests are handled independently.
+    let first = client.request(&request(1))?;
+    let second = client.request(&request(10))?;
+    let (first, second) = futures::future::try_join(first, second).await?;
+    assert_eq!(first.int64_value, 4);
+    assert_eq!(second.int64_value, 40);
+
+    done.store(true, Ordering::SeqCst);
+    spin_task.await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn stateful_service_responds_out_of_order() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_stateful_service", "")?;
+
+    // hold the requests until both arrived, then answer the second one first.
+    let held = Arc::new(Mutex::new(Vec::new()));
+    let responded = Arc::new(Mutex::new(Vec::new()));
+    let task_responded = responded.clone();
+    node.create_stateful_service::<BasicTypes::Service, _, _>(
+        "/stateful_double",
+        QosProfile::default(),
+        move |req: r2r::ServiceRequest<BasicTypes::Service>| {
+            let mut held = held.lock().unwrap();
+            held.push(req);
+            if held.len() == 2 {
+                let mut responded = task_responded.lock().unwrap();
+                for req in held.drain(..).rev() {
+                    responded.push(req.sequence_number());
+                    let response = BasicTypes::Response {
+                        int64_value: req.message.int64_value * 2,
+                        ..Default::default()
+                    };
+                    req.respond(response).expect("could not respond");
+                }
+            }
+            async {}
+        },
+    )?;
+
+    let client =
+        node.create_client::<BasicTypes::Service>("/stateful_double", QosProfile::default())?;
+    let available = r2r::Node::is_available(&client)?;
+
+    let node = Arc::new(Mutex::new(node));
+    let done = Arc::new(AtomicBool::new(false));
+    let spin_done = done.clone();
+    let spin_task = tokio::task::spawn_blocking(move || {
+        while !spin_done.load(Ordering::SeqCst) {
+            node.lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+        }
+    });
+
+    available.await?;
+    let first = client.request(&request(1))?;
+    let second = client.request(&request(10))?;
+    let (first, second) = futures::future::try_join(first, second).await?;
+    assert_eq!(first.int64_value, 2);
+    assert_eq!(second.int64_value, 20);
+
+    // the sequence numbers of the requests, in the order they were answered.
+    let responded = responded.lock().unwrap().clone();
+    assert_eq!(responded.len(), 2);
+    assert!(responded[0] > responded[1]);
+
+    done.store(true, Ordering::SeqCst);
+    spin_task.await?;
+
+    Ok(())
+}
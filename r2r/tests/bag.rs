@@ -0,0 +1,90 @@
+#![cfg(feature = "bag")]
+
+use r2r::{bag::McapWriter, std_msgs::msg::String as StringMsg, QosProfile, WrappedTypesupport};
+
+use std::{path::PathBuf, time::Duration};
+
+// a path in the temp dir not used by other tests or test runs.
+fn temp_path() -> PathBuf {
+    std::env::temp_dir().join(format!("r2r_test_{}.mcap", r2r::uuid::Uuid::new_v4()))
+}
+
+// the topics and CDR data of the messages, read back by the mcap crate.
+fn messages(data: &[u8]) -> Result<Vec<(String, Vec<u8>)>, mcap::McapError> {
+    mcap::MessageStream::new(data)?
+        .map(|msg| msg.map(|msg| (msg.channel.topic.clone(), msg.data.into_owned())))
+        .collect()
+}
+
+#[test]
+fn write_messages() -> Result<(), Box<dyn std::error::Error>> {
+    let path = temp_path();
+    let mut writer = McapWriter::open(&path)?;
+    for (i, topic) in ["/bag_a", "/bag_b", "/bag_a"].iter().enumerate() {
+        let msg = StringMsg {
+            data: format!("hello {}", i),
+        };
+        writer.write_message(topic, &msg, 1000 + i as u64)?;
+    }
+    writer.close()?;
+
+    let data = std::fs::read(&path)?;
+    let summary = mcap::Summary::read(&data)?.expect("bag has a summary");
+    // one schema is shared by the channels.
+    assert_eq!(summary.schemas.len(), 1);
+    assert_eq!(summary.channels.len(), 2);
+    let schema = summary.schemas.values().next().unwrap();
+    assert_eq!(schema.name, "std_msgs/msg/String");
+    assert_eq!(schema.encoding, "ros2msg");
+    assert_eq!(summary.stats.map(|s| s.message_count), Some(3));
+
+    let messages = messages(&data)?;
+    let topics = messages.iter().map(|m| m.0.as_str()).collect::<Vec<_>>();
+    assert_eq!(topics, ["/bag_a", "/bag_b", "/bag_a"]);
+    let msg = StringMsg::from_serialized_bytes(&messages[2].1)?;
+    assert_eq!(msg.data, "hello 2");
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn record_subscriptions() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_bag", "")?;
+    let publisher = node.create_publisher::<StringMsg>("/bag_recorded", QosProfile::default())?;
+
+    // the type of the topic is looked up in the graph.
+    for _ in 0..200 {
+        if node
+            .get_topic_names_and_types()?
+            .contains_key("/bag_recorded")
+        {
+            break;
+        }
+        node.spin_once(Duration::from_millis(10));
+    }
+    let path = temp_path();
+    let mut writer = McapWriter::from_subscriptions(&path, &mut node, &["/bag_recorded"])?;
+    let mut recorded = 0;
+    for i in 0..200 {
+        publisher.publish(&StringMsg {
+            data: format!("recorded {}", i),
+        })?;
+        node.spin_once(Duration::from_millis(10));
+        recorded += writer.record()?;
+        if recorded >= 3 {
+            break;
+        }
+    }
+    writer.close()?;
+    assert!(recorded >= 3);
+
+    let messages = messages(&std::fs::read(&path)?)?;
+    assert_eq!(messages.len(), recorded);
+    let msg = StringMsg::from_serialized_bytes(&messages[0].1)?;
+    assert!(msg.data.starts_with("recorded"));
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use futures::{future::FutureExt, stream::StreamExt};
+use r2r::{std_msgs::msg::Int32, QosProfile};
+
+use std::time::Duration;
+
+#[test]
+fn batched_subscription() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_batched_subscription", "")?;
+
+    let qos = QosProfile::default().keep_last(100);
+    let mut sub = node.create_batched_subscription::<Int32>("/batched", qos.clone(), 50)?;
+    let publisher = node.create_publisher::<Int32>("/batched", qos)?;
+
+    // wait for the publisher and subscriber to discover each other.
+    for _ in 0..100 {
+        node.spin_once(Duration::from_millis(10));
+        if publisher.get_inter_process_subscription_count()? > 0 {
+            break;
+        }
+    }
+
+    for data in 0..100 {
+        publisher.publish(&Int32 { data })?;
+    }
+
+    let mut batches = vec![];
+    let mut received = 0;
+    for _ in 0..100 {
+        node.spin_once(Duration::from_millis(10));
+        while let Some(Some(batch)) = sub.next().now_or_never() {
+            received += batch.len();
+            batches.push(batch);
+        }
+        if received == 100 {
+            break;
+        }
+    }
+
+    assert!(!batches.is_empty(), "no messages received");
+    assert!((1..=50).contains(&batches[0].len()), "{}", batches[0].len());
+    assert!(batches.iter().all(|b| !b.is_empty() && b.len() <= 50));
+    // messages arrive in order, also across batches.
+    let data = batches
+        .into_iter()
+        .flatten()
+        .map(|m| m.data)
+        .collect::<Vec<_>>();
+    assert!(data.windows(2).all(|w| w[0] < w[1]));
+
+    Ok(())
+}
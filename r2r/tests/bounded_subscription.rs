@@ -0,0 +1,144 @@
+use futures::{future::FutureExt, stream::StreamExt};
+use r2r::{std_msgs::msg::Int32, QosProfile};
+
+use std::time::{Duration, Instant};
+
+#[test]
+fn bounded_subscription_drops() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_bounded_subscription", "")?;
+
+    let (mut sub, drops) = node.create_bounded_subscription::<Int32>("/bounded", 1000, 10)?;
+    let publisher =
+        node.create_publisher::<Int32>("/bounded", QosProfile::default().keep_last(1000))?;
+
+    // wait for the publisher and subscriber to discover each other.
+    for _ in 0..100 {
+        node.spin_once(Duration::from_millis(10));
+        if publisher.get_inter_process_subscription_count()? > 0 {
+            break;
+        }
+    }
+
+    // publish without consuming the stream.
+    for data in 0..1000 {
+        publisher.publish(&Int32 { data })?;
+    }
+    let start = Instant::now();
+    while drops.count() < 990 && start.elapsed() < Duration::from_secs(10) {
+        node.spin_once(Duration::from_millis(1));
+    }
+    assert!(drops.count() > 0);
+
+    // the channel holds the first messages, the rest were dropped.
+    let mut received = vec![];
+    while let Some(Some(msg)) = sub.next().now_or_never() {
+        received.push(msg.data);
+    }
+    assert_eq!(received, (0..10).collect::<Vec<_>>());
+
+    // once there is room again, new messages are delivered.
+    let dropped = drops.count();
+    publisher.publish(&Int32 { data: 1000 })?;
+    let start = Instant::now();
+    let msg = loop {
+        assert!(start.elapsed() < Duration::from_secs(10), "no message received");
+        node.spin_once(Duration::from_millis(10));
+        if let Some(Some(msg)) = sub.next().now_or_never() {
+            break msg;
+        }
+    };
+    assert_eq!(msg.data, 1000);
+    assert_eq!(drops.count(), dropped);
+
+    Ok(())
+}
+
+#[test]
+fn queued_subscription_drops_oldest() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_queued_subscription", "")?;
+
+    let options = r2r::SubscriptionOptions {
+        queue_depth: 10,
+        overflow_policy: r2r::OverflowPolicy::DropOldest,
+        ..Default::default()
+    };
+    let mut sub = node.create_subscription_with_options::<Int32>(
+        "/queued",
+        QosProfile::default().keep_last(1000),
+        options,
+    )?;
+    let publisher =
+        node.create_publisher::<Int32>("/queued", QosProfile::default().keep_last(1000))?;
+
+    // wait for the publisher and subscriber to discover each other.
+    for _ in 0..100 {
+        node.spin_once(Duration::from_millis(10));
+        if publisher.get_inter_process_subscription_count()? > 0 {
+            break;
+        }
+    }
+
+    // publish without consuming the stream.
+    for data in 0..1000 {
+        publisher.publish(&Int32 { data })?;
+    }
+    let start = Instant::now();
+    while sub.total_dropped_count() < 990 && start.elapsed() < Duration::from_secs(10) {
+        node.spin_once(Duration::from_millis(1));
+    }
+    assert_eq!(sub.total_dropped_count(), 990);
+
+    // the queue holds the most recent messages.
+    let mut received = vec![];
+    while let Some(Some(msg)) = sub.next().now_or_never() {
+        received.push(msg.data);
+    }
+    assert_eq!(received, (990..1000).collect::<Vec<_>>());
+
+    Ok(())
+}
+
+#[test]
+fn subscribe_with_options_uses_queue_depth() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_subscribe_with_options_queue", "")?;
+
+    let options = r2r::SubscriptionOptions {
+        queue_depth: 5,
+        overflow_policy: r2r::OverflowPolicy::DropOldest,
+        ..Default::default()
+    };
+    let mut sub = node.subscribe_with_options::<Int32>(
+        "/queued_with_options",
+        QosProfile::default().keep_last(100),
+        options,
+    )?;
+    let publisher = node
+        .create_publisher::<Int32>("/queued_with_options", QosProfile::default().keep_last(100))?;
+
+    for _ in 0..100 {
+        node.spin_once(Duration::from_millis(10));
+        if publisher.get_inter_process_subscription_count()? > 0 {
+            break;
+        }
+    }
+
+    // publish without consuming the stream, then take everything from the middleware.
+    for data in 0..100 {
+        publisher.publish(&Int32 { data })?;
+    }
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_secs(1) {
+        node.spin_once(Duration::from_millis(1));
+    }
+
+    let mut received = vec![];
+    while let Some(Some(msg)) = sub.next().now_or_never() {
+        received.push(msg.data);
+    }
+    assert_eq!(received, (95..100).collect::<Vec<_>>());
+
+    Ok(())
+}
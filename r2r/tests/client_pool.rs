@@ -0,0 +1,82 @@
+use futures::{executor::LocalPool, future::join_all, task::LocalSpawnExt};
+use r2r::{test_msgs::srv::BasicTypes, QosProfile};
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn pooled_clients_are_shared() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_client_pool", "")?;
+    node.create_async_service::<BasicTypes::Service, _, _>(
+        "/pooled_double",
+        QosProfile::default(),
+        |req| async move {
+            BasicTypes::Response {
+                int64_value: req.int64_value * 2,
+                ..Default::default()
+            }
+        },
+    )?;
+    let pool =
+        node.create_client_pool::<BasicTypes::Service>("/pooled_double", QosProfile::default(), 3)?;
+    assert_eq!(pool.size(), 3);
+    assert!(node
+        .create_client_pool::<BasicTypes::Service>("/pooled_double", QosProfile::default(), 0)
+        .is_err());
+
+    let mut local = LocalPool::new();
+    let spawner = local.spawner();
+    let ready = Rc::new(Cell::new(false));
+    let task_pool = pool.clone();
+    let task_ready = ready.clone();
+    spawner.spawn_local(async move {
+        let clients = join_all((0..3).map(|_| task_pool.checkout())).await;
+        for client in &clients {
+            r2r::Node::is_available(&**client).unwrap().await.unwrap();
+        }
+        task_ready.set(true);
+    })?;
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !ready.get() {
+        assert!(Instant::now() < deadline, "service not available");
+        node.spin_once(Duration::from_millis(10));
+        local.run_until_stalled();
+    }
+    assert_eq!(pool.available(), 3);
+
+    let in_use = Rc::new(Cell::new(0));
+    let max_in_use = Rc::new(Cell::new(0));
+    let results = Rc::new(RefCell::new(vec![]));
+    for i in 0..10 {
+        let pool = pool.clone();
+        let (in_use, max_in_use, results) = (in_use.clone(), max_in_use.clone(), results.clone());
+        spawner.spawn_local(async move {
+            let client = pool.checkout().await;
+            in_use.set(in_use.get() + 1);
+            max_in_use.set(max_in_use.get().max(in_use.get()));
+            let request = BasicTypes::Request {
+                int64_value: i,
+                ..Default::default()
+            };
+            let response = client.request(&request).unwrap().await.unwrap();
+            in_use.set(in_use.get() - 1);
+            results.borrow_mut().push(response.int64_value);
+        })?;
+    }
+    while results.borrow().len() < 10 {
+        assert!(Instant::now() < deadline, "requests did not complete");
+        node.spin_once(Duration::from_millis(10));
+        local.run_until_stalled();
+    }
+
+    let mut results = results.take();
+    results.sort();
+    assert_eq!(results, (0..10).map(|i| i * 2).collect::<Vec<_>>());
+    assert_eq!(max_in_use.get(), 3);
+    assert_eq!(pool.available(), 3);
+    Ok(())
+}
@@ -0,0 +1,130 @@
+#![cfg(r2r__composition_interfaces__srv__LoadNode)]
+
+use futures::{future::FutureExt, stream::StreamExt};
+use r2r::{
+    composition_interfaces::srv::{ListNodes, LoadNode, UnloadNode},
+    std_msgs::msg::String as StringMsg,
+    Component, ComponentContainer, ComponentRegistry, QosProfile,
+};
+
+use std::{
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+// republishes the messages received on "in" on "out".
+struct Echo {
+    node: r2r::Node,
+    sub: Pin<Box<dyn futures::Stream<Item = StringMsg> + Send>>,
+    publisher: r2r::Publisher<StringMsg>,
+}
+
+impl Component for Echo {
+    fn create(ctx: r2r::Context, options: r2r::NodeOptions) -> r2r::Result<Box<dyn Component>> {
+        let mut node = r2r::Node::create_with_options(ctx, "echo", "", options)?;
+        let sub = Box::pin(node.subscribe::<StringMsg>("in", QosProfile::default())?);
+        let publisher = node.create_publisher::<StringMsg>("out", QosProfile::default())?;
+        Ok(Box::new(Echo {
+            node,
+            sub,
+            publisher,
+        }))
+    }
+
+    fn node(&mut self) -> &mut r2r::Node {
+        &mut self.node
+    }
+
+    fn after_spin(&mut self) {
+        while let Some(Some(msg)) = self.sub.next().now_or_never() {
+            self.publisher.publish(&msg).unwrap();
+        }
+    }
+}
+
+// spins the container and the client node until `f` returns a value.
+fn spin_until<T>(
+    container: &mut ComponentContainer, node: &mut r2r::Node, mut f: impl FnMut() -> Option<T>,
+) -> T {
+    let start = Instant::now();
+    loop {
+        assert!(start.elapsed() < Duration::from_secs(10), "timed out");
+        container.spin_once(Duration::from_millis(10));
+        node.spin_once(Duration::from_millis(10));
+        if let Some(value) = f() {
+            return value;
+        }
+    }
+}
+
+#[test]
+fn load_and_unload_echo_component() -> Result<(), Box<dyn std::error::Error>> {
+    ComponentRegistry::register::<Echo>("r2r_test::Echo");
+
+    let ctx = r2r::Context::create()?;
+    let mut container = ComponentContainer::new(ctx.clone())?;
+    let mut node = r2r::Node::create(ctx, "testnode_component_client", "")?;
+    let load = node.create_client::<LoadNode::Service>(
+        "/ComponentManager/_container/load_node",
+        QosProfile::services_default(),
+    )?;
+    let unload = node.create_client::<UnloadNode::Service>(
+        "/ComponentManager/_container/unload_node",
+        QosProfile::services_default(),
+    )?;
+    let list = node.create_client::<ListNodes::Service>(
+        "/ComponentManager/_container/list_nodes",
+        QosProfile::services_default(),
+    )?;
+    let publisher = node.create_publisher::<StringMsg>("/component_in", QosProfile::default())?;
+    let mut echoed = node.subscribe::<StringMsg>("/component_out", QosProfile::default())?;
+
+    let mut available = Box::pin(r2r::Node::is_available(&load)?);
+    spin_until(&mut container, &mut node, || (&mut available).now_or_never())?;
+
+    let request = LoadNode::Request {
+        package_name: "r2r_test".into(),
+        plugin_name: "r2r_test::Echo".into(),
+        node_name: "echo_component".into(),
+        remap_rules: vec!["in:=/component_in".into(), "out:=/component_out".into()],
+        ..Default::default()
+    };
+    let mut response = Box::pin(load.request(&request)?);
+    let response = spin_until(&mut container, &mut node, || (&mut response).now_or_never())?;
+    assert!(response.success, "{}", response.error_message);
+    assert_eq!(response.full_node_name, "/echo_component");
+
+    let unknown = LoadNode::Request {
+        plugin_name: "r2r_test::Unknown".into(),
+        ..Default::default()
+    };
+    let mut unknown = Box::pin(load.request(&unknown)?);
+    let unknown = spin_until(&mut container, &mut node, || (&mut unknown).now_or_never())?;
+    assert!(!unknown.success);
+
+    let mut listed = Box::pin(list.request(&ListNodes::Request::default())?);
+    let listed = spin_until(&mut container, &mut node, || (&mut listed).now_or_never())?;
+    assert_eq!(listed.unique_ids, vec![response.unique_id]);
+    assert_eq!(listed.full_node_names, vec!["/echo_component".to_owned()]);
+
+    // the component forwards messages until it is unloaded.
+    let msg = spin_until(&mut container, &mut node, || {
+        publisher
+            .publish(&StringMsg {
+                data: "hello".into(),
+            })
+            .unwrap();
+        echoed.next().now_or_never().flatten()
+    });
+    assert_eq!(msg.data, "hello");
+
+    let request = UnloadNode::Request {
+        unique_id: response.unique_id,
+    };
+    let mut unloaded = Box::pin(unload.request(&request)?);
+    let unloaded = spin_until(&mut container, &mut node, || (&mut unloaded).now_or_never())?;
+    assert!(unloaded.success, "{}", unloaded.error_message);
+    assert!(container.components().is_empty());
+
+    Ok(())
+}
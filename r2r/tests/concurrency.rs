@@ -0,0 +1,125 @@
+use futures::stream::StreamExt;
+use r2r::{CallbackGroupKind, QosProfile};
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+// Streams consumed in separate tasks do not block each other, which is
+// what callback groups are used for in rclcpp.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn slow_subscriber_does_not_block_fast() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_concurrency", "")?;
+
+    let mut slow_sub =
+        node.subscribe::<r2r::std_msgs::msg::Int32>("/slow_no", QosProfile::default())?;
+    let mut fast_sub =
+        node.subscribe::<r2r::std_msgs::msg::Int32>("/fast_no", QosProfile::default())?;
+    let slow_publisher =
+        node.create_publisher::<r2r::std_msgs::msg::Int32>("/slow_no", QosProfile::default())?;
+    let fast_publisher =
+        node.create_publisher::<r2r::std_msgs::msg::Int32>("/fast_no", QosProfile::default())?;
+
+    let slow = tokio::spawn(async move {
+        slow_sub.next().await;
+        // a blocking, slow message handler.
+        std::thread::sleep(Duration::from_millis(100));
+        Instant::now()
+    });
+    let fast = tokio::spawn(async move {
+        fast_sub.next().await;
+        Instant::now()
+    });
+
+    let node = Arc::new(Mutex::new(node));
+    let done = Arc::new(AtomicBool::new(false));
+    let spin_done = done.clone();
+    let spin_task = tokio::task::spawn_blocking(move || {
+        while !spin_done.load(Ordering::SeqCst) {
+            node.lock().unwrap().spin_once(Duration::from_millis(1));
+        }
+    });
+
+    // wait for the publishers and subscribers to discover each other.
+    while slow_publisher.get_inter_process_subscription_count()? == 0
+        || fast_publisher.get_inter_process_subscription_count()? == 0
+    {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    let published = Instant::now();
+    slow_publisher.publish(&r2r::std_msgs::msg::Int32 { data: 1 })?;
+    fast_publisher.publish(&r2r::std_msgs::msg::Int32 { data: 2 })?;
+
+    let slow_done = slow.await?;
+    let fast_done = fast.await?;
+    assert!(fast_done < slow_done);
+    assert!(slow_done - published < Duration::from_millis(110));
+
+    done.store(true, Ordering::SeqCst);
+    spin_task.await?;
+
+    Ok(())
+}
+
+// Callbacks in separate mutually exclusive groups run on threads of their
+// own, so a single thread spinning the node is enough.
+#[test]
+fn callback_groups_run_concurrently() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_callback_groups", "")?;
+
+    let slow_group = node.create_callback_group(CallbackGroupKind::MutuallyExclusive);
+    let fast_group = node.create_callback_group(CallbackGroupKind::MutuallyExclusive);
+    let (done_tx, done_rx) = std::sync::mpsc::channel();
+    let slow_done = done_tx.clone();
+    node.subscribe_in_group(
+        "/slow_group",
+        QosProfile::default(),
+        &slow_group,
+        move |_: r2r::std_msgs::msg::Int32| {
+            std::thread::sleep(Duration::from_millis(100));
+            slow_done.send(("slow", Instant::now())).unwrap();
+        },
+    )?;
+    node.subscribe_in_group(
+        "/fast_group",
+        QosProfile::default(),
+        &fast_group,
+        move |_: r2r::std_msgs::msg::Int32| {
+            done_tx.send(("fast", Instant::now())).unwrap();
+        },
+    )?;
+    let slow_publisher =
+        node.create_publisher::<r2r::std_msgs::msg::Int32>("/slow_group", QosProfile::default())?;
+    let fast_publisher =
+        node.create_publisher::<r2r::std_msgs::msg::Int32>("/fast_group", QosProfile::default())?;
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while slow_publisher.get_inter_process_subscription_count()? == 0
+        || fast_publisher.get_inter_process_subscription_count()? == 0
+    {
+        assert!(Instant::now() < deadline, "subscriptions not matched");
+        node.spin_once(Duration::from_millis(10));
+    }
+
+    let published = Instant::now();
+    slow_publisher.publish(&r2r::std_msgs::msg::Int32 { data: 1 })?;
+    fast_publisher.publish(&r2r::std_msgs::msg::Int32 { data: 2 })?;
+
+    let mut done = vec![];
+    while done.len() < 2 {
+        assert!(Instant::now() < deadline, "callbacks not called");
+        node.spin_once(Duration::from_millis(1));
+        done.extend(done_rx.try_iter());
+    }
+    // the fast callback did not wait for the slow one.
+    assert_eq!(done[0].0, "fast");
+    assert!(done[1].1 - published < Duration::from_millis(110));
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use futures::{future::FutureExt, stream::StreamExt};
+use r2r::{ContentFilter, QosProfile, SubscriptionOptions};
+
+use std::time::Duration;
+
+// Content filters are implemented by e.g. Cyclone DDS and Fast DDS. Run with
+// `RMW_IMPLEMENTATION=rmw_cyclonedds_cpp` to make sure the filter is exercised.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn content_filtered_subscription() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_content_filter", "")?;
+
+    let qos = QosProfile::default().reliable().keep_last(20);
+    let options = SubscriptionOptions {
+        content_filter: Some(ContentFilter::with_parameters("data > %0", &["5"])),
+        ..Default::default()
+    };
+    let mut sub = match node.subscribe_with_options::<r2r::std_msgs::msg::Int32>(
+        "/filtered_no",
+        qos.clone(),
+        options,
+    ) {
+        Err(r2r::Error::UnsupportedFeature(feature)) => {
+            eprintln!("skipping test, rmw does not support {feature}");
+            return Ok(());
+        }
+        sub => sub?,
+    };
+    let publisher = node.create_publisher::<r2r::std_msgs::msg::Int32>("/filtered_no", qos)?;
+
+    // wait for the publisher and subscriber to discover each other.
+    for _ in 0..100 {
+        node.spin_once(Duration::from_millis(10));
+        if publisher.get_inter_process_subscription_count()? > 0 {
+            break;
+        }
+    }
+
+    for data in 0..10 {
+        publisher.publish(&r2r::std_msgs::msg::Int32 { data })?;
+    }
+
+    let mut received = Vec::new();
+    for _ in 0..100 {
+        node.spin_once(Duration::from_millis(10));
+        while let Some(Some(msg)) = sub.next().now_or_never() {
+            received.push(msg.data);
+        }
+        if received.len() == 4 {
+            break;
+        }
+    }
+    assert_eq!(received, vec![6, 7, 8, 9]);
+
+    Ok(())
+}
@@ -0,0 +1,21 @@
+#[test]
+fn parameters_from_context_args() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create_with_args(vec![
+        "--ros-args".into(),
+        "-p".into(),
+        "key:=42".into(),
+        "-r".into(),
+        "/original:=/remapped".into(),
+    ])?;
+    assert!(ctx.is_valid());
+    let node = r2r::Node::create(ctx, "testnode_context_args", "")?;
+    assert_eq!(node.get_parameter::<i64>("key")?, 42);
+    assert_eq!(node.resolve_topic_name("/original")?, "/remapped");
+
+    // each call creates a separate context with its own arguments.
+    let other = r2r::Context::create_with_args(vec![])?;
+    let node = r2r::Node::create(other, "testnode_context_args_other", "")?;
+    assert!(node.get_parameter::<i64>("key").is_err());
+
+    Ok(())
+}
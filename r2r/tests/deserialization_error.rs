@@ -0,0 +1,84 @@
+use futures::{future::FutureExt, stream::StreamExt};
+use r2r::{
+    std_msgs::msg::String as StringMsg, DeserErrorHandler, QosProfile, SubscriptionOptions,
+    WrappedTypesupport,
+};
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+#[test]
+fn malformed_messages_are_dropped() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_deserialization_error", "")?;
+    let publisher = node.create_publisher_untyped(
+        "/deserialization_error",
+        "std_msgs/msg/String",
+        QosProfile::default(),
+    )?;
+
+    let skip_options = SubscriptionOptions {
+        on_deserialization_error: Some(DeserErrorHandler::Skip),
+        ..Default::default()
+    };
+    let mut skipping = node.subscribe_with_options::<StringMsg>(
+        "/deserialization_error",
+        QosProfile::default(),
+        skip_options,
+    )?;
+
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    let errors_in_handler = errors.clone();
+    let custom_options = SubscriptionOptions {
+        on_deserialization_error: Some(DeserErrorHandler::Custom(Arc::new(move |e| {
+            errors_in_handler.lock().unwrap().push(e)
+        }))),
+        ..Default::default()
+    };
+    let mut custom = node.subscribe_with_options::<StringMsg>(
+        "/deserialization_error",
+        QosProfile::default(),
+        custom_options,
+    )?;
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while node
+        .get_subscriptions_info_by_topic("/deserialization_error", false)?
+        .len()
+        < 2
+    {
+        assert!(Instant::now() < deadline, "subscriptions not matched");
+        node.spin_once(Duration::from_millis(10));
+    }
+
+    // the cdr header, then a string length far beyond the end of the data.
+    let truncated = vec![0, 1, 0, 0, 100, 0, 0, 0, b'a', b'b'];
+    let valid = StringMsg {
+        data: "hello".into(),
+    };
+    publisher.publish_raw(&truncated)?;
+    publisher.publish_raw(&valid.to_serialized_bytes()?)?;
+    publisher.publish_raw(&truncated)?;
+    publisher.publish_raw(&valid.to_serialized_bytes()?)?;
+
+    let (mut skipped_msgs, mut custom_msgs) = (vec![], vec![]);
+    while skipped_msgs.len() < 2 || custom_msgs.len() < 2 {
+        assert!(Instant::now() < deadline, "well-formed messages not received");
+        node.spin_once(Duration::from_millis(10));
+        while let Some(Some(msg)) = skipping.next().now_or_never() {
+            skipped_msgs.push(msg);
+        }
+        while let Some(Some(msg)) = custom.next().now_or_never() {
+            custom_msgs.push(msg);
+        }
+    }
+    assert_eq!(skipped_msgs, vec![valid.clone(), valid.clone()]);
+    assert_eq!(custom_msgs, vec![valid.clone(), valid]);
+
+    let errors = errors.lock().unwrap();
+    assert_eq!(errors.len(), 2);
+    assert!(errors.iter().all(|e| e.data == truncated));
+    Ok(())
+}
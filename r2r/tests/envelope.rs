@@ -0,0 +1,93 @@
+#![cfg(r2r__std_msgs__msg__Header)]
+
+use futures::{future::FutureExt, stream::StreamExt};
+use r2r::{
+    builtin_interfaces::msg::Time,
+    geometry_msgs::msg::PointStamped,
+    std_msgs::msg::{Header, String as StringMsg},
+    QosProfile,
+};
+
+use std::time::{Duration, Instant};
+
+#[test]
+fn filter_envelopes_by_stamp() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut pub_node = r2r::Node::create(ctx.clone(), "testnode_envelope_pub", "")?;
+    let mut sub_node = r2r::Node::create(ctx, "testnode_envelope_sub", "")?;
+
+    let qos = QosProfile::default().reliable().keep_last(1000);
+    let publisher = pub_node.create_publisher::<PointStamped>("/envelope_points", qos.clone())?;
+    let mut envelopes =
+        sub_node.create_envelope_subscription::<PointStamped>("/envelope_points", qos)?;
+
+    let start = Instant::now();
+    while publisher.get_inter_process_subscription_count()? == 0 {
+        assert!(start.elapsed() < Duration::from_secs(10), "no subscriber");
+        sub_node.spin_once(Duration::from_millis(10));
+    }
+
+    for i in 0..1000 {
+        let mut msg = PointStamped {
+            header: Header {
+                stamp: Time { sec: i, nanosec: 0 },
+                frame_id: "map".into(),
+            },
+            ..Default::default()
+        };
+        msg.point.x = i as f64;
+        publisher.publish(&msg)?;
+    }
+
+    // only every tenth message is deserialized.
+    let mut received = 0;
+    let mut deserialized = Vec::new();
+    let start = Instant::now();
+    while received < 1000 {
+        assert!(start.elapsed() < Duration::from_secs(10), "received {received} messages");
+        sub_node.spin_once(Duration::from_millis(1));
+        while let Some(Some(envelope)) = envelopes.next().now_or_never() {
+            received += 1;
+            let header = envelope.header().expect("no header");
+            assert_eq!(header.frame_id, "map");
+            if header.stamp.sec % 10 == 0 {
+                deserialized.push(envelope.deserialize()?);
+            }
+        }
+    }
+
+    assert_eq!(deserialized.len(), 100);
+    for (i, msg) in deserialized.iter().enumerate() {
+        assert_eq!(msg.header.stamp.sec, i as i32 * 10);
+        assert_eq!(msg.point.x, i as f64 * 10.0);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn envelope_without_header() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_envelope_no_header", "")?;
+
+    let publisher =
+        node.create_publisher::<StringMsg>("/envelope_strings", QosProfile::default())?;
+    let mut envelopes =
+        node.create_envelope_subscription::<StringMsg>("/envelope_strings", QosProfile::default())?;
+
+    let start = Instant::now();
+    loop {
+        assert!(start.elapsed() < Duration::from_secs(10), "no message");
+        publisher.publish(&StringMsg {
+            data: "hello".into(),
+        })?;
+        node.spin_once(Duration::from_millis(10));
+        if let Some(Some(envelope)) = envelopes.next().now_or_never() {
+            assert!(envelope.header().is_none());
+            assert_eq!(envelope.deserialize()?.data, "hello");
+            break;
+        }
+    }
+
+    Ok(())
+}
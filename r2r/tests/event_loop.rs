@@ -0,0 +1,57 @@
+use futures::stream::StreamExt;
+use r2r::{std_msgs::msg::Int32, EventLoop, QosProfile};
+
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn run_until_shutdown() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let node = r2r::Node::create(ctx.clone(), "testnode_event_loop", "")?;
+    let mut event_loop = EventLoop::new(node);
+    assert_eq!(event_loop.node().name()?, "testnode_event_loop");
+
+    let publisher = event_loop
+        .node_mut()
+        .create_publisher::<Int32>("/event_loop", QosProfile::default())?;
+    let mut subscription = event_loop
+        .node_mut()
+        .subscribe::<Int32>("/event_loop", QosProfile::default())?;
+
+    let published = Rc::new(RefCell::new(0));
+    let task_published = published.clone();
+    event_loop.spawn(async move {
+        publisher
+            .wait_for_inter_process_subscribers()
+            .unwrap()
+            .await
+            .unwrap();
+        for data in 0..5 {
+            publisher.publish(&Int32 { data }).unwrap();
+            *task_published.borrow_mut() += 1;
+        }
+    });
+    let received = Rc::new(RefCell::new(vec![]));
+    let task_received = received.clone();
+    event_loop.spawn(async move {
+        while let Some(msg) = subscription.next().await {
+            task_received.borrow_mut().push(msg.data);
+        }
+    });
+
+    let shutdown = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_secs(1));
+        ctx.shutdown()
+    });
+    let start = Instant::now();
+    event_loop.run_until_shutdown()?;
+    assert!(start.elapsed() < Duration::from_secs(10));
+    shutdown.join().unwrap()?;
+
+    assert_eq!(*published.borrow(), 5);
+    assert_eq!(*received.borrow(), vec![0, 1, 2, 3, 4]);
+    Ok(())
+}
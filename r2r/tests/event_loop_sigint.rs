@@ -0,0 +1,47 @@
+#![cfg(unix)]
+
+use r2r::EventLoop;
+
+use std::{
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+// in its own test binary, SIGINT would stop the event loops of other tests.
+#[test]
+fn sigint_stops_all_event_loops() -> Result<(), Box<dyn std::error::Error>> {
+    unsafe { libc::signal(libc::SIGINT, libc::SIG_IGN) };
+
+    let (started, running) = mpsc::channel();
+    let loops = (0..2)
+        .map(|i| {
+            let started = started.clone();
+            std::thread::spawn(move || -> r2r::Result<()> {
+                // each loop has a context of its own, all of them are shut down.
+                let ctx = r2r::Context::create_with_args(vec![])?;
+                let node = r2r::Node::create(ctx, &format!("testnode_event_loop_sigint_{i}"), "")?;
+                let mut event_loop = EventLoop::new(node);
+                event_loop.spawn(async move {
+                    started.send(()).unwrap();
+                });
+                event_loop.run_until_shutdown()
+            })
+        })
+        .collect::<Vec<_>>();
+    for _ in 0..2 {
+        running.recv_timeout(Duration::from_secs(10))?;
+    }
+
+    let start = Instant::now();
+    unsafe { libc::raise(libc::SIGINT) };
+    for event_loop in loops {
+        event_loop.join().unwrap()?;
+    }
+    assert!(start.elapsed() < Duration::from_secs(10));
+
+    // the handler from before the first loop is back.
+    let mut action: libc::sigaction = unsafe { std::mem::zeroed() };
+    unsafe { libc::sigaction(libc::SIGINT, std::ptr::null(), &mut action) };
+    assert_eq!(action.sa_sigaction, libc::SIG_IGN);
+    Ok(())
+}
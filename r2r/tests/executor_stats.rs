@@ -0,0 +1,85 @@
+use futures::future::FutureExt;
+use r2r::{std_msgs::msg::Int32, test_msgs::srv::BasicTypes, QosProfile};
+
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+
+// spin the node until the future completes.
+fn spin_until<F: Future + Unpin>(node: &mut r2r::Node, mut f: F) -> F::Output {
+    let start = Instant::now();
+    loop {
+        assert!(start.elapsed() < Duration::from_secs(10), "timed out");
+        node.spin_once(Duration::from_millis(10));
+        if let Some(output) = (&mut f).now_or_never() {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn callback_latency() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_executor_stats", "")?;
+    let stats = node.enable_statistics();
+
+    // the handler takes at least 1ms.
+    node.create_async_service::<BasicTypes::Service, _, _>(
+        "/executor_stats_slow",
+        QosProfile::default(),
+        |req| {
+            std::thread::sleep(Duration::from_millis(1));
+            async move {
+                BasicTypes::Response {
+                    int64_value: req.int64_value,
+                    ..Default::default()
+                }
+            }
+        },
+    )?;
+    let client =
+        node.create_client::<BasicTypes::Service>("/executor_stats_slow", QosProfile::default())?;
+    let mut sub = node.subscribe::<Int32>("/executor_stats_topic", QosProfile::default())?;
+    let publisher =
+        node.create_publisher::<Int32>("/executor_stats_topic", QosProfile::default())?;
+
+    let available = r2r::Node::is_available(&client)?;
+    spin_until(&mut node, Box::pin(available))?;
+    for i in 0..10 {
+        let request = BasicTypes::Request {
+            int64_value: i,
+            ..Default::default()
+        };
+        let response = spin_until(&mut node, Box::pin(client.request(&request)?))?;
+        assert_eq!(response.int64_value, i);
+    }
+
+    publisher.publish(&Int32 { data: 1 })?;
+    let msg = spin_until(&mut node, futures::StreamExt::next(&mut sub));
+    assert_eq!(msg.map(|m| m.data), Some(1));
+
+    let stats = stats.read().unwrap();
+    let handler = stats
+        .get("service /executor_stats_slow handler")
+        .expect("no statistics of the handler");
+    assert_eq!(handler.invocation_count, 10);
+    assert!(
+        (1000.0..2000.0).contains(&handler.mean_latency_us),
+        "mean latency {}",
+        handler.mean_latency_us
+    );
+    assert!(handler.max_latency_us >= handler.mean_latency_us);
+    assert!((handler.total_latency_us - 10.0 * handler.mean_latency_us).abs() < 1e-3);
+
+    let service = stats
+        .get("service /executor_stats_slow")
+        .expect("no service statistics");
+    assert_eq!(service.invocation_count, 10);
+    let subscription = stats
+        .get("subscription /executor_stats_topic")
+        .expect("no subscription statistics");
+    assert!(subscription.invocation_count >= 1);
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use r2r::QosProfile;
+
+use std::time::Duration;
+
+#[test]
+fn publisher_gid_is_matched() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut pub_node = r2r::Node::create(ctx.clone(), "testnode_gid_publisher", "")?;
+    let mut sub_node = r2r::Node::create(ctx, "testnode_gid_subscriber", "")?;
+
+    let publisher = pub_node
+        .create_publisher::<r2r::std_msgs::msg::Int32>("/gid_topic", QosProfile::default())?;
+    let gid = publisher.get_gid()?;
+    assert_eq!(r2r::gid::format(&gid).len(), 2 * gid.len());
+
+    let _sub =
+        sub_node.subscribe::<r2r::std_msgs::msg::Int32>("/gid_topic", QosProfile::default())?;
+    let mut gids = vec![];
+    for _ in 0..200 {
+        gids = sub_node.get_publisher_gids("/gid_topic")?;
+        if gids.contains(&gid) {
+            break;
+        }
+        pub_node.spin_once(Duration::from_millis(10));
+        sub_node.spin_once(Duration::from_millis(10));
+    }
+    assert!(
+        gids.contains(&gid),
+        "{} not in {:?}",
+        r2r::gid::format(&gid),
+        gids.iter().map(r2r::gid::format).collect::<Vec<_>>()
+    );
+
+    let infos = sub_node.get_publishers_info_by_topic("/gid_topic", false)?;
+    assert!(infos.iter().any(|info| info.endpoint_gid == gid));
+    assert!(sub_node.get_publisher_gids("/not_subscribed").is_err());
+    Ok(())
+}
@@ -0,0 +1,185 @@
+use futures::{future::FutureExt, stream::StreamExt, Stream};
+use r2r::{GraphEvent, QosProfile};
+
+use std::time::{Duration, Instant};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn graph_introspection() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut pub_node = r2r::Node::create(ctx.clone(), "testnode_graph_pub", "/graph")?;
+    let mut sub_node = r2r::Node::create(ctx, "testnode_graph_sub", "/graph")?;
+
+    let _publisher = pub_node
+        .create_publisher::<r2r::std_msgs::msg::Int32>("/graph_no", QosProfile::default())?;
+    let _subscriber =
+        sub_node.subscribe::<r2r::std_msgs::msg::Int32>("/graph_no", QosProfile::default())?;
+    let _service = pub_node.create_service::<r2r::test_msgs::srv::Empty::Service>(
+        "/graph_srv",
+        QosProfile::default(),
+    )?;
+
+    // wait a little for the graph to populate(?). hack to avoid CI failures.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    pub_node.spin_once(Duration::from_millis(10));
+    sub_node.spin_once(Duration::from_millis(10));
+
+    let nodes = sub_node.get_node_names()?;
+    assert!(nodes.contains(&("testnode_graph_pub".to_string(), "/graph".to_string())));
+    assert!(nodes.contains(&("testnode_graph_sub".to_string(), "/graph".to_string())));
+
+    let topics = sub_node.get_topic_names_and_types()?;
+    assert_eq!(topics.get("/graph_no"), Some(&vec!["std_msgs/msg/Int32".to_string()]));
+
+    let services = sub_node.get_service_names_and_types()?;
+    assert_eq!(services.get("/graph_srv"), Some(&vec!["test_msgs/srv/Empty".to_string()]));
+
+    let publishers = sub_node.get_publishers_info_by_topic("/graph_no", false)?;
+    assert_eq!(publishers.len(), 1);
+    assert_eq!(publishers[0].node_name, "testnode_graph_pub");
+    assert_eq!(publishers[0].node_namespace, "/graph");
+    assert_eq!(publishers[0].topic_type, "std_msgs/msg/Int32");
+
+    let subscriptions = pub_node.get_subscriptions_info_by_topic("/graph_no", false)?;
+    assert_eq!(subscriptions.len(), 1);
+    assert_eq!(subscriptions[0].node_name, "testnode_graph_sub");
+    assert_eq!(subscriptions[0].node_namespace, "/graph");
+    assert_eq!(subscriptions[0].topic_type, "std_msgs/msg/Int32");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn node_options_disable_rosout() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let options = r2r::NodeOptions {
+        enable_rosout: false,
+        start_parameter_services: false,
+        ..Default::default()
+    };
+    let mut quiet_node =
+        r2r::Node::create_with_options(ctx.clone(), "testnode_no_rosout", "", options)?;
+    let mut node = r2r::Node::create(ctx, "testnode_rosout", "")?;
+
+    let (_handler, mut events) = quiet_node.make_parameter_handler()?;
+    assert!(events.next().await.is_none());
+
+    // wait a little for the graph to populate(?). hack to avoid CI failures.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    node.spin_once(Duration::from_millis(10));
+    quiet_node.spin_once(Duration::from_millis(10));
+
+    let rosout_nodes: Vec<_> = node
+        .get_publishers_info_by_topic("/rosout", false)?
+        .into_iter()
+        .map(|info| info.node_name)
+        .collect();
+    assert!(rosout_nodes.iter().any(|n| n == "testnode_rosout"));
+    assert!(!rosout_nodes.iter().any(|n| n == "testnode_no_rosout"));
+
+    let services = node.get_service_names_and_types()?;
+    assert!(!services.contains_key("/testnode_no_rosout/set_parameters"));
+
+    Ok(())
+}
+
+/// Spin `node` until `events` yields an event matching `pred` or the timeout expires.
+fn wait_for_graph_event(
+    node: &mut r2r::Node, events: &mut (impl Stream<Item = GraphEvent> + Unpin), timeout: Duration,
+    pred: impl Fn(&GraphEvent) -> bool,
+) -> bool {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        node.spin_once(Duration::from_millis(10));
+        while let Some(Some(event)) = events.next().now_or_never() {
+            if pred(&event) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+#[test]
+fn graph_events() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx.clone(), "testnode_graph_events", "")?;
+    let mut pub_node = r2r::Node::create(ctx, "testnode_graph_events_pub", "/graph_events")?;
+
+    let mut events = node.subscribe_to_graph_events()?;
+    let publisher = pub_node
+        .create_publisher::<r2r::std_msgs::msg::Int32>("/graph_events_no", QosProfile::default())?;
+
+    let is_publisher =
+        |node_name: &String, namespace: &String, topic: &String, type_name: &String| {
+            node_name == "testnode_graph_events_pub"
+                && namespace == "/graph_events"
+                && topic == "/graph_events_no"
+                && type_name == "std_msgs/msg/Int32"
+        };
+    let added = wait_for_graph_event(&mut node, &mut events, Duration::from_millis(500), |e| {
+        matches!(e, GraphEvent::PublisherAdded { node_name, namespace, topic, type_name }
+            if is_publisher(node_name, namespace, topic, type_name))
+    });
+    assert!(added, "no PublisherAdded event");
+
+    pub_node.destroy_publisher(publisher);
+    let removed = wait_for_graph_event(&mut node, &mut events, Duration::from_millis(500), |e| {
+        matches!(e, GraphEvent::PublisherRemoved { node_name, namespace, topic, type_name }
+            if is_publisher(node_name, namespace, topic, type_name))
+    });
+    assert!(removed, "no PublisherRemoved event");
+
+    Ok(())
+}
+
+#[test]
+fn topic_type_mismatch() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut string_node = r2r::Node::create(ctx.clone(), "testnode_type_string", "")?;
+    let options = r2r::NodeOptions {
+        check_type_on_connect: true,
+        ..Default::default()
+    };
+    let mut node = r2r::Node::create_with_options(ctx, "testnode_type_check", "", options)?;
+
+    let _publisher = string_node
+        .create_publisher::<r2r::std_msgs::msg::String>("/type_checked", QosProfile::default())?;
+    let start = Instant::now();
+    while node
+        .get_publishers_info_by_topic("/type_checked", false)?
+        .is_empty()
+    {
+        assert!(start.elapsed() < Duration::from_secs(10), "publisher not discovered");
+        node.spin_once(Duration::from_millis(10));
+    }
+
+    match node.create_publisher::<r2r::std_msgs::msg::Int32>("/type_checked", QosProfile::default())
+    {
+        Err(r2r::Error::TypeMismatch {
+            topic,
+            existing,
+            requested,
+        }) => {
+            assert_eq!(topic, "/type_checked");
+            assert_eq!(existing, "std_msgs/msg/String");
+            assert_eq!(requested, "std_msgs/msg/Int32");
+        }
+        other => panic!("expected a type mismatch, got {:?}", other.map(|_| ())),
+    }
+    assert!(matches!(
+        node.subscribe::<r2r::std_msgs::msg::Int32>("/type_checked", QosProfile::default()),
+        Err(r2r::Error::TypeMismatch { .. })
+    ));
+    // no endpoint was created for the mismatched publisher.
+    node.spin_once(Duration::from_millis(100));
+    assert_eq!(
+        node.get_publishers_info_by_topic("/type_checked", false)?
+            .len(),
+        1
+    );
+
+    // endpoints of the same type are still fine.
+    let _same = node
+        .create_publisher::<r2r::std_msgs::msg::String>("/type_checked", QosProfile::default())?;
+    Ok(())
+}
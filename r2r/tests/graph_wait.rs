@@ -0,0 +1,67 @@
+use futures::{executor::LocalPool, task::LocalSpawnExt};
+use r2r::QosProfile;
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+#[test]
+fn wait_for_publisher() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_graph_wait", "")?;
+    let changed = node.wait_for_graph_change(Some(Duration::from_secs(5)));
+    let publisher_found =
+        node.wait_for_publisher("/graph_wait_topic", 1, Some(Duration::from_secs(5)))?;
+
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let done = Arc::new(Mutex::new(None));
+    let task_done = done.clone();
+    spawner.spawn_local(async move {
+        let changed = changed.await;
+        let publisher_found = publisher_found.await;
+        *task_done.lock().unwrap() = Some((changed, publisher_found));
+    })?;
+
+    let start = Instant::now();
+    let mut publisher = None;
+    while done.lock().unwrap().is_none() {
+        assert!(start.elapsed() < Duration::from_secs(10), "publisher was not found");
+        if publisher.is_none() && start.elapsed() > Duration::from_millis(100) {
+            publisher = Some(node.create_publisher::<r2r::std_msgs::msg::String>(
+                "/graph_wait_topic",
+                QosProfile::default(),
+            )?);
+        }
+        node.spin_once(Duration::from_millis(10));
+        pool.run_until_stalled();
+    }
+
+    let (changed, publisher_found) = done.lock().unwrap().take().unwrap();
+    assert!(publisher.is_some());
+    assert!(changed.is_ok());
+    assert!(publisher_found.is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn wait_for_subscriber_times_out() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_graph_wait_timeout", "")?;
+    let mut subscriber_found =
+        node.wait_for_subscriber("/graph_wait_nobody", 1, Some(Duration::from_millis(100)))?;
+
+    let start = Instant::now();
+    let result = loop {
+        assert!(start.elapsed() < Duration::from_secs(10), "waiting did not time out");
+        node.spin_once(Duration::from_millis(10));
+        if let Some(result) = futures::FutureExt::now_or_never(&mut subscriber_found) {
+            break result;
+        }
+    };
+    assert!(matches!(result, Err(r2r::Error::Timeout)));
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use futures::{executor::LocalPool, task::LocalSpawnExt};
+
+use std::{
+    cell::Cell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn guard_condition_wakes_spin() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_guard_condition", "")?;
+
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+
+    let guard_condition = node.create_guard_condition()?;
+    let triggered = guard_condition.triggered()?;
+    let woken_at = Rc::new(Cell::new(None));
+    let task_woken_at = woken_at.clone();
+    spawner.spawn_local(async move {
+        triggered.await.unwrap();
+        task_woken_at.set(Some(Instant::now()));
+    })?;
+
+    let trigger_thread = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        let triggered_at = Instant::now();
+        guard_condition.trigger().unwrap();
+        triggered_at
+    });
+
+    let started = Instant::now();
+    while woken_at.get().is_none() && started.elapsed() < Duration::from_secs(5) {
+        // a long timeout, spin_once should be woken up by the guard condition.
+        node.spin_once(Duration::from_secs(1));
+        pool.run_until_stalled();
+    }
+
+    let triggered_at = trigger_thread.join().unwrap();
+    let woken_at = woken_at.get().expect("guard condition never triggered");
+    assert!(woken_at - triggered_at < Duration::from_millis(10));
+
+    Ok(())
+}
@@ -0,0 +1,16 @@
+// a test binary of its own, as no other test may create a context first.
+
+#[test]
+fn init_maybe_without_ros() -> Result<(), Box<dyn std::error::Error>> {
+    assert!(!r2r::is_initialized());
+    assert!(r2r::init_maybe(false)?.is_none());
+    // nothing was initialized, so there is no participant and no /rosout.
+    assert!(!r2r::is_initialized());
+
+    let ctx = r2r::init_maybe(true)?.expect("a context");
+    assert!(r2r::is_initialized());
+    assert!(ctx.is_valid());
+    let node = r2r::Node::create(ctx, "testnode_init_maybe", "")?;
+    assert_eq!(node.name()?, "testnode_init_maybe");
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use futures::{
+    future::FutureExt,
+    stream::{Stream, StreamExt},
+};
+use r2r::{std_msgs::msg::Int32, PublishResult, QosProfile};
+
+use std::time::{Duration, Instant};
+
+const N_MESSAGES: i32 = 20;
+
+fn receive(
+    node: &mut r2r::Node, stream: &mut (impl Stream<Item = Int32> + Unpin), deadline: Instant,
+) -> Int32 {
+    loop {
+        assert!(Instant::now() < deadline, "no message");
+        if let Some(Some(msg)) = stream.next().now_or_never() {
+            return msg;
+        }
+        node.spin_once(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn intranode_channel_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_intranode_channel", "")?;
+    let (publisher, mut intra) =
+        node.create_intranode_channel::<Int32>("/intranode_channel", QosProfile::default())?;
+    let mut external = node.subscribe::<Int32>("/intranode_channel", QosProfile::default())?;
+    // the same round trip through the middleware only, for comparison.
+    let middleware_publisher =
+        node.create_publisher::<Int32>("/intranode_channel_middleware", QosProfile::default())?;
+    let mut middleware =
+        node.subscribe::<Int32>("/intranode_channel_middleware", QosProfile::default())?;
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while publisher.publisher().get_subscription_count()? < 1
+        || middleware_publisher.get_subscription_count()? < 1
+    {
+        assert!(Instant::now() < deadline, "subscription not matched");
+        node.spin_once(Duration::from_millis(10));
+    }
+
+    let mut intra_time = Duration::ZERO;
+    let mut middleware_time = Duration::ZERO;
+    for i in 0..N_MESSAGES {
+        let start = Instant::now();
+        assert_eq!(publisher.publish(Int32 { data: i })?, PublishResult::Sent);
+        // the intra-node stream has the message without spinning the node.
+        let msg = intra
+            .next()
+            .now_or_never()
+            .flatten()
+            .expect("no intra-node message");
+        intra_time += start.elapsed();
+        assert_eq!(msg.data, i);
+
+        // other subscriptions still get it through the middleware.
+        assert_eq!(receive(&mut node, &mut external, deadline).data, i);
+
+        let start = Instant::now();
+        middleware_publisher.publish(&Int32 { data: i })?;
+        let msg = receive(&mut node, &mut middleware, deadline);
+        middleware_time += start.elapsed();
+        assert_eq!(msg.data, i);
+    }
+
+    assert!(
+        intra_time < middleware_time,
+        "intra-node {intra_time:?}, middleware {middleware_time:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn full_intranode_stream_is_not_published() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_intranode_channel_full", "")?;
+    let (publisher, mut intra) =
+        node.create_intranode_channel::<Int32>("/intranode_channel_full", QosProfile::default())?;
+
+    let mut sent = 0;
+    while publisher.publish(Int32 { data: sent })? == PublishResult::Sent {
+        sent += 1;
+        assert!(sent < 100, "the stream is never full");
+    }
+
+    // the stream has every published message, and room again once read.
+    let msg = intra.next().now_or_never().flatten().expect("a message");
+    assert_eq!(msg.data, 0);
+    assert_eq!(publisher.publish(Int32 { data: sent })?, PublishResult::Sent);
+    for data in 1..=sent {
+        let msg = intra.next().now_or_never().flatten().expect("a message");
+        assert_eq!(msg.data, data);
+    }
+    Ok(())
+}
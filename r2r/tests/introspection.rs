@@ -0,0 +1,110 @@
+use r2r::{
+    geometry_msgs::msg::{Pose, PoseArray},
+    introspection::{get_field, ArraySize, FieldType, FieldValue, MessageDescriptor},
+};
+
+#[test]
+fn get_field_by_path() -> Result<(), Box<dyn std::error::Error>> {
+    let mut pose = Pose::default();
+    pose.position.x = 1.5;
+    pose.orientation.w = -2.0;
+    let bytes = r2r::to_cdr(&pose)?;
+
+    let x = get_field("geometry_msgs/msg/Pose", &bytes, "position.x")?;
+    assert_eq!(x, FieldValue::F64(1.5));
+    let w = get_field("geometry_msgs/msg/Pose", &bytes, "orientation.w")?;
+    assert_eq!(w, FieldValue::F64(-2.0));
+
+    assert!(get_field("geometry_msgs/msg/Pose", &bytes, "position").is_err());
+    assert!(get_field("geometry_msgs/msg/Pose", &bytes, "position.q").is_err());
+    assert!(get_field("geometry_msgs/msg/Pose", &bytes, "position.x.y").is_err());
+
+    let mut poses = PoseArray::default();
+    poses.header.frame_id = "map".into();
+    poses.poses = vec![Pose::default(), pose];
+    let bytes = r2r::to_cdr(&poses)?;
+
+    let frame_id = get_field("geometry_msgs/msg/PoseArray", &bytes, "header.frame_id")?;
+    assert_eq!(frame_id, FieldValue::String("map".into()));
+    let x = get_field("geometry_msgs/msg/PoseArray", &bytes, "poses[1].position.x")?;
+    assert_eq!(x, FieldValue::F64(1.5));
+    assert!(get_field("geometry_msgs/msg/PoseArray", &bytes, "poses[2].position.x").is_err());
+    assert!(get_field("geometry_msgs/msg/PoseArray", &bytes, "poses.position.x").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn message_descriptor() -> Result<(), Box<dyn std::error::Error>> {
+    let desc = MessageDescriptor::for_type("geometry_msgs/msg/PoseArray")?;
+    assert_eq!(desc.type_name, "geometry_msgs/msg/PoseArray");
+    let names: Vec<_> = desc.fields.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, vec!["header", "poses"]);
+
+    let poses = &desc.fields[1];
+    assert_eq!(poses.array, Some(ArraySize::Unbounded));
+    let FieldType::Message(pose) = &poses.field_type else {
+        panic!("poses is not a message");
+    };
+    assert_eq!(pose.type_name, "geometry_msgs/msg/Pose");
+    let FieldType::Message(point) = &pose.fields[0].field_type else {
+        panic!("position is not a message");
+    };
+    assert_eq!(point.fields[0].field_type, FieldType::F64);
+    assert_eq!(point.fields[0].array, None);
+
+    assert!(MessageDescriptor::for_type("geometry_msgs/msg/NoSuchMessage").is_err());
+    Ok(())
+}
+
+#[test]
+fn message_descriptor_nesting() -> Result<(), Box<dyn std::error::Error>> {
+    let desc = MessageDescriptor::for_type("geometry_msgs/msg/PoseStamped")?;
+    let names = |desc: &MessageDescriptor| {
+        desc.fields()
+            .iter()
+            .map(|f| f.name.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names(&desc), vec!["header", "pose"]);
+
+    let header = desc.fields()[0].nested_type().expect("header is a message");
+    assert_eq!(header.type_name, "std_msgs/msg/Header");
+    assert_eq!(names(header), vec!["stamp", "frame_id"]);
+    let stamp = header.fields()[0]
+        .nested_type()
+        .expect("stamp is a message");
+    assert_eq!(stamp.type_name, "builtin_interfaces/msg/Time");
+    assert_eq!(names(stamp), vec!["sec", "nanosec"]);
+    assert_eq!(stamp.fields()[0].field_type, FieldType::I32);
+    assert_eq!(stamp.fields()[1].field_type, FieldType::U32);
+    assert_eq!(header.fields()[1].field_type, FieldType::String { upper_bound: None });
+
+    let pose = desc.fields()[1].nested_type().expect("pose is a message");
+    assert_eq!(pose.type_name, "geometry_msgs/msg/Pose");
+    assert_eq!(names(pose), vec!["position", "orientation"]);
+    let position = pose.fields()[0]
+        .nested_type()
+        .expect("position is a message");
+    assert_eq!(names(position), vec!["x", "y", "z"]);
+    let orientation = pose.fields()[1]
+        .nested_type()
+        .expect("orientation is a message");
+    assert_eq!(names(orientation), vec!["x", "y", "z", "w"]);
+    for field in position.fields().iter().chain(orientation.fields()) {
+        assert_eq!(field.field_type, FieldType::F64);
+        assert!(field.nested_type().is_none());
+        assert!(!field.is_sequence());
+        assert_eq!(field.array_size(), None);
+    }
+
+    // the covariance of e.g. PoseWithCovariance is a fixed size array.
+    let desc = MessageDescriptor::for_type("geometry_msgs/msg/PoseWithCovariance")?;
+    let covariance = &desc.fields()[1];
+    assert_eq!(covariance.name, "covariance");
+    assert!(!covariance.is_sequence());
+    assert!(!covariance.is_bounded());
+    assert_eq!(covariance.array_size(), Some(36));
+
+    Ok(())
+}
@@ -0,0 +1,227 @@
+#![cfg(r2r__lifecycle_msgs__srv__ChangeState)]
+
+use futures::stream::StreamExt;
+use r2r::{lifecycle_msgs, CallbackReturn, LifecycleState, QosProfile};
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn lifecycle_transitions() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::LifecycleNode::create(ctx.clone(), "testnode_lifecycle", "")?;
+    let mut client_node = r2r::Node::create(ctx, "testnode_lifecycle_client", "")?;
+
+    let configured = Arc::new(AtomicBool::new(false));
+    let configured_cb = configured.clone();
+    node.on_configure(move |previous| {
+        let configured = configured_cb.clone();
+        async move {
+            assert_eq!(previous, LifecycleState::Unconfigured);
+            configured.store(true, Ordering::SeqCst);
+            CallbackReturn::Success
+        }
+    });
+    node.on_activate(|_| async { CallbackReturn::Failure });
+
+    let publisher = node.create_lifecycle_publisher::<r2r::std_msgs::msg::Int32>(
+        "/lifecycle_no",
+        QosProfile::default(),
+    )?;
+
+    let (handler, mut states) = node.make_lifecycle_handler()?;
+    tokio::spawn(handler);
+
+    let client = client_node.create_client::<lifecycle_msgs::srv::ChangeState::Service>(
+        "/testnode_lifecycle/change_state",
+        QosProfile::default(),
+    )?;
+    let service_available = r2r::Node::is_available(&client)?;
+
+    let node = Arc::new(Mutex::new(node));
+    let client_node = Arc::new(Mutex::new(client_node));
+    let spin_node = node.clone();
+    let done = Arc::new(AtomicBool::new(false));
+    let spin_done = done.clone();
+    let spin_task = tokio::task::spawn_blocking(move || {
+        while !spin_done.load(Ordering::SeqCst) {
+            spin_node
+                .lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+            client_node
+                .lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+        }
+    });
+
+    service_available.await?;
+
+    let transition = |id| lifecycle_msgs::srv::ChangeState::Request {
+        transition: lifecycle_msgs::msg::Transition {
+            id,
+            label: String::new(),
+        },
+    };
+
+    // configure succeeds and runs the callback.
+    let response = client
+        .request(&transition(lifecycle_msgs::msg::Transition::TRANSITION_CONFIGURE as u8))?
+        .await?;
+    assert!(response.success);
+    assert!(configured.load(Ordering::SeqCst));
+    assert_eq!(node.lock().unwrap().get_state(), LifecycleState::Inactive);
+    assert_eq!(states.next().await, Some(LifecycleState::Configuring));
+    assert_eq!(states.next().await, Some(LifecycleState::Inactive));
+
+    // the activate callback fails, so we stay inactive.
+    let response = client
+        .request(&transition(lifecycle_msgs::msg::Transition::TRANSITION_ACTIVATE as u8))?
+        .await?;
+    assert!(!response.success);
+    assert_eq!(node.lock().unwrap().get_state(), LifecycleState::Inactive);
+    assert!(!publisher.is_activated());
+    publisher.publish(&r2r::std_msgs::msg::Int32 { data: 1 })?;
+
+    // deactivate is not available from the inactive state.
+    let response = client
+        .request(&transition(lifecycle_msgs::msg::Transition::TRANSITION_DEACTIVATE as u8))?
+        .await?;
+    assert!(!response.success);
+    assert_eq!(node.lock().unwrap().get_state(), LifecycleState::Inactive);
+
+    done.store(true, Ordering::SeqCst);
+    spin_task.await?;
+
+    Ok(())
+}
+
+#[test]
+fn lifecycle_trigger_transition() -> Result<(), Box<dyn std::error::Error>> {
+    use lifecycle_msgs::msg::Transition;
+
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::LifecycleNode::create(ctx, "testnode_lifecycle_trigger", "")?;
+
+    let called = Arc::new(Mutex::new(Vec::new()));
+    let configure_calls = called.clone();
+    node.on_configure(move |_| {
+        configure_calls.lock().unwrap().push("configure");
+        async { CallbackReturn::Success }
+    });
+    let activate_calls = called.clone();
+    node.on_activate(move |_| {
+        activate_calls.lock().unwrap().push("activate");
+        async { CallbackReturn::Success }
+    });
+
+    assert_eq!(node.current_state(), LifecycleState::Unconfigured);
+    let ids = |node: &r2r::LifecycleNode| {
+        node.available_transitions()
+            .iter()
+            .map(|t| t.id)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        ids(&node),
+        vec![
+            Transition::TRANSITION_CONFIGURE as u8,
+            Transition::TRANSITION_UNCONFIGURED_SHUTDOWN as u8
+        ]
+    );
+
+    // activate is not available before configuring.
+    assert!(node
+        .trigger_transition(Transition::TRANSITION_ACTIVATE as u8)
+        .is_err());
+    assert_eq!(node.current_state(), LifecycleState::Unconfigured);
+
+    node.trigger_transition(Transition::TRANSITION_CONFIGURE as u8)?;
+    assert_eq!(node.current_state(), LifecycleState::Inactive);
+    assert!(ids(&node).contains(&(Transition::TRANSITION_ACTIVATE as u8)));
+
+    node.trigger_transition(Transition::TRANSITION_ACTIVATE as u8)?;
+    assert_eq!(node.current_state(), LifecycleState::Active);
+    assert_eq!(*called.lock().unwrap(), vec!["configure", "activate"]);
+
+    // a failing callback returns an error and goes back to the start state.
+    node.on_deactivate(|_| async { CallbackReturn::Failure });
+    assert!(node
+        .trigger_transition(Transition::TRANSITION_DEACTIVATE as u8)
+        .is_err());
+    assert_eq!(node.current_state(), LifecycleState::Active);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn lifecycle_services_and_events() -> Result<(), Box<dyn std::error::Error>> {
+    use lifecycle_msgs::{
+        msg::{State, Transition, TransitionEvent},
+        srv::GetState,
+    };
+
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::LifecycleNode::create(ctx.clone(), "testnode_lifecycle_events", "")?;
+    let mut client_node = r2r::Node::create(ctx, "testnode_lifecycle_events_client", "")?;
+
+    let (handler, _states) = node.make_lifecycle_handler()?;
+    tokio::spawn(handler);
+    assert!(node.make_lifecycle_handler().is_err());
+
+    let mut events = client_node.subscribe::<TransitionEvent>(
+        "/testnode_lifecycle_events/transition_event",
+        QosProfile::default(),
+    )?;
+    let client = client_node.create_client::<GetState::Service>(
+        "/testnode_lifecycle_events/get_state",
+        QosProfile::default(),
+    )?;
+    let service_available = r2r::Node::is_available(&client)?;
+
+    let node = Arc::new(Mutex::new(node));
+    let client_node = Arc::new(Mutex::new(client_node));
+    let spin_node = node.clone();
+    let done = Arc::new(AtomicBool::new(false));
+    let spin_done = done.clone();
+    let spin_task = tokio::task::spawn_blocking(move || {
+        while !spin_done.load(Ordering::SeqCst) {
+            spin_node
+                .lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+            client_node
+                .lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+        }
+    });
+
+    service_available.await?;
+    let response = client.request(&GetState::Request {})?.await?;
+    assert_eq!(response.current_state.id, State::PRIMARY_STATE_UNCONFIGURED as u8);
+    assert_eq!(response.current_state.label, "unconfigured");
+
+    // give the transition event subscription time to match.
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    node.lock()
+        .unwrap()
+        .trigger_transition(Transition::TRANSITION_CONFIGURE as u8)?;
+
+    let event = events.next().await.expect("no transition event");
+    assert_eq!(event.start_state.id, State::PRIMARY_STATE_UNCONFIGURED as u8);
+    assert_eq!(event.goal_state.id, State::TRANSITION_STATE_CONFIGURING as u8);
+    let event = events.next().await.expect("no transition event");
+    assert_eq!(event.goal_state.id, State::PRIMARY_STATE_INACTIVE as u8);
+
+    let response = client.request(&GetState::Request {})?.await?;
+    assert_eq!(response.current_state.id, State::PRIMARY_STATE_INACTIVE as u8);
+
+    done.store(true, Ordering::SeqCst);
+    spin_task.await?;
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use futures::{future::FutureExt, stream::StreamExt};
+use r2r::QosProfile;
+
+use std::time::Duration;
+
+// Loaned messages are only zero-copy on middlewares that support it (e.g. iceoryx
+// or Cyclone DDS with shared memory), otherwise a heap allocated message is used.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn publish_loaned_message() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_loaned_message", "")?;
+
+    let mut sub =
+        node.subscribe::<r2r::std_msgs::msg::Int32>("/loaned_no", QosProfile::default())?;
+    let publisher =
+        node.create_publisher::<r2r::std_msgs::msg::Int32>("/loaned_no", QosProfile::default())?;
+
+    // wait for the publisher and subscriber to discover each other.
+    for _ in 0..100 {
+        node.spin_once(Duration::from_millis(10));
+        if publisher.get_inter_process_subscription_count()? > 0 {
+            break;
+        }
+    }
+
+    let mut msg = publisher.borrow_loaned_message()?;
+    msg.data = 17;
+    publisher.publish_native(&mut msg)?;
+
+    let mut received = None;
+    for _ in 0..100 {
+        node.spin_once(Duration::from_millis(10));
+        if let Some(msg) = sub.next().now_or_never() {
+            received = msg;
+            break;
+        }
+    }
+
+    let msg = received.expect("no message received");
+    assert_eq!(msg.data, 17);
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use futures::{future::FutureExt, stream::StreamExt};
+use r2r::{rcl_interfaces::msg::Log, LogSeverity, QosProfile};
+
+use std::time::Duration;
+
+// Log messages of a node are published on /rosout.
+#[test]
+fn node_logs_published_on_rosout() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_logging", "")?;
+
+    // the rosout publisher is transient local, so earlier messages are received too.
+    let qos = QosProfile::default()
+        .reliable()
+        .transient_local()
+        .keep_last(100);
+    let mut rosout = node.subscribe::<Log>("/rosout", qos)?;
+
+    node.set_log_level(LogSeverity::Info)?;
+    r2r::log_debug!(node, "hidden {}", 0);
+    r2r::log_info!(node, "info {}", 1);
+    r2r::log_warn!(node.logger(), "warn {}", 2);
+    for i in 0..5 {
+        r2r::log_error_throttle!(&node, Duration::from_secs(10), "throttled {}", i);
+    }
+    node.set_log_level(LogSeverity::Error)?;
+    r2r::log_warn!(node, "hidden {}", 3);
+    r2r::log_fatal!(node, "fatal {}", 4);
+
+    let mut received = Vec::new();
+    for _ in 0..200 {
+        node.spin_once(Duration::from_millis(10));
+        while let Some(Some(log)) = rosout.next().now_or_never() {
+            if log.name == "testnode_logging" {
+                received.push((log.level, log.msg));
+            }
+        }
+        if received.len() >= 4 {
+            break;
+        }
+    }
+    // give a hidden or repeated message the chance to show up.
+    for _ in 0..10 {
+        node.spin_once(Duration::from_millis(10));
+        while let Some(Some(log)) = rosout.next().now_or_never() {
+            if log.name == "testnode_logging" {
+                received.push((log.level, log.msg));
+            }
+        }
+    }
+
+    assert_eq!(
+        received,
+        vec![
+            (Log::INFO as u8, "info 1".to_owned()),
+            (Log::WARN as u8, "warn 2".to_owned()),
+            (Log::ERROR as u8, "throttled 0".to_owned()),
+            (Log::FATAL as u8, "fatal 4".to_owned()),
+        ]
+    );
+
+    Ok(())
+}
+
+// Throttled and once messages are logged only a few times in a tight loop.
+#[test]
+fn throttle_and_once_suppress_repeats() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_logging_throttle", "")?;
+
+    let qos = QosProfile::default()
+        .reliable()
+        .transient_local()
+        .keep_last(100);
+    let mut rosout = node.subscribe::<Log>("/rosout", qos)?;
+
+    for i in 0..1000 {
+        r2r::log_warn_throttle!(node, 1.0, "throttled {}", i);
+        r2r::log_info_once!(node, "once {}", i);
+    }
+
+    let mut received = Vec::new();
+    for _ in 0..100 {
+        node.spin_once(Duration::from_millis(10));
+        while let Some(Some(log)) = rosout.next().now_or_never() {
+            if log.name == "testnode_logging_throttle" {
+                received.push(log.msg);
+            }
+        }
+    }
+
+    let throttled = received
+        .iter()
+        .filter(|m| m.starts_with("throttled"))
+        .count();
+    assert!((1..=3).contains(&throttled), "throttled logged {throttled} times");
+    assert_eq!(
+        received
+            .iter()
+            .filter(|m| m.starts_with("once"))
+            .collect::<Vec<_>>(),
+        ["once 0"]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,16 @@
+use r2r::geometry_msgs::msg::Pose;
+
+#[test]
+fn assert_message_eq_names_the_field() {
+    let expected = Pose::default();
+    let mut received = Pose::default();
+    received.position.z = 0.5;
+
+    r2r::assert_message_eq!(expected, Pose::default());
+
+    let panic = std::panic::catch_unwind(|| r2r::assert_message_eq!(received, expected))
+        .expect_err("the messages differ");
+    let message = panic.downcast_ref::<String>().expect("a formatted message");
+    assert!(message.contains("position.z: 0.0 != 0.5"), "{message}");
+    assert!(!message.contains("position.x"), "{message}");
+}
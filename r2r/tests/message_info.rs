@@ -0,0 +1,55 @@
+use futures::{future::FutureExt, stream::StreamExt};
+use r2r::{builtin_interfaces::msg::Time, std_msgs::msg::Header, QosProfile};
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn subscribe_with_info() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_message_info", "")?;
+
+    let mut sub = node.subscribe_with_info::<Header>("/stamped", QosProfile::default())?;
+    let publisher = node.create_publisher::<Header>("/stamped", QosProfile::default())?;
+
+    // wait for the publisher and subscriber to discover each other.
+    for _ in 0..100 {
+        node.spin_once(Duration::from_millis(10));
+        if publisher.get_inter_process_subscription_count()? > 0 {
+            break;
+        }
+    }
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+    let header = Header {
+        stamp: Time {
+            sec: now.as_secs() as i32,
+            nanosec: now.subsec_nanos(),
+        },
+        frame_id: "base_link".into(),
+    };
+    publisher.publish(&header)?;
+
+    let mut received = None;
+    for _ in 0..100 {
+        node.spin_once(Duration::from_millis(10));
+        if let Some(msg) = sub.next().now_or_never() {
+            received = msg;
+            break;
+        }
+    }
+
+    let (msg, info) = received.expect("no message received");
+    assert_eq!(msg, header);
+
+    let stamp = UNIX_EPOCH + now;
+    let source_timestamp = info
+        .source_timestamp
+        .expect("rmw did not set source timestamp");
+    let diff = source_timestamp
+        .duration_since(stamp)
+        .unwrap_or_else(|e| e.duration());
+    assert!(diff < Duration::from_millis(1), "{diff:?}");
+    assert!(info.received_timestamp >= source_timestamp);
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use r2r::{std_msgs::msg::Int32, Error, PublisherOptions, QosProfile, SubscriptionOptions};
+
+#[test]
+fn unique_network_flow_endpoints() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_network_flow_endpoints", "")?;
+
+    let publisher_options = PublisherOptions {
+        unique_network_flow_endpoint: true,
+        ..Default::default()
+    };
+    let publisher = node.create_publisher_with_options::<Int32>(
+        "/network_flow_endpoints",
+        QosProfile::default(),
+        publisher_options,
+    )?;
+    let subscription_options = SubscriptionOptions {
+        unique_network_flow_endpoint: true,
+        ..Default::default()
+    };
+    let _subscription = node.subscribe_with_options::<Int32>(
+        "/network_flow_endpoints",
+        QosProfile::default(),
+        subscription_options,
+    )?;
+
+    // not all middlewares report their endpoints, the default one does not.
+    match publisher.get_network_flow_endpoints() {
+        Ok(endpoints) => assert!(!endpoints.is_empty()),
+        Err(Error::UnsupportedFeature(_)) => (),
+        Err(e) => return Err(e.into()),
+    }
+    match node.get_subscription_network_flow_endpoints("/network_flow_endpoints") {
+        Ok(endpoints) => assert!(!endpoints.is_empty()),
+        Err(Error::UnsupportedFeature(_)) => (),
+        Err(e) => return Err(e.into()),
+    }
+
+    assert!(node
+        .get_subscription_network_flow_endpoints("/no_such_subscription")
+        .is_err());
+    Ok(())
+}
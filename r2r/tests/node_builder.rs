@@ -0,0 +1,46 @@
+use r2r::NodeBuilder;
+
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn builder_sets_namespace_and_rosout() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut built = NodeBuilder::new(ctx.clone(), "testnode_builder")
+        .namespace("/builder_ns")
+        .enable_rosout(false)
+        .build()?;
+    let mut node = r2r::Node::create(ctx, "testnode_builder_observer", "")?;
+
+    assert_eq!(built.namespace()?, "/builder_ns");
+    assert_eq!(built.fully_qualified_name()?, "/builder_ns/testnode_builder");
+
+    // wait a little for the graph to populate(?). hack to avoid CI failures.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    node.spin_once(Duration::from_millis(10));
+    built.spin_once(Duration::from_millis(10));
+
+    let rosout_nodes: Vec<_> = node
+        .get_publishers_info_by_topic("/rosout", false)?
+        .into_iter()
+        .map(|info| info.node_name)
+        .collect();
+    assert!(rosout_nodes
+        .iter()
+        .any(|n| n == "testnode_builder_observer"));
+    assert!(!rosout_nodes.iter().any(|n| n == "testnode_builder"));
+
+    Ok(())
+}
+
+#[test]
+fn builder_validates_names() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    assert!(NodeBuilder::new(ctx.clone(), "invalid name")
+        .build()
+        .is_err());
+    assert!(NodeBuilder::new(ctx, "testnode_builder_bad_ns")
+        .namespace("/bad//ns")
+        .build()
+        .is_err());
+    Ok(())
+}
@@ -0,0 +1,35 @@
+#[test]
+fn node_name_collision() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let node = r2r::Node::create(ctx.clone(), "testnode_collision", "/names")?;
+
+    let again = r2r::Node::create(ctx.clone(), "testnode_collision", "/names");
+    match again {
+        Err(r2r::Error::NodeNameCollision { name, namespace }) => {
+            assert_eq!(name, "testnode_collision");
+            assert_eq!(namespace, "/names");
+        }
+        _ => panic!("expected a name collision"),
+    }
+
+    // the same name in another namespace is a different node.
+    let _other = r2r::Node::create(ctx.clone(), "testnode_collision", "/other_names")?;
+
+    // and the name is free again once the node is dropped.
+    drop(node);
+    let _node = r2r::Node::create(ctx, "testnode_collision", "/names")?;
+    Ok(())
+}
+
+#[test]
+fn invalid_node_names() -> Result<(), Box<dyn std::error::Error>> {
+    assert!(r2r::validate_node_name("my_node").is_ok());
+    assert!(r2r::validate_node_name("my node").is_err());
+    assert!(r2r::validate_namespace("/my_ns").is_ok());
+    assert!(r2r::validate_namespace("my_ns").is_err());
+
+    let ctx = r2r::Context::create()?;
+    let node = r2r::Node::create(ctx, "2node", "");
+    assert!(matches!(node, Err(r2r::Error::InvalidNodeName { .. })));
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use futures::{future::FutureExt, stream::StreamExt};
+use r2r::{test_msgs::srv::BasicTypes, QosProfile};
+
+use std::time::Duration;
+
+#[test]
+fn shutdown_invalidates_entities() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx.clone(), "testnode_shutdown", "")?;
+    let mut other = r2r::Node::create(ctx.clone(), "testnode_shutdown_other", "")?;
+
+    let publisher = node
+        .create_publisher::<r2r::std_msgs::msg::Int32>("/shutdown_topic", QosProfile::default())?;
+    let mut sub =
+        node.subscribe::<r2r::std_msgs::msg::Int32>("/shutdown_topic", QosProfile::default())?;
+    let client =
+        node.create_client::<BasicTypes::Service>("/shutdown_service", QosProfile::default())?;
+
+    node.shutdown()?;
+
+    let msg = r2r::std_msgs::msg::Int32 { data: 1 };
+    assert!(matches!(publisher.publish(&msg), Err(r2r::Error::RCL_RET_PUBLISHER_INVALID)));
+    assert_eq!(sub.next().now_or_never(), Some(None));
+    assert!(matches!(
+        client.request(&BasicTypes::Request::default()),
+        Err(r2r::Error::RCL_RET_CLIENT_INVALID)
+    ));
+
+    // the context, and the other nodes in it, are still usable.
+    assert!(ctx.is_valid());
+    let other_publisher = other
+        .create_publisher::<r2r::std_msgs::msg::Int32>("/shutdown_topic", QosProfile::default())?;
+    other_publisher.publish(&msg)?;
+    other.spin_once(Duration::from_millis(10));
+    // the name can be taken by a new node.
+    let _node = r2r::Node::create(ctx, "testnode_shutdown", "")?;
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use futures::{future::FutureExt, stream::StreamExt};
+use r2r::{BatchParameterEvent, Parameter, ParameterValue};
+
+#[test]
+fn atomic_changes_are_batched() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_parameter_batch", "")?;
+    {
+        let mut params = node.params.lock().unwrap();
+        params.insert("x".into(), Parameter::new(ParameterValue::Double(0.0)));
+        params.insert("y".into(), Parameter::new(ParameterValue::Double(0.0)));
+        params.insert("z".into(), Parameter::new(ParameterValue::Double(0.0)));
+    }
+    let (_handler, mut batches) = node.make_parameter_handler_batched()?;
+
+    node.set_parameters_atomically(&[
+        ("x", ParameterValue::Double(1.0)),
+        ("y", ParameterValue::Double(2.0)),
+        ("z", ParameterValue::Double(3.0)),
+    ])?;
+
+    let batch = batches
+        .next()
+        .now_or_never()
+        .flatten()
+        .expect("no batch event");
+    assert_eq!(
+        batch,
+        BatchParameterEvent {
+            node_name: "/testnode_parameter_batch".into(),
+            changes: vec![
+                ("x".into(), ParameterValue::Double(1.0)),
+                ("y".into(), ParameterValue::Double(2.0)),
+                ("z".into(), ParameterValue::Double(3.0)),
+            ],
+        }
+    );
+    // all three changes came in the one event.
+    assert!(batches.next().now_or_never().is_none());
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use futures::{future, stream::StreamExt};
+use r2r::{rcl_interfaces, ParameterValue};
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn parameter_events_of_remote_node() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut observer = r2r::Node::create(ctx.clone(), "testnode_param_events_observer", "")?;
+    let mut remote = r2r::Node::create(ctx, "testnode_param_events_remote", "/events_ns")?;
+
+    let (parameter_handler, _parameter_events) = remote.make_parameter_handler()?;
+    tokio::spawn(parameter_handler);
+
+    // other tests may run in parallel, only look at events of the remote node.
+    let mut all_events = observer
+        .subscribe_to_parameter_events()?
+        .filter(|event| future::ready(event.node == "/events_ns/testnode_param_events_remote"));
+    let mut remote_events = observer
+        .subscribe_to_parameter_events_for_node("/events_ns/testnode_param_events_remote")?;
+    let client =
+        r2r::ParameterClient::new(&mut observer, "/events_ns/testnode_param_events_remote")?;
+    let services_available = client.wait_for_services()?;
+
+    let observer = Arc::new(Mutex::new(observer));
+    let remote = Arc::new(Mutex::new(remote));
+    let done = Arc::new(AtomicBool::new(false));
+    let spin_done = done.clone();
+    let spin_task = tokio::task::spawn_blocking(move || {
+        while !spin_done.load(Ordering::SeqCst) {
+            remote
+                .lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+            observer
+                .lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+        }
+    });
+
+    services_available.await?;
+
+    let result = client.set("gain", ParameterValue::Double(2.5)).await?;
+    assert!(result.successful);
+
+    let event = all_events.next().await.expect("stream ended");
+    assert_eq!(event.new_parameters.len(), 1);
+    assert_eq!(event.new_parameters[0].name, "gain");
+    assert_eq!(
+        event.new_parameters[0].value.type_,
+        rcl_interfaces::msg::ParameterType::PARAMETER_DOUBLE as u8
+    );
+    assert_eq!(event.new_parameters[0].value.double_value, 2.5);
+
+    let (name, value) = remote_events.next().await.expect("stream ended");
+    assert_eq!(name, "gain");
+    assert_eq!(value, ParameterValue::Double(2.5));
+
+    // changing the value again is reported as a change, not a new parameter.
+    client.set("gain", ParameterValue::Double(3.0)).await?;
+    let event = all_events.next().await.expect("stream ended");
+    assert!(event.new_parameters.is_empty());
+    assert_eq!(event.changed_parameters[0].name, "gain");
+    let (name, value) = remote_events.next().await.expect("stream ended");
+    assert_eq!(name, "gain");
+    assert_eq!(value, ParameterValue::Double(3.0));
+
+    done.store(true, Ordering::SeqCst);
+    spin_task.await?;
+
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use futures::{future::FutureExt, stream::StreamExt};
+use r2r::{ParameterDescriptor, ParameterGroup, ParameterValue};
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+#[derive(r2r::Parameters, Debug, PartialEq)]
+struct Config {
+    /// Proportional gain
+    kp: f64,
+    ki: f64,
+    kd: f64,
+    label: String,
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn parameter_group_reload() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx.clone(), "testnode_param_group", "")?;
+    let mut client_node = r2r::Node::create(ctx, "testnode_param_group_client", "")?;
+
+    let defaults = Config {
+        kp: 1.0,
+        ki: 0.1,
+        kd: 0.0,
+        label: "pid".into(),
+    };
+    Config::declare(&mut node, "pid", defaults)?;
+    assert_eq!(node.get_parameter::<f64>("pid.kp")?, 1.0);
+    assert_eq!(
+        Config::load(&node, "pid")?,
+        Config {
+            kp: 1.0,
+            ki: 0.1,
+            kd: 0.0,
+            label: "pid".into(),
+        }
+    );
+    // the parameters are looked up by their full name.
+    assert!(Config::load(&node, "other").is_err());
+
+    let mut configs = Config::event_stream(&mut node, "pid")?;
+    let (parameter_handler, _parameter_events) = node.make_parameter_handler()?;
+    tokio::spawn(parameter_handler);
+
+    let client = r2r::ParameterClient::new(&mut client_node, "/testnode_param_group")?;
+    let services_available = client.wait_for_services()?;
+
+    let node = Arc::new(Mutex::new(node));
+    let done = Arc::new(AtomicBool::new(false));
+    let spin_node = node.clone();
+    let spin_done = done.clone();
+    let spin_task = tokio::task::spawn_blocking(move || {
+        while !spin_done.load(Ordering::SeqCst) {
+            spin_node
+                .lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+            client_node.spin_once(std::time::Duration::from_millis(5));
+        }
+    });
+
+    services_available.await?;
+
+    let result = client.set("pid.ki", ParameterValue::Double(0.5)).await?;
+    assert!(result.successful);
+
+    let config = configs.next().await.expect("stream ended")?;
+    assert_eq!(
+        config,
+        Config {
+            kp: 1.0,
+            ki: 0.5,
+            kd: 0.0,
+            label: "pid".into(),
+        }
+    );
+    assert_eq!(Config::load(&node.lock().unwrap(), "pid")?, config);
+
+    // a value of the wrong type is reported as an error.
+    client.set("pid.label", ParameterValue::Integer(3)).await?;
+    assert!(configs.next().await.expect("stream ended").is_err());
+
+    done.store(true, Ordering::SeqCst);
+    spin_task.await?;
+
+    Ok(())
+}
+
+#[test]
+fn parameter_group_events() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_param_group_events", "")?;
+    for (name, value) in [
+        ("camera.width", 640),
+        ("camera.height", 480),
+        ("lidar.rpm", 600),
+    ] {
+        node.declare_parameter(
+            name,
+            ParameterValue::Integer(value),
+            ParameterDescriptor::default(),
+        )?;
+    }
+
+    let mut camera = ParameterGroup::new(&mut node, "camera")?;
+    let mut lidar = ParameterGroup::new(&mut node, "lidar")?;
+    assert_eq!(camera.get::<i64>("width")?, 640);
+    assert_eq!(lidar.get::<i64>("rpm")?, 600);
+    assert!(camera.get::<i64>("rpm").is_err());
+
+    // changes are announced once the parameter handler exists.
+    let (_parameter_handler, _parameter_events) = node.make_parameter_handler()?;
+    let mut camera_events = camera.event_stream()?;
+    let mut lidar_events = lidar.event_stream()?;
+    assert!(camera.event_stream().is_err());
+
+    camera.set("width", ParameterValue::Integer(1280))?;
+    assert_eq!(node.get_parameter::<i64>("camera.width")?, 1280);
+
+    let mut event = None;
+    for _ in 0..200 {
+        node.spin_once(std::time::Duration::from_millis(10));
+        if let Some(e) = camera_events.next().now_or_never() {
+            event = e;
+            break;
+        }
+    }
+    assert_eq!(event, Some(("width".to_owned(), ParameterValue::Integer(1280))));
+    node.spin_once(std::time::Duration::from_millis(10));
+    assert!(lidar_events.next().now_or_never().is_none());
+
+    Ok(())
+}
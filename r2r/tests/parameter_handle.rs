@@ -0,0 +1,83 @@
+use futures::stream::StreamExt;
+use r2r::ParameterValue;
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn parameter_handle_follows_changes() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx.clone(), "testnode_param_handle", "")?;
+    let mut client_node = r2r::Node::create(ctx, "testnode_param_handle_client", "")?;
+
+    let mut rate = node.declare_parameter_live("rate", 10.0)?;
+    assert_eq!(rate.get(), 10.0);
+    let mut changes = rate.changed()?;
+    assert!(rate.changed().is_err());
+
+    // a value of another type, e.g. given on the command line, is kept, and
+    // the default is used instead.
+    node.declare_parameter(
+        "mode",
+        ParameterValue::String("auto".into()),
+        r2r::ParameterDescriptor::default(),
+    )?;
+    let mode = node.declare_parameter_live("mode", 1i64)?;
+    assert_eq!(mode.get(), 1);
+    mode.set_default(2);
+    assert_eq!(mode.get(), 2);
+
+    // the descriptor of an earlier declaration is kept.
+    let read_only = r2r::ParameterDescriptor {
+        read_only: true,
+        ..Default::default()
+    };
+    node.declare_parameter("gain", ParameterValue::Double(0.5), read_only)?;
+    let gain = node.declare_parameter_live("gain", 1.0)?;
+    assert_eq!(gain.get(), 0.5);
+
+    let (parameter_handler, _parameter_events) = node.make_parameter_handler()?;
+    tokio::spawn(parameter_handler);
+
+    let client = r2r::ParameterClient::new(&mut client_node, "/testnode_param_handle")?;
+    let services_available = client.wait_for_services()?;
+
+    let node = Arc::new(Mutex::new(node));
+    let done = Arc::new(AtomicBool::new(false));
+    let spin_node = node.clone();
+    let spin_done = done.clone();
+    let spin_task = tokio::task::spawn_blocking(move || {
+        while !spin_done.load(Ordering::SeqCst) {
+            spin_node
+                .lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+            client_node.spin_once(std::time::Duration::from_millis(5));
+        }
+    });
+
+    services_available.await?;
+
+    let result = client.set("rate", ParameterValue::Double(25.0)).await?;
+    assert!(result.successful);
+    assert_eq!(rate.get(), 25.0);
+    assert_eq!(changes.next().await, Some(25.0));
+
+    // the type of the parameter can not be changed.
+    let result = client
+        .set("rate", ParameterValue::String("fast".into()))
+        .await?;
+    assert!(!result.successful);
+    assert_eq!(rate.get(), 25.0);
+
+    let result = client.set("gain", ParameterValue::Double(2.0)).await?;
+    assert!(!result.successful);
+    assert_eq!(gain.get(), 0.5);
+
+    done.store(true, Ordering::SeqCst);
+    spin_task.await?;
+
+    Ok(())
+}
@@ -0,0 +1,758 @@
+use r2r::{
+    rcl_interfaces, FloatingPointRange, IntegerRange, Parameter, ParameterDescriptor,
+    ParameterValue, QosProfile,
+};
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+fn set_gain_request(gain: f64) -> rcl_interfaces::srv::SetParameters::Request {
+    rcl_interfaces::srv::SetParameters::Request {
+        parameters: vec![rcl_interfaces::msg::Parameter {
+            name: "gain".into(),
+            value: rcl_interfaces::msg::ParameterValue {
+                type_: rcl_interfaces::msg::ParameterType::PARAMETER_DOUBLE as u8,
+                double_value: gain,
+                ..Default::default()
+            },
+        }],
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn parameter_callback_rejects() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx.clone(), "testnode_param_callback", "")?;
+    let mut client_node = r2r::Node::create(ctx, "testnode_param_callback_client", "")?;
+
+    node.params
+        .lock()
+        .unwrap()
+        .insert("gain".into(), Parameter::new(ParameterValue::Double(1.0)));
+
+    let second_called = Arc::new(AtomicBool::new(false));
+    node.add_parameter_callback(|name, value| match (name, value) {
+        ("gain", ParameterValue::Double(gain)) if *gain < 0.0 => {
+            Err("gain must not be negative".into())
+        }
+        _ => Ok(()),
+    });
+    let second_called_cb = second_called.clone();
+    node.add_parameter_callback(move |_, _| {
+        second_called_cb.store(true, Ordering::SeqCst);
+        Ok(())
+    });
+
+    let (parameter_handler, _parameter_events) = node.make_parameter_handler()?;
+    tokio::spawn(parameter_handler);
+
+    let client = client_node.create_client::<rcl_interfaces::srv::SetParameters::Service>(
+        "/testnode_param_callback/set_parameters",
+        QosProfile::default(),
+    )?;
+    let service_available = r2r::Node::is_available(&client)?;
+
+    let node = Arc::new(Mutex::new(node));
+    let client_node = Arc::new(Mutex::new(client_node));
+    let spin_node = node.clone();
+    let done = Arc::new(AtomicBool::new(false));
+    let spin_done = done.clone();
+    let spin_task = tokio::task::spawn_blocking(move || {
+        while !spin_done.load(Ordering::SeqCst) {
+            spin_node
+                .lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+            client_node
+                .lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+        }
+    });
+
+    service_available.await?;
+
+    // rejected by the first callback, the second one is never called.
+    let response = client.request(&set_gain_request(-1.0))?.await?;
+    assert!(!response.results[0].successful);
+    assert_eq!(response.results[0].reason, "gain must not be negative");
+    assert!(!second_called.load(Ordering::SeqCst));
+    assert_eq!(node.lock().unwrap().get_parameter::<f64>("gain")?, 1.0);
+
+    let response = client.request(&set_gain_request(2.0))?.await?;
+    assert!(response.results[0].successful);
+    assert!(second_called.load(Ordering::SeqCst));
+    assert_eq!(node.lock().unwrap().get_parameter::<f64>("gain")?, 2.0);
+
+    done.store(true, Ordering::SeqCst);
+    spin_task.await?;
+
+    Ok(())
+}
+
+#[test]
+fn parameter_file_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx.clone(), "testnode_param_file", "/param_ns")?;
+
+    let dir = std::env::temp_dir();
+    let path = dir.join(format!("r2r_params_{}.yaml", std::process::id()));
+    std::fs::write(
+        &path,
+        r#"
+/**:
+  ros__parameters:
+    shared: true
+/param_ns/testnode_param_file:
+  ros__parameters:
+    gain: 1.5
+    name: "wheel"
+    limits:
+      max: 10
+    bools: [true, false]
+    ints: [1, 2, 3]
+    doubles: [1.0, 2.5]
+    strings: ["a", "b"]
+other_node:
+  ros__parameters:
+    gain: 100.0
+"#,
+    )?;
+    node.load_params_from_file(&path)?;
+
+    assert!(node.get_parameter::<bool>("shared")?);
+    assert_eq!(node.get_parameter::<f64>("gain")?, 1.5);
+    assert_eq!(node.get_parameter::<String>("name")?, "wheel");
+    assert_eq!(node.get_parameter::<i64>("limits.max")?, 10);
+    assert_eq!(node.get_parameter::<Vec<bool>>("bools")?, vec![true, false]);
+    assert_eq!(node.get_parameter::<Vec<i64>>("ints")?, vec![1, 2, 3]);
+    assert_eq!(node.get_parameter::<Vec<f64>>("doubles")?, vec![1.0, 2.5]);
+    assert_eq!(node.get_parameter::<Vec<String>>("strings")?, vec!["a", "b"]);
+
+    {
+        let mut params = node.params.lock().unwrap();
+        params.get_mut("gain").unwrap().value = ParameterValue::Double(2.0);
+        params.insert("bytes".into(), Parameter::new(ParameterValue::ByteArray(vec![1, 255])));
+    }
+    node.save_params_to_file(&path)?;
+
+    // a separate context, as node names are unique within a context.
+    let ctx = r2r::Context::create_with_args(vec![])?;
+    let mut loaded = r2r::Node::create(ctx, "testnode_param_file", "/param_ns")?;
+    // byte arrays are saved as integer lists, read back by type.
+    loaded
+        .params
+        .lock()
+        .unwrap()
+        .insert("bytes".into(), Parameter::new(ParameterValue::ByteArray(vec![])));
+    let results = loaded.load_params_from_file(&path)?;
+    assert!(results.iter().all(|r| r.successful), "{results:?}");
+
+    let params = node.params.lock().unwrap();
+    let loaded_params = loaded.params.lock().unwrap();
+    assert_eq!(params.len(), loaded_params.len());
+    for (name, param) in params.iter() {
+        assert_eq!(loaded_params.get(name).map(|p| &p.value), Some(&param.value), "{name}");
+    }
+    assert_eq!(loaded_params.get("gain").unwrap().value, ParameterValue::Double(2.0));
+    assert_eq!(
+        loaded_params.get("bytes").unwrap().value,
+        ParameterValue::ByteArray(vec![1, 255])
+    );
+    drop(params);
+    drop(loaded_params);
+
+    // "gain" cannot be both a value and the mapping holding "gain.max".
+    node.params
+        .lock()
+        .unwrap()
+        .insert("gain.max".into(), Parameter::new(ParameterValue::Double(3.0)));
+    assert!(matches!(node.save_params_to_file(&path), Err(r2r::Error::ParameterFile { .. })));
+
+    // values from a file are validated like those of set_parameters.
+    loaded.declare_parameter(
+        "mode",
+        ParameterValue::String("auto".into()),
+        ParameterDescriptor {
+            read_only: true,
+            ..Default::default()
+        },
+    )?;
+    loaded.add_parameter_callback(|name, value| match (name, value) {
+        ("gain", ParameterValue::Double(gain)) if *gain < 0.0 => {
+            Err("gain must not be negative".into())
+        }
+        _ => Ok(()),
+    });
+    std::fs::write(
+        &path,
+        r#"
+/param_ns/testnode_param_file:
+  ros__parameters:
+    mode: "manual"
+    gain: -1.0
+    limits:
+      max: 20
+"#,
+    )?;
+    let results = loaded.load_params_from_file(&path)?;
+    let successful = results
+        .iter()
+        .map(|r| (r.name.as_str(), r.successful))
+        .collect::<Vec<_>>();
+    assert_eq!(successful, vec![("mode", false), ("gain", false), ("limits.max", true)]);
+    assert_eq!(loaded.get_parameter::<String>("mode")?, "auto");
+    assert_eq!(loaded.get_parameter::<f64>("gain")?, 2.0);
+    assert_eq!(loaded.get_parameter::<i64>("limits.max")?, 20);
+
+    std::fs::write(&path, "/param_ns/testnode_param_file: [")?;
+    assert!(matches!(
+        loaded.load_params_from_file(&path),
+        Err(r2r::Error::ParameterFile { .. })
+    ));
+    std::fs::remove_file(&path)?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn parameter_client() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx.clone(), "testnode_param_remote", "")?;
+    let mut client_node = r2r::Node::create(ctx, "testnode_param_client", "")?;
+
+    {
+        let mut params = node.params.lock().unwrap();
+        params.insert("gain".into(), Parameter::new(ParameterValue::Double(1.0)));
+        params.insert("limits.max".into(), Parameter::new(ParameterValue::Integer(10)));
+    }
+    node.add_parameter_callback(|name, value| match (name, value) {
+        ("gain", ParameterValue::Double(gain)) if *gain < 0.0 => {
+            Err("gain must not be negative".into())
+        }
+        _ => Ok(()),
+    });
+    let (parameter_handler, _parameter_events) = node.make_parameter_handler()?;
+    tokio::spawn(parameter_handler);
+
+    let client = r2r::ParameterClient::new(&mut client_node, "/testnode_param_remote")?;
+    let services_available = client.wait_for_services()?;
+
+    let node = Arc::new(Mutex::new(node));
+    let client_node = Arc::new(Mutex::new(client_node));
+    let spin_node = node.clone();
+    let done = Arc::new(AtomicBool::new(false));
+    let spin_done = done.clone();
+    let spin_task = tokio::task::spawn_blocking(move || {
+        while !spin_done.load(Ordering::SeqCst) {
+            spin_node
+                .lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+            client_node
+                .lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+        }
+    });
+
+    services_available.await?;
+
+    assert_eq!(client.get("gain").await?, ParameterValue::Double(1.0));
+    assert_eq!(client.get("missing").await?, ParameterValue::NotSet);
+
+    let result = client.set("gain", ParameterValue::Double(2.5)).await?;
+    assert!(result.successful);
+    assert_eq!(node.lock().unwrap().get_parameter::<f64>("gain")?, 2.5);
+
+    let result = client.set("gain", ParameterValue::Double(-1.0)).await?;
+    assert!(!result.successful);
+    assert_eq!(result.reason, "gain must not be negative");
+    assert_eq!(node.lock().unwrap().get_parameter::<f64>("gain")?, 2.5);
+
+    assert_eq!(client.list("limits").await?, vec!["limits.max"]);
+    let descriptor = client.describe("limits.max").await?;
+    assert_eq!(descriptor.type_, rcl_interfaces::msg::ParameterType::PARAMETER_INTEGER as u8);
+
+    done.store(true, Ordering::SeqCst);
+    spin_task.await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn parameter_descriptors() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx.clone(), "testnode_param_descriptor", "")?;
+    let mut client_node = r2r::Node::create(ctx, "testnode_param_descriptor_client", "")?;
+
+    node.declare_parameter(
+        "ratio",
+        ParameterValue::Double(0.5),
+        ParameterDescriptor {
+            description: "a ratio".into(),
+            floating_point_range: Some(FloatingPointRange {
+                min: 0.0,
+                max: 1.0,
+                step: 0.0,
+            }),
+            ..Default::default()
+        },
+    )?;
+    node.declare_parameter(
+        "mode",
+        ParameterValue::String("auto".into()),
+        ParameterDescriptor {
+            read_only: true,
+            ..Default::default()
+        },
+    )?;
+    // the default value must be within range.
+    assert!(node
+        .declare_parameter(
+            "count",
+            ParameterValue::Integer(20),
+            ParameterDescriptor {
+                integer_range: Some(IntegerRange {
+                    min: 0,
+                    max: 10,
+                    step: 2,
+                }),
+                ..Default::default()
+            },
+        )
+        .is_err());
+    // the distance to min does not fit in an i64.
+    node.declare_parameter(
+        "offset",
+        ParameterValue::Integer(i64::MAX - 1),
+        ParameterDescriptor {
+            integer_range: Some(IntegerRange {
+                min: i64::MIN,
+                max: i64::MAX,
+                step: 2,
+            }),
+            ..Default::default()
+        },
+    )?;
+
+    let (parameter_handler, _parameter_events) = node.make_parameter_handler()?;
+    tokio::spawn(parameter_handler);
+
+    let client = r2r::ParameterClient::new(&mut client_node, "/testnode_param_descriptor")?;
+    let services_available = client.wait_for_services()?;
+
+    let node = Arc::new(Mutex::new(node));
+    let client_node = Arc::new(Mutex::new(client_node));
+    let spin_node = node.clone();
+    let done = Arc::new(AtomicBool::new(false));
+    let spin_done = done.clone();
+    let spin_task = tokio::task::spawn_blocking(move || {
+        while !spin_done.load(Ordering::SeqCst) {
+            spin_node
+                .lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+            client_node
+                .lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+        }
+    });
+
+    services_available.await?;
+
+    let result = client.set("ratio", ParameterValue::Double(1.5)).await?;
+    assert!(!result.successful);
+    assert_eq!(node.lock().unwrap().get_parameter::<f64>("ratio")?, 0.5);
+
+    let result = client.set("ratio", ParameterValue::Double(1.0)).await?;
+    assert!(result.successful);
+    assert_eq!(node.lock().unwrap().get_parameter::<f64>("ratio")?, 1.0);
+
+    let result = client
+        .set("mode", ParameterValue::String("manual".into()))
+        .await?;
+    assert!(!result.successful);
+    assert_eq!(node.lock().unwrap().get_parameter::<String>("mode")?, "auto");
+
+    let descriptor = client.describe("ratio").await?;
+    assert_eq!(descriptor.description, "a ratio");
+    assert_eq!(descriptor.floating_point_range.len(), 1);
+    assert_eq!(descriptor.floating_point_range[0].to_value, 1.0);
+    assert!(client.describe("mode").await?.read_only);
+
+    done.store(true, Ordering::SeqCst);
+    spin_task.await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn parameter_client_many() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx.clone(), "testnode_param_many", "")?;
+    let mut client_node = r2r::Node::create(ctx, "testnode_param_many_client", "")?;
+
+    node.declare_parameter(
+        "ratio",
+        ParameterValue::Double(0.5),
+        ParameterDescriptor {
+            floating_point_range: Some(FloatingPointRange {
+                min: 0.0,
+                max: 1.0,
+                step: 0.0,
+            }),
+            ..Default::default()
+        },
+    )?;
+    node.params
+        .lock()
+        .unwrap()
+        .insert("gain".into(), Parameter::new(ParameterValue::Double(1.0)));
+    let (parameter_handler, _parameter_events) = node.make_parameter_handler()?;
+    tokio::spawn(parameter_handler);
+
+    let client = r2r::ParameterClient::new(&mut client_node, "/testnode_param_many")?;
+    let services_available = client.wait_for_services()?;
+
+    let node = Arc::new(Mutex::new(node));
+    let client_node = Arc::new(Mutex::new(client_node));
+    let spin_node = node.clone();
+    let done = Arc::new(AtomicBool::new(false));
+    let spin_done = done.clone();
+    let spin_task = tokio::task::spawn_blocking(move || {
+        while !spin_done.load(Ordering::SeqCst) {
+            spin_node
+                .lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+            client_node
+                .lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+        }
+    });
+
+    services_available.await?;
+
+    assert_eq!(
+        client.get_many(&["gain", "ratio", "missing"]).await?,
+        vec![
+            ParameterValue::Double(1.0),
+            ParameterValue::Double(0.5),
+            ParameterValue::NotSet
+        ]
+    );
+
+    let results = client
+        .set_many(&[
+            ("gain", ParameterValue::Double(2.0)),
+            ("ratio", ParameterValue::Double(2.0)),
+        ])
+        .await?;
+    assert!(results[0].successful);
+    assert!(!results[1].successful);
+    assert_eq!(node.lock().unwrap().get_parameter::<f64>("gain")?, 2.0);
+    assert_eq!(node.lock().unwrap().get_parameter::<f64>("ratio")?, 0.5);
+
+    // the out of range ratio makes the whole request fail.
+    let result = client
+        .set_atomically(&[
+            ("gain", ParameterValue::Double(3.0)),
+            ("ratio", ParameterValue::Double(2.0)),
+        ])
+        .await?;
+    assert!(!result.successful);
+    assert_eq!(node.lock().unwrap().get_parameter::<f64>("gain")?, 2.0);
+
+    let result = client
+        .set_atomically(&[
+            ("gain", ParameterValue::Double(3.0)),
+            ("ratio", ParameterValue::Double(1.0)),
+        ])
+        .await?;
+    assert!(result.successful);
+    assert_eq!(node.lock().unwrap().get_parameter::<f64>("gain")?, 3.0);
+    assert_eq!(node.lock().unwrap().get_parameter::<f64>("ratio")?, 1.0);
+
+    let mut names = client.list("").await?;
+    names.sort();
+    assert!(names.contains(&"gain".to_string()));
+    assert!(names.contains(&"ratio".to_string()));
+
+    let descriptors = client.describe_many(&["gain", "ratio"]).await?;
+    assert_eq!(descriptors.len(), 2);
+    assert_eq!(descriptors[0].name, "gain");
+    assert_eq!(descriptors[1].floating_point_range[0].to_value, 1.0);
+
+    done.store(true, Ordering::SeqCst);
+    spin_task.await?;
+
+    Ok(())
+}
+
+// rejects resolutions beyond 1920x1080, which depend on both parameters.
+fn check_resolution(
+    values: &[(String, ParameterValue)], current: &[(&str, i64)],
+) -> Result<(), String> {
+    let get = |name: &str| {
+        values
+            .iter()
+            .find(|(n, _)| n == name)
+            .and_then(|(_, v)| match v {
+                ParameterValue::Integer(i) => Some(*i),
+                _ => None,
+            })
+            .or_else(|| current.iter().find(|(n, _)| *n == name).map(|(_, v)| *v))
+    };
+    match (get("width"), get("height")) {
+        (Some(w), Some(h)) if w > 1920 && h > 1080 => Err("resolution too large".into()),
+        _ => Ok(()),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn set_parameters_atomically() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx.clone(), "testnode_param_atomic", "")?;
+    let mut client_node = r2r::Node::create(ctx, "testnode_param_atomic_client", "")?;
+
+    {
+        let mut params = node.params.lock().unwrap();
+        params.insert("width".into(), Parameter::new(ParameterValue::Integer(1280)));
+        params.insert("height".into(), Parameter::new(ParameterValue::Integer(720)));
+    }
+    let params = node.params.clone();
+    node.add_parameters_callback(move |values| {
+        let params = params.lock().unwrap();
+        let current = ["width", "height"].map(|n| match params[n].value {
+            ParameterValue::Integer(i) => (n, i),
+            _ => (n, 0),
+        });
+        check_resolution(values, &current)
+    });
+
+    // each value is fine on its own, but not together.
+    let too_large = [
+        ("width", ParameterValue::Integer(2560)),
+        ("height", ParameterValue::Integer(1440)),
+    ];
+    let result = node.set_parameters_atomically(&too_large);
+    assert!(matches!(result, Err(r2r::Error::ParameterRejected { .. })));
+    assert_eq!(node.get_parameter::<i64>("width")?, 1280);
+    assert_eq!(node.get_parameter::<i64>("height")?, 720);
+
+    node.set_parameters_atomically(&[
+        ("width", ParameterValue::Integer(1920)),
+        ("height", ParameterValue::Integer(1080)),
+    ])?;
+    assert_eq!(node.get_parameter::<i64>("width")?, 1920);
+    assert_eq!(node.get_parameter::<i64>("height")?, 1080);
+
+    let (parameter_handler, _parameter_events) = node.make_parameter_handler()?;
+    tokio::spawn(parameter_handler);
+
+    let client = r2r::ParameterClient::new(&mut client_node, "/testnode_param_atomic")?;
+    let services_available = client.wait_for_services()?;
+
+    let node = Arc::new(Mutex::new(node));
+    let client_node = Arc::new(Mutex::new(client_node));
+    let spin_node = node.clone();
+    let done = Arc::new(AtomicBool::new(false));
+    let spin_done = done.clone();
+    let spin_task = tokio::task::spawn_blocking(move || {
+        while !spin_done.load(Ordering::SeqCst) {
+            spin_node
+                .lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+            client_node
+                .lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+        }
+    });
+
+    services_available.await?;
+
+    // the same rejection for remote requests.
+    let result = client.set_atomically(&too_large).await?;
+    assert!(!result.successful);
+    assert_eq!(result.reason, "resolution too large");
+    assert_eq!(node.lock().unwrap().get_parameter::<i64>("width")?, 1920);
+    assert_eq!(node.lock().unwrap().get_parameter::<i64>("height")?, 1080);
+
+    let result = client
+        .set_atomically(&[
+            ("width", ParameterValue::Integer(2560)),
+            ("height", ParameterValue::Integer(1080)),
+        ])
+        .await?;
+    assert!(result.successful);
+    assert_eq!(node.lock().unwrap().get_parameter::<i64>("width")?, 2560);
+
+    // a single change is checked against the current value of the other.
+    let result = client.set("height", ParameterValue::Integer(1440)).await?;
+    assert!(!result.successful);
+    assert_eq!(node.lock().unwrap().get_parameter::<i64>("height")?, 1080);
+
+    done.store(true, Ordering::SeqCst);
+    spin_task.await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn describe_parameters() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx.clone(), "testnode_param_describe", "")?;
+    let mut client_node = r2r::Node::create(ctx, "testnode_param_describe_client", "")?;
+
+    node.declare_parameter(
+        "kp",
+        ParameterValue::Double(1.5),
+        ParameterDescriptor {
+            description: "The proportional gain".into(),
+            additional_constraints: "Should be tuned together with ki".into(),
+            ..Default::default()
+        },
+    )?;
+    node.declare_parameter(
+        "setpoint",
+        ParameterValue::Integer(3),
+        ParameterDescriptor {
+            dynamic_typing: true,
+            ..Default::default()
+        },
+    )?;
+
+    let (parameter_handler, _parameter_events) = node.make_parameter_handler()?;
+    tokio::spawn(parameter_handler);
+
+    let client = r2r::ParameterClient::new(&mut client_node, "/testnode_param_describe")?;
+    let services_available = client.wait_for_services()?;
+
+    let node = Arc::new(Mutex::new(node));
+    let client_node = Arc::new(Mutex::new(client_node));
+    let spin_node = node.clone();
+    let done = Arc::new(AtomicBool::new(false));
+    let spin_done = done.clone();
+    let spin_task = tokio::task::spawn_blocking(move || {
+        while !spin_done.load(Ordering::SeqCst) {
+            spin_node
+                .lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+            client_node
+                .lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+        }
+    });
+
+    services_available.await?;
+
+    let descriptors = client.describe_many(&["kp", "setpoint"]).await?;
+    assert_eq!(descriptors.len(), 2);
+    assert_eq!(descriptors[0].name, "kp");
+    assert_eq!(descriptors[0].description, "The proportional gain");
+    assert_eq!(descriptors[0].additional_constraints, "Should be tuned together with ki");
+    assert_eq!(descriptors[0].type_, rcl_interfaces::msg::ParameterType::PARAMETER_DOUBLE as u8);
+    assert!(!descriptors[0].dynamic_typing);
+    assert!(descriptors[1].dynamic_typing);
+
+    // the type can only be changed with dynamic typing.
+    let result = client
+        .set("kp", ParameterValue::String("high".into()))
+        .await?;
+    assert!(!result.successful);
+    assert_eq!(node.lock().unwrap().get_parameter::<f64>("kp")?, 1.5);
+    let result = client.set("setpoint", ParameterValue::Double(2.5)).await?;
+    assert!(result.successful);
+    assert_eq!(
+        client.describe("setpoint").await?.type_,
+        rcl_interfaces::msg::ParameterType::PARAMETER_DOUBLE as u8
+    );
+
+    done.store(true, Ordering::SeqCst);
+    spin_task.await?;
+
+    Ok(())
+}
+
+#[test]
+fn parameters_json_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_param_json", "")?;
+
+    let values = vec![
+        ("bool", ParameterValue::Bool(true)),
+        ("integer", ParameterValue::Integer(-42)),
+        ("double", ParameterValue::Double(2.0)),
+        ("string", ParameterValue::String("wheel".into())),
+        ("bools", ParameterValue::BoolArray(vec![true, false])),
+        ("bytes", ParameterValue::ByteArray(vec![0, 255])),
+        ("integers", ParameterValue::IntegerArray(vec![1, i64::MAX])),
+        ("doubles", ParameterValue::DoubleArray(vec![0.1, 1.0 / 3.0])),
+        ("strings", ParameterValue::StringArray(vec!["a".into(), "b".into()])),
+    ];
+    node.set_parameters_atomically(
+        &values
+            .iter()
+            .map(|(name, value)| (*name, value.clone()))
+            .collect::<Vec<_>>(),
+    )?;
+
+    let json = node.parameters_as_json()?;
+    assert_eq!(json["double"], serde_json::json!(2.0));
+    assert_eq!(json["strings"], serde_json::json!(["a", "b"]));
+    // through text, like a web interface would.
+    let json: serde_json::Value = serde_json::from_str(&json.to_string())?;
+
+    // a separate context, as node names are unique within a context.
+    let ctx = r2r::Context::create_with_args(vec![])?;
+    let mut loaded = r2r::Node::create(ctx, "testnode_param_json", "")?;
+    // byte arrays look like integer arrays in json, so the parameter needs
+    // to exist for the type to be known.
+    loaded
+        .params
+        .lock()
+        .unwrap()
+        .insert("bytes".into(), Parameter::new(ParameterValue::ByteArray(vec![])));
+    let results = loaded.set_parameters_from_json(&json)?;
+    assert!(results.iter().all(|r| r.successful), "{results:?}");
+
+    let params = loaded.params.lock().unwrap();
+    for (name, value) in &values {
+        assert_eq!(params.get(*name).map(|p| &p.value), Some(value), "{name}");
+    }
+    drop(params);
+
+    // values are validated like other changes.
+    loaded.add_parameter_callback(|name, _| match name {
+        "integer" => Err("read only".into()),
+        _ => Ok(()),
+    });
+    let results = loaded.set_parameters_from_json(&serde_json::json!({
+        "integer": 1,
+        "string": "tire",
+        "nested": { "a": 1 },
+    }))?;
+    let summary = results
+        .iter()
+        .map(|r| (r.name.as_str(), r.successful))
+        .collect::<Vec<_>>();
+    assert!(summary.contains(&("integer", false)));
+    assert!(summary.contains(&("string", true)));
+    assert!(summary.contains(&("nested", false)));
+    assert_eq!(loaded.get_parameter::<i64>("integer")?, -42);
+    assert_eq!(loaded.get_parameter::<String>("string")?, "tire");
+
+    assert!(loaded
+        .set_parameters_from_json(&serde_json::json!([1]))
+        .is_err());
+    Ok(())
+}
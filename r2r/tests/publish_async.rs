@@ -0,0 +1,85 @@
+use futures::{executor::LocalPool, stream::StreamExt, task::LocalSpawnExt};
+use r2r::{std_msgs::msg::Int32, PublishResult, QosProfile};
+
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn publish_async_with_slow_consumer() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut pub_node = r2r::Node::create(ctx.clone(), "testnode_publish_async_pub", "")?;
+    let mut sub_node = r2r::Node::create(ctx, "testnode_publish_async_sub", "")?;
+
+    // with keep all, the queues only drop messages when they are full, which
+    // a reliable publisher reports.
+    let qos = QosProfile::default().reliable().keep_all();
+    let publisher = pub_node.create_publisher::<Int32>("/publish_async", qos.clone())?;
+    let mut sub = sub_node.subscribe::<Int32>("/publish_async", qos)?;
+
+    let start = Instant::now();
+    while publisher.get_inter_process_subscription_count()? == 0 {
+        assert!(start.elapsed() < Duration::from_secs(10), "no subscriber");
+        pub_node.spin_once(Duration::from_millis(10));
+        sub_node.spin_once(Duration::from_millis(10));
+    }
+
+    // fill the queues while the subscription is not spun.
+    let mut filled = false;
+    for _ in 0..100_000 {
+        if publisher.try_publish(&Int32 { data: -1 })? == PublishResult::QueueFull {
+            filled = true;
+            break;
+        }
+    }
+    if !filled {
+        // the middleware grows its queues instead, publish_async does not
+        // have to retry then.
+        return Ok(());
+    }
+
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let sent = Rc::new(RefCell::new(0));
+    let task_sent = sent.clone();
+    spawner.spawn_local(async move {
+        for i in 0..20 {
+            publisher.publish_async(Int32 { data: i }).await.unwrap();
+            *task_sent.borrow_mut() += 1;
+        }
+    })?;
+
+    let received = Rc::new(RefCell::new(Vec::new()));
+    let task_received = received.clone();
+    spawner.spawn_local(async move {
+        while let Some(msg) = sub.next().await {
+            task_received.borrow_mut().push(msg.data);
+        }
+    })?;
+
+    // the consumer is only spun every few turns.
+    let start = Instant::now();
+    let mut turn = 0;
+    while received.borrow().last() != Some(&19) {
+        assert!(start.elapsed() < Duration::from_secs(10), "not all messages were sent");
+        pub_node.spin_once(Duration::from_millis(1));
+        if turn % 5 == 0 {
+            sub_node.spin_once(Duration::from_millis(1));
+        }
+        pool.run_until_stalled();
+        turn += 1;
+    }
+
+    assert_eq!(*sent.borrow(), 20);
+    // nothing was dropped, and the retried messages follow the ones filling the queue.
+    let received = received.borrow();
+    let retried = received
+        .iter()
+        .skip_while(|d| **d < 0)
+        .copied()
+        .collect::<Vec<_>>();
+    assert_eq!(retried, (0..20).collect::<Vec<_>>());
+    Ok(())
+}
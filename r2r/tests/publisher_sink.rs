@@ -0,0 +1,54 @@
+use futures::{
+    executor::LocalPool,
+    stream::{self, StreamExt},
+    task::LocalSpawnExt,
+};
+use r2r::{std_msgs::msg::Int32, QosProfile};
+
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn forward_stream_to_publisher() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_publisher_sink", "")?;
+    let publisher = node.create_publisher::<Int32>("/publisher_sink", QosProfile::default())?;
+    let mut sub = node.subscribe::<Int32>("/publisher_sink", QosProfile::default())?;
+
+    let start = Instant::now();
+    while publisher.get_inter_process_subscription_count()? == 0 {
+        assert!(start.elapsed() < Duration::from_secs(10), "no subscriber");
+        node.spin_once(Duration::from_millis(10));
+    }
+
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let forwarded = Rc::new(RefCell::new(None));
+    let task_forwarded = forwarded.clone();
+    spawner.spawn_local(async move {
+        let messages = stream::iter(vec![Int32 { data: 1 }, Int32 { data: 2 }, Int32 { data: 3 }]);
+        let result = messages.map(Ok).forward(publisher.into_sink()).await;
+        *task_forwarded.borrow_mut() = Some(result);
+    })?;
+
+    let received = Rc::new(RefCell::new(Vec::new()));
+    let task_received = received.clone();
+    spawner.spawn_local(async move {
+        while let Some(msg) = sub.next().await {
+            task_received.borrow_mut().push(msg.data);
+        }
+    })?;
+
+    while received.borrow().len() < 3 {
+        assert!(start.elapsed() < Duration::from_secs(10), "not all messages were received");
+        node.spin_once(Duration::from_millis(10));
+        pool.run_until_stalled();
+    }
+
+    assert!(matches!(*forwarded.borrow(), Some(Ok(()))));
+    assert_eq!(*received.borrow(), vec![1, 2, 3]);
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use r2r::{std_msgs::msg::Int32, QosProfile, WrappedTypesupport};
+
+use std::{
+    sync::atomic::Ordering,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn publisher_stats_rate() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_publisher_stats", "")?;
+    let publisher = node.create_publisher::<Int32>("/publisher_stats", QosProfile::default())?;
+    let stats = publisher.stats()?;
+    assert_eq!(stats.estimated_hz(Duration::from_secs(5)), 0.0);
+
+    // publish at fixed times, so the sleeps do not add up.
+    let start = Instant::now();
+    for i in 0..100 {
+        let next = start + Duration::from_millis(100 * i);
+        std::thread::sleep(next.saturating_duration_since(Instant::now()));
+        publisher.publish(&Int32 { data: i as i32 })?;
+    }
+
+    let hz = stats.estimated_hz(Duration::from_secs(5));
+    assert!((9.0..11.0).contains(&hz), "estimated {hz} Hz");
+    assert_eq!(stats.message_count.load(Ordering::Relaxed), 100);
+    assert!(stats.last_publish_time().is_some());
+    assert_eq!(stats.byte_count.load(Ordering::Relaxed), 0);
+    Ok(())
+}
+
+#[test]
+fn publisher_stats_raw_bytes() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_publisher_stats_raw", "")?;
+    let publisher = node.create_publisher_untyped(
+        "/publisher_stats_raw",
+        "std_msgs/msg/Int32",
+        QosProfile::default(),
+    )?;
+
+    let data = Int32 { data: 7 }.to_serialized_bytes()?;
+    publisher.publish_raw(&data)?;
+    publisher.publish_raw(&data)?;
+
+    let stats = publisher.stats()?;
+    assert_eq!(stats.message_count.load(Ordering::Relaxed), 2);
+    assert_eq!(stats.byte_count.load(Ordering::Relaxed), 2 * data.len() as u64);
+    Ok(())
+}
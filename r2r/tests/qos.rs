@@ -0,0 +1,86 @@
+use futures::{future::FutureExt, stream::StreamExt};
+use r2r::QosProfile;
+
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn transient_local_late_joiner() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_qos", "")?;
+
+    let qos = QosProfile::default()
+        .reliable()
+        .transient_local()
+        .keep_last(1);
+
+    let publisher =
+        node.create_publisher::<r2r::std_msgs::msg::Int32>("/latched_no", qos.clone())?;
+    publisher.publish(&r2r::std_msgs::msg::Int32 { data: 1 })?;
+    publisher.publish(&r2r::std_msgs::msg::Int32 { data: 2 })?;
+
+    // subscribe only after the messages have been published.
+    let mut sub = node.subscribe::<r2r::std_msgs::msg::Int32>("/latched_no", qos)?;
+
+    let mut received = None;
+    for _ in 0..100 {
+        node.spin_once(Duration::from_millis(10));
+        if let Some(msg) = sub.next().now_or_never() {
+            received = msg;
+            break;
+        }
+    }
+
+    let msg = received.expect("late joining subscriber got no message");
+    assert_eq!(msg.data, 2);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn infinite_deadline() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_qos_deadline", "")?;
+
+    let qos = QosProfile::default()
+        .deadline(Duration::MAX)
+        .lifespan(Duration::MAX);
+    let _publisher = node.create_publisher::<r2r::std_msgs::msg::Int32>("/deadline_no", qos)?;
+
+    // wait a little for publisher info to populate(?). hack to avoid CI failures.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    node.spin_once(Duration::from_millis(10));
+
+    let info = node.get_publishers_info_by_topic("/deadline_no", false)?;
+    assert_eq!(info.len(), 1);
+    assert_eq!(info[0].qos_profile.deadline, Duration::MAX);
+    assert_eq!(info[0].qos_profile.lifespan, Duration::MAX);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn latched_publisher_and_subscriber() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_qos_latched", "")?;
+
+    let publisher =
+        node.create_publisher_latched::<r2r::std_msgs::msg::Int32>("/latched_conv", 1)?;
+    publisher.publish(&r2r::std_msgs::msg::Int32 { data: 42 })?;
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let mut sub = node.subscribe_latched::<r2r::std_msgs::msg::Int32>("/latched_conv", 1)?;
+
+    let mut received = None;
+    for _ in 0..100 {
+        node.spin_once(Duration::from_millis(10));
+        if let Some(msg) = sub.next().now_or_never() {
+            received = msg;
+            break;
+        }
+    }
+
+    let msg = received.expect("latched subscriber got no message");
+    assert_eq!(msg.data, 42);
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+#![cfg(not(r2r__ros__distro__foxy))]
+
+use r2r::{std_msgs::msg::Int32, EndpointType, QosProfile};
+
+use std::time::{Duration, Instant};
+
+#[test]
+fn best_effort_publisher_and_reliable_subscription() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_qos_compatibility", "")?;
+    let publisher_qos = QosProfile::default().best_effort();
+    let subscription_qos = QosProfile::default().reliable();
+    let _publisher = node.create_publisher::<Int32>("/qos_compatibility", publisher_qos)?;
+    let subscription = node.subscribe::<Int32>("/qos_compatibility", subscription_qos.clone())?;
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let publishers = loop {
+        let publishers = node.get_publishers_info_by_topic("/qos_compatibility", false)?;
+        if !publishers.is_empty() {
+            break publishers;
+        }
+        assert!(Instant::now() < deadline, "publisher not discovered");
+        node.spin_once(Duration::from_millis(10));
+    };
+    assert_eq!(publishers[0].endpoint_type, EndpointType::Publisher);
+    let subscriptions = node.get_subscriptions_info_by_topic("/qos_compatibility", false)?;
+    assert_eq!(subscriptions[0].endpoint_type, EndpointType::Subscription);
+
+    let result = publishers[0]
+        .qos_profile
+        .is_compatible_with_subscription(&subscription_qos);
+    assert!(!result.compatible);
+    assert!(!result.reason.is_empty());
+
+    let result = subscription_qos.is_compatible_with_subscription(&subscription_qos);
+    assert!(result.compatible);
+
+    let publishers = subscription.get_publisher_infos()?;
+    assert_eq!(publishers.len(), 1);
+    assert_eq!(publishers[0].endpoint_type, EndpointType::Publisher);
+    drop(node);
+    assert!(subscription.get_publisher_infos().is_err());
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use r2r::{
+    IncompatibleQosStatus, MessageLostStatus, PublisherOptions, QosPolicyKind, QosProfile,
+    SubscriptionOptions,
+};
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+// A best effort publisher cannot serve a reliable subscription, so
+// both ends are notified about the incompatible reliability policy.
+#[test]
+fn incompatible_qos_callbacks() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_qos_events", "")?;
+
+    let requested: Arc<Mutex<Option<IncompatibleQosStatus>>> = Arc::new(Mutex::new(None));
+    let offered: Arc<Mutex<Option<IncompatibleQosStatus>>> = Arc::new(Mutex::new(None));
+
+    let requested_cb = requested.clone();
+    let options = SubscriptionOptions {
+        on_incompatible_qos: Some(Arc::new(move |status| {
+            *requested_cb.lock().unwrap() = Some(status);
+        })),
+        ..Default::default()
+    };
+    let _sub = node.subscribe_with_options::<r2r::std_msgs::msg::Int32>(
+        "/qos_events_incompatible",
+        QosProfile::default().reliable(),
+        options,
+    )?;
+
+    let offered_cb = offered.clone();
+    let options = PublisherOptions {
+        on_incompatible_qos: Some(Arc::new(move |status| {
+            *offered_cb.lock().unwrap() = Some(status);
+        })),
+        ..Default::default()
+    };
+    let _publisher = node.create_publisher_with_options::<r2r::std_msgs::msg::Int32>(
+        "/qos_events_incompatible",
+        QosProfile::default().best_effort(),
+        options,
+    )?;
+
+    // the events are emitted once the endpoints have discovered each other.
+    for _ in 0..100 {
+        node.spin_once(Duration::from_millis(10));
+        if requested.lock().unwrap().is_some() && offered.lock().unwrap().is_some() {
+            break;
+        }
+    }
+
+    for status in [requested, offered] {
+        let status = status.lock().unwrap().expect("no incompatible qos event");
+        assert!(status.total_count >= 1);
+        assert_eq!(status.last_policy_kind, QosPolicyKind::Reliability);
+    }
+
+    Ok(())
+}
+
+// A best effort subscription keeping a single message, which is not taken
+// while the publisher sends a burst of messages.
+#[test]
+fn message_lost_callback() -> Result<(), Box<dyn std::error::Error>> {
+    // Fast DDS and Cyclone DDS only report messages as lost on gaps in the
+    // sequence numbers, not when they are replaced in the history, which
+    // this scenario cannot provoke reliably.
+    let identifier = r2r::rmw::get_implementation_identifier();
+    if identifier.starts_with("rmw_fastrtps") || identifier == "rmw_cyclonedds_cpp" {
+        return Ok(());
+    }
+
+    let ctx = r2r::Context::create()?;
+    let mut pub_node = r2r::Node::create(ctx.clone(), "testnode_message_lost_pub", "")?;
+    let mut sub_node = r2r::Node::create(ctx, "testnode_message_lost_sub", "")?;
+
+    let lost: Arc<Mutex<Vec<MessageLostStatus>>> = Arc::new(Mutex::new(Vec::new()));
+    let lost_cb = lost.clone();
+    let options = SubscriptionOptions {
+        on_message_lost: Some(Arc::new(move |status| {
+            lost_cb.lock().unwrap().push(status);
+        })),
+        ..Default::default()
+    };
+    let qos = QosProfile::default().best_effort().keep_last(1);
+    let _sub = match sub_node.subscribe_with_options::<r2r::std_msgs::msg::Int32>(
+        "/qos_events_message_lost",
+        qos.clone(),
+        options,
+    ) {
+        // the middleware does not report lost messages at all.
+        Err(r2r::Error::RCL_RET_UNSUPPORTED) => return Ok(()),
+        result => result?,
+    };
+    let publisher =
+        pub_node.create_publisher::<r2r::std_msgs::msg::Int32>("/qos_events_message_lost", qos)?;
+
+    let start = Instant::now();
+    while publisher.get_inter_process_subscription_count()? == 0 {
+        assert!(start.elapsed() < Duration::from_secs(10), "no subscriber");
+        pub_node.spin_once(Duration::from_millis(10));
+    }
+
+    for data in 0..100 {
+        publisher.publish(&r2r::std_msgs::msg::Int32 { data })?;
+    }
+    for _ in 0..100 {
+        sub_node.spin_once(Duration::from_millis(10));
+        if !lost.lock().unwrap().is_empty() {
+            break;
+        }
+    }
+
+    let lost = lost.lock().unwrap();
+    assert!(!lost.is_empty(), "no message lost event");
+    assert!(lost.iter().all(|s| s.total_count_change > 0));
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use std::time::{Duration, Instant};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn rate_keeps_frequency() {
+    let mut rate = r2r::Rate::new(100.0);
+    let expected = rate.period() * 100;
+
+    let started = Instant::now();
+    let mut last = None;
+    for _ in 0..100 {
+        let res = rate.sleep().await;
+        assert!(res.actual >= res.scheduled);
+        last = Some(res);
+    }
+    let total_elapsed = started.elapsed();
+
+    let missed = last.unwrap().missed_deadlines;
+    assert!(missed < 5, "missed {missed} deadlines");
+    assert!(total_elapsed < expected.mul_f64(1.1), "{total_elapsed:?}");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn rate_compensates_for_slow_loop() {
+    let mut rate = r2r::Rate::new(100.0);
+    rate.sleep().await;
+
+    // the loop body takes longer than the period.
+    std::thread::sleep(Duration::from_millis(15));
+    let before = Instant::now();
+    let res = rate.sleep().await;
+    assert!(before.elapsed() < Duration::from_millis(2));
+    assert_eq!(res.missed_deadlines, 1);
+
+    rate.reset();
+    let res = rate.sleep().await;
+    assert_eq!(res.missed_deadlines, 0);
+}
@@ -0,0 +1,41 @@
+use r2r::{
+    rmw::{self, RmwFeature},
+    ContentFilter, QosProfile, SubscriptionOptions,
+};
+
+#[test]
+fn implementation_identifier() {
+    let identifier = rmw::get_implementation_identifier();
+    assert!(!identifier.is_empty());
+    assert!(identifier.starts_with("rmw_"), "{identifier}");
+}
+
+#[test]
+fn feature_queries() -> Result<(), Box<dyn std::error::Error>> {
+    let identifier = rmw::get_implementation_identifier();
+    // the middlewares shipped with every distribution r2r supports.
+    if identifier.starts_with("rmw_fastrtps") || identifier == "rmw_cyclonedds_cpp" {
+        assert!(rmw::is_feature_supported(RmwFeature::LoanedMessages));
+    }
+
+    // the answer agrees with creating a content filtered subscription.
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_rmw_features", "")?;
+    let options = SubscriptionOptions {
+        content_filter: Some(ContentFilter::new("data > 5")),
+        ..Default::default()
+    };
+    let filtered = node.subscribe_with_options::<r2r::std_msgs::msg::Int32>(
+        "/rmw_features",
+        QosProfile::default(),
+        options,
+    );
+    match filtered {
+        Ok(_) => assert!(rmw::is_feature_supported(RmwFeature::ContentFilteredTopics)),
+        Err(r2r::Error::UnsupportedFeature(_)) => {
+            assert!(!rmw::is_feature_supported(RmwFeature::ContentFilteredTopics))
+        }
+        Err(e) => return Err(e.into()),
+    }
+    Ok(())
+}
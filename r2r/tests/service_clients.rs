@@ -0,0 +1,33 @@
+use r2r::{test_msgs::srv::Empty, QosProfile};
+
+use std::time::{Duration, Instant};
+
+#[test]
+fn count_service_clients() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut server_node = r2r::Node::create(ctx.clone(), "testnode_service_clients", "")?;
+    let mut client_node_a = r2r::Node::create(ctx.clone(), "testnode_service_clients_a", "")?;
+    let mut client_node_b = r2r::Node::create(ctx, "testnode_service_clients_b", "")?;
+
+    let _service =
+        server_node.create_service::<Empty::Service>("/counted_service", QosProfile::default())?;
+    let _client_a =
+        client_node_a.create_client::<Empty::Service>("/counted_service", QosProfile::default())?;
+    let _client_b =
+        client_node_b.create_client::<Empty::Service>("/counted_service", QosProfile::default())?;
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while server_node.get_client_count("/counted_service")? < 2 {
+        assert!(Instant::now() < deadline, "clients not discovered");
+        server_node.spin_once(Duration::from_millis(10));
+    }
+    assert_eq!(server_node.get_client_count("/counted_service")?, 2);
+
+    let servers =
+        server_node.get_service_server_names_and_types_by_node("testnode_service_clients", "/")?;
+    assert_eq!(servers.get("/counted_service"), Some(&vec!["test_msgs/srv/Empty".to_string()]));
+    let clients =
+        server_node.get_client_names_and_types_by_node("testnode_service_clients_a", "/")?;
+    assert_eq!(clients.get("/counted_service"), Some(&vec!["test_msgs/srv/Empty".to_string()]));
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use r2r::{test_msgs::srv::BasicTypes, QosProfile};
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn forwarder_transforms_requests() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx.clone(), "testnode_service_forwarder", "")?;
+    let mut client_node = r2r::Node::create(ctx, "testnode_service_forwarder_client", "")?;
+
+    // the downstream service records what it receives.
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let downstream_received = received.clone();
+    node.create_async_service::<BasicTypes::Service, _, _>(
+        "/validated/set_value",
+        QosProfile::default(),
+        move |req| {
+            downstream_received.lock().unwrap().push(req.int64_value);
+            async move {
+                BasicTypes::Response {
+                    int64_value: req.int64_value + 1,
+                    ..Default::default()
+                }
+            }
+        },
+    )?;
+    node.create_service_forwarder::<BasicTypes::Service, _>(
+        "/set_value",
+        "/validated/set_value",
+        |mut req| {
+            req.int64_value *= 2;
+            req
+        },
+    )?;
+    node.create_service_forwarder_with_response::<BasicTypes::Service, _, _>(
+        "/set_value_negated",
+        "/validated/set_value",
+        |req| req,
+        |mut resp| {
+            resp.int64_value = -resp.int64_value;
+            resp
+        },
+    )?;
+
+    let client =
+        client_node.create_client::<BasicTypes::Service>("/set_value", QosProfile::default())?;
+    let negated_client = client_node
+        .create_client::<BasicTypes::Service>("/set_value_negated", QosProfile::default())?;
+    let available = r2r::Node::is_available(&client)?;
+    let negated_available = r2r::Node::is_available(&negated_client)?;
+
+    let node = Arc::new(Mutex::new(node));
+    let client_node = Arc::new(Mutex::new(client_node));
+    let done = Arc::new(AtomicBool::new(false));
+    let spin_done = done.clone();
+    let spin_task = tokio::task::spawn_blocking(move || {
+        while !spin_done.load(Ordering::SeqCst) {
+            node.lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+            client_node
+                .lock()
+                .unwrap()
+                .spin_once(std::time::Duration::from_millis(5));
+        }
+    });
+
+    available.await?;
+    negated_available.await?;
+
+    let request = BasicTypes::Request {
+        int64_value: 21,
+        ..Default::default()
+    };
+    let response = client.request(&request)?.await?;
+    assert_eq!(*received.lock().unwrap(), vec![42]);
+    assert_eq!(response.int64_value, 43);
+
+    let response = negated_client.request(&request)?.await?;
+    assert_eq!(*received.lock().unwrap(), vec![42, 21]);
+    assert_eq!(response.int64_value, -22);
+
+    done.store(true, Ordering::SeqCst);
+    spin_task.await?;
+
+    Ok(())
+}
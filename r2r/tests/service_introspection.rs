@@ -0,0 +1,165 @@
+use futures::{executor::LocalPool, stream::StreamExt, task::LocalSpawnExt};
+use r2r::{test_msgs::srv::BasicTypes, QosProfile, ServiceIntrospectionState, ServiceOptions};
+
+use std::time::{Duration, Instant};
+
+#[test]
+fn service_introspection_events() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_service_introspection", "")?;
+    let mut pool = LocalPool::new();
+
+    let options = ServiceOptions {
+        introspection: ServiceIntrospectionState::Contents,
+    };
+    let mut service = match node.create_service_with_options::<BasicTypes::Service>(
+        "/introspected_srv",
+        QosProfile::default(),
+        options.clone(),
+    ) {
+        // service introspection requires ROS 2 Iron or later.
+        Err(r2r::Error::UnsupportedFeature(_)) => return Ok(()),
+        result => result?,
+    };
+    pool.spawner().spawn_local(async move {
+        while let Some(req) = service.next().await {
+            let response = BasicTypes::Response {
+                int64_value: req.message.int64_value,
+                ..Default::default()
+            };
+            req.respond(response).expect("could not respond");
+        }
+    })?;
+
+    let client = node.create_client_with_options::<BasicTypes::Service>(
+        "/introspected_srv",
+        QosProfile::default(),
+        options,
+    )?;
+    // subscribes to /introspected_srv/_service_event.
+    let mut events = node.create_service_spy::<BasicTypes::Service>("/introspected_srv")?;
+    let available = r2r::Node::is_available(&client)?;
+    r2r::spin_until_future_complete(
+        &mut node,
+        &mut pool,
+        available,
+        Some(Duration::from_secs(5)),
+    )??;
+
+    // both the service and the client publish service events.
+    let publishers =
+        node.get_publishers_info_by_topic("/introspected_srv/_service_event", false)?;
+    assert_eq!(publishers.len(), 2);
+    for p in &publishers {
+        assert_eq!(p.node_name, "testnode_service_introspection");
+        assert_eq!(p.topic_type, "test_msgs/srv/BasicTypes_Event");
+    }
+    // the event subscription is matched separately from the service.
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while node
+        .get_subscriptions_info_by_topic("/introspected_srv/_service_event", false)?
+        .is_empty()
+    {
+        assert!(Instant::now() < deadline, "service events not matched");
+        node.spin_once(Duration::from_millis(10));
+    }
+    std::thread::sleep(Duration::from_millis(100));
+
+    let response = client.request(&BasicTypes::Request {
+        int64_value: 7,
+        ..Default::default()
+    })?;
+    let response = r2r::spin_until_future_complete(
+        &mut node,
+        &mut pool,
+        response,
+        Some(Duration::from_secs(5)),
+    )??;
+    assert_eq!(response.int64_value, 7);
+
+    let event = r2r::spin_until_future_complete(
+        &mut node,
+        &mut pool,
+        events.next(),
+        Some(Duration::from_secs(1)),
+    )?
+    .expect("service event stream ended");
+    assert_eq!(event.request.int64_value, 7);
+    assert_eq!(event.response.map(|r| r.int64_value), Some(7));
+
+    Ok(())
+}
+
+#[test]
+fn service_spy_sees_calls() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_service_spy", "")?;
+    let mut pool = LocalPool::new();
+
+    let mut spy = match node.create_service_spy::<BasicTypes::Service>("/spied_srv") {
+        // service introspection requires ROS 2 Iron or later.
+        Err(r2r::Error::UnsupportedFeature(_)) => return Ok(()),
+        result => result?,
+    };
+    let options = ServiceOptions {
+        introspection: ServiceIntrospectionState::Contents,
+    };
+    let mut service = node.create_service_with_options::<BasicTypes::Service>(
+        "/spied_srv",
+        QosProfile::default(),
+        options,
+    )?;
+    pool.spawner().spawn_local(async move {
+        while let Some(req) = service.next().await {
+            let response = BasicTypes::Response {
+                int64_value: req.message.int64_value * 2,
+                ..Default::default()
+            };
+            req.respond(response).expect("could not respond");
+        }
+    })?;
+
+    let client = node.create_client::<BasicTypes::Service>("/spied_srv", QosProfile::default())?;
+    let available = r2r::Node::is_available(&client)?;
+    r2r::spin_until_future_complete(
+        &mut node,
+        &mut pool,
+        available,
+        Some(Duration::from_secs(5)),
+    )??;
+    // the event topic is matched separately from the service.
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while node
+        .get_publishers_info_by_topic("/spied_srv/_service_event", false)?
+        .is_empty()
+    {
+        assert!(Instant::now() < deadline, "no service event publisher");
+        node.spin_once(Duration::from_millis(10));
+    }
+    std::thread::sleep(Duration::from_millis(100));
+
+    let response = client.request(&BasicTypes::Request {
+        int64_value: 21,
+        ..Default::default()
+    })?;
+    let response = r2r::spin_until_future_complete(
+        &mut node,
+        &mut pool,
+        response,
+        Some(Duration::from_secs(5)),
+    )??;
+    assert_eq!(response.int64_value, 42);
+
+    // the spy yields the call right after the response.
+    let interaction = r2r::spin_until_future_complete(
+        &mut node,
+        &mut pool,
+        spy.next(),
+        Some(Duration::from_millis(100)),
+    )?
+    .expect("spy stream ended");
+    assert!(interaction.response_time >= Some(interaction.request_time));
+    assert_eq!(interaction.request.int64_value, 21);
+    assert_eq!(interaction.response.map(|r| r.int64_value), Some(42));
+    Ok(())
+}
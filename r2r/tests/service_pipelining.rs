@@ -0,0 +1,80 @@
+use futures::stream::StreamExt;
+use r2r::{test_msgs::srv::BasicTypes, QosProfile};
+use rand::Rng;
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn pipelined_requests() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx.clone(), "testnode_pipelining", "")?;
+    let mut client_node = r2r::Node::create(ctx, "testnode_pipelining_client", "")?;
+
+    // responds after a random delay, so responses may arrive out of order.
+    node.create_async_service::<BasicTypes::Service, _, _>(
+        "/pipelined_echo",
+        QosProfile::default(),
+        |req| async move {
+            let delay = rand::thread_rng().gen_range(0..=100);
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            BasicTypes::Response {
+                int64_value: req.int64_value,
+                string_value: req.string_value,
+                ..Default::default()
+            }
+        },
+    )?;
+
+    let client = client_node
+        .create_client::<BasicTypes::Service>("/pipelined_echo", QosProfile::default())?;
+    let available = r2r::Node::is_available(&client)?;
+
+    let node = Arc::new(Mutex::new(node));
+    let client_node = Arc::new(Mutex::new(client_node));
+    let done = Arc::new(AtomicBool::new(false));
+    let spin_done = done.clone();
+    let spin_task = tokio::task::spawn_blocking(move || {
+        while !spin_done.load(Ordering::SeqCst) {
+            node.lock().unwrap().spin_once(Duration::from_millis(5));
+            client_node
+                .lock()
+                .unwrap()
+                .spin_once(Duration::from_millis(5));
+        }
+    });
+
+    available.await?;
+
+    let requests = (0..10)
+        .map(|i| BasicTypes::Request {
+            int64_value: i,
+            string_value: format!("request {i}"),
+            ..Default::default()
+        })
+        .collect();
+    let responses = tokio::time::timeout(
+        Duration::from_secs(5),
+        client.call_pipelined(requests)?.collect::<Vec<_>>(),
+    )
+    .await?;
+
+    let mut values = Vec::new();
+    for response in responses {
+        let response = response?;
+        assert_eq!(response.string_value, format!("request {}", response.int64_value));
+        values.push(response.int64_value);
+    }
+    values.sort();
+    assert_eq!(values, (0..10).collect::<Vec<_>>());
+
+    done.store(true, Ordering::SeqCst);
+    spin_task.await?;
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use futures::stream::StreamExt;
+use r2r::QosProfile;
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn service_request_timeout() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_service_timeout", "")?;
+
+    // a server that never responds.
+    let mut service = node.create_service::<r2r::test_msgs::srv::Empty::Service>(
+        "/silent_service",
+        QosProfile::default(),
+    )?;
+    let received = tokio::spawn(async move {
+        let mut requests = Vec::new();
+        while let Some(req) = service.next().await {
+            requests.push(req);
+        }
+        requests.len()
+    });
+
+    let client = node.create_client::<r2r::test_msgs::srv::Empty::Service>(
+        "/silent_service",
+        QosProfile::default(),
+    )?;
+    let missing_client = node.create_client::<r2r::test_msgs::srv::Empty::Service>(
+        "/missing_service",
+        QosProfile::default(),
+    )?;
+    let service_available = client.wait_for_service(None);
+    let missing_available = missing_client.wait_for_service(Some(Duration::from_millis(200)));
+
+    let node = Arc::new(Mutex::new(node));
+    let done = Arc::new(AtomicBool::new(false));
+    let spin_done = done.clone();
+    let spin_task = tokio::task::spawn_blocking(move || {
+        while !spin_done.load(Ordering::SeqCst) {
+            node.lock().unwrap().spin_once(Duration::from_millis(10));
+        }
+    });
+
+    service_available.await?;
+    assert!(matches!(missing_available.await, Err(r2r::Error::RCL_RET_TIMEOUT)));
+
+    let req = r2r::test_msgs::srv::Empty::Request {};
+    let sent = Instant::now();
+    let response = client
+        .call_with_timeout(req.clone(), Duration::from_millis(200))
+        .await;
+    assert_eq!(response, Err(r2r::ServiceError::Timeout));
+    assert!(sent.elapsed() >= Duration::from_millis(200));
+
+    // no server at all.
+    let response = missing_client
+        .call_with_timeout(req, Duration::from_millis(200))
+        .await;
+    assert_eq!(response, Err(r2r::ServiceError::ServerUnavailable));
+    let error = r2r::Error::from(r2r::ServiceError::ServerUnavailable);
+    assert!(matches!(error, r2r::Error::ServiceUnavailable));
+
+    done.store(true, Ordering::SeqCst);
+    spin_task.await?;
+    drop(client);
+    drop(missing_client);
+    received.abort();
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use futures::{executor::LocalPool, stream::StreamExt, task::LocalSpawnExt};
+use r2r::{std_msgs::msg::Int32, QosProfile, SharedNode};
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+#[test]
+fn shared_node_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let node = SharedNode::from(r2r::Node::create(ctx, "testnode_shared_node", "")?);
+
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let received = Arc::new(Mutex::new(vec![]));
+
+    // each task creates its own entities through a clone of the node.
+    let sub_node = node.clone();
+    let sub_received = received.clone();
+    let mut sub = sub_node.subscribe::<Int32>("/shared_node", QosProfile::default())?;
+    spawner.spawn_local(async move {
+        while let Some(msg) = sub.next().await {
+            sub_received.lock().unwrap().push(msg.data);
+        }
+    })?;
+
+    let pub_node = node.clone();
+    spawner.spawn_local(async move {
+        let publisher = pub_node
+            .create_publisher::<Int32>("/shared_node", QosProfile::default())
+            .unwrap();
+        let mut timer = pub_node
+            .create_wall_timer(Duration::from_millis(10))
+            .unwrap();
+        for data in 0.. {
+            timer.tick().await.unwrap();
+            publisher.publish(&Int32 { data }).unwrap();
+        }
+    })?;
+
+    let start = Instant::now();
+    while received.lock().unwrap().len() < 3 {
+        assert!(start.elapsed() < Duration::from_secs(10), "no messages received");
+        node.spin_once(Duration::from_millis(10));
+        pool.run_until_stalled();
+    }
+
+    let received = received.lock().unwrap();
+    assert!(received.windows(2).all(|w| w[0] < w[1]));
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+// the context is global, so shutting it down gets its own test binary.
+#[test]
+fn shutdown_invalidates_context_and_nodes() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let node = r2r::Node::create(ctx.clone(), "testnode_shutdown", "")?;
+    assert!(ctx.is_valid());
+    assert!(node.is_valid());
+
+    let called = Arc::new(AtomicUsize::new(0));
+    let cb_called = called.clone();
+    ctx.on_shutdown(move || {
+        cb_called.fetch_add(1, Ordering::SeqCst);
+    });
+    let removed = ctx.on_shutdown(|| panic!("removed callback called"));
+    assert!(ctx.remove_on_shutdown(removed));
+    assert!(!ctx.remove_on_shutdown(removed));
+
+    r2r::try_shutdown()?;
+    assert_eq!(called.load(Ordering::SeqCst), 1);
+    assert!(!ctx.is_valid());
+    assert!(!node.is_valid());
+
+    // shutting down again does nothing.
+    r2r::try_shutdown()?;
+    assert_eq!(called.load(Ordering::SeqCst), 1);
+
+    Ok(())
+}
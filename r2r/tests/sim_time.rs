@@ -0,0 +1,113 @@
+#![cfg(r2r__rosgraph_msgs__msg__Clock)]
+
+use futures::future::FutureExt;
+use r2r::{builtin_interfaces::msg::Time, rosgraph_msgs::msg::Clock, ClockType, QosProfile};
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn timer_follows_sim_time() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx.clone(), "testnode_sim_time", "")?;
+    let mut clock_node = r2r::Node::create(ctx, "testnode_sim_time_clock", "")?;
+
+    let time_source = node.get_time_source();
+    time_source.enable_sim_time(&mut node)?;
+    time_source.set_clock_timeout(None);
+
+    let mut timer = node.create_timer(Duration::from_secs(1))?;
+    let clock_publisher = clock_node.create_publisher::<Clock>("/clock", QosProfile::default())?;
+
+    let node = Arc::new(Mutex::new(node));
+    let spin_node = node.clone();
+    let done = Arc::new(AtomicBool::new(false));
+    let spin_done = done.clone();
+    let spin_task = tokio::task::spawn_blocking(move || {
+        let mut sec = 0;
+        while !spin_done.load(Ordering::SeqCst) {
+            // simulated time runs ten times faster than wall time.
+            clock_publisher
+                .publish(&Clock {
+                    clock: Time { sec, nanosec: 0 },
+                })
+                .unwrap();
+            for _ in 0..10 {
+                spin_node
+                    .lock()
+                    .unwrap()
+                    .spin_once(Duration::from_millis(10));
+            }
+            sec += 1;
+        }
+    });
+
+    // three simulated seconds pass in well under three wall seconds.
+    let started = Instant::now();
+    for _ in 0..3 {
+        timer.tick().await?;
+    }
+    assert!(started.elapsed() < Duration::from_secs(3), "{:?}", started.elapsed());
+
+    let now = node.lock().unwrap().now()?;
+    assert!(now.sec >= 3, "{now:?}");
+    assert_eq!(now.nanosec, 0);
+
+    time_source.disable_sim_time();
+    let now = node.lock().unwrap().now()?;
+    // back on system time.
+    assert!(now.sec > 1_000_000_000, "{now:?}");
+
+    done.store(true, Ordering::SeqCst);
+    spin_task.await?;
+
+    Ok(())
+}
+
+#[test]
+fn ros_timer_runs_with_sim_time_rate() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx.clone(), "testnode_sim_time_rate", "")?;
+    let mut clock_node = r2r::Node::create(ctx, "testnode_sim_time_rate_clock", "")?;
+
+    let time_source = node.get_time_source();
+    time_source.enable_sim_time(&mut node)?;
+    time_source.set_clock_timeout(None);
+
+    let period = Duration::from_millis(200);
+    let mut ros_timer = node.create_timer_with_clock(period, ClockType::RosTime)?;
+    let mut wall_timer = node.create_timer_with_clock(period, ClockType::SteadyTime)?;
+    let clock_publisher = clock_node.create_publisher::<Clock>("/clock", QosProfile::default())?;
+
+    // simulated time runs twice as fast as wall time.
+    let started = Instant::now();
+    let mut ros_ticks = 0;
+    let mut wall_ticks = 0;
+    while started.elapsed() < Duration::from_secs(3) {
+        let sim = started.elapsed() * 2;
+        clock_publisher.publish(&Clock {
+            clock: Time {
+                sec: sim.as_secs() as i32,
+                nanosec: sim.subsec_nanos(),
+            },
+        })?;
+        node.spin_once(Duration::from_millis(5));
+        while ros_timer.tick().now_or_never().is_some() {
+            ros_ticks += 1;
+        }
+        while wall_timer.tick().now_or_never().is_some() {
+            wall_ticks += 1;
+        }
+    }
+
+    assert!(wall_ticks >= 10, "{wall_ticks}");
+    let ratio = ros_ticks as f64 / wall_ticks as f64;
+    assert!((1.5..=2.5).contains(&ratio), "{ros_ticks} ros ticks, {wall_ticks} wall ticks");
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use futures::{executor::LocalPool, stream::StreamExt, task::LocalSpawnExt};
+use r2r::{test_msgs::srv::BasicTypes, QosProfile};
+
+use std::time::Duration;
+
+#[test]
+fn spin_until_service_response() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_spin_until", "")?;
+    let mut pool = LocalPool::new();
+
+    let mut service =
+        node.create_service::<BasicTypes::Service>("/spin_until_srv", QosProfile::default())?;
+    pool.spawner().spawn_local(async move {
+        while let Some(req) = service.next().await {
+            let response = BasicTypes::Response {
+                int64_value: req.message.int64_value + 1,
+                ..Default::default()
+            };
+            req.respond(response).expect("could not respond");
+        }
+    })?;
+
+    let client =
+        node.create_client::<BasicTypes::Service>("/spin_until_srv", QosProfile::default())?;
+    let available = r2r::Node::is_available(&client)?;
+    r2r::spin_until_future_complete(
+        &mut node,
+        &mut pool,
+        available,
+        Some(Duration::from_secs(5)),
+    )??;
+
+    let request = BasicTypes::Request {
+        int64_value: 41,
+        ..Default::default()
+    };
+    let response = client.request(&request)?;
+    let response = r2r::spin_until_future_complete(
+        &mut node,
+        &mut pool,
+        response,
+        Some(Duration::from_secs(5)),
+    )??;
+    assert_eq!(response.int64_value, 42);
+
+    Ok(())
+}
+
+#[test]
+fn spin_until_future_timeout() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_spin_until_timeout", "")?;
+    let mut pool = LocalPool::new();
+
+    let result = r2r::spin_until_future_complete(
+        &mut node,
+        &mut pool,
+        futures::future::pending::<()>(),
+        Some(Duration::from_millis(50)),
+    );
+    assert!(matches!(result, Err(r2r::Error::Timeout)));
+
+    // spin_some returns even if there is nothing to do.
+    r2r::spin_some(&mut node, Duration::from_millis(10));
+
+    Ok(())
+}
+
+#[test]
+fn call_service_sync() -> Result<(), Box<dyn std::error::Error>> {
+    use futures::future::FutureExt;
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    // the service is served from another thread, with its own node.
+    let ctx = r2r::Context::create()?;
+    let mut server_node = r2r::Node::create(ctx.clone(), "testnode_call_sync_server", "")?;
+    let mut service = server_node
+        .create_service::<BasicTypes::Service>("/call_sync_srv", QosProfile::default())?;
+    let done = Arc::new(AtomicBool::new(false));
+    let server_done = done.clone();
+    let server = std::thread::spawn(move || {
+        while !server_done.load(Ordering::SeqCst) {
+            server_node.spin_once(Duration::from_millis(10));
+            while let Some(Some(req)) = service.next().now_or_never() {
+                let response = BasicTypes::Response {
+                    int64_value: req.message.int64_value + 1,
+                    ..Default::default()
+                };
+                req.respond(response).expect("could not respond");
+            }
+        }
+    });
+
+    let mut node = r2r::Node::create(ctx, "testnode_call_sync", "")?;
+    let client =
+        node.create_client::<BasicTypes::Service>("/call_sync_srv", QosProfile::default())?;
+    let available = r2r::Node::is_available(&client)?;
+    r2r::spin_until_future_complete(
+        &mut node,
+        &mut LocalPool::new(),
+        available,
+        Some(Duration::from_secs(5)),
+    )??;
+
+    let request = BasicTypes::Request {
+        int64_value: 41,
+        ..Default::default()
+    };
+    let response = client.call_sync(&request, &mut node, Duration::from_secs(5))?;
+    assert_eq!(response.int64_value, 42);
+
+    // without a server, the call times out.
+    done.store(true, Ordering::SeqCst);
+    server.join().unwrap();
+    let result = client.call_sync(&request, &mut node, Duration::from_millis(100));
+    assert!(matches!(result, Err(r2r::Error::RCL_RET_TIMEOUT)));
+
+    Ok(())
+}
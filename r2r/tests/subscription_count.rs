@@ -0,0 +1,96 @@
+use futures::{future::FutureExt, stream::StreamExt};
+use r2r::QosProfile;
+
+use std::time::Duration;
+
+#[test]
+fn publisher_and_subscription_counts() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut pub_node = r2r::Node::create(ctx.clone(), "testnode_count_publisher", "")?;
+    let mut sub_node = r2r::Node::create(ctx, "testnode_count_subscriber", "/count_ns")?;
+
+    let publisher = pub_node
+        .create_publisher::<r2r::std_msgs::msg::Int32>("/count_topic", QosProfile::default())?;
+    assert_eq!(publisher.get_subscription_count()?, 0);
+
+    let mut wait_for_subscribers =
+        Box::pin(publisher.wait_for_subscribers(1, Some(Duration::from_secs(2))));
+
+    let _sub =
+        sub_node.subscribe::<r2r::std_msgs::msg::Int32>("/count_topic", QosProfile::default())?;
+    // the subscription is matched with the publisher once they discovered each other.
+    let mut result = None;
+    for _ in 0..300 {
+        pub_node.spin_once(Duration::from_millis(10));
+        sub_node.spin_once(Duration::from_millis(10));
+        if let Some(r) = (&mut wait_for_subscribers).now_or_never() {
+            result = Some(r);
+            break;
+        }
+    }
+    result.expect("wait_for_subscribers did not complete")?;
+    assert_eq!(publisher.get_subscription_count()?, 1);
+
+    for _ in 0..200 {
+        if sub_node.get_publisher_count("/count_topic")? == 1 {
+            break;
+        }
+        sub_node.spin_once(Duration::from_millis(10));
+    }
+    assert_eq!(sub_node.get_publisher_count("/count_topic")?, 1);
+    assert!(sub_node.get_publisher_count("not_subscribed").is_err());
+
+    // no second subscription shows up, so this times out.
+    let mut wait_for_two =
+        Box::pin(publisher.wait_for_subscribers(2, Some(Duration::from_millis(50))));
+    let mut result = None;
+    for _ in 0..100 {
+        pub_node.spin_once(Duration::from_millis(10));
+        if let Some(r) = (&mut wait_for_two).now_or_never() {
+            result = Some(r);
+            break;
+        }
+    }
+    assert!(matches!(result, Some(Err(r2r::Error::Timeout))));
+
+    Ok(())
+}
+
+#[test]
+fn publish_if_subscribed() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_publish_if_subscribed", "")?;
+    let publisher = node
+        .create_publisher::<r2r::std_msgs::msg::Int32>("/if_subscribed", QosProfile::default())?;
+    let conditional = publisher.clone().with_subscribers_required(2);
+
+    let msg = r2r::std_msgs::msg::Int32 { data: 3 };
+    assert!(!publisher.publish_if_subscribed(&msg)?);
+
+    let mut sub =
+        node.subscribe::<r2r::std_msgs::msg::Int32>("/if_subscribed", QosProfile::default())?;
+    for _ in 0..300 {
+        if publisher.get_subscription_count()? == 1 {
+            break;
+        }
+        node.spin_once(Duration::from_millis(10));
+    }
+    assert!(publisher.publish_if_subscribed(&msg)?);
+    // one subscription is below the threshold of the conditional publisher.
+    assert!(!conditional.publish(&msg)?);
+
+    let mut received = None;
+    for _ in 0..100 {
+        node.spin_once(Duration::from_millis(10));
+        if let Some(Some(msg)) = sub.next().now_or_never() {
+            received = Some(msg);
+            break;
+        }
+    }
+    assert_eq!(received.map(|msg| msg.data), Some(3));
+    // and only one message was published.
+    node.spin_once(Duration::from_millis(50));
+    assert!(sub.next().now_or_never().is_none());
+
+    Ok(())
+}
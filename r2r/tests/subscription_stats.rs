@@ -0,0 +1,46 @@
+use futures::{future::FutureExt, stream::StreamExt};
+use r2r::{std_msgs::msg::Int32, QosProfile};
+
+use std::time::{Duration, Instant};
+
+#[test]
+fn subscription_frequency() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_subscription_stats", "")?;
+
+    let mut sub = node.subscribe::<Int32>("/subscription_stats", QosProfile::default())?;
+    let stats = sub.stats();
+    let publisher = node.create_publisher::<Int32>("/subscription_stats", QosProfile::default())?;
+
+    // wait for the publisher and subscriber to discover each other.
+    for _ in 0..100 {
+        node.spin_once(Duration::from_millis(10));
+        if publisher.get_inter_process_subscription_count()? > 0 {
+            break;
+        }
+    }
+    assert_eq!(stats.message_count(), 0);
+    assert_eq!(stats.last_received_time(), None);
+
+    // publish at 50 Hz for two seconds.
+    let start = Instant::now();
+    for data in 0..100 {
+        let next = start + Duration::from_millis(20 * data as u64);
+        while Instant::now() < next {
+            node.spin_once(next - Instant::now());
+            while let Some(Some(_)) = sub.next().now_or_never() {}
+        }
+        publisher.publish(&Int32 { data })?;
+    }
+    let deadline = Instant::now() + Duration::from_secs(1);
+    while stats.message_count() < 100 && Instant::now() < deadline {
+        node.spin_once(Duration::from_millis(1));
+    }
+
+    assert_eq!(stats.message_count(), 100);
+    assert!(stats.last_received_time().unwrap() > start);
+    let hz = stats.estimated_frequency_hz(Duration::from_secs(1));
+    assert!((45.0..=55.0).contains(&hz), "estimated {hz} Hz");
+
+    Ok(())
+}
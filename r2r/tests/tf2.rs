@@ -0,0 +1,48 @@
+#![cfg(r2r__tf2_msgs__msg__TFMessage)]
+
+use r2r::{
+    builtin_interfaces::msg::Time,
+    geometry_msgs::msg::TransformStamped,
+    tf2::{Tf2Broadcaster, Tf2Buffer},
+};
+use std::time::{Duration, Instant};
+
+#[test]
+fn broadcast_and_lookup() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_tf2", "")?;
+    let buffer = Tf2Buffer::new(&mut node, Duration::from_secs(10))?;
+    let broadcaster = Tf2Broadcaster::new(&mut node)?;
+
+    let mut transform = TransformStamped {
+        child_frame_id: "tf2_test_base".into(),
+        ..Default::default()
+    };
+    transform.header.frame_id = "tf2_test_odom".into();
+    transform.header.stamp = Time { sec: 5, nanosec: 0 };
+    transform.transform.translation.x = 1.5;
+    transform.transform.rotation.w = 1.0;
+
+    let start = Instant::now();
+    while !buffer.can_transform("tf2_test_odom", "tf2_test_base", Time::default()) {
+        assert!(start.elapsed() < Duration::from_secs(10), "transform was not received");
+        broadcaster.send_transform(transform.clone())?;
+        node.spin_once(Duration::from_millis(10));
+    }
+
+    let t =
+        buffer.lookup_transform("tf2_test_odom", "tf2_test_base", Time { sec: 5, nanosec: 0 })?;
+    assert_eq!(t.header.frame_id, "tf2_test_odom");
+    assert_eq!(t.child_frame_id, "tf2_test_base");
+    assert!((t.transform.translation.x - 1.5).abs() < 1e-9);
+
+    // the inverse is available too.
+    let t = buffer.lookup_transform("tf2_test_base", "tf2_test_odom", Time::default())?;
+    assert!((t.transform.translation.x + 1.5).abs() < 1e-9);
+
+    assert!(buffer
+        .lookup_transform("tf2_test_odom", "tf2_test_unknown", Time::default())
+        .is_err());
+
+    Ok(())
+}
@@ -81,3 +81,42 @@ fn doesnt_crash() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+// Create the node with its publisher and subscription here, and spin it from another thread.
+fn spin_moved_node() -> Result<(), Box<dyn std::error::Error>> {
+    use futures::{executor::block_on, StreamExt};
+
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_moved", "")?;
+    let mut sub =
+        node.subscribe::<r2r::std_msgs::msg::String>("/moved_node", QosProfile::default())?;
+    let p =
+        node.create_publisher::<r2r::std_msgs::msg::String>("/moved_node", QosProfile::default())?;
+
+    let (stop_sender, stop_receiver) = std::sync::mpsc::channel::<()>();
+    let spinner = thread::spawn(move || {
+        while stop_receiver.try_recv().is_err() {
+            node.spin_once(Duration::from_millis(10));
+        }
+    });
+
+    let publisher = thread::spawn(move || {
+        let msg = r2r::std_msgs::msg::String {
+            data: "hello".into(),
+        };
+        // keep publishing until the node is gone, the subscription may not be matched yet.
+        while p.publish(&msg).is_ok() {
+            thread::sleep(Duration::from_millis(10));
+        }
+    });
+
+    let received = block_on(sub.next()).expect("subscription ended");
+    assert_eq!(received.data, "hello");
+
+    stop_sender.send(())?;
+    spinner.join().unwrap();
+    publisher.join().unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,28 @@
+#![cfg(r2r__std_msgs__msg__Header)]
+
+use r2r::{
+    builtin_interfaces::msg::{Duration as DurationMsg, Time},
+    std_msgs::msg::Header,
+};
+
+use std::time::Duration;
+
+#[test]
+fn time_from_node_clock() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let node = r2r::Node::create(ctx, "testnode_time_stamps", "")?;
+
+    let first = Time::from_node_clock(&node);
+    std::thread::sleep(Duration::from_millis(10));
+    let second = Time::from_node_clock(&node);
+    assert!(second.clone() - first >= DurationMsg::from_nanos(10_000_000));
+
+    let mut header = Header {
+        frame_id: "base_link".into(),
+        ..Default::default()
+    };
+    r2r::stamp_header(&node, &mut header);
+    assert_eq!(header.frame_id, "base_link");
+    assert!(header.stamp >= second);
+    Ok(())
+}
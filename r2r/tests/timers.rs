@@ -0,0 +1,137 @@
+use futures::{executor::LocalPool, future::FutureExt, task::LocalSpawnExt};
+
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+#[test]
+fn one_shot_timer_fires_once() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_one_shot_timer", "")?;
+
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+
+    let count = Rc::new(Cell::new(0));
+    let timer = node.create_one_shot_timer(Duration::from_millis(50))?;
+    let task_count = count.clone();
+    spawner.spawn_local(async move {
+        let elapsed = timer.await.unwrap();
+        assert!(elapsed >= Duration::from_millis(50));
+        task_count.set(task_count.get() + 1);
+    })?;
+
+    let zero_count = Rc::new(Cell::new(0));
+    let zero_timer = node.create_one_shot_timer(Duration::ZERO)?;
+    let task_zero_count = zero_count.clone();
+    spawner.spawn_local(async move {
+        zero_timer.await.unwrap();
+        task_zero_count.set(task_zero_count.get() + 1);
+    })?;
+
+    // a zero duration timer fires on the next spin.
+    node.spin_once(Duration::from_millis(10));
+    pool.run_until_stalled();
+    assert_eq!(zero_count.get(), 1);
+
+    for _ in 0..30 {
+        node.spin_once(Duration::from_millis(10));
+        pool.run_until_stalled();
+    }
+    assert_eq!(count.get(), 1);
+    assert_eq!(zero_count.get(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn mock_clock_timer() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let clock = r2r::MockClock::new()?;
+    let mut node = r2r::Node::create_with_clock(ctx, "testnode_mock_clock_timer", "", &clock)?;
+
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+
+    let count = Rc::new(Cell::new(0));
+    let mut timer = node.create_timer(Duration::from_secs(5))?;
+    let task_count = count.clone();
+    spawner.spawn_local(async move {
+        loop {
+            timer.tick().await.unwrap();
+            task_count.set(task_count.get() + 1);
+        }
+    })?;
+
+    let start = std::time::Instant::now();
+    let mut spin = |node: &mut r2r::Node| {
+        node.spin_once(Duration::ZERO);
+        pool.run_until_stalled();
+    };
+
+    spin(&mut node);
+    assert_eq!(count.get(), 0);
+
+    clock.advance(Duration::from_secs(5))?;
+    spin(&mut node);
+    assert_eq!(count.get(), 1);
+    assert_eq!(node.now()?.sec, 5);
+
+    // the next deadline is at 10 s.
+    for _ in 0..4 {
+        clock.advance(Duration::from_secs(1))?;
+        spin(&mut node);
+        assert_eq!(count.get(), 1);
+    }
+    clock.advance(Duration::from_millis(999))?;
+    spin(&mut node);
+    assert_eq!(count.get(), 1);
+    clock.advance(Duration::from_millis(1))?;
+    spin(&mut node);
+    assert_eq!(count.get(), 2);
+    assert_eq!(clock.now()?, Duration::from_secs(10));
+
+    assert!(start.elapsed() < Duration::from_secs(1));
+
+    Ok(())
+}
+
+#[test]
+fn timer_reset_and_cancel() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_timer_reset", "")?;
+
+    let period = Duration::from_millis(500);
+    let mut timer = node.create_wall_timer(period)?;
+
+    let mut ticked = false;
+    for _ in 0..100 {
+        node.spin_once(Duration::from_millis(20));
+        if timer.tick().now_or_never().is_some() {
+            ticked = true;
+            break;
+        }
+    }
+    assert!(ticked);
+
+    std::thread::sleep(Duration::from_millis(200));
+    assert!(timer.time_until_next_call()? <= Duration::from_millis(300));
+    assert!(timer.time_since_last_call()? >= Duration::from_millis(200));
+
+    // restarts the countdown from the full period.
+    timer.reset()?;
+    let remaining = timer.time_until_next_call()?;
+    assert!(remaining > Duration::from_millis(400), "{remaining:?}");
+    assert!(remaining <= period);
+
+    assert!(!timer.is_canceled());
+    timer.cancel()?;
+    assert!(timer.is_canceled());
+    timer.reset()?;
+    assert!(!timer.is_canceled());
+
+    // the timer is gone with the node.
+    drop(node);
+    assert!(timer.reset().is_err());
+    assert!(timer.is_canceled());
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+#![cfg(feature = "tokio")]
+
+use std::sync::{Arc, Mutex};
+
+use futures::stream::StreamExt;
+use r2r::QosProfile;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn tokio_spin_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let node = Arc::new(Mutex::new(r2r::Node::create(ctx, "testnode_tokio_spin", "")?));
+
+    let (mut sub, publisher) = {
+        let mut node = node.lock().unwrap();
+        let sub =
+            node.subscribe::<r2r::std_msgs::msg::Int32>("/tokio_spin_no", QosProfile::default())?;
+        let publisher = node.create_publisher::<r2r::std_msgs::msg::Int32>(
+            "/tokio_spin_no",
+            QosProfile::default(),
+        )?;
+        (sub, publisher)
+    };
+
+    tokio::spawn(r2r::tokio::spin(node.clone()));
+
+    tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        for i in 0..10 {
+            publisher
+                .publish(&r2r::std_msgs::msg::Int32 { data: i })
+                .unwrap();
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+    });
+
+    let receiver = tokio::spawn(async move {
+        let mut received = Vec::new();
+        while let Some(msg) = sub.next().await {
+            received.push(msg.data);
+            if received.len() == 10 {
+                break;
+            }
+        }
+        received
+    });
+
+    let received = tokio::time::timeout(std::time::Duration::from_secs(5), receiver).await??;
+    assert_eq!(received, (0..10).collect::<Vec<_>>());
+
+    Ok(())
+}
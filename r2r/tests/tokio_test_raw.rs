@@ -209,3 +209,55 @@ async fn tokio_publish_raw_testing() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn tokio_raw_string_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx, "testnode_raw_string", "")?;
+
+    // typed -> raw
+    let mut sub_raw =
+        node.subscribe_raw("/raw_string_a", "std_msgs/msg/String", QosProfile::default())?;
+    let pub_typed = node
+        .create_publisher::<r2r::std_msgs::msg::String>("/raw_string_a", QosProfile::default())?;
+
+    // raw -> typed
+    let mut sub_typed =
+        node.subscribe::<r2r::std_msgs::msg::String>("/raw_string_b", QosProfile::default())?;
+    let pub_raw = node.create_publisher_untyped(
+        "/raw_string_b",
+        "std_msgs/msg/String",
+        QosProfile::default(),
+    )?;
+
+    let handle = std::thread::spawn(move || {
+        for _ in 1..=30 {
+            node.spin_once(std::time::Duration::from_millis(100));
+        }
+    });
+
+    task::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let msg = r2r::std_msgs::msg::String {
+            data: "hello raw wörld".into(),
+        };
+        pub_typed.publish(&msg).unwrap();
+        pub_raw
+            .publish_raw(&msg.to_serialized_bytes().unwrap())
+            .unwrap();
+    });
+
+    let raw = sub_raw.next().await.expect("raw subscription ended");
+    let from_raw = r2r::std_msgs::msg::String::from_serialized_bytes(&raw)?;
+    assert_eq!(from_raw.data, "hello raw wörld");
+
+    let typed = sub_typed.next().await.expect("typed subscription ended");
+    assert_eq!(typed.data, "hello raw wörld");
+    assert_eq!(typed.to_serialized_bytes()?, raw);
+
+    drop(sub_raw);
+    drop(sub_typed);
+    handle.join().unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use r2r::QosProfile;
+
+fn node_with_arguments(
+    name: &str, namespace: &str, arguments: &[&str],
+) -> Result<r2r::Node, r2r::Error> {
+    let ctx = r2r::Context::create()?;
+    let options = r2r::NodeOptions {
+        arguments: arguments.iter().map(|a| a.to_string()).collect(),
+        ..Default::default()
+    };
+    r2r::Node::create_with_options(ctx, name, namespace, options)
+}
+
+#[test]
+fn publisher_topic_name_is_remapped() -> Result<(), Box<dyn std::error::Error>> {
+    let mut node = node_with_arguments(
+        "testnode_topic_names",
+        "/ns",
+        &["--ros-args", "-r", "/original:=/remapped"],
+    )?;
+
+    let remapped =
+        node.create_publisher::<r2r::std_msgs::msg::String>("/original", QosProfile::default())?;
+    assert_eq!(remapped.topic_name()?, "/remapped");
+    assert_eq!(node.resolve_topic_name("/original")?, "/remapped");
+
+    let relative =
+        node.create_publisher::<r2r::std_msgs::msg::String>("chatter", QosProfile::default())?;
+    assert_eq!(relative.topic_name()?, "/ns/chatter");
+
+    let private =
+        node.create_publisher::<r2r::std_msgs::msg::String>("~/status", QosProfile::default())?;
+    assert_eq!(private.topic_name()?, "/ns/testnode_topic_names/status");
+    assert_eq!(node.resolve_topic_name("~/status")?, "/ns/testnode_topic_names/status");
+
+    assert_eq!(node.resolve_service_name("~/get")?, "/ns/testnode_topic_names/get");
+    assert!(node.resolve_topic_name("not valid").is_err());
+
+    // subscriptions are looked up by their remapped name.
+    let _sub = node.subscribe::<r2r::std_msgs::msg::String>("/original", QosProfile::default())?;
+    assert!(node.get_publisher_count("/original").is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn node_arguments_set_parameters() -> Result<(), Box<dyn std::error::Error>> {
+    let node = node_with_arguments(
+        "testnode_topic_names_params",
+        "",
+        &["--ros-args", "-p", "gains:=[1.5, 2.5, 3.5]"],
+    )?;
+    assert_eq!(node.get_parameter::<Vec<f64>>("gains")?, vec![1.5, 2.5, 3.5]);
+
+    Ok(())
+}
+
+#[test]
+fn invalid_arguments_error_message() {
+    let err = match node_with_arguments("testnode_bad_args", "", &["--ros-args", "-r", "no_rule"]) {
+        Err(err) => err,
+        Ok(_) => panic!("invalid remap rule was accepted"),
+    };
+    assert!(matches!(err.rcl_error(), r2r::Error::RCL_RET_INVALID_ROS_ARGS));
+
+    // the message names the failing function and includes the rcl error string.
+    let message = err.to_string();
+    assert!(message.starts_with("rcl_parse_arguments failed"), "{message}");
+    match &err {
+        r2r::Error::RclCall { message, .. } => assert!(!message.is_empty()),
+        _ => panic!("expected an rcl call error, got {err:?}"),
+    }
+    let source = std::error::Error::source(&err).expect("no source");
+    assert_eq!(source.to_string(), "RCL_RET_INVALID_ROS_ARGS");
+}
@@ -0,0 +1,89 @@
+use futures::stream::StreamExt;
+use r2r::{
+    statistics_msgs::msg::{MetricsMessage, StatisticDataType},
+    QosProfile, SubscriptionOptions,
+};
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn topic_statistics_period() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut pub_node = r2r::Node::create(ctx.clone(), "testnode_stats_pub", "")?;
+    let mut sub_node = r2r::Node::create(ctx.clone(), "testnode_stats_sub", "")?;
+    let mut stats_node = r2r::Node::create(ctx, "testnode_stats_listener", "")?;
+
+    let publisher = pub_node
+        .create_publisher::<r2r::std_msgs::msg::Int32>("/stats_no", QosProfile::default())?;
+    let mut timer = pub_node.create_wall_timer(Duration::from_millis(20))?;
+
+    let options = SubscriptionOptions {
+        enable_topic_statistics: true,
+        statistics_publish_period: Duration::from_secs(2),
+        ..Default::default()
+    };
+    let mut sub = sub_node.subscribe_with_options::<r2r::std_msgs::msg::Int32>(
+        "/stats_no",
+        QosProfile::default(),
+        options,
+    )?;
+    let mut stats = stats_node.subscribe::<MetricsMessage>(
+        "/testnode_stats_sub/stats_no/statistics",
+        QosProfile::default(),
+    )?;
+
+    tokio::spawn(async move {
+        let mut data = 0;
+        while timer.tick().await.is_ok() {
+            publisher
+                .publish(&r2r::std_msgs::msg::Int32 { data })
+                .unwrap();
+            data += 1;
+        }
+    });
+    tokio::spawn(async move { while sub.next().await.is_some() {} });
+
+    let nodes = Arc::new(Mutex::new(vec![pub_node, sub_node, stats_node]));
+    let done = Arc::new(AtomicBool::new(false));
+    let spin_done = done.clone();
+    let spin_task = tokio::task::spawn_blocking(move || {
+        while !spin_done.load(Ordering::SeqCst) {
+            for node in nodes.lock().unwrap().iter_mut() {
+                node.spin_once(Duration::from_millis(1));
+            }
+        }
+    });
+
+    // the first window includes discovery so use the second one.
+    let mut period = None;
+    let mut windows = 0;
+    while let Some(msg) = stats.next().await {
+        assert_eq!(msg.measurement_source_name, "/testnode_stats_sub");
+        if msg.metrics_source != "message_period" {
+            continue;
+        }
+        windows += 1;
+        if windows == 2 {
+            period = msg
+                .statistics
+                .iter()
+                .find(|p| p.data_type == StatisticDataType::STATISTICS_DATA_TYPE_AVERAGE as u8)
+                .map(|p| p.data);
+            break;
+        }
+    }
+
+    let period = period.expect("no mean period reported");
+    assert!((period - 20.0).abs() < 2.0, "mean period {period}ms");
+
+    done.store(true, Ordering::SeqCst);
+    spin_task.await?;
+
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use futures::{future::FutureExt, stream::StreamExt};
+use r2r::{std_msgs::msg::Int32, test_msgs::srv::BasicTypes, QosProfile, WaitSet};
+
+use std::time::{Duration, Instant};
+
+#[test]
+fn wait_set_dispatches_all_nodes() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node_a = r2r::Node::create(ctx.clone(), "testnode_wait_set_a", "")?;
+    let mut node_b = r2r::Node::create(ctx.clone(), "testnode_wait_set_b", "")?;
+
+    // a subscription on one node and a service on the other.
+    let mut sub = node_a.subscribe::<Int32>("/wait_set_topic", QosProfile::default())?;
+    let publisher = node_a.create_publisher::<Int32>("/wait_set_topic", QosProfile::default())?;
+    let client =
+        node_a.create_client::<BasicTypes::Service>("/wait_set_service", QosProfile::default())?;
+    let mut requests =
+        node_b.create_service::<BasicTypes::Service>("/wait_set_service", QosProfile::default())?;
+    let mut available = Box::pin(r2r::Node::is_available(&client)?);
+
+    let mut wait_set = WaitSet::new(&ctx)?;
+    let _a = wait_set.add_node(&mut node_a)?;
+    let _b = wait_set.add_node(&mut node_b)?;
+
+    // wait for the publisher and the service to be discovered.
+    let start = Instant::now();
+    let mut is_available = false;
+    while !is_available || publisher.get_inter_process_subscription_count()? == 0 {
+        assert!(start.elapsed() < Duration::from_secs(10), "discovery timed out");
+        let ready = wait_set.wait(Duration::from_millis(10))?;
+        wait_set.dispatch(ready)?;
+        is_available |= matches!((&mut available).now_or_never(), Some(Ok(())));
+    }
+
+    publisher.publish(&Int32 { data: 7 })?;
+    let request = BasicTypes::Request {
+        int64_value: 3,
+        ..Default::default()
+    };
+    let _response = client.request(&request)?;
+    // let both arrive, so they are handled in the same turn.
+    std::thread::sleep(Duration::from_millis(200));
+
+    let ready = wait_set.wait(Duration::from_secs(1))?;
+    assert!(!ready.timed_out());
+    wait_set.dispatch(ready)?;
+
+    let msg = sub.next().now_or_never().flatten().expect("no message");
+    assert_eq!(msg.data, 7);
+    let req = requests
+        .next()
+        .now_or_never()
+        .flatten()
+        .expect("no request");
+    assert_eq!(req.message.int64_value, 3);
+
+    Ok(())
+}
+
+#[test]
+fn wait_set_token_removes_node() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut node = r2r::Node::create(ctx.clone(), "testnode_wait_set_token", "")?;
+    let mut timer = node.create_wall_timer(Duration::from_millis(1))?;
+
+    let mut wait_set = WaitSet::new(&ctx)?;
+    let token = wait_set.add_node(&mut node)?;
+    std::thread::sleep(Duration::from_millis(5));
+    let ready = wait_set.wait(Duration::from_secs(1))?;
+    wait_set.dispatch(ready)?;
+    assert!(timer.tick().now_or_never().is_some());
+
+    // without nodes there is nothing to wait for.
+    drop(token);
+    let ready = wait_set.wait(Duration::from_millis(10))?;
+    assert!(ready.timed_out());
+    wait_set.dispatch(ready)?;
+
+    // the node can be used on its own again.
+    node.spin_once(Duration::from_millis(10));
+    Ok(())
+}
+
+#[test]
+fn spin_many_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    let mut inbound = r2r::Node::create(ctx.clone(), "testnode_spin_many_in", "")?;
+    let mut outbound = r2r::Node::create(ctx, "testnode_spin_many_out", "")?;
+
+    // outbound sends pings, inbound answers them with pongs.
+    let ping_publisher =
+        outbound.create_publisher::<Int32>("/spin_many_ping", QosProfile::default())?;
+    let mut pongs = outbound.subscribe::<Int32>("/spin_many_pong", QosProfile::default())?;
+    let mut pings = inbound.subscribe::<Int32>("/spin_many_ping", QosProfile::default())?;
+    let pong_publisher =
+        inbound.create_publisher::<Int32>("/spin_many_pong", QosProfile::default())?;
+
+    let start = Instant::now();
+    let mut i = 0;
+    let pong = loop {
+        assert!(start.elapsed() < Duration::from_secs(10), "no round trip");
+        ping_publisher.publish(&Int32 { data: i })?;
+        i += 1;
+        r2r::spin_many(vec![&mut inbound, &mut outbound], Duration::from_millis(10))?;
+        while let Some(Some(ping)) = pings.next().now_or_never() {
+            pong_publisher.publish(&Int32 { data: ping.data })?;
+        }
+        r2r::spin_many(vec![&mut inbound, &mut outbound], Duration::from_millis(10))?;
+        if let Some(Some(pong)) = pongs.next().now_or_never() {
+            break pong;
+        }
+    };
+    assert!(pong.data < i);
+
+    Ok(())
+}
+
+#[test]
+fn spin_many_without_entities() -> Result<(), Box<dyn std::error::Error>> {
+    let ctx = r2r::Context::create()?;
+    // a node without subscriptions, timers, etc.
+    let mut node = r2r::Node::create(ctx, "testnode_spin_many_empty", "")?;
+
+    let start = Instant::now();
+    r2r::spin_many(vec![&mut node], Duration::from_millis(50))?;
+    assert!(start.elapsed() >= Duration::from_millis(50));
+
+    let start = Instant::now();
+    r2r::spin_many(vec![], Duration::from_millis(50))?;
+    assert!(start.elapsed() >= Duration::from_millis(50));
+
+    Ok(())
+}
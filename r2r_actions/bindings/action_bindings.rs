@@ -284,7 +284,103 @@ extern "C" {
     pub fn rcl_action_goal_handle_is_valid(goal_handle: *const rcl_action_goal_handle_t) -> bool;
 }
 pub type rcl_action_server_t = u64;
-pub type rcl_action_server_options_t = [u64; 61usize];
+#[repr(C)]
+pub struct rcl_action_server_options_s {
+    pub goal_service_qos: rmw_qos_profile_t,
+    pub cancel_service_qos: rmw_qos_profile_t,
+    pub result_service_qos: rmw_qos_profile_t,
+    pub feedback_topic_qos: rmw_qos_profile_t,
+    pub status_topic_qos: rmw_qos_profile_t,
+    pub allocator: rcl_allocator_t,
+    pub result_timeout: rcl_duration_t,
+}
+#[test]
+fn bindgen_test_layout_rcl_action_server_options_s() {
+    const UNINIT: ::std::mem::MaybeUninit<rcl_action_server_options_s> =
+        ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<rcl_action_server_options_s>(),
+        488usize,
+        concat!("Size of: ", stringify!(rcl_action_server_options_s))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<rcl_action_server_options_s>(),
+        8usize,
+        concat!("Alignment of ", stringify!(rcl_action_server_options_s))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).goal_service_qos) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rcl_action_server_options_s),
+            "::",
+            stringify!(goal_service_qos)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).cancel_service_qos) as usize - ptr as usize },
+        88usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rcl_action_server_options_s),
+            "::",
+            stringify!(cancel_service_qos)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).result_service_qos) as usize - ptr as usize },
+        176usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rcl_action_server_options_s),
+            "::",
+            stringify!(result_service_qos)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).feedback_topic_qos) as usize - ptr as usize },
+        264usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rcl_action_server_options_s),
+            "::",
+            stringify!(feedback_topic_qos)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).status_topic_qos) as usize - ptr as usize },
+        352usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rcl_action_server_options_s),
+            "::",
+            stringify!(status_topic_qos)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).allocator) as usize - ptr as usize },
+        440usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rcl_action_server_options_s),
+            "::",
+            stringify!(allocator)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).result_timeout) as usize - ptr as usize },
+        480usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rcl_action_server_options_s),
+            "::",
+            stringify!(result_timeout)
+        )
+    );
+}
+pub type rcl_action_server_options_t = rcl_action_server_options_s;
 extern "C" {
     pub fn rcl_action_get_zero_initialized_server() -> rcl_action_server_t;
 }
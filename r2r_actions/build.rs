@@ -89,7 +89,7 @@ fn generate_bindings(out_file: &Path) {
         .allowlist_type("rcl_action_client_options_t")
         .opaque_type("rcl_action_client_options_t")
         .allowlist_type("rcl_action_server_options_t")
-        .opaque_type("rcl_action_server_options_t")
+        .allowlist_type("rcl_action_server_options_s")
         .allowlist_var("RCL_RET_ACTION_.*")
         .generate_comments(false)
         .generate()
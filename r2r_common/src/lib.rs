@@ -202,6 +202,46 @@ pub fn print_cargo_link_search() {
     }
 }
 
+/// Check if the library `name` is in one of the directories that
+/// `print_cargo_link_search` adds to the link search path.
+pub fn find_library(name: &str) -> bool {
+    let ament_prefix_var_name = "AMENT_PREFIX_PATH";
+    let Some(paths) = env::var_os(ament_prefix_var_name) else {
+        return false;
+    };
+    let split_char = if cfg!(target_os = "windows") {
+        ';'
+    } else {
+        ':'
+    };
+    let paths = if let Some(cmake_prefix_var) = env::var_os("CMAKE_PREFIX_PATH") {
+        let mut cmake_paths = paths;
+        cmake_paths.push(split_char.to_string());
+        cmake_paths.push(cmake_prefix_var);
+        RawOsString::new(cmake_paths)
+    } else {
+        RawOsString::new(paths)
+    };
+    let file_name = if cfg!(target_os = "windows") {
+        format!("{name}.lib")
+    } else if cfg!(target_os = "macos") {
+        format!("lib{name}.dylib")
+    } else {
+        format!("lib{name}.so")
+    };
+    let lib_dir = if cfg!(target_os = "windows") {
+        "Lib"
+    } else {
+        "lib"
+    };
+    paths.split(split_char).any(|path| {
+        Path::new(&path.to_os_str())
+            .join(lib_dir)
+            .join(&file_name)
+            .exists()
+    })
+}
+
 pub fn get_wanted_messages() -> Vec<RosMsg> {
     let msgs = if let Ok(cmake_package_dirs) = env::var("CMAKE_IDL_PACKAGES") {
         // CMAKE_PACKAGE_DIRS should be a (cmake) list of "cmake" dirs
@@ -71,6 +71,96 @@ pub fn derive_r2r_params(input: proc_macro::TokenStream) -> proc_macro::TokenStr
     proc_macro::TokenStream::from(expanded)
 }
 
+/// Derives `declare()`, `load()` and `event_stream()` for a structure whose
+/// fields are parameters named `<prefix>.<field>`.
+///
+/// The fields must be leaf parameter types, e.g. `f64` or `String`.
+#[proc_macro_derive(Parameters)]
+pub fn derive_r2r_parameters(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => fields.named.iter().collect::<Vec<_>>(),
+            _ => {
+                return syn::Error::new_spanned(&data.fields, "Parameters needs named fields")
+                    .to_compile_error()
+                    .into()
+            }
+        },
+        Data::Enum(_) | Data::Union(_) => {
+            return syn::Error::new_spanned(&name, "Parameters can only be derived for structs")
+                .to_compile_error()
+                .into()
+        }
+    };
+    let idents = fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+    let names = idents
+        .iter()
+        .map(|i| i.as_ref().unwrap().to_string())
+        .collect::<Vec<_>>();
+    let descs = fields.iter().map(|f| get_field_doc(f));
+
+    let expanded = quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Declare the parameters on `node`, unless they are already given, e.g. on the command line.
+            pub fn declare(
+                node: &mut ::r2r::Node,
+                prefix: &str,
+                defaults: Self,
+            ) -> ::r2r::Result<()> {
+                let mut defaults = defaults;
+                #(
+                    let name = ::r2r::parameter_group_name(prefix, #names);
+                    let value = ::r2r::RosParams::get_parameter(&mut defaults.#idents, "")
+                        .map_err(|e| e.update_param_name(&name))?;
+                    let descriptor = ::r2r::ParameterDescriptor {
+                        description: #descs.into(),
+                        ..::std::default::Default::default()
+                    };
+                    node.declare_parameter(&name, value, descriptor)?;
+                )*
+                Ok(())
+            }
+
+            /// Read the current values of the parameters from `node`.
+            pub fn load(node: &::r2r::Node, prefix: &str) -> ::r2r::Result<Self> {
+                let values = node.parameter_values(&[
+                    #(::r2r::parameter_group_name(prefix, #names)),*
+                ]);
+                Ok(Self {
+                    #(#idents: ::r2r::parameter_group_value(&values, prefix, #names)?,)*
+                })
+            }
+
+            /// Stream of the values of the parameters, whenever one of them changes.
+            pub fn event_stream(
+                node: &mut ::r2r::Node,
+                prefix: &str,
+            ) -> ::r2r::Result<
+                impl ::r2r::futures::Stream<Item = ::r2r::Result<Self>> + ::std::marker::Unpin
+            > {
+                let events = node.parameter_group_events(vec![
+                    #(::r2r::parameter_group_name(prefix, #names)),*
+                ])?;
+                let prefix = prefix.to_owned();
+                Ok(::r2r::futures::StreamExt::map(
+                    events,
+                    move |values| -> ::r2r::Result<Self> {
+                        Ok(Self {
+                            #(#idents: ::r2r::parameter_group_value(&values, &prefix, #names)?,)*
+                        })
+                    },
+                ))
+            }
+        }
+    };
+
+    proc_macro::TokenStream::from(expanded)
+}
+
 // Generate calls to register functions of struct fields
 fn get_register_calls(data: &Data) -> TokenStream {
     match *data {
@@ -778,8 +778,18 @@ pub fn generate_untyped_helper(msgs: &[RosMsg]) -> proc_macro2::TokenStream {
     let funcs = funcs.into_iter().map(|tokens| tokens.unwrap());
     let entries = entries.into_iter().map(|tokens| tokens.unwrap());
 
+    let typenames = msgs
+        .iter()
+        .filter(|msg| !["srv", "action"].contains(&msg.prefix.as_str()))
+        .map(type_name);
+
     quote! {
         impl WrappedNativeMsgUntyped {
+            /// The names of all message types that can be created with `new_from`.
+            pub fn type_names() -> &'static [&'static str] {
+                &[#(#typenames),*]
+            }
+
             pub fn new_from(typename: &str) -> Result<Self> {
                 #(#funcs)*
 
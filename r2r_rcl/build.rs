@@ -59,6 +59,11 @@ fn run_dynlink() {}
 fn run_dynlink() {
     r2r_common::print_cargo_link_search();
     println!("cargo:rustc-link-lib=dylib=rcl");
+    // rcl_lifecycle is not part of every ROS install, the lifecycle bindings
+    // are only generated when its header is found.
+    if r2r_common::find_library("rcl_lifecycle") {
+        println!("cargo:rustc-link-lib=dylib=rcl_lifecycle");
+    }
     println!("cargo:rustc-link-lib=dylib=rcl_logging_spdlog");
     println!("cargo:rustc-link-lib=dylib=rcl_yaml_param_parser");
     println!("cargo:rustc-link-lib=dylib=rcutils");
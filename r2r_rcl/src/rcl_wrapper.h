@@ -11,6 +11,28 @@
 // query the network
 #include <rcl/graph.h>
 
+// topic and service names
+#include <rcl/expand_topic_name.h>
+#include <rcl/validate_topic_name.h>
+#include <rmw/validate_full_topic_name.h>
+#include <rmw/validate_namespace.h>
+#include <rmw/validate_node_name.h>
+
+// network flow endpoints, not available on foxy
+#if __has_include(<rcl/network_flow_endpoints.h>)
+#include <rcl/network_flow_endpoints.h>
+#endif
+
+// rmw feature queries, not available before iron
+#if __has_include(<rmw/features.h>)
+#include <rmw/features.h>
+#endif
+
+// lifecycle state machine
+#if __has_include(<rcl_lifecycle/rcl_lifecycle.h>)
+#include <rcl_lifecycle/rcl_lifecycle.h>
+#endif
+
 // logging
 #include <rcl/logging.h>
 